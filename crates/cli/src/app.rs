@@ -1,8 +1,18 @@
 use crate::commands::{
-    debug::DebugConfigArgs,
-    plugin::{AddPluginArgs, InfoPluginArgs, ListPluginsArgs, RemovePluginArgs},
-    AliasArgs, BinArgs, CleanArgs, CompletionsArgs, InstallArgs, ListArgs, ListRemoteArgs,
-    MigrateArgs, OutdatedArgs, PinArgs, RegenArgs, RunArgs, SetupArgs, UnaliasArgs, UninstallArgs,
+    cache::{CleanCacheArgs, ListCacheArgs},
+    debug::{DebugConfigArgs, DebugPluginLogArgs},
+    hook::PreCommitHookArgs,
+    inventory::AddInventoryArgs,
+    plugin::{
+        AddPluginArgs, DoctorPluginArgs, InfoPluginArgs, ListPluginsArgs, OutdatedPluginsArgs,
+        RemovePluginArgs, SearchPluginArgs, UpgradePluginsArgs,
+    },
+    query::QueryManifestArgs,
+    ActivateArgs, AliasArgs, BinArgs, CleanArgs, CompleteArgs, CompletionsArgs, DoctorArgs,
+    EnvArgs, ExecEnvArgs, InitArgs, InstallAllArgs, InstallArgs, ListArgs, ListRemoteArgs,
+    MigrateArgs, OutdatedArgs, PackageArgs, PinArgs, RegenArgs, ReportArgs, RunArgs, RunMatrixArgs,
+    SetupArgs, StatsArgs, StatusArgs, TeardownArgs, TryArgs, UnaliasArgs, UninstallArgs,
+    UpgradeArgs, ValidateArgs, WarningsArgs, WhichArgs,
 };
 use clap::builder::styling::{Color, Style, Styles};
 use clap::{Parser, Subcommand, ValueEnum};
@@ -39,6 +49,28 @@ impl Display for LogLevel {
     }
 }
 
+#[derive(ValueEnum, Clone, Debug, Default, PartialEq)]
+pub enum LogFormat {
+    Json,
+    #[default]
+    Text,
+}
+
+impl Display for LogFormat {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        write!(
+            f,
+            "{}",
+            match self {
+                LogFormat::Json => "json",
+                LogFormat::Text => "text",
+            }
+        )?;
+
+        Ok(())
+    }
+}
+
 fn fg(ty: ColorType) -> Style {
     Style::new().fg_color(Some(Color::from(ty as u8)))
 }
@@ -75,12 +107,42 @@ pub struct App {
     )]
     pub log: Option<LogLevel>,
 
+    #[arg(
+        value_enum,
+        long,
+        global = true,
+        env = "PROTO_LOG_FORMAT",
+        help = "Format of log output, json writes a record per line to stderr"
+    )]
+    pub log_format: Option<LogFormat>,
+
+    #[arg(
+        long,
+        global = true,
+        help = "Report the paths that plugins accessed through the virtual file system"
+    )]
+    pub audit_fs: bool,
+
+    #[arg(
+        long,
+        global = true,
+        help = "Run in offline mode, using cached versions and downloads only"
+    )]
+    pub offline: bool,
+
     #[command(subcommand)]
     pub command: Commands,
 }
 
 #[derive(Clone, Debug, Subcommand)]
 pub enum Commands {
+    #[command(
+        name = "activate",
+        about = "Print a shell hook that activates tools for the current directory.",
+        long_about = "Print a shell hook that activates tools for the current directory, by prepending the executable directories\nof the detected tool versions to PATH on every prompt and directory change, instead of relying on shims.\n\nUsage: eval \"$(proto activate bash)\""
+    )]
+    Activate(ActivateArgs),
+
     #[command(
         alias = "a",
         name = "alias",
@@ -92,19 +154,33 @@ pub enum Commands {
     #[command(
         name = "bin",
         about = "Display the absolute path to a tools executable.",
-        long_about = "Display the absolute path to a tools executable. If no version is provided,\nit will be detected from the current environment."
+        long_about = "Display the absolute path to a tools executable. If no version is provided,\nit will be detected from the current environment. Secondary executables can be\nqueried by name (proto bin node npx), and all executables listed with --all."
     )]
     Bin(BinArgs),
 
+    #[command(name = "cache", about = "Operations for managing the download cache.")]
+    Cache {
+        #[command(subcommand)]
+        command: CacheCommands,
+    },
+
     #[command(
         name = "clean",
         about = "Clean the ~/.proto directory by removing stale tools, plugins, and files."
     )]
     Clean(CleanArgs),
 
+    #[command(
+        name = "complete",
+        about = "Print completion candidates for a partial command line.",
+        hide = true
+    )]
+    Complete(CompleteArgs),
+
     #[command(
         name = "completions",
-        about = "Generate command completions for your current shell."
+        about = "Generate command completions for your current shell.",
+        long_about = "Generate command completions for your current shell.\n\nWith --dynamic, the generated script will also call proto while completing, to complete tool IDs from configured plugins, and versions from installed versions, aliases, and cached remote versions. Supported for bash, zsh, fish, and powershell."
     )]
     Completions(CompletionsArgs),
 
@@ -114,6 +190,43 @@ pub enum Commands {
         command: DebugCommands,
     },
 
+    #[command(
+        name = "doctor",
+        about = "Diagnose common problems with the proto environment.",
+        long_about = "Diagnose common problems with the proto environment, like system installs shadowing proto's shims on PATH,\nbroken shims and bins, orphaned versions in the store, incompatible plugins, and configuration warnings."
+    )]
+    Doctor(DoctorArgs),
+
+    #[command(
+        name = "env",
+        about = "Print environment variables from .prototools and the detected SDK root tools.",
+        long_about = "Print environment variables from the [env] section of .prototools, and variables (like JAVA_HOME) that point to the install directory of SDK root tools, formatted as shell exports.\nUsage: eval \"$(proto env)\""
+    )]
+    Env(EnvArgs),
+
+    #[command(
+        name = "exec-env",
+        about = "Print the command and environment that a shim would run a tool with.",
+        long_about = "Print the command and environment that a shim would run a tool with, for the current directory (or --cwd).\n\nEnvironment variables are diffed against the current environment, including variables from [env] in .prototools, from the plugin, and directories prepended to PATH. Useful when reporting environment related bugs."
+    )]
+    ExecEnv(ExecEnvArgs),
+
+    #[command(
+        name = "hook",
+        about = "Run proto as a hook for external tools, like the pre-commit framework."
+    )]
+    Hook {
+        #[command(subcommand)]
+        command: HookCommands,
+    },
+
+    #[command(
+        name = "init",
+        about = "Onboard proto for your shell and the current project.",
+        long_about = "Onboard proto by setting up your shell, scanning the current directory for ecosystem files (.nvmrc, etc), and writing an initial .prototools with the suggested plugins and version pins."
+    )]
+    Init(InitArgs),
+
     #[command(
         alias = "i",
         name = "install",
@@ -122,6 +235,15 @@ pub enum Commands {
     )]
     Install(InstallArgs),
 
+    #[command(
+        name = "inventory",
+        about = "Operations for managing installed tool versions."
+    )]
+    Inventory {
+        #[command(subcommand)]
+        command: InventoryCommands,
+    },
+
     #[command(
         alias = "ls",
         name = "list",
@@ -140,7 +262,8 @@ pub enum Commands {
 
     #[command(
         name = "migrate",
-        about = "Migrate breaking changes for the proto installation."
+        about = "Migrate breaking changes for the proto installation.",
+        long_about = "Migrate breaking changes for the proto installation.\n\nPass asdf to convert the .tool-versions file in the current directory into .prototools pins."
     )]
     Migrate(MigrateArgs),
 
@@ -150,11 +273,19 @@ pub enum Commands {
     )]
     Outdated(OutdatedArgs),
 
+    #[command(
+        name = "package",
+        about = "Package an installed tool version into an archive.",
+        long_about = "Package an installed tool version into a relocatable archive, alongside a receipt with checksums of every file.
+The archive can be installed on another machine of the same platform with proto install --from-file."
+    )]
+    Package(PackageArgs),
+
     #[command(
         alias = "p",
         name = "pin",
         about = "Pin a global or local version of a tool.",
-        long_about = "Pin a version of a tool globally to ~/.proto/.prototools, or locally to .prototools (in the current working directory)."
+        long_about = "Pin a version of a tool globally to ~/.proto/.prototools, or locally to .prototools (in the current working directory).\nMultiple tools can be pinned at once (node@20 pnpm@8), and --from-detected will pin the version currently detected for each tool."
     )]
     Pin(PinArgs),
 
@@ -168,23 +299,84 @@ pub enum Commands {
         command: PluginCommands,
     },
 
+    #[command(
+        name = "query",
+        about = "Query proto's data through stable, versioned JSON schemas."
+    )]
+    Query {
+        #[command(subcommand)]
+        command: QueryCommands,
+    },
+
     #[command(name = "regen", about = "Regenerate shims and optionally relink bins.")]
     Regen(RegenArgs),
 
+    #[command(
+        name = "report",
+        about = "Print metrics about installed tools for monitoring.",
+        long_about = "Print metrics about installed tools for monitoring, including installed versions, the proto version,\nwhen tools were last updated and used, and which tools are outdated. Use --format prometheus for\nthe Prometheus text format, so that an agent can scrape or upload it."
+    )]
+    Report(ReportArgs),
+
     #[command(
         alias = "r",
         name = "run",
         about = "Run a tool after detecting a version from the environment.",
-        long_about = "Run a tool after detecting a version from the environment. In order of priority,\na version will be resolved from a provided CLI argument, a PROTO_VERSION environment variable,\na local version file (.prototools), and lastly a global version file (~/.proto/tools).\n\nIf no version can be found, the program will exit with an error."
+        long_about = "Run a tool after detecting a version from the environment. In order of priority,\na version will be resolved from a provided CLI argument, a PROTO_VERSION environment variable,\na local version file (.prototools), and lastly a global version file (~/.proto/tools).\nA version can also be provided with the tool ID (node@20), to run a specific version without changing pins.\n\nIf no version can be found, the program will exit with an error.\n\nPass --path to run an arbitrary binary instead of a tool, with the [env] variables from .prototools\nand the detected versions of configured tools exported (<TOOL>_VERSION)."
     )]
     Run(RunArgs),
 
+    #[command(
+        name = "run-matrix",
+        about = "Run a command against multiple versions of a tool.",
+        long_about = "Run a command against multiple versions of a tool, installing missing versions as needed.
+Each version is run in sequence, and a pass/fail matrix with durations is reported at the end."
+    )]
+    RunMatrix(RunMatrixArgs),
+
     #[command(
         name = "setup",
         about = "Setup proto for your current shell by injecting exports and updating PATH."
     )]
     Setup(SetupArgs),
 
+    #[command(
+        name = "stats",
+        about = "Show how often installed tools are ran.",
+        long_about = "Show how many times each installed tool version has been ran, and when it was last used.\nRuns are only counted when the track-runs setting is enabled."
+    )]
+    Stats(StatsArgs),
+
+    #[command(
+        name = "status",
+        about = "Check the status of configured tools.",
+        long_about = "Check the status of configured tools, by verifying that plugins are configured, pinned versions are installed,\nand ecosystem files (.nvmrc, etc) agree with .prototools. Also displays which .prototools file pinned each version, and the\nexecutable a shim would run. Pass --check to exit with a non-zero code when problems are found, or --json for machine-readable output."
+    )]
+    Status(StatusArgs),
+
+    #[command(
+        name = "teardown",
+        about = "Remove proto from your system.",
+        long_about = "Remove proto from your system by deleting shims, bins, and shell profile changes,\nand optionally the entire ~/.proto store."
+    )]
+    Teardown(TeardownArgs),
+
+    #[command(
+        name = "telemetry",
+        about = "Operations for managing anonymous usage metrics (telemetry)."
+    )]
+    Telemetry {
+        #[command(subcommand)]
+        command: TelemetryCommands,
+    },
+
+    #[command(
+        name = "try",
+        about = "Start a temporary shell with tool versions applied.",
+        long_about = "Start a temporary shell (or run a command after --) with the provided tool versions (node@20 npm@10)\napplied for that session only. Missing versions are installed, and removed once the session ends, unless --keep is passed."
+    )]
+    Try(TryArgs),
+
     #[command(alias = "ua", name = "unalias", about = "Remove an alias from a tool.")]
     Unalias(UnaliasArgs),
 
@@ -199,16 +391,66 @@ pub enum Commands {
     #[command(
         alias = "up",
         name = "upgrade",
-        about = "Upgrade proto to the latest version."
+        about = "Upgrade proto to the latest version.",
+        long_about = "Upgrade proto to the latest version, or to a specific version with --to. The downloaded release is\nverified against its published SHA256 checksum, and against its minisign signature when PROTO_UPGRADE_PUBLIC_KEY\nis set. Previously installed versions are kept in ~/.proto/tools/proto, and can be switched back to with\n--rollback or --to without downloading."
     )]
-    Upgrade,
+    Upgrade(UpgradeArgs),
 
     #[command(
         alias = "u",
         name = "use",
-        about = "Download and install all tools from .prototools."
+        about = "Download and install all tools from .prototools.",
+        long_about = "Download and install all tools from .prototools in parallel. The number of concurrent installs\ncan be limited with --jobs, or the install-concurrency setting.\n\nTools that are already installed are skipped. Pass --continue-on-error to install the remaining\ntools when one fails, and print a summary of the failures at the end."
+    )]
+    Use(InstallAllArgs),
+
+    #[command(
+        name = "validate",
+        about = "Validate .prototools files.",
+        long_about = "Strictly validate all .prototools files in the current directory hierarchy, and the global .prototools,\nreporting unknown settings, invalid versions, and invalid plugin locators.\n\nPass --schema to print a JSON schema of .prototools, for use in editors."
+    )]
+    Validate(ValidateArgs),
+
+    #[command(
+        name = "verify",
+        about = "Verify that installed tools are functional.",
+        long_about = "Verify that installed tool versions exist and their executables can be located.\nPass --smoke to also run the plugin's smoke tests (verify_install), which are recorded in the tool's manifest."
+    )]
+    Verify(VerifyArgs),
+
+    #[command(
+        name = "warnings",
+        about = "List deprecations that proto may warn about.",
+        long_about = "List deprecations and breaking changes that proto may warn about, and whether they have been silenced.\n\nWarnings are displayed at most once per day, and can be silenced by adding their ID to the silence-warnings setting."
+    )]
+    Warnings(WarningsArgs),
+
+    #[command(
+        name = "which",
+        about = "Display the executable that will be run from PATH.",
+        long_about = "Display the executable that will be run from PATH. Pass --all to list every executable\nwith the same name (system, package manager, and proto) in precedence order."
+    )]
+    Which(WhichArgs),
+}
+
+#[derive(Clone, Debug, Subcommand)]
+pub enum CacheCommands {
+    #[command(
+        name = "clean",
+        about = "Remove all cached downloads.",
+        long_about = "Remove all cached downloads, including incomplete downloads that would otherwise be resumed.\nPass --unreferenced to only remove blobs that are no longer referenced by a download URL."
+    )]
+    Clean(CleanCacheArgs),
+
+    #[command(name = "dir", about = "Print the directory of the download cache.")]
+    Dir,
+
+    #[command(
+        name = "list",
+        about = "List cached downloads.",
+        long_about = "List the URLs of all cached downloads, with their size and when they were cached."
     )]
-    Use,
+    List(ListCacheArgs),
 }
 
 #[derive(Clone, Debug, Subcommand)]
@@ -221,6 +463,33 @@ pub enum DebugCommands {
 
     #[command(name = "env", about = "Debug the current proto environment and store.")]
     Env,
+
+    #[command(
+        name = "plugin-log",
+        about = "Display the most recent calls made to a tool's plugin.",
+        long_about = "Display the most recent calls made to a tool's plugin, including the function, truncated inputs and outputs,\nduration, and errors. Useful for diagnosing intermittent plugin failures after the fact."
+    )]
+    PluginLog(DebugPluginLogArgs),
+}
+
+#[derive(Clone, Debug, Subcommand)]
+pub enum HookCommands {
+    #[command(
+        name = "pre-commit",
+        about = "Verify that the toolchain is consistent before committing.",
+        long_about = "Verify that the toolchain is consistent before committing, by running the same checks as proto status --check,\nand only printing problems. Pass --generate to print a hook definition for the pre-commit framework."
+    )]
+    PreCommit(PreCommitHookArgs),
+}
+
+#[derive(Clone, Debug, Subcommand)]
+pub enum InventoryCommands {
+    #[command(
+        name = "add",
+        about = "Add an external installation of a tool to the inventory.",
+        long_about = "Add an external installation of a tool (one not installed by proto) to the inventory, and generate shims for it.\nThe installation is marked as unmanaged, and will not be deleted by proto clean or proto uninstall."
+    )]
+    Add(AddInventoryArgs),
 }
 
 #[derive(Clone, Debug, Subcommand)]
@@ -228,13 +497,21 @@ pub enum PluginCommands {
     #[command(
         name = "add",
         about = "Add a plugin to manage a tool.",
-        long_about = "Add a plugin to the local .prototools config, or global ~/.proto/.prototools config."
+        long_about = "Add a plugin to the local .prototools config, or global ~/.proto/.prototools config.\nPass --from-registry to find the plugin's locator in the registries configured with [settings.registries].\nPass --pin to pin a GitHub locator to a version constraint of its latest release, for example github:org/repo@^1.2.3."
     )]
     Add(AddPluginArgs),
 
+    #[command(
+        name = "doctor",
+        about = "Diagnose problems with a plugin.",
+        long_about = "Diagnose problems with a plugin, by verifying the cached WASM file, checking API compatibility\nand host permissions, and dry running register_tool and load_versions with timings."
+    )]
+    Doctor(DoctorPluginArgs),
+
     #[command(
         name = "info",
-        about = "Display information about a plugin and its inventory."
+        about = "Display information about a plugin and its inventory.",
+        long_about = "Display information about a plugin and its inventory, including the resolved plugin file and its checksum,\nsupported platforms, files checked for versions, binaries and shims, and the configuration schema.\nPass --json for a machine readable format."
     )]
     Info(InfoPluginArgs),
 
@@ -244,10 +521,64 @@ pub enum PluginCommands {
     )]
     List(ListPluginsArgs),
 
+    #[command(
+        name = "outdated",
+        about = "Check for newer releases of plugins.",
+        long_about = "Check whether the cached plugins are behind what their locators currently point at:\nthe latest release (or highest release matching a version constraint) for GitHub locators, or a changed ETag for URLs."
+    )]
+    Outdated(OutdatedPluginsArgs),
+
     #[command(
         name = "remove",
         about = "Remove a plugin and unmanage a tool.",
         long_about = "Remove a plugin from the local .prototools config, or global ~/.proto/.prototools config."
     )]
     Remove(RemovePluginArgs),
+
+    #[command(
+        name = "search",
+        about = "Search for plugins in the configured registries.",
+        long_about = "Search for plugins in the registries configured with [settings.registries], by matching the query\nagainst each plugin's ID, name, description, and tags."
+    )]
+    Search(SearchPluginArgs),
+
+    #[command(
+        name = "upgrade",
+        about = "Download newer releases of outdated plugins.",
+        long_about = "Download the plugins that are behind what their locators currently point at, and swap the cached plugins\nonce the download has completed. Pass --force to re-download plugins that are up to date."
+    )]
+    Upgrade(UpgradePluginsArgs),
+}
+
+#[derive(Clone, Debug, Subcommand)]
+pub enum QueryCommands {
+    #[command(
+        name = "manifest",
+        about = "Print the installed versions, timestamps, bins, and shims of a tool as JSON.",
+        long_about = "Print the installed versions, timestamps, bins, and shims of a tool as JSON.\nThe output follows a versioned schema (schema_version), unlike the internal manifest.json files, so is safe for editors and scripts to consume."
+    )]
+    Manifest(QueryManifestArgs),
+}
+
+#[derive(Clone, Debug, Subcommand)]
+pub enum TelemetryCommands {
+    #[command(
+        name = "disable",
+        about = "Disable sending anonymous usage metrics.",
+        long_about = "Disable sending anonymous usage metrics, by setting settings.telemetry.enabled to false in the global .prototools."
+    )]
+    Disable,
+
+    #[command(
+        name = "enable",
+        about = "Enable sending anonymous usage metrics.",
+        long_about = "Enable sending anonymous usage metrics, by setting settings.telemetry.enabled to true in the global .prototools.\nIndividual metrics can be disabled with the other settings.telemetry fields."
+    )]
+    Enable,
+
+    #[command(
+        name = "status",
+        about = "Display whether anonymous usage metrics are sent, and which metrics."
+    )]
+    Status,
 }
@@ -0,0 +1,235 @@
+use crate::commands::env::load_detected_tools;
+use crate::helpers::ProtoResource;
+use crate::shell::detect_shell;
+use clap::Args;
+use clap_complete::Shell;
+use miette::IntoDiagnostic;
+use proto_core::ProtoError;
+use starbase::system;
+use std::collections::BTreeMap;
+use std::env;
+use std::path::PathBuf;
+
+/// Directories that were prepended to PATH by the previous activation.
+pub const ACTIVATED_PATHS_VAR: &str = "_PROTO_ACTIVATED_PATHS";
+
+/// Variables that were exported by the previous activation.
+pub const ACTIVATED_VARS_VAR: &str = "_PROTO_ACTIVATED_VARS";
+
+const BASH_HOOK: &str = r#"
+_proto_activate_hook() {
+    local previous_exit_status=$?
+    eval "$(proto activate bash --export)"
+    return $previous_exit_status
+}
+
+if [[ ";${PROMPT_COMMAND[*]:-};" != *";_proto_activate_hook;"* ]]; then
+    if [[ "$(declare -p PROMPT_COMMAND 2>&1)" == "declare -a"* ]]; then
+        PROMPT_COMMAND=(_proto_activate_hook "${PROMPT_COMMAND[@]}")
+    else
+        PROMPT_COMMAND="_proto_activate_hook${PROMPT_COMMAND:+;$PROMPT_COMMAND}"
+    fi
+fi
+
+_proto_activate_hook
+"#;
+
+const ZSH_HOOK: &str = r#"
+_proto_activate_hook() {
+    eval "$(proto activate zsh --export)"
+}
+
+typeset -ag precmd_functions chpwd_functions
+
+if (( ! ${precmd_functions[(I)_proto_activate_hook]} )); then
+    precmd_functions=(_proto_activate_hook $precmd_functions)
+fi
+
+if (( ! ${chpwd_functions[(I)_proto_activate_hook]} )); then
+    chpwd_functions=(_proto_activate_hook $chpwd_functions)
+fi
+
+_proto_activate_hook
+"#;
+
+const FISH_HOOK: &str = r#"
+function _proto_activate_hook --on-variable PWD --on-event fish_prompt
+    proto activate fish --export | source
+end
+
+_proto_activate_hook
+"#;
+
+const POWERSHELL_HOOK: &str = r#"
+function global:_proto_activate_hook {
+    $exports = proto activate powershell --export | Out-String
+
+    if ($exports) {
+        Invoke-Expression $exports
+    }
+}
+
+if (-not (Test-Path function:\_proto_original_prompt)) {
+    Copy-Item function:\prompt function:\_proto_original_prompt
+
+    function global:prompt {
+        _proto_activate_hook
+        _proto_original_prompt
+    }
+}
+
+_proto_activate_hook
+"#;
+
+#[derive(Args, Clone, Debug)]
+pub struct ActivateArgs {
+    #[arg(help = "Shell to activate for")]
+    shell: Option<Shell>,
+
+    #[arg(
+        long,
+        hide = true,
+        help = "Print exports for the current directory, used by the hook"
+    )]
+    export: bool,
+}
+
+fn quote(shell: &Shell, value: &str) -> String {
+    match shell {
+        Shell::PowerShell => format!("'{}'", value.replace('\'', "''")),
+        Shell::Fish => format!("'{}'", value.replace('\\', "\\\\").replace('\'', "\\'")),
+        _ => format!("'{}'", value.replace('\'', r#"'\''"#)),
+    }
+}
+
+fn format_set(shell: &Shell, key: &str, value: &str) -> String {
+    match shell {
+        Shell::Fish => format!("set -gx {key} {}", quote(shell, value)),
+        Shell::PowerShell => format!("$env:{key} = {}", quote(shell, value)),
+        _ => format!("export {key}={}", quote(shell, value)),
+    }
+}
+
+fn format_unset(shell: &Shell, key: &str) -> String {
+    match shell {
+        Shell::Fish => format!("set -e {key}"),
+        Shell::PowerShell => format!("Remove-Item Env:{key} -ErrorAction SilentlyContinue"),
+        _ => format!("unset {key}"),
+    }
+}
+
+/// Create exports that prepend the executable directories of tools detected
+/// for the current directory to PATH, and remove the directories and variables
+/// of the previous activation.
+async fn create_exports(proto: &ProtoResource, shell: &Shell) -> miette::Result<Vec<String>> {
+    let config = proto.env.load_config()?;
+    let mut vars = BTreeMap::<String, String>::new();
+    let mut paths = Vec::<PathBuf>::new();
+
+    for (key, value) in config.get_env_vars(None) {
+        if let Some(value) = value {
+            vars.insert(key, value);
+        }
+    }
+
+    for mut tool in load_detected_tools(proto).await? {
+        for (key, dir) in tool.get_root_env_vars() {
+            vars.insert(key, dir.to_string_lossy().to_string());
+        }
+
+        vars.insert(
+            format!("{}_VERSION", tool.get_env_var_prefix()),
+            tool.get_resolved_version().to_string(),
+        );
+
+        tool.locate_executable().await?;
+
+        if let Some(exe_dir) = tool.get_exe_path()?.parent() {
+            if !paths.iter().any(|path| path == exe_dir) {
+                paths.push(exe_dir.to_path_buf());
+            }
+        }
+
+        tool.locate_globals_dir().await?;
+
+        if let Some(globals_dir) = tool.get_globals_bin_dir() {
+            if globals_dir.exists() && !paths.iter().any(|path| path == globals_dir) {
+                paths.push(globals_dir.to_path_buf());
+            }
+        }
+    }
+
+    let previous_paths = env::var_os(ACTIVATED_PATHS_VAR)
+        .map(|value| env::split_paths(&value).collect::<Vec<_>>())
+        .unwrap_or_default();
+    let previous_vars = env::var(ACTIVATED_VARS_VAR).unwrap_or_default();
+    let mut exports = vec![];
+
+    // Restore variables that are no longer configured
+    for key in previous_vars.split(',').filter(|key| !key.is_empty()) {
+        if !vars.contains_key(key) {
+            exports.push(format_unset(shell, key));
+        }
+    }
+
+    let mut new_path = paths.clone();
+    new_path.extend(
+        env::split_paths(&env::var_os("PATH").unwrap_or_default())
+            .filter(|path| !previous_paths.contains(path) && !paths.contains(path)),
+    );
+
+    exports.push(format_set(
+        shell,
+        "PATH",
+        &env::join_paths(new_path)
+            .into_diagnostic()?
+            .to_string_lossy(),
+    ));
+
+    if paths.is_empty() {
+        exports.push(format_unset(shell, ACTIVATED_PATHS_VAR));
+    } else {
+        exports.push(format_set(
+            shell,
+            ACTIVATED_PATHS_VAR,
+            &env::join_paths(paths).into_diagnostic()?.to_string_lossy(),
+        ));
+    }
+
+    if vars.is_empty() {
+        exports.push(format_unset(shell, ACTIVATED_VARS_VAR));
+    } else {
+        exports.push(format_set(
+            shell,
+            ACTIVATED_VARS_VAR,
+            &vars.keys().cloned().collect::<Vec<_>>().join(","),
+        ));
+    }
+
+    for (key, value) in vars {
+        exports.push(format_set(shell, &key, &value));
+    }
+
+    Ok(exports)
+}
+
+#[system]
+pub async fn activate(args: ArgsRef<ActivateArgs>, proto: ResourceRef<ProtoResource>) {
+    let shell = detect_shell(args.shell);
+
+    let hook = match shell {
+        Shell::Bash => BASH_HOOK,
+        Shell::Zsh => ZSH_HOOK,
+        Shell::Fish => FISH_HOOK,
+        Shell::PowerShell => POWERSHELL_HOOK,
+        _ => {
+            return Err(ProtoError::UnsupportedShell.into());
+        }
+    };
+
+    if args.export {
+        println!("{}", create_exports(proto, &shell).await?.join("\n"));
+    } else {
+        println!("{}", hook.trim());
+    }
+}
@@ -1,60 +1,201 @@
 use crate::error::ProtoCliError;
-use crate::helpers::ProtoResource;
+use crate::helpers::{read_batch_input, ProtoResource};
 use clap::Args;
-use proto_core::{is_alias_name, Id, ProtoConfig, UnresolvedVersionSpec};
+use proto_core::{
+    expand_composite_spec, format_alias_chain, is_alias_name, resolve_version, trace_alias_chain,
+    Id, ProtoConfig, Tool, UnresolvedVersionSpec,
+};
 use starbase::system;
 use starbase_styles::color;
+use std::path::PathBuf;
 use tracing::info;
 
 #[derive(Args, Clone, Debug)]
 pub struct AliasArgs {
-    #[arg(required = true, help = "ID of tool")]
-    id: Id,
+    #[arg(required_unless_present_any = ["file", "stdin"], help = "ID of tool")]
+    id: Option<Id>,
 
-    #[arg(required = true, help = "Alias name")]
-    alias: String,
+    #[arg(required_unless_present_any = ["file", "stdin"], help = "Alias name")]
+    alias: Option<String>,
 
-    #[arg(required = true, help = "Version or alias to associate with")]
-    spec: UnresolvedVersionSpec,
+    #[arg(
+        required_unless_present_any = ["file", "stdin"],
+        help = "Version, range, alias, or composite (joined by ||) to associate with"
+    )]
+    spec: Option<UnresolvedVersionSpec>,
+
+    #[arg(
+        long,
+        conflicts_with_all = ["id", "stdin"],
+        help = "Read aliases from a file, with an ID, alias name, and version (node lts 20) on each line"
+    )]
+    file: Option<PathBuf>,
 
     #[arg(
         long,
         help = "Add to the global .prototools instead of local .prototools"
     )]
     global: bool,
+
+    #[arg(
+        long,
+        conflicts_with = "id",
+        help = "Read aliases from stdin, with an ID, alias name, and version (node lts 20) on each line"
+    )]
+    stdin: bool,
 }
 
-#[system]
-pub async fn alias(args: ArgsRef<AliasArgs>, proto: ResourceRef<ProtoResource>) {
-    if let UnresolvedVersionSpec::Alias(inner_alias) = &args.spec {
-        if &args.alias == inner_alias {
+/// Parse lines of batch input into tool IDs, alias names, and versions.
+fn parse_batch_aliases(
+    lines: Vec<(usize, String)>,
+) -> Result<Vec<(Id, String, UnresolvedVersionSpec)>, ProtoCliError> {
+    let mut parsed = vec![];
+
+    for (line, content) in lines {
+        let handle_error = || ProtoCliError::InvalidBatchLine {
+            line,
+            content: content.clone(),
+            expected: "a tool ID, alias name, and version, for example node lts 20".into(),
+        };
+
+        let [id, alias, spec] = content.split_whitespace().collect::<Vec<_>>()[..] else {
+            return Err(handle_error());
+        };
+
+        parsed.push((
+            Id::new(id).map_err(|_| handle_error())?,
+            alias.to_owned(),
+            UnresolvedVersionSpec::parse(spec).map_err(|_| handle_error())?,
+        ));
+    }
+
+    Ok(parsed)
+}
+
+/// Validate that the alias is allowed, and that its chain resolves to a version,
+/// using the configured aliases with the pending aliases applied.
+async fn validate_alias(
+    tool: &Tool,
+    alias: &str,
+    spec: &UnresolvedVersionSpec,
+    pending: &[(String, UnresolvedVersionSpec)],
+) -> miette::Result<()> {
+    if let UnresolvedVersionSpec::Alias(inner_alias) = spec {
+        if alias == inner_alias {
             return Err(ProtoCliError::NoMatchingAliasToVersion.into());
         }
     }
 
-    if !is_alias_name(&args.alias) {
+    if !is_alias_name(alias) {
         return Err(ProtoCliError::InvalidAliasName {
-            alias: args.alias.clone(),
+            alias: alias.to_owned(),
         }
         .into());
     }
 
-    let tool = proto.load_tool(&args.id).await?;
+    let mut tool_config = tool
+        .proto
+        .load_config()?
+        .tools
+        .get(&tool.id)
+        .cloned()
+        .unwrap_or_default();
+
+    tool_config.aliases.extend(pending.iter().cloned());
+
+    let resolver = tool.load_version_resolver(spec).await?;
+    let chain = trace_alias_chain(alias, &resolver.aliases, Some(&tool_config))?;
 
-    ProtoConfig::update(tool.proto.get_config_dir(args.global), |config| {
+    // Every member of a composite must resolve, not just the highest
+    if let Some(UnresolvedVersionSpec::Composite(specs)) = chain.last() {
+        for spec in expand_composite_spec(specs, &resolver.aliases, Some(&tool_config))? {
+            if resolve_version(
+                &spec,
+                &resolver.versions,
+                &resolver.aliases,
+                None,
+                Some(&tool_config),
+            )
+            .is_none()
+            {
+                return Err(ProtoCliError::UnresolvedCompositeMember {
+                    alias: alias.to_owned(),
+                    spec: spec.to_string(),
+                }
+                .into());
+            }
+        }
+    }
+
+    if resolve_version(
+        spec,
+        &resolver.versions,
+        &resolver.aliases,
+        None,
+        Some(&tool_config),
+    )
+    .is_none()
+    {
+        return Err(ProtoCliError::UnresolvedAliasChain {
+            alias: alias.to_owned(),
+            chain: format_alias_chain(&chain),
+        }
+        .into());
+    }
+
+    Ok(())
+}
+
+#[system]
+pub async fn alias(args: ArgsRef<AliasArgs>, proto: ResourceRef<ProtoResource>) {
+    let entries = if args.stdin || args.file.is_some() {
+        parse_batch_aliases(read_batch_input(args.file.as_deref())?)?
+    } else {
+        vec![(
+            args.id.clone().unwrap(),
+            args.alias.clone().unwrap(),
+            args.spec.clone().unwrap(),
+        )]
+    };
+
+    let mut aliases = vec![];
+
+    for (id, alias, spec) in &entries {
+        let tool = proto.load_tool(id).await?;
+
+        // Aliases in the same batch may reference each other
+        let pending = entries
+            .iter()
+            .filter(|(other_id, _, _)| other_id == id)
+            .map(|(_, alias, spec)| (alias.clone(), spec.clone()))
+            .collect::<Vec<_>>();
+
+        validate_alias(&tool, alias, spec, &pending).await?;
+
+        aliases.push((tool, alias, spec));
+    }
+
+    // Write all aliases in a single transaction, so that an invalid
+    // alias doesn't leave the config partially updated
+    ProtoConfig::update(proto.env.get_config_dir(args.global), |config| {
         let tool_configs = config.tools.get_or_insert(Default::default());
-        let tool_config = tool_configs.entry(tool.id.clone()).or_default();
 
-        tool_config
-            .aliases
-            .get_or_insert(Default::default())
-            .insert(args.alias.clone(), args.spec.clone());
+        for (tool, alias, spec) in &aliases {
+            tool_configs
+                .entry(tool.id.clone())
+                .or_default()
+                .aliases
+                .get_or_insert(Default::default())
+                .insert((*alias).clone(), (*spec).clone());
+        }
     })?;
 
-    info!(
-        "Added alias {} ({}) for {}",
-        color::id(&args.alias),
-        color::muted_light(args.spec.to_string()),
-        tool.get_name(),
-    );
+    for (tool, alias, spec) in aliases {
+        info!(
+            "Added alias {} ({}) for {}",
+            color::id(alias),
+            color::muted_light(spec.to_string()),
+            tool.get_name(),
+        );
+    }
 }
@@ -1,31 +1,233 @@
+use crate::error::ProtoCliError;
 use crate::helpers::ProtoResource;
 use clap::Args;
-use proto_core::{detect_version, Id, UnresolvedVersionSpec};
+use proto_core::{detect_version, ExecutableLocation, Id, Tool, UnresolvedVersionSpec};
 use starbase::system;
+use tracing::debug;
 
 #[derive(Args, Clone, Debug)]
 pub struct BinArgs {
     #[arg(required = true, help = "ID of tool")]
     id: Id,
 
+    #[arg(help = "Version or alias of tool, or the name of a secondary executable (npx for node)")]
+    spec: Option<String>,
+
+    #[arg(
+        conflicts_with = "for_version",
+        help = "Version or alias of tool, when querying a secondary executable"
+    )]
+    version: Option<UnresolvedVersionSpec>,
+
+    #[arg(
+        long,
+        conflicts_with = "dir",
+        help = "Display the name and path of every executable the tool provides"
+    )]
+    all: bool,
+
     #[arg(long, help = "Display symlinked binary path when available")]
     bin: bool,
 
-    #[arg(help = "Version or alias of tool")]
-    spec: Option<UnresolvedVersionSpec>,
+    #[arg(
+        long,
+        conflicts_with_all = ["bin", "shim"],
+        help = "Display the install directory (SDK root) instead of an executable"
+    )]
+    dir: bool,
+
+    #[arg(
+        long = "for",
+        value_name = "VERSION",
+        conflicts_with_all = ["bin", "shim"],
+        help = "Display the binary path of a specific installed version, without detecting or pinning"
+    )]
+    for_version: Option<UnresolvedVersionSpec>,
 
     #[arg(long, help = "Display shim path when available")]
     shim: bool,
 }
 
+fn ensure_installed(tool: &Tool) -> miette::Result<()> {
+    if !tool.is_installed() {
+        return Err(ProtoCliError::MissingToolVersion {
+            tool: tool.get_name().to_owned(),
+            version: tool.get_resolved_version().to_string(),
+            command: format!("proto install {} {}", tool.id, tool.get_resolved_version()),
+        }
+        .into());
+    }
+
+    Ok(())
+}
+
+// Secondary executables are positional, like versions, so an alias-like
+// value is only a name if the resolved version provides an executable with it
+async fn resolve_exe_name(
+    tool: &mut Tool,
+    args: &BinArgs,
+) -> miette::Result<(Option<String>, Option<UnresolvedVersionSpec>)> {
+    let Some(value) = &args.spec else {
+        return Ok((None, args.version.clone()));
+    };
+
+    let spec = match UnresolvedVersionSpec::parse(value) {
+        Ok(spec) if !matches!(spec, UnresolvedVersionSpec::Alias(_)) => {
+            return Ok((None, Some(spec)));
+        }
+        Ok(spec) if args.version.is_none() => spec,
+        // Not a valid version, or a version was passed after it
+        _ => return Ok((Some(value.to_owned()), args.version.clone())),
+    };
+
+    if let Ok(version) = detect_version(tool, None).await {
+        if tool.resolve_version(&version, true).await.is_ok()
+            && tool
+                .get_exe_locations()?
+                .iter()
+                .any(|location| &location.name == value)
+        {
+            return Ok((Some(value.to_owned()), Some(version)));
+        }
+
+        tool.version = None;
+    }
+
+    Ok((None, Some(spec)))
+}
+
+// Find all installed tools and versions that provide an executable with the name
+async fn find_exe_providers(proto: &ProtoResource, name: &str) -> Vec<String> {
+    let mut providers = vec![];
+
+    let Ok(tools) = proto.load_tools().await else {
+        return providers;
+    };
+
+    for mut tool in tools {
+        let mut versions = tool
+            .manifest
+            .installed_versions
+            .iter()
+            .cloned()
+            .collect::<Vec<_>>();
+
+        versions.sort();
+
+        for version in versions.into_iter().rev() {
+            tool.set_version(version.clone());
+
+            if tool.get_exe_locations().is_ok_and(|locations| {
+                locations
+                    .iter()
+                    .any(|location| location.name == name && location.path.exists())
+            }) {
+                providers.push(format!("{} {}", tool.id, version));
+            }
+        }
+    }
+
+    providers
+}
+
+async fn find_exe_location(
+    proto: &ProtoResource,
+    tool: &Tool,
+    locations: Vec<ExecutableLocation>,
+    name: &str,
+) -> miette::Result<ExecutableLocation> {
+    if let Some(location) = locations.into_iter().find(|location| location.name == name) {
+        return Ok(location);
+    }
+
+    debug!("Executable {} not found, searching other tools", name);
+
+    let providers = find_exe_providers(proto, name).await;
+
+    Err(ProtoCliError::UnknownExecutable {
+        tool: tool.get_name().to_owned(),
+        version: tool.get_resolved_version().to_string(),
+        name: name.to_owned(),
+        suggestion: if providers.is_empty() {
+            None
+        } else {
+            Some(format!("Provided by {}", providers.join(", ")))
+        },
+    }
+    .into())
+}
+
+fn get_locations(tool: &Tool, args: &BinArgs) -> miette::Result<Vec<ExecutableLocation>> {
+    if args.bin {
+        tool.get_bin_locations()
+    } else if args.shim {
+        tool.get_shim_locations()
+    } else {
+        tool.get_exe_locations()
+    }
+}
+
 #[system]
 pub async fn bin(args: ArgsRef<BinArgs>, proto: ResourceRef<ProtoResource>) {
     let mut tool = proto.load_tool(&args.id).await?;
-    let version = detect_version(&tool, args.spec.clone()).await?;
+
+    // Only locate the executable, as creating shims and bins
+    // would change which version is used globally
+    if let Some(spec) = &args.for_version {
+        tool.resolve_version(spec, true).await?;
+
+        ensure_installed(&tool)?;
+
+        if args.dir {
+            println!("{}", tool.get_tool_dir().display());
+        } else if args.all {
+            for location in tool.get_exe_locations()? {
+                println!("{} {}", location.name, location.path.display());
+            }
+        } else if let Some(name) = &args.spec {
+            let location = find_exe_location(proto, &tool, tool.get_exe_locations()?, name).await?;
+
+            println!("{}", location.path.display());
+        } else {
+            tool.locate_executable().await?;
+
+            println!("{}", tool.get_exe_path()?.display());
+        }
+
+        return Ok(());
+    }
+
+    let (exe_name, spec) = resolve_exe_name(&mut tool, args).await?;
+    let version = detect_version(&tool, spec).await?;
 
     tool.resolve_version(&version, true).await?;
+
+    if args.dir {
+        ensure_installed(&tool)?;
+
+        println!("{}", tool.get_tool_dir().display());
+
+        return Ok(());
+    }
+
     tool.create_executables(true, false).await?;
 
+    if args.all {
+        for location in get_locations(&tool, args)? {
+            println!("{} {}", location.name, location.path.display());
+        }
+
+        return Ok(());
+    }
+
+    if let Some(name) = &exe_name {
+        let location = find_exe_location(proto, &tool, get_locations(&tool, args)?, name).await?;
+
+        println!("{}", location.path.display());
+
+        return Ok(());
+    }
+
     if args.bin {
         for bin in tool.get_bin_locations()? {
             if bin.primary {
@@ -0,0 +1,47 @@
+use crate::helpers::{format_size, ProtoResource};
+use clap::Args;
+use proto_core::DownloadCache;
+use starbase::system;
+use tracing::info;
+
+#[derive(Args, Clone, Debug)]
+pub struct CleanCacheArgs {
+    #[arg(
+        long,
+        help = "Only remove blobs that are no longer referenced by a download"
+    )]
+    unreferenced: bool,
+}
+
+#[system]
+pub async fn clean(args: ArgsRef<CleanCacheArgs>, proto: ResourceRef<ProtoResource>) {
+    let cache_dir = proto.env.get_download_cache_dir();
+
+    if !cache_dir.exists() {
+        info!("No downloads have been cached");
+
+        return Ok(());
+    }
+
+    let _lock = DownloadCache::lock(&cache_dir)?;
+    let mut cache = DownloadCache::load(&cache_dir)?;
+
+    if args.unreferenced {
+        let deleted = cache.remove_unreferenced_blobs()?;
+
+        info!("Removed {} unreferenced blobs", deleted.len());
+
+        return Ok(());
+    }
+
+    let count = cache.refs.len();
+    let freed = cache.clear()?;
+
+    cache.save()?;
+
+    info!(
+        "Removed {} cached downloads, freeing {}",
+        count,
+        format_size(freed)
+    );
+}
@@ -0,0 +1,7 @@
+use crate::helpers::ProtoResource;
+use starbase::system;
+
+#[system]
+pub async fn dir(proto: ResourceRef<ProtoResource>) {
+    println!("{}", proto.env.get_download_cache_dir().display());
+}
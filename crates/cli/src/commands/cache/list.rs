@@ -0,0 +1,84 @@
+use crate::helpers::{format_size, serialize_timestamp, ProtoResource};
+use crate::printer::{format_value, Printer};
+use clap::Args;
+use miette::IntoDiagnostic;
+use proto_core::DownloadCache;
+use serde::Serialize;
+use starbase::system;
+use starbase_styles::color;
+use starbase_utils::json;
+use std::collections::BTreeMap;
+use tracing::info;
+
+#[derive(Args, Clone, Debug)]
+pub struct ListCacheArgs {
+    #[arg(long, help = "Print the list in JSON format")]
+    json: bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct CacheItem<'a> {
+    blob: &'a str,
+    #[serde(serialize_with = "serialize_timestamp")]
+    cached_at: u128,
+    size: u64,
+}
+
+#[system]
+pub async fn list(args: ArgsRef<ListCacheArgs>, proto: ResourceRef<ProtoResource>) {
+    let cache = DownloadCache::load(proto.env.get_download_cache_dir())?;
+
+    if args.json {
+        let items = cache
+            .refs
+            .iter()
+            .map(|(url, entry)| {
+                (
+                    url,
+                    CacheItem {
+                        blob: &entry.blob,
+                        cached_at: entry.cached_at,
+                        size: entry.size,
+                    },
+                )
+            })
+            .collect::<BTreeMap<_, _>>();
+
+        println!("{}", json::to_string_pretty(&items).into_diagnostic()?);
+
+        return Ok(());
+    }
+
+    if cache.refs.is_empty() {
+        info!("No downloads have been cached");
+
+        return Ok(());
+    }
+
+    let config = proto.env.load_config()?;
+    let mut printer = Printer::new().with_date_format(config.settings.ui.date_format.clone());
+
+    let items = cache
+        .refs
+        .iter()
+        .map(|(url, entry)| {
+            let mut comments = vec![format_size(entry.size)];
+
+            if let Some(at) = printer.date(entry.cached_at) {
+                comments.push(format!("cached {at}"));
+            }
+
+            (color::url(url), format_value(comments.join(", ")))
+        })
+        .collect::<Vec<_>>();
+
+    printer.entry_map("Downloads", items, None);
+
+    printer.entry(
+        "Total size",
+        format_size(cache.refs.values().map(|entry| entry.size).sum()),
+    );
+
+    printer.flush();
+}
@@ -0,0 +1,7 @@
+mod clean;
+mod dir;
+mod list;
+
+pub use clean::*;
+pub use dir::*;
+pub use list::*;
@@ -1,13 +1,17 @@
-use crate::helpers::ProtoResource;
+use crate::helpers::{format_size, ProtoResource};
+use crate::printer::Printer;
 use clap::Args;
 use dialoguer::Confirm;
-use proto_core::{remove_bin_file, Id, ProtoError, Tool, VersionSpec};
+use proto_core::{
+    get_dir_size, now, remove_bin_file, Id, PluginCache, ProtoCleanSettingsConfig, ProtoError,
+    Tool, UnresolvedVersionSpec, VersionSpec,
+};
 use rustc_hash::FxHashSet;
 use starbase::diagnostics::IntoDiagnostic;
 use starbase::{system, SystemResult};
 use starbase_styles::color;
 use starbase_utils::fs;
-use std::time::{Duration, SystemTime};
+use std::time::Duration;
 use tracing::{debug, info};
 
 #[derive(Args, Clone, Debug, Default)]
@@ -33,21 +37,60 @@ pub struct CleanArgs {
     )]
     pub purge_plugins: bool,
 
+    #[arg(
+        long,
+        help = "Only clean stale and unreferenced plugins",
+        conflicts_with = "purge-type"
+    )]
+    pub plugins: bool,
+
+    #[arg(
+        long,
+        help = "Report stale versions and reclaimable disk space without removing anything",
+        conflicts_with_all = ["purge-type", "plugins"]
+    )]
+    pub dry_run: bool,
+
     #[arg(long, help = "Avoid and force confirm prompts")]
     pub yes: bool,
 }
 
-fn is_older_than_days(now: u128, other: u128, days: u8) -> bool {
+fn is_older_than_days(now: u128, other: u128, days: u64) -> bool {
     (now - other) > ((days as u128) * 24 * 60 * 60 * 1000)
 }
 
-pub async fn clean_tool(mut tool: Tool, now: u128, days: u8, yes: bool) -> miette::Result<usize> {
+fn is_protected_version(version: &VersionSpec, specs: &[UnresolvedVersionSpec]) -> bool {
+    let VersionSpec::Version(version) = version else {
+        return specs.contains(&version.to_unresolved_spec());
+    };
+
+    specs.iter().any(|spec| match spec {
+        UnresolvedVersionSpec::Req(req) => req.matches(version),
+        UnresolvedVersionSpec::ReqAny(reqs) => reqs.iter().any(|req| req.matches(version)),
+        UnresolvedVersionSpec::Version(other) => other == version,
+        _ => false,
+    })
+}
+
+pub struct StaleVersion {
+    pub version: VersionSpec,
+    pub size: u64,
+}
+
+/// Find versions of the tool that can be removed according to the retention
+/// policy in `[settings.clean]`, and the disk space each of them occupies.
+pub fn find_stale_versions(
+    tool: &Tool,
+    now: u128,
+    days: u64,
+    settings: &ProtoCleanSettingsConfig,
+) -> miette::Result<Vec<StaleVersion>> {
     debug!("Checking {}", color::shell(tool.get_name()));
 
     if tool.metadata.inventory.override_dir.is_some() {
         debug!("Using an external inventory, skipping");
 
-        return Ok(0);
+        return Ok(vec![]);
     }
 
     let inventory_dir = tool.get_inventory_dir();
@@ -55,7 +98,7 @@ pub async fn clean_tool(mut tool: Tool, now: u128, days: u8, yes: bool) -> miett
     if !inventory_dir.exists() {
         debug!("Not being used, skipping");
 
-        return Ok(0);
+        return Ok(vec![]);
     }
 
     let mut versions_to_clean = FxHashSet::<VersionSpec>::default();
@@ -72,8 +115,8 @@ pub async fn clean_tool(mut tool: Tool, now: u128, days: u8, yes: bool) -> miett
         if dir_type.is_dir() {
             let dir_name = fs::file_name(&dir_path);
 
-            // Node.js compat
-            if dir_name == "globals" {
+            // Node.js compat, and staged installs
+            if dir_name == "globals" || dir_name.starts_with('.') {
                 continue;
             }
 
@@ -93,6 +136,23 @@ pub async fn clean_tool(mut tool: Tool, now: u128, days: u8, yes: bool) -> miett
         }
     }
 
+    let protected = settings
+        .protected
+        .get(&tool.id)
+        .map(|specs| specs.as_slice())
+        .unwrap_or_default();
+
+    let mut latest = tool
+        .manifest
+        .versions
+        .iter()
+        .filter(|(_, metadata)| metadata.unmanaged_path.is_none())
+        .map(|(version, _)| version)
+        .collect::<Vec<_>>();
+
+    latest.sort_by(|a, d| d.cmp(a));
+    latest.truncate(settings.keep_latest);
+
     debug!("Comparing last used timestamps from manifest");
 
     for (version, metadata) in &tool.manifest.versions {
@@ -100,6 +160,15 @@ pub async fn clean_tool(mut tool: Tool, now: u128, days: u8, yes: bool) -> miett
             continue;
         }
 
+        if metadata.unmanaged_path.is_some() {
+            debug!(
+                "Version {} is unmanaged, skipping",
+                color::hash(version.to_string())
+            );
+
+            continue;
+        }
+
         if metadata.no_clean {
             debug!(
                 "Version {} is marked as not to clean, skipping",
@@ -109,6 +178,25 @@ pub async fn clean_tool(mut tool: Tool, now: u128, days: u8, yes: bool) -> miett
             continue;
         }
 
+        if latest.contains(&version) {
+            debug!(
+                "Version {} is one of the latest {} versions, skipping",
+                color::hash(version.to_string()),
+                settings.keep_latest
+            );
+
+            continue;
+        }
+
+        if is_protected_version(version, protected) {
+            debug!(
+                "Version {} is protected, skipping",
+                color::hash(version.to_string())
+            );
+
+            continue;
+        }
+
         // None may mean a few things:
         // - It was recently installed but not used yet
         // - It was installed before we started tracking last used timestamps
@@ -129,7 +217,25 @@ pub async fn clean_tool(mut tool: Tool, now: u128, days: u8, yes: bool) -> miett
         }
     }
 
-    let count = versions_to_clean.len();
+    let mut stale = versions_to_clean
+        .into_iter()
+        .map(|version| StaleVersion {
+            size: get_dir_size(&inventory_dir.join(version.to_string())),
+            version,
+        })
+        .collect::<Vec<_>>();
+
+    stale.sort_by(|a, d| a.version.cmp(&d.version));
+
+    Ok(stale)
+}
+
+pub async fn remove_stale_versions(
+    mut tool: Tool,
+    stale: Vec<StaleVersion>,
+    yes: bool,
+) -> miette::Result<usize> {
+    let count = stale.len();
     let mut clean_count = 0;
 
     if count == 0 {
@@ -143,17 +249,17 @@ pub async fn clean_tool(mut tool: Tool, now: u128, days: u8, yes: bool) -> miett
             .with_prompt(format!(
                 "Found {} versions, remove {}?",
                 count,
-                versions_to_clean
+                stale
                     .iter()
-                    .map(|v| color::hash(v.to_string()))
+                    .map(|v| color::hash(v.version.to_string()))
                     .collect::<Vec<_>>()
                     .join(", ")
             ))
             .interact()
             .into_diagnostic()?
     {
-        for version in versions_to_clean {
-            tool.set_version(version);
+        for item in stale {
+            tool.set_version(item.version);
             tool.teardown().await?;
         }
 
@@ -165,14 +271,156 @@ pub async fn clean_tool(mut tool: Tool, now: u128, days: u8, yes: bool) -> miett
     Ok(clean_count)
 }
 
+pub async fn clean_tool(
+    mut tool: Tool,
+    now: u128,
+    days: u64,
+    settings: &ProtoCleanSettingsConfig,
+    yes: bool,
+) -> miette::Result<usize> {
+    for version in tool.repair_interrupted_installs()? {
+        info!(
+            "Removed interrupted install of {} {}",
+            tool.get_name(),
+            color::hash(version.to_string())
+        );
+    }
+
+    let stale = find_stale_versions(&tool, now, days, settings)?;
+
+    remove_stale_versions(tool, stale, yes).await
+}
+
+/// Clean stale versions of the tool when their combined size exceeds
+/// the `auto-threshold` setting. This is ran after installing a tool.
+pub async fn auto_clean_tool(proto: &ProtoResource, id: &Id) -> miette::Result<usize> {
+    let config = proto.env.load_config()?;
+    let settings = &config.settings.clean;
+
+    let Some(threshold) = settings.auto_threshold else {
+        return Ok(0);
+    };
+
+    let tool = proto.load_tool(id).await?;
+    let stale = find_stale_versions(&tool, now(), settings.stale_days, settings)?;
+    let size = stale.iter().map(|item| item.size).sum::<u64>();
+
+    if size <= threshold * 1024 * 1024 {
+        debug!(
+            "Stale versions of {} use {}, which is below the auto-clean threshold",
+            tool.get_name(),
+            format_size(size)
+        );
+
+        return Ok(0);
+    }
+
+    info!(
+        "Stale versions of {} use {}, cleaning",
+        tool.get_name(),
+        format_size(size)
+    );
+
+    remove_stale_versions(tool, stale, true).await
+}
+
+pub async fn print_reclaimable(
+    proto: &ProtoResource,
+    now: u128,
+    days: u64,
+    settings: &ProtoCleanSettingsConfig,
+) -> SystemResult {
+    let mut reclaimable = vec![];
+
+    for tool in proto.load_tools().await? {
+        let stale = find_stale_versions(&tool, now, days, settings)?;
+
+        if !stale.is_empty() {
+            reclaimable.push((tool.id.clone(), tool.get_name().to_owned(), stale));
+        }
+    }
+
+    if reclaimable.is_empty() {
+        info!("No stale versions to clean");
+
+        return Ok(());
+    }
+
+    let mut printer = Printer::new();
+    let mut total = 0;
+
+    for (id, name, stale) in reclaimable {
+        let size = stale.iter().map(|item| item.size).sum::<u64>();
+
+        printer.line();
+        printer.header(&id, name);
+
+        printer.section(|p| {
+            p.entry_list(
+                "Versions",
+                stale.iter().map(|item| {
+                    format!(
+                        "{} {}",
+                        color::hash(item.version.to_string()),
+                        color::muted_light(format!("({})", format_size(item.size)))
+                    )
+                }),
+                None,
+            );
+            p.entry("Reclaimable", format_size(size));
+
+            Ok(())
+        })?;
+
+        total += size;
+    }
+
+    printer.line();
+    printer.entry("Total reclaimable", format_size(total));
+    printer.flush();
+
+    Ok(())
+}
+
 pub async fn clean_plugins(proto: &ProtoResource, days: u64) -> miette::Result<usize> {
     let duration = Duration::from_secs(86400 * days);
+    let plugins_dir = &proto.env.plugins_dir;
     let mut clean_count = 0;
 
-    for file in fs::read_dir(&proto.env.plugins_dir)? {
+    if !plugins_dir.exists() {
+        return Ok(0);
+    }
+
+    // Lock the index so that plugins aren't downloaded while collecting
+    let lock = PluginCache::lock(plugins_dir)?;
+    let mut cache = PluginCache::load(plugins_dir)?;
+
+    for key in cache.remove_stale_refs(duration) {
+        debug!(
+            "Plugin {} hasn't been downloaded in over {} days, removing reference",
+            color::file(key),
+            days
+        );
+    }
+
+    for path in cache.remove_unreferenced_blobs()? {
+        debug!(
+            "Plugin blob {} is no longer referenced, removing",
+            color::path(path)
+        );
+
+        clean_count += 1;
+    }
+
+    cache.save()?;
+    lock.unlock()?;
+
+    // Plugins downloaded before the index existed
+    for file in fs::read_dir(plugins_dir)? {
         let path = file.path();
+        let name = fs::file_name(&path);
 
-        if path.is_file() {
+        if path.is_file() && !name.starts_with('.') && name != "index.json" {
             let bytes = fs::remove_file_if_older_than(&path, duration)?;
 
             if bytes > 0 {
@@ -194,7 +442,7 @@ pub async fn clean_proto(proto: &ProtoResource, days: u64) -> miette::Result<usi
     let duration = Duration::from_secs(86400 * days);
     let mut clean_count = 0;
 
-    for file in fs::read_dir_all(proto.env.tools_dir.join("proto"))? {
+    for file in fs::read_dir_all(proto.env.root.join("tools").join("proto"))? {
         let path = file.path();
 
         if path.is_file() {
@@ -270,20 +518,23 @@ pub async fn purge_plugins(proto: &ProtoResource, yes: bool) -> SystemResult {
 }
 
 pub async fn internal_clean(proto: &ProtoResource, args: &CleanArgs) -> SystemResult {
-    let days = args.days.unwrap_or(30);
-    let now = SystemTime::now()
-        .duration_since(SystemTime::UNIX_EPOCH)
-        .unwrap()
-        .as_millis();
+    let config = proto.env.load_config()?;
+    let settings = &config.settings.clean;
+    let days = args.days.map(u64::from).unwrap_or(settings.stale_days);
+    let now = now();
     let mut clean_count = 0;
 
+    if args.dry_run {
+        return print_reclaimable(proto, now, days, settings).await;
+    }
+
     debug!("Finding installed tools to clean up...");
 
     for tool in proto.load_tools().await? {
-        clean_count += clean_tool(tool, now, days, args.yes).await?;
+        clean_count += clean_tool(tool, now, days, settings, args.yes).await?;
     }
 
-    clean_count += clean_proto(proto, days as u64).await?;
+    clean_count += clean_proto(proto, days).await?;
 
     if clean_count > 0 {
         info!("Successfully cleaned up {} versions", clean_count);
@@ -291,7 +542,7 @@ pub async fn internal_clean(proto: &ProtoResource, args: &CleanArgs) -> SystemRe
 
     debug!("Finding installed plugins to clean up...");
 
-    clean_count = clean_plugins(proto, days as u64).await?;
+    clean_count = clean_plugins(proto, days).await?;
 
     if clean_count > 0 {
         info!("Successfully cleaned up {} plugins", clean_count);
@@ -323,5 +574,20 @@ pub async fn clean(args: ArgsRef<CleanArgs>, proto: ResourceRef<ProtoResource>)
         return Ok(());
     }
 
+    if args.plugins {
+        let config = proto.env.load_config()?;
+        let days = args
+            .days
+            .map(u64::from)
+            .unwrap_or(config.settings.clean.stale_days);
+        let count = clean_plugins(proto, days).await?;
+
+        if count > 0 {
+            info!("Successfully cleaned up {} plugins", count);
+        }
+
+        return Ok(());
+    }
+
     internal_clean(proto, args).await?;
 }
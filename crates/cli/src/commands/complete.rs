@@ -0,0 +1,145 @@
+use crate::app::App;
+use crate::helpers::ProtoResource;
+use clap::{Args, Command, CommandFactory};
+use proto_core::{Id, ToolManifest};
+use proto_pdk_api::LoadVersionsOutput;
+use starbase::system;
+use starbase_utils::json;
+use std::collections::BTreeSet;
+
+#[derive(Args, Clone, Debug)]
+pub struct CompleteArgs {
+    #[arg(
+        last = true,
+        help = "Words of the command line after proto, ending with the word being completed"
+    )]
+    words: Vec<String>,
+}
+
+fn takes_value(command: &Command, flag: &str) -> bool {
+    command
+        .get_arguments()
+        .find(|arg| {
+            if let Some(short) = flag.strip_prefix('-').filter(|f| !f.starts_with('-')) {
+                short.len() == 1 && arg.get_short() == short.chars().next()
+            } else {
+                arg.get_long() == Some(&flag[2..])
+            }
+        })
+        .is_some_and(|arg| arg.get_action().takes_values())
+}
+
+/// Find the positional argument that is being completed, by walking the
+/// subcommands and flags of the command line. Returns the argument's ID,
+/// and the positional values that precede it.
+fn find_positional_arg(words: &[String]) -> Option<(String, Vec<String>)> {
+    let (current, previous) = words.split_last()?;
+
+    if current.starts_with('-') {
+        return None;
+    }
+
+    let mut app = App::command();
+    app.build();
+
+    let mut command = &app;
+    let mut positionals = vec![];
+    let mut skip_next = false;
+
+    for word in previous {
+        if skip_next {
+            skip_next = false;
+            continue;
+        }
+
+        // Arguments after -- are passed through to the tool
+        if word == "--" {
+            return None;
+        }
+
+        if word.starts_with('-') && word.len() > 1 {
+            skip_next = !word.contains('=') && takes_value(command, word);
+            continue;
+        }
+
+        if positionals.is_empty() {
+            if let Some(subcommand) = command.find_subcommand(word) {
+                command = subcommand;
+                continue;
+            }
+        }
+
+        positionals.push(word.to_owned());
+    }
+
+    let arg = command
+        .get_positionals()
+        .filter(|arg| !arg.is_last_set())
+        .nth(positionals.len())?;
+
+    Some((arg.get_id().to_string(), positionals))
+}
+
+fn complete_tools(proto: &ProtoResource) -> miette::Result<BTreeSet<String>> {
+    let config = proto.env.load_config()?;
+
+    Ok(config.plugins.keys().map(|id| id.to_string()).collect())
+}
+
+// Load versions from the manifest and the remote versions cache,
+// as loading the tool's plugin would be too slow while typing
+fn complete_versions(proto: &ProtoResource, id: &str) -> miette::Result<BTreeSet<String>> {
+    let Ok(id) = Id::new(id) else {
+        return Ok(BTreeSet::new());
+    };
+
+    let config = proto.env.load_config()?;
+    let tool_dir = proto.env.tools_dir.join(id.as_str());
+    let mut candidates = BTreeSet::new();
+
+    if let Some(tool_config) = config.tools.get(&id) {
+        candidates.extend(tool_config.aliases.keys().cloned());
+    }
+
+    if tool_dir.exists() {
+        let manifest = ToolManifest::load_from(&tool_dir)?;
+
+        candidates.extend(manifest.installed_versions.iter().map(|v| v.to_string()));
+    }
+
+    let cache_path = tool_dir.join("remote-versions.json");
+
+    if cache_path.exists() {
+        if let Ok(remote) = json::read_file::<LoadVersionsOutput>(&cache_path) {
+            if remote.latest.is_some() {
+                candidates.insert("latest".into());
+            }
+
+            candidates.extend(remote.aliases.keys().cloned());
+            candidates.extend(remote.versions.iter().map(|v| v.to_string()));
+        }
+    }
+
+    Ok(candidates)
+}
+
+#[system]
+pub async fn complete(args: ArgsRef<CompleteArgs>, proto: ResourceRef<ProtoResource>) {
+    let Some((arg_id, positionals)) = find_positional_arg(&args.words) else {
+        return Ok(());
+    };
+
+    let candidates = match (arg_id.as_str(), positionals.first()) {
+        ("id", _) => complete_tools(proto)?,
+        ("spec" | "semver", Some(id)) => complete_versions(proto, id)?,
+        _ => return Ok(()),
+    };
+
+    let prefix = args.words.last().map(|w| w.as_str()).unwrap_or_default();
+
+    for candidate in candidates {
+        if candidate.starts_with(prefix) {
+            println!("{candidate}");
+        }
+    }
+}
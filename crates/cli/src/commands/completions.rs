@@ -1,11 +1,77 @@
 use crate::app::App;
+use crate::error::ProtoCliError;
 use clap::{Args, CommandFactory};
 use clap_complete::{generate, Shell};
+use miette::IntoDiagnostic;
 use proto_core::ProtoError;
 use starbase::system;
+use std::io::Write;
+
+const BASH_DYNAMIC: &str = r#"
+_proto_dynamic() {
+    local candidates
+    candidates=$(proto complete -- "${COMP_WORDS[@]:1:COMP_CWORD}" 2>/dev/null)
+
+    if [[ -n "$candidates" ]]; then
+        COMPREPLY=($(compgen -W "$candidates" -- "${COMP_WORDS[COMP_CWORD]}"))
+        return 0
+    fi
+
+    _proto "$@"
+}
+
+complete -F _proto_dynamic -o bashdefault -o default proto
+"#;
+
+const ZSH_DYNAMIC: &str = r#"
+_proto_dynamic() {
+    local -a candidates
+    candidates=("${(@f)$(proto complete -- "${(@)words[2,CURRENT]}" 2>/dev/null)}")
+
+    if [[ -n "${candidates[1]}" ]]; then
+        compadd -a candidates
+    else
+        _proto "$@"
+    fi
+}
+
+compdef _proto_dynamic proto
+"#;
+
+const FISH_DYNAMIC: &str = r#"
+complete -c proto -f -a '(proto complete -- (commandline -opc)[2..] (commandline -ct) 2>/dev/null)'
+"#;
+
+const POWERSHELL_DYNAMIC: &str = r#"
+Register-ArgumentCompleter -Native -CommandName 'proto' -ScriptBlock {
+    param($wordToComplete, $commandAst, $cursorPosition)
+
+    $words = @($commandAst.CommandElements | Select-Object -Skip 1 | ForEach-Object { $_.ToString() })
+
+    if ($wordToComplete -ne '') {
+        $words = @($words | Select-Object -SkipLast 1)
+    }
+
+    $candidates = @(proto complete -- @words "$wordToComplete" 2>$null)
+
+    if ($candidates.Count -gt 0) {
+        $candidates | ForEach-Object {
+            [System.Management.Automation.CompletionResult]::new($_, $_, 'ParameterValue', $_)
+        }
+    } else {
+        & $__protoStaticCompleter $wordToComplete $commandAst $cursorPosition
+    }
+}
+"#;
 
 #[derive(Args, Clone, Debug)]
 pub struct CompletionsArgs {
+    #[arg(
+        long,
+        help = "Also complete tool IDs and versions dynamically, by calling proto while completing"
+    )]
+    dynamic: bool,
+
     #[arg(long, help = "Shell to generate for")]
     shell: Option<Shell>,
 }
@@ -19,5 +85,39 @@ pub async fn completions(args: ArgsRef<CompletionsArgs>) {
     let mut app = App::command();
     let mut stdio = std::io::stdout();
 
-    generate(shell, &mut app, "proto", &mut stdio);
+    if !args.dynamic {
+        generate(shell, &mut app, "proto", &mut stdio);
+
+        return Ok(());
+    }
+
+    let dynamic = match shell {
+        Shell::Bash => BASH_DYNAMIC,
+        Shell::Zsh => ZSH_DYNAMIC,
+        Shell::Fish => FISH_DYNAMIC,
+        Shell::PowerShell => POWERSHELL_DYNAMIC,
+        other => {
+            return Err(ProtoCliError::UnsupportedDynamicCompletions {
+                shell: other.to_string(),
+            })?;
+        }
+    };
+
+    let mut buffer = vec![];
+
+    generate(shell, &mut app, "proto", &mut buffer);
+
+    let mut output = String::from_utf8_lossy(&buffer).to_string();
+
+    // Keep the static completer around, so that it can be used as a fallback
+    if shell == Shell::PowerShell {
+        output = output.replace(
+            "Register-ArgumentCompleter -Native -CommandName 'proto' -ScriptBlock {",
+            "$__protoStaticCompleter = {",
+        );
+    }
+
+    output.push_str(dynamic);
+
+    stdio.write_all(output.as_bytes()).into_diagnostic()?;
 }
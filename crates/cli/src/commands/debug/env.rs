@@ -1,9 +1,11 @@
 use crate::helpers::ProtoResource;
 use crate::printer::{format_env_var, Printer};
+use proto_core::IntegrityLog;
 use proto_pdk_api::{HostArch, HostOS};
 use starbase::system;
 use starbase_styles::color;
 use std::env;
+use std::time::Duration;
 
 #[system]
 pub async fn env(proto: ResourceRef<ProtoResource>) {
@@ -14,6 +16,15 @@ pub async fn env(proto: ResourceRef<ProtoResource>) {
 
     printer.named_section("Store", |p| {
         p.entry("Root", color::path(&proto.env.root));
+
+        if let Some(project_store_dir) = &proto.env.project_store_dir {
+            p.entry("Project", color::path(project_store_dir));
+        }
+
+        if let Some(shared_store_dir) = &proto.env.shared_store_dir {
+            p.entry("Shared", color::path(shared_store_dir));
+        }
+
         p.entry("Bins", color::path(&proto.env.bin_dir));
         p.entry("Shims", color::path(&proto.env.shims_dir));
         p.entry("Plugins", color::path(&proto.env.plugins_dir));
@@ -73,5 +84,37 @@ pub async fn env(proto: ResourceRef<ProtoResource>) {
         Ok(())
     })?;
 
+    // INTEGRITY
+
+    let integrity_events =
+        IntegrityLog::load_recent(&proto.env, Duration::from_secs(60 * 60 * 24 * 30))?;
+
+    if !integrity_events.is_empty() {
+        printer.named_section("Integrity failures (last 30 days)", |p| {
+            for event in integrity_events {
+                p.entry_list(
+                    format!("{} {}", color::id(&event.tool), color::hash(&event.version)),
+                    [
+                        Some(color::url(&event.download_url)),
+                        event
+                            .expected_digest
+                            .map(|digest| format!("expected {}", color::hash(digest))),
+                        event
+                            .actual_digest
+                            .map(|digest| format!("actual {}", color::hash(digest))),
+                        event
+                            .plugin
+                            .map(|plugin| format!("plugin {}", color::muted_light(plugin))),
+                    ]
+                    .into_iter()
+                    .flatten(),
+                    None,
+                );
+            }
+
+            Ok(())
+        })?;
+    }
+
     printer.flush();
 }
@@ -1,5 +1,7 @@
 mod config;
 mod env;
+mod plugin_log;
 
 pub use config::*;
 pub use env::*;
+pub use plugin_log::*;
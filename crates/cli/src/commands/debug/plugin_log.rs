@@ -0,0 +1,70 @@
+use crate::helpers::{create_datetime, ProtoResource};
+use crate::printer::Printer;
+use clap::Args;
+use miette::IntoDiagnostic;
+use proto_core::{Id, PluginLog};
+use starbase::system;
+use starbase_styles::color;
+use starbase_utils::json;
+
+#[derive(Args, Clone, Debug)]
+pub struct DebugPluginLogArgs {
+    #[arg(required = true, help = "ID of tool")]
+    id: Id,
+
+    #[arg(long, help = "Print the log in JSON format")]
+    json: bool,
+}
+
+#[system]
+pub async fn plugin_log(args: ArgsRef<DebugPluginLogArgs>, proto: ResourceRef<ProtoResource>) {
+    let calls = PluginLog::load(&proto.env, &args.id)?;
+
+    if args.json {
+        println!("{}", json::to_string_pretty(&calls).into_diagnostic()?);
+
+        return Ok(());
+    }
+
+    if calls.is_empty() {
+        eprintln!("No plugin calls have been recorded for {}", args.id);
+
+        return Ok(());
+    }
+
+    let mut printer = Printer::new();
+
+    printer.named_section(
+        format!(
+            "Plugin calls ({})",
+            PluginLog::get_path(&proto.env, &args.id).display()
+        ),
+        |p| {
+            for call in calls {
+                p.entry_list(
+                    format!(
+                        "{} {}",
+                        color::property(&call.func),
+                        color::muted_light(format!("({}ms)", call.duration_ms))
+                    ),
+                    [
+                        create_datetime(call.called_at)
+                            .map(|at| format!("called at {}", at.format("%Y-%m-%d %H:%M:%S"))),
+                        Some(format!("input {}", color::muted_light(call.input))),
+                        call.output
+                            .map(|output| format!("output {}", color::muted_light(output))),
+                        call.error
+                            .map(|error| format!("error {}", color::failure(error))),
+                    ]
+                    .into_iter()
+                    .flatten(),
+                    None,
+                );
+            }
+
+            Ok(())
+        },
+    )?;
+
+    printer.flush();
+}
@@ -0,0 +1,615 @@
+use crate::commands::{find_executables_on_path, find_shadowing_executable, VersionManager};
+use crate::helpers::ProtoResource;
+use crate::printer::Printer;
+use clap::Args;
+use miette::IntoDiagnostic;
+use proto_core::{
+    find_permission_mismatches, fix_cache_permission, get_antivirus_exclusion_guidance,
+    get_proto_version, IntegrityLog, ProtoEnvironment, ShimRegistry, ToolInstallState,
+    ToolManifest, Version,
+};
+use rustc_hash::FxHashSet;
+use serde::Serialize;
+use starbase::system;
+use starbase_styles::color;
+use starbase_utils::{fs, json};
+use std::env;
+use std::process;
+use std::time::Duration;
+
+#[derive(Args, Clone, Debug, Default)]
+pub struct DoctorArgs {
+    #[arg(long, help = "Print the diagnostics in JSON format")]
+    json: bool,
+
+    #[arg(
+        long,
+        help = "Repair the ownership and modes of cache entries where possible"
+    )]
+    fix: bool,
+
+    #[arg(
+        long,
+        help = "Only check for other version managers, and explain which executable takes precedence for each tool"
+    )]
+    managers: bool,
+}
+
+#[derive(Serialize)]
+pub struct DoctorCheck {
+    name: String,
+    passed: bool,
+    message: String,
+    problems: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    precedence: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    repaired: Vec<String>,
+    suggestion: Option<String>,
+}
+
+impl DoctorCheck {
+    fn new(name: &str, message: &str) -> Self {
+        Self {
+            name: name.to_owned(),
+            passed: true,
+            message: message.to_owned(),
+            problems: vec![],
+            precedence: vec![],
+            repaired: vec![],
+            suggestion: None,
+        }
+    }
+
+    // Called last by each check, once all problems have been found
+    fn suggest(&mut self, suggestion: String) {
+        self.passed = self.problems.is_empty();
+
+        if !self.passed {
+            self.suggestion = Some(suggestion);
+        }
+    }
+}
+
+fn list_shim_names(proto: &ProtoEnvironment) -> miette::Result<Vec<String>> {
+    let mut names = vec![];
+
+    if !proto.shims_dir.exists() {
+        return Ok(names);
+    }
+
+    for entry in fs::read_dir(&proto.shims_dir)? {
+        let path = entry.path();
+
+        if !path.is_file() || path.extension().is_some_and(|ext| ext == "json") {
+            continue;
+        }
+
+        if let Some(stem) = path.file_stem() {
+            names.push(stem.to_string_lossy().to_string());
+        }
+    }
+
+    names.sort();
+
+    Ok(names)
+}
+
+fn has_installed_versions(proto: &ProtoEnvironment, id: &str) -> miette::Result<bool> {
+    let tool_dir = proto.tools_dir.join(id);
+
+    if !tool_dir.exists() {
+        return Ok(false);
+    }
+
+    Ok(!ToolManifest::load_from(tool_dir)?
+        .installed_versions
+        .is_empty())
+}
+
+// Check that shims belong to an installed tool
+fn check_shims(proto: &ProtoEnvironment) -> miette::Result<DoctorCheck> {
+    let mut check = DoctorCheck::new("Shims", "All shims point to an installed tool");
+    let registry = ShimRegistry::load(proto)?;
+
+    for name in list_shim_names(proto)? {
+        let Some(shim) = registry.get(&name) else {
+            check
+                .problems
+                .push(format!("{name} is not in the shim registry"));
+
+            continue;
+        };
+
+        let owner = shim.get_owner(&name);
+
+        if !has_installed_versions(proto, owner)? {
+            check.problems.push(format!(
+                "{name} points to {owner}, which has no installed versions"
+            ));
+        }
+    }
+
+    check.suggest("Regenerate shims with proto regen".into());
+
+    Ok(check)
+}
+
+// Check that bin symlinks point to existing executables
+fn check_bins(proto: &ProtoEnvironment) -> miette::Result<DoctorCheck> {
+    let mut check = DoctorCheck::new("Bins", "All bins point to an existing executable");
+
+    if proto.bin_dir.exists() {
+        for entry in fs::read_dir(&proto.bin_dir)? {
+            let path = entry.path();
+
+            let Ok(target) = std::fs::read_link(&path) else {
+                continue;
+            };
+
+            if !target.exists() {
+                check.problems.push(format!(
+                    "{} points to missing {}",
+                    path.file_name().unwrap_or_default().to_string_lossy(),
+                    target.display()
+                ));
+            }
+        }
+    }
+
+    check.problems.sort();
+    check.suggest("Relink bins with proto regen --bin".into());
+
+    Ok(check)
+}
+
+// Check that shims take precedence over system installs and proto's bins
+fn check_path(proto: &ProtoEnvironment) -> miette::Result<DoctorCheck> {
+    let mut check = DoctorCheck::new("PATH", "No shims are shadowed on PATH");
+    let paths = env::var_os("PATH")
+        .map(|path| env::split_paths(&path).collect::<Vec<_>>())
+        .unwrap_or_default();
+    let shims_index = paths.iter().position(|path| path == &proto.shims_dir);
+    let bin_index = paths.iter().position(|path| path == &proto.bin_dir);
+
+    match (shims_index, bin_index) {
+        (None, _) => {
+            check
+                .problems
+                .push(format!("{} is not on PATH", proto.shims_dir.display()));
+        }
+        (Some(shims_index), Some(bin_index)) if bin_index < shims_index => {
+            check.problems.push(format!(
+                "{} comes before {} on PATH",
+                proto.bin_dir.display(),
+                proto.shims_dir.display()
+            ));
+        }
+        _ => {}
+    };
+
+    for name in list_shim_names(proto)? {
+        let executables = find_executables_on_path(proto, &name);
+
+        if let Some(shadow) = find_shadowing_executable(&executables) {
+            check.problems.push(format!(
+                "{name} is shadowed by {} ({})",
+                shadow.path.display(),
+                shadow.source
+            ));
+        }
+    }
+
+    check.suggest(format!(
+        "Move {} to the start of PATH, or uninstall the system versions",
+        proto.shims_dir.display()
+    ));
+
+    Ok(check)
+}
+
+// Check for version directories that are not tracked by a manifest, and interrupted installs
+fn check_store(proto: &ProtoEnvironment) -> miette::Result<DoctorCheck> {
+    let mut check = DoctorCheck::new("Store", "All installed versions are tracked");
+    let mut orphaned_dirs = vec![];
+
+    if proto.tools_dir.exists() {
+        for tool_entry in fs::read_dir(&proto.tools_dir)? {
+            let tool_dir = tool_entry.path();
+
+            if !tool_dir.is_dir() {
+                continue;
+            }
+
+            let manifest = ToolManifest::load_from(&tool_dir)?;
+
+            for (version, state) in manifest.get_interrupted_installs() {
+                check.problems.push(format!(
+                    "{} {version} was interrupted while {}",
+                    fs::file_name(&tool_dir),
+                    match state {
+                        ToolInstallState::Installing => "installing",
+                        ToolInstallState::Staging => "unpacking",
+                        ToolInstallState::Validating => "validating",
+                        ToolInstallState::Committing => "moving into the inventory",
+                    }
+                ));
+            }
+
+            let installed = manifest
+                .installed_versions
+                .iter()
+                .map(|version| version.to_string())
+                .collect::<FxHashSet<_>>();
+
+            for version_entry in fs::read_dir(&tool_dir)? {
+                let version_dir = version_entry.path();
+                let name = version_dir
+                    .file_name()
+                    .unwrap_or_default()
+                    .to_string_lossy()
+                    .to_string();
+
+                // Ignore non-version directories, like globals
+                if !version_dir.is_dir() || (name != "canary" && Version::parse(&name).is_err()) {
+                    continue;
+                }
+
+                if !installed.contains(&name) {
+                    orphaned_dirs.push(version_dir);
+                }
+            }
+        }
+    }
+
+    orphaned_dirs.sort();
+
+    check.problems.extend(
+        orphaned_dirs
+            .iter()
+            .map(|dir| format!("{} is not in the tool's manifest", dir.display())),
+    );
+
+    check.suggest("Delete the directories, or run proto clean".into());
+
+    Ok(check)
+}
+
+// Check for downloads that failed checksum or signature verification recently
+fn check_integrity(proto: &ProtoEnvironment) -> miette::Result<DoctorCheck> {
+    let mut check = DoctorCheck::new(
+        "Integrity",
+        "No downloads have failed verification in the last 30 days",
+    );
+
+    for event in IntegrityLog::load_recent(proto, Duration::from_secs(60 * 60 * 24 * 30))? {
+        check.problems.push(format!(
+            "{} {} failed verification when downloading {}",
+            event.tool, event.version, event.download_url
+        ));
+    }
+
+    check.suggest(format!(
+        "Check the download mirrors and proxies of the affected tools, then delete {} once resolved",
+        IntegrityLog::get_path(proto).display()
+    ));
+
+    Ok(check)
+}
+
+// Check that configured plugins can be loaded, and that the WASM API
+// they target is supported by the current version of proto
+async fn check_plugins(proto: &ProtoResource) -> miette::Result<DoctorCheck> {
+    let mut check = DoctorCheck::new("Plugins", "All configured plugins are compatible");
+    let config = proto.env.load_config()?;
+    let ids = config
+        .versions
+        .keys()
+        .filter(|id| config.plugins.contains_key(*id))
+        .collect::<FxHashSet<_>>();
+
+    if !ids.is_empty() {
+        let proto_version = get_proto_version();
+
+        for tool in proto.load_tools_with_filters(ids).await? {
+            if let Some(api_version) = &tool.metadata.api_version {
+                if api_version > &proto_version {
+                    check.problems.push(format!(
+                        "{} targets WASM API {api_version}, but proto {proto_version} is installed",
+                        tool.id
+                    ));
+                }
+            }
+        }
+
+        for (id, error) in proto.failed_plugins.lock().unwrap().iter() {
+            check.problems.push(format!("{id} failed to load: {error}"));
+        }
+    }
+
+    check.problems.sort();
+
+    check.suggest(
+        "Inspect the plugin with proto plugin doctor <id>, update it to a compatible version, or upgrade proto"
+            .into(),
+    );
+
+    Ok(check)
+}
+
+// Check for configuration that was parsed but will be ignored
+fn check_config(proto: &ProtoEnvironment) -> miette::Result<DoctorCheck> {
+    let mut check = DoctorCheck::new("Config", "No configuration warnings");
+    let config = proto.load_config()?;
+
+    for file in &proto.load_config_manager()?.files {
+        if !file.exists {
+            continue;
+        }
+
+        if let Some(versions) = &file.config.versions {
+            for id in versions.keys() {
+                if !config.plugins.contains_key(id) {
+                    check.problems.push(format!(
+                        "{id} in {} has no plugin configured",
+                        file.path.display()
+                    ));
+                }
+            }
+        }
+
+        if let Some(tools) = &file.config.tools {
+            for id in tools.keys() {
+                if !config.plugins.contains_key(id) {
+                    check.problems.push(format!(
+                        "[tools.{id}] in {} has no plugin configured, and will be ignored",
+                        file.path.display()
+                    ));
+                }
+            }
+        }
+    }
+
+    check.suggest("Add the missing plugins with proto plugin add, or remove the fields".into());
+
+    Ok(check)
+}
+
+// Check for other version managers that take precedence over proto's shims
+fn check_managers(proto: &ProtoEnvironment, detailed: bool) -> miette::Result<DoctorCheck> {
+    let mut check = DoctorCheck::new(
+        "Version managers",
+        "No version managers take precedence over proto",
+    );
+    let paths = env::var_os("PATH")
+        .map(|path| env::split_paths(&path).collect::<Vec<_>>())
+        .unwrap_or_default();
+    let shims_index = paths.iter().position(|path| path == &proto.shims_dir);
+    let mut shadowing_dirs = vec![];
+
+    for (index, dir) in paths.iter().enumerate() {
+        let Some(manager) = VersionManager::detect(dir) else {
+            continue;
+        };
+
+        let message = match shims_index {
+            None => "is on PATH, but proto's shims are not",
+            Some(shims_index) if index < shims_index => "comes before proto's shims on PATH",
+            _ => "",
+        };
+
+        if !message.is_empty() {
+            check
+                .problems
+                .push(format!("{manager} ({}) {message}", dir.display()));
+
+            shadowing_dirs.push(dir.display().to_string());
+        } else if detailed {
+            check.precedence.push(format!(
+                "{manager} ({}) comes after proto's shims on PATH",
+                dir.display()
+            ));
+        }
+    }
+
+    if detailed {
+        for manager in VersionManager::ALL {
+            if env::var_os(manager.get_env_var()).is_some() {
+                check.precedence.push(format!(
+                    "{manager} is loaded in the current shell ({})",
+                    manager.get_env_var()
+                ));
+            }
+        }
+
+        for name in list_shim_names(proto)? {
+            let executables = find_executables_on_path(proto, &name);
+
+            if let Some(first) = executables.first() {
+                check.precedence.push(format!(
+                    "{name} resolves to {} ({}){}",
+                    first.source,
+                    first.path.display(),
+                    if executables.len() > 1 {
+                        format!(
+                            ", then {}",
+                            executables[1..]
+                                .iter()
+                                .map(|exe| exe.source.to_string())
+                                .collect::<Vec<_>>()
+                                .join(", ")
+                        )
+                    } else {
+                        String::new()
+                    }
+                ));
+            }
+        }
+    }
+
+    check.suggest(format!(
+        "Move {} before {} on PATH, or remove the managers' hooks from your shell profile",
+        proto.shims_dir.display(),
+        shadowing_dirs.join(", ")
+    ));
+
+    Ok(check)
+}
+
+// Check that cache entries are owned by the store's owner, and match the configured modes
+fn check_permissions(proto: &ProtoEnvironment, fix: bool) -> miette::Result<DoctorCheck> {
+    let mut check = DoctorCheck::new("Permissions", "All cache entries are owned by the store");
+    let permissions = &proto.load_config()?.settings.cache_permissions;
+
+    for dir in [
+        proto.plugins_dir.clone(),
+        proto.get_download_cache_dir(),
+        proto.temp_dir.clone(),
+    ] {
+        for mismatch in find_permission_mismatches(&dir, &proto.root, permissions) {
+            if !fix {
+                check.problems.push(mismatch.reason);
+                continue;
+            }
+
+            match fix_cache_permission(&mismatch.path, &proto.root, permissions) {
+                Ok(_) => check.repaired.push(mismatch.path.display().to_string()),
+                Err(error) => check.problems.push(format!(
+                    "{} could not be repaired: {error}",
+                    mismatch.path.display()
+                )),
+            };
+        }
+    }
+
+    check.suggest(if fix {
+        "Run proto doctor --fix as root, or with sudo, to repair entries owned by other users"
+            .into()
+    } else {
+        "Repair the entries with proto doctor --fix".into()
+    });
+
+    Ok(check)
+}
+
+// Check for patterns of antivirus software interfering with installs,
+// like interrupted unpacks, and installed versions with quarantined files
+fn check_antivirus(proto: &ProtoEnvironment) -> miette::Result<DoctorCheck> {
+    let mut check = DoctorCheck::new("Antivirus", "No antivirus interference detected");
+    let staging_dir = proto.temp_dir.join("unpack");
+
+    if staging_dir.exists() {
+        for entry in fs::read_dir(&staging_dir)? {
+            check.problems.push(format!(
+                "{} was left behind by an interrupted unpack",
+                entry.path().display()
+            ));
+        }
+    }
+
+    if proto.tools_dir.exists() {
+        for tool_entry in fs::read_dir(&proto.tools_dir)? {
+            let tool_dir = tool_entry.path();
+
+            if !tool_dir.is_dir() {
+                continue;
+            }
+
+            let manifest = ToolManifest::load_from(&tool_dir)?;
+
+            for version in &manifest.installed_versions {
+                let version_dir = tool_dir.join(version.to_string());
+
+                if !version_dir.exists()
+                    || manifest
+                        .versions
+                        .get(version)
+                        .is_some_and(|meta| meta.unmanaged_path.is_some())
+                {
+                    continue;
+                }
+
+                // Only proto's own metadata files remain
+                let has_files = fs::read_dir(&version_dir)?
+                    .iter()
+                    .any(|entry| !entry.file_name().to_string_lossy().starts_with('.'));
+
+                if !has_files {
+                    check.problems.push(format!(
+                        "{} {version} is installed, but its files are missing from {}, and may have been quarantined",
+                        fs::file_name(&tool_dir),
+                        version_dir.display()
+                    ));
+                }
+            }
+        }
+    }
+
+    let guidance = get_antivirus_exclusion_guidance(proto);
+
+    check.suggest(if proto.load_config()?.settings.safe_unpack {
+        guidance
+    } else {
+        format!("Enable the safe-unpack setting. {guidance}")
+    });
+
+    Ok(check)
+}
+
+#[system]
+pub async fn doctor(args: ArgsRef<DoctorArgs>, proto: ResourceRef<ProtoResource>) {
+    let checks = if args.managers {
+        vec![check_managers(&proto.env, true)?]
+    } else {
+        vec![
+            check_path(&proto.env)?,
+            check_shims(&proto.env)?,
+            check_bins(&proto.env)?,
+            check_store(&proto.env)?,
+            check_integrity(&proto.env)?,
+            check_plugins(proto).await?,
+            check_config(&proto.env)?,
+            check_permissions(&proto.env, args.fix)?,
+            check_antivirus(&proto.env)?,
+            check_managers(&proto.env, false)?,
+        ]
+    };
+
+    let failed = checks.iter().any(|check| !check.passed);
+
+    if args.json {
+        println!("{}", json::to_string_pretty(&checks).into_diagnostic()?);
+    } else {
+        let mut printer = Printer::new();
+
+        for check in &checks {
+            printer.named_section(&check.name, |p| {
+                if check.passed {
+                    p.entry("Status", color::success(&check.message));
+                } else {
+                    p.entry_list("Problems", &check.problems, None);
+                }
+
+                if !check.precedence.is_empty() {
+                    p.entry_list("Precedence", &check.precedence, None);
+                }
+
+                if !check.repaired.is_empty() {
+                    p.entry_list("Repaired", &check.repaired, None);
+                }
+
+                if let Some(suggestion) = &check.suggestion {
+                    p.entry("Suggestion", suggestion);
+                }
+
+                Ok(())
+            })?;
+        }
+
+        printer.flush();
+    }
+
+    if failed {
+        process::exit(1);
+    }
+}
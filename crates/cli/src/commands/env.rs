@@ -0,0 +1,99 @@
+use crate::helpers::ProtoResource;
+use crate::shell::{detect_shell, format_exports, Export};
+use clap::Args;
+use clap_complete::Shell;
+use miette::IntoDiagnostic;
+use proto_core::{detect_version, ProtoError, Tool};
+use rustc_hash::FxHashSet;
+use starbase::system;
+use starbase_utils::json;
+use std::collections::BTreeMap;
+use tracing::debug;
+
+#[derive(Args, Clone, Debug)]
+pub struct EnvArgs {
+    #[arg(long, help = "Print the variables as JSON")]
+    json: bool,
+
+    #[arg(long, help = "Shell to format exports for")]
+    shell: Option<Shell>,
+}
+
+/// Load tools with a configured version, and resolve the version detected
+/// for the current directory. Tools that have not been installed are skipped.
+pub async fn load_detected_tools(proto: &ProtoResource) -> miette::Result<Vec<Tool>> {
+    let config = proto.env.load_config()?;
+    let mut detected = vec![];
+
+    // Only load tools with a configured version, as an empty filter loads all
+    let ids = config.versions.keys().collect::<FxHashSet<_>>();
+
+    if ids.is_empty() {
+        return Ok(detected);
+    }
+
+    for mut tool in proto.load_tools_with_filters(ids).await? {
+        let Ok(version) = detect_version(&tool, None).await else {
+            debug!("No version detected for {}, skipping", tool.get_name());
+
+            continue;
+        };
+
+        tool.resolve_version(&version, true).await?;
+
+        if !tool.is_installed() {
+            debug!(
+                "{} {} has not been installed, skipping",
+                tool.get_name(),
+                tool.get_resolved_version()
+            );
+
+            continue;
+        }
+
+        detected.push(tool);
+    }
+
+    Ok(detected)
+}
+
+#[system]
+pub async fn env(args: ArgsRef<EnvArgs>, proto: ResourceRef<ProtoResource>) {
+    let config = proto.env.load_config()?;
+    let mut vars = BTreeMap::<String, String>::new();
+
+    // Variables from `[env]`, without a tool context
+    for (key, value) in config.get_env_vars(None) {
+        if let Some(value) = value {
+            vars.insert(key, value);
+        }
+    }
+
+    for tool in load_detected_tools(proto).await? {
+        for (key, dir) in tool.get_root_env_vars() {
+            vars.insert(key, dir.to_string_lossy().to_string());
+        }
+    }
+
+    if args.json {
+        println!("{}", json::to_string_pretty(&vars).into_diagnostic()?);
+
+        return Ok(());
+    }
+
+    if vars.is_empty() {
+        return Ok(());
+    }
+
+    let shell = detect_shell(args.shell);
+    let exports = vars
+        .into_iter()
+        .map(|(key, value)| Export::Var(key, value))
+        .collect();
+
+    let Some(content) = format_exports(&shell, "proto", exports) else {
+        return Err(ProtoError::UnsupportedShell.into());
+    };
+
+    println!("{}", content.trim());
+}
@@ -0,0 +1,184 @@
+use crate::commands::run::create_tool_command;
+use crate::helpers::ProtoResource;
+use crate::printer::Printer;
+use clap::Args;
+use miette::IntoDiagnostic;
+use proto_core::{detect_version, Id, ProtoError, UnresolvedVersionSpec};
+use serde::Serialize;
+use starbase::system;
+use starbase_styles::color;
+use starbase_utils::json;
+use std::collections::BTreeMap;
+use std::env;
+use std::path::PathBuf;
+
+#[derive(Args, Clone, Debug)]
+pub struct ExecEnvArgs {
+    #[arg(required = true, help = "ID of tool")]
+    id: Id,
+
+    #[arg(help = "Version or alias of tool")]
+    spec: Option<UnresolvedVersionSpec>,
+
+    #[arg(long, help = "Name of an alternate (secondary) binary")]
+    alt: Option<String>,
+
+    #[arg(
+        long,
+        help = "Directory to detect the version and configuration from, instead of the current directory"
+    )]
+    pub cwd: Option<PathBuf>,
+
+    #[arg(long, help = "Print the snapshot in JSON format")]
+    json: bool,
+
+    // Passthrough args (after --)
+    #[arg(
+        last = true,
+        help = "Arguments that would be passed through to the underlying command"
+    )]
+    passthrough: Vec<String>,
+}
+
+#[derive(Serialize)]
+pub struct ExecEnvVar {
+    current: Option<String>,
+    value: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct ExecEnvSnapshot {
+    command: Vec<String>,
+    cwd: PathBuf,
+    env: BTreeMap<String, ExecEnvVar>,
+    path_prepended: Vec<PathBuf>,
+}
+
+#[system]
+pub async fn exec_env(args: ArgsRef<ExecEnvArgs>, proto: ResourceRef<ProtoResource>) {
+    let mut tool = proto.load_tool(&args.id).await?;
+    let version = detect_version(&tool, args.spec.clone()).await?;
+
+    if !tool.is_setup(&version).await? {
+        return Err(ProtoError::MissingToolForRun {
+            tool: tool.get_name().to_owned(),
+            version: version.to_string(),
+            command: format!("proto install {} {}", tool.id, tool.get_resolved_version()),
+        }
+        .into());
+    }
+
+    let command = create_tool_command(&mut tool, args.alt.as_deref(), &args.passthrough).await?;
+
+    // Diff the command's environment against the current environment
+    let mut env_vars = BTreeMap::new();
+
+    for (key, value) in command.get_envs() {
+        let key = key.to_string_lossy().to_string();
+        let current = env::var_os(&key).map(|v| v.to_string_lossy().to_string());
+        let value = value.map(|v| v.to_string_lossy().to_string());
+
+        if current != value {
+            env_vars.insert(key, ExecEnvVar { current, value });
+        }
+    }
+
+    let path_prepended = match env_vars.get("PATH") {
+        Some(ExecEnvVar {
+            current,
+            value: Some(value),
+        }) => {
+            let current_paths =
+                env::split_paths(current.as_deref().unwrap_or_default()).collect::<Vec<_>>();
+
+            env::split_paths(value)
+                .take_while(|path| !current_paths.contains(path))
+                .collect()
+        }
+        _ => vec![],
+    };
+
+    let snapshot = ExecEnvSnapshot {
+        command: [command.get_program()]
+            .into_iter()
+            .chain(command.get_args())
+            .map(|arg| arg.to_string_lossy().to_string())
+            .collect(),
+        cwd: proto.env.cwd.clone(),
+        env: env_vars,
+        path_prepended,
+    };
+
+    if args.json {
+        println!("{}", json::to_string_pretty(&snapshot).into_diagnostic()?);
+
+        return Ok(());
+    }
+
+    let mut printer = Printer::new();
+
+    printer.header(&tool.id, tool.get_name());
+
+    printer.section(|p| {
+        p.entry(
+            "Version",
+            color::hash(tool.get_resolved_version().to_string()),
+        );
+        p.entry("Directory", color::path(&snapshot.cwd));
+        p.entry("Command", color::shell(snapshot.command.join(" ")));
+
+        Ok(())
+    })?;
+
+    printer.named_section("Environment", |p| {
+        if snapshot.env.is_empty() {
+            p.entry(
+                "No changes",
+                color::muted_light("inherits the current environment"),
+            );
+        }
+
+        for (key, var) in &snapshot.env {
+            match (&var.current, &var.value) {
+                (None, Some(value)) => {
+                    p.entry(
+                        format!("{} {}", color::success("+"), color::property(key)),
+                        value,
+                    );
+                }
+                (Some(current), Some(value)) => {
+                    p.entry(
+                        format!("{} {}", color::label("~"), color::property(key)),
+                        format!(
+                            "{} {} {value}",
+                            color::muted_light(current),
+                            color::muted("→")
+                        ),
+                    );
+                }
+                (_, None) => {
+                    p.entry(
+                        format!("{} {}", color::failure("-"), color::property(key)),
+                        color::muted_light("removed"),
+                    );
+                }
+            };
+        }
+
+        Ok(())
+    })?;
+
+    if !snapshot.path_prepended.is_empty() {
+        printer.named_section("PATH", |p| {
+            p.entry_list(
+                "Prepended",
+                snapshot.path_prepended.iter().map(color::path),
+                None,
+            );
+
+            Ok(())
+        })?;
+    }
+
+    printer.flush();
+}
@@ -0,0 +1,3 @@
+mod pre_commit;
+
+pub use pre_commit::*;
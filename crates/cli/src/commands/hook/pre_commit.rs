@@ -0,0 +1,87 @@
+use crate::commands::collect_status;
+use crate::helpers::ProtoResource;
+use clap::Args;
+use proto_core::PROTO_CONFIG_NAME;
+use starbase::system;
+use starbase_styles::color;
+use std::collections::BTreeSet;
+use std::process;
+use tracing::debug;
+
+#[derive(Args, Clone, Debug)]
+pub struct PreCommitHookArgs {
+    #[arg(
+        long,
+        help = "Print a .pre-commit-hooks.yaml compatible hook definition instead of running checks"
+    )]
+    generate: bool,
+}
+
+/// Escape a file name so that it can be used within a Python regex.
+fn escape_pattern(value: &str) -> String {
+    let mut pattern = String::new();
+
+    for ch in value.chars() {
+        if "\\.+*?()|[]{}^$".contains(ch) {
+            pattern.push('\\');
+        }
+
+        pattern.push(ch);
+    }
+
+    pattern
+}
+
+async fn generate_hook(proto: &ProtoResource) -> miette::Result<String> {
+    let mut files = BTreeSet::from_iter([PROTO_CONFIG_NAME.to_owned()]);
+
+    // Trigger the hook when any ecosystem version file changes
+    for tool in proto.load_tools().await? {
+        files.extend(tool.get_version_files()?);
+    }
+
+    let pattern = files
+        .iter()
+        .map(|file| escape_pattern(file))
+        .collect::<Vec<_>>()
+        .join("|");
+
+    Ok(format!(
+        r#"- id: proto-status
+  name: proto status
+  description: Verify that the toolchain configured with proto is consistent.
+  entry: proto hook pre-commit
+  language: system
+  files: (^|/)({pattern})$
+  pass_filenames: false
+"#
+    ))
+}
+
+#[system]
+pub async fn pre_commit(args: ArgsRef<PreCommitHookArgs>, proto: ResourceRef<ProtoResource>) {
+    if args.generate {
+        print!("{}", generate_hook(proto).await?);
+
+        return Ok(());
+    }
+
+    let (_, problems) = collect_status(proto).await?;
+
+    if problems.is_empty() {
+        debug!("Toolchain is consistent, no problems found");
+
+        return Ok(());
+    }
+
+    eprintln!(
+        "proto found {} toolchain problem(s):",
+        color::failure(problems.len().to_string())
+    );
+
+    for problem in &problems {
+        eprintln!("  {} {problem}", color::failure("✘"));
+    }
+
+    process::exit(1);
+}
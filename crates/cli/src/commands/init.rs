@@ -0,0 +1,239 @@
+use super::plugin::load_registry_indexes;
+use super::setup::internal_setup;
+use crate::helpers::{create_theme, ProtoResource};
+use clap::Args;
+use clap_complete::Shell;
+use dialoguer::Confirm;
+use miette::IntoDiagnostic;
+use proto_core::{Id, PluginLocator, ProtoConfig, UnresolvedVersionSpec, PROTO_CONFIG_NAME};
+use starbase::system;
+use starbase_styles::color;
+use starbase_utils::fs;
+use std::collections::BTreeMap;
+use std::env;
+use std::io::{self, IsTerminal};
+use std::path::Path;
+use tracing::{debug, info};
+
+/// Ecosystem files for tools that are not built-in, and the plugin
+/// to look for in the configured registries when they are found.
+const ECOSYSTEM_FILES: &[(&str, &str)] = &[
+    (".java-version", "java"),
+    (".ruby-version", "ruby"),
+    (".terraform-version", "terraform"),
+    (".zig-version", "zig"),
+    ("Gemfile", "ruby"),
+    ("build.zig", "zig"),
+    ("pom.xml", "java"),
+];
+
+#[derive(Args, Clone, Debug)]
+pub struct InitArgs {
+    #[arg(long, help = "Shell to setup for")]
+    shell: Option<Shell>,
+
+    #[arg(long, help = "Don't setup the shell (update PATH and profile)")]
+    no_setup: bool,
+
+    #[arg(
+        long,
+        short = 'y',
+        help = "Avoid interactive prompts and accept all suggestions"
+    )]
+    yes: bool,
+}
+
+#[derive(Debug)]
+struct Suggestion {
+    id: Id,
+    file: String,
+    locator: Option<PluginLocator>,
+    spec: Option<UnresolvedVersionSpec>,
+}
+
+impl Suggestion {
+    fn describe(&self) -> String {
+        let action = match (&self.locator, &self.spec) {
+            (Some(_), Some(spec)) => format!(
+                "Add plugin {} and pin version {}",
+                color::id(&self.id),
+                color::hash(spec.to_string())
+            ),
+            (Some(_), None) => format!("Add plugin {}", color::id(&self.id)),
+            (None, Some(spec)) => format!(
+                "Pin {} to version {}",
+                color::id(&self.id),
+                color::hash(spec.to_string())
+            ),
+            (None, None) => format!("Use {}", color::id(&self.id)),
+        };
+
+        format!("{action} (detected from {})", color::file(&self.file))
+    }
+}
+
+/// Scan the directory for ecosystem files of loaded tools (built-in and configured),
+/// and suggest pinning the version they contain.
+async fn detect_tool_suggestions(
+    proto: &ProtoResource,
+    dir: &Path,
+) -> miette::Result<Vec<Suggestion>> {
+    let mut suggestions = vec![];
+
+    for tool in proto.load_tools().await? {
+        let Some((spec, file)) = tool.detect_version_from(dir).await? else {
+            continue;
+        };
+
+        debug!(
+            tool = tool.id.as_str(),
+            file = ?file,
+            "Detected version {}", spec
+        );
+
+        suggestions.push(Suggestion {
+            id: tool.id.clone(),
+            file: file
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default(),
+            locator: None,
+            spec: Some(spec),
+        });
+    }
+
+    Ok(suggestions)
+}
+
+/// Scan the directory for ecosystem files of tools that aren't loaded,
+/// and suggest adding their plugin when found in a configured registry.
+async fn detect_plugin_suggestions(
+    proto: &ProtoResource,
+    dir: &Path,
+    known: &[Suggestion],
+) -> miette::Result<Vec<Suggestion>> {
+    let config = proto.env.load_config()?;
+    let mut found: BTreeMap<&str, &str> = BTreeMap::new();
+
+    for (file, id) in ECOSYSTEM_FILES {
+        if dir.join(file).exists()
+            && !config.plugins.contains_key(*id)
+            && !known.iter().any(|item| item.id == *id)
+        {
+            found.entry(*id).or_insert(*file);
+        }
+    }
+
+    if found.is_empty() || config.settings.registries.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let indexes = load_registry_indexes(proto).await?;
+    let mut suggestions = vec![];
+
+    for (id, file) in found {
+        let Some(plugin) = indexes
+            .iter()
+            .find_map(|(_, index)| index.find(&Id::raw(id)))
+        else {
+            debug!("No plugin found in registries for {}, skipping", id);
+
+            continue;
+        };
+
+        // Files named after the tool only contain a version
+        let spec = if file.ends_with("-version") {
+            fs::read_file(dir.join(file))
+                .ok()
+                .and_then(|content| UnresolvedVersionSpec::parse(content.trim()).ok())
+        } else {
+            None
+        };
+
+        suggestions.push(Suggestion {
+            id: plugin.id.clone(),
+            file: file.to_owned(),
+            locator: Some(plugin.locator.clone()),
+            spec,
+        });
+    }
+
+    Ok(suggestions)
+}
+
+#[system]
+pub async fn init(args: ArgsRef<InitArgs>, proto: ResourceRef<ProtoResource>) {
+    let interactive = !args.yes
+        && env::var("CI").is_err()
+        && io::stdin().is_terminal()
+        && io::stderr().is_terminal();
+
+    println!("Welcome to proto! Let's get your environment ready.");
+    println!();
+
+    if args.no_setup {
+        debug!("Skipping shell setup");
+    } else {
+        internal_setup(proto, args.shell, false, !interactive).await?;
+        println!();
+    }
+
+    let cwd = &proto.env.cwd;
+
+    info!("Scanning {} for ecosystem files", color::path(cwd));
+
+    let mut suggestions = detect_tool_suggestions(proto, cwd).await?;
+    suggestions.extend(detect_plugin_suggestions(proto, cwd, &suggestions).await?);
+
+    let mut accepted = vec![];
+
+    for suggestion in suggestions {
+        let accept = if interactive {
+            Confirm::with_theme(&create_theme())
+                .with_prompt(suggestion.describe())
+                .default(true)
+                .interact()
+                .into_diagnostic()?
+        } else {
+            info!("{}", suggestion.describe());
+
+            true
+        };
+
+        if accept {
+            accepted.push(suggestion);
+        }
+    }
+
+    if accepted.is_empty() && cwd.join(PROTO_CONFIG_NAME).exists() {
+        info!("Nothing to add, your {} is up to date", PROTO_CONFIG_NAME);
+
+        return Ok(());
+    }
+
+    let config_path = ProtoConfig::update(cwd, |config| {
+        for suggestion in accepted {
+            if let Some(locator) = suggestion.locator {
+                config
+                    .plugins
+                    .get_or_insert(Default::default())
+                    .insert(suggestion.id.clone(), locator);
+            }
+
+            if let Some(spec) = suggestion.spec {
+                config
+                    .versions
+                    .get_or_insert(Default::default())
+                    .insert(suggestion.id, spec);
+            }
+        }
+    })?;
+
+    info!("Wrote {}", color::path(config_path));
+
+    println!();
+    println!(
+        "Run {} to install the pinned tools!",
+        color::shell("proto install")
+    );
+}
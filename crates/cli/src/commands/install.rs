@@ -1,15 +1,29 @@
-use super::clean::clean_plugins;
+use super::clean::{auto_clean_tool, clean_plugins};
 use super::pin::internal_pin;
-use crate::helpers::{create_progress_bar, disable_progress_bars, ProtoResource};
+use crate::error::ProtoCliError;
+use crate::helpers::{
+    create_progress_bar, create_theme, disable_progress_bars, print_resolution_explanation,
+    ProtoResource,
+};
 use crate::shell::{self, Export};
 use crate::telemetry::{track_usage, Metric};
 use clap::{Args, ValueEnum};
-use proto_core::{Id, PinType, Tool, UnresolvedVersionSpec};
-use proto_pdk_api::{InstallHook, SyncShellProfileInput, SyncShellProfileOutput};
+use dialoguer::Confirm;
+use indicatif::{HumanBytes, ProgressBar};
+use miette::IntoDiagnostic;
+use proto_core::{
+    ConfigChangeStatus, ConfigJournal, ConfigJournalEntry, Id, IntegrityLog, LockedTool, PinType,
+    ProtoError, ProtoEvent, ProtoLock, Tool, UnresolvedVersionSpec,
+};
+use proto_pdk_api::{ConfigChange, InstallHook, SyncShellProfileInput, SyncShellProfileOutput};
 use starbase::system;
 use starbase_styles::color;
 use std::env;
-use tracing::{debug, info};
+use std::io::{self, IsTerminal};
+use std::path::{Path, PathBuf};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
 
 #[derive(Clone, Debug, ValueEnum)]
 pub enum PinOption {
@@ -29,6 +43,13 @@ pub struct InstallArgs {
     )]
     pub spec: Option<UnresolvedVersionSpec>,
 
+    #[arg(
+        long,
+        help = "Build from source instead of downloading a pre-built",
+        conflicts_with = "from_file"
+    )]
+    pub build: bool,
+
     #[arg(
         long,
         help = "Install a canary (nightly, etc) version",
@@ -36,6 +57,26 @@ pub struct InstallArgs {
     )]
     pub canary: bool,
 
+    #[arg(long, help = "Explain how the version was resolved")]
+    pub explain: bool,
+
+    #[arg(
+        long,
+        help = "Install the exact version recorded in .protolock",
+        conflicts_with = "canary"
+    )]
+    pub frozen: bool,
+
+    #[arg(
+        long,
+        help = "Install from a package created with proto package",
+        conflicts_with_all = ["canary", "frozen", "spec"]
+    )]
+    pub from_file: Option<PathBuf>,
+
+    #[arg(long, help = "Include prerelease versions when resolving")]
+    pub include_prereleases: bool,
+
     #[arg(long, help = "Pin the resolved version")]
     pub pin: Option<Option<PinOption>>,
 
@@ -80,6 +121,202 @@ async fn pin_version(
     Ok(pin)
 }
 
+fn describe_config_change(change: &ConfigChange) -> String {
+    match change {
+        ConfigChange::AddAlias { alias, version } => format!(
+            "adding alias {} ({})",
+            color::id(alias),
+            color::hash(version.to_string())
+        ),
+        ConfigChange::PinVersion { version } => {
+            format!("pinning version {}", color::hash(version.to_string()))
+        }
+    }
+}
+
+/// Review the config changes suggested by the plugin, confirm each change
+/// with the user, and record the outcome in the config journal.
+fn apply_config_changes(tool: &mut Tool) -> miette::Result<()> {
+    let changes = tool.take_config_changes();
+
+    if changes.is_empty() {
+        return Ok(());
+    }
+
+    // Only prompt when a user can actually respond
+    let interactive =
+        env::var("CI").is_err() && io::stdin().is_terminal() && io::stderr().is_terminal();
+
+    for change in changes {
+        let mut entry = ConfigJournalEntry {
+            change: change.clone(),
+            plugin: tool.locator.as_ref().map(|locator| locator.to_string()),
+            reason: None,
+            recorded_at: 0,
+            status: ConfigChangeStatus::Declined,
+            tool: tool.id.clone(),
+        };
+
+        if let Some(reason) = tool.review_config_change(&change)? {
+            warn!(
+                "Rejected config change from {} plugin, {}: {reason}",
+                tool.get_name(),
+                describe_config_change(&change)
+            );
+
+            entry.status = ConfigChangeStatus::Rejected;
+            entry.reason = Some(reason);
+        } else if !interactive {
+            debug!(
+                change = ?change,
+                "Skipping config change, as it requires confirmation"
+            );
+
+            entry.reason = Some("confirmation requires an interactive terminal".into());
+        } else if Confirm::with_theme(&create_theme())
+            .with_prompt(format!(
+                "The {} plugin suggests {}, apply to the global config?",
+                tool.get_name(),
+                describe_config_change(&change)
+            ))
+            .default(false)
+            .interact()
+            .into_diagnostic()?
+        {
+            tool.apply_config_change(&change)?;
+
+            entry.status = ConfigChangeStatus::Applied;
+        }
+
+        ConfigJournal::record(&tool.proto, entry)?;
+    }
+
+    Ok(())
+}
+
+pub fn lock_version(tool: &mut Tool, initial_version: &UnresolvedVersionSpec) {
+    let version = tool.get_resolved_version();
+
+    tool.locked
+        .get_or_insert_with(|| LockedTool::new(version))
+        .spec = Some(initial_version.to_owned());
+}
+
+/// Record the installed tools in the lockfile of the current directory,
+/// preserving the download URL and checksum of unchanged versions.
+pub fn update_lockfile(proto: &ProtoResource, tools: &[Tool]) -> miette::Result<()> {
+    let mut lock = ProtoLock::load_from(&proto.env.cwd)?;
+
+    for tool in tools {
+        let Some(mut locked) = tool.locked.clone() else {
+            continue;
+        };
+
+        if let Some(existing) = lock.tools.get(&tool.id) {
+            if existing.version == locked.version {
+                locked.checksum = locked.checksum.or_else(|| existing.checksum.clone());
+                locked.download_url = locked
+                    .download_url
+                    .or_else(|| existing.download_url.clone());
+            }
+        }
+
+        lock.tools.insert(tool.id.clone(), locked);
+    }
+
+    debug!(file = ?lock.path, "Updating lockfile");
+
+    lock.save()
+}
+
+// Update the progress bar from the events reported while installing
+fn render_install_progress(tool: &mut Tool, pb: ProgressBar) -> JoinHandle<()> {
+    let (reporter, mut events) = mpsc::unbounded_channel();
+    let name = tool.get_name().to_owned();
+
+    tool.set_reporter(reporter);
+
+    tokio::spawn(async move {
+        while let Some(event) = events.recv().await {
+            match event {
+                ProtoEvent::WaitingForLock { version, .. } => {
+                    pb.set_message(format!(
+                        "Waiting for another process to finish installing {name} {version}"
+                    ));
+                }
+                ProtoEvent::Building { version, .. } => {
+                    pb.set_message(format!("Building {name} {version}"));
+                }
+                ProtoEvent::Downloading { .. } => {
+                    pb.set_message(format!("Downloading {name}"));
+                }
+                ProtoEvent::DownloadProgress {
+                    downloaded, total, ..
+                } if total > 0 => {
+                    pb.set_message(format!(
+                        "Downloading {name} ({} / {})",
+                        HumanBytes(downloaded),
+                        HumanBytes(total)
+                    ));
+                }
+                ProtoEvent::Verifying { version, .. } => {
+                    pb.set_message(format!("Verifying {name} {version}"));
+                }
+                ProtoEvent::Unpacking { version, .. } => {
+                    pb.set_message(format!("Unpacking {name} {version}"));
+                }
+                _ => {}
+            };
+        }
+    })
+}
+
+async fn install_from_file(
+    proto: &ProtoResource,
+    mut tool: Tool,
+    file: &Path,
+    pin_type: &Option<PinType>,
+) -> miette::Result<Tool> {
+    let file = proto.env.cwd.join(file);
+
+    if !file.exists() {
+        return Err(ProtoCliError::MissingPackageFile { path: file }.into());
+    }
+
+    let pb = create_progress_bar(format!(
+        "Installing {} from {}",
+        tool.get_name(),
+        color::path(&file)
+    ));
+
+    let installed = tool.install_from_package(&file).await?;
+
+    pb.finish_and_clear();
+
+    let version = tool.get_resolved_version().to_unresolved_spec();
+
+    pin_version(&mut tool, &version, pin_type).await?;
+    lock_version(&mut tool, &version);
+
+    if installed {
+        apply_config_changes(&mut tool)?;
+
+        info!(
+            "{} has been installed to {}!",
+            tool.get_name(),
+            color::path(tool.get_tool_dir()),
+        );
+    } else {
+        info!(
+            "{} has already been installed at {}",
+            tool.get_name(),
+            color::path(tool.get_tool_dir()),
+        );
+    }
+
+    Ok(tool)
+}
+
 pub async fn internal_install(
     proto: &ProtoResource,
     args: InstallArgs,
@@ -90,6 +327,10 @@ pub async fn internal_install(
         None => proto.load_tool(&args.id).await?,
     };
 
+    if args.include_prereleases {
+        tool.enable_prereleases();
+    }
+
     let version = if args.canary {
         UnresolvedVersionSpec::Canary
     } else {
@@ -108,12 +349,37 @@ pub async fn internal_install(
         disable_progress_bars();
     }
 
+    // Packages contain an exact version, so skip resolution and downloading
+    if let Some(file) = &args.from_file {
+        return install_from_file(proto, tool, file, &pin_type).await;
+    }
+
+    // When frozen, use the locked version instead of resolving over the network
+    if args.frozen {
+        let lock = ProtoLock::load_from(&proto.env.cwd)?;
+        let locked = lock.get_frozen_tool(&tool.id, args.spec.as_ref())?;
+
+        debug!(
+            "Using locked version {} for {}",
+            locked.version,
+            tool.get_name()
+        );
+
+        tool.set_version(locked.version.clone());
+        tool.locked = Some(locked.to_owned());
+    }
+
     // Resolve version first so subsequent steps can reference the resolved version
     tool.resolve_version(&version, false).await?;
 
+    if args.explain {
+        print_resolution_explanation(&tool, Some(format!("requested {version}")));
+    }
+
     // Check if already installed, or if canary, overwrite previous install
     if !version.is_canary() && tool.is_setup(&version).await? {
         pin_version(&mut tool, &version, &pin_type).await?;
+        lock_version(&mut tool, &version);
 
         info!(
             "{} has already been installed at {}",
@@ -146,6 +412,8 @@ pub async fn internal_install(
         )?;
     }
 
+    tool.run_script_hook("pre-install")?;
+
     // Install the tool
     debug!(
         "Installing {} with version {} (from {})",
@@ -159,9 +427,25 @@ pub async fn internal_install(
         tool.get_name(),
         resolved_version
     ));
+    let progress = render_install_progress(&mut tool, pb.clone());
+
+    let installed = match tool.setup(&version, args.build).await {
+        Ok(installed) => installed,
+        Err(error) => {
+            if matches!(
+                error.downcast_ref::<ProtoError>(),
+                Some(ProtoError::InvalidChecksum { .. })
+            ) {
+                track_integrity_failure(&tool).await?;
+            }
 
-    let installed = tool.setup(&version, false).await?;
+            progress.abort();
 
+            return Err(error);
+        }
+    };
+
+    progress.abort();
     pb.finish_and_clear();
 
     if !installed {
@@ -170,6 +454,9 @@ pub async fn internal_install(
 
     let pinned = pin_version(&mut tool, &version, &pin_type).await?;
 
+    lock_version(&mut tool, &version);
+    apply_config_changes(&mut tool)?;
+
     info!(
         "{} has been installed to {}!",
         tool.get_name(),
@@ -205,6 +492,8 @@ pub async fn internal_install(
         )?;
     }
 
+    tool.run_script_hook("post-install")?;
+
     // Sync shell profile
     update_shell(&tool, args.passthrough.clone())?;
 
@@ -213,9 +502,33 @@ pub async fn internal_install(
 
     clean_plugins(proto, 7).await?;
 
+    // Clean stale versions once they exceed the threshold
+    auto_clean_tool(proto, &tool.id).await?;
+
     Ok(tool)
 }
 
+async fn track_integrity_failure(tool: &Tool) -> miette::Result<()> {
+    let Some(event) = IntegrityLog::load(&tool.proto)?
+        .into_iter()
+        .rev()
+        .find(|event| event.tool == tool.id)
+    else {
+        return Ok(());
+    };
+
+    track_usage(
+        &tool.proto,
+        Metric::IntegrityFailure {
+            download_url: event.download_url,
+            id: tool.id.to_string(),
+            plugin: event.plugin.unwrap_or_default(),
+            version: event.version,
+        },
+    )
+    .await
+}
+
 fn update_shell(tool: &Tool, passthrough_args: Vec<String>) -> miette::Result<()> {
     if !tool.plugin.has_func("sync_shell_profile") {
         return Ok(());
@@ -278,5 +591,11 @@ fn update_shell(tool: &Tool, passthrough_args: Vec<String>) -> miette::Result<()
 
 #[system]
 pub async fn install(args: ArgsRef<InstallArgs>, proto: ResourceRef<ProtoResource>) {
-    internal_install(proto, args.to_owned(), None).await?;
+    let tool = internal_install(proto, args.to_owned(), None).await?;
+
+    // Only update an existing lockfile, as global installs
+    // should not create one in an arbitrary directory
+    if !args.frozen && ProtoLock::exists_in(&proto.env.cwd) {
+        update_lockfile(proto, &[tool])?;
+    }
 }
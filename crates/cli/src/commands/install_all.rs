@@ -1,18 +1,133 @@
+use crate::error::ProtoCliError;
 use crate::helpers::{
     create_progress_bar, disable_progress_bars, enable_progress_bars, ProtoResource,
 };
+use crate::logging::{create_progress_logger, is_json_format};
 use crate::{
     commands::clean::{internal_clean, CleanArgs},
-    commands::install::{internal_install, InstallArgs},
+    commands::install::{internal_install, lock_version, update_lockfile, InstallArgs},
 };
+use clap::Args;
+use indicatif::MultiProgress;
 use miette::IntoDiagnostic;
+use proto_core::{ProtoLock, Tool, UnresolvedVersionSpec, VersionSpec};
 use starbase::system;
 use starbase_styles::color;
 use std::process;
+use std::sync::Arc;
+use tokio::sync::Semaphore;
 use tracing::{debug, info};
 
+// Downloads larger than this are installed one at a time, as downloading
+// multiple in parallel on slow connections is slower than serializing them
+const LARGE_DOWNLOAD_SIZE: u64 = 500 * 1024 * 1024;
+
+#[derive(Args, Clone, Debug, Default)]
+pub struct InstallAllArgs {
+    #[arg(
+        long,
+        short = 'j',
+        help = "Maximum number of tools to install at once (defaults to the install-concurrency setting)"
+    )]
+    pub jobs: Option<usize>,
+
+    #[arg(long, help = "Install the exact versions recorded in .protolock")]
+    pub frozen: bool,
+
+    #[arg(
+        long,
+        help = "Continue installing the remaining tools when one fails, and summarize the failures"
+    )]
+    pub continue_on_error: bool,
+}
+
+// Check the manifest for a fully-qualified (or locked) version, so that reruns
+// can skip installed tools without resolving versions over the network
+fn is_already_installed(
+    tool: &mut Tool,
+    version: &UnresolvedVersionSpec,
+    lock: Option<&ProtoLock>,
+) -> miette::Result<bool> {
+    let resolved_version = match lock {
+        Some(lock) => lock
+            .get_frozen_tool(&tool.id, Some(version))?
+            .version
+            .clone(),
+        None => match version {
+            UnresolvedVersionSpec::Version(_) => version.to_resolved_spec(),
+            _ => return Ok(false),
+        },
+    };
+
+    tool.set_version(resolved_version);
+
+    if tool.is_installed() {
+        return Ok(true);
+    }
+
+    tool.version = None;
+
+    Ok(false)
+}
+
+// Resolve the version ahead of time to determine the size of the download.
+// This is best effort, as any failure will be surfaced by the install itself.
+// The resolved version is discarded afterwards, so that the install resolves
+// the version itself, with its own settings (like caching)!
+async fn estimate_download_size(
+    tool: &mut Tool,
+    version: &UnresolvedVersionSpec,
+    locked_version: Option<VersionSpec>,
+) -> Option<u64> {
+    let previous_version = tool.version.take();
+
+    if let Some(locked_version) = locked_version {
+        tool.set_version(locked_version);
+    }
+
+    let size = match tool.resolve_version(version, false).await {
+        Ok(_) => tool.get_download_size().await,
+        Err(error) => Err(error),
+    };
+
+    tool.version = previous_version;
+
+    match size {
+        Ok(size) => {
+            debug!(size, "Estimated download size of {}", tool.get_name());
+
+            size
+        }
+        Err(error) => {
+            debug!(
+                "Failed to estimate download size of {}: {}",
+                tool.get_name(),
+                error.to_string()
+            );
+
+            None
+        }
+    }
+}
+
+// Install smaller downloads first, so that they are not stuck behind large ones,
+// and mark the downloads that are large enough to be installed one at a time
+fn schedule_installs<T>(mut installs: Vec<(T, Option<u64>)>) -> Vec<(T, bool)> {
+    installs.sort_by_key(|(_, size)| size.unwrap_or_default());
+
+    installs
+        .into_iter()
+        .map(|(install, size)| {
+            (
+                install,
+                size.is_some_and(|size| size >= LARGE_DOWNLOAD_SIZE),
+            )
+        })
+        .collect()
+}
+
 #[system]
-pub async fn install_all(proto: ResourceRef<ProtoResource>) {
+pub async fn install_all(args: ArgsRef<InstallAllArgs>, proto: ResourceRef<ProtoResource>) {
     debug!("Loading tools and plugins from .prototools");
 
     let tools = proto.load_tools().await?;
@@ -42,30 +157,130 @@ pub async fn install_all(proto: ResourceRef<ProtoResource>) {
         process::exit(1);
     }
 
-    let pb = create_progress_bar(format!(
-        "Installing {} tools: {}",
-        versions.len(),
-        versions
-            .keys()
-            .map(color::id)
-            .collect::<Vec<_>>()
-            .join(", ")
-    ));
+    let jobs = args
+        .jobs
+        .or(config.settings.install_concurrency)
+        .unwrap_or(versions.len())
+        .max(1);
+
+    debug!(jobs, "Installing {} tools", versions.len());
+
+    // Each tool has its own progress bar, but must be created
+    // before disabling the progress bars of the install internals
+    let multi = if is_json_format() {
+        MultiProgress::with_draw_target(create_progress_logger())
+    } else {
+        MultiProgress::new()
+    };
+    let mut installs = vec![];
+    let mut installed_tools = vec![];
+    let lock = if args.frozen {
+        Some(ProtoLock::load_from(&proto.env.cwd)?)
+    } else {
+        None
+    };
+
+    for mut tool in tools {
+        if let Some(version) = versions.remove(&tool.id) {
+            if is_already_installed(&mut tool, &version, lock.as_ref())? {
+                debug!(
+                    "{} {} has already been installed, skipping",
+                    tool.get_name(),
+                    tool.get_resolved_version()
+                );
+
+                match lock.as_ref().and_then(|lock| lock.tools.get(&tool.id)) {
+                    Some(locked) => tool.locked = Some(locked.to_owned()),
+                    None => lock_version(&mut tool, &version),
+                };
+
+                installed_tools.push(tool);
+                continue;
+            }
+
+            let pb = multi.add(create_progress_bar(format!(
+                "Waiting to install {} {}",
+                tool.get_name(),
+                color::hash(version.to_string())
+            )));
+
+            installs.push((tool, version, pb));
+        }
+    }
 
     disable_progress_bars();
 
-    // Then install each tool in parallel!
+    // Determine the download sizes in parallel, so that large downloads can be scheduled.
+    // This is only necessary when multiple tools are installed at once.
+    let mut estimates = vec![];
+    let estimate = jobs > 1 && installs.len() > 1;
+
+    for (mut tool, version, pb) in installs {
+        let locked_version = lock
+            .as_ref()
+            .and_then(|lock| lock.tools.get(&tool.id))
+            .map(|locked| locked.version.clone());
+
+        estimates.push(tokio::spawn(async move {
+            let size = if estimate {
+                estimate_download_size(&mut tool, &version, locked_version).await
+            } else {
+                None
+            };
+
+            (tool, version, pb, size)
+        }));
+    }
+
+    let mut estimated = vec![];
+
+    for estimate in estimates {
+        let (tool, version, pb, size) = estimate.await.into_diagnostic()?;
+
+        estimated.push(((tool, version, pb), size));
+    }
+
+    // Then install each tool in parallel, bounded by the number of jobs,
+    // while large downloads are installed one at a time!
+    let semaphore = Arc::new(Semaphore::new(jobs));
+    let large_semaphore = Arc::new(Semaphore::new(1));
     let mut futures = vec![];
+    let frozen = args.frozen;
 
-    for tool in tools {
-        if let Some(version) = versions.remove(&tool.id) {
-            let proto_clone = proto.clone();
+    for ((tool, version, pb), is_large) in schedule_installs(estimated) {
+        let proto_clone = proto.clone();
+        let semaphore = Arc::clone(&semaphore);
+        let large_semaphore = Arc::clone(&large_semaphore);
+
+        let name = tool.get_name().to_owned();
+
+        futures.push((
+            name.clone(),
+            tokio::spawn(async move {
+                let _large_permit = if is_large {
+                    Some(large_semaphore.acquire_owned().await.into_diagnostic()?)
+                } else {
+                    None
+                };
+                let _permit = semaphore.acquire_owned().await.into_diagnostic()?;
 
-            futures.push(tokio::spawn(async move {
-                internal_install(
+                debug!("Starting install of {name}");
+
+                pb.set_message(format!(
+                    "Installing {} {}",
+                    name,
+                    color::hash(version.to_string())
+                ));
+
+                let result = internal_install(
                     &proto_clone,
                     InstallArgs {
+                        build: false,
                         canary: false,
+                        explain: false,
+                        frozen,
+                        from_file: None,
+                        include_prereleases: false,
                         id: tool.id.clone(),
                         pin: None,
                         passthrough: vec![],
@@ -73,18 +288,61 @@ pub async fn install_all(proto: ResourceRef<ProtoResource>) {
                     },
                     Some(tool),
                 )
-                .await
-            }));
-        }
+                .await;
+
+                debug!("Finished install of {name}");
+
+                match &result {
+                    Ok(tool) => pb.finish_with_message(format!(
+                        "{} {} {}",
+                        color::success("✔"),
+                        name,
+                        color::hash(tool.get_resolved_version().to_string())
+                    )),
+                    Err(_) => pb.finish_with_message(format!(
+                        "{} {} failed to install",
+                        color::failure("✘"),
+                        name
+                    )),
+                };
+
+                result
+            }),
+        ));
+    }
+
+    let mut failures = vec![];
+
+    for (name, future) in futures {
+        match future.await.into_diagnostic()? {
+            Ok(tool) => installed_tools.push(tool),
+            Err(error) if args.continue_on_error => failures.push((name, error)),
+            Err(error) => return Err(error),
+        };
     }
 
-    for future in futures {
-        future.await.into_diagnostic()??;
+    if !args.frozen {
+        update_lockfile(proto, &installed_tools)?;
     }
 
     enable_progress_bars();
 
-    pb.finish_and_clear();
+    if !failures.is_empty() {
+        eprintln!(
+            "Installed {} of {} tools, the following failed:",
+            installed_tools.len(),
+            installed_tools.len() + failures.len()
+        );
+
+        for (name, error) in &failures {
+            eprintln!("  {} {name}: {error}", color::failure("✘"));
+        }
+
+        return Err(ProtoCliError::InstallAllFailed {
+            count: failures.len(),
+        }
+        .into());
+    }
 
     info!("Successfully installed tools");
 
@@ -101,3 +359,53 @@ pub async fn install_all(proto: ResourceRef<ProtoResource>) {
         .await?;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn schedules_smaller_downloads_first() {
+        let scheduled = schedule_installs(vec![
+            ("large", Some(LARGE_DOWNLOAD_SIZE * 2)),
+            ("medium", Some(50 * 1024 * 1024)),
+            ("small", Some(1024)),
+        ]);
+
+        assert_eq!(
+            scheduled.into_iter().map(|(id, _)| id).collect::<Vec<_>>(),
+            vec!["small", "medium", "large"]
+        );
+    }
+
+    #[test]
+    fn schedules_unknown_sizes_first() {
+        let scheduled = schedule_installs(vec![("known", Some(1024)), ("unknown", None)]);
+
+        assert_eq!(scheduled, vec![("unknown", false), ("known", false)]);
+    }
+
+    #[test]
+    fn marks_large_downloads() {
+        let scheduled = schedule_installs(vec![
+            ("below", Some(LARGE_DOWNLOAD_SIZE - 1)),
+            ("exact", Some(LARGE_DOWNLOAD_SIZE)),
+            ("above", Some(LARGE_DOWNLOAD_SIZE + 1)),
+        ]);
+
+        assert_eq!(
+            scheduled,
+            vec![("below", false), ("exact", true), ("above", true)]
+        );
+    }
+
+    #[test]
+    fn keeps_order_of_equal_sizes() {
+        let scheduled = schedule_installs(vec![("a", Some(1)), ("b", Some(1)), ("c", Some(1))]);
+
+        assert_eq!(
+            scheduled.into_iter().map(|(id, _)| id).collect::<Vec<_>>(),
+            vec!["a", "b", "c"]
+        );
+    }
+}
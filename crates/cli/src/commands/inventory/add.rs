@@ -0,0 +1,44 @@
+use crate::helpers::ProtoResource;
+use clap::Args;
+use proto_core::{Id, Version, VersionSpec};
+use starbase::system;
+use starbase_styles::color;
+use std::path::PathBuf;
+use tracing::info;
+
+#[derive(Args, Clone, Debug)]
+pub struct AddInventoryArgs {
+    #[arg(required = true, help = "ID of tool")]
+    id: Id,
+
+    #[arg(required = true, help = "Fully-qualified version of the installation")]
+    semver: Version,
+
+    #[arg(
+        long,
+        required = true,
+        help = "Path to the external installation directory"
+    )]
+    path: PathBuf,
+}
+
+#[system]
+pub async fn add(args: ArgsRef<AddInventoryArgs>, proto: ResourceRef<ProtoResource>) {
+    let mut tool = proto.load_tool(&args.id).await?;
+
+    let install_dir = if args.path.is_absolute() {
+        args.path.clone()
+    } else {
+        proto.env.cwd.join(&args.path)
+    };
+
+    tool.add_unmanaged(VersionSpec::Version(args.semver.clone()), install_dir)
+        .await?;
+
+    info!(
+        "Added {} {} to the inventory as an unmanaged installation ({})",
+        tool.get_name(),
+        color::hash(args.semver.to_string()),
+        color::path(tool.get_tool_dir()),
+    );
+}
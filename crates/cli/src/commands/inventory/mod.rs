@@ -0,0 +1,3 @@
+mod add;
+
+pub use add::*;
@@ -12,6 +12,9 @@ pub struct ListRemoteArgs {
 
     #[arg(long, help = "Include remote aliases in the output")]
     aliases: bool,
+
+    #[arg(long, help = "Include prerelease versions in the output")]
+    include_prereleases: bool,
 }
 
 #[system]
@@ -19,13 +22,22 @@ pub async fn list_remote(args: ArgsRef<ListRemoteArgs>, proto: ResourceRef<Proto
     let mut tool = proto.load_tool(&args.id).await?;
     tool.disable_caching();
 
+    if args.include_prereleases {
+        tool.enable_prereleases();
+    }
+
     debug!("Loading versions");
 
     let resolver = tool
         .load_version_resolver(&UnresolvedVersionSpec::default())
         .await?;
+    let include_prereleases = resolver.include_prereleases;
     let mut versions = resolver.versions;
 
+    if !include_prereleases {
+        versions.retain(|version| version.pre.is_empty());
+    }
+
     if versions.is_empty() {
         eprintln!("No versions available");
         process::exit(1);
@@ -0,0 +1,100 @@
+use crate::error::ProtoCliError;
+use crate::helpers::ProtoResource;
+use proto_core::{
+    ProtoConfig, ToolVersionsFile, UnresolvedVersionSpec, PROTO_CONFIG_NAME, TOOL_VERSIONS_NAME,
+};
+use starbase::SystemResult;
+use starbase_styles::color;
+use tracing::{info, warn};
+
+/// Convert the `.tool-versions` file in the current directory into
+/// `.prototools` pins. Tools that are already pinned are kept as-is.
+pub async fn migrate(proto: &ProtoResource) -> SystemResult {
+    let settings = &proto.env.load_config()?.settings.tool_versions;
+
+    let Some(file) = ToolVersionsFile::load_from(&proto.env.cwd)? else {
+        return Err(ProtoCliError::MissingToolVersionsFile {
+            path: proto.env.cwd.join(TOOL_VERSIONS_NAME),
+        }
+        .into());
+    };
+
+    let mut migrated = vec![];
+
+    ProtoConfig::update(&proto.env.cwd, |config| {
+        for (name, versions) in &file.tools {
+            let Some(id) = settings.get_tool_id(name) else {
+                warn!("Unable to migrate {}, invalid tool name", color::id(name));
+
+                continue;
+            };
+
+            if config
+                .versions
+                .as_ref()
+                .is_some_and(|versions| versions.contains_key(&id))
+            {
+                warn!(
+                    "Skipping {}, as it's already pinned in {}",
+                    color::id(&id),
+                    PROTO_CONFIG_NAME
+                );
+
+                continue;
+            }
+
+            // asdf specific versions (system, ref:, path:) can't be resolved by proto
+            let candidates = versions
+                .iter()
+                .filter(|version| *version != "system" && !version.contains(':'))
+                .filter_map(|version| UnresolvedVersionSpec::parse(version).ok())
+                .collect::<Vec<_>>();
+
+            if candidates.is_empty() {
+                warn!(
+                    "Unable to migrate {}, no supported versions in {}",
+                    color::id(name),
+                    versions.join(" ")
+                );
+
+                continue;
+            }
+
+            config
+                .versions
+                .get_or_insert(Default::default())
+                .insert(id.clone(), candidates[0].clone());
+
+            if candidates.len() > 1 {
+                config
+                    .fallback_versions
+                    .get_or_insert(Default::default())
+                    .insert(id.clone(), candidates.clone());
+            }
+
+            migrated.push((id, candidates));
+        }
+    })?;
+
+    for (id, candidates) in migrated {
+        info!(
+            "Pinned {} to {}",
+            color::id(&id),
+            color::hash(
+                candidates
+                    .iter()
+                    .map(|candidate| candidate.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )
+        );
+    }
+
+    info!(
+        "Migrated {} to {}, which can be removed once asdf is no longer used",
+        color::file(TOOL_VERSIONS_NAME),
+        color::file(PROTO_CONFIG_NAME)
+    );
+
+    Ok(())
+}
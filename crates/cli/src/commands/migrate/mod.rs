@@ -1,4 +1,4 @@
-#![allow(unreachable_code)]
+mod asdf;
 
 use crate::error::ProtoCliError;
 use crate::helpers::ProtoResource;
@@ -12,18 +12,16 @@ pub struct MigrateArgs {
 }
 
 #[system]
-pub async fn migrate(args: ArgsRef<MigrateArgs>, _proto: ResourceRef<ProtoResource>) {
-    // match args.operation.as_str() {
-    //     unknown => {
-    //         return Err(ProtoCliError::UnknownMigration {
-    //             op: unknown.to_owned(),
-    //         }
-    //         .into());
-    //     }
-    // }
-
-    return Err(ProtoCliError::UnknownMigration {
-        op: args.operation.to_owned(),
+pub async fn migrate(args: ArgsRef<MigrateArgs>, proto: ResourceRef<ProtoResource>) {
+    match args.operation.as_str() {
+        "asdf" => {
+            asdf::migrate(proto).await?;
+        }
+        unknown => {
+            return Err(ProtoCliError::UnknownMigration {
+                op: unknown.to_owned(),
+            }
+            .into());
+        }
     }
-    .into());
 }
@@ -1,37 +1,76 @@
+mod activate;
 mod alias;
 mod bin;
+pub mod cache;
 mod clean;
+mod complete;
 mod completions;
 pub mod debug;
+mod doctor;
+mod env;
+mod exec_env;
+pub mod hook;
+mod init;
 mod install;
 mod install_all;
+pub mod inventory;
 mod list;
 mod list_remote;
 mod migrate;
 mod outdated;
+mod package;
 mod pin;
 pub mod plugin;
+pub mod query;
 mod regen;
+mod report;
 mod run;
+mod run_matrix;
 mod setup;
+mod stats;
+mod status;
+mod teardown;
+pub mod telemetry;
+mod try_tools;
 mod unalias;
 mod uninstall;
 mod upgrade;
+mod validate;
+mod verify;
+mod warnings;
+mod which;
 
+pub use activate::*;
 pub use alias::*;
 pub use bin::*;
 pub use clean::*;
+pub use complete::*;
 pub use completions::*;
+pub use doctor::*;
+pub use env::*;
+pub use exec_env::*;
+pub use init::*;
 pub use install::*;
 pub use install_all::*;
 pub use list::*;
 pub use list_remote::*;
 pub use migrate::*;
 pub use outdated::*;
+pub use package::*;
 pub use pin::*;
 pub use regen::*;
+pub use report::*;
 pub use run::*;
+pub use run_matrix::*;
 pub use setup::*;
+pub use stats::*;
+pub use status::*;
+pub use teardown::*;
+pub use try_tools::*;
 pub use unalias::*;
 pub use uninstall::*;
 pub use upgrade::*;
+pub use validate::*;
+pub use verify::*;
+pub use warnings::*;
+pub use which::*;
@@ -1,20 +1,48 @@
 use crate::error::ProtoCliError;
-use crate::helpers::ProtoResource;
+use crate::helpers::{fetch_latest_version, ProtoResource};
+use crate::template::render_template;
 use clap::Args;
 use miette::IntoDiagnostic;
-use proto_core::{ProtoConfig, ProtoError, UnresolvedVersionSpec, VersionSpec};
+use once_cell::sync::Lazy;
+use proto_core::{
+    locate_tool, Id, PluginLocator, ProtoConfig, ProtoError, UnresolvedVersionSpec, Version,
+    VersionSpec,
+};
+use regex::Regex;
 use rustc_hash::FxHashMap;
 use serde::Serialize;
 use starbase::system;
 use starbase_styles::color::{self, OwoStyle};
 use starbase_utils::json;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::process;
 use tracing::{debug, info};
 
 #[derive(Args, Clone, Debug)]
 pub struct OutdatedArgs {
+    #[arg(
+        long,
+        conflicts_with_all = ["include_plugins", "json"],
+        help = "Print each tool on its own line using a template, for example \"{{id}} {{newer_version}}\""
+    )]
+    format: Option<String>,
+
+    #[arg(
+        long,
+        help = "Exit with a non-zero code when newer versions, plugins, or proto are available"
+    )]
+    exit_code: bool,
+
     #[arg(long, help = "Include versions in global .prototools")]
     include_global: bool,
 
+    #[arg(long, help = "Include plugin and proto updates in the report")]
+    include_plugins: bool,
+
+    #[arg(long, help = "Include prerelease versions when checking for updates")]
+    include_prereleases: bool,
+
     #[arg(long, help = "Print the list in JSON format")]
     json: bool,
 
@@ -27,7 +55,10 @@ pub struct OutdatedArgs {
     #[arg(long, help = "Only check versions in local .prototools")]
     only_local: bool,
 
-    #[arg(long, help = "Update and write the versions to the local .prototools")]
+    #[arg(
+        long,
+        help = "Update and write the versions to the .prototools they were pinned in"
+    )]
     update: bool,
 }
 
@@ -37,6 +68,86 @@ pub struct OutdatedItem {
     version_config: UnresolvedVersionSpec,
     current_version: VersionSpec,
     newer_version: VersionSpec,
+    latest_version: VersionSpec,
+    config_path: Option<PathBuf>,
+}
+
+#[derive(Serialize)]
+pub struct OutdatedPluginItem {
+    locator: PluginLocator,
+    current_tag: String,
+    newer_tag: String,
+}
+
+#[derive(Serialize)]
+pub struct OutdatedProtoItem {
+    current_version: Version,
+    newer_version: Version,
+}
+
+#[derive(Serialize)]
+pub struct OutdatedReport {
+    tools: FxHashMap<Id, OutdatedItem>,
+    plugins: FxHashMap<Id, OutdatedPluginItem>,
+    proto: Option<OutdatedProtoItem>,
+}
+
+static GITHUB_RELEASE_URL: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^https://github\.com/([^/]+/[^/]+)/releases/download/([^/]+)/").unwrap()
+});
+
+// Only GitHub releases with an explicit tag can be checked,
+// as a missing tag will always use the latest release
+fn extract_github_release(locator: &PluginLocator) -> Option<(String, String)> {
+    match locator {
+        PluginLocator::GitHub(github) => github
+            .tag
+            .as_ref()
+            .map(|tag| (github.repo_slug.clone(), tag.to_owned())),
+        PluginLocator::SourceUrl { url } => GITHUB_RELEASE_URL
+            .captures(url)
+            .map(|caps| (caps[1].to_owned(), caps[2].to_owned())),
+        PluginLocator::SourceFile { .. } | PluginLocator::Asdf { .. } => None,
+    }
+}
+
+fn print_table(rows: &[[String; 5]]) {
+    let header = ["Tool", "Current", "Newer", "Latest", "Config"].map(String::from);
+    let mut widths = [0; 5];
+
+    for row in std::iter::once(&header).chain(rows) {
+        for (i, cell) in row.iter().enumerate() {
+            widths[i] = widths[i].max(cell.len());
+        }
+    }
+
+    let format_row = |row: &[String; 5]| {
+        row.iter()
+            .enumerate()
+            .map(|(i, cell)| format!("{cell:width$}", width = widths[i]))
+            .collect::<Vec<_>>()
+            .join("  ")
+            .trim_end()
+            .to_owned()
+    };
+
+    println!("{}", OwoStyle::new().bold().style(format_row(&header)));
+
+    for row in rows {
+        let line = format_row(row);
+        let (id, rest) = line.split_at(widths[0]);
+
+        println!("{}{}", color::id(id), rest);
+    }
+}
+
+fn print_line(id: &str, comments: &[String]) {
+    println!(
+        "{} {} {}",
+        OwoStyle::new().bold().style(color::id(id)),
+        color::muted("-"),
+        comments.join(&color::muted_light(", "))
+    );
 }
 
 #[system]
@@ -55,21 +166,28 @@ pub async fn outdated(args: ArgsRef<OutdatedArgs>, proto: ResourceRef<ProtoResou
         return Err(ProtoCliError::NoConfiguredTools.into());
     }
 
-    if !args.json {
+    let structured = args.json || args.format.is_some();
+
+    if !structured {
         info!("Checking for newer versions...");
     }
 
+    let mut has_updates = false;
     let mut items = FxHashMap::default();
-    let mut tool_versions = FxHashMap::default();
+    let mut rows = vec![];
+    let mut tool_versions: BTreeMap<PathBuf, BTreeMap<Id, UnresolvedVersionSpec>> = BTreeMap::new();
     let initial_version = UnresolvedVersionSpec::default(); // latest
 
     for (tool_id, config_version) in &config.versions {
         let mut tool = proto.load_tool(tool_id).await?;
         tool.disable_caching();
 
+        if args.include_prereleases {
+            tool.enable_prereleases();
+        }
+
         debug!("Checking {}", tool.get_name());
 
-        let mut comments = vec![];
         let versions = tool.load_version_resolver(&initial_version).await?;
         let handle_error = || ProtoError::VersionResolveFailed {
             tool: tool.get_name().to_owned(),
@@ -80,90 +198,200 @@ pub async fn outdated(args: ArgsRef<OutdatedArgs>, proto: ResourceRef<ProtoResou
         let check_latest =
             args.latest || matches!(config_version, UnresolvedVersionSpec::Version(_));
 
-        comments.push(format!(
-            "current version {} {}",
-            color::symbol(current_version.to_string()),
-            color::muted_light(format!("(via {})", config_version))
-        ));
-
-        let newer_version = versions
-            .resolve_without_manifest(if check_latest {
-                &initial_version // latest alias
-            } else {
-                config_version // req, range, etc
-            })
+        // Newest version that matches the configured requirement or range
+        let matching_version = versions
+            .resolve_without_manifest(config_version)
             .ok_or_else(handle_error)?;
 
-        let mut is_outdated = false;
-        let mut is_on_latest = false;
-
-        if let (VersionSpec::Version(a), VersionSpec::Version(b)) =
-            (&current_version, &newer_version)
-        {
-            #[allow(clippy::comparison_chain)]
-            if b > a {
-                is_outdated = true;
-            } else if b == a {
-                is_on_latest = true;
-            }
-        }
+        // Newest version overall
+        let latest_version = versions
+            .resolve_without_manifest(&initial_version)
+            .ok_or_else(handle_error)?;
 
-        if is_on_latest {
-            comments.push(if check_latest {
-                "on the latest version".into()
-            } else {
-                "on the newest version".into()
-            });
+        let newer_version = if check_latest {
+            latest_version.clone()
         } else {
-            comments.push(format!(
-                "{} {}",
-                if check_latest {
-                    "latest version"
-                } else {
-                    "newer version"
-                },
-                color::symbol(newer_version.to_string())
-            ));
+            matching_version.clone()
+        };
 
-            if is_outdated {
-                comments.push(color::success("update available!"));
-            }
+        if newer_version != current_version {
+            has_updates = true;
         }
 
+        // The closest config that pinned the version, so it can be updated in place
+        let config_path = manager
+            .files
+            .iter()
+            .find(|file| {
+                file.config
+                    .versions
+                    .as_ref()
+                    .is_some_and(|versions| versions.contains_key(tool_id))
+            })
+            .map(|file| file.path.clone());
+
         if args.update {
-            tool_versions.insert(tool.id.clone(), newer_version.to_unresolved_spec());
+            if let Some(dir) = config_path.as_ref().and_then(|path| path.parent()) {
+                tool_versions
+                    .entry(dir.to_path_buf())
+                    .or_default()
+                    .insert(tool.id.clone(), newer_version.to_unresolved_spec());
+            }
         }
 
-        if args.json {
+        if structured {
             items.insert(
-                tool.id,
+                tool.id.clone(),
                 OutdatedItem {
                     is_latest: check_latest,
                     version_config: config_version.to_owned(),
                     current_version,
                     newer_version,
+                    latest_version,
+                    config_path,
                 },
             );
         } else {
-            println!(
-                "{} {} {}",
-                OwoStyle::new().bold().style(color::id(&tool.id)),
-                color::muted("-"),
-                comments.join(&color::muted_light(", "))
-            );
+            let format_version = |version: &VersionSpec| {
+                if version == &current_version {
+                    "-".to_owned()
+                } else {
+                    version.to_string()
+                }
+            };
+
+            rows.push([
+                tool.id.to_string(),
+                current_version.to_string(),
+                format_version(&matching_version),
+                format_version(&latest_version),
+                config_version.to_string(),
+            ]);
         }
     }
 
+    if !rows.is_empty() {
+        print_table(&rows);
+    }
+
+    let mut plugin_items = FxHashMap::default();
+    let mut proto_item = None;
+
+    if args.include_plugins {
+        if !args.json {
+            info!("Checking for newer plugins...");
+        }
+
+        let loader = proto.env.get_plugin_loader()?;
+
+        for tool_id in config.versions.keys() {
+            let locator = locate_tool(tool_id, &proto.env)?;
+
+            let Some((repo_slug, current_tag)) = extract_github_release(&locator) else {
+                debug!(
+                    plugin = locator.to_string(),
+                    "Unable to check plugin for updates, not a GitHub release"
+                );
+
+                continue;
+            };
+
+            debug!("Checking {} plugin", tool_id);
+
+            let newer_tag = loader.fetch_latest_github_tag(&repo_slug).await?;
+            let mut comments = vec![format!(
+                "current plugin {}",
+                color::symbol(&current_tag)
+            )];
+
+            if newer_tag == current_tag {
+                comments.push("on the latest plugin".into());
+            } else {
+                has_updates = true;
+                comments.push(format!("latest plugin {}", color::symbol(&newer_tag)));
+                comments.push(color::success("update available!"));
+            }
+
+            if args.json {
+                plugin_items.insert(
+                    tool_id.to_owned(),
+                    OutdatedPluginItem {
+                        locator,
+                        current_tag,
+                        newer_tag,
+                    },
+                );
+            } else {
+                print_line(&format!("{tool_id} plugin"), &comments);
+            }
+        }
+
+        debug!("Checking proto");
+
+        let current_version = Version::parse(env!("CARGO_PKG_VERSION")).into_diagnostic()?;
+        let newer_version =
+            Version::parse(&fetch_latest_version(&proto.env).await?).into_diagnostic()?;
+        let mut comments = vec![format!(
+            "current version {}",
+            color::symbol(current_version.to_string())
+        )];
+
+        if newer_version > current_version {
+            has_updates = true;
+            comments.push(format!(
+                "latest version {}",
+                color::symbol(newer_version.to_string())
+            ));
+            comments.push(color::success("update available!"));
+        } else {
+            comments.push("on the latest version".into());
+        }
+
+        if !args.json {
+            print_line("proto", &comments);
+        }
+
+        proto_item = Some(OutdatedProtoItem {
+            current_version,
+            newer_version,
+        });
+    }
+
     if args.update {
-        ProtoConfig::update(&proto.env.cwd, |config| {
-            config
-                .versions
-                .get_or_insert(Default::default())
-                .extend(tool_versions);
-        })?;
+        for (dir, versions) in tool_versions {
+            let path = ProtoConfig::update(dir, |config| {
+                config
+                    .versions
+                    .get_or_insert(Default::default())
+                    .extend(versions);
+            })?;
+
+            debug!(config = ?path, "Updated pinned versions");
+        }
+    }
+
+    if let Some(format) = &args.format {
+        let mut items = items.into_iter().collect::<Vec<_>>();
+        items.sort_by(|a, d| a.0.cmp(&d.0));
+
+        for (id, item) in &items {
+            println!("{}", render_template(format, id.as_str(), item)?);
+        }
+    } else if args.json {
+        if args.include_plugins {
+            let report = OutdatedReport {
+                tools: items,
+                plugins: plugin_items,
+                proto: proto_item,
+            };
+
+            println!("{}", json::to_string_pretty(&report).into_diagnostic()?);
+        } else {
+            println!("{}", json::to_string_pretty(&items).into_diagnostic()?);
+        }
     }
 
-    if args.json {
-        println!("{}", json::to_string_pretty(&items).into_diagnostic()?);
+    if args.exit_code && has_updates {
+        process::exit(1);
     }
 }
@@ -0,0 +1,70 @@
+use crate::error::ProtoCliError;
+use crate::helpers::ProtoResource;
+use clap::Args;
+use proto_core::{create_package, Id, UnresolvedVersionSpec};
+use starbase::system;
+use starbase_styles::color;
+use std::env;
+use std::path::PathBuf;
+use tracing::info;
+
+#[derive(Args, Clone, Debug)]
+pub struct PackageArgs {
+    #[arg(required = true, help = "ID of tool")]
+    id: Id,
+
+    #[arg(required = true, help = "Installed version of tool")]
+    spec: UnresolvedVersionSpec,
+
+    #[arg(
+        long,
+        help = "Path to write the package to, the archive format is derived from the extension"
+    )]
+    out: Option<PathBuf>,
+}
+
+#[system]
+pub async fn package(args: ArgsRef<PackageArgs>, proto: ResourceRef<ProtoResource>) {
+    let mut tool = proto.load_tool(&args.id).await?;
+
+    tool.resolve_version(&args.spec, true).await?;
+
+    let version = tool.get_resolved_version();
+
+    if !tool.is_installed() {
+        return Err(ProtoCliError::PackageVersionNotInstalled {
+            tool: tool.get_name().to_owned(),
+            version: version.to_string(),
+        }
+        .into());
+    }
+
+    let out_file = proto.env.cwd.join(args.out.clone().unwrap_or_else(|| {
+        PathBuf::from(format!(
+            "{}-{}-{}-{}.tar.gz",
+            tool.id,
+            version,
+            env::consts::OS,
+            env::consts::ARCH
+        ))
+    }));
+
+    let receipt = create_package(tool.id.as_str(), &version, &tool.get_tool_dir(), &out_file)?;
+
+    info!(
+        "Packaged {} {} ({} files) to {}",
+        tool.get_name(),
+        version,
+        receipt.files.len(),
+        color::path(&out_file)
+    );
+    info!(
+        "Install it on another {} machine with {}",
+        receipt.get_platform(),
+        color::shell(format!(
+            "proto install {} --from-file {}",
+            tool.id,
+            out_file.display()
+        ))
+    );
+}
@@ -1,18 +1,35 @@
-use crate::helpers::ProtoResource;
+use crate::commands::run::RunTarget;
+use crate::error::ProtoCliError;
+use crate::helpers::{read_batch_input, ProtoResource};
 use clap::Args;
-use proto_core::{Id, ProtoConfig, Tool, UnresolvedVersionSpec};
+use proto_core::{detect_version, Id, ProtoConfig, Tool, UnresolvedVersionSpec};
 use starbase::{system, SystemResult};
 use starbase_styles::color;
 use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::str::FromStr;
 use tracing::{debug, info};
 
 #[derive(Args, Clone, Debug)]
 pub struct PinArgs {
-    #[arg(required = true, help = "ID of tool")]
-    pub id: Id,
+    #[arg(
+        required_unless_present_any = ["file", "from_detected", "stdin"],
+        help = "ID of tools with a version (node@20 pnpm@8), or an ID followed by a version or alias"
+    )]
+    pub targets: Vec<String>,
 
-    #[arg(required = true, help = "Version or alias of tool")]
-    pub spec: UnresolvedVersionSpec,
+    #[arg(
+        long,
+        conflicts_with_all = ["targets", "from_detected", "stdin"],
+        help = "Read tools to pin from a file, with an ID and version (node 20) on each line"
+    )]
+    pub file: Option<PathBuf>,
+
+    #[arg(
+        long,
+        help = "Pin the currently detected or installed version of each tool, or of all tools when no IDs are provided"
+    )]
+    pub from_detected: bool,
 
     #[arg(
         long,
@@ -22,6 +39,95 @@ pub struct PinArgs {
 
     #[arg(long, help = "Resolve the version before pinning")]
     pub resolve: bool,
+
+    #[arg(
+        long,
+        conflicts_with_all = ["targets", "from_detected"],
+        help = "Read tools to pin from stdin, with an ID and version (node 20) on each line"
+    )]
+    pub stdin: bool,
+
+    #[arg(
+        long,
+        conflicts_with = "global",
+        help = "Also update existing ecosystem version files (.nvmrc, etc)"
+    )]
+    pub sync_ecosystem: bool,
+}
+
+/// Parse the targets into tool IDs and versions, while supporting the
+/// original `<id> <version>` form. Versions are optional when detecting.
+fn parse_targets(
+    targets: &[String],
+    from_detected: bool,
+) -> Result<Vec<(Id, Option<UnresolvedVersionSpec>)>, ProtoCliError> {
+    if let [id, spec] = targets {
+        if !from_detected && !id.contains('@') && !spec.contains('@') {
+            let handle_error = || ProtoCliError::InvalidRunTarget {
+                target: format!("{id} {spec}"),
+            };
+
+            return Ok(vec![(
+                Id::new(id).map_err(|_| handle_error())?,
+                Some(UnresolvedVersionSpec::parse(spec).map_err(|_| handle_error())?),
+            )]);
+        }
+    }
+
+    let mut parsed = vec![];
+
+    for target in targets {
+        let target = RunTarget::from_str(target)?;
+
+        if target.spec.is_none() && !from_detected {
+            return Err(ProtoCliError::MissingPinVersion {
+                id: target.id.to_string(),
+            });
+        }
+
+        parsed.push((target.id, target.spec));
+    }
+
+    Ok(parsed)
+}
+
+/// Parse lines of batch input into tool IDs and versions. Each line supports
+/// the same forms as the command line arguments.
+fn parse_batch_targets(
+    lines: Vec<(usize, String)>,
+) -> Result<Vec<(Id, Option<UnresolvedVersionSpec>)>, ProtoCliError> {
+    let mut parsed = vec![];
+
+    for (line, content) in lines {
+        let targets = content
+            .split_whitespace()
+            .map(|part| part.to_owned())
+            .collect::<Vec<_>>();
+
+        parsed.extend(parse_targets(&targets, false).map_err(|_| {
+            ProtoCliError::InvalidBatchLine {
+                line,
+                content,
+                expected: "a tool ID and version, for example node 20 or node@20".into(),
+            }
+        })?);
+    }
+
+    Ok(parsed)
+}
+
+/// Detect the version used in the current directory, and fallback
+/// to the highest installed version.
+async fn detect_pin_version(tool: &Tool) -> Option<UnresolvedVersionSpec> {
+    if let Ok(spec) = detect_version(tool, None).await {
+        return Some(spec);
+    }
+
+    tool.manifest
+        .installed_versions
+        .iter()
+        .max()
+        .map(|version| version.to_unresolved_spec())
 }
 
 pub async fn internal_pin(
@@ -53,20 +159,80 @@ pub async fn internal_pin(
 
 #[system]
 pub async fn pin(args: ArgsRef<PinArgs>, proto: ResourceRef<ProtoResource>) -> SystemResult {
-    let mut tool = proto.load_tool(&args.id).await?;
-
-    let spec = if args.resolve {
-        tool.resolve_version(&args.spec, false).await?;
-        tool.get_resolved_version().to_unresolved_spec()
+    let parsed = if args.stdin || args.file.is_some() {
+        parse_batch_targets(read_batch_input(args.file.as_deref())?)?
     } else {
-        args.spec.clone()
+        parse_targets(&args.targets, args.from_detected)?
     };
 
-    internal_pin(&mut tool, &spec, args.global, false).await?;
+    let mut targets = vec![];
 
-    info!(
-        "Set the {} version to {}",
-        tool.get_name(),
-        color::hash(args.spec.to_string())
-    );
+    for (id, spec) in parsed {
+        targets.push((proto.load_tool(&id).await?, spec));
+    }
+
+    // Detect all tools when none were provided
+    if targets.is_empty() && args.from_detected {
+        for tool in proto.load_tools().await? {
+            targets.push((tool, None));
+        }
+    }
+
+    let mut pins = vec![];
+
+    for (mut tool, spec) in targets {
+        let spec = match spec {
+            Some(spec) => spec,
+            None => match detect_pin_version(&tool).await {
+                Some(spec) => spec,
+                None => {
+                    debug!("No version detected for {}, skipping", tool.get_name());
+
+                    continue;
+                }
+            },
+        };
+
+        let pinned_spec = if args.resolve {
+            tool.resolve_version(&spec, false).await?;
+            tool.get_resolved_version().to_unresolved_spec()
+        } else {
+            spec.clone()
+        };
+
+        pins.push((tool, spec, pinned_spec));
+    }
+
+    if pins.is_empty() {
+        return Ok(());
+    }
+
+    // Write all versions in a single transaction, so that a failure
+    // while resolving doesn't leave the config partially updated
+    let path = ProtoConfig::update(proto.env.get_config_dir(args.global), |config| {
+        let versions = config.versions.get_or_insert(BTreeMap::default());
+
+        for (tool, _, pinned_spec) in &pins {
+            versions.insert(tool.id.clone(), pinned_spec.clone());
+        }
+    })?;
+
+    debug!(config = ?path, "Pinned {} versions", pins.len());
+
+    for (tool, spec, pinned_spec) in pins {
+        info!(
+            "Set the {} version to {}",
+            tool.get_name(),
+            color::hash(spec.to_string())
+        );
+
+        if args.sync_ecosystem {
+            for file in tool
+                .sync_version_files(&proto.env.cwd, &pinned_spec)
+                .await?
+            {
+                info!("Updated {} to match", color::path(file));
+            }
+        }
+    }
 }
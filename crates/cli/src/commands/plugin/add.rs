@@ -1,3 +1,5 @@
+use super::search::load_registry_indexes;
+use crate::error::ProtoCliError;
 use crate::helpers::ProtoResource;
 use clap::Args;
 use proto_core::{Id, PluginLocator, ProtoConfig};
@@ -10,23 +12,84 @@ pub struct AddPluginArgs {
     #[arg(required = true, help = "ID of plugin")]
     id: Id,
 
-    #[arg(required = true, help = "Locator string to find and load the plugin")]
-    plugin: PluginLocator,
+    #[arg(
+        required_unless_present = "from_registry",
+        help = "Locator string to find and load the plugin"
+    )]
+    plugin: Option<PluginLocator>,
+
+    #[arg(
+        long,
+        help = "Find the plugin's locator in the configured registries",
+        conflicts_with = "plugin"
+    )]
+    from_registry: bool,
 
     #[arg(
         long,
         help = "Add to the global .prototools instead of local .prototools"
     )]
     global: bool,
+
+    #[arg(
+        long,
+        help = "Pin a GitHub locator to a version constraint of its latest release (github:org/repo@^1.2.3)"
+    )]
+    pin: bool,
 }
 
 #[system]
 pub async fn add(args: ArgsRef<AddPluginArgs>, proto: ResourceRef<ProtoResource>) {
+    let locator = match &args.plugin {
+        Some(locator) => locator.to_owned(),
+        None => {
+            let indexes = load_registry_indexes(proto).await?;
+
+            let Some((registry, plugin)) = indexes.iter().find_map(|(registry, index)| {
+                index.find(&args.id).map(|plugin| (registry, plugin))
+            }) else {
+                return Err(ProtoCliError::MissingRegistryPlugin {
+                    id: args.id.to_string(),
+                }
+                .into());
+            };
+
+            info!(
+                "Found plugin {} in registry {}",
+                color::id(&args.id),
+                color::label(&registry.name)
+            );
+
+            plugin.locator.clone()
+        }
+    };
+
+    // --pin
+    let locator = if args.pin {
+        if !matches!(locator, PluginLocator::GitHub(_)) {
+            return Err(ProtoCliError::UnpinnablePluginLocator {
+                locator: locator.to_string(),
+            }
+            .into());
+        }
+
+        let pinned = proto.env.get_plugin_loader()?.pin_locator(&locator).await?;
+
+        info!(
+            "Pinned plugin locator to {}",
+            color::file(pinned.to_string())
+        );
+
+        pinned
+    } else {
+        locator
+    };
+
     let config_path = ProtoConfig::update(proto.env.get_config_dir(args.global), |config| {
         config
             .plugins
             .get_or_insert(Default::default())
-            .insert(args.id.clone(), args.plugin.clone());
+            .insert(args.id.clone(), locator);
     })?;
 
     info!(
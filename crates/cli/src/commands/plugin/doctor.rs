@@ -0,0 +1,343 @@
+use crate::helpers::ProtoResource;
+use crate::printer::Printer;
+use clap::Args;
+use miette::IntoDiagnostic;
+use proto_core::{
+    hash_file_contents, load_tool_from_locator, locate_tool, Id, Tool, UnresolvedVersionSpec,
+};
+use proto_pdk_api::{LoadVersionsInput, LoadVersionsOutput, ToolMetadataInput, ToolMetadataOutput};
+use serde::Serialize;
+use starbase::system;
+use starbase_styles::color;
+use starbase_utils::{fs, json};
+use std::process;
+use std::time::Instant;
+
+#[derive(Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PluginCheckStatus {
+    Pass,
+    Warn,
+    Fail,
+}
+
+#[derive(Serialize)]
+pub struct PluginCheck {
+    duration_ms: Option<u128>,
+    messages: Vec<String>,
+    name: String,
+    status: PluginCheckStatus,
+}
+
+impl PluginCheck {
+    fn new(name: &str, status: PluginCheckStatus, messages: Vec<String>) -> Self {
+        Self {
+            duration_ms: None,
+            messages,
+            name: name.to_owned(),
+            status,
+        }
+    }
+
+    fn timed(mut self, started: Instant) -> Self {
+        self.duration_ms = Some(started.elapsed().as_millis());
+        self
+    }
+}
+
+#[derive(Args, Clone, Debug)]
+pub struct DoctorPluginArgs {
+    #[arg(required = true, help = "ID of plugin")]
+    id: Id,
+
+    #[arg(long, help = "Print the diagnostics in JSON format")]
+    json: bool,
+}
+
+fn check_api(tool: &Tool) -> PluginCheck {
+    let has_func = |func: &str| tool.plugin.has_func(func);
+    let mut messages = vec![];
+    let mut status = PluginCheckStatus::Pass;
+
+    for func in ["register_tool", "load_versions"] {
+        if !has_func(func) {
+            messages.push(format!("missing required function {func}"));
+            status = PluginCheckStatus::Fail;
+        }
+    }
+
+    if !["download_prebuilt", "native_install", "build_instructions"]
+        .into_iter()
+        .any(has_func)
+    {
+        messages.push("no install method (download_prebuilt, native_install, etc)".into());
+        status = PluginCheckStatus::Fail;
+    }
+
+    if !has_func("locate_executables") {
+        messages.push("missing locate_executables, binaries and shims will not be created".into());
+
+        if status == PluginCheckStatus::Pass {
+            status = PluginCheckStatus::Warn;
+        }
+    }
+
+    if messages.is_empty() {
+        messages.push("all required functions are exported".into());
+    }
+
+    PluginCheck::new("API compatibility", status, messages)
+}
+
+fn check_permissions(tool: &Tool) -> PluginCheck {
+    let manifest = &tool.plugin.manifest;
+    let mut messages = vec![];
+
+    messages.push(format!(
+        "allowed hosts: {}",
+        match &manifest.allowed_hosts {
+            Some(hosts) if !hosts.is_empty() => hosts.join(", "),
+            _ => "none".into(),
+        }
+    ));
+
+    if let Some(paths) = &manifest.allowed_paths {
+        for (host, guest) in paths {
+            messages.push(format!(
+                "allowed path: {} -> {}",
+                host.display(),
+                guest.display()
+            ));
+        }
+    }
+
+    if let Some(timeout) = manifest.timeout_ms {
+        messages.push(format!("timeout: {}s", timeout / 1000));
+    }
+
+    if let Ok(permissions) = tool.get_permissions() {
+        let format_grant = |granted: bool| if granted { "allowed" } else { "denied" };
+
+        messages.push(format!("exec: {}", format_grant(permissions.exec)));
+        messages.push(format!(
+            "filesystem outside virtual paths: {}",
+            format_grant(permissions.filesystem)
+        ));
+    }
+
+    if tool.metadata.permissions.is_none() {
+        messages.push("permissions not declared by plugin, unrestricted unless overridden".into());
+    }
+
+    PluginCheck::new("Host permissions", PluginCheckStatus::Pass, messages)
+}
+
+#[system]
+pub async fn doctor(args: ArgsRef<DoctorPluginArgs>, proto: ResourceRef<ProtoResource>) {
+    let locator = locate_tool(&args.id, &proto.env)?;
+    let mut checks = vec![];
+    let mut name = args.id.to_string();
+
+    // Verify the cached plugin file is intact
+    let started = Instant::now();
+
+    match proto
+        .env
+        .get_plugin_loader()?
+        .load_plugin(&args.id, &locator)
+        .await
+    {
+        Ok(plugin_path) => {
+            let is_wasm = plugin_path.extension().is_some_and(|ext| ext == "wasm");
+            let bytes = fs::read_file_bytes(&plugin_path)?;
+            let mut messages = vec![
+                format!("file: {}", plugin_path.display()),
+                format!("size: {} bytes", bytes.len()),
+            ];
+
+            if let Ok(hash) = hash_file_contents(&plugin_path) {
+                messages.push(format!("sha256: {hash}"));
+            }
+
+            let status = if bytes.is_empty() || (is_wasm && !bytes.starts_with(b"\0asm")) {
+                messages.push("file is not a valid WASM module, it may be corrupt".into());
+                PluginCheckStatus::Fail
+            } else {
+                PluginCheckStatus::Pass
+            };
+
+            checks.push(PluginCheck::new("Plugin cache", status, messages).timed(started));
+        }
+        Err(error) => {
+            checks.push(
+                PluginCheck::new(
+                    "Plugin cache",
+                    PluginCheckStatus::Fail,
+                    vec![error.to_string()],
+                )
+                .timed(started),
+            );
+        }
+    };
+
+    // Instantiate the runtime, which also registers the tool
+    let tool = if checks
+        .iter()
+        .all(|check| check.status != PluginCheckStatus::Fail)
+    {
+        let started = Instant::now();
+
+        match load_tool_from_locator(&args.id, &proto.env, &locator).await {
+            Ok(tool) => {
+                checks.push(
+                    PluginCheck::new(
+                        "Runtime",
+                        PluginCheckStatus::Pass,
+                        vec!["instantiated WASM runtime".into()],
+                    )
+                    .timed(started),
+                );
+
+                Some(tool)
+            }
+            Err(error) => {
+                checks.push(
+                    PluginCheck::new("Runtime", PluginCheckStatus::Fail, vec![error.to_string()])
+                        .timed(started),
+                );
+
+                None
+            }
+        }
+    } else {
+        None
+    };
+
+    if let Some(tool) = &tool {
+        name = tool.get_name().to_owned();
+
+        // Dry run functions without caching, so that timings are accurate
+        let started = Instant::now();
+
+        checks.push(
+            match tool.plugin.call_func_with::<_, ToolMetadataOutput>(
+                "register_tool",
+                ToolMetadataInput {
+                    id: tool.id.to_string(),
+                },
+            ) {
+                Ok(metadata) => PluginCheck::new(
+                    "register_tool",
+                    PluginCheckStatus::Pass,
+                    vec![
+                        format!("name: {}", metadata.name),
+                        format!(
+                            "plugin version: {}",
+                            metadata.plugin_version.as_deref().unwrap_or("unknown")
+                        ),
+                    ],
+                ),
+                Err(error) => PluginCheck::new(
+                    "register_tool",
+                    PluginCheckStatus::Fail,
+                    vec![error.to_string()],
+                ),
+            }
+            .timed(started),
+        );
+
+        checks.push(check_api(tool));
+        checks.push(check_permissions(tool));
+
+        if tool.plugin.has_func("load_versions") {
+            let started = Instant::now();
+
+            checks.push(
+                match tool.plugin.call_func_with::<_, LoadVersionsOutput>(
+                    "load_versions",
+                    LoadVersionsInput {
+                        initial: UnresolvedVersionSpec::default(),
+                    },
+                ) {
+                    Ok(output) => PluginCheck::new(
+                        "load_versions",
+                        if output.versions.is_empty() {
+                            PluginCheckStatus::Warn
+                        } else {
+                            PluginCheckStatus::Pass
+                        },
+                        vec![
+                            format!("{} versions", output.versions.len()),
+                            format!("{} aliases", output.aliases.len()),
+                            format!(
+                                "latest: {}",
+                                output
+                                    .latest
+                                    .map(|version| version.to_string())
+                                    .unwrap_or_else(|| "unknown".into())
+                            ),
+                        ],
+                    ),
+                    Err(error) => PluginCheck::new(
+                        "load_versions",
+                        PluginCheckStatus::Fail,
+                        vec![error.to_string()],
+                    ),
+                }
+                .timed(started),
+            );
+        }
+    }
+
+    let failed = checks
+        .iter()
+        .any(|check| check.status == PluginCheckStatus::Fail);
+
+    if args.json {
+        println!("{}", json::to_string_pretty(&checks).into_diagnostic()?);
+    } else {
+        let mut printer = Printer::new();
+        printer.header(&args.id, name);
+
+        printer.named_section("Diagnostics", |p| {
+            for check in &checks {
+                p.entry_list(
+                    format!(
+                        "{} {}{}",
+                        match check.status {
+                            PluginCheckStatus::Pass => color::success("✔"),
+                            PluginCheckStatus::Warn => color::caution("!"),
+                            PluginCheckStatus::Fail => color::failure("✘"),
+                        },
+                        check.name,
+                        match check.duration_ms {
+                            Some(ms) => color::muted_light(format!(" ({ms}ms)")),
+                            None => String::new(),
+                        }
+                    ),
+                    check.messages.iter().map(color::muted_light),
+                    None,
+                );
+            }
+
+            Ok(())
+        })?;
+
+        if failed {
+            printer.line();
+            printer.entry(
+                "Suggestion",
+                format!(
+                    "Purge the plugin cache with {} and try again",
+                    color::shell("proto clean --purge-plugins")
+                ),
+            );
+        }
+
+        printer.flush();
+    }
+
+    if failed {
+        process::exit(1);
+    }
+}
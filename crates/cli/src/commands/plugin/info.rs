@@ -3,8 +3,8 @@ use crate::printer::{format_env_var, format_value, Printer};
 use clap::Args;
 use miette::IntoDiagnostic;
 use proto_core::{
-    detect_version, EnvVar, ExecutableLocation, Id, PluginLocator, ProtoToolConfig, ToolManifest,
-    UnresolvedVersionSpec,
+    detect_version, hash_file_contents, EnvVar, ExecutableLocation, Id, PluginLocator,
+    ProtoToolConfig, ToolManifest, UnresolvedVersionSpec,
 };
 use proto_pdk_api::ToolMetadataOutput;
 use serde::Serialize;
@@ -26,7 +26,10 @@ pub struct PluginInfo {
     metadata: ToolMetadataOutput,
     name: String,
     plugin: PluginLocator,
+    plugin_hash: Option<String>,
+    plugin_path: PathBuf,
     shims: Vec<ExecutableLocation>,
+    version_files: Vec<String>,
 }
 
 #[derive(Args, Clone, Debug)]
@@ -50,6 +53,24 @@ pub async fn info(args: ArgsRef<InfoPluginArgs>, proto: ResourceRef<ProtoResourc
     let mut config = proto.env.load_config()?.to_owned();
     let tool_config = config.tools.remove(&tool.id).unwrap_or_default();
 
+    // Plugins are cached, so this only resolves the path to the file,
+    // while asdf plugins are a cloned repository of scripts
+    let locator = tool.locator.clone().unwrap();
+    let (plugin_path, plugin_hash) = match &tool.asdf_plugin {
+        Some(asdf) => (asdf.dir.clone(), None),
+        None => {
+            let plugin_path = proto
+                .env
+                .get_plugin_loader()?
+                .load_plugin(&tool.id, &locator)
+                .await?;
+            let plugin_hash = hash_file_contents(&plugin_path)?;
+
+            (plugin_path, Some(plugin_hash))
+        }
+    };
+    let version_files = tool.get_version_files()?;
+
     if args.json {
         let info = PluginInfo {
             bins: tool.get_bin_locations()?,
@@ -59,11 +80,14 @@ pub async fn info(args: ArgsRef<InfoPluginArgs>, proto: ResourceRef<ProtoResourc
             globals_prefix: tool.get_globals_prefix().map(|p| p.to_owned()),
             inventory_dir: tool.get_inventory_dir(),
             shims: tool.get_shim_locations()?,
-            id: tool.id,
+            id: tool.id.clone(),
             name: tool.metadata.name.clone(),
-            manifest: tool.manifest,
-            metadata: tool.metadata,
-            plugin: tool.locator.unwrap(),
+            manifest: tool.manifest.clone(),
+            metadata: tool.metadata.clone(),
+            plugin: locator,
+            plugin_hash,
+            plugin_path,
+            version_files,
         };
 
         println!("{}", json::to_string_pretty(&info).into_diagnostic()?);
@@ -85,7 +109,35 @@ pub async fn info(args: ArgsRef<InfoPluginArgs>, proto: ResourceRef<ProtoResourc
             p.entry("Version", color::hash(version));
         }
 
-        p.locator(tool.locator.as_ref().unwrap());
+        p.locator(&locator);
+        p.entry("Source", color::path(&plugin_path));
+
+        if let Some(hash) = &plugin_hash {
+            p.entry("Checksum", color::hash(hash));
+        }
+
+        if !tool.metadata.supported_platforms.is_empty() {
+            p.entry_map(
+                "Supported platforms",
+                tool.metadata.supported_platforms.iter().map(|(os, archs)| {
+                    (
+                        color::id(os.to_string()),
+                        archs
+                            .iter()
+                            .map(|arch| arch.to_string())
+                            .collect::<Vec<_>>()
+                            .join(", "),
+                    )
+                }),
+                None,
+            );
+        }
+
+        p.entry_list(
+            "Version files",
+            version_files.iter().map(color::file),
+            Some(color::muted("None")),
+        );
 
         Ok(())
     })?;
@@ -212,5 +264,43 @@ pub async fn info(args: ArgsRef<InfoPluginArgs>, proto: ResourceRef<ProtoResourc
         })?;
     }
 
+    // SCHEMA
+
+    if let Some(properties) = tool
+        .metadata
+        .config_schema
+        .as_ref()
+        .and_then(|schema| schema.get("properties"))
+        .and_then(|properties| properties.as_object())
+    {
+        printer.named_section("Configuration schema", |p| {
+            p.entry_map(
+                "Settings",
+                properties.iter().map(|(name, property)| {
+                    let kind = property
+                        .get("type")
+                        .map(|kind| match kind.as_str() {
+                            Some(kind) => kind.to_owned(),
+                            None => kind.to_string(),
+                        })
+                        .unwrap_or_else(|| "unknown".into());
+
+                    (
+                        color::property(name),
+                        match property.get("description").and_then(|d| d.as_str()) {
+                            Some(description) => {
+                                format!("{description} {}", format_value(format!("({kind})")))
+                            }
+                            None => format_value(kind),
+                        },
+                    )
+                }),
+                None,
+            );
+
+            Ok(())
+        })?;
+    }
+
     printer.flush();
 }
@@ -1,16 +1,18 @@
 use crate::helpers::ProtoResource;
 use crate::printer::{format_value, Printer};
-use chrono::{DateTime, NaiveDateTime};
+use crate::template::render_template;
 use clap::Args;
 use miette::IntoDiagnostic;
-use proto_core::{Id, PluginLocator, ProtoToolConfig, ToolManifest, UnresolvedVersionSpec};
+use proto_core::{
+    Id, PluginLocator, PluginUpdate, ProtoToolConfig, Tool, ToolManifest, UnresolvedVersionSpec,
+};
 use rustc_hash::{FxHashMap, FxHashSet};
 use serde::Serialize;
 use starbase::system;
 use starbase_styles::color;
 use starbase_utils::json;
 use tokio::sync::Mutex;
-use tracing::info;
+use tracing::{debug, info};
 
 #[derive(Serialize)]
 pub struct PluginItem<'a> {
@@ -18,6 +20,9 @@ pub struct PluginItem<'a> {
     locator: Option<PluginLocator>,
     config: Option<&'a ProtoToolConfig>,
     manifest: ToolManifest,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    update: Option<PluginUpdate>,
 }
 
 #[derive(Args, Clone, Debug)]
@@ -28,16 +33,79 @@ pub struct ListPluginsArgs {
     #[arg(long, help = "Include resolved aliases in the output")]
     aliases: bool,
 
+    #[arg(
+        long,
+        conflicts_with = "json",
+        help = "Print each plugin on its own line using a template, for example \"{{id}} {{name}}\""
+    )]
+    format: Option<String>,
+
     #[arg(long, help = "Print the list in JSON format")]
     json: bool,
 
+    #[arg(
+        long,
+        help = "Include whether the cached plugin is behind what its locator currently points at"
+    )]
+    updates: bool,
+
     #[arg(long, help = "Include installed versions in the output")]
     versions: bool,
 }
 
+/// Check whether the tool's cached plugin is behind what its locator currently
+/// points at. Failures are logged and treated as unknown.
+pub async fn check_for_update(proto: &ProtoResource, tool: &Tool) -> Option<PluginUpdate> {
+    let locator = tool.locator.as_ref()?;
+
+    match proto
+        .env
+        .get_plugin_loader()
+        .ok()?
+        .check_plugin_update(&tool.id, locator)
+        .await
+    {
+        Ok(update) => update,
+        Err(error) => {
+            debug!(
+                plugin = locator.to_string(),
+                "Unable to check plugin for updates: {}", error
+            );
+
+            None
+        }
+    }
+}
+
+fn format_update(update: Option<&PluginUpdate>) -> String {
+    let Some(update) = update else {
+        return format_value("unknown");
+    };
+
+    if update.outdated {
+        let mut comments = vec![];
+
+        if let Some(cached) = &update.cached {
+            comments.push(format!("cached {cached}"));
+        }
+
+        if let Some(latest) = &update.latest {
+            comments.push(format!("latest {latest}"));
+        }
+
+        format!(
+            "{} {}",
+            color::success("available"),
+            format_value(comments.join(", "))
+        )
+    } else {
+        format_value("up to date")
+    }
+}
+
 #[system]
 pub async fn list(args: ArgsRef<ListPluginsArgs>, proto: ResourceRef<ProtoResource>) {
-    if !args.json {
+    if !args.json && args.format.is_none() {
         info!("Loading plugins...");
     }
 
@@ -49,32 +117,56 @@ pub async fn list(args: ArgsRef<ListPluginsArgs>, proto: ResourceRef<ProtoResour
 
     tools.sort_by(|a, d| a.id.cmp(&d.id));
 
-    // --json
-    if args.json {
+    // --updates
+    let mut updates = FxHashMap::default();
+
+    if args.updates {
+        for tool in &tools {
+            if let Some(update) = check_for_update(proto, tool).await {
+                updates.insert(tool.id.clone(), update);
+            }
+        }
+    }
+
+    // --json, --format
+    if args.json || args.format.is_some() {
         let items = tools
             .into_iter()
             .map(|t| {
                 let tool_config = config.tools.get(&t.id);
                 let name = t.get_name().to_owned();
+                let update = updates.remove(&t.id);
 
                 (
-                    t.id,
+                    t.id.clone(),
                     PluginItem {
                         name,
-                        locator: t.locator,
+                        locator: t.locator.clone(),
                         config: tool_config,
-                        manifest: t.manifest,
+                        manifest: t.manifest.clone(),
+                        update,
                     },
                 )
             })
-            .collect::<FxHashMap<_, _>>();
+            .collect::<Vec<_>>();
 
-        println!("{}", json::to_string_pretty(&items).into_diagnostic()?);
+        if let Some(format) = &args.format {
+            for (id, item) in &items {
+                println!("{}", render_template(format, id.as_str(), item)?);
+            }
+        } else {
+            println!(
+                "{}",
+                json::to_string_pretty(&items.into_iter().collect::<FxHashMap<_, _>>())
+                    .into_diagnostic()?
+            );
+        }
 
         return Ok(());
     }
 
-    let printer = Mutex::new(Printer::new());
+    let printer =
+        Mutex::new(Printer::new().with_date_format(config.settings.ui.date_format.clone()));
     let latest_version = UnresolvedVersionSpec::default();
 
     for tool in tools {
@@ -96,6 +188,11 @@ pub async fn list(args: ArgsRef<ListPluginsArgs>, proto: ResourceRef<ProtoResour
                 p.locator(locator);
             }
 
+            // --updates
+            if args.updates {
+                p.entry("Update", format_update(updates.get(&tool.id)));
+            }
+
             // --aliases
             if args.aliases {
                 p.entry_map(
@@ -114,50 +211,48 @@ pub async fn list(args: ArgsRef<ListPluginsArgs>, proto: ResourceRef<ProtoResour
                 let mut versions = tool.manifest.installed_versions.iter().collect::<Vec<_>>();
                 versions.sort();
 
-                p.entry_map(
-                    "Versions",
-                    versions
-                        .iter()
-                        .map(|version| {
-                            let mut comments = vec![];
-                            let mut is_default = false;
-
-                            if let Some(meta) = &tool.manifest.versions.get(version) {
-                                if let Some(at) = create_datetime(meta.installed_at) {
-                                    comments.push(format!("installed {}", at.format("%x")));
-                                }
+                let items = versions
+                    .iter()
+                    .map(|version| {
+                        let mut comments = vec![];
+                        let mut is_default = false;
 
-                                if let Ok(Some(last_used)) = tool
-                                    .manifest
-                                    .load_used_at(inventory_dir.join(version.to_string()))
-                                {
-                                    if let Some(at) = create_datetime(last_used) {
-                                        comments.push(format!("last used {}", at.format("%x")));
-                                    }
-                                }
+                        if let Some(meta) = &tool.manifest.versions.get(version) {
+                            if let Some(at) = p.date(meta.installed_at) {
+                                comments.push(format!("installed {at}"));
                             }
 
-                            if config
-                                .versions
-                                .get(&tool.id)
-                                .is_some_and(|dv| *dv == version.to_unresolved_spec())
+                            if let Ok(Some(last_used)) = tool
+                                .manifest
+                                .load_used_at(inventory_dir.join(version.to_string()))
                             {
-                                comments.push("default version".into());
-                                is_default = true;
+                                if let Some(at) = p.date(last_used) {
+                                    comments.push(format!("last used {at}"));
+                                }
                             }
+                        }
 
-                            (
-                                if is_default {
-                                    color::invalid(version.to_string())
-                                } else {
-                                    color::hash(version.to_string())
-                                },
-                                format_value(comments.join(", ")),
-                            )
-                        })
-                        .collect::<Vec<_>>(),
-                    None,
-                );
+                        if config
+                            .versions
+                            .get(&tool.id)
+                            .is_some_and(|dv| *dv == version.to_unresolved_spec())
+                        {
+                            comments.push("default version".into());
+                            is_default = true;
+                        }
+
+                        (
+                            if is_default {
+                                color::invalid(version.to_string())
+                            } else {
+                                color::hash(version.to_string())
+                            },
+                            format_value(comments.join(", ")),
+                        )
+                    })
+                    .collect::<Vec<_>>();
+
+                p.entry_map("Versions", items, None);
             }
 
             Ok(())
@@ -166,8 +261,3 @@ pub async fn list(args: ArgsRef<ListPluginsArgs>, proto: ResourceRef<ProtoResour
 
     printer.lock().await.flush();
 }
-
-fn create_datetime(millis: u128) -> Option<NaiveDateTime> {
-    DateTime::from_timestamp((millis / 1000) as i64, ((millis % 1000) * 1_000_000) as u32)
-        .map(|dt| dt.naive_local())
-}
@@ -1,9 +1,17 @@
 mod add;
+mod doctor;
 mod info;
 mod list;
+mod outdated;
 mod remove;
+mod search;
+mod upgrade;
 
 pub use add::*;
+pub use doctor::*;
 pub use info::*;
 pub use list::*;
+pub use outdated::*;
 pub use remove::*;
+pub use search::*;
+pub use upgrade::*;
@@ -0,0 +1,103 @@
+use super::check_for_update;
+use crate::helpers::ProtoResource;
+use crate::printer::{format_value, Printer};
+use clap::Args;
+use miette::IntoDiagnostic;
+use proto_core::{Id, PluginLocator, PluginUpdate};
+use rustc_hash::FxHashSet;
+use serde::Serialize;
+use starbase::system;
+use starbase_styles::color;
+use starbase_utils::json;
+use std::collections::BTreeMap;
+use tracing::info;
+
+#[derive(Serialize)]
+pub struct OutdatedPluginItem {
+    name: String,
+    locator: PluginLocator,
+
+    #[serde(flatten)]
+    update: PluginUpdate,
+}
+
+#[derive(Args, Clone, Debug)]
+pub struct OutdatedPluginsArgs {
+    #[arg(help = "ID of plugins to check")]
+    ids: Vec<Id>,
+
+    #[arg(long, help = "Print the outdated plugins in JSON format")]
+    json: bool,
+}
+
+#[system]
+pub async fn outdated(args: ArgsRef<OutdatedPluginsArgs>, proto: ResourceRef<ProtoResource>) {
+    if !args.json {
+        info!("Checking plugins for updates...");
+    }
+
+    let tools = proto
+        .load_tools_with_filters(FxHashSet::from_iter(&args.ids))
+        .await?;
+
+    let mut items = BTreeMap::default();
+
+    for tool in &tools {
+        let Some(locator) = &tool.locator else {
+            continue;
+        };
+
+        if let Some(update) = check_for_update(proto, tool).await {
+            if update.outdated {
+                items.insert(
+                    tool.id.clone(),
+                    OutdatedPluginItem {
+                        name: tool.get_name().to_owned(),
+                        locator: locator.to_owned(),
+                        update,
+                    },
+                );
+            }
+        }
+    }
+
+    if args.json {
+        println!("{}", json::to_string_pretty(&items).into_diagnostic()?);
+
+        return Ok(());
+    }
+
+    if items.is_empty() {
+        info!("All plugins are up to date");
+
+        return Ok(());
+    }
+
+    let mut printer = Printer::new();
+
+    for (id, item) in &items {
+        printer.line();
+        printer.header(id, &item.name);
+
+        printer.section(|p| {
+            p.locator(&item.locator);
+            p.entry(
+                "Cached",
+                format_value(item.update.cached.as_deref().unwrap_or("unknown")),
+            );
+            p.entry(
+                "Latest",
+                color::success(item.update.latest.as_deref().unwrap_or("unknown")),
+            );
+
+            Ok(())
+        })?;
+    }
+
+    printer.flush();
+
+    info!(
+        "Run {} to update the outdated plugins",
+        color::shell("proto plugin upgrade")
+    );
+}
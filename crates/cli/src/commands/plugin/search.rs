@@ -0,0 +1,123 @@
+use crate::error::ProtoCliError;
+use crate::helpers::ProtoResource;
+use crate::printer::Printer;
+use clap::Args;
+use miette::IntoDiagnostic;
+use proto_core::{PluginRegistry, RegistryIndex, RegistryPlugin};
+use serde::Serialize;
+use starbase::system;
+use starbase_styles::color;
+use starbase_utils::json;
+use tracing::{info, warn};
+
+#[derive(Serialize)]
+pub struct SearchResult<'a> {
+    registry: &'a str,
+
+    #[serde(flatten)]
+    plugin: &'a RegistryPlugin,
+}
+
+#[derive(Args, Clone, Debug)]
+pub struct SearchPluginArgs {
+    #[arg(
+        required = true,
+        help = "Query to match against plugin IDs, names, and tags"
+    )]
+    query: String,
+
+    #[arg(long, help = "Print the results in JSON format")]
+    json: bool,
+}
+
+/// Load the index of every configured registry, in name order.
+/// Registries that fail to load are logged and skipped.
+pub async fn load_registry_indexes(
+    proto: &ProtoResource,
+) -> miette::Result<Vec<(PluginRegistry, RegistryIndex)>> {
+    let config = proto.env.load_config()?;
+
+    if config.settings.registries.is_empty() {
+        return Err(ProtoCliError::NoConfiguredRegistries.into());
+    }
+
+    let client = proto.env.get_http_client()?;
+    let mut indexes = vec![];
+
+    for (name, location) in &config.settings.registries {
+        let registry = PluginRegistry::new(name, config.settings.http.rewrite_url(location));
+
+        match registry.load_index(client).await {
+            Ok(index) => {
+                indexes.push((registry, index));
+            }
+            Err(error) => {
+                warn!(
+                    "Failed to load plugin registry {}: {error}",
+                    color::id(name)
+                );
+            }
+        };
+    }
+
+    Ok(indexes)
+}
+
+#[system]
+pub async fn search(args: ArgsRef<SearchPluginArgs>, proto: ResourceRef<ProtoResource>) {
+    let indexes = load_registry_indexes(proto).await?;
+
+    let results = indexes
+        .iter()
+        .flat_map(|(registry, index)| {
+            index
+                .search(&args.query)
+                .into_iter()
+                .map(|plugin| SearchResult {
+                    registry: &registry.name,
+                    plugin,
+                })
+        })
+        .collect::<Vec<_>>();
+
+    if args.json {
+        println!("{}", json::to_string_pretty(&results).into_diagnostic()?);
+
+        return Ok(());
+    }
+
+    if results.is_empty() {
+        info!("No plugins found matching {}", color::label(&args.query));
+
+        return Ok(());
+    }
+
+    let mut printer = Printer::new();
+
+    for result in results {
+        let plugin = result.plugin;
+
+        printer.line();
+        printer.header(
+            &plugin.id,
+            plugin.name.as_deref().unwrap_or(plugin.id.as_str()),
+        );
+
+        printer.section(|p| {
+            if let Some(description) = &plugin.description {
+                p.entry("Description", description);
+            }
+
+            if let Some(author) = &plugin.author {
+                p.entry("Author", author);
+            }
+
+            p.entry("Registry", color::label(result.registry));
+            p.locator(&plugin.locator);
+
+            Ok(())
+        })?;
+    }
+
+    printer.flush();
+}
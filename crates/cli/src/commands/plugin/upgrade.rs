@@ -0,0 +1,60 @@
+use super::check_for_update;
+use crate::helpers::ProtoResource;
+use clap::Args;
+use proto_core::Id;
+use rustc_hash::FxHashSet;
+use starbase::system;
+use starbase_styles::color;
+use tracing::{debug, info};
+
+#[derive(Args, Clone, Debug)]
+pub struct UpgradePluginsArgs {
+    #[arg(help = "ID of plugins to upgrade")]
+    ids: Vec<Id>,
+
+    #[arg(long, help = "Re-download plugins even if they are up to date")]
+    force: bool,
+}
+
+#[system]
+pub async fn upgrade(args: ArgsRef<UpgradePluginsArgs>, proto: ResourceRef<ProtoResource>) {
+    let tools = proto
+        .load_tools_with_filters(FxHashSet::from_iter(&args.ids))
+        .await?;
+
+    let loader = proto.env.get_plugin_loader()?;
+    let mut upgraded = 0;
+
+    for tool in &tools {
+        let Some(locator) = &tool.locator else {
+            continue;
+        };
+
+        let update = check_for_update(proto, tool).await;
+
+        if !args.force && !update.as_ref().is_some_and(|update| update.outdated) {
+            debug!("Plugin {} is up to date, skipping", tool.id);
+
+            continue;
+        }
+
+        let plugin_path = loader.update_plugin(&tool.id, locator).await?;
+
+        debug!(plugin = ?plugin_path, "Swapped cached plugin");
+
+        match update.and_then(|update| update.latest) {
+            Some(latest) => info!(
+                "Upgraded plugin {} to {}",
+                color::id(&tool.id),
+                color::hash(latest)
+            ),
+            None => info!("Upgraded plugin {}", color::id(&tool.id)),
+        };
+
+        upgraded += 1;
+    }
+
+    if upgraded == 0 {
+        info!("All plugins are up to date");
+    }
+}
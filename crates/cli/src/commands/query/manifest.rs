@@ -0,0 +1,26 @@
+use crate::helpers::ProtoResource;
+use clap::Args;
+use miette::IntoDiagnostic;
+use proto_core::{Id, ToolManifestQuery};
+use starbase::system;
+use starbase_utils::json;
+
+#[derive(Args, Clone, Debug)]
+pub struct QueryManifestArgs {
+    #[arg(required = true, help = "ID of tool")]
+    id: Id,
+}
+
+#[system]
+pub async fn manifest(args: ArgsRef<QueryManifestArgs>, proto: ResourceRef<ProtoResource>) {
+    let mut tool = proto.load_tool(&args.id).await?;
+
+    // Bins point to a specific version, so locate them for the highest installed
+    if let Some(version) = tool.manifest.installed_versions.iter().max().cloned() {
+        tool.set_version(version);
+    }
+
+    let query = ToolManifestQuery::from_tool(&tool)?;
+
+    println!("{}", json::to_string_pretty(&query).into_diagnostic()?);
+}
@@ -0,0 +1,272 @@
+use crate::helpers::{serialize_optional_timestamp, serialize_timestamp, ProtoResource};
+use clap::{Args, ValueEnum};
+use miette::IntoDiagnostic;
+use proto_core::{get_proto_version, now, Id, Tool, UnresolvedVersionSpec, VersionSpec};
+use rustc_hash::FxHashSet;
+use serde::Serialize;
+use starbase::system;
+use starbase_utils::{fs, json};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use tracing::debug;
+
+#[derive(Clone, Debug, Default, ValueEnum)]
+pub enum ReportFormat {
+    #[default]
+    Json,
+    Prometheus,
+}
+
+#[derive(Args, Clone, Debug)]
+pub struct ReportArgs {
+    #[arg(
+        value_enum,
+        long,
+        default_value_t,
+        help = "Format to print the metrics in"
+    )]
+    format: ReportFormat,
+}
+
+#[derive(Serialize)]
+pub struct ReportVersion {
+    version: VersionSpec,
+    #[serde(serialize_with = "serialize_timestamp")]
+    installed_at: u128,
+    #[serde(serialize_with = "serialize_optional_timestamp")]
+    last_used_at: Option<u128>,
+}
+
+#[derive(Serialize)]
+pub struct ReportTool {
+    installed_versions: Vec<ReportVersion>,
+    #[serde(serialize_with = "serialize_optional_timestamp")]
+    last_updated_at: Option<u128>,
+    latest_version: Option<VersionSpec>,
+    outdated: bool,
+}
+
+#[derive(Serialize)]
+pub struct ToolchainReport {
+    #[serde(serialize_with = "serialize_timestamp")]
+    generated_at: u128,
+    outdated_count: usize,
+    proto_version: String,
+    tools: BTreeMap<Id, ReportTool>,
+}
+
+async fn create_tool_report(tool: &Tool) -> ReportTool {
+    let tool_dir = tool.get_inventory_dir();
+    let mut installed_versions = vec![];
+
+    for (version, meta) in &tool.manifest.versions {
+        if !tool.manifest.installed_versions.contains(version) {
+            continue;
+        }
+
+        installed_versions.push(ReportVersion {
+            version: version.to_owned(),
+            installed_at: meta.installed_at,
+            last_used_at: tool
+                .manifest
+                .load_used_at(tool_dir.join(version.to_string()))
+                .ok()
+                .flatten(),
+        });
+    }
+
+    installed_versions.sort_by(|a, d| a.version.cmp(&d.version));
+
+    // Uses the cached versions when available, and is skipped when they can't be loaded
+    let latest_version = match tool
+        .load_version_resolver(&UnresolvedVersionSpec::default())
+        .await
+    {
+        Ok(resolver) => resolver.resolve_without_manifest(&UnresolvedVersionSpec::default()),
+        Err(error) => {
+            debug!("Unable to load versions for {}: {error}", tool.get_name());
+
+            None
+        }
+    };
+
+    let outdated = match (&latest_version, installed_versions.last()) {
+        (Some(latest), Some(highest)) => &highest.version < latest,
+        _ => false,
+    };
+
+    ReportTool {
+        last_updated_at: installed_versions.iter().map(|v| v.installed_at).max(),
+        latest_version,
+        outdated,
+        installed_versions,
+    }
+}
+
+fn format_prometheus(report: &ToolchainReport) -> miette::Result<String> {
+    let mut out = String::new();
+    let mut metric = |name: &str, help: &str, kind: &str, samples: Vec<(String, String)>| {
+        writeln!(out, "# HELP {name} {help}")?;
+        writeln!(out, "# TYPE {name} {kind}")?;
+
+        for (labels, value) in samples {
+            if labels.is_empty() {
+                writeln!(out, "{name} {value}")?;
+            } else {
+                writeln!(out, "{name}{{{labels}}} {value}")?;
+            }
+        }
+
+        Ok::<_, std::fmt::Error>(())
+    };
+    let seconds = |millis: u128| format!("{:.3}", millis as f64 / 1000.0);
+
+    metric(
+        "proto_info",
+        "Version of proto that generated the report.",
+        "gauge",
+        vec![(format!("version=\"{}\"", report.proto_version), "1".into())],
+    )
+    .into_diagnostic()?;
+
+    metric(
+        "proto_tool_installed",
+        "Installed versions of each tool.",
+        "gauge",
+        report
+            .tools
+            .iter()
+            .flat_map(|(id, tool)| {
+                tool.installed_versions.iter().map(move |v| {
+                    (
+                        format!("tool=\"{id}\",version=\"{}\"", v.version),
+                        "1".into(),
+                    )
+                })
+            })
+            .collect(),
+    )
+    .into_diagnostic()?;
+
+    metric(
+        "proto_tool_last_used_timestamp_seconds",
+        "When each installed version was last run.",
+        "gauge",
+        report
+            .tools
+            .iter()
+            .flat_map(|(id, tool)| {
+                tool.installed_versions.iter().filter_map(move |v| {
+                    v.last_used_at.map(|at| {
+                        (
+                            format!("tool=\"{id}\",version=\"{}\"", v.version),
+                            seconds(at),
+                        )
+                    })
+                })
+            })
+            .collect(),
+    )
+    .into_diagnostic()?;
+
+    metric(
+        "proto_tool_last_updated_timestamp_seconds",
+        "When a version of each tool was last installed.",
+        "gauge",
+        report
+            .tools
+            .iter()
+            .filter_map(|(id, tool)| {
+                tool.last_updated_at
+                    .map(|at| (format!("tool=\"{id}\""), seconds(at)))
+            })
+            .collect(),
+    )
+    .into_diagnostic()?;
+
+    metric(
+        "proto_tool_outdated",
+        "Whether a newer version than the highest installed version is available.",
+        "gauge",
+        report
+            .tools
+            .iter()
+            .map(|(id, tool)| {
+                (
+                    format!(
+                        "tool=\"{id}\",latest=\"{}\"",
+                        tool.latest_version
+                            .as_ref()
+                            .map(|v| v.to_string())
+                            .unwrap_or_default()
+                    ),
+                    if tool.outdated { "1" } else { "0" }.into(),
+                )
+            })
+            .collect(),
+    )
+    .into_diagnostic()?;
+
+    metric(
+        "proto_tools_outdated",
+        "Number of tools with a newer version available.",
+        "gauge",
+        vec![(String::new(), report.outdated_count.to_string())],
+    )
+    .into_diagnostic()?;
+
+    Ok(out)
+}
+
+#[system]
+pub async fn report(args: ArgsRef<ReportArgs>, proto: ResourceRef<ProtoResource>) {
+    let config = proto.env.load_config()?;
+    let mut ids = FxHashSet::default();
+
+    // Only report on tools that have been installed and have a plugin
+    if proto.env.tools_dir.exists() {
+        for entry in fs::read_dir(&proto.env.tools_dir)? {
+            let id = Id::raw(fs::file_name(entry.path()));
+
+            if entry.path().is_dir() && config.plugins.contains_key(&id) {
+                ids.insert(id);
+            }
+        }
+    }
+
+    let tools = if ids.is_empty() {
+        vec![]
+    } else {
+        proto.load_tools_with_filters(ids.iter().collect()).await?
+    };
+
+    let mut report = ToolchainReport {
+        generated_at: now(),
+        outdated_count: 0,
+        proto_version: get_proto_version().to_string(),
+        tools: BTreeMap::new(),
+    };
+
+    for tool in tools {
+        let tool_report = create_tool_report(&tool).await;
+
+        if tool_report.installed_versions.is_empty() {
+            continue;
+        }
+
+        if tool_report.outdated {
+            report.outdated_count += 1;
+        }
+
+        report.tools.insert(tool.id.clone(), tool_report);
+    }
+
+    match args.format {
+        ReportFormat::Json => {
+            println!("{}", json::to_string_pretty(&report).into_diagnostic()?);
+        }
+        ReportFormat::Prometheus => {
+            print!("{}", format_prometheus(&report)?);
+        }
+    };
+}
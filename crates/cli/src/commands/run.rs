@@ -1,23 +1,64 @@
+use crate::commands::env::load_detected_tools;
 use crate::commands::install::{internal_install, InstallArgs};
 use crate::error::ProtoCliError;
-use crate::helpers::ProtoResource;
+use crate::helpers::{create_theme, print_resolution_explanation, ProtoResource};
 use clap::Args;
-use indexmap::IndexMap;
+use dialoguer::Confirm;
 use miette::IntoDiagnostic;
-use proto_core::{detect_version, Id, ProtoError, Tool, UnresolvedVersionSpec, ENV_VAR_SUB};
+use proto_core::{
+    detect_version, now, Id, ProtoError, ShimCacheEntry, ShimRegistry, Tool, UnresolvedVersionSpec,
+    SHIM_CACHE_PATH_VAR,
+};
 use proto_pdk_api::{ExecutableConfig, RunHook, RunHookResult};
 use proto_shim::exec_command_and_replace;
 use starbase::system;
+use starbase_styles::color;
 use std::env;
 use std::ffi::OsStr;
+use std::io::{self, IsTerminal};
+use std::path::{Path, PathBuf};
 use std::process::Command;
-use system_env::create_process_command;
-use tracing::debug;
+use std::str::FromStr;
+use system_env::{create_process_command, find_command_on_path};
+use tracing::{debug, warn};
+
+/// A tool ID, with an optional version to run (`node@20`).
+#[derive(Clone, Debug)]
+pub struct RunTarget {
+    pub id: Id,
+    pub spec: Option<UnresolvedVersionSpec>,
+}
+
+impl FromStr for RunTarget {
+    type Err = ProtoCliError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let handle_error = || ProtoCliError::InvalidRunTarget {
+            target: value.to_owned(),
+        };
+
+        let (id, spec) = match value.split_once('@') {
+            Some((id, version)) => (
+                id,
+                Some(UnresolvedVersionSpec::parse(version).map_err(|_| handle_error())?),
+            ),
+            None => (value, None),
+        };
+
+        Ok(Self {
+            id: Id::new(id).map_err(|_| handle_error())?,
+            spec,
+        })
+    }
+}
 
 #[derive(Args, Clone, Debug)]
 pub struct RunArgs {
-    #[arg(required = true, help = "ID of tool")]
-    id: Id,
+    #[arg(
+        required_unless_present = "path",
+        help = "ID of tool, with an optional version (node@20)"
+    )]
+    id: Option<RunTarget>,
 
     #[arg(help = "Version or alias of tool")]
     spec: Option<UnresolvedVersionSpec>,
@@ -25,6 +66,22 @@ pub struct RunArgs {
     #[arg(long, help = "Name of an alternate (secondary) binary to run")]
     alt: Option<String>,
 
+    #[arg(long, help = "Explain how the version was detected and resolved")]
+    explain: bool,
+
+    #[arg(
+        long,
+        help = "Never install the tool if it's missing, even when auto-install is enabled"
+    )]
+    no_install: bool,
+
+    #[arg(
+        long,
+        help = "Run an arbitrary binary with proto's environment, instead of a tool",
+        conflicts_with_all = ["id", "spec", "alt", "explain", "no_install"]
+    )]
+    path: Option<PathBuf>,
+
     // Passthrough args (after --)
     #[arg(
         last = true,
@@ -51,11 +108,81 @@ fn is_trying_to_self_upgrade(tool: &Tool, args: &[String]) -> bool {
     false
 }
 
-fn get_executable(tool: &Tool, args: &RunArgs) -> miette::Result<ExecutableConfig> {
+fn prompt_for_install(tool: &Tool, enabled: bool) -> miette::Result<bool> {
+    // Only prompt when a user can actually respond
+    if !enabled
+        || env::var("CI").is_ok()
+        || !io::stdin().is_terminal()
+        || !io::stderr().is_terminal()
+    {
+        return Ok(false);
+    }
+
+    debug!("Tool not installed, prompting the user to install");
+
+    Confirm::with_theme(&create_theme())
+        .with_prompt(format!(
+            "{} {} is required but not installed, install now?",
+            tool.get_name(),
+            color::hash(tool.get_resolved_version().to_string()),
+        ))
+        .default(true)
+        .interact()
+        .into_diagnostic()
+}
+
+fn warn_if_exceeding_max_age(tool: &Tool) -> miette::Result<()> {
+    if tool.is_unmanaged() {
+        return Ok(());
+    }
+
+    let config = tool.proto.load_config()?;
+
+    let max_age = match config.policies.get_max_age(&tool.id) {
+        Ok(Some(max_age)) => max_age,
+        Ok(None) => return Ok(()),
+        Err(error) => {
+            warn!(
+                "Unable to check the maximum age of {}: {error}",
+                tool.get_name()
+            );
+
+            return Ok(());
+        }
+    };
+
+    let version = tool.get_resolved_version();
+
+    let Some(released_at) = tool.get_release_date(&version) else {
+        debug!(
+            "Unable to determine when {} was released, skipping maximum age check",
+            version
+        );
+
+        return Ok(());
+    };
+
+    let age = now().saturating_sub(released_at);
+
+    if age > max_age.as_millis() {
+        let days = age / (1000 * 60 * 60 * 24);
+
+        warn!(
+            "{} {} was released {} days ago, which exceeds the maximum age allowed by policy. Consider upgrading to a newer version.",
+            tool.get_name(),
+            color::hash(version.to_string()),
+            days,
+        );
+    }
+
+    Ok(())
+}
+
+fn get_executable(tool: &Tool, alt: Option<&str>) -> miette::Result<ExecutableConfig> {
     let tool_dir = tool.get_tool_dir();
 
     // Run an alternate executable (via shim)
-    if let Some(alt_name) = &args.alt {
+    if let Some(alt_name) = alt {
         for location in tool.get_shim_locations()? {
             if location.name == *alt_name {
                 let Some(exe_path) = &location.config.exe_path else {
@@ -97,6 +224,32 @@ fn get_executable(tool: &Tool, args: &RunArgs) -> miette::Result<ExecutableConfi
     Ok(config)
 }
 
+fn resolve_executable_symlinks(
+    tool: &Tool,
+    alt: Option<&str>,
+    exe_config: &mut ExecutableConfig,
+) -> miette::Result<()> {
+    let shim_name = alt.unwrap_or(tool.id.as_str());
+    let registry = ShimRegistry::load(&tool.proto)?;
+
+    if !registry
+        .get(shim_name)
+        .is_some_and(|shim| shim.resolve_symlinks == Some(true))
+    {
+        return Ok(());
+    }
+
+    if let Some(exe_path) = &exe_config.exe_path {
+        let real_path = exe_path.canonicalize().into_diagnostic()?;
+
+        debug!(bin = ?exe_path, real_path = ?real_path, "Resolved executable symlinks");
+
+        exe_config.exe_path = Some(real_path);
+    }
+
+    Ok(())
+}
+
 fn create_command<I: IntoIterator<Item = A>, A: AsRef<OsStr>>(
     tool: &Tool,
     exe_config: &ExecutableConfig,
@@ -134,55 +287,251 @@ fn create_command<I: IntoIterator<Item = A>, A: AsRef<OsStr>>(
     Ok(command)
 }
 
-// We don't use a `BTreeMap` for env vars, so that variable interpolation
-// and order of declaration can work correctly!
-fn get_env_vars(tool: &Tool) -> miette::Result<IndexMap<&str, Option<String>>> {
-    let config = tool.proto.load_config()?;
-    let mut base_vars = IndexMap::new();
+// When a tool re-invokes itself by name, a child process may find a different
+// executable on PATH before the proto shims, and run a mismatched version
+fn prepend_exe_dir_to_path(
+    tool: &Tool,
+    exe_path: &Path,
+    command: &mut Command,
+) -> miette::Result<()> {
+    let (Some(exe_dir), Some(exe_name)) = (exe_path.parent(), exe_path.file_name()) else {
+        return Ok(());
+    };
 
-    base_vars.extend(config.env.iter());
+    if let Some(found_path) = find_command_on_path(exe_name) {
+        // Shims respect the version of the current process, but bins are symlinked
+        // to the globally pinned version, so may also mismatch
+        if found_path.parent() == Some(exe_dir) || found_path.starts_with(&tool.proto.shims_dir) {
+            return Ok(());
+        }
 
-    if let Some(tool_config) = config.tools.get(&tool.id) {
-        base_vars.extend(tool_config.env.iter())
+        debug!(
+            bin = ?found_path,
+            exe_dir = ?exe_dir,
+            "A different executable would be found on PATH first, prepending the executable directory"
+        );
     }
 
-    let mut vars = IndexMap::<&str, Option<String>>::new();
+    // Respect PATH if it was customized with [env]
+    let path = command
+        .get_envs()
+        .find(|(key, _)| *key == "PATH")
+        .and_then(|(_, value)| value.map(|value| value.to_os_string()))
+        .or_else(|| env::var_os("PATH"))
+        .unwrap_or_default();
 
-    for (key, value) in base_vars {
-        let key_exists = env::var(key).is_ok_and(|v| !v.is_empty());
-        let value = value.to_value();
+    let mut paths = vec![exe_dir.to_path_buf()];
+    paths.extend(env::split_paths(&path));
 
-        // Don't override parent inherited vars
-        if key_exists && value.is_some() {
-            continue;
+    command.env("PATH", env::join_paths(paths).into_diagnostic()?);
+
+    Ok(())
+}
+
+/// Create the command that runs the tool's executable, with the same
+/// environment, arguments, and parent executable that shims would use.
+pub async fn create_tool_command(
+    tool: &mut Tool,
+    alt: Option<&str>,
+    passthrough: &[String],
+) -> miette::Result<Command> {
+    // Determine the binary path to execute
+    let mut exe_config = get_executable(tool, alt)?;
+    resolve_executable_symlinks(tool, alt, &mut exe_config)?;
+
+    let exe_path = exe_config.exe_path.as_ref().unwrap();
+
+    // Run before hook
+    let hook_result = if tool.plugin.has_func("pre_run") {
+        tool.locate_globals_dir().await?;
+
+        let globals_dir = tool.get_globals_bin_dir();
+        let globals_prefix = tool.get_globals_prefix();
+
+        tool.plugin.call_func_with(
+            "pre_run",
+            RunHook {
+                context: tool.create_context(),
+                globals_dir: globals_dir.map(|dir| tool.to_virtual_path(dir)),
+                globals_prefix: globals_prefix.map(|p| p.to_owned()),
+                passthrough_args: passthrough.to_vec(),
+            },
+        )?
+    } else {
+        RunHookResult::default()
+    };
+
+    // Create the command
+    let mut command = create_command(tool, &exe_config, passthrough)?;
+
+    for (key, val) in tool.proto.load_config()?.get_env_vars(Some(&tool.id)) {
+        match val {
+            Some(val) => {
+                command.env(key, val);
+            }
+            None => {
+                command.env_remove(key);
+            }
+        };
+    }
+
+    let prepend_path = tool
+        .proto
+        .load_config()?
+        .tools
+        .get(&tool.id)
+        .and_then(|config| config.prepend_path)
+        .unwrap_or_default();
+
+    // Scripts ran with a parent executable are not on PATH
+    if prepend_path && exe_config.parent_exe_name.is_none() {
+        prepend_exe_dir_to_path(tool, exe_path, &mut command)?;
+    }
+
+    if let Some(hook_args) = hook_result.args {
+        command.args(hook_args);
+    }
+
+    if let Some(hook_env) = hook_result.env {
+        command.envs(hook_env);
+    }
+
+    command
+        .env(
+            format!("{}_VERSION", tool.get_env_var_prefix()),
+            tool.get_resolved_version().to_string(),
+        )
+        .env(
+            format!("{}_BIN", tool.get_env_var_prefix()),
+            exe_path.to_string_lossy().to_string(),
+        );
+
+    Ok(command)
+}
+
+// Cache the resolved command, so that the shim can execute it directly
+// on subsequent runs, without spawning proto
+fn cache_shim_command(
+    tool: &Tool,
+    command: &Command,
+    passthrough: &[String],
+    cache_path: &Path,
+) -> miette::Result<()> {
+    // Hooks and run tracking must be executed by proto on every run
+    if tool.plugin.has_func("pre_run") || tool.proto.load_config()?.settings.track_runs {
+        let _ = std::fs::remove_file(cache_path);
+
+        return Ok(());
+    }
+
+    let args = command
+        .get_args()
+        .map(|arg| arg.to_string_lossy().to_string())
+        .collect::<Vec<_>>();
+
+    let mut entry = ShimCacheEntry {
+        args: args[0..args.len() - passthrough.len()].to_vec(),
+        bin: PathBuf::from(command.get_program()),
+        self_upgrade_commands: tool.metadata.self_upgrade_commands.clone(),
+        ..Default::default()
+    };
+
+    for (key, value) in command.get_envs() {
+        entry.env.insert(
+            key.to_string_lossy().to_string(),
+            value.map(|value| value.to_string_lossy().to_string()),
+        );
+    }
+
+    // Variables that proto sets on its own process, and are inherited
+    for key in ["PROTO_DETECTED_FROM", "PROTO_VERSION"] {
+        if let Ok(value) = env::var(key) {
+            entry.env.entry(key.to_owned()).or_insert(Some(value));
         }
+    }
 
-        // Interpolate nested vars
-        let value = value.map(|val| {
-            ENV_VAR_SUB
-                .replace_all(&val, |cap: &regex::Captures| {
-                    let name = cap.get(1).unwrap().as_str();
-
-                    if let Ok(existing) = env::var(name) {
-                        existing
-                    } else if let Some(Some(existing)) = vars.get(name) {
-                        existing.to_owned()
-                    } else {
-                        String::new()
-                    }
-                })
-                .to_string()
-        });
-
-        vars.insert(key.as_str(), value);
+    entry.track_config_files(&tool.proto)?;
+    entry.track_path(&tool.manifest.path);
+
+    if let Ok(source) = env::var("PROTO_DETECTED_FROM") {
+        entry.track_path(Path::new(&source));
     }
 
-    Ok(vars)
+    entry.save(cache_path)
+}
+
+async fn run_passthrough(
+    proto: &ProtoResource,
+    bin: &Path,
+    passthrough: &[String],
+) -> miette::Result<()> {
+    let mut command = create_process_command(bin, passthrough);
+
+    for (key, val) in proto.env.load_config()?.get_env_vars(None) {
+        match val {
+            Some(val) => {
+                command.env(key, val);
+            }
+            None => {
+                command.env_remove(key);
+            }
+        };
+    }
+
+    // Export the detected versions, so that shims called by the binary
+    // use the same versions as the current directory
+    for tool in load_detected_tools(proto).await? {
+        command.env(
+            format!("{}_VERSION", tool.get_env_var_prefix()),
+            tool.get_resolved_version().to_string(),
+        );
+
+        for (key, dir) in tool.get_root_env_vars() {
+            command.env(key, dir);
+        }
+    }
+
+    debug!(
+        bin = ?bin,
+        args = ?passthrough,
+        pid = std::process::id(),
+        "Running {} in passthrough mode", bin.display(),
+    );
+
+    // Must be the last line!
+    exec_command_and_replace(command).into_diagnostic()
 }
 
 #[system]
 pub async fn run(args: ArgsRef<RunArgs>, proto: ResourceRef<ProtoResource>) -> SystemResult {
-    let mut tool = proto.load_tool(&args.id).await?;
+    let Some(target) = &args.id else {
+        let bin = args.path.as_ref().expect("Required path missing!");
+
+        return run_passthrough(proto, bin, &args.passthrough).await;
+    };
+
+    let id = &target.id;
+
+    let spec = match (&target.spec, &args.spec) {
+        (Some(target_spec), Some(spec)) => {
+            return Err(ProtoCliError::DuplicateRunVersion {
+                target: format!("{id}@{target_spec}"),
+                version: spec.to_string(),
+            }
+            .into());
+        }
+        (Some(spec), None) | (None, Some(spec)) => Some(spec.to_owned()),
+        (None, None) => None,
+    };
+
+    let mut tool = proto.load_tool(id).await?;
+
+    if tool.is_sdk_root() {
+        return Err(ProtoCliError::SdkRootNotExecutable {
+            tool: tool.get_name().to_owned(),
+        }
+        .into());
+    }
 
     // Avoid running the tool's native self-upgrade as it conflicts with proto
     if is_trying_to_self_upgrade(&tool, &args.passthrough) {
@@ -193,13 +542,42 @@ pub async fn run(args: ArgsRef<RunArgs>, proto: ResourceRef<ProtoResource>) -> S
         .into());
     }
 
-    let version = detect_version(&tool, args.spec.clone()).await?;
+    let version = detect_version(&tool, spec.clone()).await?;
+    let is_setup = tool.is_setup(&version).await?;
+
+    if args.explain {
+        let env_var = format!("{}_VERSION", tool.get_env_var_prefix());
+
+        print_resolution_explanation(
+            &tool,
+            Some(if spec.is_some() {
+                format!("detected {version} from the command line")
+            } else if env::var(&env_var).is_ok_and(|value| !value.is_empty()) {
+                format!("detected {version} from the {env_var} environment variable")
+            } else if let Some(sources) = env::var_os("PROTO_DETECTED_FROM_MERGED") {
+                format!(
+                    "merged {version} from {}",
+                    env::split_paths(&sources)
+                        .map(|source| source.display().to_string())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )
+            } else if let Ok(source) = env::var("PROTO_DETECTED_FROM") {
+                format!("detected {version} from {source}")
+            } else {
+                format!("detected {version}")
+            }),
+        );
+    }
 
     // Check if installed or install
-    if !tool.is_setup(&version).await? {
+    if !is_setup {
         let config = tool.proto.load_config()?;
 
-        if !config.settings.auto_install {
+        if args.no_install
+            || (!config.settings.auto_install
+                && !prompt_for_install(&tool, config.settings.install_prompt)?)
+        {
             let command = format!("proto install {} {}", tool.id, tool.get_resolved_version());
 
             if let Ok(source) = env::var("PROTO_DETECTED_FROM") {
@@ -221,13 +599,18 @@ pub async fn run(args: ArgsRef<RunArgs>, proto: ResourceRef<ProtoResource>) -> S
         }
 
         // Install the tool
-        debug!("Auto-install setting is configured, attempting to install");
+        debug!("Auto-install setting is configured or prompt was accepted, attempting to install");
 
         tool = internal_install(
             proto,
             InstallArgs {
+                build: false,
                 canary: false,
-                id: args.id.clone(),
+                explain: false,
+                frozen: false,
+                from_file: None,
+                include_prereleases: false,
+                id: id.clone(),
                 pin: None,
                 passthrough: vec![],
                 spec: Some(tool.get_resolved_version().to_unresolved_spec()),
@@ -237,65 +620,33 @@ pub async fn run(args: ArgsRef<RunArgs>, proto: ResourceRef<ProtoResource>) -> S
         .await?;
     }
 
-    // Determine the binary path to execute
-    let exe_config = get_executable(&tool, args)?;
-    let exe_path = exe_config.exe_path.as_ref().unwrap();
-
-    // Run before hook
-    let hook_result = if tool.plugin.has_func("pre_run") {
-        tool.locate_globals_dir().await?;
-
-        let globals_dir = tool.get_globals_bin_dir();
-        let globals_prefix = tool.get_globals_prefix();
-
-        tool.plugin.call_func_with(
-            "pre_run",
-            RunHook {
-                context: tool.create_context(),
-                globals_dir: globals_dir.map(|dir| tool.to_virtual_path(dir)),
-                globals_prefix: globals_prefix.map(|p| p.to_owned()),
-                passthrough_args: args.passthrough.clone(),
-            },
-        )?
-    } else {
-        RunHookResult::default()
-    };
-
-    // Create and run the command
-    let mut command = create_command(&tool, &exe_config, &args.passthrough)?;
+    // Nudge away from old versions, without blocking the run
+    warn_if_exceeding_max_age(&tool)?;
 
-    for (key, val) in get_env_vars(&tool)? {
-        match val {
-            Some(val) => {
-                command.env(key, val);
-            }
-            None => {
-                command.env_remove(key);
-            }
-        };
-    }
+    // Determine the binary path to execute and its environment
+    let mut command =
+        create_tool_command(&mut tool, args.alt.as_deref(), &args.passthrough).await?;
 
-    if let Some(hook_args) = hook_result.args {
-        command.args(hook_args);
+    // Update the last used timestamp
+    if env::var("PROTO_SKIP_USED_AT").is_err() && !tool.is_unmanaged() {
+        let _ = tool.manifest.track_used_at(tool.get_tool_dir());
     }
 
-    if let Some(hook_env) = hook_result.env {
-        command.envs(hook_env);
+    // Count the run, which is aggregated into the manifest later
+    if !tool.is_unmanaged() && tool.proto.load_config()?.settings.track_runs {
+        let _ = tool.manifest.track_run(&tool.get_resolved_version());
     }
 
-    command
-        .env(
-            format!("{}_VERSION", tool.get_env_var_prefix()),
-            tool.get_resolved_version().to_string(),
-        )
-        .env(
-            format!("{}_BIN", tool.get_env_var_prefix()),
-            exe_path.to_string_lossy().to_string(),
-        );
+    // Cache the resolved command for the shim that invoked us
+    if let Some(cache_path) = env::var_os(SHIM_CACHE_PATH_VAR) {
+        if let Err(error) =
+            cache_shim_command(&tool, &command, &args.passthrough, Path::new(&cache_path))
+        {
+            debug!("Failed to cache shim command: {error}");
+        }
 
-    // Update the last used timestamp
-    if env::var("PROTO_SKIP_USED_AT").is_err() {
-        let _ = tool.manifest.track_used_at(tool.get_tool_dir());
+        // Don't leak into child processes that run proto directly
+        command.env_remove(SHIM_CACHE_PATH_VAR);
     }
 
     // Must be the last line!
@@ -0,0 +1,230 @@
+use crate::error::ProtoCliError;
+use crate::helpers::{create_progress_bar, disable_progress_bars, ProtoResource};
+use clap::Args;
+use miette::IntoDiagnostic;
+use proto_core::{Id, UnresolvedVersionSpec};
+use serde::Serialize;
+use starbase::system;
+use starbase_styles::color::{self, OwoStyle};
+use starbase_utils::json;
+use std::env;
+use std::process::{self, Stdio};
+use std::str::FromStr;
+use std::time::Instant;
+use system_env::create_process_command;
+use tracing::debug;
+
+#[derive(Clone, Debug)]
+pub struct MatrixTarget {
+    pub id: Id,
+    pub specs: Vec<UnresolvedVersionSpec>,
+}
+
+impl FromStr for MatrixTarget {
+    type Err = ProtoCliError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let handle_error = || ProtoCliError::InvalidMatrixTarget {
+            target: value.to_owned(),
+        };
+
+        let (id, versions) = value.split_once('@').ok_or_else(handle_error)?;
+        let mut specs = vec![];
+
+        for version in versions.split(',') {
+            let version = version.trim();
+
+            if version.is_empty() {
+                continue;
+            }
+
+            specs.push(UnresolvedVersionSpec::parse(version).map_err(|_| handle_error())?);
+        }
+
+        if specs.is_empty() {
+            return Err(handle_error());
+        }
+
+        Ok(Self {
+            id: Id::new(id).map_err(|_| handle_error())?,
+            specs,
+        })
+    }
+}
+
+#[derive(Args, Clone, Debug)]
+pub struct RunMatrixArgs {
+    #[arg(
+        required = true,
+        help = "ID of tool and a comma separated list of versions (node@18,20,21)"
+    )]
+    target: MatrixTarget,
+
+    #[arg(long, help = "Stop running after the first failure")]
+    fail_fast: bool,
+
+    #[arg(long, help = "Print the matrix in JSON format")]
+    json: bool,
+
+    // Passthrough args (after --)
+    #[arg(
+        last = true,
+        required = true,
+        help = "Command to run for each version of the tool"
+    )]
+    passthrough: Vec<String>,
+}
+
+#[derive(Serialize)]
+pub struct MatrixResult {
+    duration_ms: u128,
+    exit_code: Option<i32>,
+    passed: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stdout: Option<String>,
+    version: String,
+    version_candidate: String,
+}
+
+#[system]
+pub async fn run_matrix(args: ArgsRef<RunMatrixArgs>, proto: ResourceRef<ProtoResource>) {
+    let mut results = vec![];
+
+    for spec in &args.target.specs {
+        let mut tool = proto.load_tool(&args.target.id).await?;
+
+        if tool.disable_progress_bars() {
+            disable_progress_bars();
+        }
+
+        // Install missing versions without pinning them
+        if !tool.is_setup(spec).await? {
+            let pb = create_progress_bar(format!(
+                "Installing {} {}",
+                tool.get_name(),
+                tool.get_resolved_version()
+            ));
+
+            tool.setup(spec, false).await?;
+            tool.locate_executable().await?;
+
+            pb.finish_and_clear();
+        }
+
+        let version = tool.get_resolved_version();
+        let env_prefix = tool.get_env_var_prefix();
+
+        // Prepend the tool's directory so that non-shim executables are found,
+        // while the version variable ensures that shims resolve the same version
+        let mut paths = vec![];
+
+        if let Some(exe_dir) = tool.get_exe_path()?.parent() {
+            paths.push(exe_dir.to_path_buf());
+        }
+
+        if let Some(path) = env::var_os("PATH") {
+            paths.extend(env::split_paths(&path));
+        }
+
+        let mut command = create_process_command(&args.passthrough[0], &args.passthrough[1..]);
+
+        for (key, val) in tool.proto.load_config()?.get_env_vars(Some(&tool.id)) {
+            match val {
+                Some(val) => {
+                    command.env(key, val);
+                }
+                None => {
+                    command.env_remove(key);
+                }
+            };
+        }
+
+        command
+            .env("PATH", env::join_paths(paths).into_diagnostic()?)
+            .env(format!("{env_prefix}_VERSION"), version.to_string())
+            .env("PROTO_SKIP_USED_AT", "true");
+
+        if !args.json {
+            eprintln!(
+                "{} {} {}",
+                OwoStyle::new().bold().style(color::id(tool.get_name())),
+                color::hash(version.to_string()),
+                color::shell(args.passthrough.join(" "))
+            );
+        }
+
+        debug!(
+            tool = tool.id.as_str(),
+            version = version.to_string(),
+            command = ?command,
+            "Running matrix entry"
+        );
+
+        let started = Instant::now();
+
+        // Capture stdout in JSON mode, so that it doesn't corrupt the JSON output
+        let (status, stdout) = if args.json {
+            let output = command
+                .stdin(Stdio::inherit())
+                .stdout(Stdio::piped())
+                .stderr(Stdio::inherit())
+                .output()
+                .into_diagnostic()?;
+
+            (
+                output.status,
+                Some(String::from_utf8_lossy(&output.stdout).to_string()),
+            )
+        } else {
+            (command.status().into_diagnostic()?, None)
+        };
+
+        let passed = status.success();
+
+        results.push(MatrixResult {
+            duration_ms: started.elapsed().as_millis(),
+            exit_code: status.code(),
+            passed,
+            stdout,
+            version: version.to_string(),
+            version_candidate: spec.to_string(),
+        });
+
+        if !passed && args.fail_fast {
+            break;
+        }
+    }
+
+    let failed = results.iter().any(|result| !result.passed);
+
+    if args.json {
+        println!("{}", json::to_string_pretty(&results).into_diagnostic()?);
+    } else {
+        println!();
+
+        for result in &results {
+            println!(
+                "{} {} {} {}",
+                if result.passed {
+                    color::success("pass")
+                } else {
+                    color::failure("fail")
+                },
+                OwoStyle::new().bold().style(color::hash(&result.version)),
+                color::muted("-"),
+                color::muted_light(format!(
+                    "{:.2}s{}",
+                    result.duration_ms as f64 / 1000.0,
+                    match result.exit_code {
+                        Some(code) if !result.passed => format!(", exit code {code}"),
+                        _ => String::new(),
+                    }
+                ))
+            );
+        }
+    }
+
+    if failed {
+        process::exit(1);
+    }
+}
@@ -6,8 +6,9 @@ use clap::Args;
 use clap_complete::Shell;
 use dialoguer::{Input, Select};
 use miette::IntoDiagnostic;
+use proto_core::trigger_host_warning;
 use proto_shim::get_exe_file_name;
-use starbase::system;
+use starbase::{system, SystemResult};
 use starbase_styles::color;
 use starbase_utils::fs;
 use std::env;
@@ -32,7 +33,20 @@ pub struct SetupArgs {
 
 #[system]
 pub async fn setup(args: ArgsRef<SetupArgs>, proto: ResourceRef<ProtoResource>) {
-    let shell = detect_shell(args.shell);
+    if args.profile {
+        trigger_host_warning("setup-profile-arg");
+    }
+
+    internal_setup(proto, args.shell, args.no_profile, args.yes).await?;
+}
+
+pub async fn internal_setup(
+    proto: &ProtoResource,
+    shell: Option<Shell>,
+    no_profile: bool,
+    yes: bool,
+) -> SystemResult {
+    let shell = detect_shell(shell);
     let paths = env::split_paths(&env::var("PATH").unwrap()).collect::<Vec<_>>();
 
     let installed_bin_path = env::var("PROTO_INSTALL_DIR")
@@ -62,7 +76,7 @@ pub async fn setup(args: ArgsRef<SetupArgs>, proto: ResourceRef<ProtoResource>)
     };
 
     // Avoid updating the shell profile
-    if args.no_profile {
+    if no_profile {
         finished_message(installed_bin_path, None, Some(content));
 
         return Ok(());
@@ -72,7 +86,7 @@ pub async fn setup(args: ArgsRef<SetupArgs>, proto: ResourceRef<ProtoResource>)
     debug!("Updating PATH in {} shell", shell);
 
     let profile_path;
-    let interactive = !args.yes && env::var("CI").is_err();
+    let interactive = !yes && env::var("CI").is_err();
 
     println!("Finishing proto installation...");
 
@@ -142,6 +156,8 @@ pub async fn setup(args: ArgsRef<SetupArgs>, proto: ResourceRef<ProtoResource>)
     }
 
     finished_message(installed_bin_path, profile_path, Some(content));
+
+    Ok(())
 }
 
 fn help_message() {
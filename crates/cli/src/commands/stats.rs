@@ -0,0 +1,140 @@
+use crate::helpers::{serialize_optional_timestamp, ProtoResource};
+use crate::printer::{format_value, Printer};
+use clap::Args;
+use miette::IntoDiagnostic;
+use proto_core::{Id, ToolManifest, VersionSpec, MANIFEST_NAME};
+use serde::Serialize;
+use starbase::system;
+use starbase_styles::color;
+use starbase_utils::{fs, json};
+use std::collections::BTreeMap;
+use tracing::info;
+
+#[derive(Args, Clone, Debug)]
+pub struct StatsArgs {
+    #[arg(help = "ID of tools to include")]
+    ids: Vec<Id>,
+
+    #[arg(long, help = "Print the stats in JSON format")]
+    json: bool,
+}
+
+#[derive(Serialize)]
+pub struct VersionStats {
+    #[serde(serialize_with = "serialize_optional_timestamp")]
+    last_used_at: Option<u128>,
+    run_count: u64,
+}
+
+#[derive(Serialize)]
+pub struct ToolStats {
+    run_count: u64,
+    versions: BTreeMap<VersionSpec, VersionStats>,
+}
+
+#[system]
+pub async fn stats(args: ArgsRef<StatsArgs>, proto: ResourceRef<ProtoResource>) {
+    let config = proto.env.load_config()?;
+    let mut stats = BTreeMap::<Id, ToolStats>::new();
+
+    if proto.env.tools_dir.exists() {
+        for entry in fs::read_dir(&proto.env.tools_dir)? {
+            let inventory_dir = entry.path();
+            let id = Id::raw(fs::file_name(&inventory_dir));
+
+            if !inventory_dir.join(MANIFEST_NAME).exists()
+                || (!args.ids.is_empty() && !args.ids.contains(&id))
+            {
+                continue;
+            }
+
+            // Aggregate buffered runs into the manifest
+            let manifest_lock = ToolManifest::lock_from(&inventory_dir)?;
+            let mut manifest = ToolManifest::load_from(&inventory_dir)?;
+
+            if manifest.flush_runs()? {
+                manifest.save()?;
+            }
+
+            drop(manifest_lock);
+
+            let mut tool_stats = ToolStats {
+                run_count: 0,
+                versions: BTreeMap::new(),
+            };
+
+            for version in &manifest.installed_versions {
+                let run_count = manifest
+                    .versions
+                    .get(version)
+                    .map(|meta| meta.run_count)
+                    .unwrap_or_default();
+
+                tool_stats.run_count += run_count;
+                tool_stats.versions.insert(
+                    version.to_owned(),
+                    VersionStats {
+                        last_used_at: manifest
+                            .load_used_at(inventory_dir.join(version.to_string()))
+                            .ok()
+                            .flatten(),
+                        run_count,
+                    },
+                );
+            }
+
+            stats.insert(id, tool_stats);
+        }
+    }
+
+    if args.json {
+        println!("{}", json::to_string_pretty(&stats).into_diagnostic()?);
+
+        return Ok(());
+    }
+
+    if !config.settings.track_runs {
+        info!(
+            "Run counts are not being tracked, enable them with the {} setting",
+            color::property("track-runs")
+        );
+    }
+
+    if stats.is_empty() {
+        info!("No tools have been installed");
+
+        return Ok(());
+    }
+
+    let mut printer = Printer::new().with_date_format(config.settings.ui.date_format.clone());
+
+    for (id, tool_stats) in stats {
+        printer.line();
+        printer.header(&id, format!("{} runs", tool_stats.run_count));
+
+        printer.section(|p| {
+            let items = tool_stats
+                .versions
+                .iter()
+                .map(|(version, version_stats)| {
+                    let mut comments = vec![format!("{} runs", version_stats.run_count)];
+
+                    if let Some(at) = version_stats.last_used_at.and_then(|at| p.date(at)) {
+                        comments.push(format!("last used {at}"));
+                    }
+
+                    (
+                        color::hash(version.to_string()),
+                        format_value(comments.join(", ")),
+                    )
+                })
+                .collect::<Vec<_>>();
+
+            p.entry_map("Versions", items, None);
+
+            Ok(())
+        })?;
+    }
+
+    printer.flush();
+}
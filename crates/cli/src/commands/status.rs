@@ -0,0 +1,292 @@
+use crate::helpers::ProtoResource;
+use crate::template::render_template;
+use clap::Args;
+use miette::IntoDiagnostic;
+use proto_core::{satisfies_version, Id, Tool, UnresolvedVersionSpec};
+use rustc_hash::FxHashSet;
+use serde::Serialize;
+use starbase::system;
+use starbase_styles::color;
+use starbase_utils::json;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::process;
+use tracing::debug;
+
+#[derive(Args, Clone, Debug)]
+pub struct StatusArgs {
+    #[arg(
+        long,
+        help = "Only print problems, and exit with a non-zero code if any are found"
+    )]
+    check: bool,
+
+    #[arg(
+        long,
+        conflicts_with_all = ["check", "json"],
+        help = "Print each tool on its own line using a template, for example \"{{id}} {{version}}\""
+    )]
+    format: Option<String>,
+
+    #[arg(
+        long,
+        conflicts_with = "check",
+        help = "Print the status in JSON format"
+    )]
+    json: bool,
+}
+
+#[derive(Serialize)]
+pub struct StatusItem {
+    pub config_file: Option<PathBuf>,
+    pub exe_path: Option<PathBuf>,
+    pub id: Id,
+    pub installed: bool,
+    pub problems: Vec<String>,
+    pub resolved_version: Option<String>,
+    pub version: UnresolvedVersionSpec,
+}
+
+#[derive(Serialize)]
+pub struct StatusOutput<'a> {
+    pub problems: &'a [String],
+    pub tools: BTreeMap<&'a str, &'a StatusItem>,
+}
+
+/// Return true if 2 version specs can be satisfied by the same version.
+/// When neither is fully-qualified, they must be identical.
+fn specs_agree(pin: &UnresolvedVersionSpec, other: &UnresolvedVersionSpec) -> bool {
+    match (pin, other) {
+        (_, UnresolvedVersionSpec::Version(version)) => satisfies_version(pin, version, true),
+        (UnresolvedVersionSpec::Version(version), _) => satisfies_version(other, version, true),
+        _ => pin == other,
+    }
+}
+
+async fn check_tool(
+    tool: &mut Tool,
+    version: &UnresolvedVersionSpec,
+    config_file: Option<&Path>,
+    dirs: &[PathBuf],
+) -> miette::Result<StatusItem> {
+    let mut item = StatusItem {
+        config_file: config_file.map(|file| file.to_path_buf()),
+        exe_path: None,
+        id: tool.id.clone(),
+        installed: false,
+        problems: vec![],
+        resolved_version: None,
+        version: version.to_owned(),
+    };
+
+    // Resolve without side effects (no shims or bins)
+    match tool.resolve_version(version, true).await {
+        Ok(_) => {
+            item.resolved_version = Some(tool.get_resolved_version().to_string());
+            item.installed = tool.is_installed();
+
+            // The executable that a shim would run
+            if item.installed {
+                match tool.locate_executable().await {
+                    Ok(_) => {
+                        item.exe_path = tool.get_exe_path().ok().map(|path| path.to_path_buf());
+                    }
+                    Err(error) => {
+                        debug!(
+                            tool = tool.id.as_str(),
+                            "Failed to locate executable: {error}"
+                        );
+                    }
+                };
+            } else {
+                item.problems.push(format!(
+                    "{} {} is not installed, run {}",
+                    tool.get_name(),
+                    color::hash(tool.get_resolved_version().to_string()),
+                    color::shell(format!("proto install {} {}", tool.id, version))
+                ));
+            }
+        }
+        Err(error) => {
+            debug!(
+                tool = tool.id.as_str(),
+                "Failed to resolve version: {error}"
+            );
+
+            item.problems.push(format!(
+                "{} {} could not be resolved to an available version",
+                tool.get_name(),
+                color::hash(version.to_string())
+            ));
+        }
+    };
+
+    // Ecosystem files (.nvmrc, etc) should agree with the pin
+    for dir in dirs {
+        if let Some((eco_version, eco_file)) = tool.detect_version_from(dir).await? {
+            if !specs_agree(version, &eco_version) {
+                item.problems.push(format!(
+                    "{} {} is pinned, but {} requires {}",
+                    tool.get_name(),
+                    color::hash(version.to_string()),
+                    color::path(eco_file),
+                    color::hash(eco_version.to_string())
+                ));
+            }
+        }
+    }
+
+    Ok(item)
+}
+
+/// Check every tool pinned in the loaded configs, and return the status of each
+/// tool (keyed by name), and a list of all problems found.
+pub async fn collect_status(
+    proto: &ProtoResource,
+) -> miette::Result<(Vec<(String, StatusItem)>, Vec<String>)> {
+    let config = proto.env.load_config()?;
+    let config_manager = proto.env.load_config_manager()?;
+    let mut items = vec![];
+    let mut problems = vec![];
+
+    // Plugins must be configured for every pinned tool
+    let mut filter = FxHashSet::default();
+
+    for id in config.versions.keys() {
+        if id == "proto" {
+            continue;
+        }
+
+        if config.plugins.contains_key(id) {
+            filter.insert(id);
+        } else {
+            problems.push(format!(
+                "{} is pinned, but no plugin has been configured, run {}",
+                color::id(id),
+                color::shell(format!("proto plugin add {id} <locator>"))
+            ));
+        }
+    }
+
+    if !filter.is_empty() {
+        let tools = proto.load_tools_with_filters(filter).await?;
+
+        // Report failed plugins here, instead of at shutdown
+        for (id, error) in std::mem::take(&mut *proto.failed_plugins.lock().unwrap()) {
+            problems.push(format!(
+                "{} plugin failed to load: {}",
+                color::id(id),
+                color::muted_light(error)
+            ));
+        }
+
+        for mut tool in tools {
+            let Some(version) = config.versions.get(&tool.id) else {
+                continue;
+            };
+
+            // Check the current directory, and the directory of the pinning config
+            let mut dirs = vec![proto.env.cwd.clone()];
+
+            let config_file = config_manager.files.iter().find(|file| {
+                file.config
+                    .versions
+                    .as_ref()
+                    .is_some_and(|versions| versions.contains_key(&tool.id))
+            });
+
+            if let Some(file) = config_file {
+                if let Some(dir) = file.path.parent() {
+                    if !dirs.iter().any(|d| d == dir) {
+                        dirs.push(dir.to_path_buf());
+                    }
+                }
+            }
+
+            let item = check_tool(
+                &mut tool,
+                version,
+                config_file.map(|file| file.path.as_path()),
+                &dirs,
+            )
+            .await?;
+
+            problems.extend(item.problems.clone());
+            items.push((tool.get_name().to_owned(), item));
+        }
+    }
+
+    Ok((items, problems))
+}
+
+#[system]
+pub async fn status(args: ArgsRef<StatusArgs>, proto: ResourceRef<ProtoResource>) {
+    let (mut items, problems) = collect_status(proto).await?;
+
+    if args.json {
+        let output = StatusOutput {
+            problems: &problems,
+            tools: items
+                .iter()
+                .map(|(_, item)| (item.id.as_str(), item))
+                .collect(),
+        };
+
+        println!("{}", json::to_string_pretty(&output).into_diagnostic()?);
+
+        return Ok(());
+    }
+
+    items.sort_by(|a, b| a.1.id.cmp(&b.1.id));
+
+    if let Some(format) = &args.format {
+        for (_, item) in &items {
+            println!("{}", render_template(format, item.id.as_str(), item)?);
+        }
+
+        return Ok(());
+    }
+
+    if !args.check {
+        for (name, item) in &items {
+            println!(
+                "{} {} {}",
+                if item.problems.is_empty() {
+                    color::success("✔")
+                } else {
+                    color::failure("✘")
+                },
+                color::id(name),
+                color::muted_light(format!(
+                    "{}{}",
+                    item.version,
+                    match &item.resolved_version {
+                        Some(resolved) if item.installed => format!(" -> {resolved} (installed)"),
+                        Some(resolved) => format!(" -> {resolved} (not installed)"),
+                        None => String::new(),
+                    }
+                ))
+            );
+
+            if let Some(file) = &item.config_file {
+                println!("  {} {}", color::muted("pinned in"), color::path(file));
+            }
+
+            if let Some(exe) = &item.exe_path {
+                println!("  {} {}", color::muted("runs"), color::path(exe));
+            }
+        }
+
+        if !problems.is_empty() {
+            println!();
+        }
+    }
+
+    for problem in &problems {
+        println!("{} {problem}", color::failure("✘"));
+    }
+
+    if args.check && !problems.is_empty() {
+        process::exit(1);
+    }
+}
@@ -0,0 +1,143 @@
+use crate::helpers::{create_theme, ProtoResource};
+use crate::shell::{find_profiles, remove_managed_blocks};
+use clap::Args;
+use clap_complete::Shell;
+use dialoguer::Confirm;
+use miette::IntoDiagnostic;
+use proto_core::remove_bin_file;
+use proto_shim::get_exe_file_name;
+use starbase::system;
+use starbase_styles::color;
+use starbase_utils::fs;
+use std::env;
+use std::path::PathBuf;
+use tracing::debug;
+
+#[derive(Args, Clone, Debug)]
+pub struct TeardownArgs {
+    #[arg(
+        long,
+        help = "Also delete the entire store, including installed tools and plugins"
+    )]
+    purge: bool,
+
+    #[arg(long, short = 'y', help = "Avoid and force confirm prompts")]
+    yes: bool,
+}
+
+fn find_all_profiles(proto: &ProtoResource) -> miette::Result<Vec<PathBuf>> {
+    let mut profiles = vec![];
+
+    // The profile that was chosen during setup
+    let setup_profile = proto.env.root.join("profile");
+
+    if setup_profile.exists() {
+        profiles.push(PathBuf::from(fs::read_file(setup_profile)?.trim()));
+    }
+
+    for shell in [
+        Shell::Bash,
+        Shell::Elvish,
+        Shell::Fish,
+        Shell::PowerShell,
+        Shell::Zsh,
+    ] {
+        for profile in find_profiles(&shell)? {
+            if profile.exists() && !profiles.contains(&profile) {
+                profiles.push(profile);
+            }
+        }
+    }
+
+    Ok(profiles)
+}
+
+#[system]
+pub async fn teardown(args: ArgsRef<TeardownArgs>, proto: ResourceRef<ProtoResource>) {
+    if !args.yes
+        && env::var("CI").is_err()
+        && !Confirm::with_theme(&create_theme())
+            .with_prompt(if args.purge {
+                format!(
+                    "Remove proto from your system, and delete {}?",
+                    color::path(&proto.env.root)
+                )
+            } else {
+                "Remove proto shims, bins, and shell profile changes from your system?".into()
+            })
+            .interact()
+            .into_diagnostic()?
+    {
+        return Ok(());
+    }
+
+    let mut removed = vec![];
+
+    // Remove all shims
+    if proto.env.shims_dir.exists() {
+        debug!(shims_dir = ?proto.env.shims_dir, "Removing shims");
+
+        fs::remove_dir_all(&proto.env.shims_dir)?;
+        removed.push(format!("Shims in {}", color::path(&proto.env.shims_dir)));
+    }
+
+    // Remove all bins, but keep proto itself unless purging
+    let proto_bins = [get_exe_file_name("proto"), get_exe_file_name("proto-shim")];
+
+    if proto.env.bin_dir.exists() {
+        debug!(bin_dir = ?proto.env.bin_dir, "Removing bins");
+
+        for entry in fs::read_dir(&proto.env.bin_dir)? {
+            let path = entry.path();
+
+            if proto_bins.contains(&fs::file_name(&path)) {
+                continue;
+            }
+
+            remove_bin_file(&path)?;
+            removed.push(format!("Bin {}", color::path(path)));
+        }
+    }
+
+    // Remove exports from shell profiles
+    for profile in find_all_profiles(proto)? {
+        let ids = remove_managed_blocks(&profile)?;
+
+        if !ids.is_empty() {
+            removed.push(format!(
+                "Exports for {} from {}",
+                ids.join(", "),
+                color::path(profile)
+            ));
+        }
+    }
+
+    // Remove everything else
+    if args.purge && proto.env.root.exists() {
+        debug!(store = ?proto.env.root, "Purging store");
+
+        fs::remove_dir_all(&proto.env.root)?;
+        removed.push(format!("Store {}", color::path(&proto.env.root)));
+    }
+
+    if removed.is_empty() {
+        println!("Nothing to remove, proto has already been torn down!");
+
+        return Ok(());
+    }
+
+    println!("Removed the following:");
+
+    for item in removed {
+        println!("  {} {item}", color::muted("-"));
+    }
+
+    if !args.purge {
+        println!();
+        println!(
+            "Installed tools and plugins are still in {}, pass {} to delete them.",
+            color::path(&proto.env.root),
+            color::shell("--purge")
+        );
+    }
+}
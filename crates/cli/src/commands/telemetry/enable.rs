@@ -0,0 +1,15 @@
+use crate::helpers::ProtoResource;
+use crate::telemetry::set_telemetry_consent;
+use starbase::system;
+use starbase_styles::color;
+use tracing::info;
+
+#[system]
+pub async fn enable(proto: ResourceRef<ProtoResource>) {
+    let config_path = set_telemetry_consent(&proto.env, true)?;
+
+    info!(
+        "Enabled anonymous usage metrics in {}",
+        color::path(config_path)
+    );
+}
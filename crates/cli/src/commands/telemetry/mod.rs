@@ -0,0 +1,7 @@
+mod disable;
+mod enable;
+mod status;
+
+pub use disable::*;
+pub use enable::*;
+pub use status::*;
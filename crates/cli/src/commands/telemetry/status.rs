@@ -0,0 +1,59 @@
+use crate::helpers::ProtoResource;
+use crate::printer::Printer;
+use proto_core::PROTO_CONFIG_NAME;
+use starbase::system;
+use starbase_styles::color;
+use std::env;
+
+fn format_toggle(enabled: bool) -> String {
+    if enabled {
+        color::success("on")
+    } else {
+        color::failure("off")
+    }
+}
+
+#[system]
+pub async fn status(proto: ResourceRef<ProtoResource>) {
+    let config = proto.env.load_config()?;
+    let settings = &config.settings.telemetry;
+
+    let mut printer = Printer::new();
+
+    printer.line();
+    printer.header(
+        "telemetry",
+        match settings.enabled {
+            Some(true) => "Anonymous usage metrics are enabled",
+            Some(false) => "Anonymous usage metrics are disabled",
+            None => "Consent has not been given, so no metrics are sent",
+        },
+    );
+
+    printer.section(|p| {
+        p.entry(
+            "Configured by",
+            if env::var("PROTO_TELEMETRY").is_ok() {
+                color::property("PROTO_TELEMETRY")
+            } else {
+                color::path(proto.env.get_config_dir(true).join(PROTO_CONFIG_NAME))
+            },
+        );
+
+        p.named_section("Metrics", |p| {
+            p.entry("install-tool", format_toggle(settings.install_tool));
+            p.entry(
+                "integrity-failure",
+                format_toggle(settings.integrity_failure),
+            );
+            p.entry("uninstall-tool", format_toggle(settings.uninstall_tool));
+            p.entry("upgrade-proto", format_toggle(settings.upgrade_proto));
+
+            Ok(())
+        })?;
+
+        Ok(())
+    })?;
+
+    printer.flush();
+}
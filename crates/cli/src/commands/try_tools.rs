@@ -0,0 +1,200 @@
+use crate::commands::run::RunTarget;
+use crate::helpers::{create_progress_bar, disable_progress_bars, ProtoResource};
+use clap::Args;
+use miette::IntoDiagnostic;
+use proto_core::{detect_version, ProtoConfig, Tool, ToolManifest};
+use starbase::system;
+use starbase_styles::color::{self, OwoStyle};
+use std::env;
+use std::process::{self, Command};
+use system_env::create_process_command;
+use tracing::{debug, warn};
+
+#[derive(Args, Clone, Debug)]
+pub struct TryArgs {
+    #[arg(
+        required = true,
+        help = "ID of tools, with an optional version (node@20 npm@10)"
+    )]
+    targets: Vec<RunTarget>,
+
+    #[arg(long, help = "Keep versions that were installed for the session")]
+    keep: bool,
+
+    // Passthrough args (after --)
+    #[arg(last = true, help = "Command to run instead of spawning a shell")]
+    passthrough: Vec<String>,
+}
+
+/// A version that was installed for the session, and the state
+/// required to remove it once the session ends.
+struct EphemeralInstall {
+    tool: Tool,
+    manifest: ToolManifest,
+    pinned: bool,
+}
+
+fn create_shell_command() -> Command {
+    #[cfg(unix)]
+    let shell = env::var("SHELL").unwrap_or_else(|_| "sh".into());
+
+    #[cfg(windows)]
+    let shell = env::var("COMSPEC").unwrap_or_else(|_| "cmd.exe".into());
+
+    Command::new(shell)
+}
+
+/// Wait for the session to end, while ignoring interrupts, as they're
+/// meant for the child process, and we still need to clean up after it.
+async fn wait_for_session(command: Command) -> miette::Result<Option<i32>> {
+    let mut child = tokio::process::Command::from(command)
+        .spawn()
+        .into_diagnostic()?;
+
+    loop {
+        tokio::select! {
+            status = child.wait() => {
+                return Ok(status.into_diagnostic()?.code());
+            }
+            _ = tokio::signal::ctrl_c() => {
+                continue;
+            }
+        };
+    }
+}
+
+#[system]
+pub async fn try_tools(args: ArgsRef<TryArgs>, proto: ResourceRef<ProtoResource>) {
+    let global_config = ProtoConfig::load_from(proto.env.get_config_dir(true), false)?;
+    let mut ephemeral = vec![];
+    let mut labels = vec![];
+    let mut paths = vec![];
+    let mut vars = vec![];
+
+    for target in &args.targets {
+        let mut tool = proto.load_tool(&target.id).await?;
+
+        if tool.disable_progress_bars() {
+            disable_progress_bars();
+        }
+
+        let spec = detect_version(&tool, target.spec.clone()).await?;
+
+        // Install missing versions without pinning them,
+        // and remember them so they can be removed afterwards
+        let mut rollback = None;
+
+        if !tool.is_setup(&spec).await? {
+            let manifest = tool.manifest.clone();
+            let pinned = !global_config
+                .versions
+                .as_ref()
+                .is_some_and(|versions| versions.contains_key(&tool.id));
+
+            let pb = create_progress_bar(format!(
+                "Installing {} {}",
+                tool.get_name(),
+                tool.get_resolved_version()
+            ));
+
+            tool.setup(&spec, false).await?;
+            tool.locate_executable().await?;
+
+            pb.finish_and_clear();
+
+            if !args.keep {
+                rollback = Some((manifest, pinned));
+            }
+        }
+
+        let version = tool.get_resolved_version();
+
+        if let Some(exe_dir) = tool.get_exe_path()?.parent() {
+            paths.push(exe_dir.to_path_buf());
+        }
+
+        for (key, dir) in tool.get_root_env_vars() {
+            vars.push((key, Some(dir.to_string_lossy().to_string())));
+        }
+
+        vars.push((
+            format!("{}_VERSION", tool.get_env_var_prefix()),
+            Some(version.to_string()),
+        ));
+        vars.extend(tool.proto.load_config()?.get_env_vars(Some(&tool.id)));
+
+        labels.push(format!("{}@{}", tool.id, version));
+
+        if let Some((manifest, pinned)) = rollback {
+            ephemeral.push(EphemeralInstall {
+                tool,
+                manifest,
+                pinned,
+            });
+        }
+    }
+
+    // Prepend the tool directories so that non-shim executables are found,
+    // while the version variables ensure that shims resolve the same versions
+    if let Some(path) = env::var_os("PATH") {
+        paths.extend(env::split_paths(&path));
+    }
+
+    let mut command = if args.passthrough.is_empty() {
+        eprintln!(
+            "Entering a session with {}, exit the shell to return",
+            labels
+                .iter()
+                .map(|label| OwoStyle::new().bold().style(color::id(label)).to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+
+        create_shell_command()
+    } else {
+        create_process_command(&args.passthrough[0], &args.passthrough[1..])
+    };
+
+    for (key, val) in vars {
+        match val {
+            Some(val) => {
+                command.env(key, val);
+            }
+            None => {
+                command.env_remove(key);
+            }
+        };
+    }
+
+    command
+        .env("PATH", env::join_paths(paths).into_diagnostic()?)
+        .env("PROTO_TRY", labels.join(","));
+
+    debug!(command = ?command, "Starting try session");
+
+    let exit_code = wait_for_session(command).await?;
+
+    for mut install in ephemeral {
+        debug!(
+            tool = install.tool.id.as_str(),
+            version = install.tool.get_resolved_version().to_string(),
+            "Removing version installed for the session"
+        );
+
+        if let Err(error) = install
+            .tool
+            .rollback_install(install.manifest, install.pinned)
+        {
+            warn!(
+                tool = install.tool.id.as_str(),
+                "Failed to remove version installed for the session: {error}"
+            );
+        }
+    }
+
+    if let Some(code) = exit_code {
+        if code != 0 {
+            process::exit(code);
+        }
+    }
+}
@@ -1,76 +1,235 @@
 use crate::commands::clean::purge_tool;
+use crate::commands::run::RunTarget;
+use crate::error::ProtoCliError;
 use crate::helpers::{create_progress_bar, disable_progress_bars, ProtoResource};
 use crate::telemetry::{track_usage, Metric};
 use clap::Args;
-use proto_core::{Id, Tool, UnresolvedVersionSpec};
+use proto_core::{Id, ProtoConfig, Tool, ToolManifest, UnresolvedVersionSpec, MANIFEST_NAME};
+use rustc_hash::FxHashSet;
 use starbase::system;
+use starbase_utils::fs;
+use std::collections::BTreeMap;
+use std::str::FromStr;
 use tracing::{debug, info};
 
 #[derive(Args, Clone, Debug)]
 pub struct UninstallArgs {
-    #[arg(required = true, help = "ID of tool")]
-    id: Id,
+    #[arg(
+        required = true,
+        help = "ID of tools, with an optional version (node@20), or an ID followed by a version or alias"
+    )]
+    targets: Vec<String>,
 
-    #[arg(help = "Version or alias of tool")]
-    semver: Option<UnresolvedVersionSpec>,
+    #[arg(long, help = "Uninstall all versions of each tool")]
+    all_versions: bool,
+
+    #[arg(
+        long,
+        help = "Uninstall versions required by other tools, and remove unmanaged versions from the inventory without deleting the installation"
+    )]
+    force: bool,
 
     #[arg(long, help = "Avoid and force confirm prompts")]
     yes: bool,
 }
 
-#[system]
-pub async fn uninstall(args: ArgsRef<UninstallArgs>, proto: ResourceRef<ProtoResource>) {
-    // Uninstall everything
-    let Some(spec) = &args.semver else {
-        let tool = purge_tool(proto, &args.id, args.yes).await?;
-
-        // Track usage metrics
-        track_uninstall(&tool, true).await?;
+/// Parse the targets into tool IDs and optional versions, while supporting the
+/// original `<id> <version>` form, unless the version is also a tool.
+fn parse_targets(
+    targets: &[String],
+    all_versions: bool,
+    config: &ProtoConfig,
+) -> Result<Vec<(Id, Option<UnresolvedVersionSpec>)>, ProtoCliError> {
+    if let [id, spec] = targets {
+        if !all_versions
+            && !id.contains('@')
+            && !spec.contains('@')
+            && !config.plugins.contains_key(spec.as_str())
+        {
+            let handle_error = || ProtoCliError::InvalidRunTarget {
+                target: format!("{id} {spec}"),
+            };
+
+            return Ok(vec![(
+                Id::new(id).map_err(|_| handle_error())?,
+                Some(UnresolvedVersionSpec::parse(spec).map_err(|_| handle_error())?),
+            )]);
+        }
+    }
 
-        return Ok(());
-    };
+    let mut parsed = vec![];
 
-    // Uninstall a tool by version
-    let mut tool = proto.load_tool(&args.id).await?;
+    for target in targets {
+        let run_target = RunTarget::from_str(target)?;
 
-    if !tool.is_setup(spec).await? {
-        info!(
-            "{} {} does not exist!",
-            tool.get_name(),
-            tool.get_resolved_version(),
-        );
+        if all_versions && run_target.spec.is_some() {
+            return Err(ProtoCliError::UninstallAllVersionsConflict {
+                target: target.to_owned(),
+            });
+        }
 
-        return Ok(());
+        parsed.push((run_target.id, run_target.spec));
     }
 
-    debug!("Uninstalling {} with version {}", tool.get_name(), spec);
+    Ok(parsed)
+}
 
-    if tool.disable_progress_bars() {
-        disable_progress_bars();
+/// Find installed tools that require any of the provided tools at runtime,
+/// excluding tools that are also being uninstalled. Returns a mapping of
+/// each required tool to the names of the tools that require it.
+async fn find_dependents(
+    proto: &ProtoResource,
+    removing: &[&Id],
+) -> miette::Result<BTreeMap<Id, Vec<String>>> {
+    let mut dependents = BTreeMap::<Id, Vec<String>>::new();
+    let mut candidates = vec![];
+
+    if proto.env.tools_dir.exists() {
+        for entry in fs::read_dir(&proto.env.tools_dir)? {
+            let inventory_dir = entry.path();
+            let id = Id::raw(fs::file_name(&inventory_dir));
+
+            if removing.contains(&&id) || !inventory_dir.join(MANIFEST_NAME).exists() {
+                continue;
+            }
+
+            if !ToolManifest::load_from(&inventory_dir)?
+                .installed_versions
+                .is_empty()
+            {
+                candidates.push(id);
+            }
+        }
     }
 
-    let pb = create_progress_bar(format!(
-        "Uninstalling {} {}",
-        tool.get_name(),
-        tool.get_resolved_version()
-    ));
+    if candidates.is_empty() {
+        return Ok(dependents);
+    }
 
-    let uninstalled = tool.teardown().await?;
+    for tool in proto
+        .load_tools_with_filters(FxHashSet::from_iter(&candidates))
+        .await?
+    {
+        for required in &tool.metadata.requires {
+            if let Some(id) = removing.iter().find(|id| id.as_str() == required) {
+                dependents
+                    .entry((*id).to_owned())
+                    .or_default()
+                    .push(tool.get_name().to_owned());
+            }
+        }
+    }
 
-    pb.finish_and_clear();
+    Ok(dependents)
+}
+
+#[system]
+pub async fn uninstall(args: ArgsRef<UninstallArgs>, proto: ResourceRef<ProtoResource>) {
+    let config = proto.env.load_config()?;
+    let mut plan = vec![];
+
+    for (id, spec) in parse_targets(&args.targets, args.all_versions, config)? {
+        let mut tool = proto.load_tool(&id).await?;
+
+        // Uninstall everything
+        let Some(spec) = spec else {
+            plan.push((tool, None, true));
+
+            continue;
+        };
+
+        // Uninstall a tool by version
+        if !tool.is_setup(&spec).await? {
+            info!(
+                "{} {} does not exist!",
+                tool.get_name(),
+                tool.get_resolved_version(),
+            );
+
+            continue;
+        }
+
+        if let Some(unmanaged_dir) = tool.get_unmanaged_dir() {
+            if !args.force {
+                return Err(ProtoCliError::UnmanagedToolVersion {
+                    tool: tool.get_name().to_owned(),
+                    version: tool.get_resolved_version().to_string(),
+                    path: unmanaged_dir.to_path_buf(),
+                }
+                .into());
+            }
+        }
+
+        let removes_last = tool
+            .manifest
+            .installed_versions
+            .iter()
+            .all(|version| *version == tool.get_resolved_version());
+
+        plan.push((tool, Some(spec), removes_last));
+    }
 
-    if !uninstalled {
-        return Ok(());
+    // Refuse to remove the last version of a tool that others require
+    if !args.force {
+        let removing = plan
+            .iter()
+            .filter(|(_, _, removes_last)| *removes_last)
+            .map(|(tool, _, _)| &tool.id)
+            .collect::<Vec<_>>();
+
+        if !removing.is_empty() {
+            if let Some((id, names)) = find_dependents(proto, &removing).await?.pop_first() {
+                return Err(ProtoCliError::ToolRequiredByOthers {
+                    tool: id.to_string(),
+                    dependents: names.join(", "),
+                }
+                .into());
+            }
+        }
     }
 
-    // Track usage metrics
-    track_uninstall(&tool, false).await?;
+    for (mut tool, spec, _) in plan {
+        let Some(spec) = spec else {
+            let tool = purge_tool(proto, &tool.id, args.yes).await?;
 
-    info!(
-        "{} {} has been uninstalled!",
-        tool.get_name(),
-        tool.get_resolved_version(),
-    );
+            // Track usage metrics
+            track_uninstall(&tool, true).await?;
+
+            continue;
+        };
+
+        debug!("Uninstalling {} with version {}", tool.get_name(), spec);
+
+        if tool.disable_progress_bars() {
+            disable_progress_bars();
+        }
+
+        let pb = create_progress_bar(format!(
+            "Uninstalling {} {}",
+            tool.get_name(),
+            tool.get_resolved_version()
+        ));
+
+        let uninstalled = tool.teardown().await?;
+
+        pb.finish_and_clear();
+
+        if !uninstalled {
+            continue;
+        }
+
+        // Track usage metrics
+        track_uninstall(&tool, false).await?;
+
+        // Run after hook
+        tool.run_script_hook("post-uninstall")?;
+
+        info!(
+            "{} {} has been uninstalled!",
+            tool.get_name(),
+            tool.get_resolved_version(),
+        );
+    }
 }
 
 async fn track_uninstall(tool: &Tool, purged: bool) -> miette::Result<()> {
@@ -1,85 +1,325 @@
 use crate::error::ProtoCliError;
-use crate::helpers::{fetch_latest_version, ProtoResource};
+use crate::helpers::{create_progress_bar, fetch_latest_version, ProtoResource};
+use crate::logging::{create_progress_logger, is_json_format};
 use crate::telemetry::{track_usage, Metric};
+use clap::Args;
 use indicatif::{ProgressBar, ProgressStyle};
 use proto_core::is_offline;
-use proto_installer::{determine_triple, download_release, unpack_release};
+use proto_installer::{
+    determine_triple, download_release, has_stored_release, restore_release,
+    unpack_release_with_progress, verify_release, StagedUpgrade, UnpackPhase, VerifyOptions,
+};
 use semver::Version;
 use starbase::system;
 use starbase_styles::color;
-use tracing::{debug, info, trace};
+use starbase_utils::fs;
+use std::env;
+use std::path::Path;
+use tracing::{debug, info, trace, warn};
 
-#[system]
-pub async fn upgrade(proto: ResourceRef<ProtoResource>) {
-    if is_offline() {
-        return Err(ProtoCliError::UpgradeRequiresInternet.into());
+#[derive(Args, Clone, Debug)]
+pub struct UpgradeArgs {
+    #[arg(
+        long,
+        help = "List previously installed versions that can be switched to",
+        conflicts_with_all = ["rollback", "to"]
+    )]
+    list: bool,
+
+    #[arg(
+        long,
+        alias = "repairifier",
+        help = "Complete or roll back a previous upgrade that was interrupted",
+        conflicts_with_all = ["list", "rollback", "to"]
+    )]
+    repair: bool,
+
+    #[arg(
+        long,
+        help = "Roll back to the previously installed version",
+        conflicts_with = "to"
+    )]
+    rollback: bool,
+
+    #[arg(
+        long,
+        help = "Skip verifying the checksum and signature of the downloaded release"
+    )]
+    skip_verify: bool,
+
+    #[arg(
+        long,
+        help = "Upgrade or downgrade to a specific version",
+        value_name = "VERSION"
+    )]
+    to: Option<Version>,
+}
+
+/// Return versions of proto whose binaries were stored when upgrading,
+/// sorted from highest to lowest.
+fn get_stored_versions(proto_dir: &Path) -> miette::Result<Vec<Version>> {
+    let mut versions = vec![];
+
+    if !proto_dir.exists() {
+        return Ok(versions);
     }
 
-    let current_version = env!("CARGO_PKG_VERSION");
-    let latest_version = fetch_latest_version().await?;
+    for entry in fs::read_dir(proto_dir)? {
+        let path = entry.path();
 
-    debug!(
-        "Comparing latest version {} to current version {}",
-        color::hash(&latest_version),
-        color::hash(current_version),
+        if let Ok(version) = Version::parse(&fs::file_name(&path)) {
+            if path.is_dir() && has_stored_release(&path) {
+                versions.push(version);
+            }
+        }
+    }
+
+    versions.sort_by(|a, d| d.cmp(a));
+
+    Ok(versions)
+}
+
+/// Complete an upgrade that was interrupted while replacing binaries,
+/// or roll it back if the new binaries are missing or broken.
+fn repair_upgrade(staged: StagedUpgrade) -> miette::Result<()> {
+    warn!(
+        "Found an interrupted upgrade to proto v{}, repairing",
+        staged.version
     );
 
-    if Version::parse(&latest_version).unwrap() <= Version::parse(current_version).unwrap() {
-        info!("You're already on the latest version of proto!");
+    if staged.complete()? && verify_release(&staged.bin_dir, &staged.version).is_ok() {
+        staged.clear()?;
+
+        info!("Completed the upgrade to proto v{}!", staged.version);
 
         return Ok(());
     }
 
-    // Determine the download file based on target
-    let triple_target = determine_triple()?;
-
-    debug!("Download target: {}", triple_target);
-
-    // Download the file and show a progress bar
-    let pb = ProgressBar::new(0);
-    pb.set_style(ProgressStyle::default_bar().progress_chars("━╾─").template(
-        "{bar:80.183/black} | {bytes:.239} / {total_bytes:.248} | {bytes_per_sec:.183} | eta {eta}",
-    ).unwrap());
-
-    let result = download_release(
-        &triple_target,
-        &latest_version,
-        &proto.env.temp_dir,
-        |downloaded_size, total_size| {
-            if downloaded_size == 0 {
-                pb.set_length(total_size);
-            } else {
-                pb.set_position(downloaded_size);
-            }
+    debug!("Unable to complete the upgrade, rolling back");
 
-            trace!("Downloaded {} of {} bytes", downloaded_size, total_size);
-        },
-    )
-    .await?;
+    if staged.roll_back()? {
+        staged.clear()?;
+
+        info!("Rolled back the upgrade to proto v{}", staged.version);
+
+        return Ok(());
+    }
+
+    Err(ProtoCliError::UpgradeRepairFailed {
+        version: staged.version,
+    }
+    .into())
+}
+
+#[system]
+pub async fn upgrade(args: ArgsRef<UpgradeArgs>, proto: ResourceRef<ProtoResource>) {
+    let current_version = env!("CARGO_PKG_VERSION");
+    let current = Version::parse(current_version).unwrap();
+    let proto_dir = proto.env.root.join("tools").join("proto");
+    let stored_versions = get_stored_versions(&proto_dir)?;
+
+    if args.list {
+        if stored_versions.is_empty() {
+            eprintln!("No previous versions installed");
+
+            return Ok(());
+        }
+
+        println!(
+            "{}",
+            stored_versions
+                .iter()
+                .map(|v| v.to_string())
+                .collect::<Vec<_>>()
+                .join("\n")
+        );
+
+        return Ok(());
+    }
+
+    // Binaries may be missing if a previous upgrade was interrupted
+    if let Some(staged) = StagedUpgrade::load(&proto_dir)? {
+        repair_upgrade(staged)?;
+
+        if args.repair {
+            return Ok(());
+        }
+    } else if args.repair {
+        info!("No interrupted upgrade to repair");
+
+        return Ok(());
+    }
+
+    let target_version = if args.rollback {
+        // The highest stored version below the current version
+        let Some(version) = stored_versions.iter().find(|v| **v < current) else {
+            return Err(ProtoCliError::NoPreviousProtoVersion.into());
+        };
+
+        version.to_owned()
+    } else if let Some(version) = &args.to {
+        version.to_owned()
+    } else {
+        if is_offline() {
+            return Err(ProtoCliError::UpgradeRequiresInternet.into());
+        }
+
+        let latest_version = fetch_latest_version(&proto.env).await?;
+
+        debug!(
+            "Comparing latest version {} to current version {}",
+            color::hash(&latest_version),
+            color::hash(current_version),
+        );
+
+        let latest = Version::parse(&latest_version).unwrap();
+
+        if latest <= current {
+            info!("You're already on the latest version of proto!");
+
+            return Ok(());
+        }
+
+        latest
+    };
+
+    if target_version == current {
+        info!("You're already on proto v{}!", current_version);
+
+        return Ok(());
+    }
+
+    let target_version = target_version.to_string();
+    let relocate_dir = proto_dir.join(current_version);
+    let stored_dir = proto_dir.join(&target_version);
+
+    // Switch to the stored binaries when available, instead of downloading
+    let upgraded = if has_stored_release(&stored_dir) {
+        debug!(dir = ?stored_dir, "Using previously installed binaries");
+
+        let pb = create_progress_bar(format!("Switching to proto v{target_version}"));
+
+        let upgraded = restore_release(
+            &stored_dir,
+            proto.env.bin_dir.clone(),
+            &relocate_dir,
+            |phase| {
+                debug!(phase = ?phase, "Restoring release");
+            },
+        )?;
+
+        if upgraded {
+            pb.set_message(format!("Verifying proto v{target_version}"));
+
+            verify_release(proto.env.bin_dir.clone(), &target_version)?;
+        }
+
+        pb.finish_and_clear();
+
+        upgraded
+    } else {
+        if is_offline() {
+            return Err(ProtoCliError::UpgradeRequiresInternet.into());
+        }
+
+        // Determine the download file based on target
+        let triple_target = determine_triple()?;
+
+        debug!("Download target: {}", triple_target);
+
+        if args.skip_verify {
+            warn!("Skipping verification of the downloaded release, this is not recommended");
+        }
+
+        let options = VerifyOptions {
+            public_key: env::var("PROTO_UPGRADE_PUBLIC_KEY").ok(),
+            skip: args.skip_verify,
+        };
+
+        // Download the file and show a progress bar
+        let pb = if is_json_format() {
+            let pb = ProgressBar::with_draw_target(Some(0), create_progress_logger());
+            pb.set_style(
+                ProgressStyle::with_template("Downloading {bytes} / {total_bytes}").unwrap(),
+            );
+            pb
+        } else {
+            let pb = ProgressBar::new(0);
+            pb.set_style(ProgressStyle::default_bar().progress_chars("━╾─").template(
+                "{bar:80.183/black} | {bytes:.239} / {total_bytes:.248} | {bytes_per_sec:.183} | eta {eta}",
+            ).unwrap());
+            pb
+        };
+
+        let result = download_release(
+            proto.env.get_http_client()?,
+            &triple_target,
+            &target_version,
+            &proto.env.temp_dir,
+            &options,
+            |url| proto.env.rewrite_url(url),
+            |downloaded_size, total_size| {
+                if downloaded_size == 0 {
+                    pb.set_length(total_size);
+                } else {
+                    pb.set_position(downloaded_size);
+                }
+
+                trace!("Downloaded {} of {} bytes", downloaded_size, total_size);
+            },
+        )
+        .await?;
+
+        pb.finish_and_clear();
+
+        // Unpack the downloaded file
+        debug!(archive = ?result.archive_file, "Unpacking download");
+
+        let pb = create_progress_bar("Unpacking archive");
+
+        let upgraded = unpack_release_with_progress(
+            result,
+            proto.env.bin_dir.clone(),
+            &relocate_dir,
+            |phase| {
+                debug!(phase = ?phase, "Unpacking release");
+
+                pb.set_message(match phase {
+                    UnpackPhase::Unpacking => "Unpacking archive",
+                    UnpackPhase::Relocating => "Relocating current binaries",
+                    UnpackPhase::Replacing => "Replacing binaries",
+                    UnpackPhase::CleaningUp => "Cleaning up",
+                });
+            },
+        )?;
+
+        // Verify the new binary actually works
+        if upgraded {
+            pb.set_message(format!("Verifying proto v{target_version}"));
 
-    pb.finish_and_clear();
+            verify_release(proto.env.bin_dir.clone(), &target_version)?;
+        }
 
-    // Unpack the downloaded file
-    debug!(archive = ?result.archive_file, "Unpacking download");
+        pb.finish_and_clear();
 
-    let upgraded = unpack_release(
-        result,
-        proto.env.bin_dir.clone(),
-        proto.env.tools_dir.join("proto").join(current_version),
-    )?;
+        upgraded
+    };
 
     // Track usage metrics
     track_usage(
         &proto.env,
         Metric::UpgradeProto {
             old_version: current_version.to_owned(),
-            new_version: latest_version.to_owned(),
+            new_version: target_version.to_owned(),
         },
     )
     .await?;
 
     if upgraded {
-        info!("Upgraded proto to v{}!", latest_version);
+        info!(
+            "Switched proto from v{} to v{}!",
+            current_version, target_version
+        );
 
         return Ok(());
     }
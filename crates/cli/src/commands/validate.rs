@@ -0,0 +1,236 @@
+use crate::error::ProtoCliError;
+use crate::helpers::ProtoResource;
+use clap::Args;
+use miette::{IntoDiagnostic, NamedSource, SourceSpan};
+use proto_core::{ProtoConfig, PROTO_CONFIG_NAME};
+use starbase::system;
+use starbase_styles::color;
+use starbase_utils::fs;
+use starbase_utils::toml::{self, TomlValue};
+use std::path::{Path, PathBuf};
+use std::process;
+use tracing::debug;
+
+/// Sections that contain a map of keys, which are validated individually.
+const NESTED_SECTIONS: [&str; 5] = ["env", "plugins", "policies", "settings", "tools"];
+
+#[derive(Args, Clone, Debug)]
+pub struct ValidateArgs {
+    #[arg(long, help = "Print a JSON schema of .prototools, for use in editors")]
+    schema: bool,
+}
+
+/// Find all configuration files in the hierarchy, from the current
+/// directory upwards, and lastly the global configuration file.
+fn find_config_files(proto: &ProtoResource) -> Vec<PathBuf> {
+    let mut files = vec![];
+    let mut current_dir = Some(proto.env.cwd.as_path());
+
+    while let Some(dir) = current_dir {
+        let path = dir.join(PROTO_CONFIG_NAME);
+
+        if path.exists() && !files.contains(&path) {
+            files.push(path);
+        }
+
+        current_dir = dir.parent();
+    }
+
+    let global_path = proto.env.root.join(PROTO_CONFIG_NAME);
+
+    if global_path.exists() && !files.contains(&global_path) {
+        files.push(global_path);
+    }
+
+    files
+}
+
+fn normalize_key(key: &str) -> &str {
+    key.trim().trim_matches('"').trim_matches('\'')
+}
+
+/// Find the span of the key at the provided path, by scanning table headers
+/// and key assignments. Only supports the subset of TOML used by `.prototools`.
+fn find_key_span(content: &str, path: &[&str]) -> Option<SourceSpan> {
+    let target = path.join(".");
+    let mut offset = 0;
+    let mut table = String::new();
+
+    for line in content.split_inclusive('\n') {
+        let line_offset = offset;
+        let trimmed = line.trim_start();
+        let indent = line.len() - trimmed.len();
+
+        offset += line.len();
+
+        if let Some(header) = trimmed
+            .strip_prefix('[')
+            .and_then(|header| header.split(']').next())
+        {
+            table = header
+                .split('.')
+                .map(normalize_key)
+                .collect::<Vec<_>>()
+                .join(".");
+
+            if table == target {
+                return Some((line_offset + indent, trimmed.trim_end().len()).into());
+            }
+
+            continue;
+        }
+
+        let Some((key, _)) = trimmed.split_once('=') else {
+            continue;
+        };
+
+        let key_path = key
+            .split('.')
+            .map(normalize_key)
+            .collect::<Vec<_>>()
+            .join(".");
+        let full_key = if table.is_empty() {
+            key_path
+        } else {
+            format!("{table}.{key_path}")
+        };
+
+        if full_key == target || full_key.starts_with(&format!("{target}.")) {
+            return Some((line_offset + indent, key.trim_end().len()).into());
+        }
+    }
+
+    None
+}
+
+/// Create a configuration that only contains the value at the provided path,
+/// so that errors can be attributed to a specific key.
+fn isolate_value(path: &[&str], value: &TomlValue) -> TomlValue {
+    let mut isolated = value.to_owned();
+
+    for key in path.iter().rev() {
+        isolated = TomlValue::Table([(key.to_string(), isolated)].into_iter().collect());
+    }
+
+    isolated
+}
+
+fn create_error(file: &Path, content: &str, path: &[&str], error: miette::Report) -> ProtoCliError {
+    let message = error
+        .chain()
+        .map(|cause| cause.to_string())
+        .collect::<Vec<_>>()
+        .join(": ");
+
+    ProtoCliError::InvalidConfigSetting {
+        setting: if path.is_empty() {
+            PROTO_CONFIG_NAME.into()
+        } else {
+            path.join(".")
+        },
+        source_code: NamedSource::new(file.to_string_lossy(), content.to_owned()),
+        span: find_key_span(content, path).unwrap_or_else(|| (0, 0).into()),
+        message,
+    }
+}
+
+/// Strictly validate the file, by parsing each setting individually.
+fn validate_config_file(file: &Path) -> miette::Result<Vec<ProtoCliError>> {
+    let content = fs::read_file(file)?;
+    let mut errors = vec![];
+
+    let root = match content.parse::<TomlValue>() {
+        Ok(root) => root,
+        Err(error) => {
+            errors.push(ProtoCliError::InvalidConfigSyntax {
+                source_code: NamedSource::new(file.to_string_lossy(), content.clone()),
+                span: error.span().unwrap_or(0..0).into(),
+                message: error.message().to_owned(),
+            });
+
+            return Ok(errors);
+        }
+    };
+
+    let Some(table) = root.as_table() else {
+        return Ok(errors);
+    };
+
+    for (key, value) in table {
+        let nested = value
+            .as_table()
+            .filter(|_| NESTED_SECTIONS.contains(&key.as_str()));
+
+        let paths = match nested {
+            Some(section) => section
+                .keys()
+                .map(|nested_key| vec![key.as_str(), nested_key.as_str()])
+                .collect::<Vec<_>>(),
+            None => vec![vec![key.as_str()]],
+        };
+
+        for path in paths {
+            let value = path[1..]
+                .iter()
+                .try_fold(value, |current, key| current.get(key))
+                .unwrap_or(value);
+            let isolated =
+                toml::to_string_pretty(&isolate_value(&path, value)).into_diagnostic()?;
+
+            if let Err(error) = ProtoConfig::parse(file, isolated) {
+                errors.push(create_error(file, &content, &path, error));
+            }
+        }
+    }
+
+    // Catch errors that only occur when settings are combined
+    if errors.is_empty() {
+        if let Err(error) = ProtoConfig::parse(file, content.clone()) {
+            errors.push(create_error(file, &content, &[], error));
+        }
+    }
+
+    Ok(errors)
+}
+
+#[system]
+pub async fn validate(args: ArgsRef<ValidateArgs>, proto: ResourceRef<ProtoResource>) {
+    if args.schema {
+        let schema_path = proto.env.temp_dir.join("prototools.schema.json");
+
+        ProtoConfig::generate_json_schema(&schema_path)?;
+
+        println!("{}", fs::read_file(&schema_path)?);
+
+        return Ok(());
+    }
+
+    let files = find_config_files(proto);
+    let mut failed = false;
+
+    for file in &files {
+        debug!(file = ?file, "Validating {}", PROTO_CONFIG_NAME);
+
+        let errors = validate_config_file(file)?;
+
+        if errors.is_empty() {
+            println!("{} {}", color::success("valid"), color::path(file));
+
+            continue;
+        }
+
+        failed = true;
+
+        for error in errors {
+            eprintln!("{:?}", miette::Report::new(error));
+        }
+    }
+
+    if files.is_empty() {
+        println!("No {} files found", PROTO_CONFIG_NAME);
+    }
+
+    if failed {
+        process::exit(1);
+    }
+}
@@ -0,0 +1,124 @@
+use crate::helpers::ProtoResource;
+use clap::Args;
+use proto_core::{Id, Tool, VersionSpec, MANIFEST_NAME};
+use starbase::system;
+use starbase_styles::color::{self, OwoStyle};
+use starbase_utils::fs;
+use std::process;
+use tracing::debug;
+
+#[derive(Args, Clone, Debug)]
+pub struct VerifyArgs {
+    #[arg(help = "ID of tools to verify")]
+    ids: Vec<Id>,
+
+    #[arg(
+        long,
+        help = "Also run the plugin's smoke tests (verify_install) against each version"
+    )]
+    smoke: bool,
+}
+
+async fn verify_version(
+    tool: &mut Tool,
+    version: &VersionSpec,
+    smoke: bool,
+) -> Result<bool, String> {
+    tool.resolve_version(&version.to_unresolved_spec(), true)
+        .await
+        .map_err(|error| error.to_string())?;
+
+    let tool_dir = tool.get_tool_dir();
+
+    if !tool_dir.exists() {
+        return Err(format!("{} does not exist", tool_dir.display()));
+    }
+
+    tool.locate_executable()
+        .await
+        .map_err(|error| error.to_string())?;
+
+    let exe_path = tool.get_exe_path().map_err(|error| error.to_string())?;
+
+    if !exe_path.exists() {
+        return Err(format!("{} does not exist", exe_path.display()));
+    }
+
+    if !smoke {
+        return Ok(true);
+    }
+
+    tool.verify_install()
+        .await
+        .map_err(|error| error.to_string())
+}
+
+#[system]
+pub async fn verify(args: ArgsRef<VerifyArgs>, proto: ResourceRef<ProtoResource>) {
+    let mut failed = false;
+
+    if !proto.env.tools_dir.exists() {
+        return Ok(());
+    }
+
+    for entry in fs::read_dir(&proto.env.tools_dir)? {
+        let inventory_dir = entry.path();
+        let id = Id::raw(fs::file_name(&inventory_dir));
+
+        if !inventory_dir.join(MANIFEST_NAME).exists()
+            || (!args.ids.is_empty() && !args.ids.contains(&id))
+        {
+            continue;
+        }
+
+        let mut tool = proto.load_tool(&id).await?;
+        let mut versions = tool
+            .manifest
+            .installed_versions
+            .iter()
+            .cloned()
+            .collect::<Vec<_>>();
+
+        versions.sort();
+
+        for version in versions {
+            debug!(
+                tool = id.as_str(),
+                version = version.to_string(),
+                "Verifying version"
+            );
+
+            let label = format!(
+                "{} {}",
+                OwoStyle::new().bold().style(color::id(tool.get_name())),
+                color::hash(version.to_string())
+            );
+
+            match verify_version(&mut tool, &version, args.smoke).await {
+                Ok(true) => {
+                    println!("{} {label}", color::success("pass"));
+                }
+                Ok(false) => {
+                    println!(
+                        "{} {label} {}",
+                        color::success("pass"),
+                        color::muted_light("(smoke tests not supported by plugin)")
+                    );
+                }
+                Err(error) => {
+                    failed = true;
+
+                    println!(
+                        "{} {label} {} {error}",
+                        color::failure("fail"),
+                        color::muted("-")
+                    );
+                }
+            };
+        }
+    }
+
+    if failed {
+        process::exit(1);
+    }
+}
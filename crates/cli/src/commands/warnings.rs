@@ -0,0 +1,75 @@
+use crate::helpers::{serialize_optional_timestamp, ProtoResource};
+use crate::printer::Printer;
+use clap::Args;
+use miette::IntoDiagnostic;
+use proto_core::{HostWarningsState, HOST_WARNINGS};
+use serde::Serialize;
+use starbase::system;
+use starbase_styles::color;
+use starbase_utils::json;
+
+#[derive(Args, Clone, Debug)]
+pub struct WarningsArgs {
+    #[arg(long, help = "Print the warnings in JSON format")]
+    json: bool,
+}
+
+#[derive(Serialize)]
+pub struct WarningItem {
+    id: String,
+    message: String,
+    url: String,
+    #[serde(serialize_with = "serialize_optional_timestamp")]
+    last_shown_at: Option<u128>,
+    silenced: bool,
+}
+
+#[system]
+pub async fn warnings(args: ArgsRef<WarningsArgs>, proto: ResourceRef<ProtoResource>) {
+    let config = proto.env.load_config()?;
+    let state = HostWarningsState::load_from(&proto.env.temp_dir)?;
+
+    let items = HOST_WARNINGS
+        .iter()
+        .map(|warning| WarningItem {
+            id: warning.id.to_owned(),
+            message: warning.message.to_owned(),
+            url: warning.url.to_owned(),
+            last_shown_at: state.last_shown_at.get(warning.id).copied(),
+            silenced: config
+                .settings
+                .silence_warnings
+                .iter()
+                .any(|id| id == warning.id),
+        })
+        .collect::<Vec<_>>();
+
+    if args.json {
+        println!("{}", json::to_string_pretty(&items).into_diagnostic()?);
+
+        return Ok(());
+    }
+
+    let mut printer = Printer::new().with_date_format(config.settings.ui.date_format.clone());
+
+    for item in items {
+        printer.line();
+        printer.header(&item.id, &item.message);
+
+        printer.section(|p| {
+            p.entry("Learn more", color::url(&item.url));
+
+            if let Some(at) = item.last_shown_at.and_then(|at| p.date(at)) {
+                p.entry("Last shown", color::muted_light(at));
+            }
+
+            if item.silenced {
+                p.entry("Silenced", color::muted_light("yes"));
+            }
+
+            Ok(())
+        })?;
+    }
+
+    printer.flush();
+}
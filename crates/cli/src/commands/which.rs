@@ -0,0 +1,296 @@
+use crate::helpers::ProtoResource;
+use clap::Args;
+use miette::IntoDiagnostic;
+use proto_core::ProtoEnvironment;
+use serde::Serialize;
+use starbase::system;
+use starbase_styles::color;
+use starbase_utils::json;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::process;
+use system_env::{find_all_commands_on_path, SystemPackageManager};
+use tracing::warn;
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case", tag = "type", content = "manager")]
+pub enum ExecutableSource {
+    ProtoBin,
+    ProtoShim,
+    ProtoStore,
+    PackageManager(SystemPackageManager),
+    VersionManager(VersionManager),
+    System,
+    Unknown,
+}
+
+impl ExecutableSource {
+    pub fn is_proto(&self) -> bool {
+        matches!(self, Self::ProtoBin | Self::ProtoShim | Self::ProtoStore)
+    }
+}
+
+impl fmt::Display for ExecutableSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ProtoBin => write!(f, "proto bin"),
+            Self::ProtoShim => write!(f, "proto shim"),
+            Self::ProtoStore => write!(f, "proto store"),
+            Self::PackageManager(pm) => write!(f, "{pm}"),
+            Self::VersionManager(vm) => write!(f, "{vm}"),
+            Self::System => write!(f, "system"),
+            Self::Unknown => write!(f, "unknown"),
+        }
+    }
+}
+
+/// Other version managers that users commonly run alongside proto.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum VersionManager {
+    Asdf,
+    Fnm,
+    Goenv,
+    Mise,
+    Nodenv,
+    Nvm,
+    Pyenv,
+    Rbenv,
+    Sdkman,
+    Volta,
+}
+
+impl VersionManager {
+    pub const ALL: [VersionManager; 10] = [
+        Self::Asdf,
+        Self::Fnm,
+        Self::Goenv,
+        Self::Mise,
+        Self::Nodenv,
+        Self::Nvm,
+        Self::Pyenv,
+        Self::Rbenv,
+        Self::Sdkman,
+        Self::Volta,
+    ];
+
+    /// Path segments that the manager's shims and installs live in.
+    pub fn get_path_patterns(&self) -> &'static [&'static str] {
+        match self {
+            Self::Asdf => &["/.asdf/"],
+            Self::Fnm => &["/fnm/", "/.fnm/", "/fnm_multishells/"],
+            Self::Goenv => &["/.goenv/"],
+            Self::Mise => &["/mise/", "/.mise/", "/rtx/"],
+            Self::Nodenv => &["/.nodenv/"],
+            Self::Nvm => &["/.nvm/", "/nvm/"],
+            Self::Pyenv => &["/.pyenv/", "/pyenv-win/"],
+            Self::Rbenv => &["/.rbenv/"],
+            Self::Sdkman => &["/.sdkman/"],
+            Self::Volta => &["/.volta/", "/volta/"],
+        }
+    }
+
+    /// Environment variable that is set when the manager is loaded into the shell.
+    pub fn get_env_var(&self) -> &'static str {
+        match self {
+            Self::Asdf => "ASDF_DIR",
+            Self::Fnm => "FNM_DIR",
+            Self::Goenv => "GOENV_ROOT",
+            Self::Mise => "MISE_SHELL",
+            Self::Nodenv => "NODENV_ROOT",
+            Self::Nvm => "NVM_DIR",
+            Self::Pyenv => "PYENV_ROOT",
+            Self::Rbenv => "RBENV_ROOT",
+            Self::Sdkman => "SDKMAN_DIR",
+            Self::Volta => "VOLTA_HOME",
+        }
+    }
+
+    /// Detect the manager that owns the provided file or directory.
+    pub fn detect(path: &Path) -> Option<Self> {
+        let value = format!(
+            "{}/",
+            path.to_string_lossy().replace('\\', "/").to_lowercase()
+        );
+
+        Self::ALL.into_iter().find(|manager| {
+            manager
+                .get_path_patterns()
+                .iter()
+                .any(|pattern| value.contains(pattern))
+        })
+    }
+}
+
+impl fmt::Display for VersionManager {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Asdf => write!(f, "asdf"),
+            Self::Fnm => write!(f, "fnm"),
+            Self::Goenv => write!(f, "goenv"),
+            Self::Mise => write!(f, "mise"),
+            Self::Nodenv => write!(f, "nodenv"),
+            Self::Nvm => write!(f, "nvm"),
+            Self::Pyenv => write!(f, "pyenv"),
+            Self::Rbenv => write!(f, "rbenv"),
+            Self::Sdkman => write!(f, "sdkman"),
+            Self::Volta => write!(f, "volta"),
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct PathExecutable {
+    pub path: PathBuf,
+    pub source: ExecutableSource,
+}
+
+/// Detect who installed the executable at the provided path, by checking it
+/// against proto's directories, and common version and package manager locations.
+pub fn detect_executable_source(proto: &ProtoEnvironment, path: &Path) -> ExecutableSource {
+    if path.starts_with(&proto.shims_dir) {
+        return ExecutableSource::ProtoShim;
+    }
+
+    if path.starts_with(&proto.bin_dir) {
+        return ExecutableSource::ProtoBin;
+    }
+
+    if path.starts_with(&proto.root)
+        || path.starts_with(&proto.tools_dir)
+        || proto
+            .shared_store_dir
+            .as_ref()
+            .is_some_and(|dir| path.starts_with(dir))
+    {
+        return ExecutableSource::ProtoStore;
+    }
+
+    if let Some(manager) = VersionManager::detect(path) {
+        return ExecutableSource::VersionManager(manager);
+    }
+
+    // Package managers typically symlink into their own prefix
+    let real_path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+
+    for check_path in [path, real_path.as_path()] {
+        let value = check_path
+            .to_string_lossy()
+            .replace('\\', "/")
+            .to_lowercase();
+
+        if value.contains("/homebrew/")
+            || value.contains("/linuxbrew/")
+            || value.contains("/cellar/")
+        {
+            return ExecutableSource::PackageManager(SystemPackageManager::Brew);
+        }
+
+        if value.contains("/scoop/") {
+            return ExecutableSource::PackageManager(SystemPackageManager::Scoop);
+        }
+
+        if value.contains("/chocolatey/") {
+            return ExecutableSource::PackageManager(SystemPackageManager::Choco);
+        }
+    }
+
+    if ["/bin", "/sbin", "/usr/bin", "/usr/sbin"]
+        .iter()
+        .any(|dir| path.parent().is_some_and(|parent| parent == Path::new(dir)))
+    {
+        return SystemPackageManager::detect()
+            .map(ExecutableSource::PackageManager)
+            .unwrap_or(ExecutableSource::System);
+    }
+
+    if path
+        .to_string_lossy()
+        .to_lowercase()
+        .starts_with("c:\\windows")
+    {
+        return ExecutableSource::System;
+    }
+
+    ExecutableSource::Unknown
+}
+
+/// Find every executable with the provided name on `PATH`, in precedence order.
+pub fn find_executables_on_path(proto: &ProtoEnvironment, name: &str) -> Vec<PathExecutable> {
+    find_all_commands_on_path(name)
+        .into_iter()
+        .map(|path| PathExecutable {
+            source: detect_executable_source(proto, &path),
+            path,
+        })
+        .collect()
+}
+
+/// Return the executable that takes precedence over proto's shims and bins on `PATH`,
+/// but only if proto's executable is also on `PATH`, as otherwise it's not shadowed.
+pub fn find_shadowing_executable(executables: &[PathExecutable]) -> Option<&PathExecutable> {
+    let first = executables.first()?;
+
+    if !first.source.is_proto() && executables.iter().any(|exe| exe.source.is_proto()) {
+        return Some(first);
+    }
+
+    None
+}
+
+#[derive(Args, Clone, Debug)]
+pub struct WhichArgs {
+    #[arg(required = true, help = "Name of the executable or tool ID")]
+    name: String,
+
+    #[arg(long, help = "List every executable on PATH in precedence order")]
+    all: bool,
+
+    #[arg(long, help = "Print the executables in JSON format")]
+    json: bool,
+}
+
+#[system]
+pub async fn which(args: ArgsRef<WhichArgs>, proto: ResourceRef<ProtoResource>) {
+    let mut executables = find_executables_on_path(&proto.env, &args.name);
+
+    if executables.is_empty() {
+        process::exit(1);
+    }
+
+    if let Some(shadow) = find_shadowing_executable(&executables) {
+        warn!(
+            "{} from {} is shadowing proto's {} on PATH, move {} before {} in PATH to use proto",
+            color::path(&shadow.path),
+            shadow.source,
+            color::shell(&args.name),
+            color::path(&proto.env.shims_dir),
+            color::path(shadow.path.parent().unwrap_or(&shadow.path)),
+        );
+    }
+
+    if !args.all {
+        executables.truncate(1);
+    }
+
+    if args.json {
+        println!(
+            "{}",
+            json::to_string_pretty(&executables).into_diagnostic()?
+        );
+
+        return Ok(());
+    }
+
+    for exe in executables {
+        if args.all {
+            println!(
+                "{} {}",
+                exe.path.display(),
+                color::muted_light(format!("({})", exe.source))
+            );
+        } else {
+            println!("{}", exe.path.display());
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use miette::Diagnostic;
+use miette::{Diagnostic, NamedSource, SourceSpan};
 use proto_core::PROTO_CONFIG_NAME;
 use starbase_styles::{Style, Stylize};
 use std::path::PathBuf;
@@ -6,10 +6,71 @@ use thiserror::Error;
 
 #[derive(Error, Debug, Diagnostic)]
 pub enum ProtoCliError {
+    #[diagnostic(
+        code(proto::cli::unsupported_dynamic_completions),
+        help = "Dynamic completions are supported for bash, zsh, fish, and powershell."
+    )]
+    #[error("Dynamic completions are not supported for {}.", .shell.style(Style::Id))]
+    UnsupportedDynamicCompletions { shell: String },
+
     #[diagnostic(code(proto::cli::invalid_alias))]
     #[error("Invalid alias name {}. Use alphanumeric words instead.", .alias.style(Style::Id))]
     InvalidAliasName { alias: String },
 
+    #[diagnostic(code(proto::cli::invalid_batch_line))]
+    #[error(
+        "Invalid batch input on line {line}: {}. Expected {expected}.",
+        .content.style(Style::Symbol),
+    )]
+    InvalidBatchLine {
+        line: usize,
+        content: String,
+        expected: String,
+    },
+
+    #[diagnostic(
+        code(proto::cli::invalid_matrix_target),
+        help = "Expected a tool ID and a comma separated list of versions, for example node@18,20,21"
+    )]
+    #[error("Invalid run matrix target {}.", .target.style(Style::Id))]
+    InvalidMatrixTarget { target: String },
+
+    #[diagnostic(
+        code(proto::cli::invalid_run_target),
+        help = "Expected a tool ID and an optional version, for example node@20"
+    )]
+    #[error("Invalid run target {}.", .target.style(Style::Id))]
+    InvalidRunTarget { target: String },
+
+    #[diagnostic(
+        code(proto::cli::missing_pin_version),
+        help = "Provide a version with the tool ID (node@20), or pass --from-detected."
+    )]
+    #[error("No version provided for {}.", .id.style(Style::Id))]
+    MissingPinVersion { id: String },
+
+    #[diagnostic(code(proto::cli::duplicate_run_version))]
+    #[error(
+        "A version was provided with both {} and {}. Only provide one.",
+        .target.style(Style::Id),
+        .version.style(Style::Hash),
+    )]
+    DuplicateRunVersion { target: String, version: String },
+
+    #[diagnostic(
+        code(proto::cli::invalid_format_template),
+        help = "Fields must be wrapped in double braces, and cannot be empty."
+    )]
+    #[error("Invalid format template {}.", .template.style(Style::Hash))]
+    InvalidFormatTemplate { template: String },
+
+    #[diagnostic(
+        code(proto::cli::install_all_failed),
+        help = "Successfully installed tools will be skipped when running the command again."
+    )]
+    #[error("Failed to install {count} tool(s).")]
+    InstallAllFailed { count: usize },
+
     #[diagnostic(code(proto::cli::missing_tools_config))]
     #[error(
 			"No {} has been found in current directory. Attempted to find at {}.",
@@ -26,10 +87,66 @@ pub enum ProtoCliError {
 		)]
     MissingRunAltBin { bin: String, path: PathBuf },
 
+    #[diagnostic(code(proto::cli::missing_version))]
+    #[error(
+        "{tool} {} has not been installed. Install it with {}!",
+        .version.style(Style::Hash),
+        .command.style(Style::Shell),
+    )]
+    MissingToolVersion {
+        tool: String,
+        version: String,
+        command: String,
+    },
+
+    #[diagnostic(
+        code(proto::cli::no_configured_registries),
+        help = "Add a registry URL to [settings.registries] in .prototools."
+    )]
+    #[error("No plugin registries have been configured.")]
+    NoConfiguredRegistries,
+
+    #[diagnostic(
+        code(proto::cli::missing_registry_plugin),
+        help = "Search the registries with proto plugin search."
+    )]
+    #[error("Plugin {} could not be found in the configured registries.", .id.style(Style::Id))]
+    MissingRegistryPlugin { id: String },
+
+    #[diagnostic(
+        code(proto::cli::unpinnable_plugin),
+        help = "Only GitHub locators (github:org/repo) can be pinned to a release."
+    )]
+    #[error("Unable to pin plugin locator {}.", .locator.style(Style::File))]
+    UnpinnablePluginLocator { locator: String },
+
+    #[diagnostic(
+        code(proto::cli::no_previous_proto_version),
+        help = "List the versions that can be switched to with proto upgrade --list."
+    )]
+    #[error("No previously installed version of proto to roll back to.")]
+    NoPreviousProtoVersion,
+
     #[diagnostic(code(proto::cli::no_configured_tools))]
     #[error("No tools have been configured in {}.", PROTO_CONFIG_NAME.style(Style::File))]
     NoConfiguredTools,
 
+    #[diagnostic(code(proto::cli::unresolved_alias))]
+    #[error(
+        "Unable to add alias {}, as its chain {} does not resolve to an available version.",
+        .alias.style(Style::Id),
+        .chain.style(Style::Hash),
+    )]
+    UnresolvedAliasChain { alias: String, chain: String },
+
+    #[diagnostic(code(proto::cli::unresolved_alias_member))]
+    #[error(
+        "Unable to add alias {}, as its composite member {} does not resolve to an available version.",
+        .alias.style(Style::Id),
+        .spec.style(Style::Hash),
+    )]
+    UnresolvedCompositeMember { alias: String, spec: String },
+
     #[diagnostic(code(proto::cli::no_mapped_alias))]
     #[error("Cannot map an alias to itself.")]
     NoMatchingAliasToVersion,
@@ -42,14 +159,118 @@ pub enum ProtoCliError {
     )]
     NoSelfUpgrade { command: String, tool: String },
 
+    #[diagnostic(
+        code(proto::cli::sdk_root_not_executable),
+        help = "Use `proto env` to export its environment variables instead."
+    )]
+    #[error("{tool} is an SDK root and has no executable to run.")]
+    SdkRootNotExecutable { tool: String },
+
+    #[diagnostic(
+        code(proto::cli::required_by_tools),
+        help = "Uninstall the tools that require it first, or pass --force to uninstall anyway."
+    )]
+    #[error(
+        "Unable to uninstall {}, as it's required by {dependents}.",
+        .tool.style(Style::Id),
+    )]
+    ToolRequiredByOthers { tool: String, dependents: String },
+
+    #[diagnostic(
+        code(proto::cli::uninstall_version_conflict),
+        help = "Remove the version from the target, or the --all-versions option."
+    )]
+    #[error(
+        "Unable to uninstall {} with --all-versions, as a version was provided.",
+        .target.style(Style::Id),
+    )]
+    UninstallAllVersionsConflict { target: String },
+
+    #[diagnostic(
+        code(proto::cli::unmanaged_version),
+        help = "Pass --force to remove it from the inventory. The external installation will not be deleted."
+    )]
+    #[error(
+        "{tool} {} is an external installation at {}, and is not managed by proto.",
+        .version.style(Style::Hash),
+        .path.style(Style::Path),
+    )]
+    UnmanagedToolVersion {
+        tool: String,
+        version: String,
+        path: PathBuf,
+    },
+
     #[diagnostic(code(proto::cli::upgrade_failed))]
     #[error("Failed to upgrade proto, {} could not be located after download!", .bin.style(Style::Shell))]
     UpgradeFailed { bin: String },
 
+    #[diagnostic(
+        code(proto::cli::upgrade_repair_failed),
+        help = "Reinstall proto with the installation script to recover."
+    )]
+    #[error(
+        "Unable to repair the interrupted upgrade to proto v{version}, neither the new nor previous binaries are available."
+    )]
+    UpgradeRepairFailed { version: String },
+
     #[diagnostic(code(proto::cli::offline))]
     #[error("Upgrading proto requires an internet connection!")]
     UpgradeRequiresInternet,
 
+    #[diagnostic(code(proto::cli::unknown_executable))]
+    #[error(
+        "{tool} {} does not provide an executable named {}.",
+        .version.style(Style::Hash),
+        .name.style(Style::File),
+    )]
+    UnknownExecutable {
+        tool: String,
+        version: String,
+        name: String,
+        #[help]
+        suggestion: Option<String>,
+    },
+
+    #[diagnostic(code(proto::cli::missing_tool_versions))]
+    #[error("No asdf {} file found to migrate.", .path.style(Style::Path))]
+    MissingToolVersionsFile { path: PathBuf },
+
+    #[diagnostic(code(proto::cli::missing_package))]
+    #[error("Package {} does not exist.", .path.style(Style::Path))]
+    MissingPackageFile { path: PathBuf },
+
+    #[diagnostic(
+        code(proto::cli::package_not_installed),
+        help = "Only installed versions can be packaged."
+    )]
+    #[error(
+        "{tool} {} has not been installed.",
+        .version.style(Style::Hash),
+    )]
+    PackageVersionNotInstalled { tool: String, version: String },
+
+    #[diagnostic(code(proto::cli::invalid_config_syntax))]
+    #[error("Failed to parse {}: {message}", PROTO_CONFIG_NAME)]
+    InvalidConfigSyntax {
+        message: String,
+        #[source_code]
+        source_code: NamedSource<String>,
+        #[label("here")]
+        span: SourceSpan,
+    },
+
+    #[diagnostic(code(proto::cli::invalid_config_setting))]
+    #[error("Invalid setting {}.", .setting.style(Style::Property))]
+    InvalidConfigSetting {
+        setting: String,
+        message: String,
+        #[source_code]
+        source_code: NamedSource<String>,
+        #[label("{message}")]
+        span: SourceSpan,
+    },
+
     #[diagnostic(code(proto::cli::unknown_migration))]
     #[error("Unknown migration operation {}.", .op.style(Style::Symbol))]
     UnknownMigration { op: String },
@@ -1,3 +1,5 @@
+use crate::logging::{create_progress_logger, is_json_format};
+use chrono::{DateTime, Local, NaiveDateTime, SecondsFormat};
 use dialoguer::{
     console::{style, Style},
     theme::ColorfulTheme,
@@ -5,17 +7,22 @@ use dialoguer::{
 use indicatif::{ProgressBar, ProgressStyle};
 use miette::IntoDiagnostic;
 use proto_core::{
-    load_schema_plugin_with_proto, load_tool_from_locator, load_tool_with_proto, Id,
-    ProtoEnvironment, Tool, SCHEMA_PLUGIN_KEY,
+    detect_executable_conflicts, load_schema_plugin_with_proto, load_tool_from_locator,
+    load_tool_with_proto, now, DateFormat, Id, ProtoEnvironment, Tool, SCHEMA_PLUGIN_KEY,
 };
 use rustc_hash::FxHashSet;
+use serde::Serializer;
 use starbase::Resource;
 use starbase_styles::color;
 use starbase_styles::color::Color;
+use starbase_utils::fs;
+use std::collections::BTreeMap;
 use std::env;
-use std::sync::Arc;
+use std::io::{self, Read};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
-use tracing::debug;
+use tracing::{debug, warn};
 
 pub fn create_theme() -> ColorfulTheme {
     ColorfulTheme {
@@ -58,6 +65,107 @@ pub fn create_theme() -> ColorfulTheme {
     }
 }
 
+pub fn create_datetime(millis: u128) -> Option<NaiveDateTime> {
+    DateTime::from_timestamp((millis / 1000) as i64, ((millis % 1000) * 1_000_000) as u32)
+        .map(|dt| dt.naive_local())
+}
+
+/// Format the timestamp (in milliseconds) for human-readable output,
+/// in the local timezone, according to the `ui.date-format` setting.
+pub fn format_date(millis: u128, format: &DateFormat) -> Option<String> {
+    let at =
+        DateTime::from_timestamp((millis / 1000) as i64, ((millis % 1000) * 1_000_000) as u32)?
+            .with_timezone(&Local);
+
+    Some(match format {
+        DateFormat::Iso => at.format("%Y-%m-%d").to_string(),
+        DateFormat::Locale => at.format("%x").to_string(),
+        DateFormat::Relative => format_relative_date(millis, now()),
+        DateFormat::Rfc3339 => at.to_rfc3339_opts(SecondsFormat::Secs, false),
+    })
+}
+
+/// Format the time elapsed between the timestamps (in milliseconds),
+/// like "3 weeks ago", using the largest unit that fits.
+pub fn format_relative_date(millis: u128, now: u128) -> String {
+    let seconds = now.saturating_sub(millis) / 1000;
+
+    if seconds < 60 {
+        return "just now".into();
+    }
+
+    let (count, unit) = match seconds {
+        0..=3_599 => (seconds / 60, "minute"),
+        3_600..=86_399 => (seconds / 3_600, "hour"),
+        86_400..=604_799 => (seconds / 86_400, "day"),
+        604_800..=2_591_999 => (seconds / 604_800, "week"),
+        2_592_000..=31_535_999 => (seconds / 2_592_000, "month"),
+        _ => (seconds / 31_536_000, "year"),
+    };
+
+    format!("{count} {unit}{} ago", if count == 1 { "" } else { "s" })
+}
+
+/// Format the timestamp (in milliseconds) as RFC 3339 in UTC,
+/// which is the standard for all JSON output.
+pub fn format_rfc3339(millis: u128) -> Option<String> {
+    DateTime::from_timestamp((millis / 1000) as i64, ((millis % 1000) * 1_000_000) as u32)
+        .map(|at| at.to_rfc3339_opts(SecondsFormat::Millis, true))
+}
+
+pub fn serialize_timestamp<S: Serializer>(millis: &u128, serializer: S) -> Result<S::Ok, S::Error> {
+    serialize_optional_timestamp(&Some(*millis), serializer)
+}
+
+pub fn serialize_optional_timestamp<S: Serializer>(
+    millis: &Option<u128>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    match millis.and_then(format_rfc3339) {
+        Some(at) => serializer.serialize_str(&at),
+        None => serializer.serialize_none(),
+    }
+}
+
+/// Read batch input from the file, or from stdin when not provided, and return
+/// each line with its number, skipping blank lines and `#` comments.
+pub fn read_batch_input(file: Option<&Path>) -> miette::Result<Vec<(usize, String)>> {
+    let content = match file {
+        Some(file) => fs::read_file(file)?,
+        None => {
+            let mut content = String::new();
+            io::stdin().read_to_string(&mut content).into_diagnostic()?;
+            content
+        }
+    };
+
+    Ok(content
+        .lines()
+        .enumerate()
+        .map(|(index, line)| (index + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+        .map(|(number, line)| (number, line.to_owned()))
+        .collect())
+}
+
+pub fn format_size(bytes: u64) -> String {
+    let mut size = bytes as f64;
+
+    for unit in ["B", "KB", "MB", "GB"] {
+        if size < 1024.0 {
+            return if unit == "B" {
+                format!("{bytes} {unit}")
+            } else {
+                format!("{size:.1} {unit}")
+            };
+        }
+
+        size /= 1024.0;
+    }
+
+    format!("{size:.1} TB")
+}
+
 pub fn enable_progress_bars() {
     env::remove_var("PROTO_NO_PROGRESS");
 }
@@ -69,12 +177,21 @@ pub fn disable_progress_bars() {
 pub fn create_progress_bar<S: AsRef<str>>(start: S) -> ProgressBar {
     let pb = if env::var("PROTO_NO_PROGRESS").is_ok() {
         ProgressBar::hidden()
+    } else if is_json_format() {
+        ProgressBar::with_draw_target(None, create_progress_logger())
     } else {
         ProgressBar::new_spinner()
     };
 
     pb.enable_steady_tick(Duration::from_millis(100));
     pb.set_message(start.as_ref().to_owned());
+
+    // A spinner would log a new record on every tick
+    if is_json_format() {
+        pb.set_style(ProgressStyle::with_template("{msg}").unwrap());
+
+        return pb;
+    }
     pb.set_style(
         ProgressStyle::with_template("{spinner:.183} {msg}")
             .unwrap()
@@ -94,8 +211,29 @@ pub fn create_progress_bar<S: AsRef<str>>(start: S) -> ProgressBar {
     pb
 }
 
-pub async fn fetch_latest_version() -> miette::Result<String> {
-    let version = reqwest::get("https://raw.githubusercontent.com/moonrepo/proto/master/version")
+pub fn print_resolution_explanation(tool: &Tool, detected_from: Option<String>) {
+    eprintln!(
+        "Explaining how {} {} was resolved:",
+        tool.get_name(),
+        color::hash(tool.get_resolved_version().to_string())
+    );
+
+    if let Some(source) = detected_from {
+        eprintln!("  {} {source}", color::muted("-"));
+    }
+
+    for step in tool.get_resolution_explanation() {
+        eprintln!("  {} {step}", color::muted("-"));
+    }
+
+    eprintln!();
+}
+
+pub async fn fetch_latest_version(proto: &ProtoEnvironment) -> miette::Result<String> {
+    let version = proto
+        .get_http_client()?
+        .get(proto.rewrite_url("https://raw.githubusercontent.com/moonrepo/proto/master/version")?)
+        .send()
         .await
         .into_diagnostic()?
         .text()
@@ -112,12 +250,17 @@ pub async fn fetch_latest_version() -> miette::Result<String> {
 #[derive(Clone, Resource)]
 pub struct ProtoResource {
     pub env: Arc<ProtoEnvironment>,
+
+    /// Plugins that failed to load when loading multiple tools,
+    /// mapped to their error, so they can be reported at the end.
+    pub failed_plugins: Arc<Mutex<BTreeMap<Id, String>>>,
 }
 
 impl ProtoResource {
     pub fn new() -> miette::Result<Self> {
         Ok(Self {
             env: Arc::new(ProtoEnvironment::new()?),
+            failed_plugins: Arc::new(Mutex::new(BTreeMap::new())),
         })
     }
 
@@ -139,7 +282,14 @@ impl ProtoResource {
         // We must do this here, otherwise when multiple schema
         // based tools are installed in parallel, they will
         // collide when attempting to download the schema plugin!
-        load_schema_plugin_with_proto(&self.env).await?;
+        if let Err(error) = load_schema_plugin_with_proto(&self.env).await {
+            warn!("Failed to load schema plugin, skipping");
+
+            self.failed_plugins
+                .lock()
+                .unwrap()
+                .insert(Id::raw(SCHEMA_PLUGIN_KEY), error.to_string());
+        }
 
         let mut futures = vec![];
         let mut tools = vec![];
@@ -158,15 +308,30 @@ impl ProtoResource {
             let locator = locator.to_owned();
             let proto = Arc::clone(&self.env);
 
-            futures.push(tokio::spawn(async move {
-                load_tool_from_locator(id, proto, locator).await
-            }));
+            futures.push((
+                id.clone(),
+                tokio::spawn(async move { load_tool_from_locator(id, proto, locator).await }),
+            ));
         }
 
-        for future in futures {
-            tools.push(future.await.into_diagnostic()??);
+        // A single broken plugin should not break every other tool,
+        // so isolate failures and continue with the rest
+        for (id, future) in futures {
+            match future.await.into_diagnostic()? {
+                Ok(tool) => tools.push(tool),
+                Err(error) => {
+                    warn!(tool = id.as_str(), "Failed to load plugin, skipping");
+
+                    self.failed_plugins
+                        .lock()
+                        .unwrap()
+                        .insert(id, error.to_string());
+                }
+            }
         }
 
+        detect_executable_conflicts(&tools, &config.settings.shims.priority)?;
+
         Ok(tools)
     }
 }
@@ -0,0 +1,184 @@
+use indicatif::{ProgressDrawTarget, TermLike};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde_json::{json, Map, Value};
+use std::env;
+use std::fmt;
+use std::io::{self, Write};
+use std::sync::Mutex;
+use tracing::field::{Field, Visit};
+use tracing::metadata::LevelFilter;
+use tracing::{info, Event, Subscriber};
+use tracing_subscriber::layer::{Context, Layer, SubscriberExt};
+use tracing_subscriber::{registry, EnvFilter};
+
+static ANSI_CODES: Lazy<Regex> = Lazy::new(|| Regex::new(r"\x1b\[[0-9;]*m").unwrap());
+
+/// Return true if logs should be written as NDJSON records (`--log-format json`).
+pub fn is_json_format() -> bool {
+    env::var("PROTO_LOG_FORMAT").is_ok_and(|format| format == "json")
+}
+
+fn strip_ansi(value: &str) -> String {
+    ANSI_CODES.replace_all(value, "").into_owned()
+}
+
+#[derive(Default)]
+struct JsonVisitor {
+    fields: Map<String, Value>,
+    message: String,
+}
+
+impl JsonVisitor {
+    fn insert(&mut self, field: &Field, value: Value) {
+        self.fields.insert(field.name().to_owned(), value);
+    }
+}
+
+impl Visit for JsonVisitor {
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.insert(field, Value::from(value));
+    }
+
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.insert(field, Value::from(value));
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.insert(field, Value::from(value));
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.insert(field, Value::from(value));
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message = strip_ansi(value);
+        } else {
+            self.insert(field, Value::from(strip_ansi(value)));
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        let value = strip_ansi(&format!("{value:?}"));
+
+        if field.name() == "message" {
+            self.message = value;
+        } else {
+            self.insert(field, Value::from(value));
+        }
+    }
+}
+
+/// Writes each event as a single line JSON record to stderr,
+/// so that stdout is reserved for command results.
+struct JsonLayer;
+
+impl<S: Subscriber> Layer<S> for JsonLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let metadata = event.metadata();
+        let mut visitor = JsonVisitor::default();
+
+        event.record(&mut visitor);
+
+        let record = json!({
+            "timestamp": chrono::Utc::now().to_rfc3339(),
+            "level": metadata.level().to_string(),
+            "target": metadata.target(),
+            "message": visitor.message,
+            "fields": visitor.fields,
+        });
+
+        let _ = writeln!(io::stderr().lock(), "{record}");
+    }
+}
+
+/// Setup tracing to output NDJSON records, using the same level
+/// and module filtering as the default terminal output.
+pub fn setup_json_tracing(default_level: LevelFilter, modules: &[String]) {
+    let level = env::var("STARBASE_LOG").unwrap_or_else(|_| default_level.to_string());
+    let directives = modules
+        .iter()
+        .map(|module| format!("{module}={level}"))
+        .collect::<Vec<_>>()
+        .join(",");
+
+    let subscriber = registry().with(EnvFilter::new(directives)).with(JsonLayer);
+
+    let _ = tracing::subscriber::set_global_default(subscriber);
+}
+
+#[derive(Debug, Default)]
+struct ProgressFrames {
+    current: Vec<String>,
+    previous: Vec<String>,
+}
+
+/// A fake terminal for progress bars, that logs each line that changed
+/// since the last draw as a discrete `proto::progress` event.
+#[derive(Debug, Default)]
+struct ProgressLogger {
+    frames: Mutex<ProgressFrames>,
+}
+
+impl TermLike for ProgressLogger {
+    fn width(&self) -> u16 {
+        120
+    }
+
+    fn move_cursor_up(&self, _n: usize) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn move_cursor_down(&self, _n: usize) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn move_cursor_right(&self, _n: usize) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn move_cursor_left(&self, _n: usize) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn write_line(&self, line: &str) -> io::Result<()> {
+        self.write_str(line)
+    }
+
+    fn write_str(&self, line: &str) -> io::Result<()> {
+        let line = line.trim();
+
+        if line.is_empty() {
+            return Ok(());
+        }
+
+        let mut frames = self.frames.lock().unwrap();
+
+        if !frames.previous.iter().any(|prev| prev == line) {
+            info!(target: "proto::progress", "{line}");
+        }
+
+        frames.current.push(line.to_owned());
+
+        Ok(())
+    }
+
+    fn clear_line(&self) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn flush(&self) -> io::Result<()> {
+        let mut frames = self.frames.lock().unwrap();
+
+        frames.previous = std::mem::take(&mut frames.current);
+
+        Ok(())
+    }
+}
+
+/// Create a draw target that logs progress bar messages instead of rendering them.
+pub fn create_progress_logger() -> ProgressDrawTarget {
+    ProgressDrawTarget::term_like_with_hz(Box::new(ProgressLogger::default()), 2)
+}
@@ -2,13 +2,20 @@ mod app;
 mod commands;
 mod error;
 mod helpers;
+mod logging;
 mod printer;
 mod shell;
 mod systems;
 mod telemetry;
+mod template;
 
-use app::{App as CLI, Commands, DebugCommands, PluginCommands};
+use app::{
+    App as CLI, CacheCommands, Commands, DebugCommands, HookCommands, InventoryCommands, LogFormat,
+    PluginCommands, QueryCommands, TelemetryCommands,
+};
 use clap::Parser;
+use miette::IntoDiagnostic;
+use proto_core::trigger_host_warning;
 use starbase::{tracing::TracingOptions, App, MainResult};
 use starbase_utils::string_vec;
 use std::env;
@@ -27,6 +34,34 @@ async fn main() -> MainResult {
         env::set_var("STARBASE_LOG", level);
     }
 
+    // Styles would be written into the records
+    if let Some(format) = &cli.log_format {
+        env::set_var("PROTO_LOG_FORMAT", format.to_string());
+
+        if *format == LogFormat::Json {
+            env::set_var("NO_COLOR", "1");
+        }
+    }
+
+    if cli.audit_fs {
+        env::set_var("PROTO_AUDIT_FS", "true");
+    }
+
+    if cli.offline {
+        env::set_var("PROTO_OFFLINE", "true");
+    }
+
+    if env::args().nth(1).is_some_and(|arg| arg == "tool") {
+        trigger_host_warning("tool-command");
+    }
+
+    // Detect versions and configuration from another directory
+    if let Commands::ExecEnv(args) = &cli.command {
+        if let Some(cwd) = &args.cwd {
+            env::set_current_dir(cwd).into_diagnostic()?;
+        }
+    }
+
     env::set_var("PROTO_VERSION", version);
 
     let mut modules = string_vec!["proto", "schematic", "starbase", "warpgate"];
@@ -37,20 +72,29 @@ async fn main() -> MainResult {
         modules.push("extism::pdk".into());
     }
 
-    App::setup_tracing_with_options(TracingOptions {
-        default_level: if matches!(cli.command, Commands::Bin { .. } | Commands::Run { .. }) {
-            LevelFilter::WARN
-        } else if matches!(cli.command, Commands::Completions { .. }) {
-            LevelFilter::OFF
-        } else {
-            LevelFilter::INFO
-        },
-        filter_modules: modules,
-        intercept_log: false,
-        log_env: "STARBASE_LOG".into(),
-        // test_env: "PROTO_TEST".into(),
-        ..TracingOptions::default()
-    });
+    let default_level = if matches!(cli.command, Commands::Bin { .. } | Commands::Run { .. }) {
+        LevelFilter::WARN
+    } else if matches!(
+        cli.command,
+        Commands::Complete { .. } | Commands::Completions { .. }
+    ) {
+        LevelFilter::OFF
+    } else {
+        LevelFilter::INFO
+    };
+
+    if logging::is_json_format() {
+        logging::setup_json_tracing(default_level, &modules);
+    } else {
+        App::setup_tracing_with_options(TracingOptions {
+            default_level,
+            filter_modules: modules,
+            intercept_log: false,
+            log_env: "STARBASE_LOG".into(),
+            // test_env: "PROTO_TEST".into(),
+            ..TracingOptions::default()
+        });
+    }
 
     let mut args = env::args_os().collect::<Vec<_>>();
 
@@ -69,47 +113,117 @@ async fn main() -> MainResult {
     app.analyze(systems::load_proto_configs);
     app.analyze(systems::remove_old_bins);
 
+    // Only commands that send usage metrics need consent
+    if matches!(
+        cli.command,
+        Commands::Install(_) | Commands::Uninstall(_) | Commands::Upgrade(_) | Commands::Use(_)
+    ) {
+        app.analyze(systems::request_telemetry);
+    }
+
     if !matches!(
         cli.command,
-        Commands::Bin(_)
+        Commands::Activate(_)
+            | Commands::Bin(_)
+            | Commands::Cache { .. }
+            | Commands::Complete(_)
             | Commands::Completions(_)
+            | Commands::Env(_)
+            | Commands::ExecEnv(_)
+            | Commands::Hook { .. }
+            | Commands::Init(_)
+            | Commands::Query { .. }
+            | Commands::Report(_)
             | Commands::Run(_)
             | Commands::Setup(_)
-            | Commands::Upgrade
+            | Commands::Teardown(_)
+            | Commands::Try(_)
+            | Commands::Upgrade(_)
     ) {
+        app.analyze(systems::check_for_interrupted_upgrade);
+        app.analyze(systems::suggest_onboarding);
         app.execute(systems::check_for_new_version);
     }
 
     match cli.command {
+        Commands::Activate(args) => app.execute_with_args(commands::activate, args),
         Commands::Alias(args) => app.execute_with_args(commands::alias, args),
         Commands::Bin(args) => app.execute_with_args(commands::bin, args),
+        Commands::Cache { command } => match command {
+            CacheCommands::Clean(args) => app.execute_with_args(commands::cache::clean, args),
+            CacheCommands::Dir => app.execute(commands::cache::dir),
+            CacheCommands::List(args) => app.execute_with_args(commands::cache::list, args),
+        },
         Commands::Clean(args) => app.execute_with_args(commands::clean, args),
+        Commands::Complete(args) => app.execute_with_args(commands::complete, args),
         Commands::Completions(args) => app.execute_with_args(commands::completions, args),
         Commands::Debug { command } => match command {
             DebugCommands::Config(args) => app.execute_with_args(commands::debug::config, args),
             DebugCommands::Env => app.execute(commands::debug::env),
+            DebugCommands::PluginLog(args) => {
+                app.execute_with_args(commands::debug::plugin_log, args)
+            }
+        },
+        Commands::Doctor(args) => app.execute_with_args(commands::doctor, args),
+        Commands::Env(args) => app.execute_with_args(commands::env, args),
+        Commands::ExecEnv(args) => app.execute_with_args(commands::exec_env, args),
+        Commands::Hook { command } => match command {
+            HookCommands::PreCommit(args) => {
+                app.execute_with_args(commands::hook::pre_commit, args)
+            }
         },
+        Commands::Init(args) => app.execute_with_args(commands::init, args),
         Commands::Install(args) => app.execute_with_args(commands::install, args),
+        Commands::Inventory { command } => match command {
+            InventoryCommands::Add(args) => app.execute_with_args(commands::inventory::add, args),
+        },
         Commands::List(args) => app.execute_with_args(commands::list, args),
         Commands::ListRemote(args) => app.execute_with_args(commands::list_remote, args),
         Commands::Migrate(args) => app.execute_with_args(commands::migrate, args),
         Commands::Outdated(args) => app.execute_with_args(commands::outdated, args),
+        Commands::Package(args) => app.execute_with_args(commands::package, args),
         Commands::Pin(args) => app.execute_with_args(commands::pin, args),
         Commands::Plugin { command } => match command {
             PluginCommands::Add(args) => app.execute_with_args(commands::plugin::add, args),
+            PluginCommands::Doctor(args) => app.execute_with_args(commands::plugin::doctor, args),
             PluginCommands::Info(args) => app.execute_with_args(commands::plugin::info, args),
             PluginCommands::List(args) => app.execute_with_args(commands::plugin::list, args),
+            PluginCommands::Outdated(args) => {
+                app.execute_with_args(commands::plugin::outdated, args)
+            }
             PluginCommands::Remove(args) => app.execute_with_args(commands::plugin::remove, args),
+            PluginCommands::Search(args) => app.execute_with_args(commands::plugin::search, args),
+            PluginCommands::Upgrade(args) => app.execute_with_args(commands::plugin::upgrade, args),
+        },
+        Commands::Query { command } => match command {
+            QueryCommands::Manifest(args) => app.execute_with_args(commands::query::manifest, args),
         },
         Commands::Regen(args) => app.execute_with_args(commands::regen, args),
+        Commands::Report(args) => app.execute_with_args(commands::report, args),
         Commands::Run(args) => app.execute_with_args(commands::run, args),
+        Commands::RunMatrix(args) => app.execute_with_args(commands::run_matrix, args),
         Commands::Setup(args) => app.execute_with_args(commands::setup, args),
+        Commands::Stats(args) => app.execute_with_args(commands::stats, args),
+        Commands::Status(args) => app.execute_with_args(commands::status, args),
+        Commands::Teardown(args) => app.execute_with_args(commands::teardown, args),
+        Commands::Telemetry { command } => match command {
+            TelemetryCommands::Disable => app.execute(commands::telemetry::disable),
+            TelemetryCommands::Enable => app.execute(commands::telemetry::enable),
+            TelemetryCommands::Status => app.execute(commands::telemetry::status),
+        },
+        Commands::Try(args) => app.execute_with_args(commands::try_tools, args),
         Commands::Unalias(args) => app.execute_with_args(commands::unalias, args),
         Commands::Uninstall(args) => app.execute_with_args(commands::uninstall, args),
-        Commands::Upgrade => app.execute(commands::upgrade),
-        Commands::Use => app.execute(commands::install_all),
+        Commands::Upgrade(args) => app.execute_with_args(commands::upgrade, args),
+        Commands::Use(args) => app.execute_with_args(commands::install_all, args),
+        Commands::Validate(args) => app.execute_with_args(commands::validate, args),
+        Commands::Verify(args) => app.execute_with_args(commands::verify, args),
+        Commands::Warnings(args) => app.execute_with_args(commands::warnings, args),
+        Commands::Which(args) => app.execute_with_args(commands::which, args),
     };
 
+    app.shutdown(systems::report_failed_plugins);
+    app.shutdown(systems::render_host_warnings);
     app.run().await?;
 
     Ok(())
@@ -4,10 +4,15 @@
 use anyhow::{anyhow, Result};
 use proto_shim::{exec_command_and_replace, locate_proto_exe};
 use rust_json::{json_parse, JsonElem as Json};
+#[cfg(unix)]
+use std::collections::hash_map::DefaultHasher;
 use std::collections::HashMap;
 use std::ffi::OsString;
-use std::path::PathBuf;
+#[cfg(unix)]
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use std::{env, fs};
 
 fn get_proto_home() -> Result<PathBuf> {
@@ -21,9 +26,239 @@ fn get_proto_home() -> Result<PathBuf> {
     Ok(home_dir.join(".proto"))
 }
 
+fn get_shims_dir() -> Result<PathBuf> {
+    // Prefer the directory of the shim, as shims may
+    // live in a project store instead of the user store
+    if let Some(shims_dir) = env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(|dir| dir.to_path_buf()))
+    {
+        if shims_dir.join("registry.json").exists() {
+            return Ok(shims_dir);
+        }
+    }
+
+    Ok(get_proto_home()?.join("shims"))
+}
+
+fn get_registry_path() -> Result<PathBuf> {
+    Ok(get_shims_dir()?.join("registry.json"))
+}
+
+// Resolved commands are cached per shim, working directory, and proto
+// environment variables, and are written by `proto run`. Only Unix is
+// supported, as Windows shims can't replace the current process.
+#[cfg(unix)]
+fn get_cache_path(shim_name: &str) -> Option<PathBuf> {
+    if env::var("PROTO_SHIM_CACHE").is_ok_and(|value| value == "0" || value == "false") {
+        return None;
+    }
+
+    let mut vars = env::vars_os()
+        .filter(|(key, _)| {
+            let key = key.to_string_lossy();
+
+            key == "PATH" || (key.starts_with("PROTO_") && !key.starts_with("PROTO_SHIM_"))
+        })
+        .collect::<Vec<_>>();
+
+    vars.sort();
+
+    let mut hasher = DefaultHasher::new();
+    shim_name.hash(&mut hasher);
+    env::current_dir().ok()?.hash(&mut hasher);
+    vars.hash(&mut hasher);
+
+    Some(
+        get_shims_dir()
+            .ok()?
+            .join(".cache")
+            .join(format!("{shim_name}-{:x}.json", hasher.finish())),
+    )
+}
+
+#[cfg(windows)]
+fn get_cache_path(_shim_name: &str) -> Option<PathBuf> {
+    None
+}
+
+// Must match `get_modified_time` in proto_core!
+fn get_modified_time(path: &Path) -> String {
+    fs::metadata(path)
+        .and_then(|meta| meta.modified())
+        .ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map(|duration| duration.as_millis())
+        .unwrap_or_default()
+        .to_string()
+}
+
+fn is_cache_valid(cache: &Json, passthrough_args: &[OsString]) -> bool {
+    // Expire daily, so that proto can track when the tool was last used
+    let Json::Str(created_at) = &cache["created_at"] else {
+        return false;
+    };
+
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis();
+
+    if created_at.parse::<u128>().map_or(true, |time| {
+        time + Duration::from_secs(86400).as_millis() < now
+    }) {
+        return false;
+    }
+
+    // Invalidate when any file the version was resolved from changes
+    let Json::Object(mtimes) = &cache["mtimes"] else {
+        return false;
+    };
+
+    for (path, mtime) in mtimes {
+        if !matches!(mtime, Json::Str(mtime) if *mtime == get_modified_time(Path::new(path))) {
+            return false;
+        }
+    }
+
+    // The executable may have been removed
+    let Json::Str(bin) = &cache["bin"] else {
+        return false;
+    };
+
+    if Path::new(bin).is_absolute() && !Path::new(bin).exists() {
+        return false;
+    }
+
+    // Self-upgrades are blocked by proto, so must run through it
+    if let Json::Array(commands) = &cache["self_upgrade_commands"] {
+        let command = passthrough_args
+            .iter()
+            .map(|arg| arg.to_string_lossy())
+            .find(|arg| !arg.starts_with('-'));
+
+        if let Some(command) = command {
+            if commands
+                .iter()
+                .any(|item| matches!(item, Json::Str(item) if *item == command))
+            {
+                return false;
+            }
+        }
+    }
+
+    true
+}
+
+fn create_cached_command(cache_path: &Path, passthrough_args: &[OsString]) -> Option<Command> {
+    let cache = json_parse(&fs::read_to_string(cache_path).ok()?).unwrap_or(Json::Null);
+
+    if !is_cache_valid(&cache, passthrough_args) {
+        return None;
+    }
+
+    let Json::Str(bin) = &cache["bin"] else {
+        return None;
+    };
+
+    let mut command = Command::new(bin);
+
+    if let Json::Array(args) = &cache["args"] {
+        for arg in args {
+            if let Json::Str(arg) = arg {
+                command.arg(arg);
+            }
+        }
+    }
+
+    command.args(passthrough_args);
+
+    if let Json::Object(env_vars) = &cache["env"] {
+        for (key, value) in env_vars {
+            match value {
+                Json::Str(value) => {
+                    command.env(key, value);
+                }
+                _ => {
+                    command.env_remove(key);
+                }
+            };
+        }
+    }
+
+    Some(command)
+}
+
+#[cfg(target_os = "linux")]
+fn get_parent_process_name() -> Option<String> {
+    let ppid = std::os::unix::process::parent_id();
+
+    fs::read_to_string(format!("/proc/{ppid}/comm"))
+        .ok()
+        .map(|name| name.trim().to_owned())
+}
+
+#[cfg(all(unix, not(target_os = "linux")))]
+fn get_parent_process_name() -> Option<String> {
+    let ppid = std::os::unix::process::parent_id();
+    let output = Command::new("ps")
+        .args(["-o", "comm=", "-p", &ppid.to_string()])
+        .output()
+        .ok()?;
+
+    String::from_utf8_lossy(&output.stdout)
+        .trim()
+        .rsplit('/')
+        .next()
+        .map(|name| name.to_owned())
+}
+
+// Not supported without pulling in Win32 APIs, so customizations
+// that require a parent process are never applied on Windows
+#[cfg(windows)]
+fn get_parent_process_name() -> Option<String> {
+    None
+}
+
+fn is_invoked_by(parent_process: &str) -> bool {
+    get_parent_process_name().is_some_and(|name| {
+        name.to_lowercase().replace(".exe", "") == parent_process.to_lowercase()
+    })
+}
+
+fn create_proto_command(
+    shim: &Json,
+    shim_name: &str,
+    passthrough_args: Vec<OsString>,
+    cache_path: Option<PathBuf>,
+) -> Command {
+    // Create the command and handle alternate logic
+    let mut command = Command::new(locate_proto_exe("proto").unwrap_or_else(|| "proto".into()));
+
+    if let Json::Str(parent_name) = &shim["parent"] {
+        command.args(["run", parent_name]);
+
+        if matches!(shim["alt_bin"], Json::Bool(true)) {
+            command.args(["--alt", shim_name]);
+        }
+    } else {
+        command.args(["run", shim_name]);
+    }
+
+    if !passthrough_args.is_empty() {
+        command.arg("--");
+        command.args(passthrough_args);
+    }
+
+    if let Some(cache_path) = cache_path {
+        command.env("PROTO_SHIM_CACHE_PATH", cache_path);
+    }
+
+    command
+}
+
 fn create_command(args: Vec<OsString>, shim_name: &str) -> Result<Command> {
-    let proto_home_dir = get_proto_home()?;
-    let registry_path = proto_home_dir.join("shims").join("registry.json");
+    let registry_path = get_registry_path()?;
     let mut shim = Json::Object(HashMap::default());
 
     // Load the shims registry if it exists
@@ -40,10 +275,16 @@ fn create_command(args: Vec<OsString>, shim_name: &str) -> Result<Command> {
         }
     }
 
+    // Customizations may only apply to a specific parent process
+    let customize = match &shim["parent_process"] {
+        Json::Str(parent_process) => is_invoked_by(parent_process),
+        _ => true,
+    };
+
     // Determine args to pass to the underlying binary
     let mut passthrough_args = vec![];
 
-    if let Json::Array(before_args) = &shim["before_args"] {
+    if let (true, Json::Array(before_args)) = (customize, &shim["before_args"]) {
         for arg in before_args {
             if let Json::Str(arg) = arg {
                 passthrough_args.push(OsString::from(arg));
@@ -61,7 +302,7 @@ fn create_command(args: Vec<OsString>, shim_name: &str) -> Result<Command> {
         }
     }
 
-    if let Json::Array(after_args) = &shim["after_args"] {
+    if let (true, Json::Array(after_args)) = (customize, &shim["after_args"]) {
         for arg in after_args {
             if let Json::Str(arg) = arg {
                 passthrough_args.push(OsString::from(arg));
@@ -69,29 +310,19 @@ fn create_command(args: Vec<OsString>, shim_name: &str) -> Result<Command> {
         }
     }
 
-    // Create the command and handle alternate logic
-    let mut command = Command::new(locate_proto_exe("proto").unwrap_or_else(|| "proto".into()));
-
-    // command.args(["run", "node", "--"]);
-    // command.arg("./docs/shim-test.mjs");
-    // command.arg("--version");
-
-    if let Json::Str(parent_name) = &shim["parent"] {
-        command.args(["run", parent_name]);
+    let cache_path = get_cache_path(shim_name);
 
-        if matches!(shim["alt_bin"], Json::Bool(true)) {
-            command.args(["--alt", shim_name]);
-        }
-    } else {
-        command.args(["run", shim_name]);
-    }
-
-    if !passthrough_args.is_empty() {
-        command.arg("--");
-        command.args(passthrough_args);
-    }
+    // Execute the tool directly if it was previously resolved,
+    // otherwise resolve it with proto, which will cache the result
+    let mut command = match cache_path
+        .as_deref()
+        .and_then(|path| create_cached_command(path, &passthrough_args))
+    {
+        Some(command) => command,
+        None => create_proto_command(&shim, shim_name, passthrough_args, cache_path),
+    };
 
-    if let Json::Object(env_vars) = &shim["env_vars"] {
+    if let (true, Json::Object(env_vars)) = (customize, &shim["env_vars"]) {
         for (env, value) in env_vars {
             if let Json::Str(var) = value {
                 command.env(env, var);
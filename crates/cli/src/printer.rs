@@ -1,9 +1,11 @@
-use proto_core::PluginLocator;
+use crate::helpers::format_date;
+use proto_core::{DateFormat, PluginLocator};
 use starbase_styles::color::{self, OwoStyle};
 use std::io::{BufWriter, StdoutLock, Write};
 
 pub struct Printer<'std> {
     buffer: BufWriter<StdoutLock<'std>>,
+    date_format: DateFormat,
     depth: u8,
 }
 
@@ -15,7 +17,20 @@ impl<'std> Printer<'std> {
         let stdout = std::io::stdout();
         let buffer = BufWriter::new(stdout.lock());
 
-        Printer { buffer, depth: 0 }
+        Printer {
+            buffer,
+            date_format: DateFormat::default(),
+            depth: 0,
+        }
+    }
+
+    pub fn with_date_format(mut self, format: DateFormat) -> Self {
+        self.date_format = format;
+        self
+    }
+
+    pub fn date(&self, millis: u128) -> Option<String> {
+        format_date(millis, &self.date_format)
     }
 
     pub fn flush(&mut self) {
@@ -173,6 +188,13 @@ impl<'std> Printer<'std> {
                     color::hash(github.tag.as_deref().unwrap_or("latest")),
                 );
             }
+            PluginLocator::Asdf { repo, rev } => {
+                self.entry(
+                    "asdf",
+                    color::url(PluginLocator::create_asdf_repo_url(repo)),
+                );
+                self.entry("Revision", color::hash(rev.as_deref().unwrap_or("HEAD")));
+            }
         };
     }
 }
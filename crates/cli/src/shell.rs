@@ -169,10 +169,93 @@ pub fn format_exports(shell: &Shell, comment: &str, exports: Vec<Export>) -> Opt
     Some(lines.join("\n"))
 }
 
+fn get_block_markers(id: &str) -> (String, String) {
+    (
+        format!("# >>> proto:{id} >>>"),
+        format!("# <<< proto:{id} <<<"),
+    )
+}
+
+// Wrap the contents in a block that proto manages, so that it
+// can be detected, updated in place, or removed in the future
+pub fn format_managed_block(contents: &str, id: &str) -> String {
+    let (start, end) = get_block_markers(id);
+
+    format!("\n{start}\n{}\n{end}\n", contents.trim())
+}
+
+pub fn replace_managed_block(existing: &str, id: &str, replacement: &str) -> Option<String> {
+    let (start, end) = get_block_markers(id);
+    let start_index = existing.find(&start)?;
+    let end_index = start_index + existing[start_index..].find(&end)? + end.len();
+
+    // Also consume the surrounding newlines that were added with the block
+    let before = existing[..start_index]
+        .strip_suffix('\n')
+        .unwrap_or(&existing[..start_index]);
+    let after = existing[end_index..]
+        .strip_prefix('\n')
+        .unwrap_or(&existing[end_index..]);
+
+    Some(format!("{before}{replacement}{after}"))
+}
+
+fn find_managed_block_id(contents: &str) -> Option<String> {
+    let prefix = "# >>> proto:";
+    let start = contents.find(prefix)? + prefix.len();
+    let end = contents[start..].find(" >>>")?;
+
+    Some(contents[start..start + end].to_owned())
+}
+
+pub fn remove_managed_blocks(profile: &Path) -> miette::Result<Vec<String>> {
+    if !profile.exists() {
+        return Ok(vec![]);
+    }
+
+    let mut contents = fs::read_file(profile)?;
+    let mut removed = vec![];
+
+    while let Some(id) = find_managed_block_id(&contents) {
+        let Some(updated) = replace_managed_block(&contents, &id, "") else {
+            break;
+        };
+
+        contents = updated;
+        removed.push(id);
+    }
+
+    if !removed.is_empty() {
+        fs::write_file(profile, contents)?;
+
+        debug!(
+            "Removed {} from profile {}",
+            removed.join(", "),
+            color::path(profile)
+        );
+    }
+
+    Ok(removed)
+}
+
 pub fn write_profile(profile: &Path, contents: &str, env_var: &str) -> miette::Result<()> {
-    fs::append_file(profile, contents)?;
+    let block = format_managed_block(contents, env_var);
+
+    if profile.exists() {
+        let existing = fs::read_file(profile)?;
+
+        if let Some(updated) = replace_managed_block(&existing, env_var, &block) {
+            fs::write_file(profile, updated)?;
+
+            debug!("Updated profile {} with {}", color::path(profile), env_var);
+
+            return Ok(());
+        }
+    }
 
-    debug!("Setup profile {} with {}", color::path(profile), env_var,);
+    fs::append_file(profile, block)?;
+
+    debug!("Setup profile {} with {}", color::path(profile), env_var);
 
     Ok(())
 }
@@ -240,6 +323,65 @@ mod tests {
         ]
     }
 
+    #[test]
+    fn appends_managed_block() {
+        assert_eq!(
+            format_managed_block("\n# proto\nexport FOO=1", "PROTO_HOME"),
+            "\n# >>> proto:PROTO_HOME >>>\n# proto\nexport FOO=1\n# <<< proto:PROTO_HOME <<<\n"
+        );
+    }
+
+    #[test]
+    fn replaces_managed_block_in_place() {
+        let existing = format!(
+            "export A=1\n{}export B=2\n",
+            format_managed_block("export FOO=1", "PROTO_HOME")
+        );
+
+        assert_eq!(
+            replace_managed_block(
+                &existing,
+                "PROTO_HOME",
+                &format_managed_block("export FOO=2", "PROTO_HOME")
+            )
+            .unwrap(),
+            format!(
+                "export A=1\n{}export B=2\n",
+                format_managed_block("export FOO=2", "PROTO_HOME")
+            )
+        );
+    }
+
+    #[test]
+    fn removes_managed_block() {
+        let existing = format!(
+            "export A=1\n{}export B=2\n",
+            format_managed_block("export FOO=1", "PROTO_HOME")
+        );
+
+        assert_eq!(
+            replace_managed_block(&existing, "PROTO_HOME", "").unwrap(),
+            "export A=1\nexport B=2\n"
+        );
+    }
+
+    #[test]
+    fn finds_managed_block_ids() {
+        assert_eq!(
+            find_managed_block_id(&format_managed_block("export FOO=1", "BUN_INSTALL")).unwrap(),
+            "BUN_INSTALL"
+        );
+        assert_eq!(find_managed_block_id("export FOO=1"), None);
+    }
+
+    #[test]
+    fn ignores_missing_managed_block() {
+        assert_eq!(
+            replace_managed_block("export A=1\n", "PROTO_HOME", ""),
+            None
+        );
+    }
+
     #[test]
     fn formats_bash_env_vars() {
         assert_eq!(
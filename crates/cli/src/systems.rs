@@ -1,13 +1,17 @@
 use crate::helpers::{fetch_latest_version, ProtoResource};
+use crate::telemetry::request_telemetry_consent;
 use miette::IntoDiagnostic;
-use proto_core::{is_offline, now};
+use proto_core::{is_offline, now, take_triggered_host_warnings, HostWarningsState, StoreType};
+use proto_installer::StagedUpgrade;
 use semver::Version;
 use starbase::system;
 use starbase_styles::color;
 use starbase_utils::fs;
 use std::env;
+use std::io::{self, IsTerminal};
+use std::sync::Arc;
 use std::time::Duration;
-use tracing::debug;
+use tracing::{debug, info, warn};
 
 // STARTUP
 
@@ -20,7 +24,14 @@ pub fn detect_proto_env(resources: ResourcesMut) {
 
 #[system]
 pub fn load_proto_configs(proto: ResourceMut<ProtoResource>) {
-    proto.env.load_config()?;
+    let config = proto.env.load_config()?;
+
+    if config.settings.store == StoreType::Project {
+        let mut env = proto.env.as_ref().to_owned();
+        env.use_project_store()?;
+
+        proto.env = Arc::new(env);
+    }
 }
 
 #[system]
@@ -36,6 +47,55 @@ pub fn remove_old_bins(proto: ResourceRef<ProtoResource>) {
     }
 }
 
+#[system]
+pub fn request_telemetry(proto: ResourceRef<ProtoResource>) {
+    if
+    // Don't ask when running tests
+    env::var("PROTO_TEST").is_ok() ||
+        // Or when printing formatted output
+        env::args().any(|arg| arg == "--json") ||
+            // Or when metrics can't be sent anyways
+            is_offline()
+    {
+        return Ok(());
+    }
+
+    request_telemetry_consent(&proto.env)?;
+}
+
+#[system]
+pub fn check_for_interrupted_upgrade(proto: ResourceRef<ProtoResource>) {
+    if StagedUpgrade::exists(proto.env.root.join("tools").join("proto")) {
+        warn!(
+            "A previous proto upgrade was interrupted, run {} to complete or roll it back",
+            color::shell("proto upgrade --repair")
+        );
+    }
+}
+
+#[system]
+pub fn suggest_onboarding(proto: ResourceRef<ProtoResource>) {
+    if
+    // Don't suggest when running tests
+    env::var("PROTO_TEST").is_ok() ||
+        // Or when not interactive
+        env::var("CI").is_ok() || !io::stderr().is_terminal()
+    {
+        return Ok(());
+    }
+
+    let store_is_empty = std::fs::read_dir(&proto.env.tools_dir)
+        .map(|mut entries| entries.next().is_none())
+        .unwrap_or(true);
+
+    if store_is_empty {
+        info!(
+            "Looks like this is your first time using proto! Run {} to setup your shell and project",
+            color::shell("proto init")
+        );
+    }
+}
+
 // EXECUTE
 
 #[system]
@@ -72,7 +132,7 @@ pub async fn check_for_new_version(proto: ResourceRef<ProtoResource>) {
 
     debug!(current_version, "Checking for a new version of proto");
 
-    let Ok(latest_version) = fetch_latest_version().await else {
+    let Ok(latest_version) = fetch_latest_version(&proto.env).await else {
         return Ok(());
     };
 
@@ -100,3 +160,82 @@ pub async fn check_for_new_version(proto: ResourceRef<ProtoResource>) {
     // And write the cache
     fs::write_file(cache_file, now().to_string())?;
 }
+
+// SHUTDOWN
+
+#[system]
+pub fn report_failed_plugins(proto: ResourceRef<ProtoResource>) {
+    let failed_plugins = proto.failed_plugins.lock().unwrap();
+
+    if failed_plugins.is_empty() {
+        return Ok(());
+    }
+
+    eprintln!();
+    eprintln!(
+        "{}",
+        color::failure(format!(
+            "Failed to load {} plugin(s), which were skipped:",
+            failed_plugins.len()
+        ))
+    );
+
+    for (id, error) in failed_plugins.iter() {
+        eprintln!("  {} {}", color::id(id), color::muted_light(error));
+    }
+
+    eprintln!(
+        "Try purging the plugin cache with {} so that it is re-downloaded, or remove the plugin with {}.",
+        color::shell("proto clean --purge-plugins"),
+        color::shell("proto plugin remove <id>"),
+    );
+}
+
+#[system]
+pub fn render_host_warnings(proto: ResourceRef<ProtoResource>) {
+    let warnings = take_triggered_host_warnings();
+
+    if warnings.is_empty() {
+        return Ok(());
+    }
+
+    let config = proto.env.load_config()?;
+    let mut state = HostWarningsState::load_from(&proto.env.temp_dir)?;
+    let mut rendered = false;
+
+    for warning in warnings {
+        if config
+            .settings
+            .silence_warnings
+            .iter()
+            .any(|id| id == warning.id)
+            || !state.should_show(warning.id)
+        {
+            continue;
+        }
+
+        if !rendered {
+            eprintln!();
+        }
+
+        eprintln!(
+            "{} {} {}",
+            color::failure("Deprecated:"),
+            warning.message,
+            color::muted_light(format!("[{}]", warning.id)),
+        );
+        eprintln!("  Learn more: {}", color::url(warning.url));
+
+        rendered = true;
+    }
+
+    if rendered {
+        eprintln!(
+            "Silence a warning by adding its ID to the {} setting, or list all with {}.",
+            color::property("silence-warnings"),
+            color::shell("proto warnings"),
+        );
+
+        state.save()?;
+    }
+}
@@ -1,9 +1,18 @@
-use proto_core::{is_offline, ProtoEnvironment};
+use crate::helpers::create_theme;
+use dialoguer::Confirm;
+use miette::IntoDiagnostic;
+use once_cell::sync::OnceCell;
+use proto_core::{is_offline, ProtoConfig, ProtoEnvironment, ProtoTelemetrySettingsConfig};
 use rustc_hash::FxHashMap;
 use starbase_utils::fs;
 use std::env::{self, consts};
+use std::io::{self, IsTerminal};
+use std::path::PathBuf;
 use tracing::debug;
 
+// Consent given during the current process, as the loaded config is not reloaded
+static CONSENT: OnceCell<bool> = OnceCell::new();
+
 pub enum Metric {
     InstallTool {
         id: String,
@@ -12,6 +21,12 @@ pub enum Metric {
         version: String,
         version_candidate: String,
     },
+    IntegrityFailure {
+        download_url: String,
+        id: String,
+        plugin: String,
+        version: String,
+    },
     UninstallTool {
         id: String,
         plugin: String,
@@ -39,6 +54,17 @@ impl Metric {
                 ("ToolVersion".into(), version),
                 ("ToolVersionCandidate".into(), version_candidate),
             ]),
+            Metric::IntegrityFailure {
+                download_url,
+                id,
+                plugin,
+                version,
+            } => FxHashMap::from_iter([
+                ("DownloadUrl".into(), download_url),
+                ("ToolId".into(), id),
+                ("ToolPlugin".into(), plugin),
+                ("ToolVersion".into(), version),
+            ]),
             Metric::UninstallTool {
                 id,
                 plugin,
@@ -58,12 +84,22 @@ impl Metric {
         }
     }
 
+    pub fn is_enabled(&self, settings: &ProtoTelemetrySettingsConfig) -> bool {
+        match self {
+            Metric::InstallTool { .. } => settings.install_tool,
+            Metric::IntegrityFailure { .. } => settings.integrity_failure,
+            Metric::UninstallTool { .. } => settings.uninstall_tool,
+            Metric::UpgradeProto { .. } => settings.upgrade_proto,
+        }
+    }
+
     pub fn get_url(&self) -> String {
         format!(
             "https://launch.moonrepo.app/{}",
             // "http://0.0.0.0:8081/{}",
             match self {
                 Metric::InstallTool { .. } => "proto/install_tool",
+                Metric::IntegrityFailure { .. } => "proto/integrity_failure",
                 Metric::UninstallTool { .. } => "proto/uninstall_tool",
                 Metric::UpgradeProto { .. } => "proto/upgrade_proto",
             }
@@ -85,14 +121,63 @@ fn load_or_create_anonymous_uid(proto: &ProtoEnvironment) -> miette::Result<Stri
     Ok(id)
 }
 
+/// Persist whether usage metrics can be sent in the global config.
+pub fn set_telemetry_consent(proto: &ProtoEnvironment, enabled: bool) -> miette::Result<PathBuf> {
+    ProtoConfig::update(proto.get_config_dir(true), |config| {
+        config
+            .settings
+            .get_or_insert(Default::default())
+            .telemetry
+            .get_or_insert(Default::default())
+            .enabled = Some(enabled);
+    })
+}
+
+/// Ask whether usage metrics can be sent, if consent has not been given or denied yet,
+/// and persist the answer. Without an interactive terminal, nothing is asked or sent.
+pub fn request_telemetry_consent(proto: &ProtoEnvironment) -> miette::Result<()> {
+    if proto.load_config()?.settings.telemetry.enabled.is_some() {
+        return Ok(());
+    }
+
+    let interactive =
+        env::var("CI").is_err() && io::stdin().is_terminal() && io::stderr().is_terminal();
+
+    if !interactive {
+        debug!("Telemetry consent not configured and not interactive, skipping metrics");
+
+        return Ok(());
+    }
+
+    let enabled = Confirm::with_theme(&create_theme())
+        .with_prompt(
+            "Help improve proto by sending anonymous usage metrics? This can be changed at any time with proto telemetry",
+        )
+        .default(false)
+        .interact()
+        .into_diagnostic()?;
+
+    set_telemetry_consent(proto, enabled)?;
+
+    let _ = CONSENT.set(enabled);
+
+    Ok(())
+}
+
 pub async fn track_usage(proto: &ProtoEnvironment, metric: Metric) -> miette::Result<()> {
     let config = proto.load_config()?;
+    let settings = &config.settings.telemetry;
+    let enabled = CONSENT.get().copied().or(settings.enabled);
 
-    if !config.settings.telemetry || is_offline() || env::var("PROTO_TEST").is_ok() {
+    if enabled != Some(true)
+        || !metric.is_enabled(settings)
+        || is_offline()
+        || env::var("PROTO_TEST").is_ok()
+    {
         return Ok(());
     }
 
-    let mut client = reqwest::Client::new().post(metric.get_url());
+    let mut client = proto.get_http_client()?.post(metric.get_url());
 
     let mut headers = metric.into_headers();
     headers.insert("UID".into(), load_or_create_anonymous_uid(proto)?);
@@ -0,0 +1,68 @@
+use crate::error::ProtoCliError;
+use miette::IntoDiagnostic;
+use serde::Serialize;
+use serde_json::Value;
+
+fn format_field(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(string) => string.to_owned(),
+        Value::Array(list) => list.iter().map(format_field).collect::<Vec<_>>().join(","),
+        other => other.to_string(),
+    }
+}
+
+fn lookup_field<'a>(value: &'a Value, path: &str) -> Option<&'a Value> {
+    path.split('.')
+        .try_fold(value, |current, key| match current {
+            Value::Object(map) => map.get(key),
+            Value::Array(list) => list.get(key.parse::<usize>().ok()?),
+            _ => None,
+        })
+}
+
+/// Render a line of output for an item, by replacing `{{field}}` placeholders
+/// with values from its JSON representation. Nested fields are accessed with
+/// dot notation (`{{manifest.installed_versions}}`), lists are joined with commas,
+/// and missing fields are rendered as empty. The `id` field is always available.
+pub fn render_template<T: Serialize>(template: &str, id: &str, item: &T) -> miette::Result<String> {
+    let mut value = serde_json::to_value(item).into_diagnostic()?;
+
+    if let Value::Object(map) = &mut value {
+        map.entry("id")
+            .or_insert_with(|| Value::String(id.to_owned()));
+    }
+
+    let mut output = String::new();
+    let mut rest = template;
+
+    while let Some(start) = rest.find("{{") {
+        output.push_str(&rest[..start]);
+
+        let Some(end) = rest[start..].find("}}") else {
+            return Err(ProtoCliError::InvalidFormatTemplate {
+                template: template.to_owned(),
+            }
+            .into());
+        };
+
+        let field = rest[start + 2..start + end].trim();
+
+        if field.is_empty() {
+            return Err(ProtoCliError::InvalidFormatTemplate {
+                template: template.to_owned(),
+            }
+            .into());
+        }
+
+        if let Some(field_value) = lookup_field(&value, field) {
+            output.push_str(&format_field(field_value));
+        }
+
+        rest = &rest[start + end + 2..];
+    }
+
+    output.push_str(rest);
+
+    Ok(output)
+}
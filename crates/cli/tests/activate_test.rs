@@ -0,0 +1,78 @@
+mod utils;
+
+use starbase_sandbox::predicates::prelude::*;
+use utils::*;
+
+mod activate {
+    use super::*;
+
+    #[test]
+    fn prints_hook() {
+        let sandbox = create_empty_sandbox();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        cmd.arg("activate")
+            .arg("zsh")
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("proto activate zsh --export"))
+            .stdout(predicate::str::contains("precmd_functions"));
+    }
+
+    #[test]
+    fn errors_for_unsupported_shell() {
+        let sandbox = create_empty_sandbox();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        cmd.arg("activate").arg("elvish").assert().failure();
+    }
+
+    #[test]
+    fn removes_previous_activation() {
+        let sandbox = create_empty_sandbox();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        cmd.arg("activate")
+            .arg("bash")
+            .arg("--export")
+            .env("PATH", "/old/bin:/usr/bin")
+            .env("_PROTO_ACTIVATED_PATHS", "/old/bin")
+            .env("_PROTO_ACTIVATED_VARS", "OLD_VAR")
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("export PATH='/usr/bin'"))
+            .stdout(predicate::str::contains("unset OLD_VAR"))
+            .stdout(predicate::str::contains("unset _PROTO_ACTIVATED_PATHS"));
+    }
+
+    #[test]
+    fn prepends_detected_tools_to_path() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(".prototools", "node = \"19.0.0\"");
+
+        let mut cmd = create_proto_command(sandbox.path());
+        cmd.arg("install")
+            .arg("node")
+            .arg("19.0.0")
+            .assert()
+            .success();
+
+        let bin_dir = if cfg!(windows) {
+            sandbox.path().join(".proto/tools/node/19.0.0")
+        } else {
+            sandbox.path().join(".proto/tools/node/19.0.0/bin")
+        };
+
+        let mut cmd = create_proto_command(sandbox.path());
+        cmd.arg("activate")
+            .arg("bash")
+            .arg("--export")
+            .assert()
+            .success()
+            .stdout(predicate::str::contains(format!(
+                "export _PROTO_ACTIVATED_PATHS='{}",
+                bin_dir.to_string_lossy()
+            )))
+            .stdout(predicate::str::contains("export NODE_VERSION='19.0.0'"));
+    }
+}
@@ -120,6 +120,105 @@ mod alias_local {
 
         assert.stderr(predicate::str::contains("Cannot map an alias to itself."));
     }
+
+    #[test]
+    fn supports_ranges() {
+        let sandbox = create_empty_sandbox();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        cmd.arg("alias")
+            .arg("node")
+            .arg("supported")
+            .arg(">=18 <21")
+            .assert()
+            .success();
+
+        let config = load_config(sandbox.path());
+
+        assert_eq!(
+            config.tools.get("node").unwrap().aliases,
+            BTreeMap::from_iter([(
+                "supported".into(),
+                UnresolvedVersionSpec::parse(">=18, <21").unwrap()
+            )])
+        );
+    }
+
+    #[test]
+    fn supports_composites() {
+        let sandbox = create_empty_sandbox();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        cmd.arg("alias")
+            .arg("node")
+            .arg("supported")
+            .arg("latest || ^20")
+            .assert()
+            .success();
+
+        let config = load_config(sandbox.path());
+
+        assert_eq!(
+            config.tools.get("node").unwrap().aliases.get("supported"),
+            Some(&UnresolvedVersionSpec::parse("latest || ^20").unwrap())
+        );
+    }
+
+    #[test]
+    fn errors_when_composite_member_doesnt_resolve() {
+        let sandbox = create_empty_sandbox();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd
+            .arg("alias")
+            .arg("node")
+            .arg("supported")
+            .arg("latest || ^999")
+            .assert();
+
+        assert.stderr(predicate::str::contains("composite member"));
+    }
+
+    #[test]
+    fn adds_aliases_from_stdin() {
+        let sandbox = create_empty_sandbox();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        cmd.arg("alias")
+            .arg("--stdin")
+            .write_stdin("node example 19.0.0\nnode other example\n")
+            .assert()
+            .success();
+
+        let config = load_config(sandbox.path());
+
+        assert_eq!(
+            config.tools.get("node").unwrap().aliases,
+            BTreeMap::from_iter([
+                (
+                    "example".into(),
+                    UnresolvedVersionSpec::parse("19.0.0").unwrap()
+                ),
+                (
+                    "other".into(),
+                    UnresolvedVersionSpec::parse("example").unwrap()
+                )
+            ])
+        );
+    }
+
+    #[test]
+    fn doesnt_add_any_when_a_batch_alias_is_invalid() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file("aliases.txt", "node example 19.0.0\nnode 1.2.3 4.5.6\n");
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd.arg("alias").arg("--file").arg("aliases.txt").assert();
+
+        assert.stderr(predicate::str::contains("Invalid alias name 1.2.3"));
+
+        assert!(!sandbox.path().join(".prototools").exists());
+    }
 }
 
 mod alias_global {
@@ -41,6 +41,27 @@ mod bin {
         }
     }
 
+    #[test]
+    fn returns_dir_path() {
+        let sandbox = create_empty_sandbox();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        cmd.arg("install")
+            .arg("npm")
+            .arg("9.0.0")
+            .assert()
+            .success();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd.arg("bin").arg("npm").arg("9.0.0").arg("--dir").assert();
+
+        if cfg!(windows) {
+            assert.stdout(predicate::str::contains("tools\\npm\\9.0.0"));
+        } else {
+            assert.stdout(predicate::str::contains("tools/npm/9.0.0"));
+        }
+    }
+
     #[test]
     fn returns_bin_path() {
         let sandbox = create_empty_sandbox();
@@ -87,4 +108,107 @@ mod bin {
             assert.stdout(predicate::str::contains("shims/npm"));
         }
     }
+
+    #[test]
+    fn errors_for_version_if_not_installed() {
+        let sandbox = create_empty_sandbox();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd.arg("bin").arg("npm").arg("--for").arg("9.0.0").assert();
+
+        assert.stderr(predicate::str::contains("npm 9.0.0 has not been installed"));
+    }
+
+    #[test]
+    fn returns_path_for_version_without_pinning() {
+        let sandbox = create_empty_sandbox();
+
+        for version in ["8.19.4", "9.0.0"] {
+            let mut cmd = create_proto_command(sandbox.path());
+            cmd.arg("install")
+                .arg("npm")
+                .arg(version)
+                .assert()
+                .success();
+        }
+
+        sandbox.create_file(".prototools", "npm = \"9.0.0\"");
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd.arg("bin").arg("npm").arg("--for").arg("8").assert();
+
+        if cfg!(windows) {
+            assert.stdout(predicate::str::contains(
+                "tools\\npm\\8.19.4\\bin/npm-cli.js",
+            ));
+        } else {
+            assert.stdout(predicate::str::contains("tools/npm/8.19.4/bin/npm-cli.js"));
+        }
+
+        assert_eq!(
+            std::fs::read_to_string(sandbox.path().join(".prototools")).unwrap(),
+            "npm = \"9.0.0\""
+        );
+    }
+
+    #[test]
+    fn returns_secondary_exe_path() {
+        let sandbox = create_empty_sandbox();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        cmd.arg("install")
+            .arg("npm")
+            .arg("9.0.0")
+            .assert()
+            .success();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd.arg("bin").arg("npm").arg("npx").arg("9.0.0").assert();
+
+        assert.success().stdout(predicate::str::contains("npx"));
+    }
+
+    #[test]
+    fn lists_all_executables() {
+        let sandbox = create_empty_sandbox();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        cmd.arg("install")
+            .arg("npm")
+            .arg("9.0.0")
+            .assert()
+            .success();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd.arg("bin").arg("npm").arg("9.0.0").arg("--all").assert();
+
+        assert
+            .success()
+            .stdout(predicate::str::contains("npm "))
+            .stdout(predicate::str::contains("npx "));
+    }
+
+    #[test]
+    fn errors_for_unknown_executable() {
+        let sandbox = create_empty_sandbox();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        cmd.arg("install")
+            .arg("npm")
+            .arg("9.0.0")
+            .assert()
+            .success();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd
+            .arg("bin")
+            .arg("npm")
+            .arg("unknown")
+            .arg("9.0.0")
+            .assert();
+
+        assert.failure().stderr(predicate::str::contains(
+            "does not provide an executable named",
+        ));
+    }
 }
@@ -0,0 +1,79 @@
+mod utils;
+
+use starbase_sandbox::predicates::prelude::*;
+use utils::*;
+
+mod cache {
+    use super::*;
+
+    #[test]
+    fn prints_the_cache_dir() {
+        let sandbox = create_empty_sandbox();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd.arg("cache").arg("dir").assert();
+
+        assert.success().stdout(predicate::str::contains(
+            sandbox
+                .path()
+                .join(".proto")
+                .join("cache")
+                .join("downloads")
+                .to_string_lossy(),
+        ));
+    }
+
+    #[test]
+    fn lists_cached_downloads_as_json() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(
+            ".proto/cache/downloads/index.json",
+            r#"{
+  "refs": {
+    "https://nodejs.org/dist/v20.0.0/node.tar.gz": {
+      "blob": "abc.tar.gz",
+      "cached-at": 0,
+      "size": 1024
+    }
+  }
+}"#,
+        );
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd.arg("cache").arg("list").arg("--json").assert();
+
+        assert
+            .success()
+            .stdout(predicate::str::contains(
+                "https://nodejs.org/dist/v20.0.0/node.tar.gz",
+            ))
+            .stdout(predicate::str::contains(r#""size": 1024"#));
+    }
+
+    #[test]
+    fn cleans_cached_downloads() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(
+            ".proto/cache/downloads/index.json",
+            r#"{ "refs": { "https://a.com/a.zip": { "blob": "abc.zip", "cached-at": 0, "size": 7 } } }"#,
+        );
+        sandbox.create_file(".proto/cache/downloads/blobs/abc.zip", "archive");
+        sandbox.create_file(".proto/cache/downloads/partials/def.part", "arc");
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd.arg("cache").arg("clean").assert();
+
+        assert
+            .success()
+            .stderr(predicate::str::contains("Removed 1 cached downloads"));
+
+        assert!(!sandbox
+            .path()
+            .join(".proto/cache/downloads/blobs/abc.zip")
+            .exists());
+        assert!(!sandbox
+            .path()
+            .join(".proto/cache/downloads/partials/def.part")
+            .exists());
+    }
+}
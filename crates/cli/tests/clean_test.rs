@@ -1,7 +1,36 @@
 mod utils;
 
+use starbase_sandbox::predicates::prelude::*;
 use utils::*;
 
+fn create_stale_versions(sandbox: &Sandbox) {
+    for version in ["1.0.0", "2.0.0", "3.0.0"] {
+        sandbox.create_file(format!(".proto/tools/node/{version}/.last-used"), "0");
+        sandbox.create_file(format!(".proto/tools/node/{version}/bin/node"), "binary");
+    }
+
+    sandbox.create_file(
+        ".proto/tools/node/manifest.json",
+        r#"{
+  "installed_versions": ["1.0.0", "2.0.0", "3.0.0"],
+  "versions": {
+    "1.0.0": { "installed_at": 0 },
+    "2.0.0": { "installed_at": 0 },
+    "3.0.0": { "installed_at": 0 }
+  }
+}"#,
+    );
+
+    sandbox.create_file(
+        ".prototools",
+        r#"
+[settings.clean]
+keep-latest = 1
+protected = { node = ["1"] }
+"#,
+    );
+}
+
 mod clean {
     use super::*;
 
@@ -13,6 +42,30 @@ mod clean {
         cmd.arg("clean").arg("--yes").assert().success();
     }
 
+    #[test]
+    fn repairs_interrupted_installs() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(".proto/tools/node/20.0.0/bin/node", "");
+        sandbox.create_file(
+            ".proto/tools/node/manifest.json",
+            r#"{ "install_states": { "20.0.0": "staging" } }"#,
+        );
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd.arg("clean").arg("--yes").assert();
+
+        assert
+            .success()
+            .stderr(predicate::str::contains("Removed interrupted install"));
+
+        assert!(!sandbox.path().join(".proto/tools/node/20.0.0").exists());
+        assert!(
+            !std::fs::read_to_string(sandbox.path().join(".proto/tools/node/manifest.json"))
+                .unwrap()
+                .contains("20.0.0")
+        );
+    }
+
     #[test]
     fn purges_tool_inventory() {
         let sandbox = create_empty_sandbox();
@@ -112,4 +165,112 @@ mod clean {
             .join(".proto/plugins/npm_plugin.wasm")
             .exists());
     }
+
+    #[test]
+    fn cleans_unreferenced_plugin_blobs() {
+        let sandbox = create_empty_sandbox();
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::SystemTime::UNIX_EPOCH)
+            .unwrap()
+            .as_millis();
+
+        sandbox.create_file(".proto/plugins/blobs/abc.wasm", "");
+        sandbox.create_file(".proto/plugins/blobs/def.wasm", "");
+        sandbox.create_file(".proto/plugins/blobs/ghi.wasm", "");
+        sandbox.create_file(
+            ".proto/plugins/index.json",
+            format!(
+                r#"{{
+  "refs": {{
+    "node-123.wasm": {{ "blob": "abc.wasm", "cached-at": {now} }},
+    "npm-456.wasm": {{ "blob": "def.wasm", "cached-at": 0 }}
+  }}
+}}"#
+            ),
+        );
+
+        let mut cmd = create_proto_command(sandbox.path());
+        cmd.arg("clean")
+            .arg("--yes")
+            .arg("--plugins")
+            .assert()
+            .success();
+
+        assert!(sandbox
+            .path()
+            .join(".proto/plugins/blobs/abc.wasm")
+            .exists());
+        assert!(!sandbox
+            .path()
+            .join(".proto/plugins/blobs/def.wasm")
+            .exists());
+        assert!(!sandbox
+            .path()
+            .join(".proto/plugins/blobs/ghi.wasm")
+            .exists());
+    }
+
+    #[test]
+    fn reports_reclaimable_space_in_dry_run() {
+        let sandbox = create_empty_sandbox();
+        create_stale_versions(&sandbox);
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd.arg("clean").arg("--dry-run").assert();
+
+        assert
+            .success()
+            .stdout(predicate::str::contains("2.0.0 (6 B)"))
+            .stdout(predicate::str::contains("1.0.0").not())
+            .stdout(predicate::str::contains("3.0.0").not())
+            .stdout(predicate::str::contains("Total reclaimable: 6 B"));
+
+        assert!(sandbox.path().join(".proto/tools/node/2.0.0").exists());
+    }
+
+    #[test]
+    fn keeps_latest_and_protected_versions() {
+        let sandbox = create_empty_sandbox();
+        create_stale_versions(&sandbox);
+
+        let mut cmd = create_proto_command(sandbox.path());
+        cmd.arg("clean").arg("--yes").assert().success();
+
+        assert!(sandbox.path().join(".proto/tools/node/1.0.0").exists());
+        assert!(!sandbox.path().join(".proto/tools/node/2.0.0").exists());
+        assert!(sandbox.path().join(".proto/tools/node/3.0.0").exists());
+    }
+
+    #[test]
+    fn auto_cleans_after_install_when_over_threshold() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(".proto/tools/node/1.0.0/.last-used", "0");
+        sandbox.create_file(".proto/tools/node/1.0.0/bin/node", "binary");
+        sandbox.create_file(
+            ".proto/tools/node/manifest.json",
+            r#"{
+  "installed_versions": ["1.0.0"],
+  "versions": { "1.0.0": { "installed_at": 0 } }
+}"#,
+        );
+        sandbox.create_file(
+            ".prototools",
+            r#"
+[settings.clean]
+auto-threshold = 0
+"#,
+        );
+
+        let mut cmd = create_proto_command(sandbox.path());
+        cmd.arg("install")
+            .arg("node")
+            .arg("19.0.0")
+            .arg("--")
+            .arg("--no-bundled-npm")
+            .assert()
+            .success();
+
+        assert!(!sandbox.path().join(".proto/tools/node/1.0.0").exists());
+        assert!(sandbox.path().join(".proto/tools/node/19.0.0").exists());
+    }
 }
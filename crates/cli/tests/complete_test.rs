@@ -0,0 +1,81 @@
+mod utils;
+
+use proto_core::{ToolManifest, VersionSpec};
+use starbase_sandbox::{output_to_string, predicates::prelude::*};
+use utils::*;
+
+mod complete {
+    use super::*;
+
+    #[test]
+    fn completes_tool_ids() {
+        let sandbox = create_empty_sandbox();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd.args(["complete", "--", "install", "no"]).assert();
+
+        let output = output_to_string(&assert.get_output().stdout);
+
+        assert_eq!(output.trim(), "node");
+    }
+
+    #[test]
+    fn completes_installed_versions() {
+        let sandbox = create_empty_sandbox();
+
+        let mut manifest =
+            ToolManifest::load(sandbox.path().join(".proto/tools/node/manifest.json")).unwrap();
+        manifest
+            .installed_versions
+            .insert(VersionSpec::parse("19.0.0").unwrap());
+        manifest
+            .installed_versions
+            .insert(VersionSpec::parse("18.0.0").unwrap());
+        manifest.save().unwrap();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd
+            .args(["complete", "--", "run", "--alt", "npx", "node", "19"])
+            .assert();
+
+        let output = output_to_string(&assert.get_output().stdout);
+
+        assert_eq!(output.trim(), "19.0.0");
+    }
+
+    #[test]
+    fn completes_nothing_for_flags() {
+        let sandbox = create_empty_sandbox();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        cmd.args(["complete", "--", "install", "--pi"])
+            .assert()
+            .success()
+            .stdout(predicate::str::is_empty());
+    }
+}
+
+mod completions {
+    use super::*;
+
+    #[test]
+    fn appends_dynamic_completer() {
+        let sandbox = create_empty_sandbox();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        cmd.args(["completions", "--shell", "bash", "--dynamic"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("proto complete --"));
+    }
+
+    #[test]
+    fn errors_for_unsupported_dynamic_shell() {
+        let sandbox = create_empty_sandbox();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        cmd.args(["completions", "--shell", "elvish", "--dynamic"])
+            .assert()
+            .failure();
+    }
+}
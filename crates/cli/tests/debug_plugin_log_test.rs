@@ -0,0 +1,40 @@
+mod utils;
+
+use starbase_sandbox::predicates::prelude::*;
+use utils::*;
+
+mod debug_plugin_log {
+    use super::*;
+
+    #[test]
+    fn reports_no_calls() {
+        let sandbox = create_empty_sandbox();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd.arg("debug").arg("plugin-log").arg("node").assert();
+
+        assert.success().stderr(predicate::str::contains(
+            "No plugin calls have been recorded for node",
+        ));
+    }
+
+    #[test]
+    fn records_calls_after_running_commands() {
+        let sandbox = create_empty_sandbox();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        cmd.arg("list-remote").arg("node").assert().success();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd
+            .arg("debug")
+            .arg("plugin-log")
+            .arg("node")
+            .arg("--json")
+            .assert();
+
+        assert
+            .success()
+            .stdout(predicate::str::contains("\"func\": \"register_tool\""));
+    }
+}
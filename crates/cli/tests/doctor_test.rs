@@ -0,0 +1,317 @@
+mod utils;
+
+use proto_shim::get_exe_file_name;
+use starbase_sandbox::predicates::prelude::*;
+use utils::*;
+
+mod doctor {
+    use super::*;
+
+    #[test]
+    fn reports_shadowed_shims() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(format!("system/bin/{}", get_exe_file_name("node")), "");
+        sandbox.create_file(format!(".proto/shims/{}", get_exe_file_name("node")), "");
+        sandbox.create_file(format!(".proto/shims/{}", get_exe_file_name("npm")), "");
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd
+            .arg("doctor")
+            .env("PATH", create_path_env(&sandbox))
+            .assert();
+
+        assert
+            .failure()
+            .stdout(predicate::str::contains("node is shadowed by"))
+            .stdout(predicate::str::contains("npm is shadowed").not());
+    }
+
+    #[test]
+    fn reports_no_issues() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(format!(".proto/shims/{}", get_exe_file_name("node")), "");
+        sandbox.create_file(".proto/shims/registry.json", r#"{ "node": {} }"#);
+        sandbox.create_file(
+            ".proto/tools/node/manifest.json",
+            r#"{ "installed_versions": ["19.0.0"] }"#,
+        );
+        sandbox.create_file(".proto/tools/node/19.0.0/index.js", "");
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd
+            .arg("doctor")
+            .env("PATH", create_path_env(&sandbox))
+            .assert();
+
+        assert
+            .success()
+            .stdout(predicate::str::contains("No shims are shadowed on PATH"));
+    }
+
+    #[test]
+    fn reports_version_managers_before_shims() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(
+            format!(
+                ".nvm/versions/node/v20.0.0/bin/{}",
+                get_exe_file_name("node")
+            ),
+            "",
+        );
+        sandbox.create_file(format!(".proto/shims/{}", get_exe_file_name("node")), "");
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd
+            .arg("doctor")
+            .env(
+                "PATH",
+                env::join_paths([
+                    sandbox.path().join(".nvm/versions/node/v20.0.0/bin"),
+                    sandbox.path().join(".proto/shims"),
+                ])
+                .unwrap(),
+            )
+            .assert();
+
+        assert
+            .failure()
+            .stdout(predicate::str::contains("nvm ("))
+            .stdout(predicate::str::contains(
+                "comes before proto's shims on PATH",
+            ));
+    }
+
+    #[test]
+    fn explains_precedence_with_managers() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(format!(".pyenv/shims/{}", get_exe_file_name("python")), "");
+        sandbox.create_file(format!(".proto/shims/{}", get_exe_file_name("python")), "");
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd
+            .arg("doctor")
+            .arg("--managers")
+            .env(
+                "PATH",
+                env::join_paths([
+                    sandbox.path().join(".proto/shims"),
+                    sandbox.path().join(".pyenv/shims"),
+                ])
+                .unwrap(),
+            )
+            .assert();
+
+        assert
+            .success()
+            .stdout(predicate::str::contains("pyenv ("))
+            .stdout(predicate::str::contains("python resolves to proto shim"))
+            .stdout(predicate::str::contains("then pyenv"))
+            .stdout(predicate::str::contains("Shims").not());
+    }
+
+    #[test]
+    fn reports_unregistered_shims() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(format!(".proto/shims/{}", get_exe_file_name("node")), "");
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd
+            .arg("doctor")
+            .env("PATH", create_path_env(&sandbox))
+            .assert();
+
+        assert
+            .failure()
+            .stdout(predicate::str::contains("node is not in the shim registry"));
+    }
+
+    #[test]
+    fn reports_missing_shims_dir_on_path() {
+        let sandbox = create_empty_sandbox();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd
+            .arg("doctor")
+            .env("PATH", sandbox.path().join("system/bin"))
+            .assert();
+
+        assert
+            .failure()
+            .stdout(predicate::str::contains("is not on PATH"));
+    }
+
+    #[test]
+    fn reports_orphaned_versions() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(
+            ".proto/tools/node/manifest.json",
+            r#"{ "installed_versions": ["19.0.0"] }"#,
+        );
+        sandbox.create_file(".proto/tools/node/19.0.0/index.js", "");
+        sandbox.create_file(".proto/tools/node/18.0.0/index.js", "");
+        sandbox.create_file(".proto/tools/node/globals/bin/tool", "");
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd
+            .arg("doctor")
+            .env("PATH", create_path_env(&sandbox))
+            .assert();
+
+        assert
+            .failure()
+            .stdout(predicate::str::contains(
+                "18.0.0 is not in the tool's manifest",
+            ))
+            .stdout(predicate::str::contains("19.0.0 is not").not())
+            .stdout(predicate::str::contains("globals is not").not());
+    }
+
+    #[test]
+    fn reports_interrupted_installs() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(
+            ".proto/tools/node/manifest.json",
+            r#"{ "install_states": { "20.0.0": "staging" } }"#,
+        );
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd
+            .arg("doctor")
+            .env("PATH", create_path_env(&sandbox))
+            .assert();
+
+        assert.failure().stdout(predicate::str::contains(
+            "node 20.0.0 was interrupted while unpacking",
+        ));
+    }
+
+    #[test]
+    fn reports_quarantined_versions() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(
+            ".proto/tools/node/manifest.json",
+            r#"{ "installed_versions": ["19.0.0", "20.0.0"] }"#,
+        );
+        sandbox.create_file(".proto/tools/node/19.0.0/.install-receipt.json", "{}");
+        sandbox.create_file(".proto/tools/node/20.0.0/bin/node", "");
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd
+            .arg("doctor")
+            .env("PATH", create_path_env(&sandbox))
+            .assert();
+
+        assert
+            .failure()
+            .stdout(predicate::str::contains(
+                "node 19.0.0 is installed, but its files are missing",
+            ))
+            .stdout(predicate::str::contains("node 20.0.0 is installed").not())
+            .stdout(predicate::str::contains("safe-unpack"));
+    }
+
+    #[test]
+    fn reports_interrupted_unpacks() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(".proto/temp/unpack/node-20.0.0/bin/node", "");
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd
+            .arg("doctor")
+            .env("PATH", create_path_env(&sandbox))
+            .assert();
+
+        assert.failure().stdout(predicate::str::contains(
+            "left behind by an interrupted unpack",
+        ));
+    }
+
+    #[test]
+    fn reports_recent_integrity_failures() {
+        let sandbox = create_empty_sandbox();
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis();
+
+        sandbox.create_file(
+            ".proto/integrity.json",
+            format!(
+                r#"[
+  {{ "download_url": "https://example.com/node-18.tar.gz", "recorded_at": 0, "tool": "node", "version": "18.0.0" }},
+  {{ "download_url": "https://example.com/node-20.tar.gz", "recorded_at": {now}, "tool": "node", "version": "20.0.0" }}
+]"#
+            ),
+        );
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd
+            .arg("doctor")
+            .env("PATH", create_path_env(&sandbox))
+            .assert();
+
+        assert
+            .failure()
+            .stdout(predicate::str::contains(
+                "node 20.0.0 failed verification when downloading",
+            ))
+            .stdout(predicate::str::contains("node 18.0.0").not());
+    }
+
+    #[test]
+    fn reports_unknown_tools_in_config() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(".prototools", r#"unknown-tool = "1.0.0""#);
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd
+            .arg("doctor")
+            .arg("--json")
+            .env("PATH", create_path_env(&sandbox))
+            .assert();
+
+        assert
+            .failure()
+            .stdout(predicate::str::contains("unknown-tool in"))
+            .stdout(predicate::str::contains("\"passed\": false"))
+            .stdout(predicate::str::contains("\"problems\""));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn repairs_cache_permissions() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(
+            ".prototools",
+            r#"
+[settings]
+cache-permissions = "group"
+"#,
+        );
+        sandbox.create_file(".proto/cache/downloads/blob", "");
+
+        let blob = sandbox.path().join(".proto/cache/downloads/blob");
+
+        std::fs::set_permissions(&blob, std::fs::Permissions::from_mode(0o600)).unwrap();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        cmd.arg("doctor")
+            .env("PATH", create_path_env(&sandbox))
+            .assert()
+            .stdout(predicate::str::contains("has mode 600"));
+
+        let mut cmd = create_proto_command(sandbox.path());
+        cmd.arg("doctor")
+            .arg("--fix")
+            .env("PATH", create_path_env(&sandbox))
+            .assert()
+            .stdout(predicate::str::contains("Repaired"));
+
+        assert_eq!(
+            std::fs::metadata(&blob).unwrap().permissions().mode() & 0o777,
+            0o664
+        );
+    }
+}
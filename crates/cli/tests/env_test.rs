@@ -0,0 +1,30 @@
+mod utils;
+
+use starbase_sandbox::predicates::prelude::*;
+use utils::*;
+
+mod env {
+    use super::*;
+
+    #[test]
+    fn prints_nothing_without_sdk_roots() {
+        let sandbox = create_empty_sandbox();
+
+        sandbox.create_file(".prototools", r#"node = "19.0.0""#);
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd.arg("env").assert().success();
+
+        assert.stdout(predicate::str::is_empty());
+    }
+
+    #[test]
+    fn prints_json() {
+        let sandbox = create_empty_sandbox();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd.arg("env").arg("--json").assert().success();
+
+        assert.stdout(predicate::str::contains("{}"));
+    }
+}
@@ -0,0 +1,73 @@
+mod utils;
+
+use starbase_sandbox::predicates::prelude::*;
+use utils::*;
+
+mod exec_env {
+    use super::*;
+
+    #[test]
+    fn errors_if_not_installed() {
+        let sandbox = create_empty_sandbox();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        cmd.arg("exec-env")
+            .arg("node")
+            .arg("19.0.0")
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("has not been installed"));
+    }
+
+    #[test]
+    fn prints_env_diff() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(
+            ".prototools",
+            r#"
+[env]
+EXEC_ENV_TEST = "abc"
+"#,
+        );
+
+        let mut cmd = create_proto_command(sandbox.path());
+        cmd.arg("install")
+            .arg("node")
+            .arg("19.0.0")
+            .assert()
+            .success();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        cmd.arg("exec-env")
+            .arg("node")
+            .arg("19.0.0")
+            .arg("--json")
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("\"EXEC_ENV_TEST\""))
+            .stdout(predicate::str::contains("\"NODE_VERSION\""))
+            .stdout(predicate::str::contains("\"path_prepended\""));
+    }
+
+    #[test]
+    fn detects_from_cwd() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file("project/.prototools", "node = \"19.0.0\"");
+
+        let mut cmd = create_proto_command(sandbox.path());
+        cmd.arg("install")
+            .arg("node")
+            .arg("19.0.0")
+            .assert()
+            .success();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        cmd.arg("exec-env")
+            .arg("node")
+            .arg("--cwd")
+            .arg(sandbox.path().join("project"))
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("19.0.0"));
+    }
+}
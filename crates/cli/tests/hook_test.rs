@@ -0,0 +1,43 @@
+mod utils;
+
+use starbase_sandbox::predicates::prelude::*;
+use utils::*;
+
+mod hook_pre_commit {
+    use super::*;
+
+    #[test]
+    fn passes_when_nothing_configured() {
+        let sandbox = create_empty_sandbox();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        cmd.arg("hook").arg("pre-commit").assert().success();
+    }
+
+    #[test]
+    fn fails_for_missing_plugins() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(".prototools", "unknown-tool = \"1.0.0\"");
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd.arg("hook").arg("pre-commit").assert();
+
+        assert
+            .failure()
+            .stderr(predicate::str::contains("no plugin has been configured"));
+    }
+
+    #[test]
+    fn generates_hook_definition() {
+        let sandbox = create_empty_sandbox();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd.arg("hook").arg("pre-commit").arg("--generate").assert();
+
+        assert
+            .success()
+            .stdout(predicate::str::contains("id: proto-status"))
+            .stdout(predicate::str::contains("entry: proto hook pre-commit"))
+            .stdout(predicate::str::contains(r"\.prototools"));
+    }
+}
@@ -0,0 +1,60 @@
+mod utils;
+
+use starbase_sandbox::predicates::prelude::*;
+use std::fs;
+use utils::*;
+
+mod init {
+    use super::*;
+
+    #[test]
+    fn pins_detected_versions() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(".nvmrc", "20.0.0");
+
+        let mut cmd = create_proto_command(sandbox.path());
+        cmd.arg("init")
+            .arg("--yes")
+            .arg("--no-setup")
+            .assert()
+            .success()
+            .stderr(predicate::str::contains(".nvmrc"));
+
+        assert!(fs::read_to_string(sandbox.path().join(".prototools"))
+            .unwrap()
+            .contains("node = \"20.0.0\""));
+    }
+
+    #[test]
+    fn writes_config_without_ecosystem_files() {
+        let sandbox = create_empty_sandbox();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        cmd.arg("init")
+            .arg("--yes")
+            .arg("--no-setup")
+            .assert()
+            .success();
+
+        assert!(sandbox.path().join(".prototools").exists());
+    }
+
+    #[test]
+    fn doesnt_overwrite_existing_pins() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(".prototools", "deno = \"1.40.0\"\n");
+        sandbox.create_file(".nvmrc", "20.0.0");
+
+        let mut cmd = create_proto_command(sandbox.path());
+        cmd.arg("init")
+            .arg("--yes")
+            .arg("--no-setup")
+            .assert()
+            .success();
+
+        let config = fs::read_to_string(sandbox.path().join(".prototools")).unwrap();
+
+        assert!(config.contains("deno = \"1.40.0\""));
+        assert!(config.contains("node = \"20.0.0\""));
+    }
+}
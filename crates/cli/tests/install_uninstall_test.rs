@@ -185,6 +185,129 @@ mod install_uninstall {
             .contains_key(&VersionSpec::parse("19.0.0").unwrap()));
     }
 
+    #[test]
+    fn writes_a_receipt_after_installing() {
+        let sandbox = create_empty_sandbox();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        cmd.arg("install")
+            .arg("node")
+            .arg("19.0.0")
+            .arg("--")
+            .arg("--no-bundled-npm")
+            .assert()
+            .success();
+
+        let manifest =
+            ToolManifest::load(sandbox.path().join(".proto/tools/node/manifest.json")).unwrap();
+        let receipt = manifest
+            .load_receipt(sandbox.path().join(".proto/tools/node/19.0.0"))
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(receipt.version, VersionSpec::parse("19.0.0").unwrap());
+        assert!(receipt.checksum.is_some());
+        assert!(receipt.download_url.is_some());
+    }
+
+    #[test]
+    fn detects_legacy_install_without_receipt() {
+        let sandbox = create_empty_sandbox();
+        let receipt_path = sandbox
+            .path()
+            .join(".proto/tools/node/19.0.0/.install-receipt.json");
+
+        let mut cmd = create_proto_command(sandbox.path());
+        cmd.arg("install")
+            .arg("node")
+            .arg("19.0.0")
+            .arg("--")
+            .arg("--no-bundled-npm")
+            .assert()
+            .success();
+
+        // Versions installed before receipts existed only have a manifest entry
+        std::fs::remove_file(&receipt_path).unwrap();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd
+            .arg("install")
+            .arg("node")
+            .arg("19.0.0")
+            .arg("--")
+            .arg("--no-bundled-npm")
+            .assert();
+
+        assert
+            .success()
+            .stderr(predicate::str::contains("has already been installed"));
+
+        assert!(!receipt_path.exists());
+    }
+
+    #[test]
+    fn removes_leftovers_from_an_incomplete_install() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(".proto/tools/node/19.0.0/leftover.txt", "");
+
+        let mut cmd = create_proto_command(sandbox.path());
+        cmd.arg("install")
+            .arg("node")
+            .arg("19.0.0")
+            .arg("--")
+            .arg("--no-bundled-npm")
+            .assert()
+            .success();
+
+        assert!(!sandbox
+            .path()
+            .join(".proto/tools/node/19.0.0/leftover.txt")
+            .exists());
+        assert!(sandbox
+            .path()
+            .join(".proto/tools/node/19.0.0/.install-receipt.json")
+            .exists());
+    }
+
+    #[test]
+    fn errors_when_build_from_source_is_unsupported() {
+        let sandbox = create_empty_sandbox_with_tools();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd.arg("install").arg("moon-test").arg("--build").assert();
+
+        assert.failure().stderr(predicate::str::contains(
+            "Build from source is not supported",
+        ));
+    }
+
+    #[test]
+    fn removes_install_when_exceeding_plugin_quota() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(
+            ".prototools",
+            r#"
+[settings]
+plugin-quota = "1KB"
+"#,
+        );
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd
+            .arg("install")
+            .arg("node")
+            .arg("19.0.0")
+            .arg("--")
+            .arg("--no-bundled-npm")
+            .assert();
+
+        assert
+            .failure()
+            .stderr(predicate::str::contains("exceeds the plugin quota of 1KB"));
+
+        assert!(!sandbox.path().join(".proto/tools/node/19.0.0/bin").exists());
+    }
+
     mod pin {
         use super::*;
 
@@ -0,0 +1,102 @@
+mod utils;
+
+use proto_core::{ToolManifest, VersionSpec};
+use starbase_sandbox::predicates::prelude::*;
+use utils::*;
+
+mod inventory_add {
+    use super::*;
+
+    #[test]
+    fn errors_if_path_doesnt_exist() {
+        let sandbox = create_empty_sandbox();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd
+            .arg("inventory")
+            .arg("add")
+            .arg("node")
+            .arg("20.11.0")
+            .arg("--path")
+            .arg("opt/node-20.11.0")
+            .assert();
+
+        assert.stderr(predicate::str::contains("as the external installation"));
+    }
+
+    #[test]
+    fn adds_unmanaged_version_to_manifest() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(
+            if cfg!(windows) {
+                "opt/node-20.11.0/node.exe"
+            } else {
+                "opt/node-20.11.0/bin/node"
+            },
+            "",
+        );
+
+        let mut cmd = create_proto_command(sandbox.path());
+        cmd.arg("inventory")
+            .arg("add")
+            .arg("node")
+            .arg("20.11.0")
+            .arg("--path")
+            .arg(sandbox.path().join("opt/node-20.11.0"))
+            .assert()
+            .success();
+
+        let manifest =
+            ToolManifest::load(sandbox.path().join(".proto/tools/node/manifest.json")).unwrap();
+        let version = VersionSpec::parse("20.11.0").unwrap();
+
+        assert!(manifest.installed_versions.contains(&version));
+        assert_eq!(
+            manifest.versions.get(&version).unwrap().unmanaged_path,
+            Some(sandbox.path().join("opt/node-20.11.0"))
+        );
+        assert!(!sandbox.path().join(".proto/tools/node/20.11.0").exists());
+    }
+
+    #[test]
+    fn doesnt_uninstall_unmanaged_unless_forced() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(
+            if cfg!(windows) {
+                "opt/node-20.11.0/node.exe"
+            } else {
+                "opt/node-20.11.0/bin/node"
+            },
+            "",
+        );
+
+        let mut cmd = create_proto_command(sandbox.path());
+        cmd.arg("inventory")
+            .arg("add")
+            .arg("node")
+            .arg("20.11.0")
+            .arg("--path")
+            .arg(sandbox.path().join("opt/node-20.11.0"))
+            .assert()
+            .success();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd.arg("uninstall").arg("node").arg("20.11.0").assert();
+
+        assert.stderr(predicate::str::contains("is not managed by proto"));
+
+        let mut cmd = create_proto_command(sandbox.path());
+        cmd.arg("uninstall")
+            .arg("node")
+            .arg("20.11.0")
+            .arg("--force")
+            .assert()
+            .success();
+
+        let manifest =
+            ToolManifest::load(sandbox.path().join(".proto/tools/node/manifest.json")).unwrap();
+
+        assert!(manifest.installed_versions.is_empty());
+        assert!(sandbox.path().join("opt/node-20.11.0").exists());
+    }
+}
@@ -0,0 +1,33 @@
+mod utils;
+
+use utils::*;
+
+mod log_format {
+    use super::*;
+
+    #[test]
+    fn writes_json_records_to_stderr() {
+        let sandbox = create_empty_sandbox();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd
+            .arg("upgrade")
+            .arg("--repair")
+            .arg("--log-format")
+            .arg("json")
+            .assert()
+            .success();
+
+        let output = assert.get_output();
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let records = stderr
+            .lines()
+            .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap())
+            .collect::<Vec<_>>();
+
+        assert!(output.stdout.is_empty());
+        assert!(records.iter().any(|record| {
+            record["level"] == "INFO" && record["message"] == "No interrupted upgrade to repair"
+        }));
+    }
+}
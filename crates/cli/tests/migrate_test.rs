@@ -0,0 +1,67 @@
+mod utils;
+
+use proto_core::{Id, UnresolvedVersionSpec};
+use starbase_sandbox::predicates::prelude::*;
+use utils::*;
+
+mod migrate_asdf {
+    use super::*;
+
+    #[test]
+    fn errors_if_no_tool_versions() {
+        let sandbox = create_empty_sandbox();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd.arg("migrate").arg("asdf").assert();
+
+        assert.stderr(predicate::str::contains("No asdf"));
+    }
+
+    #[test]
+    fn converts_tool_versions() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(
+            ".tool-versions",
+            "nodejs 20.10.0 18.0.0\nbun 1.0.0 # comment\npython system\n",
+        );
+
+        let mut cmd = create_proto_command(sandbox.path());
+        cmd.arg("migrate").arg("asdf").assert().success();
+
+        let config = load_config(sandbox.path());
+
+        assert_eq!(
+            config.versions.get("node").unwrap(),
+            &UnresolvedVersionSpec::parse("20.10.0").unwrap()
+        );
+        assert_eq!(
+            config.fallback_versions.get("node").unwrap(),
+            &vec![
+                UnresolvedVersionSpec::parse("20.10.0").unwrap(),
+                UnresolvedVersionSpec::parse("18.0.0").unwrap()
+            ]
+        );
+        assert_eq!(
+            config.versions.get("bun").unwrap(),
+            &UnresolvedVersionSpec::parse("1.0.0").unwrap()
+        );
+        assert!(!config.versions.contains_key(&Id::raw("python")));
+    }
+
+    #[test]
+    fn keeps_existing_pins() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(".prototools", "node = \"19.0.0\"");
+        sandbox.create_file(".tool-versions", "nodejs 20.10.0");
+
+        let mut cmd = create_proto_command(sandbox.path());
+        cmd.arg("migrate").arg("asdf").assert().success();
+
+        let config = load_config(sandbox.path());
+
+        assert_eq!(
+            config.versions.get("node").unwrap(),
+            &UnresolvedVersionSpec::parse("19.0.0").unwrap()
+        );
+    }
+}
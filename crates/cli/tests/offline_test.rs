@@ -0,0 +1,85 @@
+mod utils;
+
+use starbase_sandbox::predicates::prelude::*;
+use utils::*;
+
+mod offline {
+    use super::*;
+
+    #[test]
+    fn errors_installing_without_previous_download() {
+        let sandbox = create_empty_sandbox();
+
+        // Cache the plugin and available versions while online
+        let mut cmd = create_proto_command(sandbox.path());
+        cmd.arg("list-remote").arg("node").assert().success();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd
+            .arg("install")
+            .arg("node")
+            .arg("19.0.0")
+            .arg("--offline")
+            .assert();
+
+        assert.stderr(predicate::str::contains(
+            "while offline, as it has not been downloaded previously",
+        ));
+    }
+
+    #[test]
+    fn resolves_from_installed_versions_without_cache() {
+        let sandbox = create_empty_sandbox();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        cmd.arg("install")
+            .arg("node")
+            .arg("19.0.0")
+            .assert()
+            .success();
+
+        std::fs::remove_file(
+            sandbox
+                .path()
+                .join(".proto/tools/node/remote-versions.json"),
+        )
+        .unwrap();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd
+            .arg("bin")
+            .arg("node")
+            .arg("latest")
+            .arg("--offline")
+            .assert();
+
+        assert.success().stdout(predicate::str::contains("19.0.0"));
+    }
+
+    #[test]
+    fn errors_resolving_without_cache_or_installed_versions() {
+        let sandbox = create_empty_sandbox();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        cmd.arg("list-remote").arg("node").assert().success();
+
+        std::fs::remove_file(
+            sandbox
+                .path()
+                .join(".proto/tools/node/remote-versions.json"),
+        )
+        .unwrap();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd
+            .arg("bin")
+            .arg("node")
+            .arg("latest")
+            .arg("--offline")
+            .assert();
+
+        assert.stderr(predicate::str::contains(
+            "Internet connection required to load and resolve a valid version",
+        ));
+    }
+}
@@ -0,0 +1,171 @@
+mod utils;
+
+use proto_core::{ProtoConfig, UnresolvedVersionSpec};
+use starbase_sandbox::output_to_string;
+use std::path::PathBuf;
+use utils::*;
+
+fn run_json(sandbox: &Sandbox, args: &[&str]) -> serde_json::Value {
+    let mut cmd = create_proto_command(sandbox.path());
+    let assert = cmd
+        .arg("outdated")
+        .arg("--json")
+        .args(args)
+        .assert()
+        .success();
+
+    serde_json::from_str(&output_to_string(&assert.get_output().stdout)).unwrap()
+}
+
+mod outdated {
+    use super::*;
+
+    #[test]
+    fn exits_with_code_when_updates_available() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(".prototools", "node = \"19.0.0\"");
+
+        let mut cmd = create_proto_command(sandbox.path());
+        cmd.arg("outdated").assert().success();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        cmd.arg("outdated").arg("--exit-code").assert().code(1);
+    }
+
+    #[test]
+    fn prints_table_of_versions() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(".prototools", "node = \"19.0.0\"");
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd.arg("outdated").assert().success();
+        let output = output_to_string(&assert.get_output().stdout);
+        let mut lines = output.lines();
+
+        let header = lines.next().unwrap();
+
+        for column in ["Tool", "Current", "Newer", "Latest", "Config"] {
+            assert!(header.contains(column));
+        }
+
+        let row = lines.next().unwrap();
+
+        assert!(row.starts_with("node"));
+        assert!(row.contains("19.0.0"));
+    }
+
+    #[test]
+    fn includes_latest_version_and_config_path_in_json() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(".prototools", "node = \"~19\"");
+
+        let json = run_json(&sandbox, &[]);
+        let item = &json["node"];
+
+        assert_eq!(item["version_config"], "~19");
+        assert!(item["newer_version"].as_str().unwrap().starts_with("19."));
+        assert!(!item["latest_version"].as_str().unwrap().starts_with("19."));
+        assert_eq!(
+            PathBuf::from(item["config_path"].as_str().unwrap()),
+            sandbox.path().join(".prototools")
+        );
+    }
+
+    #[test]
+    fn prefers_local_pin_over_global_pin() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(".prototools", "node = \"19.0.0\"");
+        sandbox.create_file(".proto/.prototools", "node = \"18.0.0\"");
+
+        let json = run_json(&sandbox, &["--include-global"]);
+        let item = &json["node"];
+
+        assert_eq!(item["version_config"], "19.0.0");
+        assert_eq!(item["current_version"], "19.0.0");
+        assert_eq!(
+            PathBuf::from(item["config_path"].as_str().unwrap()),
+            sandbox.path().join(".prototools")
+        );
+    }
+
+    #[test]
+    fn reports_global_pin_config_path() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(".proto/.prototools", "node = \"18.0.0\"");
+
+        let json = run_json(&sandbox, &["--include-global"]);
+        let item = &json["node"];
+
+        assert_eq!(item["version_config"], "18.0.0");
+        assert_eq!(
+            PathBuf::from(item["config_path"].as_str().unwrap()),
+            sandbox.path().join(".proto/.prototools")
+        );
+    }
+}
+
+mod outdated_update {
+    use super::*;
+
+    #[test]
+    fn writes_to_local_config_that_pinned_version() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(".prototools", "node = \"19.0.0\"");
+        sandbox.create_file(".proto/.prototools", "node = \"18.0.0\"");
+
+        let mut cmd = create_proto_command(sandbox.path());
+        cmd.arg("outdated")
+            .arg("--include-global")
+            .arg("--update")
+            .assert()
+            .success();
+
+        let local_config = load_config(sandbox.path());
+        let local_version = local_config.versions.get("node").unwrap();
+
+        assert_ne!(
+            local_version,
+            &UnresolvedVersionSpec::parse("19.0.0").unwrap()
+        );
+        assert_ne!(
+            local_version,
+            &UnresolvedVersionSpec::parse("18.0.0").unwrap()
+        );
+
+        let global_versions = ProtoConfig::load_from(sandbox.path().join(".proto"), false)
+            .unwrap()
+            .versions
+            .unwrap();
+
+        assert_eq!(
+            global_versions.get("node").unwrap(),
+            &UnresolvedVersionSpec::parse("18.0.0").unwrap()
+        );
+    }
+
+    #[test]
+    fn writes_to_global_config_that_pinned_version() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(".proto/.prototools", "node = \"18.0.0\"");
+
+        let mut cmd = create_proto_command(sandbox.path());
+        cmd.arg("outdated")
+            .arg("--include-global")
+            .arg("--update")
+            .assert()
+            .success();
+
+        let global_versions = ProtoConfig::load_from(sandbox.path().join(".proto"), false)
+            .unwrap()
+            .versions
+            .unwrap();
+
+        assert_ne!(
+            global_versions.get("node").unwrap(),
+            &UnresolvedVersionSpec::parse("18.0.0").unwrap()
+        );
+
+        // Not written to the current directory
+        assert!(!sandbox.path().join(".prototools").exists());
+    }
+}
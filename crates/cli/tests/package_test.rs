@@ -0,0 +1,80 @@
+mod utils;
+
+use proto_core::{ToolManifest, VersionSpec};
+use starbase_sandbox::predicates::prelude::*;
+use utils::*;
+
+mod package {
+    use super::*;
+
+    #[test]
+    fn errors_if_not_installed() {
+        let sandbox = create_empty_sandbox();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd.arg("package").arg("node").arg("19.0.0").assert();
+
+        assert
+            .failure()
+            .stderr(predicate::str::contains("has not been installed"));
+    }
+
+    #[test]
+    fn packages_and_installs_from_file() {
+        let sandbox = create_empty_sandbox();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        cmd.arg("install")
+            .arg("node")
+            .arg("19.0.0")
+            .assert()
+            .success();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        cmd.arg("package")
+            .arg("node")
+            .arg("19.0.0")
+            .arg("--out")
+            .arg("node.tar.gz")
+            .assert()
+            .success();
+
+        assert!(sandbox.path().join("node.tar.gz").exists());
+
+        // Install into a fresh proto home
+        let target = create_empty_sandbox();
+
+        let mut cmd = create_proto_command(target.path());
+        cmd.arg("install")
+            .arg("node")
+            .arg("--from-file")
+            .arg(sandbox.path().join("node.tar.gz"))
+            .assert()
+            .success();
+
+        let manifest =
+            ToolManifest::load(target.path().join(".proto/tools/node/manifest.json")).unwrap();
+
+        assert!(manifest
+            .installed_versions
+            .contains(&VersionSpec::parse("19.0.0").unwrap()));
+        assert!(target.path().join(".proto/tools/node/19.0.0").exists());
+    }
+
+    #[test]
+    fn errors_for_missing_package_file() {
+        let sandbox = create_empty_sandbox();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd
+            .arg("install")
+            .arg("node")
+            .arg("--from-file")
+            .arg("missing.tar.gz")
+            .assert();
+
+        assert
+            .failure()
+            .stderr(predicate::str::contains("does not exist"));
+    }
+}
@@ -1,6 +1,7 @@
 mod utils;
 
 use proto_core::UnresolvedVersionSpec;
+use starbase_sandbox::predicates::prelude::*;
 use std::fs;
 use utils::*;
 
@@ -121,6 +122,174 @@ npm = "9.0.0"
             "npm = \"6.14.18\"\n"
         )
     }
+
+    #[test]
+    fn pins_multiple_tools() {
+        let sandbox = create_empty_sandbox();
+        let version_file = sandbox.path().join(".prototools");
+
+        let mut cmd = create_proto_command(sandbox.path());
+        cmd.arg("pin")
+            .arg("node@20")
+            .arg("npm@9.0.0")
+            .assert()
+            .success();
+
+        assert_eq!(
+            fs::read_to_string(version_file).unwrap(),
+            r#"node = "~20"
+npm = "9.0.0"
+"#
+        )
+    }
+
+    #[test]
+    fn errors_for_missing_version() {
+        let sandbox = create_empty_sandbox();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd.arg("pin").arg("node@20").arg("npm").assert();
+
+        assert.stderr(predicate::str::contains("No version provided for npm"));
+    }
+
+    #[test]
+    fn pins_from_detected() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(".nvmrc", "18.0.0");
+        sandbox.create_file(".proto/.prototools", "npm = \"9.0.0\"");
+
+        let mut cmd = create_proto_command(sandbox.path());
+        cmd.arg("pin")
+            .arg("node")
+            .arg("npm")
+            .arg("--from-detected")
+            .assert()
+            .success();
+
+        assert_eq!(
+            fs::read_to_string(sandbox.path().join(".prototools")).unwrap(),
+            r#"node = "18.0.0"
+npm = "9.0.0"
+"#
+        )
+    }
+
+    #[test]
+    fn pins_from_stdin() {
+        let sandbox = create_empty_sandbox();
+        let version_file = sandbox.path().join(".prototools");
+
+        let mut cmd = create_proto_command(sandbox.path());
+        cmd.arg("pin")
+            .arg("--stdin")
+            .write_stdin("# tools\nnode 19.0.0\n\nnpm@9.0.0\n")
+            .assert()
+            .success();
+
+        assert_eq!(
+            fs::read_to_string(version_file).unwrap(),
+            r#"node = "19.0.0"
+npm = "9.0.0"
+"#
+        )
+    }
+
+    #[test]
+    fn pins_from_file() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file("pins.txt", "node 19.0.0\nnpm 9.0.0\n");
+
+        let mut cmd = create_proto_command(sandbox.path());
+        cmd.arg("pin")
+            .arg("--file")
+            .arg("pins.txt")
+            .assert()
+            .success();
+
+        assert_eq!(
+            fs::read_to_string(sandbox.path().join(".prototools")).unwrap(),
+            r#"node = "19.0.0"
+npm = "9.0.0"
+"#
+        )
+    }
+
+    #[test]
+    fn doesnt_pin_any_when_a_batch_line_is_invalid() {
+        let sandbox = create_empty_sandbox();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd
+            .arg("pin")
+            .arg("--stdin")
+            .write_stdin("node 19.0.0\nnpm\n")
+            .assert();
+
+        assert.stderr(predicate::str::contains("Invalid batch input on line 2"));
+
+        assert!(!sandbox.path().join(".prototools").exists());
+    }
+}
+
+mod pin_sync_ecosystem {
+    use super::*;
+
+    #[test]
+    fn doesnt_create_version_files() {
+        let sandbox = create_empty_sandbox();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        cmd.arg("pin")
+            .arg("node")
+            .arg("19.0.0")
+            .arg("--sync-ecosystem")
+            .assert()
+            .success();
+
+        assert!(!sandbox.path().join(".nvmrc").exists());
+        assert!(!sandbox.path().join(".node-version").exists());
+    }
+
+    #[test]
+    fn updates_plain_version_files() {
+        let sandbox = create_empty_sandbox();
+        let package_json = r#"{ "engines": { "node": "^18" } }"#;
+
+        sandbox.create_file(".nvmrc", "18.0.0\n");
+        sandbox.create_file("package.json", package_json);
+
+        let mut cmd = create_proto_command(sandbox.path());
+        cmd.arg("pin")
+            .arg("node")
+            .arg("19.0.0")
+            .arg("--sync-ecosystem")
+            .assert()
+            .success();
+
+        assert_eq!(
+            std::fs::read_to_string(sandbox.path().join(".nvmrc")).unwrap(),
+            "19.0.0\n"
+        );
+        assert_eq!(
+            std::fs::read_to_string(sandbox.path().join("package.json")).unwrap(),
+            package_json
+        );
+    }
+
+    #[test]
+    fn errors_when_global() {
+        let sandbox = create_empty_sandbox();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        cmd.arg("pin")
+            .arg("node")
+            .arg("19.0.0")
+            .arg("--global")
+            .arg("--sync-ecosystem")
+            .assert()
+            .failure();
+    }
 }
 
 mod pin_global {
@@ -24,6 +24,23 @@ mod plugin_add {
         ));
     }
 
+    #[test]
+    fn errors_pinning_non_github_locator() {
+        let sandbox = create_empty_sandbox();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd
+            .arg("plugin")
+            .arg("add")
+            .arg("id")
+            .arg("source:https://github.com/moonrepo/schema-plugin/releases/latest/download/schema_plugin.wasm")
+            .arg("--pin")
+            .assert();
+
+        assert.stderr(predicate::str::contains("Unable to pin plugin locator"));
+        assert!(!sandbox.path().join(".prototools").exists());
+    }
+
     #[test]
     fn updates_local_file() {
         let sandbox = create_empty_sandbox();
@@ -0,0 +1,46 @@
+mod utils;
+
+use starbase_sandbox::predicates::prelude::*;
+use utils::*;
+
+mod plugin_doctor {
+    use super::*;
+
+    #[test]
+    fn errors_for_unknown_plugin() {
+        let sandbox = create_empty_sandbox();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd.arg("plugin").arg("doctor").arg("unknown").assert();
+
+        assert.stderr(predicate::str::contains(
+            "unknown is not a built-in tool or has not been configured as a plugin",
+        ));
+    }
+
+    #[test]
+    fn detects_corrupt_wasm_file() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file("broken.wasm", "not a wasm file");
+        sandbox.create_file(
+            ".prototools",
+            r#"
+[plugins]
+broken = "source:./broken.wasm"
+"#,
+        );
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd
+            .arg("plugin")
+            .arg("doctor")
+            .arg("broken")
+            .arg("--json")
+            .assert();
+
+        assert
+            .failure()
+            .stdout(predicate::str::contains("\"name\": \"Plugin cache\""))
+            .stdout(predicate::str::contains("it may be corrupt"));
+    }
+}
@@ -0,0 +1,43 @@
+mod utils;
+
+use starbase_sandbox::predicates::prelude::*;
+use utils::*;
+
+mod plugin_info {
+    use super::*;
+
+    #[test]
+    fn prints_plugin_source_and_version_files() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(".prototools", r#"node = "19.0.0""#);
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd.arg("plugin").arg("info").arg("node").assert();
+
+        assert
+            .success()
+            .stdout(predicate::str::contains("Source:"))
+            .stdout(predicate::str::contains("Checksum:"))
+            .stdout(predicate::str::contains(".nvmrc"));
+    }
+
+    #[test]
+    fn includes_plugin_details_in_json() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(".prototools", r#"node = "19.0.0""#);
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd
+            .arg("plugin")
+            .arg("info")
+            .arg("node")
+            .arg("--json")
+            .assert();
+
+        assert
+            .success()
+            .stdout(predicate::str::contains("\"plugin_hash\""))
+            .stdout(predicate::str::contains("\"plugin_path\""))
+            .stdout(predicate::str::contains("\"version_files\""));
+    }
+}
@@ -0,0 +1,48 @@
+mod utils;
+
+use starbase_sandbox::predicates::prelude::*;
+use utils::*;
+
+mod plugin_list {
+    use super::*;
+
+    #[test]
+    fn continues_when_a_plugin_fails_to_load() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file("broken.wasm", "not a wasm file");
+        sandbox.create_file(
+            ".prototools",
+            r#"
+[plugins]
+broken = "source:./broken.wasm"
+"#,
+        );
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd.arg("plugin").arg("list").arg("broken").assert();
+
+        assert
+            .success()
+            .stderr(predicate::str::contains("which were skipped"))
+            .stderr(predicate::str::contains("broken Failed to load"))
+            .stderr(predicate::str::contains("proto clean --purge-plugins"));
+    }
+
+    #[test]
+    fn prints_each_plugin_with_a_format_template() {
+        let sandbox = create_empty_sandbox();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd
+            .arg("plugin")
+            .arg("list")
+            .arg("node")
+            .arg("--format")
+            .arg("{{id}}: {{name}}")
+            .assert();
+
+        assert
+            .success()
+            .stdout(predicate::str::contains("node: Node.js\n"));
+    }
+}
@@ -0,0 +1,129 @@
+mod utils;
+
+use starbase_sandbox::predicates::prelude::*;
+use starbase_sandbox::Sandbox;
+use utils::*;
+
+fn create_registry_sandbox() -> Sandbox {
+    let sandbox = create_empty_sandbox();
+
+    sandbox.create_file(
+        "registry.json",
+        r#"{
+  "plugins": [
+    {
+      "id": "zig",
+      "name": "Zig",
+      "description": "Zig language toolchain",
+      "locator": "github:konomae/zig-plugin",
+      "tags": ["language"]
+    },
+    {
+      "id": "hugo",
+      "name": "Hugo",
+      "description": "Static site generator",
+      "locator": "source:https://example.com/hugo_plugin.wasm"
+    }
+  ]
+}"#,
+    );
+
+    sandbox.create_file(
+        ".prototools",
+        format!(
+            "[settings.registries]\nexample = \"{}\"\n",
+            sandbox
+                .path()
+                .join("registry.json")
+                .to_string_lossy()
+                .replace('\\', "/")
+        ),
+    );
+
+    sandbox
+}
+
+mod plugin_search {
+    use super::*;
+
+    #[test]
+    fn errors_without_registries() {
+        let sandbox = create_empty_sandbox();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd.arg("plugin").arg("search").arg("zig").assert();
+
+        assert.stderr(predicate::str::contains(
+            "No plugin registries have been configured.",
+        ));
+    }
+
+    #[test]
+    fn finds_matching_plugins() {
+        let sandbox = create_registry_sandbox();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd.arg("plugin").arg("search").arg("LANGUAGE").assert();
+
+        assert
+            .success()
+            .stdout(predicate::str::contains("Zig language toolchain"))
+            .stdout(predicate::str::contains("konomae/zig-plugin"))
+            .stdout(predicate::str::contains("hugo").not());
+    }
+
+    #[test]
+    fn prints_json() {
+        let sandbox = create_registry_sandbox();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd
+            .arg("plugin")
+            .arg("search")
+            .arg("site")
+            .arg("--json")
+            .assert();
+
+        assert
+            .success()
+            .stdout(predicate::str::contains(r#""registry": "example""#))
+            .stdout(predicate::str::contains(r#""id": "hugo""#));
+    }
+
+    #[test]
+    fn adds_plugin_from_registry() {
+        let sandbox = create_registry_sandbox();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        cmd.arg("plugin")
+            .arg("add")
+            .arg("hugo")
+            .arg("--from-registry")
+            .assert()
+            .success();
+
+        let config = load_config(sandbox.path());
+
+        assert_eq!(
+            config.plugins.get("hugo").unwrap().to_string(),
+            "source:https://example.com/hugo_plugin.wasm"
+        );
+    }
+
+    #[test]
+    fn errors_adding_unknown_plugin_from_registry() {
+        let sandbox = create_registry_sandbox();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd
+            .arg("plugin")
+            .arg("add")
+            .arg("unknown")
+            .arg("--from-registry")
+            .assert();
+
+        assert.stderr(predicate::str::contains(
+            "could not be found in the configured registries",
+        ));
+    }
+}
@@ -4,6 +4,7 @@ use proto_core::{
     load_tool_from_locator, Id, PluginLocator, ProtoEnvironment, Tool, UnresolvedVersionSpec,
 };
 use starbase_sandbox::assert_snapshot;
+use starbase_sandbox::predicates::prelude::*;
 use std::env;
 use std::fs;
 use std::future::Future;
@@ -328,5 +329,83 @@ mod plugins {
 
             // Doesn't create shims
         }
+
+        #[test]
+        fn runs_toml_schema_hook_scripts() {
+            let sandbox = create_empty_sandbox();
+            let schema = fs::read_to_string(
+                PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/moon-schema.toml"),
+            )
+            .unwrap();
+
+            sandbox.create_file(
+                "moon.toml",
+                format!(
+                    r#"{schema}
+[hooks]
+pre-install = "echo pre > pre-install.txt"
+post-install = "echo post > post-install.txt"
+"#
+                ),
+            );
+            sandbox.create_file(
+                ".prototools",
+                r#"
+[plugins]
+moon-test = "source:./moon.toml"
+
+[tools.moon-test.permissions]
+script-hooks = true
+"#,
+            );
+
+            create_proto_command(sandbox.path())
+                .arg("install")
+                .arg("moon-test")
+                .arg("1.0.0")
+                .assert()
+                .success();
+
+            assert!(sandbox.path().join("pre-install.txt").exists());
+            assert!(sandbox.path().join("post-install.txt").exists());
+        }
+
+        #[test]
+        fn denies_toml_schema_hook_scripts_unless_allowed() {
+            let sandbox = create_empty_sandbox();
+            let schema = fs::read_to_string(
+                PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/moon-schema.toml"),
+            )
+            .unwrap();
+
+            sandbox.create_file(
+                "moon.toml",
+                format!(
+                    r#"{schema}
+[hooks]
+pre-install = "echo pre > pre-install.txt"
+"#
+                ),
+            );
+            sandbox.create_file(
+                ".prototools",
+                r#"
+[plugins]
+moon-test = "source:./moon.toml"
+"#,
+            );
+
+            create_proto_command(sandbox.path())
+                .arg("install")
+                .arg("moon-test")
+                .arg("1.0.0")
+                .assert()
+                .failure()
+                .stderr(predicate::str::contains(
+                    "hook scripts from third-party plugins must be allowed",
+                ));
+
+            assert!(!sandbox.path().join("pre-install.txt").exists());
+        }
     }
 }
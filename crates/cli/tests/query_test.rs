@@ -0,0 +1,65 @@
+mod utils;
+
+use proto_core::{ToolManifestQuery, VersionSpec, MANIFEST_QUERY_SCHEMA_VERSION};
+use starbase_sandbox::output_to_string;
+use utils::*;
+
+mod query_manifest {
+    use super::*;
+
+    #[test]
+    fn prints_empty_inventory() {
+        let sandbox = create_empty_sandbox();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd.args(["query", "manifest", "node"]).assert().success();
+
+        let query: ToolManifestQuery =
+            serde_json::from_str(&output_to_string(&assert.get_output().stdout)).unwrap();
+
+        assert_eq!(query.schema_version, MANIFEST_QUERY_SCHEMA_VERSION);
+        assert_eq!(query.id.as_str(), "node");
+        assert!(query.versions.is_empty());
+    }
+
+    #[test]
+    fn prints_installed_versions_in_order() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(".proto/tools/node/19.0.0/.last-used", "200");
+        sandbox.create_file(
+            ".proto/tools/node/manifest.json",
+            r#"{
+  "installed_versions": ["19.0.0", "18.0.0"],
+  "versions": {
+    "18.0.0": { "installed_at": 100, "no_clean": true },
+    "19.0.0": { "installed_at": 150, "run_count": 3 }
+  }
+}"#,
+        );
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd.args(["query", "manifest", "node"]).assert().success();
+
+        let query: ToolManifestQuery =
+            serde_json::from_str(&output_to_string(&assert.get_output().stdout)).unwrap();
+
+        assert_eq!(
+            query
+                .versions
+                .iter()
+                .map(|item| item.version.clone())
+                .collect::<Vec<_>>(),
+            vec![
+                VersionSpec::parse("18.0.0").unwrap(),
+                VersionSpec::parse("19.0.0").unwrap()
+            ]
+        );
+
+        assert_eq!(query.versions[0].installed_at, 100);
+        assert!(query.versions[0].no_clean);
+        assert_eq!(query.versions[0].last_used_at, None);
+        assert_eq!(query.versions[1].run_count, 3);
+        assert_eq!(query.versions[1].last_used_at, Some(200));
+        assert!(query.versions[1].path.ends_with("tools/node/19.0.0"));
+    }
+}
@@ -0,0 +1,71 @@
+mod utils;
+
+use starbase_sandbox::predicates::prelude::*;
+use utils::*;
+
+mod report {
+    use super::*;
+
+    #[test]
+    fn prints_json_without_tools() {
+        let sandbox = create_empty_sandbox();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd.arg("report").assert();
+
+        assert
+            .success()
+            .stdout(predicate::str::contains(r#""outdated_count": 0"#))
+            .stdout(predicate::str::contains(r#""tools": {}"#));
+    }
+
+    #[test]
+    fn prints_prometheus_metrics() {
+        let sandbox = create_empty_sandbox();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        cmd.arg("install")
+            .arg("node")
+            .arg("19.0.0")
+            .arg("--")
+            .arg("--no-bundled-npm")
+            .assert()
+            .success();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd.arg("report").arg("--format").arg("prometheus").assert();
+
+        assert
+            .success()
+            .stdout(predicate::str::contains("# TYPE proto_info gauge"))
+            .stdout(predicate::str::contains(
+                r#"proto_tool_installed{tool="node",version="19.0.0"} 1"#,
+            ))
+            .stdout(predicate::str::contains(
+                r#"proto_tool_outdated{tool="node",latest="#,
+            ))
+            .stdout(predicate::str::contains("proto_tools_outdated 1"));
+    }
+
+    #[test]
+    fn includes_installed_versions_in_json() {
+        let sandbox = create_empty_sandbox();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        cmd.arg("install")
+            .arg("node")
+            .arg("19.0.0")
+            .arg("--")
+            .arg("--no-bundled-npm")
+            .assert()
+            .success();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd.arg("report").arg("--format").arg("json").assert();
+
+        assert
+            .success()
+            .stdout(predicate::str::contains(r#""version": "19.0.0""#))
+            .stdout(predicate::str::contains(r#""outdated": true"#));
+    }
+}
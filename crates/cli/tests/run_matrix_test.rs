@@ -0,0 +1,85 @@
+mod utils;
+
+use starbase_sandbox::predicates::prelude::*;
+use utils::*;
+
+mod run_matrix {
+    use super::*;
+
+    #[test]
+    fn errors_for_invalid_target() {
+        let sandbox = create_empty_sandbox();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd
+            .arg("run-matrix")
+            .arg("node")
+            .arg("--")
+            .arg("node")
+            .assert();
+
+        assert.stderr(predicate::str::contains("Invalid run matrix target"));
+    }
+
+    #[test]
+    fn runs_each_version_without_pinning() {
+        let sandbox = create_empty_sandbox();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd
+            .arg("run-matrix")
+            .arg("node@19,20")
+            .arg("--")
+            .arg("node")
+            .arg("--version")
+            .assert();
+
+        assert
+            .success()
+            .stdout(predicate::str::contains("v19."))
+            .stdout(predicate::str::contains("v20."))
+            .stdout(predicate::str::contains("pass"));
+
+        assert!(!sandbox.path().join(".proto/.prototools").exists());
+    }
+
+    #[test]
+    fn fails_if_any_version_fails() {
+        let sandbox = create_empty_sandbox();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd
+            .arg("run-matrix")
+            .arg("node@20")
+            .arg("--json")
+            .arg("--")
+            .arg("node")
+            .arg("-e")
+            .arg("process.exit(3)")
+            .assert();
+
+        assert
+            .failure()
+            .stdout(predicate::str::contains("\"exit_code\": 3"));
+    }
+
+    #[test]
+    fn captures_stdout_in_json() {
+        let sandbox = create_empty_sandbox();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd
+            .arg("run-matrix")
+            .arg("node@20")
+            .arg("--json")
+            .arg("--")
+            .arg("node")
+            .arg("--version")
+            .assert();
+
+        let results: serde_json::Value =
+            serde_json::from_str(&output_to_string(&assert.success().get_output().stdout)).unwrap();
+
+        assert!(results[0]["stdout"].as_str().unwrap().starts_with("v20."));
+    }
+}
@@ -66,6 +66,87 @@ mod run {
         assert.stdout(predicate::str::contains("19.0.0"));
     }
 
+    #[test]
+    fn runs_a_tool_with_version_in_target() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(".prototools", "node = \"20.0.0\"");
+
+        install_node(sandbox.path());
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd
+            .arg("run")
+            .arg("node@19.0.0")
+            .arg("--")
+            .arg("--version")
+            .assert();
+
+        assert.stdout(predicate::str::contains("19.0.0"));
+    }
+
+    #[test]
+    fn errors_if_version_in_target_and_arg() {
+        let sandbox = create_empty_sandbox();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd.arg("run").arg("node@19.0.0").arg("18.0.0").assert();
+
+        assert
+            .failure()
+            .stderr(predicate::str::contains("A version was provided with both"));
+    }
+
+    #[test]
+    fn errors_if_invalid_target() {
+        let sandbox = create_empty_sandbox();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd.arg("run").arg("node@").assert();
+
+        assert
+            .failure()
+            .stderr(predicate::str::contains("Invalid run target"));
+    }
+
+    #[test]
+    fn prepends_exe_dir_to_path_when_enabled() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(
+            ".prototools",
+            r#"
+[tools.node]
+prepend-path = true
+"#,
+        );
+
+        let mut cmd = create_proto_command(sandbox.path());
+        cmd.arg("install")
+            .arg("node")
+            .arg("19.0.0")
+            .assert()
+            .success();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd
+            .arg("run")
+            .arg("node")
+            .arg("19.0.0")
+            .arg("--")
+            .arg("-e")
+            .arg("console.log(process.env.PATH.split(require('path').delimiter)[0])")
+            .assert();
+
+        let bin_dir = if cfg!(windows) {
+            sandbox.path().join(".proto/tools/node/19.0.0")
+        } else {
+            sandbox.path().join(".proto/tools/node/19.0.0/bin")
+        };
+
+        assert.stdout(predicate::str::contains(
+            bin_dir.to_string_lossy().to_string(),
+        ));
+    }
+
     #[test]
     fn runs_a_tool_using_version_detection() {
         let sandbox = create_empty_sandbox();
@@ -224,6 +305,27 @@ mod run {
         ));
     }
 
+    #[test]
+    fn doesnt_auto_install_if_opted_out() {
+        let sandbox = create_empty_sandbox();
+
+        sandbox.create_file(".prototools", "[settings]\nauto-install = true");
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd
+            .arg("run")
+            .arg("node")
+            .arg("19.0.0")
+            .arg("--no-install")
+            .assert();
+
+        assert.stderr(predicate::str::contains(
+            "This project requires Node.js 19.0.0",
+        ));
+
+        assert!(!sandbox.path().join(".proto/tools/node/19.0.0").exists());
+    }
+
     #[test]
     fn doesnt_auto_install_subsequently() {
         let sandbox = create_empty_sandbox();
@@ -401,4 +503,79 @@ FOURTH = "ignores-$FIRST-$PARENT"
             assert_snapshot!(get_assert_output(&assert));
         }
     }
+
+    mod passthrough {
+        use super::*;
+
+        #[test]
+        fn errors_without_id_or_path() {
+            let sandbox = create_empty_sandbox();
+
+            let mut cmd = create_proto_command(sandbox.path());
+            let assert = cmd.arg("run").assert();
+
+            assert.failure();
+        }
+
+        #[cfg(not(windows))]
+        #[test]
+        fn applies_config_env_vars() {
+            let sandbox = create_empty_sandbox();
+            sandbox.create_file(
+                ".prototools",
+                r#"
+[env]
+PASSTHROUGH_VAR = "abc"
+"#,
+            );
+
+            let mut cmd = create_proto_command(sandbox.path());
+            let assert = cmd
+                .arg("run")
+                .arg("--path")
+                .arg("sh")
+                .arg("--")
+                .arg("-c")
+                .arg("echo value=$PASSTHROUGH_VAR")
+                .assert();
+
+            assert
+                .success()
+                .stdout(predicate::str::contains("value=abc"));
+        }
+
+        #[cfg(not(windows))]
+        #[test]
+        fn exports_detected_versions() {
+            let sandbox = create_empty_sandbox();
+
+            install_node(sandbox.path());
+
+            sandbox.create_file(".prototools", "node = \"19.0.0\"");
+
+            let mut cmd = create_proto_command(sandbox.path());
+            let assert = cmd
+                .arg("run")
+                .arg("--path")
+                .arg("sh")
+                .arg("--")
+                .arg("-c")
+                .arg("echo version=$PROTO_NODE_VERSION")
+                .assert();
+
+            assert
+                .success()
+                .stdout(predicate::str::contains("version=19.0.0"));
+        }
+
+        #[test]
+        fn cannot_pass_id_and_path() {
+            let sandbox = create_empty_sandbox();
+
+            let mut cmd = create_proto_command(sandbox.path());
+            let assert = cmd.arg("run").arg("node").arg("--path").arg("sh").assert();
+
+            assert.stderr(predicate::str::contains("cannot be used with"));
+        }
+    }
 }
@@ -1,5 +1,6 @@
 mod utils;
 
+use starbase_sandbox::predicates::prelude::*;
 use starbase_sandbox::{assert_snapshot, get_assert_output};
 use std::path::PathBuf;
 use utils::*;
@@ -186,6 +187,96 @@ mod shim_bin {
         assert_eq!(child.wait().unwrap().signal().unwrap(), 1);
     }
 
+    #[test]
+    #[cfg(not(windows))]
+    fn caches_resolved_command_until_config_changes() {
+        let sandbox = create_empty_sandbox();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        cmd.arg("install")
+            .arg("node")
+            .arg("--pin")
+            .arg("--")
+            .arg("--no-bundled-npm")
+            .assert()
+            .success();
+
+        let run_shim = || {
+            let mut shim = create_shim_command(sandbox.path(), "node");
+            shim.current_dir(sandbox.path());
+            shim.args(["-e", "console.log(process.env.SHIM_CACHE_HIT || 'miss')"]);
+            shim.env_remove("PROTO_LOG");
+            shim.assert().success()
+        };
+
+        run_shim().stdout(predicate::str::contains("miss"));
+
+        // Mark the cached command, so we can tell when it's used
+        let cache_file = std::fs::read_dir(sandbox.path().join(".proto/shims/.cache"))
+            .unwrap()
+            .next()
+            .unwrap()
+            .unwrap()
+            .path();
+        let cache = std::fs::read_to_string(&cache_file).unwrap();
+
+        std::fs::write(
+            &cache_file,
+            cache.replace("\"env\":{", "\"env\":{\"SHIM_CACHE_HIT\":\"hit\","),
+        )
+        .unwrap();
+
+        run_shim().stdout(predicate::str::contains("hit"));
+
+        // Creating a config in the working directory invalidates
+        sandbox.create_file(".prototools", "");
+
+        run_shim().stdout(predicate::str::contains("miss"));
+    }
+
+    #[test]
+    fn only_customizes_when_invoked_by_parent_process() {
+        let sandbox = create_empty_sandbox();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        cmd.arg("install")
+            .arg("node")
+            .arg("--pin")
+            .arg("--")
+            .arg("--no-bundled-npm")
+            .assert()
+            .success();
+
+        let registry_path = sandbox.path().join(".proto/shims/registry.json");
+        let write_shim = |shim: serde_json::Value| {
+            let mut registry: serde_json::Value =
+                serde_json::from_str(&std::fs::read_to_string(&registry_path).unwrap()).unwrap();
+            registry["node"] = shim;
+
+            std::fs::write(&registry_path, registry.to_string()).unwrap();
+        };
+        let run_shim = || {
+            let mut shim = create_shim_command(sandbox.path(), "node");
+            shim.args(["-e", "console.log(process.env.SHIM_CUSTOMIZED || 'plain')"]);
+            shim.env("PROTO_SHIM_CACHE", "0");
+            shim.env_remove("PROTO_LOG");
+            shim.assert().success()
+        };
+
+        write_shim(serde_json::json!({
+            "env_vars": { "SHIM_CUSTOMIZED": "customized" },
+            "parent_process": "unknown-parent-process",
+        }));
+
+        run_shim().stdout(predicate::str::contains("plain"));
+
+        write_shim(serde_json::json!({
+            "env_vars": { "SHIM_CUSTOMIZED": "customized" },
+        }));
+
+        run_shim().stdout(predicate::str::contains("customized"));
+    }
+
     #[test]
     #[cfg(windows)]
     fn works_with_a_different_casing() {
@@ -0,0 +1,131 @@
+mod utils;
+
+use starbase_sandbox::predicates::prelude::*;
+use utils::*;
+
+mod stats {
+    use super::*;
+
+    #[test]
+    fn aggregates_buffered_runs() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(
+            ".proto/tools/node/manifest.json",
+            r#"{
+  "installed_versions": ["18.0.0", "20.0.0"],
+  "versions": {
+    "18.0.0": { "installed_at": 0, "run_count": 5 },
+    "20.0.0": { "installed_at": 0 }
+  }
+}"#,
+        );
+        sandbox.create_file(".proto/tools/node/.runs", "18.0.0\n20.0.0\n20.0.0\n");
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd.arg("stats").arg("--json").assert();
+
+        assert
+            .success()
+            .stdout(predicate::str::contains(r#""run_count": 8"#))
+            .stdout(predicate::str::contains(r#""run_count": 6"#))
+            .stdout(predicate::str::contains(r#""run_count": 2"#));
+
+        assert!(!sandbox.path().join(".proto/tools/node/.runs").exists());
+
+        let manifest =
+            std::fs::read_to_string(sandbox.path().join(".proto/tools/node/manifest.json"))
+                .unwrap();
+
+        assert!(manifest.contains(r#""run_count": 6"#));
+    }
+
+    #[test]
+    fn filters_by_id() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(
+            ".proto/tools/node/manifest.json",
+            r#"{ "installed_versions": ["18.0.0"], "versions": { "18.0.0": {} } }"#,
+        );
+        sandbox.create_file(
+            ".proto/tools/bun/manifest.json",
+            r#"{ "installed_versions": ["1.0.0"], "versions": { "1.0.0": {} } }"#,
+        );
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd.arg("stats").arg("bun").arg("--json").assert();
+
+        assert
+            .success()
+            .stdout(predicate::str::contains(r#""bun""#))
+            .stdout(predicate::str::contains(r#""node""#).not());
+    }
+
+    #[test]
+    fn prints_timestamps_as_rfc3339_in_json() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(
+            ".proto/tools/node/manifest.json",
+            r#"{ "installed_versions": ["18.0.0"], "versions": { "18.0.0": {} } }"#,
+        );
+        sandbox.create_file(".proto/tools/node/18.0.0/.last-used", "1710000000000");
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd.arg("stats").arg("--json").assert();
+
+        assert.success().stdout(predicate::str::contains(
+            r#""last_used_at": "2024-03-09T16:00:00.000Z""#,
+        ));
+    }
+
+    #[test]
+    fn prints_relative_dates_when_configured() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(".prototools", "[settings.ui]\ndate-format = \"relative\"");
+        sandbox.create_file(
+            ".proto/tools/node/manifest.json",
+            r#"{ "installed_versions": ["18.0.0"], "versions": { "18.0.0": {} } }"#,
+        );
+        sandbox.create_file(".proto/tools/node/18.0.0/.last-used", "1710000000000");
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd.arg("stats").assert();
+
+        assert
+            .success()
+            .stdout(predicate::str::contains("last used"))
+            .stdout(predicate::str::contains("years ago"));
+    }
+
+    #[test]
+    fn tracks_runs_when_enabled() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(".prototools", "[settings]\ntrack-runs = true");
+
+        let mut cmd = create_proto_command(sandbox.path());
+        cmd.arg("install")
+            .arg("node")
+            .arg("19.0.0")
+            .arg("--")
+            .arg("--no-bundled-npm")
+            .assert()
+            .success();
+
+        for _ in 0..2 {
+            let mut cmd = create_proto_command(sandbox.path());
+            cmd.arg("run")
+                .arg("node")
+                .arg("19.0.0")
+                .arg("--")
+                .arg("--version")
+                .assert()
+                .success();
+        }
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd.arg("stats").arg("--json").assert();
+
+        assert
+            .success()
+            .stdout(predicate::str::contains(r#""run_count": 2"#));
+    }
+}
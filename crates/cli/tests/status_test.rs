@@ -0,0 +1,139 @@
+mod utils;
+
+use starbase_sandbox::predicates::prelude::*;
+use utils::*;
+
+mod status {
+    use super::*;
+
+    #[test]
+    fn reports_missing_plugins() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(".prototools", "unknown-tool = \"1.0.0\"");
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd.arg("status").assert();
+
+        assert.success().stdout(predicate::str::contains(
+            "unknown-tool is pinned, but no plugin has been configured",
+        ));
+    }
+
+    #[test]
+    fn fails_check_for_missing_plugins() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(".prototools", "unknown-tool = \"1.0.0\"");
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd.arg("status").arg("--check").assert();
+
+        assert
+            .failure()
+            .stdout(predicate::str::contains("no plugin has been configured"));
+    }
+
+    #[test]
+    fn passes_check_when_nothing_configured() {
+        let sandbox = create_empty_sandbox();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        cmd.arg("status").arg("--check").assert().success();
+    }
+
+    #[test]
+    fn fails_check_when_not_installed() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(".prototools", "node = \"19.0.0\"");
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd.arg("status").arg("--check").assert();
+
+        assert
+            .failure()
+            .stdout(predicate::str::contains("Node.js 19.0.0 is not installed"));
+    }
+
+    #[test]
+    fn fails_check_when_ecosystem_disagrees() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(".prototools", "node = \"19.0.0\"");
+        sandbox.create_file(".nvmrc", "18.0.0");
+
+        let mut cmd = create_proto_command(sandbox.path());
+        cmd.arg("install").arg("node").arg("19.0.0").assert();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd.arg("status").arg("--check").assert();
+
+        assert
+            .failure()
+            .stdout(predicate::str::contains("requires 18.0.0"));
+    }
+
+    #[test]
+    fn prints_problems_as_json() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(".prototools", "unknown-tool = \"1.0.0\"");
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd.arg("status").arg("--json").assert();
+
+        assert
+            .success()
+            .stdout(predicate::str::contains(r#""problems": ["#))
+            .stdout(predicate::str::contains(r#""tools": {}"#));
+    }
+
+    #[test]
+    fn includes_pinning_config_file_in_json() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(".prototools", "node = \"19.0.0\"");
+        sandbox.create_file("nested/.prototools", "");
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd
+            .current_dir(sandbox.path().join("nested"))
+            .arg("status")
+            .arg("--json")
+            .assert();
+
+        assert
+            .success()
+            .stdout(predicate::str::contains(format!(
+                r#""config_file": {:?}"#,
+                sandbox.path().join(".prototools").to_str().unwrap()
+            )))
+            .stdout(predicate::str::contains(r#""exe_path": null"#))
+            .stdout(predicate::str::contains(r#""installed": false"#));
+    }
+
+    #[test]
+    fn prints_each_tool_with_a_format_template() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(".prototools", "node = \"19.0.0\"");
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd
+            .arg("status")
+            .arg("--format")
+            .arg("{{id}} {{ version }} installed={{installed}}{{unknown}}")
+            .assert();
+
+        assert
+            .success()
+            .stdout(predicate::str::contains("node 19.0.0 installed=false\n"));
+    }
+
+    #[test]
+    fn errors_for_invalid_format_template() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(".prototools", "node = \"19.0.0\"");
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd.arg("status").arg("--format").arg("{{id").assert();
+
+        assert
+            .failure()
+            .stderr(predicate::str::contains("Invalid format template"));
+    }
+}
@@ -0,0 +1,58 @@
+mod utils;
+
+use starbase_sandbox::predicates::prelude::*;
+use std::fs;
+use utils::*;
+
+mod teardown {
+    use super::*;
+
+    #[test]
+    fn removes_shims_and_profile_blocks() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(".proto/shims/node", "");
+        sandbox.create_file(".proto/tools/node/20.0.0/bin/node", "");
+        sandbox.create_file(
+            ".home/.zshrc",
+            "export A=1\n\n# >>> proto:PROTO_HOME >>>\nexport PROTO_HOME=\"$HOME/.proto\"\n# <<< proto:PROTO_HOME <<<\nexport B=2\n",
+        );
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd
+            .arg("teardown")
+            .arg("--yes")
+            .env("HOME", sandbox.path().join(".home"))
+            .env("PROTO_SHELL_PROFILE", sandbox.path().join(".home/.zshrc"))
+            .assert();
+
+        assert
+            .success()
+            .stdout(predicate::str::contains("Removed the following"));
+
+        assert!(!sandbox.path().join(".proto/shims").exists());
+        assert!(sandbox.path().join(".proto/tools/node/20.0.0").exists());
+        assert_eq!(
+            fs::read_to_string(sandbox.path().join(".home/.zshrc")).unwrap(),
+            "export A=1\nexport B=2\n"
+        );
+    }
+
+    #[test]
+    fn purges_the_store() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(".proto/tools/node/20.0.0/bin/node", "");
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd
+            .arg("teardown")
+            .arg("--yes")
+            .arg("--purge")
+            .env("HOME", sandbox.path().join(".home"))
+            .env("PROTO_SHELL_PROFILE", sandbox.path().join(".home/.zshrc"))
+            .assert();
+
+        assert.success();
+
+        assert!(!sandbox.path().join(".proto").exists());
+    }
+}
@@ -0,0 +1,55 @@
+mod utils;
+
+use proto_core::ProtoConfig;
+use starbase_sandbox::predicates::prelude::*;
+use utils::*;
+
+mod telemetry {
+    use super::*;
+
+    #[test]
+    fn enables_in_global_config() {
+        let sandbox = create_empty_sandbox();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        cmd.arg("telemetry").arg("enable").assert().success();
+
+        let config = ProtoConfig::load_from(sandbox.path().join(".proto"), false).unwrap();
+
+        assert_eq!(
+            config.settings.unwrap().telemetry.unwrap().enabled,
+            Some(true)
+        );
+    }
+
+    #[test]
+    fn disables_in_global_config() {
+        let sandbox = create_empty_sandbox();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        cmd.arg("telemetry").arg("disable").assert().success();
+
+        let config = ProtoConfig::load_from(sandbox.path().join(".proto"), false).unwrap();
+
+        assert_eq!(
+            config.settings.unwrap().telemetry.unwrap().enabled,
+            Some(false)
+        );
+    }
+
+    #[test]
+    fn displays_status() {
+        let sandbox = create_empty_sandbox();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        cmd.arg("telemetry").arg("enable").assert().success();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd.arg("telemetry").arg("status").assert();
+
+        assert
+            .success()
+            .stdout(predicate::str::contains("metrics are enabled"))
+            .stdout(predicate::str::contains("install-tool"));
+    }
+}
@@ -0,0 +1,90 @@
+mod utils;
+
+use starbase_sandbox::predicates::prelude::*;
+use utils::*;
+
+mod try_tools {
+    use super::*;
+
+    #[test]
+    fn errors_for_invalid_target() {
+        let sandbox = create_empty_sandbox();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd.arg("try").arg("node@").arg("--").arg("node").assert();
+
+        assert.stderr(predicate::str::contains("Invalid run target"));
+    }
+
+    #[test]
+    fn runs_command_and_removes_installed_version() {
+        let sandbox = create_empty_sandbox();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd
+            .arg("try")
+            .arg("node@19.0.0")
+            .arg("--")
+            .arg("node")
+            .arg("--version")
+            .assert();
+
+        assert.success().stdout(predicate::str::contains("v19.0.0"));
+
+        assert!(!sandbox.path().join(".proto/tools/node/19.0.0").exists());
+        assert!(!sandbox.path().join(".proto/.prototools").exists());
+    }
+
+    #[test]
+    fn keeps_installed_version() {
+        let sandbox = create_empty_sandbox();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        cmd.arg("try")
+            .arg("node@19.0.0")
+            .arg("--keep")
+            .arg("--")
+            .arg("node")
+            .arg("--version")
+            .assert()
+            .success();
+
+        assert!(sandbox.path().join(".proto/tools/node/19.0.0").exists());
+    }
+
+    #[test]
+    fn exports_try_variable() {
+        let sandbox = create_empty_sandbox();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd
+            .arg("try")
+            .arg("node@19.0.0")
+            .arg("--")
+            .arg("node")
+            .arg("-e")
+            .arg("console.log(process.env.PROTO_TRY)")
+            .assert();
+
+        assert
+            .success()
+            .stdout(predicate::str::contains("node@19.0.0"));
+    }
+
+    #[test]
+    fn exits_with_command_code() {
+        let sandbox = create_empty_sandbox();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd
+            .arg("try")
+            .arg("node@19.0.0")
+            .arg("--")
+            .arg("node")
+            .arg("-e")
+            .arg("process.exit(3)")
+            .assert();
+
+        assert.code(3);
+    }
+}
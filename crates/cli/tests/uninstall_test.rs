@@ -48,4 +48,47 @@ mod uninstall {
 
         assert!(!temp.path().join(".proto/tools/node").exists());
     }
+
+    #[test]
+    fn uninstalls_all_versions_of_multiple_tools() {
+        let temp = create_empty_sandbox();
+        temp.create_file(
+            ".proto/tools/node/manifest.json",
+            r#"{ "installed_versions": ["19.0.0"] }"#,
+        );
+        temp.create_file(".proto/tools/node/19.0.0/README.md", "");
+        temp.create_file(
+            ".proto/tools/bun/manifest.json",
+            r#"{ "installed_versions": ["1.0.0"] }"#,
+        );
+        temp.create_file(".proto/tools/bun/1.0.0/README.md", "");
+
+        let mut cmd = create_proto_command(temp.path());
+        cmd.arg("uninstall")
+            .arg("node")
+            .arg("bun")
+            .arg("--all-versions")
+            .arg("--yes")
+            .assert()
+            .success();
+
+        assert!(!temp.path().join(".proto/tools/node").exists());
+        assert!(!temp.path().join(".proto/tools/bun").exists());
+    }
+
+    #[test]
+    fn errors_for_version_with_all_versions() {
+        let temp = create_empty_sandbox();
+
+        let mut cmd = create_proto_command(temp.path());
+        let assert = cmd
+            .arg("uninstall")
+            .arg("node@20")
+            .arg("--all-versions")
+            .assert();
+
+        assert.stderr(predicate::str::contains(
+            "Unable to uninstall node@20 with --all-versions",
+        ));
+    }
 }
@@ -0,0 +1,121 @@
+mod utils;
+
+use starbase_sandbox::predicates::prelude::*;
+use utils::*;
+
+mod upgrade {
+    use super::*;
+
+    #[test]
+    fn lists_previous_versions() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(".proto/tools/proto/0.1.0/proto", "");
+        sandbox.create_file(".proto/tools/proto/0.10.0/proto-shim", "");
+        sandbox.create_file(".proto/tools/proto/0.2.0/proto", "");
+        sandbox.create_file(".proto/tools/proto/0.3.0/other", "");
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd.arg("upgrade").arg("--list").assert();
+
+        assert
+            .success()
+            .stdout(predicate::eq("0.10.0\n0.2.0\n0.1.0\n"));
+    }
+
+    #[test]
+    fn lists_nothing_without_previous_versions() {
+        let sandbox = create_empty_sandbox();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd.arg("upgrade").arg("--list").assert();
+
+        assert
+            .success()
+            .stderr(predicate::str::contains("No previous versions installed"));
+    }
+
+    #[test]
+    fn errors_rolling_back_without_previous_versions() {
+        let sandbox = create_empty_sandbox();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd.arg("upgrade").arg("--rollback").assert();
+
+        assert.failure().stderr(predicate::str::contains(
+            "No previously installed version of proto to roll back to.",
+        ));
+    }
+
+    #[test]
+    fn errors_for_invalid_target_version() {
+        let sandbox = create_empty_sandbox();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd.arg("upgrade").arg("--to").arg("latest").assert();
+
+        assert.failure();
+    }
+
+    #[test]
+    fn repairs_nothing_without_interrupted_upgrade() {
+        let sandbox = create_empty_sandbox();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd.arg("upgrade").arg("--repair").assert();
+
+        assert
+            .success()
+            .stderr(predicate::str::contains("No interrupted upgrade to repair"));
+    }
+
+    #[test]
+    fn rolls_back_interrupted_upgrade_without_staged_binaries() {
+        let sandbox = create_empty_sandbox();
+        let proto_dir = sandbox.path().join(".proto/tools/proto");
+
+        sandbox.create_file(".proto/tools/proto/0.1.0/proto", "old");
+        sandbox.create_file(
+            ".proto/tools/proto/.staged-upgrade",
+            format!(
+                "version=0.2.0\nbin_dir={}\nrelocate_dir={}\nstaged_dir={}",
+                sandbox.path().join(".proto/bin").display(),
+                proto_dir.join("0.1.0").display(),
+                sandbox.path().join(".proto/temp/missing").display(),
+            ),
+        );
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd.arg("upgrade").arg("--repair").assert();
+
+        assert
+            .success()
+            .stderr(predicate::str::contains("Rolled back the upgrade"));
+
+        assert_eq!(
+            std::fs::read_to_string(sandbox.path().join(".proto/bin/proto")).unwrap(),
+            "old"
+        );
+        assert!(!proto_dir.join(".staged-upgrade").exists());
+    }
+
+    #[test]
+    fn errors_repairing_without_any_binaries() {
+        let sandbox = create_empty_sandbox();
+
+        sandbox.create_file(
+            ".proto/tools/proto/.staged-upgrade",
+            format!(
+                "version=0.2.0\nbin_dir={}\nrelocate_dir={}",
+                sandbox.path().join(".proto/bin").display(),
+                sandbox.path().join(".proto/tools/proto/0.1.0").display(),
+            ),
+        );
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd.arg("upgrade").arg("--repair").assert();
+
+        assert.failure().stderr(predicate::str::contains(
+            "Unable to repair the interrupted upgrade",
+        ));
+    }
+}
@@ -1,5 +1,6 @@
 mod utils;
 
+use starbase_sandbox::predicates::prelude::*;
 use utils::*;
 
 mod install_all {
@@ -32,6 +33,44 @@ deno = "1.30.0"
         assert!(deno_path.exists());
     }
 
+    #[test]
+    fn installs_all_tools_with_limited_jobs() {
+        let sandbox = create_empty_sandbox();
+        let node_path = sandbox.path().join(".proto/tools/node/19.0.0");
+        let deno_path = sandbox.path().join(".proto/tools/deno/1.30.0");
+
+        sandbox.create_file(
+            ".prototools",
+            r#"node = "19.0.0"
+deno = "1.30.0"
+    "#,
+        );
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd.arg("use").arg("--jobs").arg("1").assert().success();
+
+        assert!(node_path.exists());
+        assert!(deno_path.exists());
+
+        // Installs must not overlap when limited to 1 job
+        let stderr = String::from_utf8_lossy(&assert.get_output().stderr).to_string();
+        let mut in_flight = 0;
+        let mut started = 0;
+
+        for line in stderr.lines() {
+            if line.contains("Starting install of") {
+                in_flight += 1;
+                started += 1;
+
+                assert_eq!(in_flight, 1, "more than 1 install was in flight");
+            } else if line.contains("Finished install of") {
+                in_flight -= 1;
+            }
+        }
+
+        assert_eq!(started, 2);
+    }
+
     #[test]
     fn installs_tool_via_detection() {
         let sandbox = create_empty_sandbox();
@@ -65,4 +104,135 @@ deno = "1.30.0"
         assert!(node_path.exists());
         assert!(!deno_path.exists());
     }
+
+    #[test]
+    fn writes_lockfile() {
+        let sandbox = create_empty_sandbox();
+
+        sandbox.create_file(".prototools", r#"deno = "1.30""#);
+
+        let mut cmd = create_proto_command(sandbox.path());
+        cmd.arg("use").assert().success();
+
+        let lock = std::fs::read_to_string(sandbox.path().join(".protolock")).unwrap();
+
+        assert!(lock.contains("[tools.deno]"));
+        assert!(lock.contains("spec = \"1.30\""));
+        assert!(lock.contains("version = \"1.30.3\""));
+        assert!(lock.contains("checksum = "));
+        assert!(lock.contains("download-url = "));
+    }
+
+    #[test]
+    fn installs_locked_versions_when_frozen() {
+        let sandbox = create_empty_sandbox();
+        let deno_path = sandbox.path().join(".proto/tools/deno/1.30.0");
+
+        sandbox.create_file(".prototools", r#"deno = "1.30""#);
+        sandbox.create_file(
+            ".protolock",
+            r#"[tools.deno]
+spec = "1.30"
+version = "1.30.0"
+"#,
+        );
+
+        let mut cmd = create_proto_command(sandbox.path());
+        cmd.arg("use").arg("--frozen").assert().success();
+
+        assert!(deno_path.exists());
+    }
+
+    #[test]
+    fn errors_when_frozen_and_not_locked() {
+        let sandbox = create_empty_sandbox();
+
+        sandbox.create_file(".prototools", r#"deno = "1.30.0""#);
+        sandbox.create_file(".protolock", "");
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd.arg("use").arg("--frozen").assert().failure();
+
+        assert.stderr(predicate::str::contains("has not been locked"));
+    }
+
+    #[test]
+    fn errors_when_frozen_and_config_differs_from_lock() {
+        let sandbox = create_empty_sandbox();
+
+        sandbox.create_file(".prototools", r#"deno = "1.31""#);
+        sandbox.create_file(
+            ".protolock",
+            r#"[tools.deno]
+spec = "1.30"
+version = "1.30.0"
+"#,
+        );
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd.arg("use").arg("--frozen").assert().failure();
+
+        assert.stderr(predicate::str::contains(
+            "does not match the locked version",
+        ));
+    }
+
+    #[test]
+    fn errors_when_frozen_checksum_doesnt_match() {
+        let sandbox = create_empty_sandbox();
+
+        sandbox.create_file(".prototools", r#"deno = "1.30.0""#);
+        sandbox.create_file(
+            ".protolock",
+            r#"[tools.deno]
+version = "1.30.0"
+checksum = "invalid"
+"#,
+        );
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd.arg("use").arg("--frozen").assert().failure();
+
+        assert.stderr(predicate::str::contains("does not match the checksum"));
+    }
+
+    #[test]
+    fn continues_on_error_and_summarizes_failures() {
+        let sandbox = create_empty_sandbox();
+        let node_path = sandbox.path().join(".proto/tools/node/19.0.0");
+
+        sandbox.create_file(
+            ".prototools",
+            r#"node = "19.0.0"
+deno = "0.0.1"
+    "#,
+        );
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd.arg("use").arg("--continue-on-error").assert().failure();
+
+        assert
+            .stderr(predicate::str::contains("Installed 1 of 2 tools"))
+            .stderr(predicate::str::contains("Failed to install 1 tool(s)"));
+
+        assert!(node_path.exists());
+        assert!(sandbox.path().join(".protolock").exists());
+    }
+
+    #[test]
+    fn skips_installed_tools_when_rerun() {
+        let sandbox = create_empty_sandbox();
+
+        sandbox.create_file(".prototools", r#"node = "19.0.0""#);
+
+        let mut cmd = create_proto_command(sandbox.path());
+        cmd.arg("use").assert().success();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd.arg("use").arg("--log").arg("debug").assert().success();
+
+        assert.stderr(predicate::str::contains(
+            "has already been installed, skipping",
+        ));
+    }
 }
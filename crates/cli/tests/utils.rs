@@ -4,6 +4,8 @@ use proto_core::{ProtoConfig, ProtoConfigManager};
 use proto_shim::get_exe_file_name;
 use starbase_sandbox::{assert_cmd, create_command_with_name};
 pub use starbase_sandbox::{create_empty_sandbox, Sandbox};
+use std::env;
+use std::ffi::OsString;
 use std::fs;
 use std::path::{Path, PathBuf};
 
@@ -45,6 +47,15 @@ pub fn create_proto_command<T: AsRef<Path>>(path: T) -> assert_cmd::Command {
     cmd
 }
 
+// A `PATH` with a system bin directory before the proto shims directory
+pub fn create_path_env(sandbox: &Sandbox) -> OsString {
+    env::join_paths([
+        sandbox.path().join("system/bin"),
+        sandbox.path().join(".proto/shims"),
+    ])
+    .unwrap()
+}
+
 pub fn create_shim_command<T: AsRef<Path>>(path: T, name: &str) -> assert_cmd::Command {
     let mut cmd = assert_cmd::Command::from_std(create_shim_command_std(path, name));
     cmd.timeout(std::time::Duration::from_secs(240));
@@ -0,0 +1,95 @@
+mod utils;
+
+use starbase_sandbox::predicates::prelude::*;
+use utils::*;
+
+mod validate {
+    use super::*;
+
+    #[test]
+    fn passes_for_valid_files() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(
+            ".prototools",
+            r#"
+node = "20"
+
+[settings]
+auto-install = true
+"#,
+        );
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd.arg("validate").assert();
+
+        assert.success().stdout(predicate::str::contains("valid"));
+    }
+
+    #[test]
+    fn reports_invalid_versions() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(".prototools", "node = \"20\"\nnpm = \"not-a@version\"\n");
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd.arg("validate").assert();
+
+        assert
+            .failure()
+            .stderr(predicate::str::contains("Invalid setting npm"));
+    }
+
+    #[test]
+    fn reports_unknown_settings() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(".prototools", "[settings]\nauto-instal = true\n");
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd.arg("validate").assert();
+
+        assert.failure().stderr(predicate::str::contains(
+            "Invalid setting settings.auto-instal",
+        ));
+    }
+
+    #[test]
+    fn reports_invalid_locators() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(
+            ".prototools",
+            "[plugins]\nmy-tool = \"sorce:./plugin.wasm\"\n",
+        );
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd.arg("validate").assert();
+
+        assert
+            .failure()
+            .stderr(predicate::str::contains("Invalid setting plugins.my-tool"));
+    }
+
+    #[test]
+    fn reports_syntax_errors() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(".prototools", "node = \n");
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd.arg("validate").assert();
+
+        assert
+            .failure()
+            .stderr(predicate::str::contains("Failed to parse .prototools"));
+    }
+
+    #[test]
+    fn prints_json_schema() {
+        let sandbox = create_empty_sandbox();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd.arg("validate").arg("--schema").assert();
+
+        assert
+            .success()
+            .stdout(predicate::str::contains("\"$schema\""))
+            .stdout(predicate::str::contains("auto-install"));
+    }
+}
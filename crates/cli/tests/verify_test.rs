@@ -0,0 +1,42 @@
+mod utils;
+
+use starbase_sandbox::predicates::prelude::*;
+use utils::*;
+
+mod verify {
+    use super::*;
+
+    #[test]
+    fn passes_for_installed_versions() {
+        let sandbox = create_empty_sandbox();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        cmd.arg("install")
+            .arg("node")
+            .arg("19.0.0")
+            .assert()
+            .success();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd.arg("verify").arg("--smoke").assert();
+
+        assert
+            .success()
+            .stdout(predicate::str::contains("pass"))
+            .stdout(predicate::str::contains("19.0.0"));
+    }
+
+    #[test]
+    fn fails_for_missing_install_dir() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(
+            ".proto/tools/node/manifest.json",
+            r#"{ "installed_versions": ["19.0.0"] }"#,
+        );
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd.arg("verify").arg("node").assert();
+
+        assert.failure().stdout(predicate::str::contains("fail"));
+    }
+}
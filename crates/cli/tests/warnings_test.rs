@@ -0,0 +1,49 @@
+mod utils;
+
+use starbase_sandbox::predicates::prelude::*;
+use utils::*;
+
+mod warnings {
+    use super::*;
+
+    #[test]
+    fn lists_warnings() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(
+            ".prototools",
+            r#"
+[settings]
+silence-warnings = ["tool-command"]
+"#,
+        );
+
+        let mut cmd = create_proto_command(sandbox.path());
+        cmd.arg("warnings")
+            .arg("--json")
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("\"proto-root-env-var\""))
+            .stdout(predicate::str::contains("\"silenced\": true"));
+    }
+
+    #[test]
+    fn renders_deprecations_once_per_day() {
+        let sandbox = create_empty_sandbox();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        cmd.arg("warnings")
+            .env("PROTO_ROOT", sandbox.path().join(".proto"))
+            .env_remove("PROTO_HOME")
+            .assert()
+            .success()
+            .stderr(predicate::str::contains("[proto-root-env-var]"));
+
+        let mut cmd = create_proto_command(sandbox.path());
+        cmd.arg("warnings")
+            .env("PROTO_ROOT", sandbox.path().join(".proto"))
+            .env_remove("PROTO_HOME")
+            .assert()
+            .success()
+            .stderr(predicate::str::contains("[proto-root-env-var]").not());
+    }
+}
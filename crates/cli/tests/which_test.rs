@@ -0,0 +1,60 @@
+mod utils;
+
+use proto_shim::get_exe_file_name;
+use starbase_sandbox::predicates::prelude::*;
+use utils::*;
+
+mod which {
+    use super::*;
+
+    #[test]
+    fn errors_if_not_on_path() {
+        let sandbox = create_empty_sandbox();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        cmd.arg("which")
+            .arg("unknown-exe")
+            .env("PATH", create_path_env(&sandbox))
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn prints_first_executable() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(format!(".proto/shims/{}", get_exe_file_name("node")), "");
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd
+            .arg("which")
+            .arg("node")
+            .env("PATH", create_path_env(&sandbox))
+            .assert();
+
+        assert
+            .success()
+            .stdout(predicate::str::contains(".proto"))
+            .stdout(predicate::str::contains("shims"));
+    }
+
+    #[test]
+    fn lists_all_executables_and_warns_when_shadowed() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(format!("system/bin/{}", get_exe_file_name("node")), "");
+        sandbox.create_file(format!(".proto/shims/{}", get_exe_file_name("node")), "");
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd
+            .arg("which")
+            .arg("node")
+            .arg("--all")
+            .env("PATH", create_path_env(&sandbox))
+            .assert();
+
+        assert
+            .success()
+            .stdout(predicate::str::contains("(unknown)"))
+            .stdout(predicate::str::contains("(proto shim)"))
+            .stderr(predicate::str::contains("is shadowing proto's"));
+    }
+}
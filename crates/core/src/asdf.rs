@@ -0,0 +1,476 @@
+use crate::error::ProtoError;
+use crate::helpers::is_offline;
+use miette::IntoDiagnostic;
+use proto_pdk_api::{ExecutableConfig, LoadVersionsOutput, LocateExecutablesOutput};
+use rustc_hash::FxHashMap;
+use semver::Version;
+use starbase_utils::fs;
+use std::env;
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use tracing::{debug, trace};
+use warpgate::Id;
+
+/// Environment variables inherited by asdf scripts. All other variables
+/// are removed, so that scripts only rely on the asdf contract.
+pub const ASDF_INHERITED_ENV_VARS: [&str; 9] = [
+    "HOME", "LANG", "LC_ALL", "LOGNAME", "PATH", "SHELL", "TERM", "TMPDIR", "USER",
+];
+
+/// Variables that bash sets itself, which are ignored when
+/// collecting the variables exported by `exec-env`.
+const BASH_ENV_VARS: [&str; 4] = ["_", "OLDPWD", "PWD", "SHLVL"];
+
+/// File within the plugin directory that records the requested
+/// revision and the commit that was checked out for it.
+const REVISION_FILE: &str = ".proto-rev";
+
+/// A plugin from the asdf ecosystem, which is a Git repository of shell
+/// scripts in a `bin` directory (`list-all`, `download`, `install`, etc).
+#[derive(Clone, Debug)]
+pub struct AsdfPlugin {
+    /// Directory the plugin repository is cloned to.
+    pub dir: PathBuf,
+
+    pub id: Id,
+
+    /// Git URL of the plugin repository.
+    pub repo_url: String,
+
+    /// Branch, tag, or commit to check out. When not provided,
+    /// the default branch at the time of cloning is used.
+    pub rev: Option<String>,
+}
+
+impl AsdfPlugin {
+    pub fn new(id: &Id, dir: PathBuf, repo_url: String) -> Self {
+        Self {
+            dir,
+            id: id.to_owned(),
+            repo_url,
+            rev: None,
+        }
+    }
+
+    /// Return the requested revision and the commit it was checked out at,
+    /// as recorded when the repository was cloned.
+    pub fn load_recorded_revision(&self) -> miette::Result<Option<(String, String)>> {
+        let path = self.dir.join(REVISION_FILE);
+
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let content = fs::read_file(path)?;
+        let mut lines = content.lines();
+
+        Ok(match (lines.next(), lines.next()) {
+            (Some(rev), Some(commit)) => Some((rev.to_owned(), commit.to_owned())),
+            _ => None,
+        })
+    }
+
+    /// Clone the plugin repository at the requested revision, if it has not already
+    /// been cloned. Existing clones are pinned to the commit that was recorded when
+    /// cloned, and are only cloned again when a different revision is requested.
+    pub fn clone_repository(&self) -> miette::Result<()> {
+        let requested_rev = self.rev.as_deref().unwrap_or("HEAD");
+
+        if self.dir.join("bin").exists() {
+            let recorded_rev = self.load_recorded_revision()?.map(|(rev, _)| rev);
+
+            if self.rev.is_none() || recorded_rev.as_deref() == Some(requested_rev) {
+                return Ok(());
+            }
+
+            debug!(
+                tool = self.id.as_str(),
+                from = recorded_rev.as_deref().unwrap_or("unknown"),
+                to = requested_rev,
+                "Requested asdf plugin revision has changed, cloning again"
+            );
+        }
+
+        if is_offline() {
+            return Err(ProtoError::InternetConnectionRequired.into());
+        }
+
+        debug!(
+            tool = self.id.as_str(),
+            repo = &self.repo_url,
+            dir = ?self.dir,
+            "Cloning asdf plugin repository"
+        );
+
+        // Remove leftovers from a previously failed or outdated clone
+        fs::remove_dir_all(&self.dir)?;
+        fs::create_dir_all(&self.dir)?;
+
+        self.run_git(&["init", "--quiet"])?;
+        self.run_git(&[
+            "fetch",
+            "--depth",
+            "1",
+            "--quiet",
+            &self.repo_url,
+            requested_rev,
+        ])?;
+        self.run_git(&["checkout", "--quiet", "--detach", "FETCH_HEAD"])?;
+
+        let commit = self.run_git(&["rev-parse", "HEAD"])?;
+
+        debug!(
+            tool = self.id.as_str(),
+            rev = requested_rev,
+            commit = &commit,
+            "Pinned asdf plugin repository"
+        );
+
+        fs::write_file(
+            self.dir.join(REVISION_FILE),
+            format!("{requested_rev}\n{commit}\n"),
+        )?;
+
+        Ok(())
+    }
+
+    fn run_git(&self, args: &[&str]) -> miette::Result<String> {
+        let output = Command::new("git")
+            .args(args)
+            .current_dir(&self.dir)
+            .stdin(Stdio::null())
+            .output()
+            .into_diagnostic()?;
+
+        if !output.status.success() {
+            return Err(ProtoError::AsdfPluginCloneFailed {
+                tool: self.id.to_string(),
+                repo: self.repo_url.clone(),
+                error: String::from_utf8_lossy(&output.stderr).trim().to_owned(),
+            }
+            .into());
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_owned())
+    }
+
+    /// Return an absolute path to the script with the provided name.
+    pub fn get_script_path(&self, name: &str) -> PathBuf {
+        self.dir.join("bin").join(name)
+    }
+
+    pub fn has_script(&self, name: &str) -> bool {
+        self.get_script_path(name).is_file()
+    }
+
+    /// Return the script with the provided name, ensuring that it's a file
+    /// within the plugin's `bin` directory, and not a symlink outside of it.
+    fn get_checked_script_path(&self, name: &str) -> miette::Result<PathBuf> {
+        if cfg!(windows) {
+            return Err(ProtoError::AsdfUnsupportedPlatform {
+                tool: self.id.to_string(),
+            }
+            .into());
+        }
+
+        let script = self.get_script_path(name);
+
+        let is_within_plugin = match (script.canonicalize(), self.dir.canonicalize()) {
+            (Ok(script), Ok(dir)) => script.starts_with(dir),
+            _ => false,
+        };
+
+        if name.contains(['/', '\\']) || !script.is_file() || !is_within_plugin {
+            return Err(ProtoError::AsdfMissingScript {
+                tool: self.id.to_string(),
+                script: name.to_owned(),
+                path: script,
+            }
+            .into());
+        }
+
+        Ok(script)
+    }
+
+    /// Create a bash command from within the plugin directory, with stdin closed,
+    /// and with an environment that only contains the inherited variables,
+    /// asdf's plugin variables, and the provided variables. This is not an
+    /// isolation boundary, as scripts run with the current user's permissions.
+    fn create_command(&self, envs: &[(&str, OsString)]) -> Command {
+        let mut command = Command::new("bash");
+        command
+            .current_dir(&self.dir)
+            .env_clear()
+            .stdin(Stdio::null())
+            .stderr(Stdio::inherit());
+
+        for key in ASDF_INHERITED_ENV_VARS {
+            if let Some(value) = env::var_os(key) {
+                command.env(key, value);
+            }
+        }
+
+        command
+            .env("ASDF_PLUGIN_PATH", &self.dir)
+            .env("ASDF_PLUGIN_SOURCE_URL", &self.repo_url)
+            .envs(envs.to_vec());
+
+        command
+    }
+
+    fn run_command(&self, name: &str, mut command: Command) -> miette::Result<String> {
+        let output = command.output().into_diagnostic()?;
+
+        if !output.status.success() {
+            return Err(ProtoError::AsdfScriptFailed {
+                tool: self.id.to_string(),
+                script: name.to_owned(),
+                status: format!("exit code {}", output.status),
+            }
+            .into());
+        }
+
+        let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+
+        trace!(tool = self.id.as_str(), script = name, output = %stdout, "Ran asdf script");
+
+        Ok(stdout)
+    }
+
+    /// Run a script from the plugin's `bin` directory with bash, and return its stdout.
+    pub fn run_script(&self, name: &str, envs: &[(&str, OsString)]) -> miette::Result<String> {
+        let script = self.get_checked_script_path(name)?;
+
+        debug!(tool = self.id.as_str(), script = ?script, "Running asdf script");
+
+        let mut command = self.create_command(envs);
+        command.arg(&script);
+
+        self.run_command(name, command)
+    }
+
+    /// Load available versions with the `list-all` script. Versions that
+    /// are not valid semver (`lts`, `nightly`, etc) are ignored.
+    pub fn list_all(&self) -> miette::Result<LoadVersionsOutput> {
+        let output = self.run_script("list-all", &[])?;
+        let mut versions = vec![];
+
+        for value in output.split_whitespace() {
+            match Version::parse(value) {
+                Ok(version) => versions.push(version),
+                Err(_) => {
+                    trace!(
+                        tool = self.id.as_str(),
+                        version = value,
+                        "Ignoring non-semver asdf version"
+                    );
+                }
+            };
+        }
+
+        Ok(LoadVersionsOutput::from_versions(versions))
+    }
+
+    fn create_install_env(
+        &self,
+        version: &str,
+        install_dir: &Path,
+        download_dir: &Path,
+    ) -> Vec<(&'static str, OsString)> {
+        vec![
+            ("ASDF_INSTALL_TYPE", "version".into()),
+            ("ASDF_INSTALL_VERSION", version.into()),
+            ("ASDF_INSTALL_PATH", install_dir.into()),
+            ("ASDF_DOWNLOAD_PATH", download_dir.into()),
+            (
+                "ASDF_CONCURRENCY",
+                std::thread::available_parallelism()
+                    .map(|count| count.get())
+                    .unwrap_or(1)
+                    .to_string()
+                    .into(),
+            ),
+        ]
+    }
+
+    /// Install the provided version into the install directory, by running
+    /// the `download` script (when available) and then the `install` script.
+    pub fn install(
+        &self,
+        version: &str,
+        install_dir: &Path,
+        download_dir: &Path,
+    ) -> miette::Result<()> {
+        let envs = self.create_install_env(version, install_dir, download_dir);
+
+        fs::create_dir_all(install_dir)?;
+        fs::create_dir_all(download_dir)?;
+
+        if self.has_script("download") {
+            self.run_script("download", &envs)?;
+        }
+
+        let result = self.run_script("install", &envs);
+
+        fs::remove_dir_all(download_dir)?;
+
+        result.map(|_| ())
+    }
+
+    /// Load the environment variables that the `exec-env` script exports, which
+    /// asdf sources before executing the tool. Variables that are inherited or
+    /// set by bash are ignored.
+    pub fn load_exec_env(
+        &self,
+        version: &str,
+        install_dir: &Path,
+    ) -> miette::Result<FxHashMap<String, String>> {
+        let mut env_vars = FxHashMap::default();
+
+        if !self.has_script("exec-env") {
+            return Ok(env_vars);
+        }
+
+        let script = self.get_checked_script_path("exec-env")?;
+        let envs = self.create_install_env(version, install_dir, install_dir);
+
+        debug!(tool = self.id.as_str(), script = ?script, "Sourcing asdf exec-env script");
+
+        let mut command = self.create_command(&envs);
+        command.arg("-c").arg(". \"$0\" >&2 && env -0").arg(&script);
+
+        let output = self.run_command("exec-env", command)?;
+        let base_envs = command_envs(&envs);
+
+        for pair in output.split('\0') {
+            let Some((key, value)) = pair.split_once('=') else {
+                continue;
+            };
+
+            if BASH_ENV_VARS.contains(&key)
+                || key.starts_with("ASDF_")
+                || base_envs.get(key).is_some_and(|base| base == value)
+            {
+                continue;
+            }
+
+            env_vars.insert(key.to_owned(), value.to_owned());
+        }
+
+        Ok(env_vars)
+    }
+
+    /// Locate executables within the directories returned by the `list-bin-paths`
+    /// script, or `bin`. Like asdf, every executable file is exposed, with the file
+    /// matching the tool's ID as the primary. When not installed yet, the primary
+    /// is assumed to be `<bin>/<id>`. Variables from `exec-env` are set for shims.
+    pub fn locate_executables(
+        &self,
+        version: &str,
+        install_dir: &Path,
+    ) -> miette::Result<LocateExecutablesOutput> {
+        let bin_dirs = if self.has_script("list-bin-paths") {
+            let envs = self.create_install_env(version, install_dir, install_dir);
+
+            self.run_script("list-bin-paths", &envs)?
+                .split_whitespace()
+                .map(|dir| dir.to_owned())
+                .collect::<Vec<_>>()
+        } else {
+            vec![]
+        };
+        let bin_dirs = if bin_dirs.is_empty() {
+            vec!["bin".to_owned()]
+        } else {
+            bin_dirs
+        };
+
+        let mut exes = vec![];
+
+        for bin_dir in &bin_dirs {
+            let dir = install_dir.join(bin_dir);
+
+            if !dir.is_dir() {
+                continue;
+            }
+
+            let mut names = fs::read_dir(&dir)?
+                .into_iter()
+                .filter(|entry| is_executable(&entry.path()))
+                .map(|entry| fs::file_name(entry.path()))
+                .collect::<Vec<_>>();
+            names.sort();
+
+            for name in names {
+                if !exes.iter().any(|(existing, _)| *existing == name) {
+                    exes.push((name.clone(), format!("{bin_dir}/{name}")));
+                }
+            }
+        }
+
+        let primary_index = exes
+            .iter()
+            .position(|(name, _)| name == self.id.as_str())
+            .or((!exes.is_empty()).then_some(0));
+
+        let mut primary = match primary_index {
+            Some(index) => ExecutableConfig::new(exes.remove(index).1),
+            None => ExecutableConfig::new(format!("{}/{}", bin_dirs[0], self.id)),
+        };
+
+        let env_vars = self.load_exec_env(version, install_dir)?;
+
+        if !env_vars.is_empty() {
+            primary.shim_env_vars = Some(env_vars.clone());
+        }
+
+        let mut output = LocateExecutablesOutput {
+            primary: Some(primary),
+            ..LocateExecutablesOutput::default()
+        };
+
+        for (name, exe_path) in exes {
+            let mut secondary = ExecutableConfig::new(exe_path);
+
+            if !env_vars.is_empty() {
+                secondary.shim_env_vars = Some(env_vars.clone());
+            }
+
+            output.secondary.insert(name, secondary);
+        }
+
+        Ok(output)
+    }
+}
+
+/// Collect the variables that a command created with `AsdfPlugin::create_command`
+/// starts with, excluding those set by bash.
+fn command_envs(envs: &[(&str, OsString)]) -> FxHashMap<String, String> {
+    let mut map = FxHashMap::default();
+
+    for key in ASDF_INHERITED_ENV_VARS {
+        if let Ok(value) = env::var(key) {
+            map.insert(key.to_owned(), value);
+        }
+    }
+
+    for (key, value) in envs {
+        map.insert((*key).to_owned(), value.to_string_lossy().to_string());
+    }
+
+    map
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+
+    path.metadata()
+        .is_ok_and(|meta| meta.is_file() && meta.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file()
+}
@@ -0,0 +1,178 @@
+use crate::proto_config::CachePermissions;
+use std::io;
+use std::path::{Path, PathBuf};
+use tracing::trace;
+
+impl CachePermissions {
+    /// Return the modes to apply to directories and files respectively,
+    /// or `None` if the current umask should be respected.
+    pub fn get_modes(&self) -> Option<(u32, u32)> {
+        match self {
+            Self::Inherit => None,
+            // Setgid so that new entries inherit the directory's group
+            Self::Group => Some((0o2775, 0o664)),
+            Self::World => Some((0o777, 0o666)),
+        }
+    }
+}
+
+/// A cache entry whose ownership or mode doesn't match the store.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PermissionMismatch {
+    pub path: PathBuf,
+    pub reason: String,
+}
+
+#[cfg(unix)]
+mod unix {
+    use super::*;
+    use std::fs::{self, Metadata, Permissions};
+    use std::os::unix::fs::{chown, MetadataExt, PermissionsExt};
+
+    fn get_expected_mode(meta: &Metadata, permissions: &CachePermissions) -> Option<u32> {
+        let (dir_mode, file_mode) = permissions.get_modes()?;
+
+        if meta.is_dir() {
+            Some(dir_mode)
+        } else if meta.mode() & 0o111 != 0 {
+            // Keep executables executable
+            Some(file_mode | ((file_mode & 0o444) >> 2))
+        } else {
+            Some(file_mode)
+        }
+    }
+
+    fn walk(path: &Path, entries: &mut Vec<(PathBuf, Metadata)>) {
+        let Ok(meta) = fs::symlink_metadata(path) else {
+            return;
+        };
+
+        // Symlinks point to entries that we check separately
+        if meta.file_type().is_symlink() {
+            return;
+        }
+
+        let is_dir = meta.is_dir();
+
+        entries.push((path.to_path_buf(), meta));
+
+        if is_dir {
+            if let Ok(dir) = fs::read_dir(path) {
+                for entry in dir.flatten() {
+                    walk(&entry.path(), entries);
+                }
+            }
+        }
+    }
+
+    pub fn find_permission_mismatches(
+        path: &Path,
+        owner_dir: &Path,
+        permissions: &CachePermissions,
+    ) -> Vec<PermissionMismatch> {
+        let Ok(owner) = fs::metadata(owner_dir) else {
+            return vec![];
+        };
+
+        let mut entries = vec![];
+        let mut mismatches = vec![];
+
+        walk(path, &mut entries);
+
+        for (path, meta) in entries {
+            if meta.uid() != owner.uid() {
+                mismatches.push(PermissionMismatch {
+                    reason: format!(
+                        "{} is owned by uid {}, but the store is owned by uid {}",
+                        path.display(),
+                        meta.uid(),
+                        owner.uid()
+                    ),
+                    path,
+                });
+
+                continue;
+            }
+
+            if let Some(mode) = get_expected_mode(&meta, permissions) {
+                if meta.mode() & mode != mode {
+                    mismatches.push(PermissionMismatch {
+                        reason: format!(
+                            "{} has mode {:o}, but the cache requires {:o}",
+                            path.display(),
+                            meta.mode() & 0o7777,
+                            mode
+                        ),
+                        path,
+                    });
+                }
+            }
+        }
+
+        mismatches
+    }
+
+    pub fn fix_cache_permission(
+        path: &Path,
+        owner_dir: &Path,
+        permissions: &CachePermissions,
+    ) -> io::Result<()> {
+        let owner = fs::metadata(owner_dir)?;
+        let meta = fs::symlink_metadata(path)?;
+
+        if meta.file_type().is_symlink() {
+            return Ok(());
+        }
+
+        if let Some(mode) = get_expected_mode(&meta, permissions) {
+            if meta.mode() & mode != mode {
+                fs::set_permissions(path, Permissions::from_mode(meta.mode() & 0o7777 | mode))?;
+            }
+        }
+
+        // Only succeeds when ran as root, or when the owner already matches
+        if meta.uid() != owner.uid() || meta.gid() != owner.gid() {
+            chown(path, Some(owner.uid()), Some(owner.gid()))?;
+        }
+
+        Ok(())
+    }
+
+    pub fn apply_cache_permissions(path: &Path, owner_dir: &Path, permissions: &CachePermissions) {
+        let mut entries = vec![];
+
+        walk(path, &mut entries);
+
+        for (entry, _) in entries {
+            if let Err(error) = fix_cache_permission(&entry, owner_dir, permissions) {
+                trace!(path = ?entry, "Failed to apply cache permissions: {error}");
+            }
+        }
+    }
+}
+
+#[cfg(unix)]
+pub use unix::*;
+
+#[cfg(not(unix))]
+pub fn find_permission_mismatches(
+    _path: &Path,
+    _owner_dir: &Path,
+    _permissions: &CachePermissions,
+) -> Vec<PermissionMismatch> {
+    vec![]
+}
+
+#[cfg(not(unix))]
+pub fn fix_cache_permission(
+    _path: &Path,
+    _owner_dir: &Path,
+    _permissions: &CachePermissions,
+) -> io::Result<()> {
+    Ok(())
+}
+
+#[cfg(not(unix))]
+pub fn apply_cache_permissions(_path: &Path, _owner_dir: &Path, _permissions: &CachePermissions) {
+    trace!("Cache permissions are not supported on this platform");
+}
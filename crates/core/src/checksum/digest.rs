@@ -0,0 +1,175 @@
+use proto_pdk_api::{ChecksumAlgorithm, ChecksumFormat};
+use sha2::{Digest, Sha256, Sha512};
+use starbase_utils::fs::{self, FsError};
+use std::io::{self, Write};
+use std::path::Path;
+use tracing::trace;
+
+/// A single hash parsed from a checksum file.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ChecksumEntry {
+    pub file: Option<String>,
+    pub hash: String,
+}
+
+impl ChecksumEntry {
+    /// Return true if the entry is for the provided file name. Entries without a
+    /// file name (single-hash files) match all files.
+    pub fn matches_file(&self, file_name: &str) -> bool {
+        self.file.as_ref().map_or(true, |file| {
+            file.rsplit(['/', '\\']).next().unwrap_or(file) == file_name
+        })
+    }
+}
+
+pub fn get_checksum_algorithm_name(algorithm: ChecksumAlgorithm) -> &'static str {
+    match algorithm {
+        ChecksumAlgorithm::Blake3 => "blake3",
+        ChecksumAlgorithm::Sha256 => "sha256",
+        ChecksumAlgorithm::Sha512 => "sha512",
+    }
+}
+
+fn copy_file_into<W: Write>(path: &Path, hasher: &mut W) -> miette::Result<()> {
+    let mut file = fs::open_file(path)?;
+
+    io::copy(&mut file, hasher).map_err(|error| FsError::Read {
+        path: path.to_path_buf(),
+        error,
+    })?;
+
+    Ok(())
+}
+
+/// Hash the contents of the file with the provided algorithm,
+/// and return the digest as a lowercase hex string.
+pub fn hash_file_contents_with(
+    path: &Path,
+    algorithm: ChecksumAlgorithm,
+) -> miette::Result<String> {
+    trace!(
+        file = ?path,
+        algorithm = get_checksum_algorithm_name(algorithm),
+        "Calculating checksum"
+    );
+
+    let hash = match algorithm {
+        ChecksumAlgorithm::Blake3 => {
+            let mut hasher = blake3::Hasher::new();
+            copy_file_into(path, &mut hasher)?;
+            hasher.finalize().to_hex().to_string()
+        }
+        ChecksumAlgorithm::Sha256 => {
+            let mut hasher = Sha256::new();
+            copy_file_into(path, &mut hasher)?;
+            format!("{:x}", hasher.finalize())
+        }
+        ChecksumAlgorithm::Sha512 => {
+            let mut hasher = Sha512::new();
+            copy_file_into(path, &mut hasher)?;
+            format!("{:x}", hasher.finalize())
+        }
+    };
+
+    trace!(hash, "Calculated hash");
+
+    Ok(hash)
+}
+
+fn is_hash(value: &str) -> bool {
+    !value.is_empty() && value.chars().all(|ch| ch.is_ascii_hexdigit())
+}
+
+// SHA256 (<file>) = <hash>
+fn parse_bsd_line(line: &str) -> Option<ChecksumEntry> {
+    let (_, rest) = line.split_once(" (")?;
+    let (file, hash) = rest.rsplit_once(") = ")?;
+
+    is_hash(hash.trim()).then(|| ChecksumEntry {
+        file: Some(file.to_owned()),
+        hash: hash.trim().to_owned(),
+    })
+}
+
+// <hash>  <file>
+// <hash> *<file>
+fn parse_gnu_line(line: &str) -> Option<ChecksumEntry> {
+    let (hash, file) = line.split_once(char::is_whitespace)?;
+    let file = file.trim_start();
+    let file = file.strip_prefix('*').unwrap_or(file);
+
+    (is_hash(hash) && !file.is_empty()).then(|| ChecksumEntry {
+        file: Some(file.to_owned()),
+        hash: hash.to_owned(),
+    })
+}
+
+// <hash>
+fn parse_single_line(line: &str) -> Option<ChecksumEntry> {
+    is_hash(line).then(|| ChecksumEntry {
+        file: None,
+        hash: line.to_owned(),
+    })
+}
+
+/// Parse a single line of a checksum file in the provided format.
+pub fn parse_checksum_line(line: &str, format: ChecksumFormat) -> Option<ChecksumEntry> {
+    let line = line.trim();
+
+    match format {
+        ChecksumFormat::Auto => parse_bsd_line(line)
+            .or_else(|| parse_gnu_line(line))
+            .or_else(|| parse_single_line(line)),
+        ChecksumFormat::Bsd => parse_bsd_line(line),
+        ChecksumFormat::Gnu => parse_gnu_line(line),
+        ChecksumFormat::Single => parse_single_line(line),
+    }
+}
+
+/// Parse all entries from the checksum file, ignoring empty lines,
+/// comments, and lines that do not match the format.
+pub fn parse_checksum_file(
+    checksum_file: &Path,
+    format: ChecksumFormat,
+) -> miette::Result<Vec<ChecksumEntry>> {
+    Ok(fs::read_file(checksum_file)?
+        .lines()
+        .filter(|line| !line.trim_start().starts_with('#'))
+        .filter_map(|line| parse_checksum_line(line, format))
+        .collect())
+}
+
+pub fn verify_checksum(
+    download_file: &Path,
+    checksum_file: &Path,
+    algorithm: ChecksumAlgorithm,
+    format: ChecksumFormat,
+) -> miette::Result<bool> {
+    let checksum_hash = hash_file_contents_with(download_file, algorithm)?;
+    let download_file_name = fs::file_name(download_file);
+
+    Ok(parse_checksum_file(checksum_file, format)?
+        .into_iter()
+        .any(|entry| {
+            entry.hash.eq_ignore_ascii_case(&checksum_hash)
+                && entry.matches_file(&download_file_name)
+        }))
+}
+
+pub fn extract_checksum(
+    download_file: &Path,
+    checksum_file: &Path,
+    format: ChecksumFormat,
+) -> Option<String> {
+    let download_file_name = fs::file_name(download_file);
+    let mut entries = parse_checksum_file(checksum_file, format).ok()?;
+
+    if entries.len() == 1 {
+        return entries.pop().map(|entry| entry.hash);
+    }
+
+    entries
+        .into_iter()
+        .find(|entry| entry.file.is_some() && entry.matches_file(&download_file_name))
+        .map(|entry| entry.hash)
+}
@@ -1,20 +1,67 @@
+mod digest;
 mod minisign;
-mod sha256;
+
+pub use digest::*;
 
 use crate::error::ProtoError;
+use proto_pdk_api::{ChecksumAlgorithm, ChecksumFormat};
 use std::path::Path;
 
+/// How a download should be verified, as declared by the plugin.
+#[derive(Clone, Debug, Default)]
+pub struct ChecksumOptions {
+    pub algorithm: ChecksumAlgorithm,
+    pub format: ChecksumFormat,
+    pub public_key: Option<String>,
+}
+
+fn is_signature_file(checksum_file: &Path) -> bool {
+    matches!(
+        checksum_file.extension().and_then(|ext| ext.to_str()),
+        Some("minisig" | "minisign")
+    )
+}
+
+/// Extract the expected digest for the downloaded file from the checksum file.
+/// Signatures (minisign) do not contain a digest, so return nothing.
+pub fn extract_expected_checksum(
+    download_file: &Path,
+    checksum_file: &Path,
+    options: &ChecksumOptions,
+) -> Option<String> {
+    if is_signature_file(checksum_file) {
+        return None;
+    }
+
+    digest::extract_checksum(download_file, checksum_file, options.format)
+}
+
 pub fn verify_checksum(
     download_file: &Path,
     checksum_file: &Path,
-    checksum_public_key: Option<&str>,
+    options: &ChecksumOptions,
 ) -> miette::Result<bool> {
-    match checksum_file.extension().and_then(|ext| ext.to_str()) {
-        Some("minisig" | "minisign") => minisign::verify_checksum(
-            download_file,
-            checksum_file,
-            checksum_public_key.ok_or_else(|| ProtoError::MissingChecksumPublicKey)?,
-        ),
-        _ => sha256::verify_checksum(download_file, checksum_file),
+    if is_signature_file(checksum_file) {
+        return verify_signature(download_file, checksum_file, options.public_key.as_deref());
     }
+
+    digest::verify_checksum(
+        download_file,
+        checksum_file,
+        options.algorithm,
+        options.format,
+    )
+}
+
+/// Verify the downloaded file against a detached minisign signature.
+pub fn verify_signature(
+    download_file: &Path,
+    signature_file: &Path,
+    public_key: Option<&str>,
+) -> miette::Result<bool> {
+    minisign::verify_checksum(
+        download_file,
+        signature_file,
+        public_key.ok_or_else(|| ProtoError::MissingChecksumPublicKey)?,
+    )
 }
@@ -0,0 +1,78 @@
+use crate::helpers::{now, read_json_file_with_lock, write_json_file_with_lock};
+use crate::proto::ProtoEnvironment;
+use proto_pdk_api::ConfigChange;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use warpgate::Id;
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ConfigChangeStatus {
+    /// The change was confirmed and written to the config.
+    Applied,
+
+    /// The change was declined by the user, or could not be confirmed.
+    #[default]
+    Declined,
+
+    /// The change was rejected by proto during review.
+    Rejected,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct ConfigJournalEntry {
+    pub change: ConfigChange,
+
+    /// Plugin locator of the tool that suggested the change.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub plugin: Option<String>,
+
+    /// Why the change was declined or rejected.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+
+    /// Timestamp in milliseconds when the change was reviewed.
+    #[serde(default)]
+    pub recorded_at: u128,
+
+    pub status: ConfigChangeStatus,
+
+    pub tool: Id,
+}
+
+/// A record of every config change suggested by plugins, and its outcome.
+/// Unlike other logs, entries are never dropped, as this is for auditing.
+pub struct ConfigJournal;
+
+impl ConfigJournal {
+    pub fn get_path<P: AsRef<ProtoEnvironment>>(proto: P) -> PathBuf {
+        proto.as_ref().root.join("config-journal.json")
+    }
+
+    pub fn load<P: AsRef<ProtoEnvironment>>(proto: P) -> miette::Result<Vec<ConfigJournalEntry>> {
+        let file = Self::get_path(proto);
+
+        if file.exists() {
+            return read_json_file_with_lock(file);
+        }
+
+        Ok(vec![])
+    }
+
+    pub fn record<P: AsRef<ProtoEnvironment>>(
+        proto: P,
+        mut entry: ConfigJournalEntry,
+    ) -> miette::Result<()> {
+        let proto = proto.as_ref();
+        let mut entries = Self::load(proto)?;
+
+        if entry.recorded_at == 0 {
+            entry.recorded_at = now();
+        }
+
+        entries.push(entry);
+
+        write_json_file_with_lock(Self::get_path(proto), &entries)
+    }
+}
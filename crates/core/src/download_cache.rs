@@ -0,0 +1,223 @@
+use crate::error::ProtoError;
+use crate::helpers::now;
+use crate::store_lock::StoreLock;
+use reqwest::{header, StatusCode};
+use serde::{Deserialize, Serialize};
+use starbase_utils::fs::{self, FsError};
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::Path;
+use tracing::debug;
+use warpgate::{BlobRef, BlobStore};
+
+/// A reference from a download URL to a content-addressed blob.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct DownloadCacheEntry {
+    /// File name of the blob, composed of the SHA256 of its contents and an extension.
+    pub blob: String,
+
+    /// Timestamp (in milliseconds) of when the blob was downloaded for this URL.
+    pub cached_at: u128,
+
+    /// Size of the blob in bytes.
+    pub size: u64,
+}
+
+impl BlobRef for DownloadCacheEntry {
+    fn create(blob: String, blob_path: &Path) -> miette::Result<Self> {
+        Ok(Self {
+            size: fs::metadata(blob_path)?.len(),
+            blob,
+            cached_at: now(),
+        })
+    }
+
+    fn get_blob(&self) -> &str {
+        &self.blob
+    }
+}
+
+/// An index of download URLs to archive blobs, shared across all tools.
+pub type DownloadCache = BlobStore<DownloadCacheEntry>;
+
+// Download into the partial file, and resume from its current size
+// with a range request when a previous download was interrupted.
+// The partial file must be locked by the caller.
+async fn download_resumable(
+    client: &reqwest::Client,
+    url: &str,
+    partial_file: &Path,
+    on_chunk: impl Fn(u64, u64),
+) -> miette::Result<()> {
+    let handle_error = |error: reqwest::Error| ProtoError::Http {
+        url: url.to_owned(),
+        error,
+    };
+
+    let mut offset = if partial_file.exists() {
+        fs::metadata(partial_file)?.len()
+    } else {
+        0
+    };
+
+    let mut request = client.get(url);
+
+    if offset > 0 {
+        debug!(url, offset, "Resuming interrupted download");
+
+        request = request.header(header::RANGE, format!("bytes={offset}-"));
+    }
+
+    let mut response = request.send().await.map_err(handle_error)?;
+    let mut status = response.status();
+
+    // The partial file is invalid for this URL, so start over
+    if status == StatusCode::RANGE_NOT_SATISFIABLE {
+        offset = 0;
+        response = client.get(url).send().await.map_err(handle_error)?;
+        status = response.status();
+    }
+
+    if !status.is_success() {
+        return Err(ProtoError::DownloadFailed {
+            url: url.to_owned(),
+            status: status.to_string(),
+        }
+        .into());
+    }
+
+    // Servers that don't support ranges will send the entire file
+    let append = offset > 0 && status == StatusCode::PARTIAL_CONTENT;
+
+    if let Some(parent) = partial_file.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let handle_write_error = |error: std::io::Error| FsError::Write {
+        path: partial_file.to_path_buf(),
+        error,
+    };
+
+    let mut file = OpenOptions::new()
+        .create(true)
+        .write(true)
+        .append(append)
+        .truncate(!append)
+        .open(partial_file)
+        .map_err(handle_write_error)?;
+
+    let mut downloaded = if append { offset } else { 0 };
+    let total = response
+        .content_length()
+        .map(|length| length + downloaded)
+        .unwrap_or(0);
+
+    on_chunk(downloaded, total);
+
+    while let Some(chunk) = response.chunk().await.map_err(handle_error)? {
+        file.write_all(&chunk).map_err(handle_write_error)?;
+
+        downloaded += chunk.len() as u64;
+
+        on_chunk(downloaded, total);
+    }
+
+    Ok(())
+}
+
+/// Return the size in bytes of the provided URL, from the `Content-Length`
+/// header of a HEAD request. Not all servers provide this, so may be unknown.
+pub async fn fetch_download_size(
+    client: &reqwest::Client,
+    url: &str,
+) -> miette::Result<Option<u64>> {
+    let response = client
+        .head(url)
+        .send()
+        .await
+        .map_err(|error| ProtoError::Http {
+            url: url.to_owned(),
+            error,
+        })?;
+
+    if !response.status().is_success() {
+        return Ok(None);
+    }
+
+    Ok(response
+        .headers()
+        .get(header::CONTENT_LENGTH)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse().ok()))
+}
+
+/// Download the provided URL to the destination file, using the shared download
+/// cache in the provided directory. If the URL has already been downloaded, the
+/// cached blob is copied instead, and interrupted downloads are resumed.
+/// Progress is reported with the downloaded and total bytes (0 when unknown).
+pub async fn download_with_cache(
+    client: &reqwest::Client,
+    cache_dir: &Path,
+    url: &str,
+    dest_file: &Path,
+    on_chunk: impl Fn(u64, u64),
+) -> miette::Result<()> {
+    let cache = DownloadCache::load(cache_dir)?;
+
+    if let Some(blob_path) = cache.get_blob_path(url) {
+        debug!(url, blob = ?blob_path, "Using cached download");
+
+        fs::copy_file(&blob_path, dest_file)?;
+
+        return Ok(());
+    }
+
+    let partial_file = cache.get_partial_path(url);
+
+    // Only 1 process may append to the partial file of a URL at a time
+    let url_lock = StoreLock::acquire(partial_file.with_extension("lock"))?;
+
+    // Another process may have downloaded it while we were waiting
+    if let Some(blob_path) = DownloadCache::load(cache_dir)?.get_blob_path(url) {
+        debug!(url, blob = ?blob_path, "Using cached download");
+
+        fs::copy_file(&blob_path, dest_file)?;
+
+        return Ok(());
+    }
+
+    download_resumable(client, url, &partial_file, on_chunk).await?;
+
+    let blob_path = {
+        fs::create_dir_all(cache_dir)?;
+
+        let _lock = DownloadCache::lock(cache_dir)?;
+        let mut cache = DownloadCache::load(cache_dir)?;
+        let blob_path = cache.insert_blob(url, &partial_file, &get_blob_extension(dest_file))?;
+
+        cache.save()?;
+        blob_path
+    };
+
+    drop(url_lock);
+
+    fs::copy_file(&blob_path, dest_file)?;
+
+    Ok(())
+}
+
+// Preserve compound extensions (.tar.gz), so blobs can be identified
+fn get_blob_extension(file: &Path) -> String {
+    let name = fs::file_name(file);
+
+    for ext in [".tar.gz", ".tar.xz", ".tar.bz2", ".tar.zst"] {
+        if name.ends_with(ext) {
+            return ext.into();
+        }
+    }
+
+    file.extension()
+        .map(|ext| format!(".{}", ext.to_string_lossy()))
+        .unwrap_or_default()
+}
@@ -17,6 +17,16 @@ pub enum ProtoError {
     #[error("Failed to install {tool}. {error}")]
     InstallFailed { tool: String, error: String },
 
+    #[diagnostic(
+        code(proto::tool::invalid_install),
+        help = "The archive may be incomplete, or the plugin may be locating the wrong executable."
+    )]
+    #[error(
+        "Failed to install {tool}, as the unpacked archive does not contain the executable {}. The install has been discarded.",
+        .path.style(Style::Path),
+    )]
+    InvalidStagedInstall { tool: String, path: PathBuf },
+
     #[diagnostic(code(proto::tool::build_failed))]
     #[error("Failed to build {tool} from {}: {status}", .url.style(Style::Url))]
     BuildFailed {
@@ -25,6 +35,141 @@ pub enum ProtoError {
         status: String,
     },
 
+    #[diagnostic(code(proto::tool::build_command_failed))]
+    #[error(
+        "Failed to build {tool}, command {} failed: {status}",
+        .command.style(Style::Shell),
+    )]
+    BuildCommandFailed {
+        tool: String,
+        command: String,
+        status: String,
+    },
+
+    #[diagnostic(
+        code(proto::tool::plugin_quota_exceeded),
+        help = "Increase the plugin-quota setting if this plugin is trusted."
+    )]
+    #[error(
+        "Failed to install {tool}, as {} ({size} bytes) exceeds the plugin quota of {quota}.",
+        .path.style(Style::Path),
+    )]
+    PluginQuotaExceeded {
+        tool: String,
+        path: PathBuf,
+        size: u64,
+        quota: String,
+    },
+
+    #[diagnostic(
+        code(proto::store::lock_timeout),
+        help = "If no other proto process is running, the lock is stale and the file can be deleted. Increase PROTO_LOCK_TIMEOUT to wait longer."
+    )]
+    #[error(
+        "Timed out after {seconds}s waiting for another proto process{owner} to release the lock {}.",
+        .path.style(Style::Path),
+    )]
+    StoreLockTimeout {
+        path: PathBuf,
+        owner: String,
+        seconds: u64,
+    },
+
+    #[diagnostic(code(proto::asdf::clone_failed))]
+    #[error("Failed to clone asdf plugin repository {} for {tool}: {error}", .repo.style(Style::Url))]
+    AsdfPluginCloneFailed {
+        tool: String,
+        repo: String,
+        error: String,
+    },
+
+    #[diagnostic(code(proto::asdf::missing_script))]
+    #[error(
+        "asdf plugin for {tool} does not provide a {} script, expected {}.",
+        .script.style(Style::Id),
+        .path.style(Style::Path),
+    )]
+    AsdfMissingScript {
+        tool: String,
+        script: String,
+        path: PathBuf,
+    },
+
+    #[diagnostic(code(proto::asdf::script_failed))]
+    #[error("Failed to run asdf {} script for {tool}: {status}", .script.style(Style::Id))]
+    AsdfScriptFailed {
+        tool: String,
+        script: String,
+        status: String,
+    },
+
+    #[diagnostic(
+        code(proto::asdf::unsupported_platform),
+        help = "asdf plugins are bash scripts, use WSL or a WASM plugin instead."
+    )]
+    #[error("asdf plugin for {tool} cannot be used on Windows.")]
+    AsdfUnsupportedPlatform { tool: String },
+
+    #[diagnostic(
+        code(proto::tool::script_hook_denied),
+        help = "If this plugin is trusted, allow its hook scripts with script-hooks = true in the tool's permissions section of .prototools."
+    )]
+    #[error(
+        "Unable to run {} hook script for {tool}, as hook scripts from third-party plugins must be allowed in [tools.{id}.permissions].",
+        .hook.style(Style::Id),
+    )]
+    ScriptHookDenied { tool: String, id: Id, hook: String },
+
+    #[diagnostic(code(proto::tool::script_hook_failed))]
+    #[error("Failed to run {} hook script for {tool}: {status}", .hook.style(Style::Id))]
+    ScriptHookFailed {
+        tool: String,
+        hook: String,
+        status: String,
+    },
+
+    #[diagnostic(code(proto::tool::shared_readonly))]
+    #[error(
+        "Unable to uninstall {tool}, as it was installed in the read-only shared store {}.",
+        .path.style(Style::Path)
+    )]
+    SharedStoreReadonly { tool: String, path: PathBuf },
+
+    #[diagnostic(code(proto::tool::unmanaged_missing))]
+    #[error(
+        "Unable to add {tool} {} to the inventory, as the external installation {} does not exist.",
+        .version.style(Style::Hash),
+        .path.style(Style::Path)
+    )]
+    MissingUnmanagedInstall {
+        tool: String,
+        version: String,
+        path: PathBuf,
+    },
+
+    #[diagnostic(code(proto::tool::already_managed))]
+    #[error(
+        "Unable to add {tool} {} to the inventory, as it has already been installed by proto.",
+        .version.style(Style::Hash),
+    )]
+    AlreadyManagedVersion { tool: String, version: String },
+
+    #[diagnostic(
+        code(proto::shim::conflict),
+        help = "Add the tool that should take precedence to the [settings.shims] priority list."
+    )]
+    #[error(
+        "Executable {} is claimed by both {} and {}.",
+        .bin.style(Style::File),
+        .first.style(Style::Id),
+        .second.style(Style::Id),
+    )]
+    ExecutableConflict { bin: String, first: Id, second: Id },
+
+    #[diagnostic(code(proto::version::alias_cycle))]
+    #[error("Alias chain {} contains a cycle.", .chain.style(Style::Hash))]
+    AliasCycle { chain: String },
+
     #[diagnostic(code(proto::misc::offline))]
     #[error("Internet connection required, unable to download, install, or run tools.")]
     InternetConnectionRequired,
@@ -37,6 +182,21 @@ pub enum ProtoError {
     )]
     InternetConnectionRequiredForVersion { command: String, bin_dir: PathBuf },
 
+    #[diagnostic(
+        code(proto::misc::offline_download_missing),
+        help = "Install this version while online to make it available offline."
+    )]
+    #[error(
+        "Unable to install {tool} {} while offline, as it has not been downloaded previously from {}.",
+        .version.style(Style::Hash),
+        .url.style(Style::Url),
+    )]
+    OfflineDownloadMissing {
+        tool: String,
+        version: String,
+        url: String,
+    },
+
     #[diagnostic(code(proto::verify::missing_public_key))]
     #[error(
         "A {} is required to verify this tool.", "checksum_public_key".style(Style::Property)
@@ -52,6 +212,112 @@ pub enum ProtoError {
         download: PathBuf,
     },
 
+    #[diagnostic(code(proto::lock::mismatch))]
+    #[error(
+        "Download for {tool} does not match the {} recorded in {}. Expected {}, received {}.",
+        .field,
+        ".protolock".style(Style::File),
+        .expected.style(Style::Hash),
+        .actual.style(Style::Hash),
+    )]
+    LockfileMismatch {
+        tool: String,
+        field: String,
+        expected: String,
+        actual: String,
+    },
+
+    #[diagnostic(
+        code(proto::lock::missing_tool),
+        help = "Run the install without --frozen to update the lockfile."
+    )]
+    #[error(
+        "{} has not been locked in {}, unable to install in frozen mode.",
+        .id.style(Style::Id),
+        .path.style(Style::Path),
+    )]
+    LockfileMissingTool { id: Id, path: PathBuf },
+
+    #[diagnostic(
+        code(proto::lock::outdated),
+        help = "Run the install without --frozen to update the lockfile."
+    )]
+    #[error(
+        "{} {} does not match the locked version {} in {}, unable to install in frozen mode.",
+        .id.style(Style::Id),
+        .spec.style(Style::Hash),
+        .version.style(Style::Hash),
+        .path.style(Style::Path),
+    )]
+    LockfileOutdated {
+        id: Id,
+        spec: String,
+        version: String,
+        path: PathBuf,
+    },
+
+    #[diagnostic(code(proto::registry::request_failed))]
+    #[error(
+        "Failed to load plugin registry {} from {}: {status}",
+        .name.style(Style::Id),
+        .url.style(Style::Url),
+    )]
+    RegistryRequestFailed {
+        name: String,
+        url: String,
+        status: String,
+    },
+
+    #[diagnostic(
+        code(proto::registry::invalid),
+        help = "Registries must be a JSON object with a plugins list."
+    )]
+    #[error(
+        "Failed to parse plugin registry {} from {}: {error}",
+        .name.style(Style::Id),
+        .url.style(Style::Url),
+    )]
+    InvalidRegistry {
+        name: String,
+        url: String,
+        error: String,
+    },
+
+    #[diagnostic(
+        code(proto::config::invalid_policy),
+        help = "Durations must be a number followed by days, weeks, months, or years."
+    )]
+    #[error(
+        "Invalid {} policy {}.",
+        .setting.style(Style::Property),
+        .value.style(Style::Hash),
+    )]
+    InvalidPolicyDuration { setting: String, value: String },
+
+    #[diagnostic(
+        code(proto::config::invalid_plugin_quota),
+        help = "Sizes must be a number followed by an optional unit: B, KB, MB, GB, or TB."
+    )]
+    #[error("Invalid {} size {}.", "plugin-quota".style(Style::Property), .value.style(Style::Hash))]
+    InvalidPluginQuota { value: String },
+
+    #[diagnostic(
+        code(proto::config::invalid_os_config),
+        help = "Operating system tables support the same settings as [tools.<id>], and a version."
+    )]
+    #[error(
+        "Invalid {} configuration for {} in {}: {error}",
+        .os.style(Style::Id),
+        .tool.style(Style::Id),
+        .path.style(Style::Path),
+    )]
+    InvalidOsConfig {
+        tool: Id,
+        os: String,
+        path: PathBuf,
+        error: String,
+    },
+
     #[diagnostic(code(proto::env::home_dir))]
     #[error("Unable to determine your home directory.")]
     MissingHomeDir,
@@ -113,6 +379,89 @@ pub enum ProtoError {
     #[error("Unable to detect shell.")]
     UnsupportedShell,
 
+    #[diagnostic(
+        code(proto::version::constraint_conflict),
+        help = "Update the files so that their versions overlap, or change the detect-strategy setting."
+    )]
+    #[error(
+        "Unable to merge {tool} versions, as {} (detected from {}) does not overlap with {}.",
+        .version.style(Style::Hash),
+        .path.style(Style::Path),
+        .merged.style(Style::Hash),
+    )]
+    VersionConstraintConflict {
+        tool: String,
+        version: String,
+        merged: String,
+        path: PathBuf,
+    },
+
+    #[diagnostic(
+        code(proto::install::verify_failed),
+        help = "The downloaded artifact may be corrupted or incompatible with this machine."
+    )]
+    #[error(
+        "Failed to verify {tool} {}, {} failed: {error}",
+        .version.style(Style::Hash),
+        .command.style(Style::Shell),
+    )]
+    InstallVerificationFailed {
+        tool: String,
+        version: String,
+        command: String,
+        error: String,
+    },
+
+    #[diagnostic(
+        code(proto::package::missing_receipt),
+        help = "Packages must be created with `proto package`."
+    )]
+    #[error(
+        "Package {} does not contain a receipt, unable to install.",
+        .path.style(Style::Path)
+    )]
+    MissingPackageReceipt { path: PathBuf },
+
+    #[diagnostic(code(proto::package::tool_mismatch))]
+    #[error(
+        "Package {} contains {}, but {} was requested.",
+        .path.style(Style::Path),
+        .actual.style(Style::Id),
+        .expected.style(Style::Id),
+    )]
+    PackageToolMismatch {
+        path: PathBuf,
+        actual: String,
+        expected: String,
+    },
+
+    #[diagnostic(
+        code(proto::package::platform_mismatch),
+        help = "Packages can only be installed on the platform they were created on."
+    )]
+    #[error(
+        "Package {} was created for {}, but the current platform is {}.",
+        .path.style(Style::Path),
+        .actual.style(Style::Symbol),
+        .expected.style(Style::Symbol),
+    )]
+    PackagePlatformMismatch {
+        path: PathBuf,
+        actual: String,
+        expected: String,
+    },
+
+    #[diagnostic(
+        code(proto::package::checksum_mismatch),
+        help = "The package may be corrupted or has been tampered with."
+    )]
+    #[error(
+        "Checksum mismatch for {} in package {}.",
+        .file.style(Style::File),
+        .path.style(Style::Path),
+    )]
+    PackageChecksumMismatch { path: PathBuf, file: String },
+
     #[diagnostic(
         code(proto::version::undetected),
         help = "Has the tool been installed?"
@@ -130,6 +479,14 @@ pub enum ProtoError {
     )]
     VersionResolveFailed { tool: String, version: String },
 
+    #[diagnostic(
+        code(proto::version::resolve_timeout),
+        help = "Increase the resolve-timeout setting, or pass a semantic version explicitly."
+    )]
+    #[error("Loading available versions for {tool} took longer than {timeout} seconds, and there are no cached or installed versions to fall back to.")]
+    VersionResolveTimeout { tool: String, timeout: u64 },
+
+    #[cfg(feature = "full")]
     #[diagnostic(code(proto::http))]
     #[error("Failed to request {}.", .url.style(Style::Url))]
     Http {
@@ -138,6 +495,10 @@ pub enum ProtoError {
         error: reqwest::Error,
     },
 
+    #[diagnostic(code(proto::download::failed))]
+    #[error("Failed to download {}: {status}", .url.style(Style::Url))]
+    DownloadFailed { url: String, status: String },
+
     #[diagnostic(code(proto::verify::minisign))]
     #[error("Failed to verify minisign checksum.")]
     Minisign {
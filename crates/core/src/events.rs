@@ -1,5 +1,6 @@
 use starbase_events::Event;
 use version_spec::*;
+use warpgate::Id;
 
 macro_rules! impl_event {
     ($name:ident, $impl:tt) => {
@@ -44,3 +45,63 @@ impl_event!(ResolvedVersionEvent, {
     pub candidate: UnresolvedVersionSpec,
     pub version: VersionSpec,
 });
+
+/// Progress of resolving, installing, and uninstalling a tool, sent to the reporter
+/// channel of a [`Tool`](crate::Tool). This allows consumers embedding proto to
+/// render their own progress, instead of relying on proto's terminal output.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ProtoEvent {
+    ResolvingVersion {
+        tool: Id,
+        candidate: UnresolvedVersionSpec,
+    },
+    ResolvedVersion {
+        tool: Id,
+        version: VersionSpec,
+    },
+    /// Sent when another process is installing the same version, and
+    /// its lock must be released before continuing.
+    WaitingForLock {
+        tool: Id,
+        version: VersionSpec,
+        owner: Option<u32>,
+    },
+    Installing {
+        tool: Id,
+        version: VersionSpec,
+    },
+    Building {
+        tool: Id,
+        version: VersionSpec,
+    },
+    Downloading {
+        tool: Id,
+        url: String,
+    },
+    /// Sent for each chunk received. The total is 0 when unknown.
+    DownloadProgress {
+        tool: Id,
+        downloaded: u64,
+        total: u64,
+    },
+    Verifying {
+        tool: Id,
+        version: VersionSpec,
+    },
+    Unpacking {
+        tool: Id,
+        version: VersionSpec,
+    },
+    Installed {
+        tool: Id,
+        version: VersionSpec,
+    },
+    Uninstalling {
+        tool: Id,
+        version: VersionSpec,
+    },
+    Uninstalled {
+        tool: Id,
+        version: VersionSpec,
+    },
+}
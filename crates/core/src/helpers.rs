@@ -1,4 +1,5 @@
 use crate::error::ProtoError;
+use crate::warnings::trigger_host_warning;
 use cached::proc_macro::cached;
 use miette::IntoDiagnostic;
 use once_cell::sync::Lazy;
@@ -6,16 +7,22 @@ use regex::Regex;
 use semver::Version;
 use serde::de::DeserializeOwned;
 use serde::Serialize;
+#[cfg(feature = "full")]
 use sha2::{Digest, Sha256};
+#[cfg(feature = "full")]
 use starbase_archive::is_supported_archive_extension;
 use starbase_utils::dirs::home_dir;
-use starbase_utils::fs::{self, FsError};
+use starbase_utils::fs;
+#[cfg(feature = "full")]
+use starbase_utils::fs::FsError;
 use starbase_utils::json::{self, JsonError};
+#[cfg(feature = "full")]
+use std::io;
 use std::net::{Shutdown, SocketAddr, TcpStream, ToSocketAddrs};
 use std::path::Path;
+use std::thread;
 use std::time::{Duration, SystemTime};
 use std::{env, path::PathBuf};
-use std::{io, thread};
 use tracing::trace;
 
 pub static ENV_VAR: Lazy<Regex> = Lazy::new(|| Regex::new(r"\$(?<name>[A-Z0-9_]+)").unwrap());
@@ -34,6 +41,8 @@ pub fn get_proto_home() -> miette::Result<PathBuf> {
     }
 
     if let Ok(root) = env::var("PROTO_ROOT") {
+        trigger_host_warning("proto-root-env-var");
+
         return Ok(root.into());
     }
 
@@ -99,6 +108,10 @@ fn check_connection_from_host(host: String, timeout: u64) -> bool {
     false
 }
 
+pub fn is_fs_audit_enabled() -> bool {
+    env::var("PROTO_AUDIT_FS").is_ok_and(|value| value == "1" || value == "true")
+}
+
 #[cached(time = 300)]
 #[tracing::instrument]
 pub fn is_offline() -> bool {
@@ -174,10 +187,12 @@ pub fn is_cache_enabled() -> bool {
     }
 }
 
+#[cfg(feature = "full")]
 pub fn is_archive_file<P: AsRef<Path>>(path: P) -> bool {
     is_supported_archive_extension(path.as_ref())
 }
 
+#[cfg(feature = "full")]
 pub fn hash_file_contents<P: AsRef<Path>>(path: P) -> miette::Result<String> {
     let path = path.as_ref();
 
@@ -205,6 +220,62 @@ pub fn now() -> u128 {
         .unwrap_or(0)
 }
 
+/// Parse a human readable duration, like "90 days" or "18 months", into a [`Duration`].
+/// Months are treated as 30 days, and years as 365 days.
+pub fn parse_duration(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    let index = value.find(|c: char| !c.is_ascii_digit())?;
+    let (amount, unit) = value.split_at(index);
+    let amount: u64 = amount.parse().ok()?;
+
+    let days = match unit.trim().to_lowercase().as_str() {
+        "d" | "day" | "days" => 1,
+        "w" | "week" | "weeks" => 7,
+        "mo" | "month" | "months" => 30,
+        "y" | "year" | "years" => 365,
+        _ => return None,
+    };
+
+    Some(Duration::from_secs(amount * days * 24 * 60 * 60))
+}
+
+/// Parse a human readable size, like "500MB" or "2 GB", into bytes.
+/// Units are powers of 1024, and a number without a unit is treated as bytes.
+pub fn parse_size(value: &str) -> Option<u64> {
+    let value = value.trim();
+    let index = value
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(value.len());
+    let (amount, unit) = value.split_at(index);
+    let amount: u64 = amount.parse().ok()?;
+
+    let multiplier: u64 = match unit.trim().to_lowercase().as_str() {
+        "" | "b" => 1,
+        "kb" => 1024,
+        "mb" => 1024 * 1024,
+        "gb" => 1024 * 1024 * 1024,
+        "tb" => 1024 * 1024 * 1024 * 1024,
+        _ => return None,
+    };
+
+    amount.checked_mul(multiplier)
+}
+
+/// Return the total size in bytes of all files within the directory.
+pub fn get_dir_size(dir: &Path) -> u64 {
+    fs::read_dir_all(dir)
+        .map(|entries| {
+            entries
+                .iter()
+                .filter_map(|entry| entry.metadata().ok())
+                .filter(|meta| meta.is_file())
+                .map(|meta| meta.len())
+                .sum()
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(feature = "full")]
 pub fn extract_filename_from_url<U: AsRef<str>>(url: U) -> miette::Result<String> {
     let url = url::Url::parse(url.as_ref()).into_diagnostic()?;
     let segments = url.path_segments().unwrap();
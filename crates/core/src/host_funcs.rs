@@ -0,0 +1,150 @@
+use crate::version_resolver::satisfies_version;
+use extism::{CurrentPlugin, Error, Function, UserData, Val, ValType};
+use proto_pdk_api::{
+    CompareVersionsInput, CompareVersionsOutput, ParseVersionSpecInput, ParseVersionSpecOutput,
+    SatisfiesVersionInput, SatisfiesVersionOutput,
+};
+use semver::Version;
+use tracing::trace;
+use version_spec::UnresolvedVersionSpec;
+use warpgate::host_funcs::{self, HostData};
+
+/// Create the host functions provided by warpgate, and extend them with
+/// proto specific functions, like version parsing and matching, so that
+/// plugins behave identically to the host.
+pub fn create_host_functions(data: HostData) -> Vec<Function> {
+    let mut funcs = host_funcs::create_host_functions(data.clone());
+
+    funcs.extend([
+        Function::new(
+            "compare_versions",
+            [ValType::I64],
+            [ValType::I64],
+            UserData::new(data.clone()),
+            compare_versions,
+        ),
+        Function::new(
+            "parse_version_spec",
+            [ValType::I64],
+            [ValType::I64],
+            UserData::new(data.clone()),
+            parse_version_spec,
+        ),
+        Function::new(
+            "satisfies_version",
+            [ValType::I64],
+            [ValType::I64],
+            UserData::new(data),
+            satisfies_version_spec,
+        ),
+    ]);
+
+    funcs
+}
+
+fn parse_version_spec(
+    plugin: &mut CurrentPlugin,
+    inputs: &[Val],
+    outputs: &mut [Val],
+    _user_data: UserData<HostData>,
+) -> Result<(), Error> {
+    let input: ParseVersionSpecInput = serde_json::from_str(plugin.memory_get_val(&inputs[0])?)?;
+
+    trace!(
+        spec = &input.spec,
+        "Calling host function parse_version_spec"
+    );
+
+    let output = match UnresolvedVersionSpec::parse(&input.spec) {
+        Ok(spec) => ParseVersionSpecOutput {
+            kind: Some(
+                match &spec {
+                    UnresolvedVersionSpec::Alias(_) => "alias",
+                    UnresolvedVersionSpec::Canary => "canary",
+                    UnresolvedVersionSpec::Req(_) => "req",
+                    UnresolvedVersionSpec::ReqAny(_) => "req-any",
+                    UnresolvedVersionSpec::Composite(_) => "composite",
+                    UnresolvedVersionSpec::Version(_) => "version",
+                }
+                .into(),
+            ),
+            spec: Some(spec.to_string()),
+            ..Default::default()
+        },
+        Err(error) => ParseVersionSpecOutput {
+            error: Some(error.to_string()),
+            ..Default::default()
+        },
+    };
+
+    plugin.memory_set_val(&mut outputs[0], serde_json::to_string(&output)?)?;
+
+    Ok(())
+}
+
+fn compare_versions(
+    plugin: &mut CurrentPlugin,
+    inputs: &[Val],
+    outputs: &mut [Val],
+    _user_data: UserData<HostData>,
+) -> Result<(), Error> {
+    let input: CompareVersionsInput = serde_json::from_str(plugin.memory_get_val(&inputs[0])?)?;
+
+    trace!(
+        left = &input.left,
+        right = &input.right,
+        "Calling host function compare_versions"
+    );
+
+    let output = match (Version::parse(&input.left), Version::parse(&input.right)) {
+        (Ok(left), Ok(right)) => CompareVersionsOutput {
+            ordering: Some(left.cmp(&right) as i8),
+            ..Default::default()
+        },
+        (Err(error), _) | (_, Err(error)) => CompareVersionsOutput {
+            error: Some(error.to_string()),
+            ..Default::default()
+        },
+    };
+
+    plugin.memory_set_val(&mut outputs[0], serde_json::to_string(&output)?)?;
+
+    Ok(())
+}
+
+fn satisfies_version_spec(
+    plugin: &mut CurrentPlugin,
+    inputs: &[Val],
+    outputs: &mut [Val],
+    _user_data: UserData<HostData>,
+) -> Result<(), Error> {
+    let input: SatisfiesVersionInput = serde_json::from_str(plugin.memory_get_val(&inputs[0])?)?;
+
+    trace!(
+        spec = &input.spec,
+        version = &input.version,
+        "Calling host function satisfies_version"
+    );
+
+    let output = match (
+        UnresolvedVersionSpec::parse(&input.spec),
+        Version::parse(&input.version),
+    ) {
+        (Ok(spec), Ok(version)) => SatisfiesVersionOutput {
+            satisfied: satisfies_version(&spec, &version, input.include_prereleases),
+            ..Default::default()
+        },
+        (Err(error), _) => SatisfiesVersionOutput {
+            error: Some(error.to_string()),
+            ..Default::default()
+        },
+        (_, Err(error)) => SatisfiesVersionOutput {
+            error: Some(error.to_string()),
+            ..Default::default()
+        },
+    };
+
+    plugin.memory_set_val(&mut outputs[0], serde_json::to_string(&output)?)?;
+
+    Ok(())
+}
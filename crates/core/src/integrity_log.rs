@@ -0,0 +1,90 @@
+use crate::helpers::{now, read_json_file_with_lock, write_json_file_with_lock};
+use crate::proto::ProtoEnvironment;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::time::Duration;
+use warpgate::Id;
+
+// Only keep the most recent events, as this is for visibility, not auditing
+const MAX_EVENTS: usize = 50;
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(default)]
+pub struct IntegrityEvent {
+    /// SHA256 digest of the downloaded file.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub actual_digest: Option<String>,
+
+    /// URL the checksum file was downloaded from.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub checksum_url: Option<String>,
+
+    /// URL the verified file was downloaded from.
+    pub download_url: String,
+
+    /// Digest listed in the checksum file, if it could be extracted.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expected_digest: Option<String>,
+
+    /// Plugin locator of the tool that failed verification.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub plugin: Option<String>,
+
+    /// Timestamp in milliseconds when the failure occurred.
+    pub recorded_at: u128,
+
+    pub tool: Id,
+
+    pub version: String,
+}
+
+pub struct IntegrityLog;
+
+impl IntegrityLog {
+    pub fn get_path<P: AsRef<ProtoEnvironment>>(proto: P) -> PathBuf {
+        proto.as_ref().root.join("integrity.json")
+    }
+
+    pub fn load<P: AsRef<ProtoEnvironment>>(proto: P) -> miette::Result<Vec<IntegrityEvent>> {
+        let file = Self::get_path(proto);
+
+        if file.exists() {
+            return read_json_file_with_lock(file);
+        }
+
+        Ok(vec![])
+    }
+
+    /// Load events that were recorded within the provided duration.
+    pub fn load_recent<P: AsRef<ProtoEnvironment>>(
+        proto: P,
+        within: Duration,
+    ) -> miette::Result<Vec<IntegrityEvent>> {
+        let threshold = now().saturating_sub(within.as_millis());
+
+        Ok(Self::load(proto)?
+            .into_iter()
+            .filter(|event| event.recorded_at >= threshold)
+            .collect())
+    }
+
+    pub fn record<P: AsRef<ProtoEnvironment>>(
+        proto: P,
+        mut event: IntegrityEvent,
+    ) -> miette::Result<()> {
+        let proto = proto.as_ref();
+        let mut events = Self::load(proto)?;
+
+        if event.recorded_at == 0 {
+            event.recorded_at = now();
+        }
+
+        events.push(event);
+
+        if events.len() > MAX_EVENTS {
+            events.drain(0..events.len() - MAX_EVENTS);
+        }
+
+        write_json_file_with_lock(Self::get_path(proto), &events)
+    }
+}
@@ -1,28 +1,106 @@
+#[cfg(feature = "full")]
+mod asdf;
+mod cache_permissions;
+#[cfg(feature = "full")]
 mod checksum;
+#[cfg(feature = "full")]
+mod config_journal;
+#[cfg(feature = "full")]
+mod download_cache;
 mod error;
+#[cfg(feature = "full")]
 mod events;
 mod helpers;
+#[cfg(feature = "full")]
+mod host_funcs;
+mod integrity_log;
+mod lockfile;
+#[cfg(feature = "full")]
+mod plugin_log;
 mod proto;
 mod proto_config;
+#[cfg(feature = "full")]
+mod registry;
+#[cfg(feature = "full")]
+mod safe_unpack;
+#[cfg(feature = "full")]
+mod script_hooks;
+#[cfg(feature = "full")]
+mod shim_cache;
+#[cfg(feature = "full")]
 mod shim_registry;
+mod store_lock;
+#[cfg(feature = "full")]
 mod tool;
+#[cfg(feature = "full")]
 mod tool_loader;
 mod tool_manifest;
+#[cfg(feature = "full")]
+mod tool_package;
+#[cfg(feature = "full")]
+mod tool_query;
+mod tool_versions;
+#[cfg(feature = "full")]
 mod version_detector;
+#[cfg(feature = "full")]
+mod version_file;
+#[cfg(feature = "resolver")]
 mod version_resolver;
+mod warnings;
 
+#[cfg(feature = "full")]
+pub use asdf::*;
+pub use cache_permissions::*;
+#[cfg(feature = "full")]
+pub use checksum::*;
+#[cfg(feature = "full")]
+pub use config_journal::*;
+#[cfg(feature = "full")]
+pub use download_cache::*;
 pub use error::*;
+#[cfg(feature = "full")]
 pub use events::*;
 pub use helpers::*;
+#[cfg(feature = "full")]
+pub use host_funcs::*;
+pub use integrity_log::*;
+pub use lockfile::*;
+#[cfg(feature = "full")]
+pub use plugin_log::*;
 pub use proto::*;
 pub use proto_config::*;
+#[cfg(feature = "full")]
+pub use registry::*;
+#[cfg(feature = "full")]
+pub use safe_unpack::*;
+#[cfg(feature = "full")]
+pub use script_hooks::*;
+#[cfg(feature = "full")]
+pub use shim_cache::*;
+#[cfg(feature = "full")]
+pub use shim_registry::*;
+pub use store_lock::*;
+#[cfg(feature = "full")]
 pub use tool::*;
+#[cfg(feature = "full")]
 pub use tool_loader::*;
 pub use tool_manifest::*;
+#[cfg(feature = "full")]
+pub use tool_package::*;
+#[cfg(feature = "full")]
+pub use tool_query::*;
+pub use tool_versions::*;
+#[cfg(feature = "full")]
 pub use version_detector::*;
+#[cfg(feature = "full")]
+pub use version_file::*;
+#[cfg(feature = "resolver")]
 pub use version_resolver::*;
 pub use version_spec::*;
+pub use warnings::*;
 
 // Only export things consumers will actually need!
 pub use semver::{Version, VersionReq};
 pub use warpgate::{Id, PluginLocator};
+#[cfg(feature = "full")]
+pub use warpgate::{PluginCache, PluginUpdate};
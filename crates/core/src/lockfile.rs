@@ -0,0 +1,135 @@
+use crate::error::ProtoError;
+use miette::IntoDiagnostic;
+use serde::{Deserialize, Serialize};
+use starbase_utils::{fs, toml};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use tracing::debug;
+use version_spec::{UnresolvedVersionSpec, VersionSpec};
+use warpgate::Id;
+
+pub const PROTO_LOCK_NAME: &str = ".protolock";
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct LockedTool {
+    /// SHA256 digest of the downloaded archive or binary.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub checksum: Option<String>,
+
+    /// URL the archive or binary was downloaded from.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub download_url: Option<String>,
+
+    /// The version or alias that was requested.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub spec: Option<UnresolvedVersionSpec>,
+
+    /// The exact version that was resolved and installed.
+    pub version: VersionSpec,
+}
+
+impl LockedTool {
+    pub fn new(version: VersionSpec) -> Self {
+        Self {
+            checksum: None,
+            download_url: None,
+            spec: None,
+            version,
+        }
+    }
+
+    /// Return true if the locked entry satisfies the provided specification.
+    /// When the requested spec was recorded, it must match exactly, otherwise
+    /// the locked version must match it. Aliases can't be compared without
+    /// resolving, so are assumed to match.
+    pub fn is_locked_for(&self, spec: &UnresolvedVersionSpec) -> bool {
+        if let Some(locked_spec) = &self.spec {
+            return locked_spec == spec;
+        }
+
+        match (spec, &self.version) {
+            (UnresolvedVersionSpec::Version(a), VersionSpec::Version(b)) => a == b,
+            (UnresolvedVersionSpec::Req(req), VersionSpec::Version(version)) => {
+                req.matches(version)
+            }
+            (UnresolvedVersionSpec::ReqAny(reqs), VersionSpec::Version(version)) => {
+                reqs.iter().any(|req| req.matches(version))
+            }
+            (UnresolvedVersionSpec::Canary, version) => version == &VersionSpec::Canary,
+            _ => true,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct ProtoLock {
+    pub tools: BTreeMap<Id, LockedTool>,
+
+    #[serde(skip)]
+    pub path: PathBuf,
+}
+
+impl ProtoLock {
+    pub fn exists_in<P: AsRef<Path>>(dir: P) -> bool {
+        dir.as_ref().join(PROTO_LOCK_NAME).exists()
+    }
+
+    pub fn load_from<P: AsRef<Path>>(dir: P) -> miette::Result<Self> {
+        Self::load(dir.as_ref().join(PROTO_LOCK_NAME))
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> miette::Result<Self> {
+        let path = path.as_ref();
+
+        debug!(file = ?path, "Loading {}", PROTO_LOCK_NAME);
+
+        let mut lock: ProtoLock = if path.exists() {
+            toml::read_file(path)?
+        } else {
+            ProtoLock::default()
+        };
+
+        lock.path = path.to_path_buf();
+
+        Ok(lock)
+    }
+
+    /// Return the locked entry for the provided tool, for use in frozen mode.
+    /// Errors if the tool has not been locked, or if the requested specification
+    /// does not match what was locked.
+    pub fn get_frozen_tool(
+        &self,
+        id: &Id,
+        spec: Option<&UnresolvedVersionSpec>,
+    ) -> miette::Result<&LockedTool> {
+        let Some(locked) = self.tools.get(id) else {
+            return Err(ProtoError::LockfileMissingTool {
+                id: id.to_owned(),
+                path: self.path.clone(),
+            }
+            .into());
+        };
+
+        if let Some(spec) = spec {
+            if !locked.is_locked_for(spec) {
+                return Err(ProtoError::LockfileOutdated {
+                    id: id.to_owned(),
+                    spec: spec.to_string(),
+                    version: locked.version.to_string(),
+                    path: self.path.clone(),
+                }
+                .into());
+            }
+        }
+
+        Ok(locked)
+    }
+
+    pub fn save(&self) -> miette::Result<()> {
+        fs::write_file_with_lock(&self.path, toml::to_string_pretty(self).into_diagnostic()?)?;
+
+        Ok(())
+    }
+}
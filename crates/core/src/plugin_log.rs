@@ -0,0 +1,46 @@
+use crate::helpers::{read_json_file_with_lock, write_json_file_with_lock};
+use crate::proto::ProtoEnvironment;
+use std::path::PathBuf;
+use warpgate::{Id, PluginCall};
+
+// Only keep the most recent calls, as this is for debugging intermittent issues
+const MAX_CALLS: usize = 100;
+
+pub struct PluginLog;
+
+impl PluginLog {
+    pub fn get_path<P: AsRef<ProtoEnvironment>>(proto: P, id: &Id) -> PathBuf {
+        proto
+            .as_ref()
+            .tools_dir
+            .join(id.as_str())
+            .join("plugin-log.json")
+    }
+
+    pub fn load<P: AsRef<ProtoEnvironment>>(proto: P, id: &Id) -> miette::Result<Vec<PluginCall>> {
+        let file = Self::get_path(proto, id);
+
+        if file.exists() {
+            return read_json_file_with_lock(file);
+        }
+
+        Ok(vec![])
+    }
+
+    pub fn record<P: AsRef<ProtoEnvironment>>(
+        proto: P,
+        id: &Id,
+        calls: Vec<PluginCall>,
+    ) -> miette::Result<()> {
+        let proto = proto.as_ref();
+        let mut log = Self::load(proto, id)?;
+
+        log.extend(calls);
+
+        if log.len() > MAX_CALLS {
+            log.drain(0..log.len() - MAX_CALLS);
+        }
+
+        write_json_file_with_lock(Self::get_path(proto, id), &log)
+    }
+}
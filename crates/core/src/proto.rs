@@ -1,5 +1,8 @@
-use crate::helpers::{get_home_dir, get_proto_home, is_offline};
-use crate::proto_config::{ProtoConfig, ProtoConfigFile, ProtoConfigManager, PROTO_CONFIG_NAME};
+use crate::cache_permissions::apply_cache_permissions;
+use crate::helpers::{get_home_dir, get_proto_home};
+use crate::proto_config::{
+    ProtoConfig, ProtoConfigFile, ProtoConfigManager, StoreType, PROTO_CONFIG_NAME,
+};
 use once_cell::sync::OnceCell;
 use starbase_utils::fs;
 use std::collections::BTreeMap;
@@ -7,6 +10,7 @@ use std::env;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use tracing::debug;
+#[cfg(feature = "full")]
 use warpgate::PluginLoader;
 
 #[derive(Clone)]
@@ -15,6 +19,8 @@ pub struct ProtoEnvironment {
     pub cwd: PathBuf,
     pub env_mode: Option<String>,
     pub plugins_dir: PathBuf,
+    pub project_store_dir: Option<PathBuf>,
+    pub shared_store_dir: Option<PathBuf>,
     pub shims_dir: PathBuf,
     pub temp_dir: PathBuf,
     pub tools_dir: PathBuf,
@@ -22,6 +28,7 @@ pub struct ProtoEnvironment {
     pub root: PathBuf, // ~/.proto
 
     config_manager: Arc<OnceCell<ProtoConfigManager>>,
+    #[cfg(feature = "full")]
     plugin_loader: Arc<OnceCell<PluginLoader>>,
     test_mode: bool,
 }
@@ -49,12 +56,17 @@ impl ProtoEnvironment {
             cwd: env::current_dir().expect("Unable to determine current working directory!"),
             env_mode: env::var("PROTO_ENV").ok(),
             plugins_dir: root.join("plugins"),
+            project_store_dir: None,
+            shared_store_dir: env::var_os("PROTO_SHARED_STORE")
+                .filter(|value| !value.is_empty())
+                .map(PathBuf::from),
             shims_dir: root.join("shims"),
             temp_dir: root.join("temp"),
             tools_dir: root.join("tools"),
             home: get_home_dir()?,
             root: root.to_owned(),
             config_manager: Arc::new(OnceCell::new()),
+            #[cfg(feature = "full")]
             plugin_loader: Arc::new(OnceCell::new()),
             test_mode: false,
         })
@@ -68,18 +80,53 @@ impl ProtoEnvironment {
         }
     }
 
+    #[cfg(feature = "full")]
     pub fn get_plugin_loader(&self) -> miette::Result<&PluginLoader> {
         let config = self.load_config()?;
 
         self.plugin_loader.get_or_try_init(|| {
             let mut loader = PluginLoader::new(&self.plugins_dir, &self.temp_dir);
             loader.set_client_options(&config.settings.http);
-            loader.set_offline_checker(is_offline);
+            loader.set_offline_checker(crate::helpers::is_offline);
+
+            if let Some(shared_dir) = &self.shared_store_dir {
+                loader.set_shared_plugins_dir(shared_dir.join("plugins"));
+            }
 
             Ok(loader)
         })
     }
 
+    /// Return the HTTP client configured with `[settings.http]`, which should be
+    /// used for all requests, so that proxies and certificates are respected.
+    #[cfg(feature = "full")]
+    pub fn get_http_client(&self) -> miette::Result<&reqwest::Client> {
+        self.get_plugin_loader()?.get_client()
+    }
+
+    /// Rewrite the provided URL to a mirror configured in `[settings.http.mirrors]`.
+    pub fn rewrite_url(&self, url: &str) -> miette::Result<String> {
+        Ok(self.load_config()?.settings.http.rewrite_url(url))
+    }
+
+    /// Return the directory of the download cache, which is shared across all tools.
+    pub fn get_download_cache_dir(&self) -> PathBuf {
+        self.root.join("cache").join("downloads")
+    }
+
+    /// Apply the configured cache permissions, and the ownership of the store,
+    /// to the provided path and its children. Failures are ignored, as only
+    /// root can change ownership.
+    pub fn apply_cache_permissions(&self, path: &Path) -> miette::Result<()> {
+        apply_cache_permissions(
+            path,
+            &self.root,
+            &self.load_config()?.settings.cache_permissions,
+        );
+
+        Ok(())
+    }
+
     pub fn get_profile_path(&self) -> miette::Result<Option<PathBuf>> {
         let cache_file = self.root.join("profile");
 
@@ -109,6 +156,49 @@ impl ProtoEnvironment {
         ])
     }
 
+    /// Relocate the tool inventory and shims into a `.proto` directory within
+    /// the project root, which is the directory of the closest `.prototools`
+    /// that enabled the project store.
+    pub fn use_project_store(&mut self) -> miette::Result<()> {
+        let manager = self.load_config_manager()?;
+        let local_files = manager
+            .files
+            .iter()
+            .filter(|file| !file.global && file.exists)
+            .collect::<Vec<_>>();
+
+        let project_root = local_files
+            .iter()
+            .find(|file| {
+                file.config
+                    .settings
+                    .as_ref()
+                    .and_then(|settings| settings.store.as_ref())
+                    .is_some_and(|store| *store == StoreType::Project)
+            })
+            .or(local_files.first())
+            .and_then(|file| file.path.parent())
+            .unwrap_or(&self.cwd)
+            .to_path_buf();
+
+        let store_dir = project_root.join(".proto");
+
+        debug!(store = ?store_dir, "Using a project store");
+
+        // The store should never be committed, similar to node_modules
+        let gitignore = store_dir.join(".gitignore");
+
+        if !gitignore.exists() {
+            fs::write_file(gitignore, "# Generated by proto\n*\n")?;
+        }
+
+        self.shims_dir = store_dir.join("shims");
+        self.tools_dir = store_dir.join("tools");
+        self.project_store_dir = Some(store_dir);
+
+        Ok(())
+    }
+
     pub fn load_config(&self) -> miette::Result<&ProtoConfig> {
         self.load_config_manager()?.get_merged_config()
     }
@@ -132,9 +222,14 @@ impl ProtoEnvironment {
                 exists: path.exists(),
                 global: true,
                 path,
-                config: ProtoConfig::load_from(&self.root, true)?,
+                config: ProtoConfig::load_with_os_overrides(
+                    self.root.join(PROTO_CONFIG_NAME),
+                    true,
+                )?,
             });
 
+            manager.restrict_to_detect_root(&self.cwd)?;
+
             Ok(manager)
         })
     }
@@ -1,13 +1,17 @@
+use crate::error::ProtoError;
+use crate::helpers::{parse_duration, parse_size, ENV_VAR_SUB};
 use indexmap::IndexMap;
 use miette::IntoDiagnostic;
 use once_cell::sync::OnceCell;
 use rustc_hash::FxHashMap;
+use schematic::schema::{JsonSchemaRenderer, SchemaGenerator};
 use schematic::{
     derive_enum, env, merge, Config, ConfigEnum, ConfigError, ConfigLoader, Format, PartialConfig,
     ValidateError, ValidateErrorType, ValidatorError,
 };
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use starbase_styles::color;
+use starbase_utils::glob::GlobSet;
 use starbase_utils::json::JsonValue;
 use starbase_utils::toml::TomlValue;
 use starbase_utils::{fs, toml};
@@ -15,12 +19,15 @@ use std::collections::BTreeMap;
 use std::hash::Hash;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::Duration;
 use tracing::{debug, trace};
 use version_spec::*;
+use warpgate::api::PluginPermissions;
 use warpgate::{HttpOptions, Id, PluginLocator};
 
 pub const PROTO_CONFIG_NAME: &str = ".prototools";
 pub const SCHEMA_PLUGIN_KEY: &str = "internal-schema";
+pub const OS_CONFIG_KEYS: [&str; 3] = ["linux", "macos", "windows"];
 
 fn merge_tools(
     mut prev: BTreeMap<Id, PartialProtoToolConfig>,
@@ -64,11 +71,52 @@ where
     Ok(Some(prev))
 }
 
+derive_enum!(
+    #[derive(ConfigEnum, Default)]
+    pub enum CachePermissions {
+        // Respect the current umask
+        #[default]
+        Inherit,
+        // Readable and writable by the group
+        Group,
+        // Readable and writable by everyone
+        World,
+    }
+);
+
+derive_enum!(
+    #[derive(ConfigEnum, Default)]
+    pub enum DateFormat {
+        // Calendar date, like 2024-03-21
+        #[default]
+        Iso,
+        // Date in the format of the system locale
+        Locale,
+        // Time elapsed since, like "3 weeks ago"
+        Relative,
+        // Full date and time with offset, like 2024-03-21T14:05:00+00:00
+        Rfc3339,
+    }
+);
+
+derive_enum!(
+    #[derive(ConfigEnum, Default)]
+    pub enum DetectRoot {
+        CwdOnly,
+        #[default]
+        Filesystem,
+        Repo,
+        Superproject,
+    }
+);
+
 derive_enum!(
     #[derive(ConfigEnum, Default)]
     pub enum DetectStrategy {
         #[default]
         FirstAvailable,
+        // Intersect the versions of all files in the closest directory
+        Merge,
         PreferPrototools,
     }
 );
@@ -81,6 +129,15 @@ derive_enum!(
     }
 );
 
+derive_enum!(
+    #[derive(ConfigEnum, Default)]
+    pub enum StoreType {
+        #[default]
+        User,
+        Project,
+    }
+);
+
 #[derive(Clone, Config, Debug, PartialEq, Serialize)]
 #[serde(untagged)]
 pub enum EnvVar {
@@ -97,6 +154,62 @@ impl EnvVar {
     }
 }
 
+#[derive(Clone, Config, Debug, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct ProtoToolPermissionsConfig {
+    // Hosts that the plugin may send HTTP requests to
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allowed_hosts: Option<Vec<String>>,
+
+    // Allow the plugin to execute commands on the host
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exec: Option<bool>,
+
+    // Allow the plugin to access paths outside of the virtual paths
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filesystem: Option<bool>,
+
+    // Allow hook scripts declared by a TOML plugin to run on the host
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub script_hooks: Option<bool>,
+}
+
+impl ProtoToolPermissionsConfig {
+    pub fn is_empty(&self) -> bool {
+        self.allowed_hosts.is_none()
+            && self.exec.is_none()
+            && self.filesystem.is_none()
+            && self.script_hooks.is_none()
+    }
+
+    /// Apply the configured overrides to the permissions declared by the plugin.
+    /// Trusted (built-in) plugins that have not declared permissions are
+    /// unrestricted, while all other plugins are denied by default.
+    pub fn apply(&self, declared: Option<&PluginPermissions>, trusted: bool) -> PluginPermissions {
+        let mut permissions = declared.cloned().unwrap_or_else(|| {
+            if trusted {
+                PluginPermissions::unrestricted()
+            } else {
+                PluginPermissions::denied()
+            }
+        });
+
+        if let Some(hosts) = &self.allowed_hosts {
+            permissions.allowed_hosts = hosts.to_owned();
+        }
+
+        if let Some(exec) = self.exec {
+            permissions.exec = exec;
+        }
+
+        if let Some(filesystem) = self.filesystem {
+            permissions.filesystem = filesystem;
+        }
+
+        permissions
+    }
+}
+
 #[derive(Clone, Config, Debug, Serialize)]
 #[config(allow_unknown_fields)]
 #[serde(rename_all = "kebab-case")]
@@ -109,12 +222,136 @@ pub struct ProtoToolConfig {
     #[serde(skip_serializing_if = "IndexMap::is_empty")]
     pub env: IndexMap<String, EnvVar>,
 
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub include_prereleases: Option<bool>,
+
+    // Override the hosts and host capabilities that the plugin is granted
+    #[setting(nested)]
+    #[serde(skip_serializing_if = "ProtoToolPermissionsConfig::is_empty")]
+    pub permissions: ProtoToolPermissionsConfig,
+
+    // Prepend the directory of the resolved executable to PATH when running,
+    // if a different executable would be found first by child processes
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prepend_path: Option<bool>,
+
     // Custom configuration to pass to plugins
     #[setting(merge = merge_fxhashmap)]
     #[serde(flatten, skip_serializing_if = "FxHashMap::is_empty")]
     pub config: FxHashMap<String, JsonValue>,
 }
 
+#[derive(Clone, Config, Debug, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct ProtoCleanSettingsConfig {
+    // Automatically clean stale versions of a tool after installing it,
+    // when their combined size exceeds this threshold (in megabytes)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auto_threshold: Option<u64>,
+
+    // Number of the highest installed versions of each tool to always keep
+    pub keep_latest: usize,
+
+    // Versions of each tool that must never be cleaned
+    #[setting(merge = merge::merge_btreemap)]
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub protected: BTreeMap<Id, Vec<UnresolvedVersionSpec>>,
+
+    // Number of days since a version was last used before it's stale
+    #[setting(default = 30)]
+    pub stale_days: u64,
+}
+
+#[derive(Clone, Config, Debug, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct ProtoPoliciesConfig {
+    // Warn when the active version of a tool was released
+    // longer ago than this duration, e.g. "18 months"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_age: Option<String>,
+
+    // Overrides `max-age` for specific tools
+    #[setting(merge = merge::merge_btreemap)]
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub max_age_by_tool: BTreeMap<Id, String>,
+}
+
+impl ProtoPoliciesConfig {
+    /// Return the maximum age allowed for the active version of the provided tool,
+    /// with the tool specific setting taking precedence over the global setting.
+    pub fn get_max_age(&self, id: &Id) -> miette::Result<Option<Duration>> {
+        let Some(value) = self.max_age_by_tool.get(id).or(self.max_age.as_ref()) else {
+            return Ok(None);
+        };
+
+        match parse_duration(value) {
+            Some(duration) => Ok(Some(duration)),
+            None => Err(ProtoError::InvalidPolicyDuration {
+                setting: "max-age".into(),
+                value: value.to_owned(),
+            }
+            .into()),
+        }
+    }
+}
+
+#[derive(Clone, Config, Debug, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct ProtoShimsSettingsConfig {
+    // Tools that take precedence when multiple tools claim
+    // the same shim or bin name, in order of priority
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub priority: Vec<Id>,
+}
+
+#[derive(Clone, Config, Debug, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct ProtoTelemetrySettingsConfig {
+    // Allow anonymous usage metrics to be sent. When not configured,
+    // consent is requested on the first interactive run
+    #[setting(env = "PROTO_TELEMETRY", parse_env = env::parse_bool)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+
+    // Send a metric when a tool is installed
+    #[setting(default = true)]
+    pub install_tool: bool,
+
+    // Send a metric when checksum or signature verification fails
+    #[setting(default = true)]
+    pub integrity_failure: bool,
+
+    // Send a metric when a tool is uninstalled
+    #[setting(default = true)]
+    pub uninstall_tool: bool,
+
+    // Send a metric when proto is upgraded
+    #[setting(default = true)]
+    pub upgrade_proto: bool,
+}
+
+#[derive(Clone, Config, Debug, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct ProtoToolVersionsSettingsConfig {
+    // Map asdf plugin names to tool IDs, e.g. nodejs = "node"
+    #[setting(merge = merge::merge_btreemap)]
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub aliases: BTreeMap<String, Id>,
+
+    // Detect versions from asdf `.tool-versions` files, alongside `.prototools`
+    #[setting(env = "PROTO_TOOL_VERSIONS", parse_env = env::parse_bool)]
+    pub enabled: bool,
+}
+
+#[derive(Clone, Config, Debug, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct ProtoUiSettingsConfig {
+    // Format of dates and times rendered in human-readable output.
+    // JSON output always uses RFC 3339
+    #[setting(env = "PROTO_DATE_FORMAT")]
+    pub date_format: DateFormat,
+}
+
 #[derive(Clone, Config, Debug, Serialize)]
 #[serde(rename_all = "kebab-case")]
 pub struct ProtoSettingsConfig {
@@ -124,16 +361,138 @@ pub struct ProtoSettingsConfig {
     #[setting(env = "PROTO_AUTO_INSTALL", parse_env = env::parse_bool)]
     pub auto_install: bool,
 
+    // Modes to apply to cache entries, for stores shared between users
+    #[setting(env = "PROTO_CACHE_PERMISSIONS")]
+    pub cache_permissions: CachePermissions,
+
+    #[setting(nested)]
+    pub clean: ProtoCleanSettingsConfig,
+
+    #[setting(env = "PROTO_DETECT_ROOT")]
+    pub detect_root: DetectRoot,
+
     #[setting(env = "PROTO_DETECT_STRATEGY")]
     pub detect_strategy: DetectStrategy,
 
     pub http: HttpOptions,
 
+    #[setting(env = "PROTO_INCLUDE_PRERELEASES", parse_env = env::parse_bool)]
+    pub include_prereleases: bool,
+
+    // Maximum number of tools to install at once with `proto use`
+    pub install_concurrency: Option<usize>,
+
+    #[setting(default = true, env = "PROTO_INSTALL_PROMPT", parse_env = env::parse_bool)]
+    pub install_prompt: bool,
+
     #[setting(env = "PROTO_PIN_LATEST")]
     pub pin_latest: Option<PinType>,
 
-    #[setting(default = true)]
-    pub telemetry: bool,
+    // Maximum size of a directory that plugins can write to, like "2GB"
+    #[setting(env = "PROTO_PLUGIN_QUOTA")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub plugin_quota: Option<String>,
+
+    // Third-party plugin registries, mapped from a name to a URL
+    #[setting(merge = merge::merge_btreemap)]
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub registries: BTreeMap<String, String>,
+
+    // Maximum number of seconds to wait when loading available versions,
+    // before falling back to previously cached or installed versions
+    #[setting(env = "PROTO_RESOLVE_TIMEOUT")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resolve_timeout: Option<u64>,
+
+    // Unpack archives one file at a time through a staging directory, and retry
+    // writes that fail while antivirus software is scanning, like Microsoft Defender
+    #[setting(env = "PROTO_SAFE_UNPACK", parse_env = env::parse_bool)]
+    pub safe_unpack: bool,
+
+    #[setting(nested)]
+    pub shims: ProtoShimsSettingsConfig,
+
+    // IDs of deprecation warnings to never display
+    #[setting(merge = merge::append_vec)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub silence_warnings: Vec<String>,
+
+    #[setting(env = "PROTO_STORE")]
+    pub store: StoreType,
+
+    #[setting(nested)]
+    pub telemetry: ProtoTelemetrySettingsConfig,
+
+    #[setting(nested)]
+    pub tool_versions: ProtoToolVersionsSettingsConfig,
+
+    // Count how many times each tool version is ran, stored locally
+    #[setting(env = "PROTO_TRACK_RUNS", parse_env = env::parse_bool)]
+    pub track_runs: bool,
+
+    #[setting(nested)]
+    pub ui: ProtoUiSettingsConfig,
+}
+
+impl ProtoSettingsConfig {
+    /// Return the maximum size in bytes of a directory that plugins can write to.
+    pub fn get_plugin_quota(&self) -> miette::Result<Option<u64>> {
+        let Some(value) = &self.plugin_quota else {
+            return Ok(None);
+        };
+
+        match parse_size(value) {
+            Some(size) => Ok(Some(size)),
+            None => Err(ProtoError::InvalidPluginQuota {
+                value: value.to_owned(),
+            }
+            .into()),
+        }
+    }
+}
+
+// Telemetry was previously a boolean setting, so convert it to the `enabled` field
+fn migrate_telemetry_setting(content: String) -> String {
+    if !content.contains("telemetry") {
+        return content;
+    }
+
+    let Ok(mut data) = content.parse::<TomlValue>() else {
+        return content;
+    };
+
+    let Some(settings) = data
+        .get_mut("settings")
+        .and_then(|value| value.as_table_mut())
+    else {
+        return content;
+    };
+
+    let Some(TomlValue::Boolean(enabled)) = settings.get("telemetry").cloned() else {
+        return content;
+    };
+
+    let Ok(telemetry) = format!("enabled = {enabled}").parse::<TomlValue>() else {
+        return content;
+    };
+
+    settings.insert("telemetry".into(), telemetry);
+
+    toml::to_string_pretty(&data).unwrap_or(content)
+}
+
+fn parse_fallback_versions(list: &[TomlValue]) -> Option<Vec<UnresolvedVersionSpec>> {
+    let mut candidates = vec![];
+
+    for item in list {
+        candidates.push(UnresolvedVersionSpec::parse(item.as_str()?).ok()?);
+    }
+
+    if candidates.is_empty() {
+        None
+    } else {
+        Some(candidates)
+    }
 }
 
 #[derive(Clone, Config, Debug, Serialize)]
@@ -152,9 +511,30 @@ pub struct ProtoConfig {
     #[serde(skip_serializing_if = "BTreeMap::is_empty")]
     pub plugins: BTreeMap<Id, PluginLocator>,
 
+    #[setting(nested)]
+    pub policies: ProtoPoliciesConfig,
+
     #[setting(nested)]
     pub settings: ProtoSettingsConfig,
 
+    /// When disabled, configs in parent directories are not inherited.
+    /// The global config is always inherited.
+    #[setting(default = true)]
+    #[serde(skip)]
+    pub extends: bool,
+
+    /// Versions to use when the current directory matches a glob,
+    /// relative to the directory of the config file.
+    #[setting(merge = merge::merge_btreemap)]
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub overrides: BTreeMap<String, BTreeMap<Id, UnresolvedVersionSpec>>,
+
+    /// Versions that were pinned as a list of candidates, which are tried in order
+    /// when resolving. The first candidate is also used as the version in `versions`.
+    #[setting(merge = merge::merge_btreemap)]
+    #[serde(skip)]
+    pub fallback_versions: BTreeMap<Id, Vec<UnresolvedVersionSpec>>,
+
     #[setting(merge = merge::merge_btreemap)]
     #[serde(flatten)]
     pub versions: BTreeMap<Id, UnresolvedVersionSpec>,
@@ -165,6 +545,73 @@ pub struct ProtoConfig {
 }
 
 impl ProtoConfig {
+    /// Return environment variables to inject into a process, with variables from
+    /// `[tools.<id>.env]` taking precedence over `[env]`, which in turn are merged
+    /// from the closest `.prototools` file up to the global file. Variables
+    /// inherited from the parent process are not overridden, and `${VAR}`
+    /// references are expanded against the merged variables, so that tool-level
+    /// values are visible to project-level references. A `None` value means
+    /// the variable will be removed.
+    ///
+    /// We don't use a `BTreeMap` for env vars, so that variable interpolation
+    /// and order of declaration can work correctly!
+    pub fn get_env_vars(&self, id: Option<&Id>) -> IndexMap<String, Option<String>> {
+        let mut base_vars = IndexMap::new();
+
+        base_vars.extend(self.env.iter());
+
+        if let Some(tool_config) = id.and_then(|id| self.tools.get(id)) {
+            base_vars.extend(tool_config.env.iter())
+        }
+
+        let mut vars = IndexMap::<String, Option<String>>::new();
+
+        for (&key, &value) in &base_vars {
+            let key_exists = std::env::var(key).is_ok_and(|v| !v.is_empty());
+            let value = value.to_value();
+
+            // Don't override parent inherited vars
+            if key_exists && value.is_some() {
+                continue;
+            }
+
+            // Interpolate nested vars
+            let value = value.map(|val| {
+                ENV_VAR_SUB
+                    .replace_all(&val, |cap: &regex::Captures| {
+                        let name = cap.get(1).unwrap().as_str();
+
+                        if let Ok(existing) = std::env::var(name) {
+                            existing
+                        } else if let Some(Some(existing)) = vars.get(name) {
+                            existing.to_owned()
+                        } else if let Some(Some(declared)) = base_vars
+                            .iter()
+                            .find(|(declared, _)| declared.as_str() == name)
+                            .map(|(_, value)| value.to_value())
+                        {
+                            // Declared later in the merged map, like a tool-level
+                            // variable that isn't declared at the project level
+                            declared
+                        } else {
+                            String::new()
+                        }
+                    })
+                    .to_string()
+            });
+
+            vars.insert(key.to_owned(), value);
+        }
+
+        vars
+    }
+
+    /// Return true if the locator is the built-in plugin for the tool,
+    /// and has not been overridden with a third-party plugin.
+    pub fn is_builtin_plugin(id: &Id, locator: &PluginLocator) -> bool {
+        Self::builtin_plugins().get(id) == Some(locator)
+    }
+
     pub fn builtin_plugins() -> BTreeMap<Id, PluginLocator> {
         let mut config = ProtoConfig::default();
         config.inherit_builtin_plugins();
@@ -263,15 +710,42 @@ impl ProtoConfig {
 
         debug!(file = ?path, "Loading {}", PROTO_CONFIG_NAME);
 
-        let config_path = path.to_string_lossy();
         let config_content = if with_lock {
             fs::read_file_with_lock(path)?
         } else {
             fs::read_file(path)?
         };
 
+        Self::parse(path, config_content)
+    }
+
+    /// Load the configuration file, and apply the `[tools.<id>.<os>]` tables that
+    /// match the current operating system, so that each file reflects the versions
+    /// used on this machine. The result should not be saved, as the tables for
+    /// other operating systems are removed.
+    pub fn load_with_os_overrides<P: AsRef<Path>>(
+        path: P,
+        with_lock: bool,
+    ) -> miette::Result<PartialProtoConfig> {
+        let path = path.as_ref();
+        let mut config = Self::load(path, with_lock)?;
+
+        apply_os_overrides(&mut config, path)?;
+
+        Ok(config)
+    }
+
+    /// Parse and validate the content of a configuration file. The path is used
+    /// for error messages, and to make relative file paths absolute.
+    pub fn parse<P: AsRef<Path>>(
+        path: P,
+        config_content: String,
+    ) -> miette::Result<PartialProtoConfig> {
+        let path = path.as_ref();
+        let config_path = path.to_string_lossy();
+
         let mut config = ConfigLoader::<ProtoConfig>::new()
-            .code(config_content, Format::Toml)?
+            .code(migrate_telemetry_setting(config_content), Format::Toml)?
             .load_partial(&())?;
 
         config
@@ -300,6 +774,44 @@ impl ProtoConfig {
                     continue;
                 }
 
+                // When a list of versions exists, the flattened versions map fails
+                // to deserialize, so all versions are only available here
+                if let (Ok(id), Some(Ok(version))) = (
+                    Id::new(field),
+                    value.as_str().map(UnresolvedVersionSpec::parse),
+                ) {
+                    config
+                        .versions
+                        .get_or_insert(Default::default())
+                        .insert(id, version);
+
+                    continue;
+                }
+
+                // A list of fallback versions to try in order
+                if let (Ok(id), Some(list)) = (Id::new(field), value.as_array()) {
+                    if let Some(candidates) = parse_fallback_versions(list) {
+                        config
+                            .versions
+                            .get_or_insert(Default::default())
+                            .insert(id.clone(), candidates[0].clone());
+
+                        config
+                            .fallback_versions
+                            .get_or_insert(Default::default())
+                            .insert(id, candidates);
+
+                        continue;
+                    }
+
+                    error.errors.push(ValidateErrorType::setting(
+                        error.path.join_key(field),
+                        ValidateError::new(format!("invalid version list `{value}`")),
+                    ));
+
+                    continue;
+                }
+
                 let message = if value.is_array() || value.is_table() {
                     format!("unknown field `{field}`")
                 } else {
@@ -359,10 +871,42 @@ impl ProtoConfig {
         Ok(config)
     }
 
+    /// Generate a JSON schema of the configuration, for use in editors.
+    pub fn generate_json_schema<P: AsRef<Path>>(path: P) -> miette::Result<()> {
+        let mut generator = SchemaGenerator::default();
+        generator.add::<ProtoConfig>();
+        generator.generate(path.as_ref(), JsonSchemaRenderer::default())?;
+
+        Ok(())
+    }
+
     pub fn save_to<P: AsRef<Path>>(dir: P, config: PartialProtoConfig) -> miette::Result<PathBuf> {
         let path = dir.as_ref().join(PROTO_CONFIG_NAME);
+        let mut data = TomlValue::try_from(&config).into_diagnostic()?;
+
+        // Write fallback versions back as a list, but only if the pinned
+        // version wasn't changed, otherwise the new pin takes precedence
+        if let (Some(table), Some(fallbacks), Some(versions)) = (
+            data.as_table_mut(),
+            &config.fallback_versions,
+            &config.versions,
+        ) {
+            for (id, candidates) in fallbacks {
+                if versions.get(id) == candidates.first() {
+                    table.insert(
+                        id.to_string(),
+                        TomlValue::Array(
+                            candidates
+                                .iter()
+                                .map(|candidate| TomlValue::String(candidate.to_string()))
+                                .collect(),
+                        ),
+                    );
+                }
+            }
+        }
 
-        fs::write_file_with_lock(&path, toml::to_string_pretty(&config).into_diagnostic()?)?;
+        fs::write_file_with_lock(&path, toml::to_string_pretty(&data).into_diagnostic()?)?;
 
         Ok(path)
     }
@@ -403,6 +947,173 @@ pub struct ProtoConfigManager {
     cwd_config: Arc<OnceCell<ProtoConfig>>,
 }
 
+/// Find the root of the git repository that contains the provided directory.
+/// Worktrees are a full checkout, so their root is the boundary, while
+/// submodules are a boundary unless continuing into their superproject.
+pub fn find_repo_root(start_dir: &Path, into_superproject: bool) -> Option<PathBuf> {
+    let mut root = None;
+
+    for dir in start_dir.ancestors() {
+        let git = dir.join(".git");
+
+        if git.is_dir() {
+            return Some(dir.to_path_buf());
+        }
+
+        if git.is_file() {
+            root = Some(dir.to_path_buf());
+
+            // Submodules point to the `.git/modules` directory of the
+            // superproject, for example, `gitdir: ../.git/modules/name`
+            let is_submodule = fs::read_file(&git).is_ok_and(|content| {
+                content
+                    .trim()
+                    .strip_prefix("gitdir:")
+                    .is_some_and(|gitdir| gitdir.replace('\\', "/").contains(".git/modules/"))
+            });
+
+            if !is_submodule || !into_superproject {
+                break;
+            }
+        }
+    }
+
+    root
+}
+
+/// Remove the `[tools.<id>.<os>]` tables from the plugin settings of each tool,
+/// and merge the table that matches the current operating system over the
+/// tool's config. The `version` key of the table takes precedence over the
+/// version pinned in the same file.
+fn apply_os_overrides(config: &mut PartialProtoConfig, path: &Path) -> miette::Result<()> {
+    let Some(tools) = &mut config.tools else {
+        return Ok(());
+    };
+
+    let context = &();
+    let mut versions = vec![];
+
+    for (id, tool_config) in tools.iter_mut() {
+        let Some(settings) = &mut tool_config.config else {
+            continue;
+        };
+
+        let mut current = None;
+
+        for os in OS_CONFIG_KEYS {
+            if let Some(value) = settings.remove(os) {
+                if os == std::env::consts::OS {
+                    current = Some(value);
+                }
+            }
+        }
+
+        let Some(value) = current else {
+            continue;
+        };
+
+        let handle_error = |error: String| ProtoError::InvalidOsConfig {
+            tool: id.to_owned(),
+            os: std::env::consts::OS.to_owned(),
+            path: path.to_path_buf(),
+            error,
+        };
+
+        let JsonValue::Object(mut table) = value else {
+            return Err(handle_error("expected a table".into()).into());
+        };
+
+        if let Some(version) = table.remove("version") {
+            let version = version
+                .as_str()
+                .and_then(|version| UnresolvedVersionSpec::parse(version).ok())
+                .ok_or_else(|| handle_error(format!("invalid version value `{version}`")))?;
+
+            trace!(
+                tool = id.as_str(),
+                os = std::env::consts::OS,
+                version = version.to_string(),
+                "Using operating system specific version"
+            );
+
+            versions.push((id.to_owned(), version));
+        }
+
+        tool_config.merge(
+            context,
+            PartialProtoToolConfig::deserialize(JsonValue::Object(table))
+                .map_err(|error| handle_error(error.to_string()))?,
+        )?;
+    }
+
+    for (id, version) in versions {
+        if let Some(fallbacks) = &mut config.fallback_versions {
+            fallbacks.remove(&id);
+        }
+
+        config
+            .versions
+            .get_or_insert(Default::default())
+            .insert(id, version);
+    }
+
+    Ok(())
+}
+
+/// Merge the versions of the `[overrides]` globs that match the current directory
+/// over the versions of the config. Globs are relative to the directory of the
+/// config file, and when multiple globs match, longer globs take precedence.
+fn apply_workspace_overrides(
+    config: &mut PartialProtoConfig,
+    path: &Path,
+    cwd: &Path,
+) -> miette::Result<()> {
+    let (Some(overrides), Some(dir)) = (&config.overrides, path.parent()) else {
+        return Ok(());
+    };
+
+    let Ok(rel_cwd) = cwd.strip_prefix(dir) else {
+        return Ok(());
+    };
+
+    let mut patterns = overrides.keys().collect::<Vec<_>>();
+    patterns.sort_by_key(|pattern| pattern.len());
+
+    let mut versions = vec![];
+
+    for pattern in patterns {
+        let glob = GlobSet::new([pattern])?;
+
+        // Also match the parent directories, so that overrides for
+        // a directory apply to everything within it
+        if rel_cwd
+            .ancestors()
+            .any(|rel_dir| !rel_dir.as_os_str().is_empty() && glob.is_match(rel_dir))
+        {
+            trace!(
+                file = ?path,
+                glob = pattern,
+                "Using workspace override versions"
+            );
+
+            versions.extend(overrides[pattern].clone());
+        }
+    }
+
+    for (id, version) in versions {
+        if let Some(fallbacks) = &mut config.fallback_versions {
+            fallbacks.remove(&id);
+        }
+
+        config
+            .versions
+            .get_or_insert(Default::default())
+            .insert(id, version);
+    }
+
+    Ok(())
+}
+
 impl ProtoConfigManager {
     pub fn load(
         start_dir: impl AsRef<Path>,
@@ -417,9 +1128,12 @@ impl ProtoConfigManager {
         while let Some(dir) = current_dir {
             if let Some(env) = env_mode {
                 let env_path = dir.join(format!("{}.{env}", PROTO_CONFIG_NAME));
+                let mut env_config = ProtoConfig::load_with_os_overrides(&env_path, false)?;
+
+                apply_workspace_overrides(&mut env_config, &env_path, start_dir.as_ref())?;
 
                 files.push(ProtoConfigFile {
-                    config: ProtoConfig::load(&env_path, false)?,
+                    config: env_config,
                     exists: env_path.exists(),
                     global: false,
                     path: env_path,
@@ -427,14 +1141,27 @@ impl ProtoConfigManager {
             }
 
             let path = dir.join(PROTO_CONFIG_NAME);
+            let mut config = ProtoConfig::load_with_os_overrides(&path, false)?;
+
+            apply_workspace_overrides(&mut config, &path, start_dir.as_ref())?;
+
+            // Configs in parent directories are not inherited
+            // once a config opts out with `extends = false`
+            let extends = config.extends != Some(false);
 
             files.push(ProtoConfigFile {
-                config: ProtoConfig::load(&path, false)?,
+                config,
                 exists: path.exists(),
                 global: false,
                 path,
             });
 
+            if !extends {
+                trace!(dir = ?dir, "Not inheriting parent {} files", PROTO_CONFIG_NAME);
+
+                break;
+            }
+
             if end_dir.is_some_and(|end| end == dir) {
                 break;
             }
@@ -450,6 +1177,39 @@ impl ProtoConfigManager {
         })
     }
 
+    /// Remove local configs that are outside of the boundary configured
+    /// with the `detect-root` setting, relative to the provided directory.
+    /// The global config is always kept.
+    pub fn restrict_to_detect_root(&mut self, cwd: &Path) -> miette::Result<()> {
+        let detect_root = self
+            .merge_configs(self.files.iter().collect())?
+            .settings
+            .detect_root;
+
+        let root = match detect_root {
+            DetectRoot::Filesystem => return Ok(()),
+            DetectRoot::CwdOnly => cwd.to_path_buf(),
+            DetectRoot::Repo | DetectRoot::Superproject => {
+                match find_repo_root(cwd, matches!(detect_root, DetectRoot::Superproject)) {
+                    Some(root) => root,
+                    None => {
+                        debug!("No git repository found, traversing the file system");
+
+                        return Ok(());
+                    }
+                }
+            }
+        };
+
+        debug!(root = ?root, "Restricting {} files to detection root", PROTO_CONFIG_NAME);
+
+        self.files.retain(|file| {
+            file.global || file.path.parent().is_some_and(|dir| dir.starts_with(&root))
+        });
+
+        Ok(())
+    }
+
     pub fn get_global_config(&self) -> miette::Result<&ProtoConfig> {
         self.cwd_config.get_or_try_init(|| {
             debug!("Loading global config only");
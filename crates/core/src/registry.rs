@@ -0,0 +1,132 @@
+use crate::error::ProtoError;
+use crate::helpers::is_offline;
+use serde::{Deserialize, Serialize};
+use starbase_utils::{fs, json};
+use std::path::PathBuf;
+use tracing::debug;
+use warpgate::{Id, PluginLocator};
+
+/// A plugin that has been published to a registry index.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct RegistryPlugin {
+    pub id: Id,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+
+    pub locator: PluginLocator,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+}
+
+impl RegistryPlugin {
+    /// Return true if the ID, name, description, or tags contain the query.
+    /// Matching is case-insensitive.
+    pub fn matches(&self, query: &str) -> bool {
+        let query = query.to_lowercase();
+        let contains = |value: &str| value.to_lowercase().contains(&query);
+
+        contains(self.id.as_str())
+            || self.name.as_deref().is_some_and(contains)
+            || self.description.as_deref().is_some_and(contains)
+            || self.tags.iter().any(|tag| contains(tag))
+    }
+}
+
+/// The JSON document hosted by a registry.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct RegistryIndex {
+    pub plugins: Vec<RegistryPlugin>,
+}
+
+impl RegistryIndex {
+    pub fn find(&self, id: &Id) -> Option<&RegistryPlugin> {
+        self.plugins.iter().find(|plugin| &plugin.id == id)
+    }
+
+    pub fn search(&self, query: &str) -> Vec<&RegistryPlugin> {
+        self.plugins
+            .iter()
+            .filter(|plugin| plugin.matches(query))
+            .collect()
+    }
+}
+
+/// A third-party index of plugins, configured with `[settings.registries]`.
+/// The location can be an HTTP(S) URL, or a path to a file on the host.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PluginRegistry {
+    pub name: String,
+    pub location: String,
+}
+
+impl PluginRegistry {
+    pub fn new(name: impl AsRef<str>, location: impl AsRef<str>) -> Self {
+        Self {
+            name: name.as_ref().to_owned(),
+            location: location.as_ref().to_owned(),
+        }
+    }
+
+    pub fn is_remote(&self) -> bool {
+        self.location.starts_with("https://") || self.location.starts_with("http://")
+    }
+
+    /// Load the index, either by requesting the URL or reading the file.
+    pub async fn load_index(&self, client: &reqwest::Client) -> miette::Result<RegistryIndex> {
+        debug!(
+            registry = &self.name,
+            location = &self.location,
+            "Loading plugin registry index"
+        );
+
+        let content = if self.is_remote() {
+            if is_offline() {
+                return Err(ProtoError::InternetConnectionRequired.into());
+            }
+
+            let handle_error = |error: reqwest::Error| ProtoError::Http {
+                url: self.location.clone(),
+                error,
+            };
+
+            let response = client
+                .get(&self.location)
+                .send()
+                .await
+                .map_err(handle_error)?;
+            let status = response.status();
+
+            if !status.is_success() {
+                return Err(ProtoError::RegistryRequestFailed {
+                    name: self.name.clone(),
+                    url: self.location.clone(),
+                    status: status.to_string(),
+                }
+                .into());
+            }
+
+            response.text().await.map_err(handle_error)?
+        } else {
+            fs::read_file(PathBuf::from(&self.location))?
+        };
+
+        json::from_str(&content).map_err(|error| {
+            ProtoError::InvalidRegistry {
+                name: self.name.clone(),
+                url: self.location.clone(),
+                error: error.to_string(),
+            }
+            .into()
+        })
+    }
+}
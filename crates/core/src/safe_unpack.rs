@@ -0,0 +1,219 @@
+use crate::proto::ProtoEnvironment;
+use starbase_archive::Archiver;
+use starbase_utils::fs::{self, FsError};
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::Duration;
+use tracing::{debug, trace};
+
+pub const PARTIAL_FILE_EXT: &str = "partial";
+
+const RETRY_ATTEMPTS: u32 = 6;
+const RETRY_DELAY: Duration = Duration::from_millis(100);
+
+/// Return true if the error was likely caused by another process holding
+/// the file open, usually an antivirus scanner, and should be retried.
+pub fn is_file_lock_error(error: &io::Error) -> bool {
+    let codes: &[i32] = if cfg!(windows) {
+        // ERROR_ACCESS_DENIED, ERROR_SHARING_VIOLATION, ERROR_LOCK_VIOLATION
+        &[5, 32, 33]
+    } else {
+        // EBUSY, ETXTBSY
+        &[16, 26]
+    };
+
+    error
+        .raw_os_error()
+        .is_some_and(|code| codes.contains(&code))
+}
+
+/// Run the operation, and retry with an exponential backoff when it fails
+/// with a file lock error. Returns the result and the number of retries.
+pub fn retry_on_file_lock<T>(
+    path: &Path,
+    mut op: impl FnMut() -> io::Result<T>,
+) -> (io::Result<T>, u32) {
+    let mut delay = RETRY_DELAY;
+    let mut retries = 0;
+
+    loop {
+        match op() {
+            Err(error) if retries < RETRY_ATTEMPTS && is_file_lock_error(&error) => {
+                trace!(
+                    file = ?path,
+                    "File is locked by another process, retrying in {}ms",
+                    delay.as_millis()
+                );
+
+                thread::sleep(delay);
+
+                delay *= 2;
+                retries += 1;
+            }
+            result => return (result, retries),
+        }
+    }
+}
+
+/// Return the store directories that should be excluded from real-time
+/// antivirus scanning, as they are written to heavily during installs.
+pub fn get_antivirus_exclusion_dirs(proto: &ProtoEnvironment) -> Vec<PathBuf> {
+    vec![proto.tools_dir.clone(), proto.temp_dir.clone()]
+}
+
+/// Return a message explaining how to exclude the store directories
+/// from real-time antivirus scanning.
+pub fn get_antivirus_exclusion_guidance(proto: &ProtoEnvironment) -> String {
+    let dirs = get_antivirus_exclusion_dirs(proto);
+
+    if cfg!(windows) {
+        format!(
+            "Exclude the directories from Microsoft Defender by running in an elevated PowerShell: Add-MpPreference -ExclusionPath {}",
+            dirs.iter()
+                .map(|dir| format!("\"{}\"", dir.display()))
+                .collect::<Vec<_>>()
+                .join(",")
+        )
+    } else {
+        format!(
+            "Exclude the directories from your antivirus software's real-time scanning: {}",
+            dirs.iter()
+                .map(|dir| dir.display().to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        )
+    }
+}
+
+/// Result of unpacking an archive with [`unpack_safely`].
+#[derive(Debug, Default)]
+pub struct SafeUnpackResult {
+    pub files: usize,
+    pub retries: u32,
+}
+
+/// Unpack the archive into a staging directory, and then move each file into
+/// the output directory one at a time, by streaming its contents into a partial
+/// file that is renamed once fully written and closed. Real-time scanners
+/// inspect each file once when closed, instead of while the archive is being
+/// extracted in bulk, and writes that fail because a scanner is holding the
+/// file open are retried with a backoff.
+pub fn unpack_safely(
+    input_file: &Path,
+    output_dir: &Path,
+    staging_dir: &Path,
+    prefix: Option<&str>,
+) -> miette::Result<SafeUnpackResult> {
+    debug!(
+        input_file = ?input_file,
+        staging_dir = ?staging_dir,
+        "Unpacking archive into staging directory",
+    );
+
+    if staging_dir.exists() {
+        fs::remove_dir_all(staging_dir)?;
+    }
+
+    let mut archiver = Archiver::new(staging_dir, input_file);
+
+    if let Some(prefix) = prefix {
+        archiver.set_prefix(prefix);
+    }
+
+    archiver.unpack_from_ext()?;
+
+    let mut result = SafeUnpackResult::default();
+
+    move_dir_safely(staging_dir, output_dir, &mut result)?;
+
+    debug!(
+        output_dir = ?output_dir,
+        files = result.files,
+        retries = result.retries,
+        "Moved unpacked files into output directory",
+    );
+
+    fs::remove_dir_all(staging_dir)?;
+
+    Ok(result)
+}
+
+fn move_dir_safely(
+    source_dir: &Path,
+    dest_dir: &Path,
+    result: &mut SafeUnpackResult,
+) -> miette::Result<()> {
+    fs::create_dir_all(dest_dir)?;
+
+    for entry in fs::read_dir(source_dir)? {
+        let source = entry.path();
+        let dest = dest_dir.join(entry.file_name());
+
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+
+        if file_type.is_dir() {
+            move_dir_safely(&source, &dest, result)?;
+
+            continue;
+        }
+
+        // Symlinks are moved as-is, as streaming would copy their target
+        if file_type.is_symlink() {
+            let (moved, retries) = retry_on_file_lock(&dest, || std::fs::rename(&source, &dest));
+
+            result.retries += retries;
+            moved.map_err(|error| FsError::Write {
+                path: dest.clone(),
+                error,
+            })?;
+
+            continue;
+        }
+
+        let partial = dest.with_file_name(format!(
+            "{}.{PARTIAL_FILE_EXT}",
+            entry.file_name().to_string_lossy()
+        ));
+
+        let (written, retries) = retry_on_file_lock(&partial, || stream_file(&source, &partial));
+
+        result.retries += retries;
+        written.map_err(|error| FsError::Write {
+            path: partial.clone(),
+            error,
+        })?;
+
+        let (renamed, retries) = retry_on_file_lock(&dest, || std::fs::rename(&partial, &dest));
+
+        result.retries += retries;
+        renamed.map_err(|error| FsError::Write {
+            path: dest.clone(),
+            error,
+        })?;
+
+        result.files += 1;
+    }
+
+    Ok(())
+}
+
+// Write the file in a single pass, and close it before it's renamed
+fn stream_file(source: &Path, dest: &Path) -> io::Result<()> {
+    let input = File::open(source)?;
+    let permissions = input.metadata()?.permissions();
+
+    let mut reader = BufReader::new(input);
+    let mut writer = BufWriter::new(File::create(dest)?);
+
+    io::copy(&mut reader, &mut writer)?;
+
+    writer.into_inner()?.sync_all()?;
+
+    std::fs::set_permissions(dest, permissions)?;
+
+    Ok(())
+}
@@ -0,0 +1,32 @@
+use serde::{Deserialize, Serialize};
+
+/// Shell scripts declared in the `[hooks]` table of a TOML plugin. TOML plugins
+/// can't implement WASM hook functions, so these are ran by proto on the host.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct ScriptHooks {
+    /// Ran before a tool is installed.
+    pub pre_install: Option<String>,
+
+    /// Ran after a tool has been installed.
+    pub post_install: Option<String>,
+
+    /// Ran after a tool has been uninstalled.
+    pub post_uninstall: Option<String>,
+}
+
+impl ScriptHooks {
+    /// Return the script for the provided hook name, in kebab-case.
+    pub fn get(&self, hook: &str) -> Option<&str> {
+        match hook {
+            "pre-install" => self.pre_install.as_deref(),
+            "post-install" => self.post_install.as_deref(),
+            "post-uninstall" => self.post_uninstall.as_deref(),
+            _ => None,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pre_install.is_none() && self.post_install.is_none() && self.post_uninstall.is_none()
+    }
+}
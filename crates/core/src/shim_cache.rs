@@ -0,0 +1,81 @@
+use crate::helpers::now;
+use crate::proto::ProtoEnvironment;
+use serde::{Deserialize, Serialize};
+use starbase_utils::json;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+use tracing::debug;
+
+/// Environment variable that shims set to the path where the resolved
+/// command should be cached, when the cache is missing or invalidated.
+pub const SHIM_CACHE_PATH_VAR: &str = "PROTO_SHIM_CACHE_PATH";
+
+/// Return the modified time of the file or directory in milliseconds,
+/// or 0 if it does not exist. Must match the shim's implementation!
+pub fn get_modified_time(path: &Path) -> u128 {
+    fs::metadata(path)
+        .and_then(|meta| meta.modified())
+        .ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map(|duration| duration.as_millis())
+        .unwrap_or_default()
+}
+
+/// A command resolved by `proto run` for a shim. Shims execute the command
+/// directly, without spawning proto, until any of the tracked files change.
+/// Timestamps are stored as strings, as the shim's lean JSON parser
+/// would lose precision with large numbers.
+#[derive(Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(default)]
+pub struct ShimCacheEntry {
+    /// Arguments to pass before the shim's passthrough arguments.
+    pub args: Vec<String>,
+
+    /// Executable to run, either the tool's or a parent executable.
+    pub bin: PathBuf,
+
+    pub created_at: String,
+
+    /// Environment variables to set, or remove when `None`.
+    pub env: BTreeMap<String, Option<String>>,
+
+    /// Files and directories the resolution was derived from,
+    /// mapped to their modified time.
+    pub mtimes: BTreeMap<PathBuf, String>,
+
+    /// Commands that must be ran through proto, as they're blocked.
+    pub self_upgrade_commands: Vec<String>,
+}
+
+impl ShimCacheEntry {
+    pub fn track_path(&mut self, path: &Path) {
+        self.mtimes
+            .insert(path.to_path_buf(), get_modified_time(path).to_string());
+    }
+
+    /// Track all configuration files that were loaded, and the directories
+    /// they live in, so that new version files will also invalidate.
+    pub fn track_config_files(&mut self, proto: &ProtoEnvironment) -> miette::Result<()> {
+        for file in &proto.load_config_manager()?.files {
+            self.track_path(&file.path);
+
+            if let Some(dir) = file.path.parent() {
+                self.track_path(dir);
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn save(&mut self, path: &Path) -> miette::Result<()> {
+        debug!(cache = ?path, "Caching resolved shim command");
+
+        self.created_at = now().to_string();
+
+        json::write_file(path, self, false)?;
+
+        Ok(())
+    }
+}
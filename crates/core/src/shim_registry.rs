@@ -1,8 +1,12 @@
+use crate::error::ProtoError;
 use crate::helpers::{read_json_file_with_lock, write_json_file_with_lock};
 use crate::proto::ProtoEnvironment;
-use rustc_hash::FxHashMap;
+use crate::tool::Tool;
+use proto_pdk_api::ShimConfig;
+use rustc_hash::{FxHashMap, FxHashSet};
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
+use warpgate::Id;
 
 #[derive(Default, Deserialize, PartialEq, Serialize)]
 #[serde(default)]
@@ -16,30 +20,92 @@ pub struct Shim {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub parent: Option<String>,
 
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parent_process: Option<String>,
+
     #[serde(skip_serializing_if = "Vec::is_empty")]
     pub before_args: Vec<String>,
 
     #[serde(skip_serializing_if = "FxHashMap::is_empty")]
     pub env_vars: FxHashMap<String, String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resolve_symlinks: Option<bool>,
+}
+
+impl Shim {
+    /// Return the ID of the tool that registered this shim. Primary shims
+    /// are keyed by the tool ID, while secondary shims have a parent.
+    pub fn get_owner<'shim>(&'shim self, name: &'shim str) -> &'shim str {
+        self.parent.as_deref().unwrap_or(name)
+    }
+
+    /// Apply a config returned from the `define_shims` plugin function,
+    /// which takes precedence over the `shim_*` executable settings.
+    pub fn apply_config(&mut self, config: ShimConfig) {
+        if !config.before_args.is_empty() {
+            self.before_args = config.before_args;
+        }
+
+        if !config.after_args.is_empty() {
+            self.after_args = config.after_args;
+        }
+
+        self.env_vars.extend(config.env_vars);
+        self.parent_process = config.parent_process;
+
+        if config.resolve_symlinks {
+            self.resolve_symlinks = Some(true);
+        }
+    }
 }
 
 pub type ShimsMap = BTreeMap<String, Shim>;
 
+/// Determine which of the 2 tools should own an executable that both claim,
+/// based on their order in the `[settings.shims] priority` list. If neither
+/// tool has been prioritized, the conflict cannot be resolved and will error.
+pub fn resolve_executable_conflict<'tool>(
+    bin: &str,
+    current: &'tool Id,
+    other: &'tool Id,
+    priority: &[Id],
+) -> miette::Result<&'tool Id> {
+    let position = |id: &Id| priority.iter().position(|item| item == id);
+
+    match (position(current), position(other)) {
+        (Some(a), Some(b)) => Ok(if a <= b { current } else { other }),
+        (Some(_), None) => Ok(current),
+        (None, Some(_)) => Ok(other),
+        (None, None) => Err(ProtoError::ExecutableConflict {
+            bin: bin.to_owned(),
+            first: other.to_owned(),
+            second: current.to_owned(),
+        }
+        .into()),
+    }
+}
+
 pub struct ShimRegistry;
 
 impl ShimRegistry {
+    pub fn load<P: AsRef<ProtoEnvironment>>(proto: P) -> miette::Result<ShimsMap> {
+        let file = proto.as_ref().shims_dir.join("registry.json");
+
+        if file.exists() {
+            return read_json_file_with_lock(file);
+        }
+
+        Ok(BTreeMap::default())
+    }
+
     pub fn update<P: AsRef<ProtoEnvironment>>(proto: P, entries: ShimsMap) -> miette::Result<()> {
         if entries.is_empty() {
             return Ok(());
         }
 
         let file = proto.as_ref().shims_dir.join("registry.json");
-
-        let mut config: ShimsMap = if file.exists() {
-            read_json_file_with_lock(&file)?
-        } else {
-            BTreeMap::default()
-        };
+        let mut config = Self::load(proto)?;
 
         let mut mutated = false;
 
@@ -62,4 +128,53 @@ impl ShimRegistry {
 
         Ok(())
     }
+
+    pub fn remove<P: AsRef<ProtoEnvironment>>(proto: P, names: &[String]) -> miette::Result<()> {
+        let file = proto.as_ref().shims_dir.join("registry.json");
+        let mut config = Self::load(proto)?;
+        let count = config.len();
+
+        config.retain(|key, _| !names.contains(key));
+
+        if config.len() != count {
+            write_json_file_with_lock(file, &config)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Detect shim and bin names that are claimed by multiple tools, and error
+/// when the conflict cannot be resolved with the priority list.
+pub fn detect_executable_conflicts(tools: &[Tool], priority: &[Id]) -> miette::Result<()> {
+    let mut claims = BTreeMap::<String, &Id>::default();
+
+    for tool in tools {
+        let mut names = FxHashSet::default();
+
+        // Locating may fail without a resolved version, so skip
+        names.extend(
+            tool.get_shim_locations()
+                .unwrap_or_default()
+                .into_iter()
+                .map(|location| location.name),
+        );
+        names.extend(
+            tool.get_bin_locations()
+                .unwrap_or_default()
+                .into_iter()
+                .map(|location| location.name),
+        );
+
+        for name in names {
+            let owner = match claims.get(&name) {
+                Some(other) => resolve_executable_conflict(&name, &tool.id, other, priority)?,
+                None => &tool.id,
+            };
+
+            claims.insert(name, owner);
+        }
+    }
+
+    Ok(())
 }
@@ -0,0 +1,223 @@
+use crate::error::ProtoError;
+use fs4::FileExt;
+use starbase_utils::fs::{self, FsError};
+use std::env;
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, Instant};
+use tracing::{debug, info, trace};
+
+pub const STORE_LOCK_NAME: &str = ".lock";
+
+const LOCK_POLL_INTERVAL: Duration = Duration::from_millis(100);
+const LOCK_NOTICE_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Return the number of seconds to wait for a lock held by another
+/// process (`PROTO_LOCK_TIMEOUT`), before failing. Defaults to 5 minutes.
+pub fn get_lock_timeout() -> Duration {
+    Duration::from_secs(
+        env::var("PROTO_LOCK_TIMEOUT")
+            .ok()
+            .and_then(|value| value.parse().ok())
+            .unwrap_or(300),
+    )
+}
+
+/// An advisory lock on a file in the store, shared across proto processes,
+/// that is released when dropped. The ID of the owning process is written
+/// to the file, so that it can be reported to processes waiting on it.
+///
+/// The lock is held by the operating system, not by the file's existence,
+/// so a lock file left behind by a process that crashed or was killed is
+/// stale, and will be recovered by the next process that acquires it.
+#[derive(Debug)]
+pub struct StoreLock {
+    pub path: PathBuf,
+    file: File,
+}
+
+impl StoreLock {
+    /// Lock the directory with a `.lock` file inside of it.
+    /// The directory will be created if it does not exist.
+    pub fn acquire_dir(dir: impl AsRef<Path>) -> miette::Result<Self> {
+        Self::acquire_dir_with(dir, |_| {})
+    }
+
+    /// Lock the directory with a `.lock` file inside of it, and call the
+    /// provided function with the owning process ID, if the lock is held
+    /// by another process and must be waited on.
+    pub fn acquire_dir_with(
+        dir: impl AsRef<Path>,
+        on_wait: impl FnOnce(Option<u32>),
+    ) -> miette::Result<Self> {
+        Self::acquire_with(dir.as_ref().join(STORE_LOCK_NAME), on_wait)
+    }
+
+    /// Lock the provided file, waiting for other processes to release it.
+    pub fn acquire(path: impl AsRef<Path>) -> miette::Result<Self> {
+        Self::acquire_with(path, |_| {})
+    }
+
+    /// Lock the provided file, and call the provided function with the owning
+    /// process ID, if the lock is held by another process and must be waited on.
+    pub fn acquire_with(
+        path: impl AsRef<Path>,
+        on_wait: impl FnOnce(Option<u32>),
+    ) -> miette::Result<Self> {
+        let path = path.as_ref();
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let mut file = open_lock_file(path)?;
+
+        if file.try_lock_exclusive().is_err() {
+            let owner = read_owner(&mut file);
+            let timeout = get_lock_timeout();
+            let started = Instant::now();
+            let mut noticed = started;
+
+            info!(
+                lock = ?path,
+                "Waiting for another proto process{} to release its lock",
+                format_owner(owner)
+            );
+
+            on_wait(owner);
+
+            loop {
+                thread::sleep(LOCK_POLL_INTERVAL);
+
+                if file.try_lock_exclusive().is_ok() {
+                    break;
+                }
+
+                let elapsed = started.elapsed();
+
+                if elapsed >= timeout {
+                    return Err(ProtoError::StoreLockTimeout {
+                        path: path.to_path_buf(),
+                        owner: format_owner(read_owner(&mut file)),
+                        seconds: elapsed.as_secs(),
+                    }
+                    .into());
+                }
+
+                if noticed.elapsed() >= LOCK_NOTICE_INTERVAL {
+                    noticed = Instant::now();
+
+                    info!(
+                        lock = ?path,
+                        "Still waiting for the lock to be released ({}s)",
+                        elapsed.as_secs()
+                    );
+                }
+            }
+        }
+
+        // We own the lock, so any previous owner is no longer running
+        if let Some(owner) = read_owner(&mut file) {
+            if owner != std::process::id() {
+                debug!(
+                    lock = ?path,
+                    "Recovered stale lock left by process {}", owner
+                );
+            }
+        }
+
+        write_owner(&mut file, path)?;
+
+        trace!(lock = ?path, "Acquired lock");
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            file,
+        })
+    }
+
+    /// Return true if the file is currently locked by a process. A lock file
+    /// that exists but is not held is stale, and is not considered locked.
+    pub fn is_locked(path: impl AsRef<Path>) -> bool {
+        let path = path.as_ref();
+
+        if !path.exists() {
+            return false;
+        }
+
+        match open_lock_file(path) {
+            Ok(file) => match file.try_lock_shared() {
+                Ok(_) => {
+                    let _ = file.unlock();
+
+                    false
+                }
+                Err(_) => true,
+            },
+            Err(_) => false,
+        }
+    }
+
+    /// Return true if the directory is currently locked by a process.
+    pub fn is_dir_locked(dir: impl AsRef<Path>) -> bool {
+        Self::is_locked(dir.as_ref().join(STORE_LOCK_NAME))
+    }
+
+    /// Release the lock. The file is truncated instead of removed, as other
+    /// processes may be waiting on it, and a new file would not be locked.
+    pub fn unlock(self) -> miette::Result<()> {
+        // Handled by drop
+        Ok(())
+    }
+}
+
+impl Drop for StoreLock {
+    fn drop(&mut self) {
+        let _ = self.file.set_len(0);
+        let _ = self.file.unlock();
+
+        trace!(lock = ?self.path, "Released lock");
+    }
+}
+
+fn open_lock_file(path: &Path) -> miette::Result<File> {
+    Ok(OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(path)
+        .map_err(|error| FsError::Create {
+            path: path.to_path_buf(),
+            error,
+        })?)
+}
+
+fn read_owner(file: &mut File) -> Option<u32> {
+    let mut content = String::new();
+
+    file.seek(SeekFrom::Start(0)).ok()?;
+    file.read_to_string(&mut content).ok()?;
+
+    content.trim().parse().ok()
+}
+
+fn write_owner(file: &mut File, path: &Path) -> miette::Result<()> {
+    let handle_error = |error: std::io::Error| FsError::Write {
+        path: path.to_path_buf(),
+        error,
+    };
+
+    file.set_len(0).map_err(handle_error)?;
+    file.seek(SeekFrom::Start(0)).map_err(handle_error)?;
+    file.write_all(std::process::id().to_string().as_bytes())
+        .map_err(handle_error)?;
+
+    Ok(())
+}
+
+fn format_owner(owner: Option<u32>) -> String {
+    owner.map(|pid| format!(" (pid {pid})")).unwrap_or_default()
+}
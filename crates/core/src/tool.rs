@@ -1,14 +1,32 @@
-use crate::checksum::verify_checksum;
+use crate::asdf::AsdfPlugin;
+use crate::checksum::{
+    extract_expected_checksum, get_checksum_algorithm_name, hash_file_contents_with,
+    verify_checksum, verify_signature, ChecksumOptions,
+};
+use crate::download_cache::{download_with_cache, fetch_download_size, DownloadCache};
 use crate::error::ProtoError;
 use crate::events::*;
 use crate::helpers::{
-    extract_filename_from_url, get_proto_version, is_archive_file, is_cache_enabled, is_offline,
-    remove_bin_file, ENV_VAR,
+    extract_filename_from_url, get_dir_size, get_proto_version, hash_file_contents,
+    is_archive_file, is_cache_enabled, is_fs_audit_enabled, is_offline, now, remove_bin_file,
+    ENV_VAR,
 };
+use crate::host_funcs::create_host_functions;
+use crate::integrity_log::{IntegrityEvent, IntegrityLog};
+use crate::lockfile::LockedTool;
+use crate::plugin_log::PluginLog;
 use crate::proto::ProtoEnvironment;
 use crate::proto_config::ProtoConfig;
-use crate::shim_registry::{Shim, ShimRegistry, ShimsMap};
-use crate::tool_manifest::{ToolManifest, ToolManifestVersion};
+use crate::safe_unpack::{get_antivirus_exclusion_guidance, retry_on_file_lock, unpack_safely};
+use crate::script_hooks::ScriptHooks;
+use crate::shim_registry::{resolve_executable_conflict, Shim, ShimRegistry, ShimsMap};
+use crate::store_lock::StoreLock;
+use crate::tool_manifest::{
+    ToolInstallReceipt, ToolInstallState, ToolIntegrity, ToolManifest, ToolManifestVersion,
+    ToolVerification, INSTALL_STAGING_DIR,
+};
+use crate::tool_package::unpack_package;
+use crate::version_file::{extract_version_from_content, get_detect_version_files};
 use crate::version_resolver::VersionResolver;
 use miette::IntoDiagnostic;
 use proto_pdk_api::*;
@@ -18,18 +36,22 @@ use serde::Serialize;
 use starbase_archive::Archiver;
 use starbase_events::Emitter;
 use starbase_styles::color;
-use starbase_utils::{fs, json};
+use starbase_utils::fs::{self, FsError};
+use starbase_utils::json;
 use std::collections::BTreeMap;
 use std::env;
 use std::fmt::Debug;
 use std::path::{Path, PathBuf};
 use std::process::Command;
-use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLock};
 use std::time::{Duration, SystemTime};
+use system_env::{create_process_command, System};
+use tokio::sync::mpsc::UnboundedSender;
 use tracing::{debug, info, trace, warn};
 use warpgate::{
     download_from_url_to_file,
-    host_funcs::{create_host_functions, HostData},
+    host_funcs::{HostData, PathAudit},
     Id, PluginContainer, PluginLocator, PluginManifest, VirtualPath, Wasm,
 };
 
@@ -42,12 +64,16 @@ pub struct ExecutableLocation {
 }
 
 pub struct Tool {
+    pub asdf_plugin: Option<AsdfPlugin>,
     pub id: Id,
     pub manifest: ToolManifest,
+    pub shared_manifest: Option<ToolManifest>,
     pub metadata: ToolMetadataOutput,
     pub locator: Option<PluginLocator>,
+    pub locked: Option<LockedTool>,
     pub plugin: Arc<PluginContainer>,
     pub proto: Arc<ProtoEnvironment>,
+    pub script_hooks: ScriptHooks,
     pub version: Option<VersionSpec>,
 
     // Events
@@ -60,9 +86,50 @@ pub struct Tool {
     pub on_uninstalled: Emitter<UninstalledEvent>,
 
     cache: bool,
+    config_changes: Vec<ConfigChange>,
+    created_shims: Vec<String>,
+    detected_prereleases: AtomicBool,
     exe_path: Option<PathBuf>,
+    explanation: Vec<String>,
+    integrity: Option<ToolIntegrity>,
+    prereleases: bool,
     globals_dir: Option<PathBuf>,
     globals_prefix: Option<String>,
+    path_audit: Option<PathAudit>,
+    permissions: PluginPermissions,
+    reporter: Option<UnboundedSender<ProtoEvent>>,
+}
+
+// Persist plugin calls when the tool is no longer used,
+// so that intermittent failures can be debugged after the fact
+impl Drop for Tool {
+    fn drop(&mut self) {
+        let paths = self.take_audited_paths();
+
+        if !paths.is_empty() {
+            eprintln!(
+                "{} accessed the following paths through the virtual file system:",
+                color::id(self.id.as_str())
+            );
+
+            for path in paths {
+                eprintln!("  {}", color::path(path));
+            }
+        }
+
+        let calls = self.plugin.take_calls();
+
+        if calls.is_empty() {
+            return;
+        }
+
+        if let Err(error) = PluginLog::record(&self.proto, &self.id, calls) {
+            warn!(
+                tool = self.id.as_str(),
+                "Failed to record plugin calls: {error}"
+            );
+        }
+    }
 }
 
 impl Tool {
@@ -78,14 +145,38 @@ impl Tool {
 
         let mut tool = Tool {
             cache: true,
+            config_changes: vec![],
+            created_shims: vec![],
+            detected_prereleases: AtomicBool::new(false),
             exe_path: None,
+            explanation: vec![],
+            integrity: None,
+            prereleases: false,
             globals_dir: None,
             globals_prefix: None,
+            path_audit: None,
+            permissions: PluginPermissions::unrestricted(),
+            reporter: None,
             locator: None,
+            locked: None,
             manifest: ToolManifest::load_from(proto.tools_dir.join(id.as_str()))?,
+            shared_manifest: match &proto.shared_store_dir {
+                Some(shared_dir) => {
+                    let dir = shared_dir.join("tools").join(id.as_str());
+
+                    if dir.exists() {
+                        Some(ToolManifest::load_from(dir)?)
+                    } else {
+                        None
+                    }
+                }
+                None => None,
+            },
             metadata: ToolMetadataOutput::default(),
             plugin,
             proto,
+            script_hooks: ScriptHooks::default(),
+            asdf_plugin: None,
             version: None,
             id,
 
@@ -118,6 +209,18 @@ impl Tool {
         id: I,
         proto: P,
         manifest: PluginManifest,
+    ) -> miette::Result<Self> {
+        Self::load_from_manifest_with_trust(id, proto, manifest, true)
+    }
+
+    /// Load the tool from the plugin manifest. Untrusted (third-party) plugins
+    /// that have not declared their permissions are denied all hosts and
+    /// capabilities, unless granted with `[tools.<id>.permissions]`.
+    pub fn load_from_manifest_with_trust<I: AsRef<Id>, P: AsRef<ProtoEnvironment>>(
+        id: I,
+        proto: P,
+        manifest: PluginManifest,
+        trusted: bool,
     ) -> miette::Result<Self> {
         let id = id.as_ref();
         let proto = proto.as_ref();
@@ -127,18 +230,74 @@ impl Tool {
             color::id(id.as_str())
         );
 
-        Self::new(
-            id.to_owned(),
-            Arc::new(proto.to_owned()),
-            Arc::new(PluginContainer::new(
+        let path_audit = is_fs_audit_enabled().then(PathAudit::default);
+        let permissions_config = proto
+            .load_config()?
+            .tools
+            .get(id)
+            .map(|config| config.permissions.clone())
+            .unwrap_or_default();
+
+        // Apply the user's overrides before the plugin first runs, as it
+        // has not declared its own permissions until it's registered
+        let baseline = permissions_config.apply(None, trusted);
+        let permissions = Arc::new(RwLock::new(baseline.clone()));
+        let host_data = HostData {
+            id: id.to_owned(),
+            path_audit: path_audit.clone(),
+            permissions: Arc::clone(&permissions),
+            virtual_paths: proto.get_virtual_paths(),
+            working_dir: proto.cwd.clone(),
+        };
+
+        // Allowed hosts are enforced by the WASM runtime,
+        // so restricting them requires a new plugin instance
+        let create_plugin = |granted: &PluginPermissions| -> miette::Result<_> {
+            let manifest = if granted.allows_all_hosts() {
+                manifest.clone()
+            } else {
+                debug!(
+                    tool = id.as_str(),
+                    hosts = ?granted.allowed_hosts,
+                    "Restricting plugin to allowed hosts"
+                );
+
+                manifest
+                    .clone()
+                    .with_allowed_hosts(granted.allowed_hosts.clone().into_iter())
+            };
+
+            Ok(Arc::new(PluginContainer::new(
                 id.to_owned(),
                 manifest,
-                create_host_functions(HostData {
-                    virtual_paths: proto.get_virtual_paths(),
-                    working_dir: proto.cwd.clone(),
-                }),
-            )?),
-        )
+                create_host_functions(host_data.clone()),
+            )?))
+        };
+
+        let mut tool = Self::new(
+            id.to_owned(),
+            Arc::new(proto.to_owned()),
+            create_plugin(&baseline)?,
+        )?;
+
+        let granted = permissions_config.apply(tool.metadata.permissions.as_ref(), trusted);
+
+        if granted.allowed_hosts != baseline.allowed_hosts {
+            tool.plugin = create_plugin(&granted)?;
+        }
+
+        *permissions.write().unwrap() = granted.clone();
+
+        tool.permissions = granted;
+        tool.path_audit = path_audit;
+
+        Ok(tool)
+    }
+
+    /// Return the permissions granted to the plugin, which are the permissions
+    /// declared by the plugin with the `[tools.<id>.permissions]` overrides applied.
+    pub fn get_permissions(&self) -> miette::Result<PluginPermissions> {
+        Ok(self.permissions.clone())
     }
 
     pub fn create_plugin_manifest<P: AsRef<ProtoEnvironment>>(
@@ -161,6 +320,13 @@ impl Tool {
     }
 
     fn call_locate_executables(&self) -> miette::Result<LocateExecutablesOutput> {
+        if let Some(asdf) = &self.asdf_plugin {
+            return asdf.locate_executables(
+                &self.get_resolved_version().to_string(),
+                &self.get_tool_dir(),
+            );
+        }
+
         self.plugin.cache_func_with(
             "locate_executables",
             LocateExecutablesInput {
@@ -169,16 +335,70 @@ impl Tool {
         )
     }
 
+    fn call_define_shims(&self, shims: Vec<String>) -> miette::Result<DefineShimsOutput> {
+        if !self.plugin.has_func("define_shims") {
+            return Ok(DefineShimsOutput::default());
+        }
+
+        self.plugin.call_func_with(
+            "define_shims",
+            DefineShimsInput {
+                context: self.create_context(),
+                shims,
+            },
+        )
+    }
+
     /// Disable internal caching when applicable.
     pub fn disable_caching(&mut self) {
         self.cache = false;
     }
 
+    /// Include prerelease versions when resolving, regardless of settings.
+    pub fn enable_prereleases(&mut self) {
+        self.prereleases = true;
+    }
+
+    /// Send progress events to the provided channel while resolving,
+    /// installing, and uninstalling, so that consumers can render their own UI.
+    pub fn set_reporter(&mut self, reporter: UnboundedSender<ProtoEvent>) {
+        self.reporter = Some(reporter);
+    }
+
+    // The receiver may have been dropped, which is fine
+    fn report(&self, event: ProtoEvent) {
+        if let Some(reporter) = &self.reporter {
+            let _ = reporter.send(event);
+        }
+    }
+
     /// Return the prefix for environment variable names.
     pub fn get_env_var_prefix(&self) -> String {
         format!("PROTO_{}", self.id.to_uppercase().replace('-', "_"))
     }
 
+    /// Return a list of environment variables that point to the tool's
+    /// install directory, when the tool is an SDK root.
+    pub fn get_root_env_vars(&self) -> Vec<(String, PathBuf)> {
+        if !self.is_sdk_root() {
+            return vec![];
+        }
+
+        let tool_dir = self.get_tool_dir();
+
+        self.metadata
+            .root_env_vars
+            .iter()
+            .map(|name| (name.to_owned(), tool_dir.clone()))
+            .collect()
+    }
+
+    /// Return true if the tool is an SDK root, which is consumed
+    /// through environment variables instead of executables.
+    pub fn is_sdk_root(&self) -> bool {
+        matches!(self.metadata.kind, ToolKind::SdkRoot)
+    }
+
     /// Return an absolute path to the tool's inventory directory. The inventory houses
     /// installed versions, the manifest, and more.
     pub fn get_inventory_dir(&self) -> PathBuf {
@@ -208,15 +428,110 @@ impl Tool {
             .join(self.get_resolved_version().to_string())
     }
 
+    /// Return an absolute path to the tool's install directory within the read-only
+    /// shared store, but only if the currently resolved version has been pre-installed.
+    pub fn get_shared_tool_dir(&self) -> Option<PathBuf> {
+        let shared_dir = self.proto.shared_store_dir.as_ref()?;
+        let version = self.version.as_ref()?;
+
+        // Overridden inventories and mutable versions are never shared
+        if self.metadata.inventory.override_dir.is_some()
+            || version.is_latest()
+            || version.is_canary()
+        {
+            return None;
+        }
+
+        let dir = shared_dir
+            .join("tools")
+            .join(self.id.as_str())
+            .join(self.get_versioned_dir_name());
+
+        if dir.exists() {
+            Some(dir)
+        } else {
+            None
+        }
+    }
+
+    /// Return an absolute path to the external installation of the currently resolved version,
+    /// but only if it was added to the inventory as unmanaged.
+    pub fn get_unmanaged_dir(&self) -> Option<&Path> {
+        self.manifest
+            .versions
+            .get(self.version.as_ref()?)?
+            .unmanaged_path
+            .as_deref()
+    }
+
+    /// Return true if the currently resolved version was not installed by proto.
+    pub fn is_unmanaged(&self) -> bool {
+        self.get_unmanaged_dir().is_some()
+    }
+
     /// Return an absolute path to the tool's install directory for the currently resolved version.
+    /// If not installed in the user store, but installed in the shared store, use that instead.
     pub fn get_tool_dir(&self) -> PathBuf {
+        if let Some(unmanaged_dir) = self.get_unmanaged_dir() {
+            return unmanaged_dir.to_path_buf();
+        }
+
+        let dir = self.get_inventory_dir().join(self.get_versioned_dir_name());
+
+        if !dir.exists() {
+            if let Some(shared_dir) = self.get_shared_tool_dir() {
+                return shared_dir;
+            }
+        }
+
+        dir
+    }
+
+    /// Return true if the tool directory for the currently resolved version
+    /// is located in the read-only shared store.
+    pub fn is_shared(&self) -> bool {
+        self.proto
+            .shared_store_dir
+            .as_ref()
+            .is_some_and(|shared_dir| self.get_tool_dir().starts_with(shared_dir))
+    }
+
+    /// Return an absolute path to the lock file that is held while
+    /// installing the currently resolved version.
+    pub fn get_install_lock_path(&self) -> PathBuf {
+        self.manifest
+            .get_install_lock_path(&self.get_resolved_version())
+    }
+
+    /// Return an absolute path to the directory that pre-built archives are
+    /// unpacked into, before being moved into the inventory. Returns none when
+    /// the inventory has been overridden, as it may be on another file system.
+    pub fn get_staging_dir(&self) -> Option<PathBuf> {
+        if self.metadata.inventory.override_dir.is_some() {
+            return None;
+        }
+
+        Some(
+            self.get_inventory_dir()
+                .join(INSTALL_STAGING_DIR)
+                .join(self.get_versioned_dir_name()),
+        )
+    }
+
+    fn get_versioned_dir_name(&self) -> String {
         let mut version = self.get_resolved_version().to_string();
 
         if let Some(suffix) = &self.metadata.inventory.version_suffix {
             version = format!("{}{}", version, suffix);
         }
 
-        self.get_inventory_dir().join(version)
+        version
+    }
+
+    /// Return the steps that were taken when resolving the current version,
+    /// in a human readable format. Used for explaining the resolution process.
+    pub fn get_resolution_explanation(&self) -> &[String] {
+        &self.explanation
     }
 
     /// Explicitly set the version to use.
@@ -277,6 +592,87 @@ impl Tool {
         Ok(())
     }
 
+    /// Run the shell script for the provided hook, if declared by a TOML plugin.
+    /// The script is ran from the current directory, with the tool's ID, version,
+    /// and directory available as `PROTO_TOOL_ID`, `PROTO_TOOL_VERSION`, and `PROTO_TOOL_DIR`.
+    pub fn run_script_hook(&self, hook: &str) -> miette::Result<()> {
+        let Some(script) = self.script_hooks.get(hook) else {
+            return Ok(());
+        };
+
+        // Scripts run unsandboxed on the host, so third-party plugins must be opted in
+        let allowed = self
+            .locator
+            .as_ref()
+            .is_some_and(|locator| ProtoConfig::is_builtin_plugin(&self.id, locator))
+            || self
+                .proto
+                .load_config()?
+                .tools
+                .get(&self.id)
+                .and_then(|config| config.permissions.script_hooks)
+                .unwrap_or_default();
+
+        if !allowed {
+            return Err(ProtoError::ScriptHookDenied {
+                tool: self.get_name().to_owned(),
+                id: self.id.clone(),
+                hook: hook.to_owned(),
+            }
+            .into());
+        }
+
+        debug!(tool = self.id.as_str(), hook, script, "Running hook script");
+
+        let mut command = if cfg!(windows) {
+            let mut command = Command::new("cmd");
+            command.arg("/C");
+            command
+        } else {
+            let mut command = Command::new("sh");
+            command.arg("-c");
+            command
+        };
+
+        let status = command
+            .arg(script)
+            .current_dir(&self.proto.cwd)
+            .env("PROTO_TOOL_ID", self.id.as_str())
+            .env(
+                "PROTO_TOOL_VERSION",
+                self.get_resolved_version().to_string(),
+            )
+            .env("PROTO_TOOL_DIR", self.get_tool_dir())
+            .spawn()
+            .into_diagnostic()?
+            .wait()
+            .into_diagnostic()?;
+
+        if !status.success() {
+            return Err(ProtoError::ScriptHookFailed {
+                tool: self.get_name().to_owned(),
+                hook: hook.to_owned(),
+                status: format!("exit code {}", status),
+            }
+            .into());
+        }
+
+        Ok(())
+    }
+
+    /// Return and clear the real paths that the plugin has accessed through host
+    /// functions. Paths are only recorded when `PROTO_AUDIT_FS` is enabled.
+    pub fn take_audited_paths(&self) -> Vec<PathBuf> {
+        self.path_audit
+            .as_ref()
+            .map(|audit| {
+                std::mem::take(&mut *audit.lock().unwrap())
+                    .into_iter()
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
     /// Sync the local tool manifest with changes from the plugin.
     pub fn sync_manifest(&mut self) -> miette::Result<()> {
         if !self.plugin.has_func("sync_manifest") {
@@ -296,34 +692,119 @@ impl Tool {
             return Ok(());
         }
 
-        let mut modified = false;
+        // Config changes are not applied here, as they must be reviewed first
+        if !sync_changes.config_changes.is_empty() {
+            debug!(
+                tool = self.id.as_str(),
+                changes = ?sync_changes.config_changes,
+                "Plugin suggested config changes"
+            );
+
+            self.config_changes.extend(sync_changes.config_changes);
+        }
 
         if let Some(versions) = sync_changes.versions {
-            modified = true;
+            self.manifest.update(|manifest| {
+                let mut entries = FxHashMap::default();
+                let mut installed = FxHashSet::default();
 
-            let mut entries = FxHashMap::default();
-            let mut installed = FxHashSet::default();
+                for version in versions {
+                    let key = VersionSpec::Version(version);
+                    let value = manifest.versions.get(&key).cloned().unwrap_or_default();
 
-            for version in versions {
-                let key = VersionSpec::Version(version);
-                let value = self
-                    .manifest
-                    .versions
-                    .get(&key)
-                    .cloned()
-                    .unwrap_or_default();
+                    installed.insert(key.clone());
+                    entries.insert(key, value);
+                }
+
+                manifest.versions = entries;
+                manifest.installed_versions = installed;
+            })?;
+        }
+
+        Ok(())
+    }
+
+    /// Take the config changes that were suggested by the plugin, which
+    /// must be reviewed and confirmed before being applied.
+    pub fn take_config_changes(&mut self) -> Vec<ConfigChange> {
+        std::mem::take(&mut self.config_changes)
+    }
+
+    /// Review a config change suggested by the plugin, and return the reason
+    /// it was rejected. Changes may only add to the config, never replace
+    /// aliases or pins that were configured by the user.
+    pub fn review_config_change(&self, change: &ConfigChange) -> miette::Result<Option<String>> {
+        let config = self.proto.load_config()?;
 
-                installed.insert(key.clone());
-                entries.insert(key, value);
+        let reason = match change {
+            ConfigChange::AddAlias { alias, version } => {
+                if !is_alias_name(alias) || alias == "latest" || alias == "canary" {
+                    Some(format!("{alias} is not a valid alias name"))
+                } else if matches!(version, UnresolvedVersionSpec::Alias(inner) if inner == alias) {
+                    Some(format!("{alias} can not alias itself"))
+                } else if config
+                    .tools
+                    .get(&self.id)
+                    .is_some_and(|tool_config| tool_config.aliases.contains_key(alias))
+                {
+                    Some(format!("{alias} has already been configured"))
+                } else {
+                    None
+                }
             }
+            ConfigChange::PinVersion { version } => {
+                let global_config = ProtoConfig::load_from(self.proto.get_config_dir(true), false)?;
 
-            self.manifest.versions = entries;
-            self.manifest.installed_versions = installed;
-        }
+                if global_config
+                    .versions
+                    .is_some_and(|versions| versions.contains_key(&self.id))
+                {
+                    Some("a version has already been pinned".into())
+                } else if let UnresolvedVersionSpec::Version(version) = version {
+                    if self
+                        .manifest
+                        .installed_versions
+                        .contains(&VersionSpec::Version(version.to_owned()))
+                    {
+                        None
+                    } else {
+                        Some(format!("{version} has not been installed"))
+                    }
+                } else {
+                    None
+                }
+            }
+        };
 
-        if modified {
-            self.manifest.save()?;
-        }
+        Ok(reason)
+    }
+
+    /// Apply a reviewed config change to the global config.
+    pub fn apply_config_change(&self, change: &ConfigChange) -> miette::Result<()> {
+        debug!(
+            tool = self.id.as_str(),
+            change = ?change,
+            "Applying config change suggested by plugin"
+        );
+
+        ProtoConfig::update(self.proto.get_config_dir(true), |config| match change {
+            ConfigChange::AddAlias { alias, version } => {
+                config
+                    .tools
+                    .get_or_insert(Default::default())
+                    .entry(self.id.clone())
+                    .or_default()
+                    .aliases
+                    .get_or_insert(Default::default())
+                    .insert(alias.to_owned(), version.to_owned());
+            }
+            ConfigChange::PinVersion { version } => {
+                config
+                    .versions
+                    .get_or_insert(Default::default())
+                    .insert(self.id.clone(), version.to_owned());
+            }
+        })?;
 
         Ok(())
     }
@@ -343,12 +824,7 @@ impl Tool {
         let mut versions = LoadVersionsOutput::default();
         let mut cached = false;
 
-        // Don't use the overridden inventory path
-        let cache_path = self
-            .proto
-            .tools_dir
-            .join(self.id.as_str())
-            .join("remote-versions.json");
+        let cache_path = self.get_remote_versions_cache_path();
 
         // Attempt to read from the cache first
         if cache_path.exists() {
@@ -380,9 +856,11 @@ impl Tool {
             }
         }
 
-        // Nothing cached, so load from the plugin
-        if !cached {
-            if is_offline() {
+        // Nothing cached and offline, so fallback to installed versions
+        if !cached && is_offline() {
+            versions = self.load_installed_versions_output();
+
+            if versions.versions.is_empty() {
                 return Err(ProtoError::InternetConnectionRequiredForVersion {
                     command: format!("{}_VERSION=1.2.3 {}", self.get_env_var_prefix(), self.id),
                     bin_dir: self.proto.bin_dir.clone(),
@@ -390,84 +868,273 @@ impl Tool {
                 .into());
             }
 
+            debug!(
+                tool = self.id.as_str(),
+                "Offline and no versions cached, resolving against installed versions only"
+            );
+        }
+        // Nothing cached, so load from the plugin
+        else if !cached {
             if env::var("PROTO_BYPASS_VERSION_CHECK").is_err() {
-                versions = self.plugin.cache_func_with(
-                    "load_versions",
-                    LoadVersionsInput {
-                        initial: initial_version.to_owned(),
-                    },
-                )?;
+                if let Some(asdf) = &self.asdf_plugin {
+                    versions = asdf.list_all()?;
+
+                    json::write_file(&cache_path, &versions, false)?;
+                } else if let Some(output) = self.load_versions_from_plugin(initial_version).await?
+                {
+                    versions = output;
+
+                    json::write_file(&cache_path, &versions, false)?;
+                }
+                // Took too long, so fallback to a stale cache or installed versions
+                else {
+                    if cache_path.exists() {
+                        versions = json::read_file(&cache_path)?;
+                        cached = true;
+                    } else {
+                        versions = self.load_installed_versions_output();
+                    }
+
+                    if versions.versions.is_empty() {
+                        return Err(ProtoError::VersionResolveTimeout {
+                            tool: self.get_name().to_owned(),
+                            timeout: self
+                                .proto
+                                .load_config()?
+                                .settings
+                                .resolve_timeout
+                                .unwrap_or_default(),
+                        }
+                        .into());
+                    }
 
-                json::write_file(cache_path, &versions, false)?;
+                    warn!(
+                        tool = self.id.as_str(),
+                        "Loading available versions for {} took too long, resolving against {} versions instead, which may be outdated",
+                        self.get_name(),
+                        if cached { "previously cached" } else { "installed" },
+                    );
+                }
             }
         }
 
         // Cache the results and create a resolver
         let mut resolver = VersionResolver::from_output(versions);
 
-        resolver.with_manifest(&self.manifest);
-
-        let config = self.proto.load_config()?;
-
-        if let Some(tool_config) = config.tools.get(&self.id) {
-            resolver.with_config(tool_config);
+        if cached {
+            resolver.cache_path = Some(cache_path);
         }
 
+        self.configure_version_resolver(&mut resolver)?;
+
         Ok(resolver)
     }
 
-    /// Given an initial version, resolve it to a fully qualifed and semantic version
-    /// (or alias) according to the tool's ecosystem.
-    pub async fn resolve_version(
-        &mut self,
+    /// Load available versions from the plugin. When the `resolve-timeout` setting
+    /// is configured and the call exceeds it, the call is cancelled and nothing is returned.
+    async fn load_versions_from_plugin(
+        &self,
         initial_version: &UnresolvedVersionSpec,
-        short_circuit: bool,
-    ) -> miette::Result<()> {
-        if self.version.is_some() {
-            return Ok(());
-        }
-
-        debug!(
-            tool = self.id.as_str(),
-            initial_version = initial_version.to_string(),
-            "Resolving a semantic version or alias",
-        );
+    ) -> miette::Result<Option<LoadVersionsOutput>> {
+        let input = LoadVersionsInput {
+            initial: initial_version.to_owned(),
+        };
 
-        // If we have a fully qualified semantic version,
-        // exit early and assume the version is legitimate!
-        // Also canary is a special type that we can simply just use.
-        if short_circuit && matches!(initial_version, UnresolvedVersionSpec::Version(_))
-            || matches!(initial_version, UnresolvedVersionSpec::Canary)
-        {
-            let version = initial_version.to_resolved_spec();
+        let Some(timeout) = self.proto.load_config()?.settings.resolve_timeout else {
+            return Ok(Some(self.plugin.cache_func_with("load_versions", input)?));
+        };
 
-            debug!(
-                tool = self.id.as_str(),
-                version = version.to_string(),
-                "Resolved to {} (without validation)",
-                version
-            );
+        // Plugin calls are blocking, so run on another thread that can be abandoned
+        let plugin = Arc::clone(&self.plugin);
+        let cancel_handle = plugin.cancel_handle();
+        let task = tokio::task::spawn_blocking(move || {
+            plugin.cache_func_with::<_, LoadVersionsOutput>("load_versions", input)
+        });
 
-            self.on_resolved_version
-                .emit(ResolvedVersionEvent {
-                    candidate: initial_version.to_owned(),
-                    version: version.clone(),
-                })
-                .await?;
+        match tokio::time::timeout(Duration::from_secs(timeout), task).await {
+            Ok(result) => Ok(Some(result.into_diagnostic()??)),
+            Err(_) => {
+                debug!(
+                    tool = self.id.as_str(),
+                    timeout, "Loading versions exceeded the resolve timeout, cancelling"
+                );
 
-            self.version = Some(version);
+                // Release the plugin as soon as possible for subsequent calls
+                let _ = cancel_handle.cancel();
 
-            return Ok(());
+                Ok(None)
+            }
         }
+    }
 
-        let resolver = self.load_version_resolver(initial_version).await?;
-        let handle_error = || ProtoError::VersionResolveFailed {
-            tool: self.get_name().to_owned(),
-            version: initial_version.to_string(),
+    fn get_remote_versions_cache_path(&self) -> PathBuf {
+        // Don't use the overridden inventory path
+        self.proto
+            .tools_dir
+            .join(self.id.as_str())
+            .join("remote-versions.json")
+    }
+
+    /// Return when the provided version was released, as a UNIX timestamp in milliseconds.
+    /// Release dates are read from the cached versions when the plugin provides them,
+    /// otherwise the release date is unknown.
+    pub fn get_release_date(&self, version: &VersionSpec) -> Option<u128> {
+        let VersionSpec::Version(inner) = version else {
+            return None;
         };
 
-        let mut version = VersionSpec::default();
-        let mut resolved = false;
+        let cache_path = self.get_remote_versions_cache_path();
+
+        if !cache_path.exists() {
+            return None;
+        }
+
+        json::read_file::<LoadVersionsOutput>(&cache_path)
+            .ok()
+            .and_then(|output| output.release_dates.get(inner).copied())
+    }
+
+    /// Create a versions output from the installed versions, with the
+    /// highest installed version as the latest. Used when offline.
+    fn load_installed_versions_output(&self) -> LoadVersionsOutput {
+        let mut output = LoadVersionsOutput::default();
+        let manifests = [Some(&self.manifest), self.shared_manifest.as_ref()];
+
+        for manifest in manifests.into_iter().flatten() {
+            for version in &manifest.installed_versions {
+                if let VersionSpec::Version(version) = version {
+                    output.versions.push(version.to_owned());
+                }
+            }
+        }
+
+        output.versions.sort();
+        output.versions.dedup();
+        output.latest = output.versions.last().cloned();
+        output
+    }
+
+    /// Attempt to resolve a version requirement against installed versions only,
+    /// without loading available versions from the plugin (network).
+    pub fn resolve_installed_version(
+        &self,
+        initial_version: &UnresolvedVersionSpec,
+    ) -> miette::Result<Option<(VersionSpec, Vec<String>)>> {
+        if !matches!(
+            initial_version,
+            UnresolvedVersionSpec::Req(_) | UnresolvedVersionSpec::ReqAny(_)
+        ) {
+            return Ok(None);
+        }
+
+        let mut resolver = VersionResolver::default();
+
+        self.configure_version_resolver(&mut resolver)?;
+
+        Ok(resolver.explain(initial_version))
+    }
+
+    fn configure_version_resolver<'tool>(
+        &'tool self,
+        resolver: &mut VersionResolver<'tool>,
+    ) -> miette::Result<()> {
+        resolver.with_manifest(&self.manifest);
+
+        if let Some(shared_manifest) = &self.shared_manifest {
+            resolver.with_shared_manifest(shared_manifest);
+        }
+
+        let config = self.proto.load_config()?;
+        let tool_config = config.tools.get(&self.id);
+
+        resolver.with_prereleases(
+            self.prereleases
+                || self.detected_prereleases.load(Ordering::Relaxed)
+                || tool_config
+                    .and_then(|tool_config| tool_config.include_prereleases)
+                    .unwrap_or(config.settings.include_prereleases),
+        );
+
+        if let Some(tool_config) = tool_config {
+            resolver.with_config(tool_config);
+        }
+
+        Ok(())
+    }
+
+    /// Given an initial version, resolve it to a fully qualifed and semantic version
+    /// (or alias) according to the tool's ecosystem.
+    pub async fn resolve_version(
+        &mut self,
+        initial_version: &UnresolvedVersionSpec,
+        short_circuit: bool,
+    ) -> miette::Result<()> {
+        if self.version.is_some() {
+            return Ok(());
+        }
+
+        debug!(
+            tool = self.id.as_str(),
+            initial_version = initial_version.to_string(),
+            "Resolving a semantic version or alias",
+        );
+
+        self.report(ProtoEvent::ResolvingVersion {
+            tool: self.id.clone(),
+            candidate: initial_version.to_owned(),
+        });
+
+        // When pinned with a list of candidates, the remaining candidates
+        // are tried in order if the initial version can't be resolved
+        let fallbacks = self
+            .proto
+            .load_config()?
+            .fallback_versions
+            .get(&self.id)
+            .filter(|candidates| candidates.first() == Some(initial_version))
+            .map(|candidates| candidates[1..].to_vec())
+            .unwrap_or_default();
+        let short_circuit = short_circuit && fallbacks.is_empty();
+
+        // If we have a fully qualified semantic version,
+        // exit early and assume the version is legitimate!
+        // Also canary is a special type that we can simply just use.
+        if short_circuit && matches!(initial_version, UnresolvedVersionSpec::Version(_))
+            || matches!(initial_version, UnresolvedVersionSpec::Canary)
+        {
+            let version = initial_version.to_resolved_spec();
+
+            debug!(
+                tool = self.id.as_str(),
+                version = version.to_string(),
+                "Resolved to {} (without validation)",
+                version
+            );
+
+            self.explanation = vec![format!(
+                "{initial_version} is fully-qualified, so used without validation"
+            )];
+
+            self.on_resolved_version
+                .emit(ResolvedVersionEvent {
+                    candidate: initial_version.to_owned(),
+                    version: version.clone(),
+                })
+                .await?;
+
+            self.report(ProtoEvent::ResolvedVersion {
+                tool: self.id.clone(),
+                version: version.clone(),
+            });
+
+            self.version = Some(version);
+
+            return Ok(());
+        }
+
+        // Allow plugins to remap the version first, as the remapped
+        // version may not be satisfied by the installed versions
+        let mut plugin_result = None;
 
         if self.plugin.has_func("resolve_version") {
             let result: ResolveVersionOutput = self.plugin.call_func_with(
@@ -477,6 +1144,67 @@ impl Tool {
                 },
             )?;
 
+            plugin_result = Some(result);
+        }
+
+        let remapped = plugin_result
+            .as_ref()
+            .is_some_and(|result| result.candidate.is_some() || result.version.is_some());
+
+        // Ranges (typically from ecosystem manifests, like `engines` in package.json)
+        // are often satisfied by an installed version, so avoid the network
+        if short_circuit && !remapped {
+            if let Some((version, steps)) = self.resolve_installed_version(initial_version)? {
+                debug!(
+                    tool = self.id.as_str(),
+                    version = version.to_string(),
+                    "Resolved to {} (from installed versions)",
+                    version
+                );
+
+                self.explanation = steps;
+                self.explanation
+                    .push(format!("resolved {initial_version} to {version}"));
+
+                self.on_resolved_version
+                    .emit(ResolvedVersionEvent {
+                        candidate: initial_version.to_owned(),
+                        version: version.clone(),
+                    })
+                    .await?;
+
+                self.report(ProtoEvent::ResolvedVersion {
+                    tool: self.id.clone(),
+                    version: version.clone(),
+                });
+
+                self.version = Some(version);
+
+                return Ok(());
+            }
+        }
+
+        let resolver = self.load_version_resolver(initial_version).await?;
+        let handle_error = || ProtoError::VersionResolveFailed {
+            tool: self.get_name().to_owned(),
+            version: initial_version.to_string(),
+        };
+
+        let mut version = VersionSpec::default();
+        let mut resolved = false;
+        let mut explanation = vec![match &resolver.cache_path {
+            Some(cache_path) => format!(
+                "loaded {} versions from cache {}",
+                resolver.versions.len(),
+                cache_path.display()
+            ),
+            None => format!(
+                "loaded {} versions from the plugin (network)",
+                resolver.versions.len()
+            ),
+        }];
+
+        if let Some(result) = plugin_result {
             if let Some(candidate) = result.candidate {
                 debug!(
                     tool = self.id.as_str(),
@@ -484,8 +1212,14 @@ impl Tool {
                     "Received a possible version or alias to use",
                 );
 
+                let (candidate_version, steps) =
+                    resolver.explain(&candidate).ok_or_else(handle_error)?;
+
+                explanation.push(format!("plugin provided candidate {candidate}"));
+                explanation.extend(steps);
+
                 resolved = true;
-                version = resolver.resolve(&candidate).ok_or_else(handle_error)?;
+                version = candidate_version;
             }
 
             if let Some(candidate) = result.version {
@@ -495,15 +1229,47 @@ impl Tool {
                     "Received an explicit version or alias to use",
                 );
 
+                explanation.push(format!("plugin provided explicit version {candidate}"));
+
                 resolved = true;
                 version = candidate;
             }
         }
 
         if !resolved {
-            version = resolver.resolve(initial_version).ok_or_else(handle_error)?;
+            let mut result = resolver.explain(initial_version);
+            let mut previous = initial_version;
+
+            for fallback in &fallbacks {
+                if result.is_some() {
+                    break;
+                }
+
+                debug!(
+                    tool = self.id.as_str(),
+                    fallback = fallback.to_string(),
+                    "Unable to resolve {}, trying the next fallback",
+                    previous
+                );
+
+                explanation.push(format!(
+                    "{previous} could not be resolved, falling back to {fallback}"
+                ));
+
+                result = resolver.explain(fallback);
+                previous = fallback;
+            }
+
+            let (resolved_version, steps) = result.ok_or_else(handle_error)?;
+
+            explanation.extend(steps);
+            version = resolved_version;
         }
 
+        explanation.push(format!("resolved {initial_version} to {version}"));
+
+        self.explanation = explanation;
+
         debug!(
             tool = self.id.as_str(),
             version = version.to_string(),
@@ -518,18 +1284,60 @@ impl Tool {
             })
             .await?;
 
+        self.report(ProtoEvent::ResolvedVersion {
+            tool: self.id.clone(),
+            version: version.clone(),
+        });
+
         self.version = Some(version);
 
         Ok(())
     }
 
+    /// Return the file names that will be checked for a version, as defined by the plugin.
+    pub fn get_version_files(&self) -> miette::Result<Vec<String>> {
+        if !self.plugin.has_func("detect_version_files") {
+            return Ok(vec![]);
+        }
+
+        let result: DetectVersionOutput = self.plugin.cache_func("detect_version_files")?;
+
+        Ok(get_detect_version_files(&result)
+            .into_iter()
+            .map(|file| file.file)
+            .collect())
+    }
+
     /// Attempt to detect an applicable version from the provided directory.
     pub async fn detect_version_from(
         &self,
         current_dir: &Path,
     ) -> miette::Result<Option<(UnresolvedVersionSpec, PathBuf)>> {
+        Ok(self
+            .detect_versions_from_dir(current_dir, false)
+            .await?
+            .into_iter()
+            .next())
+    }
+
+    /// Like [`Tool::detect_version_from`], but return the version of every
+    /// file in the directory, in order of priority.
+    pub async fn detect_all_versions_from(
+        &self,
+        current_dir: &Path,
+    ) -> miette::Result<Vec<(UnresolvedVersionSpec, PathBuf)>> {
+        self.detect_versions_from_dir(current_dir, true).await
+    }
+
+    async fn detect_versions_from_dir(
+        &self,
+        current_dir: &Path,
+        all: bool,
+    ) -> miette::Result<Vec<(UnresolvedVersionSpec, PathBuf)>> {
+        let mut versions = vec![];
+
         if !self.plugin.has_func("detect_version_files") {
-            return Ok(None);
+            return Ok(versions);
         }
 
         let has_parser = self.plugin.has_func("parse_version_file");
@@ -538,7 +1346,7 @@ impl Tool {
         if !result.ignore.is_empty() {
             if let Some(dir) = current_dir.to_str() {
                 if result.ignore.iter().any(|ignore| dir.contains(ignore)) {
-                    return Ok(None);
+                    return Ok(versions);
                 }
             }
         }
@@ -549,20 +1357,40 @@ impl Tool {
             "Attempting to detect a version from directory"
         );
 
-        for file in result.files {
-            let file_path = current_dir.join(&file);
+        for detect_file in get_detect_version_files(&result) {
+            let file = &detect_file.file;
+            let file_path = current_dir.join(file);
 
             if !file_path.exists() {
                 continue;
             }
 
+            if detect_file.ignore_local && current_dir != self.proto.home {
+                trace!(
+                    tool = self.id.as_str(),
+                    file = ?file_path,
+                    "File is only detected from the home directory, skipping"
+                );
+
+                continue;
+            }
+
             let content = fs::read_file(&file_path)?.trim().to_owned();
 
             if content.is_empty() {
                 continue;
             }
 
-            let version = if has_parser {
+            let version = if let Some(format) = &detect_file.format {
+                let Some(content) = extract_version_from_content(&content, format)? else {
+                    continue;
+                };
+
+                UnresolvedVersionSpec::parse(&content).map_err(|error| ProtoError::Semver {
+                    version: content,
+                    error,
+                })?
+            } else if has_parser {
                 let result: ParseVersionFileOutput = self.plugin.call_func_with(
                     "parse_version_file",
                     ParseVersionFileInput {
@@ -583,6 +1411,10 @@ impl Tool {
                 })?
             };
 
+            if detect_file.prerelease_allowed {
+                self.detected_prereleases.store(true, Ordering::Relaxed);
+            }
+
             debug!(
                 tool = self.id.as_str(),
                 file = ?file_path,
@@ -590,10 +1422,103 @@ impl Tool {
                 "Detected a version"
             );
 
-            return Ok(Some((version, file_path)));
+            versions.push((version, file_path));
+
+            if !all {
+                break;
+            }
         }
 
-        Ok(None)
+        Ok(versions)
+    }
+
+    /// Update existing ecosystem version files (`.nvmrc`, etc) in the provided directory
+    /// with the pinned version, and return the paths of all files that were modified.
+    /// Files are never created, only updated.
+    pub async fn sync_version_files(
+        &self,
+        current_dir: &Path,
+        version: &UnresolvedVersionSpec,
+    ) -> miette::Result<Vec<PathBuf>> {
+        let mut synced = vec![];
+
+        if !self.plugin.has_func("detect_version_files") {
+            return Ok(synced);
+        }
+
+        let has_parser = self.plugin.has_func("parse_version_file");
+        let has_writer = self.plugin.has_func("write_version_file");
+
+        let result: DetectVersionOutput = self.plugin.cache_func("detect_version_files")?;
+
+        for file in result.files {
+            let file_path = current_dir.join(&file);
+
+            if !file_path.exists() {
+                continue;
+            }
+
+            let content = fs::read_file(&file_path)?;
+
+            let new_content = if has_writer {
+                let result: WriteVersionFileOutput = self.plugin.call_func_with(
+                    "write_version_file",
+                    WriteVersionFileInput {
+                        content: content.clone(),
+                        file: file.clone(),
+                        version: version.to_owned(),
+                    },
+                )?;
+
+                match result.content {
+                    Some(new_content) => new_content,
+                    None => continue,
+                }
+            } else {
+                // When files are parsed by the plugin, we don't know their format,
+                // so only overwrite files that contain nothing but the version
+                if has_parser {
+                    let result: ParseVersionFileOutput = self.plugin.call_func_with(
+                        "parse_version_file",
+                        ParseVersionFileInput {
+                            content: content.clone(),
+                            file: file.clone(),
+                        },
+                    )?;
+
+                    if result.version.is_none()
+                        || UnresolvedVersionSpec::parse(content.trim()).ok() != result.version
+                    {
+                        debug!(
+                            tool = self.id.as_str(),
+                            file = ?file_path,
+                            "Version file contains more than a version and the plugin does not support writing it, skipping sync"
+                        );
+
+                        continue;
+                    }
+                }
+
+                format!("{version}\n")
+            };
+
+            if new_content == content {
+                continue;
+            }
+
+            fs::write_file(&file_path, new_content)?;
+
+            debug!(
+                tool = self.id.as_str(),
+                file = ?file_path,
+                version = version.to_string(),
+                "Synced version file"
+            );
+
+            synced.push(file_path);
+        }
+
+        Ok(synced)
     }
 }
 
@@ -601,7 +1526,7 @@ impl Tool {
 
 impl Tool {
     /// Return true if the tool has been installed. This is less accurate than `is_setup`,
-    /// as it only checks for the existence of the inventory directory.
+    /// as it only checks for the existence of the inventory directory and install receipt.
     pub fn is_installed(&self) -> bool {
         let dir = self.get_tool_dir();
 
@@ -609,19 +1534,55 @@ impl Tool {
             .as_ref()
             // Canary can be overwritten so treat as not-installed
             .is_some_and(|v| {
-                !v.is_latest() && !v.is_canary() && self.manifest.installed_versions.contains(v)
+                !v.is_latest()
+                    && !v.is_canary()
+                    && (self.manifest.installed_versions.contains(v) || self.is_shared())
             })
             && dir.exists()
-            && !fs::is_dir_locked(dir)
+            && self.has_install_receipt()
+            && !StoreLock::is_locked(self.get_install_lock_path())
+    }
+
+    // The receipt is only written once every install step has succeeded,
+    // while unmanaged versions were not installed by proto, so have none.
+    // Versions installed before receipts existed are only tracked in the
+    // manifest, so treat them as legacy installs, unless an install is
+    // still in progress (or was interrupted) for that version.
+    fn has_install_receipt(&self) -> bool {
+        if self.is_unmanaged() {
+            return true;
+        }
+
+        match self
+            .manifest
+            .load_receipt(self.get_tool_dir())
+            .ok()
+            .flatten()
+        {
+            Some(receipt) => self.version.as_ref() == Some(&receipt.version),
+            None => {
+                self.is_installed_in_manifest()
+                    && self
+                        .version
+                        .as_ref()
+                        .is_some_and(|v| !self.manifest.install_states.contains_key(v))
+            }
+        }
+    }
+
+    fn is_installed_in_manifest(&self) -> bool {
+        self.version.as_ref().is_some_and(|v| {
+            !v.is_latest() && !v.is_canary() && self.manifest.installed_versions.contains(v)
+        })
     }
 
     /// Verify the downloaded file using the checksum strategy for the tool.
-    /// Common strategies are SHA256 and MD5.
+    /// Supports SHA256, SHA512, and BLAKE3 digests, and minisign signatures.
     pub async fn verify_checksum(
         &self,
         checksum_file: &Path,
         download_file: &Path,
-        checksum_public_key: Option<&str>,
+        options: &ChecksumOptions,
     ) -> miette::Result<bool> {
         debug!(
             tool = self.id.as_str(),
@@ -643,9 +1604,18 @@ impl Tool {
 
             result.verified
 
-        // Otherwise attempt to verify it ourselves
+        // Otherwise attempt to verify it ourselves. Hashing is CPU bound,
+        // so run it on a blocking thread to not stall other downloads.
         } else {
-            verify_checksum(download_file, checksum_file, checksum_public_key)?
+            let download_file = download_file.to_path_buf();
+            let checksum_file = checksum_file.to_path_buf();
+            let options = options.clone();
+
+            tokio::task::spawn_blocking(move || {
+                verify_checksum(&download_file, &checksum_file, &options)
+            })
+            .await
+            .into_diagnostic()??
         };
 
         if verified {
@@ -664,7 +1634,105 @@ impl Tool {
         .into())
     }
 
-    pub async fn build_from_source(&self, install_dir: &Path) -> miette::Result<()> {
+    /// Record a failed verification so that possible tampering is visible later.
+    /// Failing to record should never mask the original verification error.
+    fn record_integrity_failure(&self, mut event: IntegrityEvent) {
+        event.tool = self.id.clone();
+        event.version = self.get_resolved_version().to_string();
+        event.plugin = self.locator.as_ref().map(|locator| locator.to_string());
+
+        warn!(
+            tool = self.id.as_str(),
+            download_url = &event.download_url,
+            checksum_url = event.checksum_url.as_deref(),
+            expected_digest = event.expected_digest.as_deref(),
+            actual_digest = event.actual_digest.as_deref(),
+            plugin = event.plugin.as_deref(),
+            "Checksum verification failed, the download may have been tampered with"
+        );
+
+        if let Err(error) = IntegrityLog::record(&self.proto, event) {
+            debug!(
+                tool = self.id.as_str(),
+                "Failed to record integrity event: {}",
+                error.to_string()
+            );
+        }
+    }
+
+    /// Run a command for building from source, and error if it fails.
+    fn run_build_command(&self, mut command: Command) -> miette::Result<()> {
+        debug!(tool = self.id.as_str(), command = ?command, "Running build command");
+
+        let status = command
+            .spawn()
+            .into_diagnostic()?
+            .wait()
+            .into_diagnostic()?;
+
+        if !status.success() {
+            let program = command.get_program().to_string_lossy().to_string();
+            let args = command
+                .get_args()
+                .map(|arg| arg.to_string_lossy().to_string())
+                .collect::<Vec<_>>();
+
+            return Err(ProtoError::BuildCommandFailed {
+                tool: self.get_name().to_owned(),
+                command: format!("{program} {}", args.join(" ")).trim().to_owned(),
+                status: format!("exit code {}", status.code().unwrap_or(-1)),
+            }
+            .into());
+        }
+
+        Ok(())
+    }
+
+    /// Install the system dependencies required for building from source,
+    /// with the system's package manager. Installing is a no-op for
+    /// packages that already exist.
+    fn install_build_dependencies(&self, deps: &[SystemDependency]) -> miette::Result<()> {
+        if deps.is_empty() {
+            return Ok(());
+        }
+
+        let system = System::new().into_diagnostic()?;
+        let dep_configs = system.resolve_dependencies(deps.to_vec());
+
+        if dep_configs.is_empty() {
+            return Ok(());
+        }
+
+        debug!(
+            tool = self.id.as_str(),
+            deps = ?dep_configs,
+            "Installing system dependencies for build"
+        );
+
+        if let Some(args) = system.get_update_index_command(false) {
+            self.run_build_command(create_process_command(&args[0], &args[1..]))?;
+        }
+
+        for dep_config in dep_configs {
+            let mut args = system
+                .get_install_package_command(&dep_config, false)
+                .into_diagnostic()?;
+
+            if dep_config.sudo {
+                args.insert(0, "sudo".into());
+            }
+
+            self.run_build_command(create_process_command(&args[0], &args[1..]))?;
+        }
+
+        Ok(())
+    }
+
+    /// Build the tool from source, by acquiring the sources into a temporary
+    /// build directory, installing system dependencies, and executing the
+    /// plugin's build instructions within that directory. The build directory
+    /// is removed once the build has completed.
+    pub async fn build_from_source(&self, install_dir: &Path) -> miette::Result<LockedTool> {
         debug!(
             tool = self.id.as_str(),
             "Installing tool by building from source"
@@ -677,20 +1745,33 @@ impl Tool {
             .into());
         }
 
+        self.report(ProtoEvent::Building {
+            tool: self.id.clone(),
+            version: self.get_resolved_version(),
+        });
+
         let temp_dir = self.get_temp_dir();
+        let build_dir = temp_dir.join("build");
+        let mut locked = LockedTool::new(self.get_resolved_version());
 
         let options: BuildInstructionsOutput = self.plugin.cache_func_with(
             "build_instructions",
             BuildInstructionsInput {
                 context: self.create_context(),
+                install_dir: self.to_virtual_path(install_dir),
             },
         )?;
 
+        if is_offline() && !matches!(options.source, SourceLocation::None) {
+            return Err(ProtoError::InternetConnectionRequired.into());
+        }
+
+        // Start from a clean build directory
+        fs::remove_dir_all(&build_dir)?;
+        fs::create_dir_all(&build_dir)?;
+
         match &options.source {
-            // Should this do anything?
-            SourceLocation::None => {
-                return Ok(());
-            }
+            SourceLocation::None => {}
 
             // Download from archive
             SourceLocation::Archive { url: archive_url } => {
@@ -700,18 +1781,20 @@ impl Tool {
                     tool = self.id.as_str(),
                     archive_url,
                     download_file = ?download_file,
-                    install_dir = ?install_dir,
+                    build_dir = ?build_dir,
                     "Attempting to download and unpack sources",
                 );
 
                 download_from_url_to_file(
-                    archive_url,
+                    &self.proto.rewrite_url(archive_url)?,
                     &download_file,
-                    self.proto.get_plugin_loader()?.get_client()?,
+                    self.proto.get_http_client()?,
                 )
                 .await?;
 
-                Archiver::new(install_dir, &download_file).unpack_from_ext()?;
+                Archiver::new(&build_dir, &download_file).unpack_from_ext()?;
+
+                locked.download_url = Some(archive_url.to_owned());
             }
 
             // Clone from Git repository
@@ -724,14 +1807,14 @@ impl Tool {
                     tool = self.id.as_str(),
                     repo_url,
                     ref_name,
-                    install_dir = ?install_dir,
+                    build_dir = ?build_dir,
                     "Attempting to clone a Git repository",
                 );
 
                 let run_git = |args: &[&str]| -> miette::Result<()> {
                     let status = Command::new("git")
                         .args(args)
-                        .current_dir(install_dir)
+                        .current_dir(&build_dir)
                         .spawn()
                         .into_diagnostic()?
                         .wait()
@@ -749,37 +1832,107 @@ impl Tool {
                     Ok(())
                 };
 
-                // TODO, pull if already cloned
+                let mut clone_args = vec!["clone"];
 
-                fs::create_dir_all(install_dir)?;
+                if *submodules {
+                    clone_args.push("--recurse-submodules");
+                }
 
-                run_git(&[
-                    "clone",
-                    if *submodules {
-                        "--recurse-submodules"
-                    } else {
-                        ""
-                    },
-                    repo_url,
-                    ".",
-                ])?;
+                clone_args.extend([repo_url.as_str(), "."]);
 
+                run_git(&clone_args)?;
                 run_git(&["checkout", ref_name])?;
+
+                locked.download_url = Some(repo_url.to_owned());
             }
         };
 
-        Ok(())
+        self.install_build_dependencies(&options.system_dependencies)?;
+
+        for instruction in &options.instructions {
+            match instruction {
+                BuildInstruction::Command { bin, args, env } => {
+                    let mut command = create_process_command(bin, args);
+
+                    command
+                        .current_dir(&build_dir)
+                        .envs(env)
+                        .env("PROTO_BUILD_DIR", &build_dir)
+                        .env("PROTO_INSTALL_DIR", install_dir);
+
+                    self.run_build_command(command)?;
+                }
+            };
+        }
+
+        // When the instructions did not install anything,
+        // the build directory is the installation
+        if fs::read_dir(install_dir)?
+            .iter()
+            .all(|entry| entry.file_name() == ".lock")
+        {
+            debug!(
+                tool = self.id.as_str(),
+                build_dir = ?build_dir,
+                install_dir = ?install_dir,
+                "Moving build directory to install directory"
+            );
+
+            for entry in fs::read_dir(&build_dir)? {
+                fs::rename(entry.path(), install_dir.join(entry.file_name()))?;
+            }
+        }
+
+        fs::remove_dir_all(&build_dir)?;
+
+        Ok(locked)
+    }
+
+    /// Return the size in bytes of the pre-built download for the resolved version,
+    /// either as hinted by the plugin, or from a HEAD request. Downloads that have
+    /// already been cached are zero. Returns nothing if the size is unknown.
+    pub async fn get_download_size(&self) -> miette::Result<Option<u64>> {
+        if self.asdf_plugin.is_some() || self.plugin.has_func("native_install") || is_offline() {
+            return Ok(None);
+        }
+
+        let options: DownloadPrebuiltOutput = self.plugin.cache_func_with(
+            "download_prebuilt",
+            DownloadPrebuiltInput {
+                context: self.create_context(),
+                install_dir: self.to_virtual_path(&self.get_tool_dir()),
+            },
+        )?;
+
+        let request_url = self.proto.rewrite_url(&options.download_url)?;
+
+        if DownloadCache::load(self.proto.get_download_cache_dir())?
+            .get_blob_path(&request_url)
+            .is_some()
+        {
+            return Ok(Some(0));
+        }
+
+        if options.download_size.is_some() {
+            return Ok(options.download_size);
+        }
+
+        fetch_download_size(self.proto.get_http_client()?, &request_url).await
     }
 
     /// Download the tool (as an archive) from its distribution registry
     /// into the `~/.proto/tools/<version>` folder, and optionally verify checksums.
-    pub async fn install_from_prebuilt(&self, install_dir: &Path) -> miette::Result<()> {
+    /// If the tool has been locked, the download must match the locked entry.
+    pub async fn install_from_prebuilt(
+        &mut self,
+        install_dir: &Path,
+    ) -> miette::Result<LockedTool> {
         debug!(
             tool = self.id.as_str(),
             "Installing tool from a pre-built archive"
         );
 
-        let client = self.proto.get_plugin_loader()?.get_client()?;
+        let client = self.proto.get_http_client()?;
         let options: DownloadPrebuiltOutput = self.plugin.cache_func_with(
             "download_prebuilt",
             DownloadPrebuiltInput {
@@ -790,46 +1943,223 @@ impl Tool {
 
         let temp_dir = self.get_temp_dir();
 
-        // Download the prebuilt
-        let download_url = options.download_url;
-        let download_file = match options.download_name {
-            Some(name) => temp_dir.join(name),
-            None => temp_dir.join(extract_filename_from_url(&download_url)?),
-        };
+        // Download the prebuilt, preferring the locked URL
+        let download_url = self
+            .locked
+            .as_ref()
+            .and_then(|locked| locked.download_url.clone())
+            .unwrap_or(options.download_url);
+        let download_file = match options.download_name {
+            Some(name) => temp_dir.join(name),
+            None => temp_dir.join(extract_filename_from_url(&download_url)?),
+        };
+
+        let download_prebuilt = async {
+            if download_file.exists() {
+                debug!(
+                    tool = self.id.as_str(),
+                    "Tool already downloaded, continuing"
+                );
+            } else {
+                debug!(tool = self.id.as_str(), "Tool not downloaded, downloading");
+
+                let request_url = self.proto.rewrite_url(&download_url)?;
+
+                self.report(ProtoEvent::Downloading {
+                    tool: self.id.clone(),
+                    url: request_url.clone(),
+                });
+
+                if is_cache_enabled() {
+                    download_with_cache(
+                        client,
+                        &self.proto.get_download_cache_dir(),
+                        &request_url,
+                        &download_file,
+                        |downloaded, total| {
+                            self.report(ProtoEvent::DownloadProgress {
+                                tool: self.id.clone(),
+                                downloaded,
+                                total,
+                            });
+                        },
+                    )
+                    .await?;
+
+                    self.proto
+                        .apply_cache_permissions(&self.proto.get_download_cache_dir())?;
+                } else {
+                    download_from_url_to_file(&request_url, &download_file, client).await?;
+                }
+            }
+
+            Ok::<_, miette::Report>(())
+        };
+
+        // Download the checksum alongside the prebuilt
+        let checksum_file = match &options.checksum_url {
+            Some(checksum_url) => Some(temp_dir.join(match &options.checksum_name {
+                Some(name) => name.to_owned(),
+                None => extract_filename_from_url(checksum_url)?,
+            })),
+            None => None,
+        };
+
+        // Download the signature alongside the prebuilt
+        let signature_file = match &options.signature_url {
+            Some(signature_url) => Some(temp_dir.join(extract_filename_from_url(signature_url)?)),
+            None => None,
+        };
+
+        // When offline, we can only install from previous downloads
+        if is_offline() {
+            let missing = [
+                (Some(&download_url), Some(&download_file)),
+                (options.checksum_url.as_ref(), checksum_file.as_ref()),
+                (options.signature_url.as_ref(), signature_file.as_ref()),
+            ];
+
+            for (url, file) in missing {
+                if let (Some(url), Some(file)) = (url, file) {
+                    if !file.exists() {
+                        return Err(ProtoError::OfflineDownloadMissing {
+                            tool: self.get_name().to_owned(),
+                            version: self.get_resolved_version().to_string(),
+                            url: url.to_owned(),
+                        }
+                        .into());
+                    }
+                }
+            }
+        }
+
+        let download_checksum = async {
+            if let (Some(checksum_url), Some(checksum_file)) =
+                (&options.checksum_url, &checksum_file)
+            {
+                if !checksum_file.exists() {
+                    debug!(
+                        tool = self.id.as_str(),
+                        "Checksum does not exist, downloading"
+                    );
+
+                    download_from_url_to_file(
+                        &self.proto.rewrite_url(checksum_url)?,
+                        checksum_file,
+                        client,
+                    )
+                    .await?;
+                }
+            }
+
+            Ok::<_, miette::Report>(())
+        };
+
+        let download_signature = async {
+            if let (Some(signature_url), Some(signature_file)) =
+                (&options.signature_url, &signature_file)
+            {
+                if !signature_file.exists() {
+                    debug!(
+                        tool = self.id.as_str(),
+                        "Signature does not exist, downloading"
+                    );
+
+                    download_from_url_to_file(
+                        &self.proto.rewrite_url(signature_url)?,
+                        signature_file,
+                        client,
+                    )
+                    .await?;
+                }
+            }
+
+            Ok::<_, miette::Report>(())
+        };
+
+        tokio::try_join!(download_prebuilt, download_checksum, download_signature)?;
+
+        let checksum_options = ChecksumOptions {
+            algorithm: options.checksum_algorithm.unwrap_or_default(),
+            format: options.checksum_format.unwrap_or_default(),
+            public_key: options
+                .checksum_public_key
+                .clone()
+                .or_else(|| self.metadata.checksum_public_key.clone()),
+        };
+        let mut integrity = ToolIntegrity {
+            algorithm: get_checksum_algorithm_name(checksum_options.algorithm).to_owned(),
+            digest: hash_file_contents_with(&download_file, checksum_options.algorithm)?,
+            ..Default::default()
+        };
+
+        if checksum_file.is_some() || signature_file.is_some() {
+            self.report(ProtoEvent::Verifying {
+                tool: self.id.clone(),
+                version: self.get_resolved_version(),
+            });
+        }
+
+        // Verify the checksum if applicable
+        if let Some(checksum_file) = &checksum_file {
+            if let Err(error) = self
+                .verify_checksum(checksum_file, &download_file, &checksum_options)
+                .await
+            {
+                if matches!(
+                    error.downcast_ref::<ProtoError>(),
+                    Some(ProtoError::InvalidChecksum { .. })
+                ) {
+                    self.record_integrity_failure(IntegrityEvent {
+                        actual_digest: Some(integrity.digest.clone()),
+                        checksum_url: options.checksum_url.clone(),
+                        download_url: download_url.clone(),
+                        expected_digest: extract_expected_checksum(
+                            &download_file,
+                            checksum_file,
+                            &checksum_options,
+                        ),
+                        ..Default::default()
+                    });
+                }
+
+                return Err(error);
+            }
+
+            integrity.checksum_verified = true;
+            integrity.signature_verified = checksum_file
+                .extension()
+                .is_some_and(|ext| ext == "minisig" || ext == "minisign");
+        }
 
-        if download_file.exists() {
+        // Verify the detached signature if applicable
+        if let Some(signature_file) = &signature_file {
             debug!(
                 tool = self.id.as_str(),
-                "Tool already downloaded, continuing"
+                signature_file = ?signature_file,
+                "Verifying signature of downloaded file",
             );
-        } else {
-            debug!(tool = self.id.as_str(), "Tool not downloaded, downloading");
 
-            download_from_url_to_file(&download_url, &download_file, client).await?;
-        }
+            verify_signature(
+                &download_file,
+                signature_file,
+                checksum_options.public_key.as_deref(),
+            )?;
 
-        // Verify the checksum if applicable
-        if let Some(checksum_url) = options.checksum_url {
-            let checksum_file = temp_dir.join(match options.checksum_name {
-                Some(name) => name,
-                None => extract_filename_from_url(&checksum_url)?,
-            });
+            integrity.signature_verified = true;
+        }
 
-            if !checksum_file.exists() {
-                debug!(
-                    tool = self.id.as_str(),
-                    "Checksum does not exist, downloading"
-                );
+        integrity.verified_at = now();
 
-                download_from_url_to_file(&checksum_url, &checksum_file, client).await?;
-            }
+        // Verify against the lockfile before unpacking
+        let locked = LockedTool {
+            checksum: Some(hash_file_contents(&download_file)?),
+            download_url: Some(download_url.clone()),
+            ..LockedTool::new(self.get_resolved_version())
+        };
 
-            self.verify_checksum(
-                &checksum_file,
-                &download_file,
-                options.checksum_public_key.as_deref(),
-            )
-            .await?;
+        if let Some(expected) = &self.locked {
+            self.verify_locked(expected, &locked)?;
         }
 
         // Attempt to unpack the archive
@@ -840,6 +2170,11 @@ impl Tool {
             "Attempting to unpack archive",
         );
 
+        self.report(ProtoEvent::Unpacking {
+            tool: self.id.clone(),
+            version: self.get_resolved_version(),
+        });
+
         if self.plugin.has_func("unpack_archive") {
             self.plugin.call_func_without_output(
                 "unpack_archive",
@@ -852,13 +2187,35 @@ impl Tool {
 
             // Is an archive, unpack it
         } else if is_archive_file(&download_file) {
-            let mut archiver = Archiver::new(install_dir, &download_file);
+            if self.proto.load_config()?.settings.safe_unpack {
+                let result = unpack_safely(
+                    &download_file,
+                    install_dir,
+                    &self.proto.temp_dir.join("unpack").join(format!(
+                        "{}-{}",
+                        self.id,
+                        self.get_resolved_version()
+                    )),
+                    options.archive_prefix.as_deref(),
+                )?;
 
-            if let Some(prefix) = &options.archive_prefix {
-                archiver.set_prefix(prefix);
-            }
+                if result.retries > 0 {
+                    warn!(
+                        tool = self.id.as_str(),
+                        "Unpacked files were locked by another process {} times, which is usually caused by antivirus scanning. {}",
+                        result.retries,
+                        get_antivirus_exclusion_guidance(&self.proto)
+                    );
+                }
+            } else {
+                let mut archiver = Archiver::new(install_dir, &download_file);
+
+                if let Some(prefix) = &options.archive_prefix {
+                    archiver.set_prefix(prefix);
+                }
 
-            archiver.unpack_from_ext()?;
+                archiver.unpack_from_ext()?;
+            }
 
             // Not an archive, assume a binary and copy
         } else {
@@ -868,12 +2225,67 @@ impl Tool {
             fs::update_perms(install_path, None)?;
         }
 
+        self.integrity = Some(integrity);
+
+        Ok(locked)
+    }
+
+    fn verify_locked(&self, expected: &LockedTool, actual: &LockedTool) -> miette::Result<()> {
+        let fields = [
+            ("download URL", &expected.download_url, &actual.download_url),
+            ("checksum", &expected.checksum, &actual.checksum),
+        ];
+
+        for (field, expected, actual) in fields {
+            if let (Some(expected), Some(actual)) = (expected, actual) {
+                if expected != actual {
+                    return Err(ProtoError::LockfileMismatch {
+                        tool: self.get_name().to_owned(),
+                        field: field.to_owned(),
+                        expected: expected.to_owned(),
+                        actual: actual.to_owned(),
+                    }
+                    .into());
+                }
+            }
+        }
+
         Ok(())
     }
 
     /// Install a tool into proto, either by downloading and unpacking
     /// a pre-built archive, or by using a native installation method.
-    pub async fn install(&mut self, _build: bool) -> miette::Result<bool> {
+    // Plugins can write to the install directory, so remove the install
+    // when it exceeds the configured quota
+    fn enforce_plugin_quota(&self, install_dir: &Path) -> miette::Result<()> {
+        let settings = &self.proto.load_config()?.settings;
+
+        let Some(quota) = settings.get_plugin_quota()? else {
+            return Ok(());
+        };
+
+        let size = get_dir_size(install_dir);
+
+        if size <= quota {
+            return Ok(());
+        }
+
+        for entry in fs::read_dir(install_dir)? {
+            if entry.file_name() != ".lock" {
+                fs::remove(entry.path())?;
+            }
+        }
+
+        Err(ProtoError::PluginQuotaExceeded {
+            tool: self.get_name().to_owned(),
+            path: install_dir.to_path_buf(),
+            size,
+            quota: settings.plugin_quota.clone().unwrap_or_default(),
+        }
+        .into())
+    }
+
+    pub async fn install(&mut self, build: bool) -> miette::Result<bool> {
         if self.is_installed() {
             debug!(
                 tool = self.id.as_str(),
@@ -883,40 +2295,181 @@ impl Tool {
             return Ok(false);
         }
 
-        if is_offline() {
-            return Err(ProtoError::InternetConnectionRequired.into());
-        }
-
         let install_dir = self.get_tool_dir();
-        let mut installed = false;
+        let staging_dir = if build { None } else { self.get_staging_dir() };
 
-        // Lock the install directory. If the inventory has been overridden,
-        // lock the internal proto tool directory instead.
-        let install_lock = fs::lock_directory(if self.metadata.inventory.override_dir.is_some() {
-            self.proto
-                .tools_dir
-                .join(self.id.as_str())
-                .join(self.get_resolved_version().to_string())
-        } else {
-            install_dir.clone()
+        // Lock the version, so that other processes installing the
+        // same version wait for this one
+        let install_lock = StoreLock::acquire_with(self.get_install_lock_path(), |owner| {
+            self.report(ProtoEvent::WaitingForLock {
+                tool: self.id.clone(),
+                version: self.get_resolved_version(),
+                owner,
+            });
         })?;
 
+        // Another process may have installed the tool while we were
+        // waiting for the lock, so reload the manifest and check again
+        self.manifest = ToolManifest::load(&self.manifest.path)?;
+
+        if self.is_installed_in_manifest() {
+            debug!(
+                tool = self.id.as_str(),
+                "Tool was installed by another process, continuing"
+            );
+
+            install_lock.unlock()?;
+
+            return Ok(false);
+        }
+
+        // A previous install may have been interrupted, so start clean
+        if let Some(state) = self
+            .manifest
+            .install_states
+            .get(&self.get_resolved_version())
+        {
+            debug!(
+                tool = self.id.as_str(),
+                state = ?state,
+                "Repairing an interrupted install"
+            );
+        }
+
+        self.remove_incomplete_install(&install_dir, staging_dir.as_deref())?;
+
         self.on_installing
             .emit(InstallingEvent {
                 version: self.get_resolved_version(),
             })
             .await?;
 
+        self.report(ProtoEvent::Installing {
+            tool: self.id.clone(),
+            version: self.get_resolved_version(),
+        });
+
+        // Clean up after a failure, so that a partial install doesn't appear installed
+        if let Err(error) = self
+            .install_with_strategy(build, &install_dir, staging_dir.as_deref())
+            .await
+        {
+            warn!(
+                tool = self.id.as_str(),
+                "Failed to install, removing incomplete install"
+            );
+
+            if let Err(cleanup_error) = self
+                .remove_incomplete_install(&install_dir, staging_dir.as_deref())
+                .and_then(|_| self.set_install_state(None))
+            {
+                warn!(
+                    tool = self.id.as_str(),
+                    "Failed to remove incomplete install: {cleanup_error}"
+                );
+            }
+
+            return Err(error);
+        }
+
+        self.set_install_state(None)?;
+
+        // Keep the lock until the manifest has been updated
+        self.on_installed
+            .emit(InstalledEvent {
+                version: self.get_resolved_version(),
+            })
+            .await?;
+
+        self.report(ProtoEvent::Installed {
+            tool: self.id.clone(),
+            version: self.get_resolved_version(),
+        });
+
+        install_lock.unlock()?;
+
+        debug!(
+            tool = self.id.as_str(),
+            install_dir = ?install_dir,
+            "Successfully installed tool",
+        );
+
+        Ok(true)
+    }
+
+    async fn install_with_strategy(
+        &mut self,
+        build: bool,
+        install_dir: &Path,
+        staging_dir: Option<&Path>,
+    ) -> miette::Result<()> {
+        let mut installed = false;
+
+        // Pre-built archives are unpacked into a staging directory, and only
+        // moved into the inventory once validated, so that an interrupted
+        // unpack never leaves a partial version in the inventory
+        if let Some(staging_dir) = staging_dir {
+            if self.asdf_plugin.is_none() && !self.plugin.has_func("native_install") {
+                self.set_install_state(Some(ToolInstallState::Staging))?;
+
+                fs::create_dir_all(staging_dir)?;
+
+                self.locked = Some(self.install_from_prebuilt(staging_dir).await?);
+                self.enforce_plugin_quota(staging_dir)?;
+
+                self.set_install_state(Some(ToolInstallState::Validating))?;
+                self.validate_staged_install(staging_dir, install_dir)?;
+
+                self.set_install_state(Some(ToolInstallState::Committing))?;
+                self.commit_staged_install(staging_dir, install_dir)?;
+
+                return Ok(());
+            }
+        }
+
+        self.set_install_state(Some(ToolInstallState::Installing))?;
+
+        fs::create_dir_all(install_dir)?;
+
+        // asdf plugins install with their own scripts
+        if let Some(asdf) = &self.asdf_plugin {
+            if is_offline() {
+                return Err(ProtoError::InternetConnectionRequired.into());
+            }
+
+            debug!(tool = self.id.as_str(), "Installing tool with asdf plugin");
+
+            let version = self.get_resolved_version();
+
+            asdf.install(
+                &version.to_string(),
+                install_dir,
+                &self
+                    .proto
+                    .temp_dir
+                    .join("asdf")
+                    .join(self.id.as_str())
+                    .join(version.to_string()),
+            )?;
+
+            self.locked = Some(LockedTool::new(version));
+            installed = true;
+        }
+
         // If this function is defined, it acts like an escape hatch and
         // takes precedence over all other install strategies
-        if self.plugin.has_func("native_install") {
+        if !installed && self.plugin.has_func("native_install") {
+            if is_offline() {
+                return Err(ProtoError::InternetConnectionRequired.into());
+            }
+
             debug!(tool = self.id.as_str(), "Installing tool natively");
 
             let result: NativeInstallOutput = self.plugin.call_func_with(
                 "native_install",
                 NativeInstallInput {
                     context: self.create_context(),
-                    install_dir: self.to_virtual_path(&install_dir),
+                    install_dir: self.to_virtual_path(install_dir),
                 },
             )?;
 
@@ -934,39 +2487,183 @@ impl Tool {
         }
 
         if !installed {
-            // // Build the tool from source
-            // if build {
-            //     self.build_from_source(&install_dir).await?;
+            self.locked = Some(if build {
+                self.build_from_source(install_dir).await?
+            } else {
+                self.install_from_prebuilt(install_dir).await?
+            });
+        }
+
+        self.enforce_plugin_quota(install_dir)?;
+
+        Ok(())
+    }
+
+    // Record the progress of the install in the manifest, so that it
+    // can be detected and repaired if the process is interrupted
+    fn set_install_state(&mut self, state: Option<ToolInstallState>) -> miette::Result<()> {
+        let version = self.get_resolved_version();
 
-            // // Install from a prebuilt archive
-            // } else {
-            //     self.install_from_prebuilt(&install_dir).await?;
-            // }
+        self.manifest.update(|manifest| {
+            match state {
+                Some(state) => manifest.install_states.insert(version, state),
+                None => manifest.install_states.remove(&version),
+            };
+        })
+    }
 
-            self.install_from_prebuilt(&install_dir).await?;
+    // Remove the install and staging directories of an install that did not complete.
+    // Overridden inventories are external, and are not owned by proto.
+    fn remove_incomplete_install(
+        &self,
+        install_dir: &Path,
+        staging_dir: Option<&Path>,
+    ) -> miette::Result<()> {
+        if self.metadata.inventory.override_dir.is_some() || self.is_shared() {
+            return Ok(());
         }
 
-        install_lock.unlock()?;
+        for dir in [Some(install_dir), staging_dir].into_iter().flatten() {
+            if dir.exists() {
+                debug!(
+                    tool = self.id.as_str(),
+                    dir = ?dir,
+                    "Removing leftovers from an incomplete install"
+                );
 
-        self.on_installed
-            .emit(InstalledEvent {
-                version: self.get_resolved_version(),
-            })
-            .await?;
+                fs::remove_dir_all(dir)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    // Verify that the primary executable exists within the staging directory,
+    // before it's moved into the inventory, where it would appear installed
+    fn validate_staged_install(
+        &self,
+        staging_dir: &Path,
+        install_dir: &Path,
+    ) -> miette::Result<()> {
+        if self.is_sdk_root() {
+            return Ok(());
+        }
+
+        let exe_path = match self.get_exe_location()? {
+            Some(location) => location.path,
+            None => install_dir.join(self.id.as_str()),
+        };
+
+        // Executables outside of the install directory can't be validated
+        let Ok(relative_path) = exe_path.strip_prefix(install_dir) else {
+            return Ok(());
+        };
+
+        let staged_path = staging_dir.join(relative_path);
+
+        if staged_path.exists() {
+            return Ok(());
+        }
+
+        Err(ProtoError::InvalidStagedInstall {
+            tool: self.get_name().to_owned(),
+            path: relative_path.to_path_buf(),
+        }
+        .into())
+    }
 
+    // Move the staging directory into the inventory with a single rename, which is atomic
+    // when on the same file system, and is retried if the directory is locked by a scanner
+    fn commit_staged_install(&self, staging_dir: &Path, install_dir: &Path) -> miette::Result<()> {
         debug!(
             tool = self.id.as_str(),
+            staging_dir = ?staging_dir,
             install_dir = ?install_dir,
-            "Successfully installed tool",
+            "Moving staged install into the inventory"
         );
 
-        Ok(true)
+        if let Some(parent) = install_dir.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let (result, _) =
+            retry_on_file_lock(install_dir, || std::fs::rename(staging_dir, install_dir));
+
+        result.map_err(|error| FsError::Write {
+            path: install_dir.to_path_buf(),
+            error,
+        })?;
+
+        Ok(())
+    }
+
+    /// Repair installs that were interrupted, by removing their partial files
+    /// and recorded state. Each version is locked while being repaired, so that
+    /// an install starting at the same time isn't removed. Returns the versions
+    /// that were repaired.
+    pub fn repair_interrupted_installs(&mut self) -> miette::Result<Vec<VersionSpec>> {
+        let interrupted = self.manifest.get_interrupted_installs();
+        let previous_version = self.version.clone();
+        let mut repaired = vec![];
+
+        for (version, state) in interrupted {
+            self.version = Some(version.clone());
+
+            let install_lock = StoreLock::acquire(self.get_install_lock_path())?;
+
+            // Another process may have installed or repaired the version
+            // while we were waiting for the lock, so reload and check again
+            self.manifest = ToolManifest::load(&self.manifest.path)?;
+
+            if !self.manifest.install_states.contains_key(&version) {
+                install_lock.unlock()?;
+
+                continue;
+            }
+
+            debug!(
+                tool = self.id.as_str(),
+                state = ?state,
+                "Repairing interrupted install of {}", version
+            );
+
+            let staging_dir = self.get_staging_dir();
+
+            // Never remove a version that was registered before the state was cleared
+            if self.manifest.installed_versions.contains(&version) {
+                if let Some(dir) = staging_dir.filter(|dir| dir.exists()) {
+                    fs::remove_dir_all(dir)?;
+                }
+            } else {
+                self.remove_incomplete_install(&self.get_tool_dir(), staging_dir.as_deref())?;
+            }
+
+            self.manifest.update(|manifest| {
+                manifest.install_states.remove(&version);
+            })?;
+
+            install_lock.unlock()?;
+
+            repaired.push(version);
+        }
+
+        self.version = previous_version;
+
+        Ok(repaired)
     }
 
     /// Uninstall the tool by deleting the current install directory.
     pub async fn uninstall(&self) -> miette::Result<bool> {
         let install_dir = self.get_tool_dir();
 
+        if self.is_shared() {
+            return Err(ProtoError::SharedStoreReadonly {
+                tool: self.get_name().to_owned(),
+                path: install_dir,
+            }
+            .into());
+        }
+
         if !install_dir.exists() {
             debug!(
                 tool = self.id.as_str(),
@@ -976,12 +2673,28 @@ impl Tool {
             return Ok(false);
         }
 
+        // Never delete installations that we don't own
+        if self.is_unmanaged() {
+            debug!(
+                tool = self.id.as_str(),
+                install_dir = ?install_dir,
+                "Tool is unmanaged, skipping deletion of install directory"
+            );
+
+            return Ok(true);
+        }
+
         self.on_uninstalling
             .emit(UninstallingEvent {
                 version: self.get_resolved_version(),
             })
             .await?;
 
+        self.report(ProtoEvent::Uninstalling {
+            tool: self.id.clone(),
+            version: self.get_resolved_version(),
+        });
+
         if self.plugin.has_func("native_uninstall") {
             debug!(tool = self.id.as_str(), "Uninstalling tool natively");
 
@@ -1001,6 +2714,9 @@ impl Tool {
             }
         }
 
+        // Wait for other processes that are installing this version
+        StoreLock::acquire(self.get_install_lock_path())?.unlock()?;
+
         debug!(
             tool = self.id.as_str(),
             install_dir = ?install_dir,
@@ -1015,6 +2731,11 @@ impl Tool {
             })
             .await?;
 
+        self.report(ProtoEvent::Uninstalled {
+            tool: self.id.clone(),
+            version: self.get_resolved_version(),
+        });
+
         debug!(tool = self.id.as_str(), "Successfully uninstalled tool");
 
         Ok(true)
@@ -1064,6 +2785,10 @@ impl Tool {
     /// The list will contain the executable config, and an absolute path
     /// to the binaries final location.
     pub fn get_bin_locations(&self) -> miette::Result<Vec<ExecutableLocation>> {
+        if self.is_sdk_root() {
+            return Ok(vec![]);
+        }
+
         let options = self.call_locate_executables()?;
         let mut locations = vec![];
 
@@ -1110,13 +2835,46 @@ impl Tool {
             }
         }
 
-        Ok(None)
+        Ok(None)
+    }
+
+    /// Return location information for the primary and all secondary executables
+    /// within the tool directory. Executables without a file (shim only) are excluded.
+    pub fn get_exe_locations(&self) -> miette::Result<Vec<ExecutableLocation>> {
+        let options = self.call_locate_executables()?;
+        let tool_dir = self.get_tool_dir();
+        let mut locations = vec![];
+
+        let mut add = |name: &str, config: ExecutableConfig, primary: bool| {
+            if let Some(path) = config.exe_path.as_ref().map(|exe| tool_dir.join(exe)) {
+                locations.push(ExecutableLocation {
+                    path,
+                    name: name.to_owned(),
+                    config,
+                    primary,
+                });
+            }
+        };
+
+        if let Some(primary) = options.primary {
+            add(&self.id, primary, true);
+        }
+
+        for (name, secondary) in options.secondary {
+            add(&name, secondary, false);
+        }
+
+        Ok(locations)
     }
 
     /// Return a list of all shims that get created in `~/.proto/shims`.
     /// The list will contain the executable config, and an absolute path
     /// to the shims final location.
     pub fn get_shim_locations(&self) -> miette::Result<Vec<ExecutableLocation>> {
+        if self.is_sdk_root() {
+            return Ok(vec![]);
+        }
+
         let options = self.call_locate_executables()?;
         let mut locations = vec![];
 
@@ -1143,10 +2901,13 @@ impl Tool {
     }
 
     /// Locate the primary executable from the tool directory.
+    /// SDK roots have no executable, so the tool directory is used instead.
     pub async fn locate_executable(&mut self) -> miette::Result<()> {
         debug!(tool = self.id.as_str(), "Locating executable for tool");
 
-        let exe_path = if let Some(location) = self.get_exe_location()? {
+        let exe_path = if self.is_sdk_root() {
+            self.get_tool_dir()
+        } else if let Some(location) = self.get_exe_location()? {
             location.path
         } else {
             self.get_tool_dir().join(self.id.as_str())
@@ -1169,7 +2930,10 @@ impl Tool {
 
     /// Locate the directory that global packages are installed to.
     pub async fn locate_globals_dir(&mut self) -> miette::Result<()> {
-        if !self.plugin.has_func("locate_executables") || self.globals_dir.is_some() {
+        if !self.plugin.has_func("locate_executables")
+            || self.asdf_plugin.is_some()
+            || self.globals_dir.is_some()
+        {
             return Ok(());
         }
 
@@ -1226,6 +2990,53 @@ impl Tool {
         Ok(())
     }
 
+    /// Return true if the current tool should own the executable with the
+    /// provided name, when another tool has already claimed it.
+    fn claim_executable(
+        &self,
+        config: &ProtoConfig,
+        name: &str,
+        owner: Option<&str>,
+    ) -> miette::Result<bool> {
+        let Some(owner) = owner else {
+            return Ok(true);
+        };
+
+        // Ignore claims from tools that are no longer configured
+        let other = Id::raw(owner);
+
+        if other == self.id || !config.plugins.contains_key(&other) {
+            return Ok(true);
+        }
+
+        let winner =
+            resolve_executable_conflict(name, &self.id, &other, &config.settings.shims.priority)?;
+
+        if *winner != self.id {
+            debug!(
+                tool = self.id.as_str(),
+                bin = name,
+                owner = other.as_str(),
+                "Executable has been claimed by a higher priority tool, skipping"
+            );
+
+            return Ok(false);
+        }
+
+        Ok(true)
+    }
+
+    /// Return the ID of the tool that a symlinked bin points to, if any.
+    fn get_bin_owner(&self, bin_path: &Path) -> Option<String> {
+        let target = std::fs::read_link(bin_path).ok()?;
+        let relative = target.strip_prefix(&self.proto.tools_dir).ok()?;
+
+        relative
+            .components()
+            .next()
+            .map(|component| component.as_os_str().to_string_lossy().to_string())
+    }
+
     /// Create shim files for the current tool if they are missing or out of date.
     /// If find only is enabled, will only check if they exist, and not create.
     pub async fn generate_shims(&mut self, force: bool) -> miette::Result<()> {
@@ -1247,8 +3058,9 @@ impl Tool {
                 "Creating shims as they either do not exist, or are outdated"
             );
 
-            self.manifest.shim_version = SHIM_VERSION;
-            self.manifest.save()?;
+            self.manifest.update(|manifest| {
+                manifest.shim_version = SHIM_VERSION;
+            })?;
         }
 
         let mut event = CreatedShimsEvent {
@@ -1266,9 +3078,32 @@ impl Tool {
                 }
             })?)?;
 
+        // Other processes may be creating shims and updating the registry
+        let _shims_lock = StoreLock::acquire(self.proto.shims_dir.with_extension("lock"))?;
+
         fs::create_dir_all(&self.proto.shims_dir)?;
 
+        let mut shim_configs = self
+            .call_define_shims(shims.iter().map(|shim| shim.name.clone()).collect())?
+            .shims;
+
+        let config = self.proto.load_config()?;
+        let existing_registry = ShimRegistry::load(&self.proto)?;
+        let mut created_shims = vec![];
+
         for location in shims {
+            if !self.claim_executable(
+                config,
+                &location.name,
+                existing_registry
+                    .get(&location.name)
+                    .map(|shim| shim.get_owner(&location.name)),
+            )? {
+                registry.remove(&location.name);
+
+                continue;
+            }
+
             let mut shim_entry = Shim::default();
 
             // Handle before and after args
@@ -1290,6 +3125,11 @@ impl Tool {
                 shim_entry.env_vars.extend(env_vars);
             }
 
+            // Configs from `define_shims` take precedence
+            if let Some(shim_config) = shim_configs.remove(&location.name) {
+                shim_entry.apply_config(shim_config);
+            }
+
             if !location.primary {
                 shim_entry.parent = Some(self.id.to_string());
 
@@ -1300,6 +3140,10 @@ impl Tool {
             }
 
             // Create the shim file by copying the source bin
+            if !location.path.exists() {
+                created_shims.push(location.name.clone());
+            }
+
             create_shim(&shim_binary, &location.path, find_only).map_err(|error| {
                 ProtoError::CreateShimFailed {
                     path: location.path.to_owned(),
@@ -1321,6 +3165,7 @@ impl Tool {
             );
         }
 
+        self.created_shims.extend(created_shims);
         self.on_created_shims.emit(event).await?;
 
         ShimRegistry::update(&self.proto, registry)?;
@@ -1346,6 +3191,7 @@ impl Tool {
 
         fs::create_dir_all(&self.proto.bin_dir)?;
 
+        let config = self.proto.load_config()?;
         let tool_dir = self.get_tool_dir();
         let mut event = CreatedBinariesEvent { bins: vec![] };
 
@@ -1375,6 +3221,14 @@ impl Tool {
                 continue;
             }
 
+            if !self.claim_executable(
+                config,
+                &location.name,
+                self.get_bin_owner(&output_path).as_deref(),
+            )? {
+                continue;
+            }
+
             debug!(
                 tool = self.id.as_str(),
                 source = ?input_path,
@@ -1456,9 +3310,53 @@ impl Tool {
             return Ok(false);
         }
 
-        self.create_executables(true, false).await?;
+        // Register the version, and if any step fails, roll back
+        // so that the version is not left half-registered
+        let manifest = self.manifest.clone();
+        let mut pinned = false;
+
+        if let Err(error) = self.register_installed_version(&mut pinned).await {
+            warn!(
+                tool = self.id.as_str(),
+                "Failed to register installed version, rolling back"
+            );
+
+            if let Err(rollback_error) = self.rollback_install(manifest, pinned) {
+                warn!(
+                    tool = self.id.as_str(),
+                    "Failed to roll back install: {rollback_error}"
+                );
+            }
+
+            return Err(error);
+        }
+
+        // Catch broken artifacts now, instead of on first use
+        if let Err(error) = self.verify_install().await {
+            warn!(
+                tool = self.id.as_str(),
+                "Failed to verify installed version, rolling back"
+            );
+
+            if let Err(rollback_error) = self.rollback_install(manifest, pinned) {
+                warn!(
+                    tool = self.id.as_str(),
+                    "Failed to roll back install: {rollback_error}"
+                );
+            }
+
+            return Err(error);
+        }
+
         self.cleanup().await?;
 
+        Ok(true)
+    }
+
+    async fn register_installed_version(&mut self, pinned: &mut bool) -> miette::Result<()> {
+        self.created_shims.clear();
+        self.create_executables(true, false).await?;
+
         let version = self.get_resolved_version();
         let default_version = self
             .metadata
@@ -1467,27 +3365,360 @@ impl Tool {
             .unwrap_or_else(|| version.to_unresolved_spec());
 
         // Add version to manifest
-        self.manifest.installed_versions.insert(version.clone());
-        self.manifest
-            .versions
-            .insert(version.clone(), ToolManifestVersion::default());
-        self.manifest.save()?;
+        let integrity = self.integrity.take();
+
+        self.manifest.update(|manifest| {
+            manifest.installed_versions.insert(version.clone());
+            manifest.versions.insert(
+                version.clone(),
+                ToolManifestVersion {
+                    integrity,
+                    ..Default::default()
+                },
+            );
+        })?;
 
         // Pin the global version
         ProtoConfig::update(self.proto.get_config_dir(true), |config| {
-            config
-                .versions
-                .get_or_insert(Default::default())
-                .entry(self.id.clone())
-                .or_insert(default_version);
+            let versions = config.versions.get_or_insert(Default::default());
+
+            if !versions.contains_key(&self.id) {
+                versions.insert(self.id.clone(), default_version);
+                *pinned = true;
+            }
         })?;
 
         // Allow plugins to override manifest
         self.sync_manifest()?;
 
+        // Only write the receipt once every step has succeeded
+        let locked = self.locked.as_ref();
+
+        self.manifest.save_receipt(
+            self.get_tool_dir(),
+            &ToolInstallReceipt {
+                installed_at: now(),
+                checksum: locked.and_then(|locked| locked.checksum.clone()),
+                download_url: locked.and_then(|locked| locked.download_url.clone()),
+                version,
+            },
+        )?;
+
+        Ok(())
+    }
+
+    /// Run the plugin's smoke tests against the resolved version, and record
+    /// the result in the manifest. Returns false if the plugin doesn't support it.
+    pub async fn verify_install(&mut self) -> miette::Result<bool> {
+        if !self.plugin.has_func("verify_install") {
+            return Ok(false);
+        }
+
+        debug!(tool = self.id.as_str(), "Verifying installed version");
+
+        if self.exe_path.is_none() {
+            self.locate_executable().await?;
+        }
+
+        let output: VerifyInstallOutput = self.plugin.call_func_with(
+            "verify_install",
+            VerifyInstallInput {
+                context: self.create_context(),
+            },
+        )?;
+
+        let version = self.get_resolved_version();
+        let tool_dir = self.get_tool_dir();
+        let primary_exe = self.get_exe_path()?.to_path_buf();
+        let mut result = Ok(());
+
+        for verify in output.commands {
+            let exe_path = match &verify.exe_path {
+                Some(path) => tool_dir.join(path),
+                None => primary_exe.clone(),
+            };
+            let command_line = format!(
+                "{} {}",
+                exe_path.file_name().unwrap_or_default().to_string_lossy(),
+                verify.args.join(" ")
+            );
+
+            debug!(
+                tool = self.id.as_str(),
+                command = command_line.trim(),
+                "Running verification command"
+            );
+
+            let mut paths = vec![];
+
+            if let Some(exe_dir) = exe_path.parent() {
+                paths.push(exe_dir.to_path_buf());
+            }
+
+            if let Some(path) = env::var_os("PATH") {
+                paths.extend(env::split_paths(&path));
+            }
+
+            let error = match Command::new(&exe_path)
+                .args(&verify.args)
+                .env("PATH", env::join_paths(paths).into_diagnostic()?)
+                .env(
+                    format!("{}_VERSION", self.get_env_var_prefix()),
+                    version.to_string(),
+                )
+                .output()
+            {
+                Ok(out) => {
+                    let stdout = String::from_utf8_lossy(&out.stdout);
+                    let stderr = String::from_utf8_lossy(&out.stderr);
+
+                    if !out.status.success() {
+                        Some(format!(
+                            "exited with code {}",
+                            out.status.code().unwrap_or(-1)
+                        ))
+                    } else if verify.expected_output.as_ref().is_some_and(|expected| {
+                        !stdout.contains(expected) && !stderr.contains(expected)
+                    }) {
+                        Some(format!(
+                            "output did not contain {}",
+                            verify.expected_output.as_deref().unwrap_or_default()
+                        ))
+                    } else {
+                        None
+                    }
+                }
+                Err(error) => Some(error.to_string()),
+            };
+
+            if let Some(error) = error {
+                result = Err(ProtoError::InstallVerificationFailed {
+                    tool: self.get_name().to_owned(),
+                    version: version.to_string(),
+                    command: command_line.trim().to_owned(),
+                    error,
+                });
+
+                break;
+            }
+        }
+
+        if self.manifest.versions.contains_key(&version) {
+            let verification = ToolVerification {
+                passed: result.is_ok(),
+                verified_at: now(),
+                error: result.as_ref().err().map(|error| error.to_string()),
+            };
+
+            self.manifest.update(|manifest| {
+                if let Some(entry) = manifest.versions.get_mut(&version) {
+                    entry.verification = Some(verification);
+                }
+            })?;
+        }
+
+        result?;
+
+        Ok(true)
+    }
+
+    /// Undo the changes of an install, by restoring the version in the
+    /// manifest and global pin, removing the shims that were created,
+    /// and deleting the version's directory.
+    pub fn rollback_install(&mut self, manifest: ToolManifest, pinned: bool) -> miette::Result<()> {
+        let install_dir = self.get_tool_dir();
+        let version = self.get_resolved_version();
+
+        // Only restore the rolled back version, as other processes
+        // may have changed other versions in the meantime
+        self.manifest.update(|current| {
+            if manifest.installed_versions.contains(&version) {
+                current.installed_versions.insert(version.clone());
+            } else {
+                current.installed_versions.remove(&version);
+            }
+
+            match manifest.versions.get(&version) {
+                Some(entry) => {
+                    current.versions.insert(version.clone(), entry.to_owned());
+                }
+                None => {
+                    current.versions.remove(&version);
+                }
+            };
+        })?;
+
+        if pinned {
+            ProtoConfig::update(self.proto.get_config_dir(true), |config| {
+                if let Some(versions) = &mut config.versions {
+                    versions.remove(&self.id);
+                }
+            })?;
+        }
+
+        // Bins may point to the deleted directory
+        if pinned || self.manifest.installed_versions.is_empty() {
+            for bin in self.get_bin_locations()? {
+                remove_bin_file(bin.path)?;
+            }
+        }
+
+        // Shims that existed before the install are still used by other versions
+        let created_shims = std::mem::take(&mut self.created_shims);
+
+        if !created_shims.is_empty() {
+            let _shims_lock = StoreLock::acquire(self.proto.shims_dir.with_extension("lock"))?;
+
+            for name in &created_shims {
+                fs::remove_file(self.proto.shims_dir.join(get_shim_file_name(name)))?;
+            }
+
+            ShimRegistry::remove(&self.proto, &created_shims)?;
+        }
+
+        debug!(
+            tool = self.id.as_str(),
+            install_dir = ?install_dir,
+            "Deleting install directory",
+        );
+
+        fs::remove_dir_all(install_dir)?;
+
+        Ok(())
+    }
+
+    /// Install the version contained in a package created with `proto package`,
+    /// instead of downloading it. The package is verified against its receipt
+    /// before the version is registered. Return true if the install occurred.
+    pub async fn install_from_package(&mut self, package: &Path) -> miette::Result<bool> {
+        let temp_dir = self
+            .proto
+            .temp_dir
+            .join("packages")
+            .join(self.id.as_str())
+            .join(fs::file_name(package));
+
+        fs::remove_dir_all(&temp_dir)?;
+
+        let receipt = match unpack_package(package, &temp_dir).and_then(|receipt| {
+            receipt.verify(package, self.id.as_str(), &temp_dir)?;
+
+            Ok(receipt)
+        }) {
+            Ok(receipt) => receipt,
+            Err(error) => {
+                fs::remove_dir_all(&temp_dir)?;
+
+                return Err(error);
+            }
+        };
+
+        self.set_version(receipt.version.clone());
+
+        if self.is_installed() {
+            debug!(
+                tool = self.id.as_str(),
+                "Tool already installed, continuing"
+            );
+
+            fs::remove_dir_all(&temp_dir)?;
+
+            return Ok(false);
+        }
+
+        let install_dir = self.get_tool_dir();
+
+        debug!(
+            tool = self.id.as_str(),
+            package = ?package,
+            install_dir = ?install_dir,
+            "Installing tool from package"
+        );
+
+        fs::remove_dir_all(&install_dir)?;
+        fs::rename(&temp_dir, &install_dir)?;
+
+        self.locked = Some(LockedTool::new(receipt.version));
+
+        // Register the version, and if any step fails, roll back
+        // so that the version is not left half-registered
+        let manifest = self.manifest.clone();
+        let mut pinned = false;
+
+        if let Err(error) = self.register_installed_version(&mut pinned).await {
+            if let Err(rollback_error) = self.rollback_install(manifest, pinned) {
+                warn!(
+                    tool = self.id.as_str(),
+                    "Failed to roll back install: {rollback_error}"
+                );
+            }
+
+            return Err(error);
+        }
+
         Ok(true)
     }
 
+    /// Add an external installation of the provided version (one not installed by proto)
+    /// to the inventory, and mark it as unmanaged. Once added, the version can be
+    /// used like any other installed version, and executables are created for it.
+    pub async fn add_unmanaged(
+        &mut self,
+        version: VersionSpec,
+        install_dir: PathBuf,
+    ) -> miette::Result<()> {
+        if !install_dir.exists() {
+            return Err(ProtoError::MissingUnmanagedInstall {
+                tool: self.get_name().to_owned(),
+                version: version.to_string(),
+                path: install_dir,
+            }
+            .into());
+        }
+
+        if self.manifest.installed_versions.contains(&version)
+            && self
+                .manifest
+                .versions
+                .get(&version)
+                .is_some_and(|meta| meta.unmanaged_path.is_none())
+        {
+            return Err(ProtoError::AlreadyManagedVersion {
+                tool: self.get_name().to_owned(),
+                version: version.to_string(),
+            }
+            .into());
+        }
+
+        debug!(
+            tool = self.id.as_str(),
+            install_dir = ?install_dir,
+            "Adding unmanaged version {} to the inventory",
+            version
+        );
+
+        let entry = ToolManifestVersion {
+            unmanaged_path: Some(install_dir),
+            ..Default::default()
+        };
+
+        self.set_version(version.clone());
+        self.manifest.installed_versions.insert(version.clone());
+        self.manifest
+            .versions
+            .insert(version.clone(), entry.clone());
+
+        // Verify the executable exists before persisting
+        self.create_executables(true, false).await?;
+
+        self.manifest.update(|manifest| {
+            manifest.installed_versions.insert(version.clone());
+            manifest.versions.insert(version, entry);
+        })?;
+
+        Ok(())
+    }
+
     /// Teardown the tool by uninstalling the current version, removing the version
     /// from the manifest, and cleaning up temporary files. Return true if the teardown occurred.
     pub async fn teardown(&mut self) -> miette::Result<bool> {
@@ -1501,9 +3732,10 @@ impl Tool {
         let mut removed_default_version = false;
 
         // Remove version from manifest
-        self.manifest.installed_versions.remove(&version);
-        self.manifest.versions.remove(&version);
-        self.manifest.save()?;
+        self.manifest.update(|manifest| {
+            manifest.installed_versions.remove(&version);
+            manifest.versions.remove(&version);
+        })?;
 
         // Unpin global version if a match
         ProtoConfig::update(self.proto.get_config_dir(true), |config| {
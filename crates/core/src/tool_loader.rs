@@ -1,8 +1,11 @@
+use crate::asdf::AsdfPlugin;
 use crate::error::ProtoError;
 use crate::proto::ProtoEnvironment;
 use crate::proto_config::{ProtoConfig, SCHEMA_PLUGIN_KEY};
+use crate::script_hooks::ScriptHooks;
 use crate::tool::Tool;
 use miette::IntoDiagnostic;
+use serde::Deserialize;
 use starbase_utils::{json, toml};
 use std::path::PathBuf;
 use tracing::{debug, trace};
@@ -80,6 +83,65 @@ pub async fn load_schema_plugin_with_proto(
         .await
 }
 
+/// Load a tool backed by an asdf plugin. The schema plugin is used for the tool's
+/// metadata, while versions, installs, and executables are handled by the asdf scripts.
+pub async fn load_asdf_tool(
+    id: &Id,
+    proto: &ProtoEnvironment,
+    locator: &PluginLocator,
+    repo: &str,
+    rev: Option<&str>,
+) -> miette::Result<Tool> {
+    let mut asdf = AsdfPlugin::new(
+        id,
+        proto.plugins_dir.join("asdf").join(id.as_str()),
+        PluginLocator::create_asdf_repo_url(repo),
+    );
+    asdf.rev = rev.map(|rev| rev.to_owned());
+
+    asdf.clone_repository()?;
+    proto.apply_cache_permissions(&asdf.dir)?;
+
+    debug!(source = ?asdf.dir, "Loading asdf plugin");
+
+    let mut manifest = Tool::create_plugin_manifest(
+        proto,
+        Wasm::file(load_schema_plugin_with_proto(proto).await?),
+    )?;
+
+    // The download URL is never used, as asdf plugins download with their own scripts
+    let schema = serde_json::json!({
+        "name": id.as_str(),
+        "type": "cli",
+        "platform": {
+            "linux": { "download-file": id.as_str() },
+            "macos": { "download-file": id.as_str() },
+        },
+        "install": {
+            "download-url": asdf.repo_url,
+        },
+        "resolve": {
+            "git-url": asdf.repo_url,
+        },
+    });
+
+    manifest.config.insert(
+        "schema".to_string(),
+        json::to_string(&schema).into_diagnostic()?,
+    );
+
+    inject_default_manifest_config(id, &proto.home, &mut manifest)?;
+    inject_proto_manifest_config(id, proto, &mut manifest)?;
+
+    // The schema is generated by proto, not supplied by the asdf plugin,
+    // and its scripts are ran by proto outside of the WASM runtime
+    let mut tool = Tool::load_from_manifest(id, proto, manifest)?;
+    tool.locator = Some(locator.to_owned());
+    tool.asdf_plugin = Some(asdf);
+
+    Ok(tool)
+}
+
 pub async fn load_tool_from_locator(
     id: impl AsRef<Id>,
     proto: impl AsRef<ProtoEnvironment>,
@@ -89,10 +151,20 @@ pub async fn load_tool_from_locator(
     let proto = proto.as_ref();
     let locator = locator.as_ref();
 
+    if let PluginLocator::Asdf { repo, rev } = locator {
+        return load_asdf_tool(id, proto, locator, repo, rev.as_deref()).await;
+    }
+
     let plugin_path = proto.get_plugin_loader()?.load_plugin(id, locator).await?;
 
+    // Local file plugins are not owned by the store
+    if plugin_path.starts_with(&proto.plugins_dir) {
+        proto.apply_cache_permissions(&plugin_path)?;
+    }
+
     // If a TOML plugin, we need to load the WASM plugin for it,
     // wrap it, and modify the plugin manifest.
+    let mut script_hooks = ScriptHooks::default();
     let mut manifest = if plugin_path.extension().is_some_and(|ext| ext == "toml") {
         debug!(source = ?plugin_path, "Loading TOML plugin");
 
@@ -102,7 +174,13 @@ pub async fn load_tool_from_locator(
         )?;
 
         // Convert TOML to JSON
-        let schema: json::JsonValue = toml::read_file(plugin_path)?;
+        let mut schema: json::JsonValue = toml::read_file(plugin_path)?;
+
+        // Hook scripts are ran by proto, not the schema plugin
+        if let Some(hooks) = schema.as_object_mut().and_then(|map| map.remove("hooks")) {
+            script_hooks = ScriptHooks::deserialize(hooks).into_diagnostic()?;
+        }
+
         let schema = json::to_string(&schema).into_diagnostic()?;
 
         trace!(schema = %schema, "Storing schema settings");
@@ -120,8 +198,16 @@ pub async fn load_tool_from_locator(
     inject_default_manifest_config(id, &proto.home, &mut manifest)?;
     inject_proto_manifest_config(id, proto, &mut manifest)?;
 
-    let mut tool = Tool::load_from_manifest(id, proto, manifest)?;
+    // Third-party plugins are denied by default, unless they declare
+    // their permissions, or are granted them by the user
+    let mut tool = Tool::load_from_manifest_with_trust(
+        id,
+        proto,
+        manifest,
+        ProtoConfig::is_builtin_plugin(id, locator),
+    )?;
     tool.locator = Some(locator.to_owned());
+    tool.script_hooks = script_hooks;
 
     Ok(tool)
 }
@@ -1,21 +1,111 @@
 use crate::helpers::{now, read_json_file_with_lock, write_json_file_with_lock};
+use crate::store_lock::StoreLock;
 use rustc_hash::{FxHashMap, FxHashSet};
 use serde::{Deserialize, Serialize};
-use starbase_utils::fs;
+use starbase_utils::fs::{self, FsError};
 use std::{
     env,
+    fs::OpenOptions,
+    io::Write,
     path::{Path, PathBuf},
 };
 use tracing::debug;
 use version_spec::*;
 
+pub const INSTALL_STAGING_DIR: &str = ".staging";
 pub const MANIFEST_NAME: &str = "manifest.json";
+pub const MANIFEST_LOCK_NAME: &str = "manifest.lock";
+pub const RECEIPT_NAME: &str = ".install-receipt.json";
+pub const RUNS_BUFFER_NAME: &str = ".runs";
+
+// Aggregate buffered runs once the buffer reaches this size (in bytes),
+// so that it doesn't grow unbounded when `proto stats` is never ran
+pub const RUNS_BUFFER_FLUSH_SIZE: u64 = 4096;
+
+fn is_zero(value: &u64) -> bool {
+    *value == 0
+}
+
+/// Written to a version's directory once every install step has succeeded.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(default)]
+pub struct ToolInstallReceipt {
+    pub version: VersionSpec,
+    pub installed_at: u128,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub checksum: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub download_url: Option<String>,
+}
+
+/// Result of running the plugin's `verify_install` smoke tests.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(default)]
+pub struct ToolVerification {
+    pub passed: bool,
+    pub verified_at: u128,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// How the download of an installed version was verified.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(default)]
+pub struct ToolIntegrity {
+    /// Algorithm of the digest, like `sha256`.
+    pub algorithm: String,
+    pub digest: String,
+
+    /// Whether the download matched a checksum file.
+    pub checksum_verified: bool,
+
+    /// Whether the download matched a minisign signature.
+    pub signature_verified: bool,
+
+    pub verified_at: u128,
+}
+
+/// Progress of an install that has not completed, recorded in the manifest
+/// as it transitions, so that interrupted installs can be detected and repaired.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ToolInstallState {
+    /// Installing directly into the inventory, as the install method
+    /// (native, build, asdf, or an overridden inventory) can't be staged.
+    Installing,
+    /// Downloading and unpacking into the staging directory.
+    Staging,
+    /// Locating executables within the staging directory.
+    Validating,
+    /// Moving the staging directory into the inventory.
+    Committing,
+}
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(default)]
 pub struct ToolManifestVersion {
     pub no_clean: bool,
     pub installed_at: u128,
+
+    /// Integrity of the download, when installed from a pre-built archive.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub integrity: Option<ToolIntegrity>,
+
+    /// Number of times the version has been ran, when `track-runs` is enabled.
+    #[serde(skip_serializing_if = "is_zero")]
+    pub run_count: u64,
+
+    /// Absolute path to an external installation that was not installed
+    /// by proto, and must not be modified or deleted by proto.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unmanaged_path: Option<PathBuf>,
+
+    /// Result of the last install verification, if the plugin supports it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub verification: Option<ToolVerification>,
 }
 
 impl Default for ToolManifestVersion {
@@ -23,6 +113,10 @@ impl Default for ToolManifestVersion {
         Self {
             no_clean: env::var("PROTO_NO_CLEAN").is_ok(),
             installed_at: now(),
+            integrity: None,
+            run_count: 0,
+            unmanaged_path: None,
+            verification: None,
         }
     }
 }
@@ -32,6 +126,11 @@ impl Default for ToolManifestVersion {
 pub struct ToolManifest {
     // Full versions only
     pub installed_versions: FxHashSet<VersionSpec>,
+
+    /// Installs that are in progress, or were interrupted.
+    #[serde(skip_serializing_if = "FxHashMap::is_empty")]
+    pub install_states: FxHashMap<VersionSpec, ToolInstallState>,
+
     pub shim_version: u8,
     pub versions: FxHashMap<VersionSpec, ToolManifestVersion>,
 
@@ -68,12 +167,153 @@ impl ToolManifest {
         Ok(())
     }
 
+    /// Lock the manifest in the provided directory across processes,
+    /// until the returned lock is dropped.
+    pub fn lock_from<P: AsRef<Path>>(dir: P) -> miette::Result<StoreLock> {
+        StoreLock::acquire(dir.as_ref().join(MANIFEST_LOCK_NAME))
+    }
+
+    /// Return an absolute path to the lock file that is held while installing the version.
+    pub fn get_install_lock_path(&self, version: &VersionSpec) -> PathBuf {
+        self.path.with_file_name(format!("{version}.lock"))
+    }
+
+    /// Return installs that have a recorded state, but are not being installed
+    /// by any process, as the process was interrupted (crashed, killed, etc).
+    pub fn get_interrupted_installs(&self) -> Vec<(VersionSpec, ToolInstallState)> {
+        let mut installs = self
+            .install_states
+            .iter()
+            .filter(|(version, _)| !StoreLock::is_locked(self.get_install_lock_path(version)))
+            .map(|(version, state)| (version.to_owned(), *state))
+            .collect::<Vec<_>>();
+
+        installs.sort_by(|a, d| a.0.cmp(&d.0));
+        installs
+    }
+
+    /// Lock the manifest across processes, reload it to pick up changes made
+    /// by other processes since it was loaded, apply the changes, and save.
+    /// This avoids concurrent installs overwriting each other's versions.
+    pub fn update(&mut self, op: impl FnOnce(&mut Self)) -> miette::Result<()> {
+        let _lock = StoreLock::acquire(self.path.with_file_name(MANIFEST_LOCK_NAME))?;
+        let mut manifest = Self::load(&self.path)?;
+
+        op(&mut manifest);
+        manifest.save()?;
+
+        *self = manifest;
+
+        Ok(())
+    }
+
     pub fn track_used_at(&mut self, tool_dir: impl AsRef<Path>) -> miette::Result<()> {
         fs::write_file(tool_dir.as_ref().join(".last-used"), now().to_string())?;
 
         Ok(())
     }
 
+    /// Record a run of the provided version. Runs are appended to a buffer
+    /// next to the manifest, instead of rewriting the manifest on every run,
+    /// and are aggregated into the manifest with [`ToolManifest::flush_runs`],
+    /// or automatically once the buffer reaches [`RUNS_BUFFER_FLUSH_SIZE`].
+    pub fn track_run(&self, version: &VersionSpec) -> miette::Result<()> {
+        let file = self.get_runs_buffer_path();
+
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&file)
+            .and_then(|mut handle| handle.write_all(format!("{version}\n").as_bytes()))
+            .map_err(|error| FsError::Write {
+                path: file.clone(),
+                error,
+            })?;
+
+        if fs::metadata(&file)?.len() >= RUNS_BUFFER_FLUSH_SIZE {
+            self.flush_runs_and_save()?;
+        }
+
+        Ok(())
+    }
+
+    /// Lock the manifest across processes, reload it, aggregate buffered runs
+    /// into it with [`ToolManifest::flush_runs`], and save if any counts changed.
+    pub fn flush_runs_and_save(&self) -> miette::Result<()> {
+        let _lock = StoreLock::acquire(self.path.with_file_name(MANIFEST_LOCK_NAME))?;
+        let mut manifest = Self::load(&self.path)?;
+
+        if manifest.flush_runs()? {
+            manifest.save()?;
+        }
+
+        Ok(())
+    }
+
+    /// Aggregate buffered runs into the run count of each version, and remove
+    /// the buffer. Returns true if any counts changed, and the manifest
+    /// should be saved.
+    pub fn flush_runs(&mut self) -> miette::Result<bool> {
+        let file = self.get_runs_buffer_path();
+
+        if !file.exists() {
+            return Ok(false);
+        }
+
+        // Move the buffer first, so that concurrent runs start a new one
+        let flushing_file = file.with_extension(format!("flushing-{}", std::process::id()));
+
+        fs::rename(&file, &flushing_file)?;
+
+        let contents = fs::read_file(&flushing_file)?;
+        let mut changed = false;
+
+        for line in contents.lines() {
+            let Ok(version) = VersionSpec::parse(line.trim()) else {
+                continue;
+            };
+
+            if let Some(metadata) = self.versions.get_mut(&version) {
+                metadata.run_count += 1;
+                changed = true;
+            }
+        }
+
+        fs::remove_file(&flushing_file)?;
+
+        Ok(changed)
+    }
+
+    fn get_runs_buffer_path(&self) -> PathBuf {
+        self.path
+            .parent()
+            .map(|dir| dir.join(RUNS_BUFFER_NAME))
+            .unwrap_or_else(|| PathBuf::from(RUNS_BUFFER_NAME))
+    }
+
+    pub fn save_receipt(
+        &self,
+        tool_dir: impl AsRef<Path>,
+        receipt: &ToolInstallReceipt,
+    ) -> miette::Result<()> {
+        write_json_file_with_lock(tool_dir.as_ref().join(RECEIPT_NAME), receipt)?;
+
+        Ok(())
+    }
+
+    pub fn load_receipt(
+        &self,
+        tool_dir: impl AsRef<Path>,
+    ) -> miette::Result<Option<ToolInstallReceipt>> {
+        let file = tool_dir.as_ref().join(RECEIPT_NAME);
+
+        if file.exists() {
+            return Ok(Some(read_json_file_with_lock(file)?));
+        }
+
+        Ok(None)
+    }
+
     pub fn load_used_at(&self, tool_dir: impl AsRef<Path>) -> miette::Result<Option<u128>> {
         let file = tool_dir.as_ref().join(".last-used");
 
@@ -0,0 +1,169 @@
+use crate::error::ProtoError;
+use crate::helpers::{hash_file_contents, now};
+use crate::tool_manifest::RECEIPT_NAME;
+use serde::{Deserialize, Serialize};
+use starbase_archive::Archiver;
+use starbase_utils::{fs, json};
+use std::collections::BTreeMap;
+use std::env;
+use std::path::Path;
+use tracing::debug;
+use version_spec::VersionSpec;
+
+pub const PACKAGE_RECEIPT_NAME: &str = ".package-receipt.json";
+
+/// Files that are specific to an install on the current machine,
+/// and should not be copied to other machines.
+const PACKAGE_EXCLUDED_FILES: [&str; 4] =
+    [".last-used", ".lock", PACKAGE_RECEIPT_NAME, RECEIPT_NAME];
+
+/// Describes the contents of a packaged version, created with `proto package`,
+/// so that it can be verified before being installed on another machine.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct ToolPackageReceipt {
+    pub id: String,
+    pub version: VersionSpec,
+    pub os: String,
+    pub arch: String,
+    pub packaged_at: u128,
+
+    /// Relative file paths mapped to their SHA256 checksum.
+    pub files: BTreeMap<String, String>,
+}
+
+impl ToolPackageReceipt {
+    /// Create a receipt for the provided version directory,
+    /// by calculating a checksum for every file within.
+    pub fn create(id: &str, version: &VersionSpec, dir: &Path) -> miette::Result<Self> {
+        let mut files = BTreeMap::new();
+
+        for file in fs::read_dir_all(dir)? {
+            let path = file.path();
+            let Ok(rel_path) = path.strip_prefix(dir) else {
+                continue;
+            };
+
+            if PACKAGE_EXCLUDED_FILES.contains(&fs::file_name(&path).as_str()) {
+                continue;
+            }
+
+            files.insert(
+                rel_path.to_string_lossy().replace('\\', "/"),
+                hash_file_contents(&path)?,
+            );
+        }
+
+        Ok(Self {
+            id: id.to_owned(),
+            version: version.to_owned(),
+            os: env::consts::OS.to_owned(),
+            arch: env::consts::ARCH.to_owned(),
+            packaged_at: now(),
+            files,
+        })
+    }
+
+    pub fn get_platform(&self) -> String {
+        format!("{}-{}", self.os, self.arch)
+    }
+
+    /// Verify that the package was created for the provided tool and the
+    /// current platform, and that the unpacked files match their checksums.
+    pub fn verify(&self, package: &Path, id: &str, dir: &Path) -> miette::Result<()> {
+        if self.id != id {
+            return Err(ProtoError::PackageToolMismatch {
+                path: package.to_path_buf(),
+                actual: self.id.clone(),
+                expected: id.to_owned(),
+            }
+            .into());
+        }
+
+        if self.os != env::consts::OS || self.arch != env::consts::ARCH {
+            return Err(ProtoError::PackagePlatformMismatch {
+                path: package.to_path_buf(),
+                actual: self.get_platform(),
+                expected: format!("{}-{}", env::consts::OS, env::consts::ARCH),
+            }
+            .into());
+        }
+
+        for (file, checksum) in &self.files {
+            let path = dir.join(file);
+
+            if !path.exists() || &hash_file_contents(&path)? != checksum {
+                return Err(ProtoError::PackageChecksumMismatch {
+                    path: package.to_path_buf(),
+                    file: file.to_owned(),
+                }
+                .into());
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Package the provided version directory into an archive at the output path,
+/// alongside a receipt of its contents. The archive format is derived from the
+/// output file's extension.
+pub fn create_package(
+    id: &str,
+    version: &VersionSpec,
+    tool_dir: &Path,
+    output_file: &Path,
+) -> miette::Result<ToolPackageReceipt> {
+    let receipt = ToolPackageReceipt::create(id, version, tool_dir)?;
+    let receipt_path = tool_dir.join(PACKAGE_RECEIPT_NAME);
+
+    debug!(
+        tool = id,
+        version = version.to_string(),
+        file = ?output_file,
+        "Packaging {} files",
+        receipt.files.len()
+    );
+
+    // The receipt must live in the source directory to be archived,
+    // so write it temporarily and remove it once packed
+    json::write_file(&receipt_path, &receipt, true)?;
+
+    let mut archiver = Archiver::new(tool_dir, output_file);
+
+    archiver.add_source_file(PACKAGE_RECEIPT_NAME, None);
+
+    for file in receipt.files.keys() {
+        archiver.add_source_file(file, None);
+    }
+
+    let result = archiver.pack_from_ext();
+
+    fs::remove_file(&receipt_path)?;
+    result?;
+
+    Ok(receipt)
+}
+
+/// Unpack a package created with [`create_package`] into the output directory,
+/// and return its receipt. The receipt is removed from the output directory.
+pub fn unpack_package(package: &Path, output_dir: &Path) -> miette::Result<ToolPackageReceipt> {
+    debug!(file = ?package, dir = ?output_dir, "Unpacking package");
+
+    Archiver::new(output_dir, package).unpack_from_ext()?;
+
+    let receipt_path = output_dir.join(PACKAGE_RECEIPT_NAME);
+
+    if !receipt_path.exists() {
+        return Err(ProtoError::MissingPackageReceipt {
+            path: package.to_path_buf(),
+        }
+        .into());
+    }
+
+    let receipt: ToolPackageReceipt = json::read_file(&receipt_path)?;
+
+    fs::remove_file(&receipt_path)?;
+
+    Ok(receipt)
+}
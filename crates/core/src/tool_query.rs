@@ -0,0 +1,135 @@
+use crate::tool::{ExecutableLocation, Tool};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use version_spec::VersionSpec;
+use warpgate::Id;
+
+/// Version of the [`ToolManifestQuery`] schema. Bumped when a field is removed,
+/// renamed, or changes meaning. Fields may be added without a bump.
+pub const MANIFEST_QUERY_SCHEMA_VERSION: u32 = 1;
+
+/// An executable (bin or shim) created for the tool.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct ToolExecutableQuery {
+    pub name: String,
+    pub path: PathBuf,
+    pub primary: bool,
+}
+
+impl From<ExecutableLocation> for ToolExecutableQuery {
+    fn from(location: ExecutableLocation) -> Self {
+        Self {
+            name: location.name,
+            path: location.path,
+            primary: location.primary,
+        }
+    }
+}
+
+/// An installed version of the tool.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct ToolVersionQuery {
+    pub version: VersionSpec,
+
+    /// Absolute path to the install directory.
+    pub path: PathBuf,
+
+    /// Timestamps in milliseconds since the Unix epoch.
+    pub installed_at: u128,
+    pub last_used_at: Option<u128>,
+
+    /// Digest of the download, when installed from a pre-built archive.
+    pub checksum: Option<String>,
+
+    /// Whether the version is protected from `proto clean`.
+    pub no_clean: bool,
+
+    /// Number of times the version has been ran, when `track-runs` is enabled.
+    pub run_count: u64,
+
+    /// Whether the version is an external installation not managed by proto.
+    pub unmanaged: bool,
+
+    /// Result of the last install verification, if the plugin supports it.
+    pub verified: Option<bool>,
+}
+
+/// A stable view of the tool's manifest and inventory, for editors and scripts.
+/// Unlike `manifest.json`, which is internal and may change between releases,
+/// this schema is versioned with [`MANIFEST_QUERY_SCHEMA_VERSION`].
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct ToolManifestQuery {
+    pub schema_version: u32,
+    pub id: Id,
+    pub name: String,
+
+    /// Absolute path to the directory that houses installed versions.
+    pub inventory_dir: PathBuf,
+
+    /// Installed versions, in ascending order.
+    pub versions: Vec<ToolVersionQuery>,
+
+    /// Bins and shims that currently exist on the file system.
+    pub bins: Vec<ToolExecutableQuery>,
+    pub shims: Vec<ToolExecutableQuery>,
+}
+
+impl ToolManifestQuery {
+    pub fn from_tool(tool: &Tool) -> miette::Result<Self> {
+        let manifest = &tool.manifest;
+        let inventory_dir = tool.get_inventory_dir();
+        let suffix = tool
+            .metadata
+            .inventory
+            .version_suffix
+            .as_deref()
+            .unwrap_or_default();
+
+        let mut installed = manifest.installed_versions.iter().collect::<Vec<_>>();
+        installed.sort();
+
+        let mut versions = vec![];
+
+        for version in installed {
+            let metadata = manifest.versions.get(version);
+            let unmanaged_path = metadata.and_then(|meta| meta.unmanaged_path.clone());
+            let path = unmanaged_path
+                .clone()
+                .unwrap_or_else(|| inventory_dir.join(format!("{version}{suffix}")));
+
+            versions.push(ToolVersionQuery {
+                version: version.to_owned(),
+                last_used_at: manifest.load_used_at(&path)?,
+                path,
+                installed_at: metadata.map(|meta| meta.installed_at).unwrap_or_default(),
+                checksum: metadata
+                    .and_then(|meta| meta.integrity.as_ref())
+                    .map(|integrity| format!("{}:{}", integrity.algorithm, integrity.digest)),
+                no_clean: metadata.is_some_and(|meta| meta.no_clean),
+                run_count: metadata.map(|meta| meta.run_count).unwrap_or_default(),
+                unmanaged: unmanaged_path.is_some(),
+                verified: metadata
+                    .and_then(|meta| meta.verification.as_ref())
+                    .map(|verification| verification.passed),
+            });
+        }
+
+        let existing = |locations: Vec<ExecutableLocation>| {
+            locations
+                .into_iter()
+                .filter(|location| location.path.exists())
+                .map(ToolExecutableQuery::from)
+                .collect::<Vec<_>>()
+        };
+
+        Ok(Self {
+            schema_version: MANIFEST_QUERY_SCHEMA_VERSION,
+            id: tool.id.clone(),
+            name: tool.get_name().to_owned(),
+            inventory_dir,
+            versions,
+            bins: existing(tool.get_bin_locations()?),
+            shims: existing(tool.get_shim_locations()?),
+        })
+    }
+}
@@ -0,0 +1,89 @@
+use crate::proto_config::ProtoToolVersionsSettingsConfig;
+use starbase_utils::fs;
+use std::path::{Path, PathBuf};
+use warpgate::Id;
+
+pub const TOOL_VERSIONS_NAME: &str = ".tool-versions";
+
+/// asdf plugin names that differ from the proto tool ID.
+pub const TOOL_VERSIONS_DEFAULT_ALIASES: [(&str, &str); 2] = [("golang", "go"), ("nodejs", "node")];
+
+impl ProtoToolVersionsSettingsConfig {
+    /// Return the proto tool ID for the provided asdf plugin name,
+    /// with configured aliases taking precedence over the defaults.
+    pub fn get_tool_id(&self, name: &str) -> Option<Id> {
+        if let Some(id) = self.aliases.get(name) {
+            return Some(id.to_owned());
+        }
+
+        if let Some((_, id)) = TOOL_VERSIONS_DEFAULT_ALIASES
+            .iter()
+            .find(|(alias, _)| *alias == name)
+        {
+            return Some(Id::raw(id));
+        }
+
+        Id::new(name).ok()
+    }
+}
+
+/// An asdf `.tool-versions` file, which maps plugin names to a list of
+/// versions. The first version is used, while the others are fallbacks.
+#[derive(Debug, Default)]
+pub struct ToolVersionsFile {
+    pub path: PathBuf,
+    pub tools: Vec<(String, Vec<String>)>,
+}
+
+impl ToolVersionsFile {
+    pub fn parse(path: PathBuf, content: &str) -> Self {
+        let mut tools = vec![];
+
+        for line in content.lines() {
+            // Comments are allowed at the end of lines
+            let line = line.split('#').next().unwrap_or_default();
+            let mut parts = line.split_whitespace();
+
+            let Some(name) = parts.next() else {
+                continue;
+            };
+
+            let versions = parts.map(|part| part.to_owned()).collect::<Vec<_>>();
+
+            if !versions.is_empty() {
+                tools.push((name.to_owned(), versions));
+            }
+        }
+
+        Self { path, tools }
+    }
+
+    /// Load the file from the provided directory, if it exists.
+    pub fn load_from(dir: &Path) -> miette::Result<Option<Self>> {
+        let path = dir.join(TOOL_VERSIONS_NAME);
+
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let content = fs::read_file(&path)?;
+
+        Ok(Some(Self::parse(path, &content)))
+    }
+
+    /// Return the versions for the provided tool, by mapping plugin names
+    /// to tool IDs with the configured aliases.
+    pub fn get_versions(
+        &self,
+        id: &Id,
+        settings: &ProtoToolVersionsSettingsConfig,
+    ) -> Option<&[String]> {
+        self.tools.iter().find_map(|(name, versions)| {
+            if settings.get_tool_id(name).is_some_and(|other| &other == id) {
+                Some(versions.as_slice())
+            } else {
+                None
+            }
+        })
+    }
+}
@@ -1,19 +1,76 @@
 use crate::error::ProtoError;
 use crate::proto_config::*;
 use crate::tool::Tool;
+use crate::tool_versions::*;
 use std::env;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use tracing::{debug, trace};
 use version_spec::*;
 
 fn set_detected_env_var(path: &Path) {
     env::set_var("PROTO_DETECTED_FROM", path);
+    env::remove_var("PROTO_DETECTED_FROM_MERGED");
+}
+
+/// Detect a version from the `.tool-versions` file that sits alongside
+/// the provided local `.prototools` file, if enabled.
+fn detect_version_from_tool_versions(
+    tool: &Tool,
+    config_file: &ProtoConfigFile,
+    settings: &ProtoToolVersionsSettingsConfig,
+) -> miette::Result<Option<(UnresolvedVersionSpec, PathBuf)>> {
+    if !settings.enabled
+        || config_file.global
+        || config_file
+            .path
+            .file_name()
+            .is_some_and(|name| name != PROTO_CONFIG_NAME)
+    {
+        return Ok(None);
+    }
+
+    let Some(file) = ToolVersionsFile::load_from(config_file.path.parent().unwrap())? else {
+        return Ok(None);
+    };
+
+    let Some(versions) = file.get_versions(&tool.id, settings) else {
+        return Ok(None);
+    };
+
+    for version in versions {
+        // asdf specific versions can't be resolved by proto
+        if version == "system" || version.contains(':') {
+            trace!(
+                tool = tool.id.as_str(),
+                version,
+                "Skipping unsupported {} version",
+                TOOL_VERSIONS_NAME
+            );
+
+            continue;
+        }
+
+        if let Ok(spec) = UnresolvedVersionSpec::parse(version) {
+            debug!(
+                tool = tool.id.as_str(),
+                version = spec.to_string(),
+                file = ?file.path,
+                "Detected version from {} file", TOOL_VERSIONS_NAME
+            );
+
+            return Ok(Some((spec, file.path)));
+        }
+    }
+
+    Ok(None)
 }
 
 pub async fn detect_version_first_available(
     tool: &Tool,
     config_manager: &ProtoConfigManager,
 ) -> miette::Result<Option<UnresolvedVersionSpec>> {
+    let settings = &config_manager.get_merged_config()?.settings.tool_versions;
+
     for file in &config_manager.files {
         if let Some(versions) = &file.config.versions {
             if let Some(version) = versions.get(tool.id.as_str()) {
@@ -30,6 +87,12 @@ pub async fn detect_version_first_available(
             }
         }
 
+        if let Some((version, path)) = detect_version_from_tool_versions(tool, file, settings)? {
+            set_detected_env_var(&path);
+
+            return Ok(Some(version));
+        }
+
         let dir = file.path.parent().unwrap();
 
         if let Some((version, file)) = tool.detect_version_from(dir).await? {
@@ -53,6 +116,8 @@ pub async fn detect_version_prefer_prototools(
     tool: &Tool,
     config_manager: &ProtoConfigManager,
 ) -> miette::Result<Option<UnresolvedVersionSpec>> {
+    let settings = &config_manager.get_merged_config()?.settings.tool_versions;
+
     // Check config files first
     for file in &config_manager.files {
         if let Some(versions) = &file.config.versions {
@@ -69,6 +134,12 @@ pub async fn detect_version_prefer_prototools(
                 return Ok(Some(version.to_owned()));
             }
         }
+
+        if let Some((version, path)) = detect_version_from_tool_versions(tool, file, settings)? {
+            set_detected_env_var(&path);
+
+            return Ok(Some(version));
+        }
     }
 
     // Then check the ecosystem
@@ -92,6 +163,120 @@ pub async fn detect_version_prefer_prototools(
     Ok(None)
 }
 
+fn is_mergeable(spec: &UnresolvedVersionSpec) -> bool {
+    matches!(
+        spec,
+        UnresolvedVersionSpec::Req(_)
+            | UnresolvedVersionSpec::ReqAny(_)
+            | UnresolvedVersionSpec::Version(_)
+    )
+}
+
+/// Intersect the provided versions in order. Aliases and canary can't be
+/// intersected, so the first version takes precedence over them.
+pub fn merge_detected_versions(
+    tool: &Tool,
+    candidates: Vec<(UnresolvedVersionSpec, PathBuf)>,
+) -> miette::Result<Option<(UnresolvedVersionSpec, Vec<PathBuf>)>> {
+    let mut candidates = candidates.into_iter();
+
+    let Some((mut merged, file)) = candidates.next() else {
+        return Ok(None);
+    };
+
+    let mut files = vec![file];
+
+    for (version, file) in candidates {
+        if !is_mergeable(&merged) || !is_mergeable(&version) {
+            debug!(
+                tool = tool.id.as_str(),
+                version = version.to_string(),
+                file = ?file,
+                "Unable to merge version, skipping"
+            );
+
+            continue;
+        }
+
+        merged =
+            merged
+                .intersect(&version)
+                .ok_or_else(|| ProtoError::VersionConstraintConflict {
+                    tool: tool.get_name().to_owned(),
+                    version: version.to_string(),
+                    merged: merged.to_string(),
+                    path: file.clone(),
+                })?;
+
+        files.push(file);
+    }
+
+    Ok(Some((merged, files)))
+}
+
+pub async fn detect_version_merged(
+    tool: &Tool,
+    config_manager: &ProtoConfigManager,
+) -> miette::Result<Option<UnresolvedVersionSpec>> {
+    let settings = &config_manager.get_merged_config()?.settings.tool_versions;
+    let mut dirs: Vec<&Path> = vec![];
+
+    for file in &config_manager.files {
+        let dir = file.path.parent().unwrap();
+
+        if !dirs.contains(&dir) {
+            dirs.push(dir);
+        }
+    }
+
+    // Only merge files in the closest directory, as parent
+    // directories (and the global config) are fallbacks
+    for dir in dirs {
+        let mut candidates = vec![];
+
+        for file in &config_manager.files {
+            if file.path.parent() != Some(dir) {
+                continue;
+            }
+
+            if let Some(version) = file
+                .config
+                .versions
+                .as_ref()
+                .and_then(|versions| versions.get(tool.id.as_str()))
+            {
+                candidates.push((version.to_owned(), file.path.clone()));
+            }
+
+            candidates.extend(detect_version_from_tool_versions(tool, file, settings)?);
+        }
+
+        candidates.extend(tool.detect_all_versions_from(dir).await?);
+
+        if let Some((version, files)) = merge_detected_versions(tool, candidates)? {
+            debug!(
+                tool = tool.id.as_str(),
+                version = version.to_string(),
+                files = ?files,
+                "Detected version by merging files"
+            );
+
+            set_detected_env_var(&files[0]);
+
+            if files.len() > 1 {
+                env::set_var(
+                    "PROTO_DETECTED_FROM_MERGED",
+                    env::join_paths(&files).unwrap_or_default(),
+                );
+            }
+
+            return Ok(Some(version));
+        }
+    }
+
+    Ok(None)
+}
+
 pub async fn detect_version(
     tool: &Tool,
     forced_version: Option<UnresolvedVersionSpec>,
@@ -143,6 +328,7 @@ pub async fn detect_version(
         DetectStrategy::FirstAvailable => {
             detect_version_first_available(tool, config_manager).await?
         }
+        DetectStrategy::Merge => detect_version_merged(tool, config_manager).await?,
         DetectStrategy::PreferPrototools => {
             detect_version_prefer_prototools(tool, config_manager).await?
         }
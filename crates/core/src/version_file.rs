@@ -0,0 +1,110 @@
+use miette::IntoDiagnostic;
+use proto_pdk_api::{DetectVersionFile, DetectVersionOutput, VersionFileFormat};
+use regex::RegexBuilder;
+use starbase_utils::json::{self, JsonValue};
+use starbase_utils::toml::TomlValue;
+
+/// Flatten the plain and detailed files of the plugin's output into a single
+/// list, sorted by priority. Plain files are checked before detailed files
+/// of the same priority.
+pub fn get_detect_version_files(output: &DetectVersionOutput) -> Vec<DetectVersionFile> {
+    let mut files = output
+        .files
+        .iter()
+        .map(|file| DetectVersionFile {
+            file: file.to_owned(),
+            ..Default::default()
+        })
+        .chain(output.detailed_files.iter().cloned())
+        .collect::<Vec<_>>();
+
+    // Stable, so the defined order is kept for equal priorities
+    files.sort_by(|a, d| d.priority.cmp(&a.priority));
+
+    files
+}
+
+fn traverse_json<'value>(value: &'value JsonValue, path: &str) -> Option<&'value JsonValue> {
+    path.split('.')
+        .try_fold(value, |current, key| current.get(key))
+}
+
+fn traverse_toml<'value>(value: &'value TomlValue, path: &str) -> Option<&'value TomlValue> {
+    path.split('.')
+        .try_fold(value, |current, key| current.get(key))
+}
+
+/// Parse a corepack `packageManager` field (`pnpm@8.15.1+sha256.abc`)
+/// into a version, if it declares the provided package manager.
+fn parse_package_manager_field(value: &str, name: &str) -> Option<String> {
+    let (manager, version) = value.split_once('@')?;
+
+    if manager != name {
+        return None;
+    }
+
+    // Strip the integrity hash, which is only used by corepack
+    Some(
+        version
+            .split_once('+')
+            .map(|(version, _)| version)
+            .unwrap_or(version)
+            .to_owned(),
+    )
+}
+
+/// Extract the raw version string from the file's content,
+/// according to the provided format.
+pub fn extract_version_from_content(
+    content: &str,
+    format: &VersionFileFormat,
+) -> miette::Result<Option<String>> {
+    let version = match format {
+        VersionFileFormat::Plain => Some(content.trim().to_owned()),
+        VersionFileFormat::Json { path } => {
+            let value: JsonValue = json::from_str(content).into_diagnostic()?;
+
+            traverse_json(&value, path)
+                .and_then(|value| value.as_str())
+                .map(|value| value.to_owned())
+        }
+        VersionFileFormat::Toml { path } => {
+            let value: TomlValue = content.parse().into_diagnostic()?;
+
+            traverse_toml(&value, path)
+                .and_then(|value| value.as_str())
+                .map(|value| value.to_owned())
+        }
+        VersionFileFormat::ToolVersions { name } => content.lines().find_map(|line| {
+            // Comments are allowed at the end of lines
+            let line = line.split('#').next().unwrap_or_default();
+            let mut parts = line.split_whitespace();
+
+            if parts.next() == Some(name) {
+                // Only the first version is used, the others are fallbacks
+                parts.next().map(|version| version.to_owned())
+            } else {
+                None
+            }
+        }),
+        VersionFileFormat::PackageManager { name } => {
+            let value: JsonValue = json::from_str(content).into_diagnostic()?;
+
+            value
+                .get("packageManager")
+                .and_then(|value| value.as_str())
+                .and_then(|value| parse_package_manager_field(value, name))
+        }
+        VersionFileFormat::Regex { pattern } => RegexBuilder::new(pattern)
+            .multi_line(true)
+            .build()
+            .into_diagnostic()?
+            .captures(content)
+            .and_then(|caps| caps.get(1))
+            .map(|value| value.as_str().to_owned()),
+    };
+
+    Ok(version
+        .map(|version| version.trim().to_owned())
+        .filter(|version| !version.is_empty()))
+}
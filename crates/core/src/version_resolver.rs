@@ -1,9 +1,11 @@
+use crate::error::ProtoError;
 use crate::proto_config::ProtoToolConfig;
 use crate::tool_manifest::ToolManifest;
 use proto_pdk_api::LoadVersionsOutput;
 use rustc_hash::FxHashSet;
 use semver::{Version, VersionReq};
 use std::collections::BTreeMap;
+use std::path::PathBuf;
 use version_spec::*;
 
 #[derive(Default)]
@@ -11,7 +13,16 @@ pub struct VersionResolver<'tool> {
     pub aliases: BTreeMap<String, UnresolvedVersionSpec>,
     pub versions: Vec<Version>,
 
+    /// Path to the cache file if versions were loaded from the cache,
+    /// otherwise they were loaded from the plugin.
+    pub cache_path: Option<PathBuf>,
+
+    /// Whether prerelease versions may be matched by requirements
+    /// and the `latest` alias.
+    pub include_prereleases: bool,
+
     manifest: Option<&'tool ToolManifest>,
+    shared_manifest: Option<&'tool ToolManifest>,
     config: Option<&'tool ProtoToolConfig>,
 }
 
@@ -42,17 +53,47 @@ impl<'tool> VersionResolver<'tool> {
         self.manifest = Some(manifest);
     }
 
+    pub fn with_prereleases(&mut self, include: bool) {
+        self.include_prereleases = include;
+    }
+
+    pub fn with_shared_manifest(&mut self, manifest: &'tool ToolManifest) {
+        self.shared_manifest = Some(manifest);
+    }
+
     pub fn with_config(&mut self, config: &'tool ProtoToolConfig) {
         self.config = Some(config);
     }
 
     pub fn resolve(&self, candidate: &UnresolvedVersionSpec) -> Option<VersionSpec> {
-        resolve_version(
+        self.explain(candidate).map(|(version, _)| version)
+    }
+
+    pub fn explain(&self, candidate: &UnresolvedVersionSpec) -> Option<(VersionSpec, Vec<String>)> {
+        // Versions installed in the shared store are treated as installed
+        if let Some(shared_manifest) = self.shared_manifest {
+            let mut manifest = self.manifest.cloned().unwrap_or_default();
+            manifest
+                .installed_versions
+                .extend(shared_manifest.installed_versions.iter().cloned());
+
+            return explain_version_with_prereleases(
+                candidate,
+                &self.versions,
+                &self.aliases,
+                Some(&manifest),
+                self.config,
+                self.include_prereleases,
+            );
+        }
+
+        explain_version_with_prereleases(
             candidate,
             &self.versions,
             &self.aliases,
             self.manifest,
             self.config,
+            self.include_prereleases,
         )
     }
 
@@ -60,15 +101,44 @@ impl<'tool> VersionResolver<'tool> {
         &self,
         candidate: &UnresolvedVersionSpec,
     ) -> Option<VersionSpec> {
-        resolve_version(candidate, &self.versions, &self.aliases, None, None)
+        explain_version_with_prereleases(
+            candidate,
+            &self.versions,
+            &self.aliases,
+            None,
+            None,
+            self.include_prereleases,
+        )
+        .map(|(version, _)| version)
     }
 }
 
 pub fn match_highest_version(req: &VersionReq, versions: &[&Version]) -> Option<VersionSpec> {
+    match_highest_version_with_prereleases(req, versions, false)
+}
+
+// By default, semver requirements only match prereleases when the requirement
+// itself has a prerelease, so compare against the stable counterpart instead
+fn matches_with_prereleases(req: &VersionReq, version: &Version, include: bool) -> bool {
+    if include && !version.pre.is_empty() {
+        let mut stable = version.clone();
+        stable.pre = semver::Prerelease::EMPTY;
+
+        return req.matches(&stable);
+    }
+
+    req.matches(version)
+}
+
+fn match_highest_version_with_prereleases(
+    req: &VersionReq,
+    versions: &[&Version],
+    include: bool,
+) -> Option<VersionSpec> {
     let mut highest_match: Option<Version> = None;
 
     for version in versions {
-        if req.matches(version)
+        if matches_with_prereleases(req, version, include)
             && (highest_match.is_none() || highest_match.as_ref().is_some_and(|v| *version > v))
         {
             highest_match = Some((*version).clone());
@@ -89,6 +159,112 @@ fn extract_installed_versions(installed: &FxHashSet<VersionSpec>) -> Vec<&Versio
         .collect()
 }
 
+/// Follow an alias through any aliases it references, and return each spec
+/// in the chain, starting with the provided alias. The last spec is either
+/// a version/requirement, or an alias that does not exist. Errors if the
+/// chain contains a cycle.
+pub fn trace_alias_chain(
+    alias: &str,
+    aliases: &BTreeMap<String, UnresolvedVersionSpec>,
+    config: Option<&ProtoToolConfig>,
+) -> miette::Result<Vec<UnresolvedVersionSpec>> {
+    let mut chain = vec![UnresolvedVersionSpec::Alias(alias.to_owned())];
+    let mut current = alias;
+
+    loop {
+        let value = config
+            .and_then(|config| config.aliases.get(current))
+            .or_else(|| aliases.get(current));
+
+        let Some(value) = value else {
+            break;
+        };
+
+        if chain.contains(value) {
+            chain.push(value.to_owned());
+
+            return Err(ProtoError::AliasCycle {
+                chain: format_alias_chain(&chain),
+            }
+            .into());
+        }
+
+        chain.push(value.to_owned());
+
+        match value {
+            UnresolvedVersionSpec::Alias(next) => {
+                current = next;
+            }
+            _ => break,
+        };
+    }
+
+    Ok(chain)
+}
+
+/// Expand the members of a composite spec by following aliases that
+/// map to other composites, so that only non-composite specs remain.
+/// Errors if a composite references itself through an alias.
+pub fn expand_composite_spec(
+    specs: &[UnresolvedVersionSpec],
+    aliases: &BTreeMap<String, UnresolvedVersionSpec>,
+    config: Option<&ProtoToolConfig>,
+) -> miette::Result<Vec<UnresolvedVersionSpec>> {
+    let mut expanded = vec![];
+
+    expand_composite_spec_inner(specs, aliases, config, &mut vec![], &mut expanded)?;
+
+    Ok(expanded)
+}
+
+fn expand_composite_spec_inner(
+    specs: &[UnresolvedVersionSpec],
+    aliases: &BTreeMap<String, UnresolvedVersionSpec>,
+    config: Option<&ProtoToolConfig>,
+    seen: &mut Vec<UnresolvedVersionSpec>,
+    expanded: &mut Vec<UnresolvedVersionSpec>,
+) -> miette::Result<()> {
+    for spec in specs {
+        let chain = match spec {
+            UnresolvedVersionSpec::Alias(alias) => trace_alias_chain(alias, aliases, config)?,
+            _ => vec![spec.to_owned()],
+        };
+
+        let Some(UnresolvedVersionSpec::Composite(inner)) = chain.last() else {
+            if !expanded.contains(spec) {
+                expanded.push(spec.to_owned());
+            }
+
+            continue;
+        };
+
+        if seen.contains(spec) {
+            seen.push(spec.to_owned());
+
+            return Err(ProtoError::AliasCycle {
+                chain: format_alias_chain(seen),
+            }
+            .into());
+        }
+
+        seen.push(spec.to_owned());
+
+        expand_composite_spec_inner(inner, aliases, config, seen, expanded)?;
+
+        seen.pop();
+    }
+
+    Ok(())
+}
+
+pub fn format_alias_chain(chain: &[UnresolvedVersionSpec]) -> String {
+    chain
+        .iter()
+        .map(|spec| spec.to_string())
+        .collect::<Vec<_>>()
+        .join(" -> ")
+}
+
 pub fn resolve_version(
     candidate: &UnresolvedVersionSpec,
     versions: &[Version],
@@ -96,6 +272,51 @@ pub fn resolve_version(
     manifest: Option<&ToolManifest>,
     config: Option<&ProtoToolConfig>,
 ) -> Option<VersionSpec> {
+    explain_version(candidate, versions, aliases, manifest, config).map(|(version, _)| version)
+}
+
+/// Return true if the version would be resolved by the candidate,
+/// using the same matching rules as `resolve_version`.
+pub fn satisfies_version(
+    candidate: &UnresolvedVersionSpec,
+    version: &Version,
+    include_prereleases: bool,
+) -> bool {
+    explain_version_with_prereleases(
+        candidate,
+        &[version.to_owned()],
+        &BTreeMap::new(),
+        None,
+        None,
+        include_prereleases,
+    )
+    .is_some_and(|(resolved, _)| resolved == VersionSpec::Version(version.to_owned()))
+}
+
+/// Resolve the candidate like `resolve_version`, but also return a list of
+/// human readable steps that explain which rules selected the version.
+pub fn explain_version(
+    candidate: &UnresolvedVersionSpec,
+    versions: &[Version],
+    aliases: &BTreeMap<String, UnresolvedVersionSpec>,
+    manifest: Option<&ToolManifest>,
+    config: Option<&ProtoToolConfig>,
+) -> Option<(VersionSpec, Vec<String>)> {
+    explain_version_with_prereleases(candidate, versions, aliases, manifest, config, false)
+}
+
+fn explain_version_with_prereleases(
+    candidate: &UnresolvedVersionSpec,
+    versions: &[Version],
+    aliases: &BTreeMap<String, UnresolvedVersionSpec>,
+    manifest: Option<&ToolManifest>,
+    config: Option<&ProtoToolConfig>,
+    include_prereleases: bool,
+) -> Option<(VersionSpec, Vec<String>)> {
+    let include = include_prereleases
+        || config
+            .and_then(|config| config.include_prereleases)
+            .unwrap_or_default();
     let remote_versions = versions.iter().collect::<Vec<_>>();
     let installed_versions = if let Some(manifest) = manifest {
         extract_installed_versions(&manifest.installed_versions)
@@ -105,63 +326,151 @@ pub fn resolve_version(
 
     match &candidate {
         UnresolvedVersionSpec::Canary => {
-            return Some(VersionSpec::Canary);
+            return Some((VersionSpec::Canary, vec!["canary is used as-is".into()]));
         }
         UnresolvedVersionSpec::Alias(alias) => {
-            let mut alias_value = None;
+            // Cycles are unresolvable
+            let chain = trace_alias_chain(alias, aliases, config).ok()?;
+            let value = chain.last()?;
 
-            if let Some(config) = config {
-                alias_value = config.aliases.get(alias);
+            // Never let a prerelease sneak into latest unless requested
+            if let (false, "latest", UnresolvedVersionSpec::Version(latest)) =
+                (include, alias.as_str(), value)
+            {
+                if let Some(stable) = versions
+                    .iter()
+                    .filter(|v| v.pre.is_empty())
+                    .max()
+                    .filter(|_| !latest.pre.is_empty())
+                {
+                    return Some((
+                        VersionSpec::Version(stable.to_owned()),
+                        vec![
+                            format!("expanded alias chain {}", format_alias_chain(&chain)),
+                            format!(
+                                "{latest} is a prerelease, using the highest stable version {stable} instead"
+                            ),
+                        ],
+                    ));
+                }
             }
 
-            if alias_value.is_none() {
-                alias_value = aliases.get(alias);
-            }
+            if !matches!(value, UnresolvedVersionSpec::Alias(_)) {
+                let (version, mut steps) = explain_version_with_prereleases(
+                    value, versions, aliases, manifest, config, include,
+                )?;
 
-            if let Some(value) = alias_value {
-                return resolve_version(value, versions, aliases, manifest, config);
+                steps.insert(
+                    0,
+                    format!("expanded alias chain {}", format_alias_chain(&chain)),
+                );
+
+                return Some((version, steps));
             }
         }
         UnresolvedVersionSpec::Req(req) => {
             // Check locally installed versions first
             if !installed_versions.is_empty() {
-                if let Some(version) = match_highest_version(req, &installed_versions) {
-                    return Some(version);
+                if let Some(version) =
+                    match_highest_version_with_prereleases(req, &installed_versions, include)
+                {
+                    return Some((
+                        version,
+                        vec![format!("highest installed version matching {req}")],
+                    ));
                 }
             }
 
             // Otherwise we'll need to download from remote
-            if let Some(version) = match_highest_version(req, &remote_versions) {
-                return Some(version);
+            if let Some(version) =
+                match_highest_version_with_prereleases(req, &remote_versions, include)
+            {
+                return Some((
+                    version,
+                    vec![format!("highest available version matching {req}")],
+                ));
             }
         }
         UnresolvedVersionSpec::ReqAny(reqs) => {
             // Check locally installed versions first
             if !installed_versions.is_empty() {
                 for req in reqs {
-                    if let Some(version) = match_highest_version(req, &installed_versions) {
-                        return Some(version);
+                    if let Some(version) =
+                        match_highest_version_with_prereleases(req, &installed_versions, include)
+                    {
+                        return Some((
+                            version,
+                            vec![format!(
+                                "highest installed version matching {req} (first matching requirement of {candidate})"
+                            )],
+                        ));
                     }
                 }
             }
 
             // Otherwise we'll need to download from remote
             for req in reqs {
-                if let Some(version) = match_highest_version(req, &remote_versions) {
-                    return Some(version);
+                if let Some(version) =
+                    match_highest_version_with_prereleases(req, &remote_versions, include)
+                {
+                    return Some((
+                        version,
+                        vec![format!(
+                            "highest available version matching {req} (first matching requirement of {candidate})"
+                        )],
+                    ));
                 }
             }
         }
+        UnresolvedVersionSpec::Composite(specs) => {
+            // Cycles are unresolvable
+            let specs = expand_composite_spec(specs, aliases, config).ok()?;
+            let mut highest: Option<(VersionSpec, Vec<String>)> = None;
+
+            for spec in &specs {
+                let Some((version, steps)) = explain_version_with_prereleases(
+                    spec, versions, aliases, manifest, config, include,
+                ) else {
+                    continue;
+                };
+
+                let is_higher = match (&highest, &version) {
+                    (None, _) => true,
+                    (Some((VersionSpec::Version(prev), _)), VersionSpec::Version(next)) => {
+                        next > prev
+                    }
+                    _ => false,
+                };
+
+                if is_higher {
+                    highest = Some((version, steps));
+                }
+            }
+
+            if let Some((version, mut steps)) = highest {
+                steps.push(format!(
+                    "highest version {version} of composite {candidate}"
+                ));
+
+                return Some((version, steps));
+            }
+        }
         UnresolvedVersionSpec::Version(ver) => {
             // Check locally installed versions first
             if installed_versions.contains(&ver) {
-                return Some(VersionSpec::Version(ver.to_owned()));
+                return Some((
+                    VersionSpec::Version(ver.to_owned()),
+                    vec![format!("exact version {ver} is installed")],
+                ));
             }
 
             // Otherwise we'll need to download from remote
             for version in versions {
                 if ver == version {
-                    return Some(VersionSpec::Version(ver.to_owned()));
+                    return Some((
+                        VersionSpec::Version(ver.to_owned()),
+                        vec![format!("exact version {ver} is available")],
+                    ));
                 }
             }
         }
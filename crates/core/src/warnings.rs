@@ -0,0 +1,120 @@
+use crate::helpers::now;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use starbase_utils::json;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+use tracing::trace;
+
+pub const HOST_WARNINGS_STATE: &str = ".warnings.json";
+
+/// A deprecation or breaking change in proto itself (not a plugin),
+/// that users are warned about when they rely on it.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct HostWarning {
+    /// Unique ID, used for silencing with the `silence-warnings` setting.
+    pub id: &'static str,
+
+    pub message: &'static str,
+
+    /// Link to documentation on how to migrate.
+    pub url: &'static str,
+}
+
+/// All deprecations that proto can warn about.
+pub const HOST_WARNINGS: &[HostWarning] = &[
+    HostWarning {
+        id: "proto-root-env-var",
+        message: "The PROTO_ROOT environment variable is deprecated, use PROTO_HOME instead.",
+        url: "https://moonrepo.dev/docs/proto/config#environment-variables",
+    },
+    HostWarning {
+        id: "setup-profile-arg",
+        message: "The --profile option of proto setup is deprecated and does nothing.",
+        url: "https://moonrepo.dev/docs/proto/commands/setup",
+    },
+    HostWarning {
+        id: "tool-command",
+        message: "The proto tool command has been renamed to proto plugin.",
+        url: "https://moonrepo.dev/docs/proto/commands/plugin",
+    },
+];
+
+static TRIGGERED_WARNINGS: Lazy<Mutex<BTreeSet<&'static str>>> =
+    Lazy::new(|| Mutex::new(BTreeSet::new()));
+
+pub fn find_host_warning(id: &str) -> Option<&'static HostWarning> {
+    HOST_WARNINGS.iter().find(|warning| warning.id == id)
+}
+
+/// Mark the warning with the provided ID as triggered by the current process,
+/// so that it can be rendered when the process finishes.
+pub fn trigger_host_warning(id: &str) {
+    if let Some(warning) = find_host_warning(id) {
+        trace!(warning = id, "Triggered a host warning");
+
+        TRIGGERED_WARNINGS.lock().unwrap().insert(warning.id);
+    }
+}
+
+/// Return and clear the warnings triggered by the current process.
+pub fn take_triggered_host_warnings() -> Vec<&'static HostWarning> {
+    std::mem::take(&mut *TRIGGERED_WARNINGS.lock().unwrap())
+        .into_iter()
+        .filter_map(find_host_warning)
+        .collect()
+}
+
+/// Tracks when each warning was last rendered, so that
+/// each warning is only rendered once per day.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct HostWarningsState {
+    /// Mapping of warning IDs to a timestamp (in milliseconds).
+    pub last_shown_at: BTreeMap<String, u128>,
+
+    #[serde(skip)]
+    pub path: PathBuf,
+}
+
+impl HostWarningsState {
+    pub fn load_from<P: AsRef<Path>>(dir: P) -> miette::Result<Self> {
+        let path = dir.as_ref().join(HOST_WARNINGS_STATE);
+
+        let mut state: HostWarningsState = if path.exists() {
+            json::read_file(&path).unwrap_or_default()
+        } else {
+            HostWarningsState::default()
+        };
+
+        state.path = path;
+
+        Ok(state)
+    }
+
+    pub fn save(&self) -> miette::Result<()> {
+        json::write_file(&self.path, self, false)?;
+
+        Ok(())
+    }
+
+    /// Return true if the warning has not been rendered in the last day,
+    /// and mark it as rendered.
+    pub fn should_show(&mut self, id: &str) -> bool {
+        let timestamp = now();
+
+        if self
+            .last_shown_at
+            .get(id)
+            .is_some_and(|last| last + Duration::from_secs(86400).as_millis() > timestamp)
+        {
+            return false;
+        }
+
+        self.last_shown_at.insert(id.to_owned(), timestamp);
+
+        true
+    }
+}
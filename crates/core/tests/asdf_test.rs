@@ -0,0 +1,212 @@
+#![cfg(unix)]
+
+use proto_core::{AsdfPlugin, Id};
+use semver::Version;
+use starbase_sandbox::{create_empty_sandbox, Sandbox};
+use std::os::unix::fs::PermissionsExt;
+use std::path::PathBuf;
+
+fn create_plugin(sandbox: &Sandbox) -> AsdfPlugin {
+    AsdfPlugin::new(
+        &Id::raw("tool"),
+        sandbox.path().join("plugin"),
+        "https://github.com/org/asdf-tool.git".into(),
+    )
+}
+
+mod asdf {
+    use super::*;
+
+    #[test]
+    fn lists_semver_versions() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(
+            "plugin/bin/list-all",
+            "echo \"1.0.0 1.2.0 2.0.0-rc.1 lts nightly 1.10.3\"",
+        );
+
+        let output = create_plugin(&sandbox).list_all().unwrap();
+
+        assert_eq!(
+            output.versions,
+            vec![
+                Version::new(1, 0, 0),
+                Version::new(1, 2, 0),
+                Version::parse("2.0.0-rc.1").unwrap(),
+                Version::new(1, 10, 3),
+            ]
+        );
+        assert_eq!(output.latest, Some(Version::new(1, 10, 3)));
+    }
+
+    #[test]
+    fn clears_environment_for_scripts() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(
+            "plugin/bin/list-all",
+            "echo \"${ASDF_PLUGIN_TEST_SECRET:-1.0.0}\"",
+        );
+
+        std::env::set_var("ASDF_PLUGIN_TEST_SECRET", "9.9.9");
+
+        let output = create_plugin(&sandbox).list_all().unwrap();
+
+        std::env::remove_var("ASDF_PLUGIN_TEST_SECRET");
+
+        assert_eq!(output.versions, vec![Version::new(1, 0, 0)]);
+    }
+
+    #[test]
+    fn installs_with_asdf_env_vars() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(
+            "plugin/bin/download",
+            "echo \"$ASDF_INSTALL_VERSION\" > \"$ASDF_DOWNLOAD_PATH/archive\"",
+        );
+        sandbox.create_file(
+            "plugin/bin/install",
+            "mkdir -p \"$ASDF_INSTALL_PATH/bin\" && cp \"$ASDF_DOWNLOAD_PATH/archive\" \"$ASDF_INSTALL_PATH/bin/tool\"",
+        );
+
+        let install_dir = sandbox.path().join("tools/tool/1.2.3");
+        let download_dir = sandbox.path().join("temp/tool/1.2.3");
+
+        create_plugin(&sandbox)
+            .install("1.2.3", &install_dir, &download_dir)
+            .unwrap();
+
+        assert_eq!(
+            std::fs::read_to_string(install_dir.join("bin/tool")).unwrap(),
+            "1.2.3\n"
+        );
+        assert!(!download_dir.exists());
+    }
+
+    #[test]
+    fn errors_when_install_fails() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file("plugin/bin/install", "exit 1");
+
+        let result = create_plugin(&sandbox).install(
+            "1.2.3",
+            &sandbox.path().join("tools/tool/1.2.3"),
+            &sandbox.path().join("temp/tool/1.2.3"),
+        );
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn errors_for_missing_script() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file("plugin/bin/install", "exit 0");
+
+        assert!(create_plugin(&sandbox).list_all().is_err());
+    }
+
+    #[test]
+    fn errors_for_scripts_outside_bin() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file("plugin/lib/utils", "echo 1.0.0");
+
+        assert!(create_plugin(&sandbox)
+            .run_script("../lib/utils", &[])
+            .is_err());
+    }
+
+    #[test]
+    fn locates_executable_in_bin_by_default() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file("plugin/bin/install", "exit 0");
+
+        let output = create_plugin(&sandbox)
+            .locate_executables("1.2.3", &sandbox.path().join("tools/tool/1.2.3"))
+            .unwrap();
+
+        assert_eq!(
+            output.primary.unwrap().exe_path,
+            Some(PathBuf::from("bin/tool"))
+        );
+    }
+
+    #[test]
+    fn locates_executable_with_list_bin_paths() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file("plugin/bin/list-bin-paths", "echo \"usr/bin lib/bin\"");
+
+        let output = create_plugin(&sandbox)
+            .locate_executables("1.2.3", &sandbox.path().join("tools/tool/1.2.3"))
+            .unwrap();
+
+        assert_eq!(
+            output.primary.unwrap().exe_path,
+            Some(PathBuf::from("usr/bin/tool"))
+        );
+    }
+
+    #[test]
+    fn locates_all_installed_executables() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file("plugin/bin/install", "exit 0");
+        sandbox.create_file("tools/tool/1.2.3/bin/helper", "");
+        sandbox.create_file("tools/tool/1.2.3/bin/tool", "");
+        sandbox.create_file("tools/tool/1.2.3/bin/README", "");
+
+        for name in ["helper", "tool"] {
+            std::fs::set_permissions(
+                sandbox.path().join("tools/tool/1.2.3/bin").join(name),
+                std::fs::Permissions::from_mode(0o755),
+            )
+            .unwrap();
+        }
+
+        let output = create_plugin(&sandbox)
+            .locate_executables("1.2.3", &sandbox.path().join("tools/tool/1.2.3"))
+            .unwrap();
+
+        assert_eq!(
+            output.primary.unwrap().exe_path,
+            Some(PathBuf::from("bin/tool"))
+        );
+        assert_eq!(output.secondary.len(), 1);
+        assert_eq!(
+            output.secondary.get("helper").unwrap().exe_path,
+            Some(PathBuf::from("bin/helper"))
+        );
+    }
+
+    #[test]
+    fn loads_exec_env_vars() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(
+            "plugin/bin/exec-env",
+            "export TOOL_HOME=\"$ASDF_INSTALL_PATH/home\"",
+        );
+
+        let install_dir = sandbox.path().join("tools/tool/1.2.3");
+        let env_vars = create_plugin(&sandbox)
+            .load_exec_env("1.2.3", &install_dir)
+            .unwrap();
+
+        assert_eq!(env_vars.len(), 1);
+        assert_eq!(
+            env_vars.get("TOOL_HOME").unwrap(),
+            &format!("{}/home", install_dir.display())
+        );
+    }
+
+    #[test]
+    fn loads_recorded_revision() {
+        let sandbox = create_empty_sandbox();
+        let plugin = create_plugin(&sandbox);
+
+        assert_eq!(plugin.load_recorded_revision().unwrap(), None);
+
+        sandbox.create_file("plugin/.proto-rev", "v1.0.0\na1b2c3d\n");
+
+        assert_eq!(
+            plugin.load_recorded_revision().unwrap(),
+            Some(("v1.0.0".into(), "a1b2c3d".into()))
+        );
+    }
+}
@@ -0,0 +1,79 @@
+#![cfg(unix)]
+
+use proto_core::{apply_cache_permissions, find_permission_mismatches, CachePermissions};
+use starbase_sandbox::create_empty_sandbox;
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+
+fn get_mode(path: &std::path::Path) -> u32 {
+    fs::metadata(path).unwrap().permissions().mode() & 0o7777
+}
+
+mod cache_permissions {
+    use super::*;
+
+    #[test]
+    fn inherit_respects_umask() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file("cache/file", "");
+
+        let file = sandbox.path().join("cache/file");
+        fs::set_permissions(&file, fs::Permissions::from_mode(0o600)).unwrap();
+
+        assert!(find_permission_mismatches(
+            &sandbox.path().join("cache"),
+            sandbox.path(),
+            &CachePermissions::Inherit
+        )
+        .is_empty());
+
+        apply_cache_permissions(
+            &sandbox.path().join("cache"),
+            sandbox.path(),
+            &CachePermissions::Inherit,
+        );
+
+        assert_eq!(get_mode(&file), 0o600);
+    }
+
+    #[test]
+    fn group_reports_and_applies_modes() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file("cache/file", "");
+        sandbox.create_file("cache/exe", "");
+
+        let dir = sandbox.path().join("cache");
+        let file = dir.join("file");
+        let exe = dir.join("exe");
+
+        fs::set_permissions(&dir, fs::Permissions::from_mode(0o700)).unwrap();
+        fs::set_permissions(&file, fs::Permissions::from_mode(0o600)).unwrap();
+        fs::set_permissions(&exe, fs::Permissions::from_mode(0o700)).unwrap();
+
+        assert_eq!(
+            find_permission_mismatches(&dir, sandbox.path(), &CachePermissions::Group).len(),
+            3
+        );
+
+        apply_cache_permissions(&dir, sandbox.path(), &CachePermissions::Group);
+
+        assert_eq!(get_mode(&dir), 0o2775);
+        assert_eq!(get_mode(&file), 0o664);
+        assert_eq!(get_mode(&exe), 0o775);
+        assert!(
+            find_permission_mismatches(&dir, sandbox.path(), &CachePermissions::Group).is_empty()
+        );
+    }
+
+    #[test]
+    fn world_applies_modes() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file("cache/file", "");
+
+        let file = sandbox.path().join("cache/file");
+
+        apply_cache_permissions(&file, sandbox.path(), &CachePermissions::World);
+
+        assert_eq!(get_mode(&file), 0o666);
+    }
+}
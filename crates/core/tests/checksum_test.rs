@@ -0,0 +1,151 @@
+use proto_core::{
+    extract_expected_checksum, hash_file_contents_with, parse_checksum_line, verify_checksum,
+    ChecksumEntry, ChecksumOptions,
+};
+use proto_pdk_api::{ChecksumAlgorithm, ChecksumFormat};
+use starbase_sandbox::create_empty_sandbox;
+
+mod checksum {
+    use super::*;
+
+    const SHA256: &str = "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
+    const SHA512: &str = "ddaf35a193617abacc417349ae20413112e6fa4e89a97ea20a9eeee64b55d39a2192992a274fc1a836ba3c23a3feebbd454d4423643ce80e2a9ac94fa54ca49f";
+    const BLAKE3: &str = "6437b3ac38465133ffb63b75273a8db548c558465d79db03fd359c6cd5bd9d85";
+
+    fn options(algorithm: ChecksumAlgorithm, format: ChecksumFormat) -> ChecksumOptions {
+        ChecksumOptions {
+            algorithm,
+            format,
+            public_key: None,
+        }
+    }
+
+    #[test]
+    fn hashes_with_each_algorithm() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file("file.tar.gz", "abc");
+
+        let file = sandbox.path().join("file.tar.gz");
+
+        assert_eq!(
+            hash_file_contents_with(&file, ChecksumAlgorithm::Sha256).unwrap(),
+            SHA256
+        );
+        assert_eq!(
+            hash_file_contents_with(&file, ChecksumAlgorithm::Sha512).unwrap(),
+            SHA512
+        );
+        assert_eq!(
+            hash_file_contents_with(&file, ChecksumAlgorithm::Blake3).unwrap(),
+            BLAKE3
+        );
+    }
+
+    #[test]
+    fn parses_each_format() {
+        let gnu = ChecksumEntry {
+            file: Some("file.tar.gz".into()),
+            hash: "abc123".into(),
+        };
+
+        assert_eq!(
+            parse_checksum_line("abc123  file.tar.gz", ChecksumFormat::Gnu).unwrap(),
+            gnu
+        );
+        assert_eq!(
+            parse_checksum_line("abc123 *file.tar.gz", ChecksumFormat::Gnu).unwrap(),
+            gnu
+        );
+        assert_eq!(
+            parse_checksum_line("SHA512 (file.tar.gz) = abc123", ChecksumFormat::Bsd).unwrap(),
+            gnu
+        );
+        assert_eq!(
+            parse_checksum_line("abc123\n", ChecksumFormat::Single).unwrap(),
+            ChecksumEntry {
+                file: None,
+                hash: "abc123".into(),
+            }
+        );
+        assert!(parse_checksum_line("abc123  file.tar.gz", ChecksumFormat::Bsd).is_none());
+    }
+
+    #[test]
+    fn verifies_bsd_sha512() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file("file.tar.gz", "abc");
+        sandbox.create_file(
+            "SHASUMS",
+            format!("SHA512 (other.tar.gz) = {SHA256}\nSHA512 (file.tar.gz) = {SHA512}\n"),
+        );
+
+        assert!(verify_checksum(
+            &sandbox.path().join("file.tar.gz"),
+            &sandbox.path().join("SHASUMS"),
+            &options(ChecksumAlgorithm::Sha512, ChecksumFormat::Auto),
+        )
+        .unwrap());
+    }
+
+    #[test]
+    fn verifies_gnu_blake3() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file("file.tar.gz", "abc");
+        sandbox.create_file("b3sums", format!("{BLAKE3}  dist/file.tar.gz\n"));
+
+        assert!(verify_checksum(
+            &sandbox.path().join("file.tar.gz"),
+            &sandbox.path().join("b3sums"),
+            &options(ChecksumAlgorithm::Blake3, ChecksumFormat::Gnu),
+        )
+        .unwrap());
+    }
+
+    #[test]
+    fn doesnt_verify_hash_for_other_file() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file("file.tar.gz", "abc");
+        sandbox.create_file("SHASUMS", format!("{SHA256}  other.tar.gz\n"));
+
+        assert!(!verify_checksum(
+            &sandbox.path().join("file.tar.gz"),
+            &sandbox.path().join("SHASUMS"),
+            &ChecksumOptions::default(),
+        )
+        .unwrap());
+    }
+
+    #[test]
+    fn extracts_expected_checksum_for_file() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file("file.tar.gz", "abc");
+        sandbox.create_file(
+            "SHASUMS",
+            "# comment\naaa111  other.tar.gz\nbbb222  file.tar.gz\n",
+        );
+
+        assert_eq!(
+            extract_expected_checksum(
+                &sandbox.path().join("file.tar.gz"),
+                &sandbox.path().join("SHASUMS"),
+                &ChecksumOptions::default(),
+            )
+            .unwrap(),
+            "bbb222"
+        );
+    }
+
+    #[test]
+    fn requires_public_key_for_signatures() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file("file.tar.gz", "abc");
+        sandbox.create_file("file.tar.gz.minisig", "");
+
+        assert!(verify_checksum(
+            &sandbox.path().join("file.tar.gz"),
+            &sandbox.path().join("file.tar.gz.minisig"),
+            &ChecksumOptions::default(),
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,69 @@
+use proto_core::{
+    ConfigChangeStatus, ConfigJournal, ConfigJournalEntry, Id, ProtoEnvironment,
+    UnresolvedVersionSpec,
+};
+use proto_pdk_api::ConfigChange;
+use starbase_sandbox::create_empty_sandbox;
+
+mod config_journal {
+    use super::*;
+
+    fn create_entry(alias: &str, status: ConfigChangeStatus) -> ConfigJournalEntry {
+        ConfigJournalEntry {
+            change: ConfigChange::AddAlias {
+                alias: alias.into(),
+                version: UnresolvedVersionSpec::parse("20").unwrap(),
+            },
+            plugin: None,
+            reason: None,
+            recorded_at: 0,
+            status,
+            tool: Id::raw("node"),
+        }
+    }
+
+    #[test]
+    fn records_entries() {
+        let sandbox = create_empty_sandbox();
+        let proto = ProtoEnvironment::new_testing(sandbox.path());
+
+        assert!(ConfigJournal::load(&proto).unwrap().is_empty());
+
+        ConfigJournal::record(&proto, create_entry("work", ConfigChangeStatus::Applied)).unwrap();
+
+        let entries = ConfigJournal::load(&proto).unwrap();
+
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].status, ConfigChangeStatus::Applied);
+        assert_ne!(entries[0].recorded_at, 0);
+    }
+
+    #[test]
+    fn keeps_all_entries() {
+        let sandbox = create_empty_sandbox();
+        let proto = ProtoEnvironment::new_testing(sandbox.path());
+
+        for i in 0..150 {
+            ConfigJournal::record(
+                &proto,
+                create_entry(&format!("alias{i}"), ConfigChangeStatus::Declined),
+            )
+            .unwrap();
+        }
+
+        assert_eq!(ConfigJournal::load(&proto).unwrap().len(), 150);
+    }
+
+    #[test]
+    fn serializes_change_with_type() {
+        let sandbox = create_empty_sandbox();
+        let proto = ProtoEnvironment::new_testing(sandbox.path());
+
+        ConfigJournal::record(&proto, create_entry("work", ConfigChangeStatus::Rejected)).unwrap();
+
+        let content = std::fs::read_to_string(ConfigJournal::get_path(&proto)).unwrap();
+
+        assert!(content.contains("\"type\": \"add-alias\""));
+        assert!(content.contains("\"status\": \"rejected\""));
+    }
+}
@@ -0,0 +1,128 @@
+use proto_core::DownloadCache;
+use starbase_sandbox::create_empty_sandbox;
+
+mod download_cache {
+    use super::*;
+
+    #[test]
+    fn stores_blobs_by_content() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file("a.part", "archive");
+        sandbox.create_file("b.part", "archive");
+
+        let mut cache = DownloadCache::load(sandbox.path().join("cache")).unwrap();
+        let a = cache
+            .insert_blob(
+                "https://a.com/node.tar.gz",
+                &sandbox.path().join("a.part"),
+                ".tar.gz",
+            )
+            .unwrap();
+        let b = cache
+            .insert_blob(
+                "https://b.com/node.tar.gz",
+                &sandbox.path().join("b.part"),
+                ".tar.gz",
+            )
+            .unwrap();
+
+        assert_eq!(a, b);
+        assert!(a.exists());
+        assert!(a.to_string_lossy().ends_with(".tar.gz"));
+        assert!(a.starts_with(sandbox.path().join("cache/blobs")));
+        assert!(!sandbox.path().join("a.part").exists());
+        assert!(!sandbox.path().join("b.part").exists());
+        assert_eq!(cache.refs.len(), 2);
+        assert_eq!(cache.refs["https://a.com/node.tar.gz"].size, 7);
+    }
+
+    #[test]
+    fn saves_and_loads_index() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file("a.part", "archive");
+
+        let mut cache = DownloadCache::load(sandbox.path().join("cache")).unwrap();
+        let a = cache
+            .insert_blob(
+                "https://a.com/node.zip",
+                &sandbox.path().join("a.part"),
+                ".zip",
+            )
+            .unwrap();
+
+        cache.save().unwrap();
+
+        let cache = DownloadCache::load(sandbox.path().join("cache")).unwrap();
+
+        assert_eq!(cache.get_blob_path("https://a.com/node.zip").unwrap(), a);
+        assert_eq!(cache.get_blob_path("https://a.com/unknown.zip"), None);
+    }
+
+    #[test]
+    fn uses_a_stable_partial_path_per_url() {
+        let sandbox = create_empty_sandbox();
+        let cache = DownloadCache::load(sandbox.path().join("cache")).unwrap();
+
+        assert_eq!(
+            cache.get_partial_path("https://a.com/node.zip"),
+            cache.get_partial_path("https://a.com/node.zip")
+        );
+        assert_ne!(
+            cache.get_partial_path("https://a.com/node.zip"),
+            cache.get_partial_path("https://b.com/node.zip")
+        );
+        assert!(cache
+            .get_partial_path("https://a.com/node.zip")
+            .starts_with(sandbox.path().join("cache/partials")));
+    }
+
+    #[test]
+    fn removes_unreferenced_blobs() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file("a.part", "a");
+        sandbox.create_file("b.part", "b");
+
+        let mut cache = DownloadCache::load(sandbox.path().join("cache")).unwrap();
+        let a = cache
+            .insert_blob(
+                "https://a.com/a.zip",
+                &sandbox.path().join("a.part"),
+                ".zip",
+            )
+            .unwrap();
+        let b = cache
+            .insert_blob(
+                "https://a.com/b.zip",
+                &sandbox.path().join("b.part"),
+                ".zip",
+            )
+            .unwrap();
+
+        cache.refs.remove("https://a.com/b.zip");
+
+        assert_eq!(cache.remove_unreferenced_blobs().unwrap(), vec![b.clone()]);
+        assert!(a.exists());
+        assert!(!b.exists());
+    }
+
+    #[test]
+    fn clears_blobs_and_partials() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file("a.part", "archive");
+        sandbox.create_file("cache/partials/abc.part", "arc");
+
+        let mut cache = DownloadCache::load(sandbox.path().join("cache")).unwrap();
+        let a = cache
+            .insert_blob(
+                "https://a.com/a.zip",
+                &sandbox.path().join("a.part"),
+                ".zip",
+            )
+            .unwrap();
+
+        assert_eq!(cache.clear().unwrap(), 10);
+        assert!(cache.refs.is_empty());
+        assert!(!a.exists());
+        assert!(!sandbox.path().join("cache/partials/abc.part").exists());
+    }
+}
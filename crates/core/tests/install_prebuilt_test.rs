@@ -0,0 +1,76 @@
+use proto_core::{
+    load_tool_from_locator, ProtoConfig, ProtoEnvironment, ProtoError, ProtoEvent, Tool,
+    VersionSpec,
+};
+use starbase_sandbox::{create_empty_sandbox, Sandbox};
+use std::fs;
+use tokio::sync::mpsc;
+use warpgate::Id;
+
+async fn load_node(sandbox: &Sandbox) -> Tool {
+    // Folders must exist for WASM to compile correctly!
+    fs::create_dir_all(sandbox.path().join(".home")).unwrap();
+    fs::create_dir_all(sandbox.path().join(".proto")).unwrap();
+
+    let mut tool = load_tool_from_locator(
+        Id::raw("node"),
+        ProtoEnvironment::new_testing(sandbox.path()),
+        ProtoConfig::builtin_plugins().get("node").unwrap(),
+    )
+    .await
+    .unwrap();
+
+    tool.set_version(VersionSpec::parse("20.0.0").unwrap());
+    tool
+}
+
+mod install_from_prebuilt {
+    use super::*;
+
+    #[tokio::test]
+    async fn downloads_and_verifies_checksum() {
+        let sandbox = create_empty_sandbox();
+        let mut tool = load_node(&sandbox).await;
+        let install_dir = sandbox.path().join("install");
+
+        let (reporter, mut events) = mpsc::unbounded_channel();
+        tool.set_reporter(reporter);
+
+        let locked = tool.install_from_prebuilt(&install_dir).await.unwrap();
+
+        assert!(locked.checksum.is_some());
+        assert!(tool.get_temp_dir().join("SHASUMS256.txt").exists());
+        assert!(install_dir.join("bin").exists() || install_dir.join("node.exe").exists());
+
+        let mut verified = false;
+
+        while let Ok(event) = events.try_recv() {
+            if matches!(event, ProtoEvent::Verifying { .. }) {
+                verified = true;
+            }
+        }
+
+        assert!(verified);
+    }
+
+    #[tokio::test]
+    async fn errors_when_checksum_doesnt_match() {
+        let sandbox = create_empty_sandbox();
+        let mut tool = load_node(&sandbox).await;
+        let install_dir = sandbox.path().join("install");
+
+        // Existing checksum files are not downloaded again
+        let checksum_file = tool.get_temp_dir().join("SHASUMS256.txt");
+
+        fs::create_dir_all(checksum_file.parent().unwrap()).unwrap();
+        fs::write(&checksum_file, "0".repeat(64)).unwrap();
+
+        let error = tool.install_from_prebuilt(&install_dir).await.unwrap_err();
+
+        assert!(matches!(
+            error.downcast_ref::<ProtoError>(),
+            Some(ProtoError::InvalidChecksum { .. })
+        ));
+        assert!(!install_dir.exists());
+    }
+}
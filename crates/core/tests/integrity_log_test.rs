@@ -0,0 +1,66 @@
+use proto_core::{now, Id, IntegrityEvent, IntegrityLog, ProtoEnvironment};
+use starbase_sandbox::create_empty_sandbox;
+use std::time::Duration;
+
+mod integrity_log {
+    use super::*;
+
+    fn create_event(version: &str, recorded_at: u128) -> IntegrityEvent {
+        IntegrityEvent {
+            actual_digest: Some("abc".into()),
+            download_url: format!("https://example.com/node-{version}.tar.gz"),
+            expected_digest: Some("def".into()),
+            recorded_at,
+            tool: Id::raw("node"),
+            version: version.into(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn records_events() {
+        let sandbox = create_empty_sandbox();
+        let proto = ProtoEnvironment::new_testing(sandbox.path());
+
+        assert!(IntegrityLog::load(&proto).unwrap().is_empty());
+
+        IntegrityLog::record(&proto, create_event("20.0.0", 0)).unwrap();
+
+        let events = IntegrityLog::load(&proto).unwrap();
+
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].version, "20.0.0");
+        assert_ne!(events[0].recorded_at, 0);
+    }
+
+    #[test]
+    fn only_keeps_most_recent_events() {
+        let sandbox = create_empty_sandbox();
+        let proto = ProtoEnvironment::new_testing(sandbox.path());
+
+        for i in 0..60 {
+            IntegrityLog::record(&proto, create_event(&format!("{i}.0.0"), 0)).unwrap();
+        }
+
+        let events = IntegrityLog::load(&proto).unwrap();
+
+        assert_eq!(events.len(), 50);
+        assert_eq!(events[0].version, "10.0.0");
+    }
+
+    #[test]
+    fn filters_recent_events() {
+        let sandbox = create_empty_sandbox();
+        let proto = ProtoEnvironment::new_testing(sandbox.path());
+        let day = Duration::from_secs(60 * 60 * 24).as_millis();
+
+        IntegrityLog::record(&proto, create_event("18.0.0", now() - day * 10)).unwrap();
+        IntegrityLog::record(&proto, create_event("20.0.0", now() - day)).unwrap();
+
+        let events =
+            IntegrityLog::load_recent(&proto, Duration::from_secs(60 * 60 * 24 * 7)).unwrap();
+
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].version, "20.0.0");
+    }
+}
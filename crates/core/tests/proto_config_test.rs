@@ -1,14 +1,17 @@
 use indexmap::IndexMap;
 use proto_core::{
-    DetectStrategy, EnvVar, PartialEnvVar, PartialProtoSettingsConfig, PinType, ProtoConfig,
-    ProtoConfigManager,
+    find_repo_root, DateFormat, DetectStrategy, EnvVar, PartialEnvVar, PartialProtoSettingsConfig,
+    PinType, ProtoConfig, ProtoConfigManager, ProtoEnvironment, ProtoToolPermissionsConfig,
+    StoreType,
 };
 use schematic::ConfigError;
 use starbase_sandbox::create_empty_sandbox;
 use starbase_utils::json::JsonValue;
 use std::collections::BTreeMap;
 use std::env;
+use std::time::Duration;
 use version_spec::UnresolvedVersionSpec;
+use warpgate::api::PluginPermissions;
 use warpgate::{GitHubLocator, HttpOptions, Id, PluginLocator};
 
 fn handle_error(report: miette::Report) {
@@ -60,7 +63,10 @@ mod proto_config {
 [settings]
 auto-clean = true
 auto-install = true
+include-prereleases = true
+install-prompt = false
 pin-latest = "global"
+store = "project"
 "#,
         );
 
@@ -71,12 +77,348 @@ pin-latest = "global"
             PartialProtoSettingsConfig {
                 auto_clean: Some(true),
                 auto_install: Some(true),
+                include_prereleases: Some(true),
+                install_prompt: Some(false),
                 pin_latest: Some(PinType::Global),
+                store: Some(StoreType::Project),
                 ..Default::default()
             }
         );
     }
 
+    #[test]
+    #[should_panic(expected = "invalid version list")]
+    fn errors_for_invalid_fallback_versions() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(".prototools", "node = [\"20.11.0\", 123]");
+
+        handle_error(ProtoConfig::load_from(sandbox.path(), false).unwrap_err());
+    }
+
+    #[test]
+    fn can_set_fallback_versions() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(
+            ".prototools",
+            r#"
+node = ["20.11.0", "^20", "lts"]
+npm = "10.0.0"
+"#,
+        );
+
+        let config = ProtoConfig::load_from(sandbox.path(), false).unwrap();
+
+        assert_eq!(
+            config.versions.unwrap(),
+            BTreeMap::from_iter([
+                (
+                    Id::raw("node"),
+                    UnresolvedVersionSpec::parse("20.11.0").unwrap()
+                ),
+                (
+                    Id::raw("npm"),
+                    UnresolvedVersionSpec::parse("10.0.0").unwrap()
+                ),
+            ])
+        );
+        assert_eq!(
+            config.fallback_versions.unwrap(),
+            BTreeMap::from_iter([(
+                Id::raw("node"),
+                vec![
+                    UnresolvedVersionSpec::parse("20.11.0").unwrap(),
+                    UnresolvedVersionSpec::parse("^20").unwrap(),
+                    UnresolvedVersionSpec::Alias("lts".into()),
+                ]
+            )])
+        );
+    }
+
+    #[test]
+    fn preserves_fallback_versions_when_saving() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(
+            ".prototools",
+            r#"
+node = ["20.11.0", "^20"]
+npm = ["10.0.0", "latest"]
+"#,
+        );
+
+        ProtoConfig::update(sandbox.path(), |config| {
+            config.versions.get_or_insert(Default::default()).insert(
+                Id::raw("npm"),
+                UnresolvedVersionSpec::parse("9.0.0").unwrap(),
+            );
+        })
+        .unwrap();
+
+        let config = ProtoConfig::load_from(sandbox.path(), false).unwrap();
+
+        assert_eq!(
+            config.fallback_versions.unwrap(),
+            BTreeMap::from_iter([(
+                Id::raw("node"),
+                vec![
+                    UnresolvedVersionSpec::parse("20.11.0").unwrap(),
+                    UnresolvedVersionSpec::parse("^20").unwrap(),
+                ]
+            )])
+        );
+        assert_eq!(
+            config.versions.unwrap().get("npm").unwrap(),
+            &UnresolvedVersionSpec::parse("9.0.0").unwrap()
+        );
+    }
+
+    #[test]
+    fn can_set_shims_priority() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(
+            ".prototools",
+            r#"
+[settings.shims]
+priority = ["python", "pypy"]
+"#,
+        );
+
+        let manager = ProtoConfigManager::load(sandbox.path(), None, None).unwrap();
+        let config = manager.get_merged_config().unwrap();
+
+        assert_eq!(
+            config.settings.shims.priority,
+            vec![Id::raw("python"), Id::raw("pypy")]
+        );
+    }
+
+    #[test]
+    fn can_set_clean_settings() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(
+            ".prototools",
+            r#"
+[settings.clean]
+auto-threshold = 500
+keep-latest = 2
+protected = { node = ["18", "20.1.0"] }
+"#,
+        );
+
+        let manager = ProtoConfigManager::load(sandbox.path(), None, None).unwrap();
+        let config = manager.get_merged_config().unwrap();
+        let settings = &config.settings.clean;
+
+        assert_eq!(settings.auto_threshold, Some(500));
+        assert_eq!(settings.keep_latest, 2);
+        assert_eq!(settings.stale_days, 30);
+        assert_eq!(
+            settings.protected.get("node").unwrap(),
+            &vec![
+                UnresolvedVersionSpec::parse("18").unwrap(),
+                UnresolvedVersionSpec::parse("20.1.0").unwrap()
+            ]
+        );
+    }
+
+    #[test]
+    fn can_set_policies() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(
+            ".prototools",
+            r#"
+[policies]
+max-age = "18 months"
+
+[policies.max-age-by-tool]
+python = "3 years"
+"#,
+        );
+
+        let manager = ProtoConfigManager::load(sandbox.path(), None, None).unwrap();
+        let config = manager.get_merged_config().unwrap();
+
+        assert_eq!(
+            config.policies.get_max_age(&Id::raw("node")).unwrap(),
+            Some(Duration::from_secs(18 * 30 * 24 * 60 * 60))
+        );
+        assert_eq!(
+            config.policies.get_max_age(&Id::raw("python")).unwrap(),
+            Some(Duration::from_secs(3 * 365 * 24 * 60 * 60))
+        );
+    }
+
+    #[test]
+    fn can_set_plugin_quota() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(
+            ".prototools",
+            r#"
+[settings]
+plugin-quota = "2 GB"
+"#,
+        );
+
+        let manager = ProtoConfigManager::load(sandbox.path(), None, None).unwrap();
+        let config = manager.get_merged_config().unwrap();
+
+        assert_eq!(
+            config.settings.get_plugin_quota().unwrap(),
+            Some(2 * 1024 * 1024 * 1024)
+        );
+    }
+
+    #[test]
+    fn errors_for_invalid_plugin_quota() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(
+            ".prototools",
+            r#"
+[settings]
+plugin-quota = "lots"
+"#,
+        );
+
+        let manager = ProtoConfigManager::load(sandbox.path(), None, None).unwrap();
+        let config = manager.get_merged_config().unwrap();
+
+        assert!(config.settings.get_plugin_quota().is_err());
+    }
+
+    #[test]
+    fn can_set_telemetry_metrics() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(
+            ".prototools",
+            r#"
+[settings.telemetry]
+enabled = true
+install-tool = false
+"#,
+        );
+
+        let manager = ProtoConfigManager::load(sandbox.path(), None, None).unwrap();
+        let config = manager.get_merged_config().unwrap();
+
+        assert_eq!(config.settings.telemetry.enabled, Some(true));
+        assert!(!config.settings.telemetry.install_tool);
+        assert!(config.settings.telemetry.uninstall_tool);
+    }
+
+    #[test]
+    fn migrates_boolean_telemetry_setting() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(
+            ".prototools",
+            r#"
+[settings]
+telemetry = false
+"#,
+        );
+
+        let manager = ProtoConfigManager::load(sandbox.path(), None, None).unwrap();
+        let config = manager.get_merged_config().unwrap();
+
+        assert_eq!(config.settings.telemetry.enabled, Some(false));
+    }
+
+    #[test]
+    fn can_set_date_format() {
+        let sandbox = create_empty_sandbox();
+
+        let manager = ProtoConfigManager::load(sandbox.path(), None, None).unwrap();
+        let config = manager.get_merged_config().unwrap();
+
+        assert_eq!(config.settings.ui.date_format, DateFormat::Iso);
+
+        sandbox.create_file(
+            ".prototools",
+            r#"
+[settings.ui]
+date-format = "relative"
+"#,
+        );
+
+        let manager = ProtoConfigManager::load(sandbox.path(), None, None).unwrap();
+        let config = manager.get_merged_config().unwrap();
+
+        assert_eq!(config.settings.ui.date_format, DateFormat::Relative);
+    }
+
+    #[test]
+    fn can_set_resolve_timeout() {
+        let sandbox = create_empty_sandbox();
+
+        let manager = ProtoConfigManager::load(sandbox.path(), None, None).unwrap();
+        let config = manager.get_merged_config().unwrap();
+
+        assert_eq!(config.settings.resolve_timeout, None);
+
+        sandbox.create_file(
+            ".prototools",
+            r#"
+[settings]
+resolve-timeout = 5
+"#,
+        );
+
+        let manager = ProtoConfigManager::load(sandbox.path(), None, None).unwrap();
+        let config = manager.get_merged_config().unwrap();
+
+        assert_eq!(config.settings.resolve_timeout, Some(5));
+    }
+
+    #[test]
+    fn policies_have_no_max_age_by_default() {
+        let sandbox = create_empty_sandbox();
+
+        let manager = ProtoConfigManager::load(sandbox.path(), None, None).unwrap();
+        let config = manager.get_merged_config().unwrap();
+
+        assert_eq!(config.policies.get_max_age(&Id::raw("node")).unwrap(), None);
+    }
+
+    #[test]
+    fn errors_for_invalid_policy_max_age() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(
+            ".prototools",
+            r#"
+[policies]
+max-age = "a long time"
+"#,
+        );
+
+        let manager = ProtoConfigManager::load(sandbox.path(), None, None).unwrap();
+        let config = manager.get_merged_config().unwrap();
+
+        assert!(config.policies.get_max_age(&Id::raw("node")).is_err());
+    }
+
+    #[test]
+    fn relocates_inventory_for_project_store() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(
+            "project/.prototools",
+            r#"
+[settings]
+store = "project"
+"#,
+        );
+        sandbox.create_file("project/nested/.prototools", "node = \"20\"");
+
+        let mut env = ProtoEnvironment::new_testing(sandbox.path());
+        env.cwd = sandbox.path().join("project/nested");
+        env.use_project_store().unwrap();
+
+        let store_dir = sandbox.path().join("project/.proto");
+
+        assert_eq!(env.project_store_dir, Some(store_dir.clone()));
+        assert_eq!(env.tools_dir, store_dir.join("tools"));
+        assert_eq!(env.shims_dir, store_dir.join("shims"));
+        assert_eq!(env.plugins_dir, sandbox.path().join(".proto/plugins"));
+        assert!(store_dir.join(".gitignore").exists());
+    }
+
     #[test]
     fn can_set_settings_from_env_vars() {
         let sandbox = create_empty_sandbox();
@@ -211,6 +553,37 @@ root-cert = "../cert.pem"
         );
     }
 
+    #[test]
+    fn can_set_http_proxies_and_mirrors() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(
+            ".prototools",
+            r#"
+[settings.http]
+proxies = ["https://proxy.corp:8080"]
+no-proxy = ["localhost", ".corp"]
+
+[settings.http.mirrors]
+"nodejs.org" = "https://artifactory.corp/nodejs"
+"#,
+        );
+
+        let config = ProtoConfig::load_from(sandbox.path(), false).unwrap();
+
+        assert_eq!(
+            config.settings.unwrap().http.unwrap(),
+            HttpOptions {
+                mirrors: BTreeMap::from_iter([(
+                    "nodejs.org".into(),
+                    "https://artifactory.corp/nodejs".into()
+                )]),
+                no_proxy: vec!["localhost".into(), ".corp".into()],
+                proxies: vec!["https://proxy.corp:8080".into()],
+                ..Default::default()
+            }
+        );
+    }
+
     #[test]
     fn parses_plugins_table() {
         let sandbox = create_empty_sandbox();
@@ -370,11 +743,99 @@ value = "root"
                 .to_owned();
 
             assert_eq!(
-                config.tools.get("node").unwrap().config,
-                FxHashMap::from_iter([
-                    ("value".to_owned(), JsonValue::String("b".into())),
-                    ("depth".to_owned(), JsonValue::from(1)),
-                ])
+                config.tools.get("node").unwrap().config,
+                FxHashMap::from_iter([
+                    ("value".to_owned(), JsonValue::String("b".into())),
+                    ("depth".to_owned(), JsonValue::from(1)),
+                ])
+            );
+        }
+
+        #[test]
+        fn can_set_permissions() {
+            let sandbox = create_empty_sandbox();
+            sandbox.create_file(
+                ".prototools",
+                r#"
+[tools.node.permissions]
+allowed-hosts = ["*.github.com"]
+exec = false
+"#,
+            );
+
+            let config = ProtoConfigManager::load(sandbox.path(), None, None)
+                .unwrap()
+                .get_merged_config()
+                .unwrap()
+                .to_owned();
+
+            let permissions = &config.tools.get("node").unwrap().permissions;
+
+            assert_eq!(
+                permissions.allowed_hosts,
+                Some(vec!["*.github.com".to_owned()])
+            );
+            assert_eq!(permissions.exec, Some(false));
+            assert_eq!(permissions.filesystem, None);
+        }
+
+        #[test]
+        fn permissions_override_declared() {
+            let permissions = ProtoToolPermissionsConfig {
+                exec: Some(true),
+                filesystem: Some(false),
+                ..Default::default()
+            };
+
+            assert_eq!(
+                permissions.apply(
+                    Some(&PluginPermissions {
+                        allowed_hosts: vec!["nodejs.org".into()],
+                        exec: false,
+                        filesystem: true,
+                    }),
+                    false
+                ),
+                PluginPermissions {
+                    allowed_hosts: vec!["nodejs.org".into()],
+                    exec: true,
+                    filesystem: false,
+                }
+            );
+        }
+
+        #[test]
+        fn permissions_default_to_unrestricted_when_trusted() {
+            let permissions = ProtoToolPermissionsConfig::default();
+
+            assert!(permissions.is_empty());
+            assert_eq!(
+                permissions.apply(None, true),
+                PluginPermissions::unrestricted()
+            );
+        }
+
+        #[test]
+        fn permissions_default_to_denied_when_untrusted() {
+            let permissions = ProtoToolPermissionsConfig::default();
+
+            assert_eq!(permissions.apply(None, false), PluginPermissions::denied());
+        }
+
+        #[test]
+        fn permissions_can_be_granted_when_untrusted() {
+            let permissions = ProtoToolPermissionsConfig {
+                allowed_hosts: Some(vec!["nodejs.org".into()]),
+                ..Default::default()
+            };
+
+            assert_eq!(
+                permissions.apply(None, false),
+                PluginPermissions {
+                    allowed_hosts: vec!["nodejs.org".into()],
+                    exec: false,
+                    filesystem: false,
+                }
             );
         }
 
@@ -560,6 +1021,162 @@ deno = "7.8.9"
         );
     }
 
+    #[test]
+    fn applies_os_specific_tables() {
+        let sandbox = create_empty_sandbox();
+        let other_os = if env::consts::OS == "windows" {
+            "linux"
+        } else {
+            "windows"
+        };
+
+        sandbox.create_file(
+            ".prototools",
+            format!(
+                r#"
+node = "20.0.0"
+deno = "1.0.0"
+
+[tools.node]
+bundled-npm = true
+
+[tools.node.{}]
+version = "18.0.0"
+bundled-npm = false
+
+[tools.node.{}.env]
+NODE_OS = "current"
+
+[tools.deno.{other_os}]
+version = "2.0.0"
+"#,
+                env::consts::OS,
+                env::consts::OS,
+            ),
+        );
+
+        let manager = ProtoConfigManager::load(sandbox.path(), None, None).unwrap();
+        let config = manager.get_merged_config().unwrap();
+
+        assert_eq!(
+            config.versions.get("node").unwrap(),
+            &UnresolvedVersionSpec::parse("18.0.0").unwrap()
+        );
+        assert_eq!(
+            config.versions.get("deno").unwrap(),
+            &UnresolvedVersionSpec::parse("1.0.0").unwrap()
+        );
+
+        let node = config.tools.get("node").unwrap();
+
+        assert_eq!(
+            node.config.get("bundled-npm").unwrap(),
+            &JsonValue::Bool(false)
+        );
+        assert_eq!(
+            node.env.get("NODE_OS").unwrap(),
+            &EnvVar::Value("current".into())
+        );
+        assert!(!node.config.contains_key(env::consts::OS));
+        assert!(!config
+            .tools
+            .get("deno")
+            .unwrap()
+            .config
+            .contains_key(other_os));
+
+        // Version detection reads each file, not the merged config
+        assert_eq!(
+            manager.files[0]
+                .config
+                .versions
+                .as_ref()
+                .unwrap()
+                .get("node")
+                .unwrap(),
+            &UnresolvedVersionSpec::parse("18.0.0").unwrap()
+        );
+    }
+
+    #[test]
+    fn errors_for_invalid_os_specific_version() {
+        let sandbox = create_empty_sandbox();
+
+        sandbox.create_file(
+            ".prototools",
+            format!(
+                r#"
+[tools.node.{}]
+version = 123
+"#,
+                env::consts::OS
+            ),
+        );
+
+        assert!(ProtoConfigManager::load(sandbox.path(), None, None).is_err());
+    }
+
+    #[test]
+    fn resolves_env_vars_with_precedence() {
+        let sandbox = create_empty_sandbox();
+
+        sandbox.create_file(
+            "one/.prototools",
+            r#"
+[env]
+ENV_TEST_PROJECT = "project"
+ENV_TEST_TOOL = "project"
+ENV_TEST_EXPANDED = "${ENV_TEST_PROJECT}-${ENV_TEST_TOOL}"
+ENV_TEST_TOOL_ONLY_EXPANDED = "${ENV_TEST_TOOL_ONLY}"
+ENV_TEST_REMOVED = false
+
+[tools.node.env]
+ENV_TEST_TOOL = "tool"
+ENV_TEST_TOOL_ONLY = "tool-only"
+"#,
+        );
+
+        sandbox.create_file(
+            ".prototools",
+            r#"
+[env]
+ENV_TEST_PROJECT = "parent"
+ENV_TEST_PARENT = "parent"
+"#,
+        );
+
+        let manager = ProtoConfigManager::load(sandbox.path().join("one"), None, None).unwrap();
+        let config = manager.get_merged_config().unwrap();
+
+        let vars = config.get_env_vars(None);
+
+        assert_eq!(
+            vars.get("ENV_TEST_PROJECT").unwrap().as_deref(),
+            Some("project")
+        );
+        assert_eq!(
+            vars.get("ENV_TEST_PARENT").unwrap().as_deref(),
+            Some("parent")
+        );
+        assert_eq!(
+            vars.get("ENV_TEST_TOOL").unwrap().as_deref(),
+            Some("project")
+        );
+        assert_eq!(vars.get("ENV_TEST_REMOVED").unwrap().as_deref(), None);
+
+        let vars = config.get_env_vars(Some(&Id::raw("node")));
+
+        assert_eq!(vars.get("ENV_TEST_TOOL").unwrap().as_deref(), Some("tool"));
+        assert_eq!(
+            vars.get("ENV_TEST_EXPANDED").unwrap().as_deref(),
+            Some("project-tool")
+        );
+        assert_eq!(
+            vars.get("ENV_TEST_TOOL_ONLY_EXPANDED").unwrap().as_deref(),
+            Some("tool-only")
+        );
+    }
+
     #[test]
     fn merges_traversing_upwards_without_global() {
         let sandbox = create_empty_sandbox();
@@ -759,4 +1376,289 @@ deno = "7.8.9"
             ])
         );
     }
+
+    fn loaded_dirs(manager: &ProtoConfigManager, root: &std::path::Path) -> Vec<String> {
+        manager
+            .files
+            .iter()
+            .filter_map(|file| file.path.parent()?.strip_prefix(root).ok())
+            .map(|dir| dir.to_string_lossy().replace('\\', "/"))
+            .collect()
+    }
+
+    #[test]
+    fn traverses_filesystem_by_default() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file("repo/.git/HEAD", "");
+        sandbox.create_file("repo/app/.prototools", "");
+
+        let mut manager =
+            ProtoConfigManager::load(sandbox.path().join("repo/app"), Some(sandbox.path()), None)
+                .unwrap();
+        manager
+            .restrict_to_detect_root(&sandbox.path().join("repo/app"))
+            .unwrap();
+
+        assert_eq!(
+            loaded_dirs(&manager, sandbox.path()),
+            ["repo/app", "repo", ""]
+        );
+    }
+
+    #[test]
+    fn restricts_to_cwd_only() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(".prototools", "node = \"1.2.3\"");
+        sandbox.create_file(
+            "one/two/.prototools",
+            r#"
+[settings]
+detect-root = "cwd-only"
+"#,
+        );
+
+        let cwd = sandbox.path().join("one/two");
+        let mut manager = ProtoConfigManager::load(&cwd, Some(sandbox.path()), None).unwrap();
+        manager.restrict_to_detect_root(&cwd).unwrap();
+
+        assert_eq!(loaded_dirs(&manager, sandbox.path()), ["one/two"]);
+        assert!(manager.get_merged_config().unwrap().versions.is_empty());
+    }
+
+    #[test]
+    fn restricts_to_repo_root() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(".prototools", "node = \"1.2.3\"");
+        sandbox.create_file("repo/.git/HEAD", "");
+        sandbox.create_file(
+            "repo/.prototools",
+            r#"
+[settings]
+detect-root = "repo"
+"#,
+        );
+
+        let cwd = sandbox.path().join("repo/app");
+        let mut manager = ProtoConfigManager::load(&cwd, Some(sandbox.path()), None).unwrap();
+        manager.restrict_to_detect_root(&cwd).unwrap();
+
+        assert_eq!(loaded_dirs(&manager, sandbox.path()), ["repo/app", "repo"]);
+    }
+
+    #[test]
+    fn restricts_to_submodule_or_superproject_root() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file("repo/.git/HEAD", "");
+        sandbox.create_file("repo/sub/.git", "gitdir: ../.git/modules/sub");
+        sandbox.create_file(
+            "repo/sub/.prototools",
+            r#"
+[settings]
+detect-root = "repo"
+"#,
+        );
+
+        let cwd = sandbox.path().join("repo/sub");
+        let mut manager = ProtoConfigManager::load(&cwd, Some(sandbox.path()), None).unwrap();
+        manager.restrict_to_detect_root(&cwd).unwrap();
+
+        assert_eq!(loaded_dirs(&manager, sandbox.path()), ["repo/sub"]);
+
+        sandbox.create_file(
+            "repo/sub/.prototools",
+            r#"
+[settings]
+detect-root = "superproject"
+"#,
+        );
+
+        let mut manager = ProtoConfigManager::load(&cwd, Some(sandbox.path()), None).unwrap();
+        manager.restrict_to_detect_root(&cwd).unwrap();
+
+        assert_eq!(loaded_dirs(&manager, sandbox.path()), ["repo/sub", "repo"]);
+    }
+
+    #[test]
+    fn restricts_to_repo_root_from_env_var() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file("repo/.git/HEAD", "");
+
+        env::set_var("PROTO_DETECT_ROOT", "repo");
+
+        let cwd = sandbox.path().join("repo/app");
+        let mut manager = ProtoConfigManager::load(&cwd, Some(sandbox.path()), None).unwrap();
+        manager.restrict_to_detect_root(&cwd).unwrap();
+
+        env::remove_var("PROTO_DETECT_ROOT");
+
+        assert_eq!(loaded_dirs(&manager, sandbox.path()), ["repo/app", "repo"]);
+    }
+
+    #[test]
+    fn applies_workspace_overrides_for_matching_dirs() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(
+            ".prototools",
+            r#"
+node = "20"
+npm = "10"
+
+[overrides."packages/legacy/**"]
+node = "16"
+
+[overrides."packages/legacy/old/**"]
+node = "14"
+"#,
+        );
+
+        let manager =
+            ProtoConfigManager::load(sandbox.path().join("packages/legacy/app"), None, None)
+                .unwrap();
+        let config = manager.get_merged_config().unwrap();
+
+        assert_eq!(
+            config.versions.get("node").unwrap(),
+            &UnresolvedVersionSpec::parse("16").unwrap()
+        );
+        assert_eq!(
+            config.versions.get("npm").unwrap(),
+            &UnresolvedVersionSpec::parse("10").unwrap()
+        );
+
+        let manager =
+            ProtoConfigManager::load(sandbox.path().join("packages/legacy/old/app"), None, None)
+                .unwrap();
+        let config = manager.get_merged_config().unwrap();
+
+        assert_eq!(
+            config.versions.get("node").unwrap(),
+            &UnresolvedVersionSpec::parse("14").unwrap()
+        );
+
+        let manager =
+            ProtoConfigManager::load(sandbox.path().join("packages/modern"), None, None).unwrap();
+        let config = manager.get_merged_config().unwrap();
+
+        assert_eq!(
+            config.versions.get("node").unwrap(),
+            &UnresolvedVersionSpec::parse("20").unwrap()
+        );
+    }
+
+    #[test]
+    fn closer_configs_take_precedence_over_workspace_overrides() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(
+            ".prototools",
+            r#"
+[overrides."packages/*"]
+node = "16"
+"#,
+        );
+        sandbox.create_file("packages/app/.prototools", "node = \"18\"");
+
+        let manager =
+            ProtoConfigManager::load(sandbox.path().join("packages/app"), None, None).unwrap();
+        let config = manager.get_merged_config().unwrap();
+
+        assert_eq!(
+            config.versions.get("node").unwrap(),
+            &UnresolvedVersionSpec::parse("18").unwrap()
+        );
+    }
+
+    #[test]
+    fn stops_inheriting_when_not_extending() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(".prototools", "node = \"20\"\nnpm = \"10\"");
+        sandbox.create_file("one/.prototools", "extends = false\nnode = \"18\"");
+
+        let cwd = sandbox.path().join("one/two");
+        let manager = ProtoConfigManager::load(&cwd, None, None).unwrap();
+        let config = manager.get_merged_config().unwrap();
+
+        assert_eq!(loaded_dirs(&manager, sandbox.path()), ["one/two", "one"]);
+        assert_eq!(
+            config.versions.get("node").unwrap(),
+            &UnresolvedVersionSpec::parse("18").unwrap()
+        );
+        assert!(!config.versions.contains_key("npm"));
+    }
+}
+
+mod find_repo_root {
+    use super::*;
+
+    #[test]
+    fn returns_none_outside_a_repo() {
+        let sandbox = create_empty_sandbox();
+
+        assert_eq!(find_repo_root(sandbox.path(), true), None);
+    }
+
+    #[test]
+    fn finds_closest_repo() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file("repo/.git/HEAD", "");
+        sandbox.create_file("repo/nested/.git/HEAD", "");
+
+        assert_eq!(
+            find_repo_root(&sandbox.path().join("repo/one/two"), false),
+            Some(sandbox.path().join("repo"))
+        );
+        assert_eq!(
+            find_repo_root(&sandbox.path().join("repo/nested/one"), false),
+            Some(sandbox.path().join("repo/nested"))
+        );
+    }
+
+    #[test]
+    fn stops_at_worktree_root() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file("repo/.git/HEAD", "");
+        sandbox.create_file(
+            "repo/worktree/.git",
+            "gitdir: /path/to/main/.git/worktrees/worktree",
+        );
+
+        assert_eq!(
+            find_repo_root(&sandbox.path().join("repo/worktree/app"), true),
+            Some(sandbox.path().join("repo/worktree"))
+        );
+    }
+
+    #[test]
+    fn continues_from_submodule_into_superproject() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file("repo/.git/HEAD", "");
+        sandbox.create_file("repo/libs/sub/.git", "gitdir: ../../.git/modules/sub");
+
+        assert_eq!(
+            find_repo_root(&sandbox.path().join("repo/libs/sub/src"), true),
+            Some(sandbox.path().join("repo"))
+        );
+    }
+
+    #[test]
+    fn stops_at_submodule_root() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file("repo/.git/HEAD", "");
+        sandbox.create_file("repo/libs/sub/.git", "gitdir: ../../.git/modules/sub");
+
+        assert_eq!(
+            find_repo_root(&sandbox.path().join("repo/libs/sub/src"), false),
+            Some(sandbox.path().join("repo/libs/sub"))
+        );
+    }
+
+    #[test]
+    fn uses_submodule_root_without_superproject() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file("sub/.git", "gitdir: ../.git/modules/sub");
+
+        assert_eq!(
+            find_repo_root(&sandbox.path().join("sub"), true),
+            Some(sandbox.path().join("sub"))
+        );
+    }
 }
@@ -0,0 +1,80 @@
+use proto_core::{Id, PluginRegistry, RegistryIndex};
+use starbase_sandbox::create_empty_sandbox;
+use starbase_utils::json;
+
+fn create_index() -> RegistryIndex {
+    json::from_str(
+        r#"{
+  "plugins": [
+    { "id": "zig", "name": "Zig", "locator": "github:konomae/zig-plugin", "tags": ["language"] },
+    { "id": "hugo", "description": "Static site generator", "locator": "source:https://example.com/hugo.wasm" }
+  ]
+}"#,
+    )
+    .unwrap()
+}
+
+mod registry_index {
+    use super::*;
+
+    #[test]
+    fn finds_by_id() {
+        let index = create_index();
+
+        assert_eq!(index.find(&Id::raw("hugo")).unwrap().id.as_str(), "hugo");
+        assert!(index.find(&Id::raw("unknown")).is_none());
+    }
+
+    #[test]
+    fn searches_all_fields_case_insensitive() {
+        let index = create_index();
+
+        let ids = |query: &str| {
+            index
+                .search(query)
+                .into_iter()
+                .map(|plugin| plugin.id.to_string())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(ids("ZIG"), vec!["zig"]);
+        assert_eq!(ids("static"), vec!["hugo"]);
+        assert_eq!(ids("language"), vec!["zig"]);
+        assert!(ids("python").is_empty());
+    }
+}
+
+mod plugin_registry {
+    use super::*;
+
+    #[tokio::test]
+    async fn loads_index_from_file() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file("registry.json", r#"{ "plugins": [] }"#);
+
+        let registry = PluginRegistry::new(
+            "local",
+            sandbox.path().join("registry.json").to_string_lossy(),
+        );
+
+        assert!(!registry.is_remote());
+        assert_eq!(
+            registry.load_index(&reqwest::Client::new()).await.unwrap(),
+            RegistryIndex::default()
+        );
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Failed to parse plugin registry")]
+    async fn errors_for_invalid_index() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file("registry.json", "[]");
+
+        let registry = PluginRegistry::new(
+            "local",
+            sandbox.path().join("registry.json").to_string_lossy(),
+        );
+
+        registry.load_index(&reqwest::Client::new()).await.unwrap();
+    }
+}
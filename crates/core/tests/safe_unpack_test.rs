@@ -0,0 +1,75 @@
+use proto_core::{is_file_lock_error, retry_on_file_lock, unpack_safely};
+use starbase_archive::Archiver;
+use starbase_sandbox::create_empty_sandbox;
+use std::io;
+
+mod safe_unpack {
+    use super::*;
+
+    #[test]
+    fn unpacks_through_staging_dir() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file("source/bin/tool", "binary");
+        sandbox.create_file("source/lib/index.js", "library");
+
+        let archive = sandbox.path().join("tool.tar.gz");
+        let source_dir = sandbox.path().join("source");
+        let mut archiver = Archiver::new(&source_dir, &archive);
+
+        archiver.add_source_file("bin/tool", None);
+        archiver.add_source_file("lib/index.js", None);
+        archiver.pack_from_ext().unwrap();
+
+        let output_dir = sandbox.path().join("output");
+        let staging_dir = sandbox.path().join("staging");
+        let result = unpack_safely(&archive, &output_dir, &staging_dir, None).unwrap();
+
+        assert_eq!(result.files, 2);
+        assert_eq!(result.retries, 0);
+        assert_eq!(
+            std::fs::read_to_string(output_dir.join("bin/tool")).unwrap(),
+            "binary"
+        );
+        assert_eq!(
+            std::fs::read_to_string(output_dir.join("lib/index.js")).unwrap(),
+            "library"
+        );
+        assert!(!output_dir.join("bin/tool.partial").exists());
+        assert!(!staging_dir.exists());
+    }
+
+    #[test]
+    fn retries_file_lock_errors() {
+        let sandbox = create_empty_sandbox();
+        let code = if cfg!(windows) { 32 } else { 26 };
+        let mut attempts = 0;
+
+        let (result, retries) = retry_on_file_lock(sandbox.path(), || {
+            attempts += 1;
+
+            if attempts < 3 {
+                Err(io::Error::from_raw_os_error(code))
+            } else {
+                Ok(attempts)
+            }
+        });
+
+        assert_eq!(result.unwrap(), 3);
+        assert_eq!(retries, 2);
+    }
+
+    #[test]
+    fn doesnt_retry_other_errors() {
+        let sandbox = create_empty_sandbox();
+        let error = io::Error::new(io::ErrorKind::NotFound, "missing");
+
+        assert!(!is_file_lock_error(&error));
+
+        let (result, retries) = retry_on_file_lock(sandbox.path(), || {
+            Err::<(), _>(io::Error::new(io::ErrorKind::NotFound, "missing"))
+        });
+
+        assert!(result.is_err());
+        assert_eq!(retries, 0);
+    }
+}
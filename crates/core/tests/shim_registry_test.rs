@@ -0,0 +1,73 @@
+use proto_core::{resolve_executable_conflict, Id, Shim};
+use proto_pdk_api::ShimConfig;
+use rustc_hash::FxHashMap;
+
+mod shim_registry {
+    use super::*;
+
+    #[test]
+    fn returns_owner_of_shim() {
+        assert_eq!(Shim::default().get_owner("node"), "node");
+
+        let shim = Shim {
+            parent: Some("node".into()),
+            ..Default::default()
+        };
+
+        assert_eq!(shim.get_owner("npx"), "node");
+    }
+
+    #[test]
+    fn applies_define_shims_config_over_executable_settings() {
+        let mut shim = Shim {
+            after_args: vec!["--after".into()],
+            before_args: vec!["--before".into()],
+            env_vars: FxHashMap::from_iter([
+                ("KEEP".into(), "1".into()),
+                ("REPLACE".into(), "old".into()),
+            ]),
+            ..Default::default()
+        };
+
+        shim.apply_config(ShimConfig {
+            before_args: vec!["--custom".into()],
+            env_vars: FxHashMap::from_iter([("REPLACE".into(), "new".into())]),
+            parent_process: Some("npm".into()),
+            resolve_symlinks: true,
+            ..Default::default()
+        });
+
+        assert_eq!(shim.before_args, ["--custom"]);
+        assert_eq!(shim.after_args, ["--after"]);
+        assert_eq!(shim.env_vars.get("KEEP").unwrap(), "1");
+        assert_eq!(shim.env_vars.get("REPLACE").unwrap(), "new");
+        assert_eq!(shim.parent_process.as_deref(), Some("npm"));
+        assert_eq!(shim.resolve_symlinks, Some(true));
+    }
+
+    #[test]
+    fn resolves_conflicts_with_priority() {
+        let python = Id::raw("python");
+        let pypy = Id::raw("pypy");
+
+        assert_eq!(
+            resolve_executable_conflict("python", &pypy, &python, &[python.clone()]).unwrap(),
+            &python
+        );
+        assert_eq!(
+            resolve_executable_conflict("python", &python, &pypy, &[pypy.clone()]).unwrap(),
+            &pypy
+        );
+        assert_eq!(
+            resolve_executable_conflict("python", &pypy, &python, &[pypy.clone(), python.clone()])
+                .unwrap(),
+            &pypy
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "claimed by both python and pypy")]
+    fn errors_for_unresolved_conflict() {
+        resolve_executable_conflict("python", &Id::raw("pypy"), &Id::raw("python"), &[]).unwrap();
+    }
+}
@@ -0,0 +1,46 @@
+use proto_core::{StoreLock, STORE_LOCK_NAME};
+use starbase_sandbox::create_empty_sandbox;
+
+mod store_lock {
+    use super::*;
+
+    #[test]
+    fn locks_and_unlocks_dir() {
+        let sandbox = create_empty_sandbox();
+        let dir = sandbox.path().join("node/20.0.0");
+
+        assert!(!StoreLock::is_dir_locked(&dir));
+
+        let lock = StoreLock::acquire_dir(&dir).unwrap();
+
+        assert!(dir.join(STORE_LOCK_NAME).exists());
+        assert!(StoreLock::is_dir_locked(&dir));
+
+        lock.unlock().unwrap();
+
+        assert!(!StoreLock::is_dir_locked(&dir));
+    }
+
+    #[test]
+    fn recovers_stale_lock() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file("node/20.0.0/.lock", "999999");
+
+        let dir = sandbox.path().join("node/20.0.0");
+
+        // Left behind by a process that is no longer running
+        assert!(!StoreLock::is_dir_locked(&dir));
+
+        let lock = StoreLock::acquire_dir(&dir).unwrap();
+
+        assert!(StoreLock::is_dir_locked(&dir));
+
+        // Truncated instead of removed, as other processes may be waiting on it
+        lock.unlock().unwrap();
+
+        assert_eq!(
+            std::fs::read_to_string(dir.join(STORE_LOCK_NAME)).unwrap(),
+            ""
+        );
+    }
+}
@@ -0,0 +1,44 @@
+use proto_core::{
+    load_tool_from_locator, ProtoConfig, ProtoEnvironment, ProtoEvent, UnresolvedVersionSpec,
+    VersionSpec,
+};
+use tokio::sync::mpsc;
+use warpgate::Id;
+
+mod tool_events {
+    use super::*;
+
+    #[tokio::test]
+    async fn reports_resolving_versions() {
+        let mut tool = load_tool_from_locator(
+            Id::raw("node"),
+            ProtoEnvironment::new().unwrap(),
+            ProtoConfig::builtin_plugins().get("node").unwrap(),
+        )
+        .await
+        .unwrap();
+
+        let (reporter, mut events) = mpsc::unbounded_channel();
+        tool.set_reporter(reporter);
+
+        tool.resolve_version(&UnresolvedVersionSpec::parse("20.0.0").unwrap(), true)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            events.try_recv().unwrap(),
+            ProtoEvent::ResolvingVersion {
+                tool: Id::raw("node"),
+                candidate: UnresolvedVersionSpec::parse("20.0.0").unwrap(),
+            }
+        );
+        assert_eq!(
+            events.try_recv().unwrap(),
+            ProtoEvent::ResolvedVersion {
+                tool: Id::raw("node"),
+                version: VersionSpec::parse("20.0.0").unwrap(),
+            }
+        );
+        assert!(events.try_recv().is_err());
+    }
+}
@@ -0,0 +1,137 @@
+use proto_core::{
+    StoreLock, ToolInstallState, ToolManifest, ToolManifestVersion, VersionSpec,
+    RUNS_BUFFER_FLUSH_SIZE, RUNS_BUFFER_NAME,
+};
+use starbase_sandbox::create_empty_sandbox;
+
+mod tool_manifest {
+    use super::*;
+
+    #[test]
+    fn buffers_runs_next_to_manifest() {
+        let sandbox = create_empty_sandbox();
+        let manifest = ToolManifest::load_from(sandbox.path()).unwrap();
+        let version = VersionSpec::parse("1.2.3").unwrap();
+
+        manifest.track_run(&version).unwrap();
+        manifest.track_run(&version).unwrap();
+
+        assert_eq!(
+            std::fs::read_to_string(sandbox.path().join(RUNS_BUFFER_NAME)).unwrap(),
+            "1.2.3\n1.2.3\n"
+        );
+    }
+
+    #[test]
+    fn flushes_runs_into_versions() {
+        let sandbox = create_empty_sandbox();
+        let mut manifest = ToolManifest::load_from(sandbox.path()).unwrap();
+        let version = VersionSpec::parse("1.2.3").unwrap();
+        let other_version = VersionSpec::parse("4.5.6").unwrap();
+
+        manifest
+            .versions
+            .insert(version.clone(), ToolManifestVersion::default());
+        manifest
+            .versions
+            .insert(other_version.clone(), ToolManifestVersion::default());
+
+        manifest.track_run(&version).unwrap();
+        manifest.track_run(&other_version).unwrap();
+        manifest.track_run(&version).unwrap();
+
+        // Not installed, so ignored
+        manifest
+            .track_run(&VersionSpec::parse("7.8.9").unwrap())
+            .unwrap();
+
+        assert!(manifest.flush_runs().unwrap());
+        assert_eq!(manifest.versions.get(&version).unwrap().run_count, 2);
+        assert_eq!(manifest.versions.get(&other_version).unwrap().run_count, 1);
+        assert!(!sandbox.path().join(RUNS_BUFFER_NAME).exists());
+
+        // Nothing left to flush
+        assert!(!manifest.flush_runs().unwrap());
+    }
+
+    #[test]
+    fn flushes_runs_into_manifest_when_buffer_is_full() {
+        let sandbox = create_empty_sandbox();
+        let mut manifest = ToolManifest::load_from(sandbox.path()).unwrap();
+        let version = VersionSpec::parse("1.2.3").unwrap();
+
+        manifest
+            .update(|manifest| {
+                manifest
+                    .versions
+                    .insert(version.clone(), ToolManifestVersion::default());
+            })
+            .unwrap();
+
+        // Each run is 6 bytes, so the last run reaches the flush size
+        let runs = RUNS_BUFFER_FLUSH_SIZE.div_ceil("1.2.3\n".len() as u64);
+
+        for _ in 0..runs {
+            manifest.track_run(&version).unwrap();
+        }
+
+        assert!(!sandbox.path().join(RUNS_BUFFER_NAME).exists());
+        assert_eq!(
+            ToolManifest::load_from(sandbox.path())
+                .unwrap()
+                .versions
+                .get(&version)
+                .unwrap()
+                .run_count,
+            runs
+        );
+    }
+
+    #[test]
+    fn update_keeps_changes_from_other_processes() {
+        let sandbox = create_empty_sandbox();
+        let mut a = ToolManifest::load_from(sandbox.path()).unwrap();
+        let mut b = ToolManifest::load_from(sandbox.path()).unwrap();
+        let a_version = VersionSpec::parse("1.2.3").unwrap();
+        let b_version = VersionSpec::parse("4.5.6").unwrap();
+
+        a.update(|manifest| {
+            manifest.installed_versions.insert(a_version.clone());
+        })
+        .unwrap();
+
+        b.update(|manifest| {
+            manifest.installed_versions.insert(b_version.clone());
+        })
+        .unwrap();
+
+        let manifest = ToolManifest::load_from(sandbox.path()).unwrap();
+
+        assert!(manifest.installed_versions.contains(&a_version));
+        assert!(manifest.installed_versions.contains(&b_version));
+        assert_eq!(b.installed_versions, manifest.installed_versions);
+    }
+
+    #[test]
+    fn detects_interrupted_installs() {
+        let sandbox = create_empty_sandbox();
+        let mut manifest = ToolManifest::load_from(sandbox.path()).unwrap();
+        let interrupted = VersionSpec::parse("1.2.3").unwrap();
+        let in_progress = VersionSpec::parse("4.5.6").unwrap();
+
+        manifest
+            .install_states
+            .insert(interrupted.clone(), ToolInstallState::Staging);
+        manifest
+            .install_states
+            .insert(in_progress.clone(), ToolInstallState::Committing);
+
+        // Another process is installing this version
+        let _lock = StoreLock::acquire(manifest.get_install_lock_path(&in_progress)).unwrap();
+
+        assert_eq!(
+            manifest.get_interrupted_installs(),
+            vec![(interrupted, ToolInstallState::Staging)]
+        );
+    }
+}
@@ -0,0 +1,99 @@
+use proto_core::{create_package, unpack_package, ToolPackageReceipt, VersionSpec};
+use starbase_sandbox::create_empty_sandbox;
+
+mod tool_package {
+    use super::*;
+
+    #[test]
+    fn creates_receipt_with_checksums() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file("node/20.0.0/bin/node", "binary");
+        sandbox.create_file("node/20.0.0/.lock", "");
+        sandbox.create_file("node/20.0.0/.install-receipt.json", "{}");
+
+        let receipt = ToolPackageReceipt::create(
+            "node",
+            &VersionSpec::parse("20.0.0").unwrap(),
+            &sandbox.path().join("node/20.0.0"),
+        )
+        .unwrap();
+
+        assert_eq!(receipt.id, "node");
+        assert_eq!(receipt.files.keys().collect::<Vec<_>>(), ["bin/node"]);
+    }
+
+    #[test]
+    fn round_trips_through_archive() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file("source/bin/node", "binary");
+        sandbox.create_file("source/lib/index.js", "script");
+
+        let version = VersionSpec::parse("20.0.0").unwrap();
+        let package = sandbox.path().join("node.tar.gz");
+        let created =
+            create_package("node", &version, &sandbox.path().join("source"), &package).unwrap();
+
+        assert!(package.exists());
+
+        let output_dir = sandbox.path().join("output");
+        let unpacked = unpack_package(&package, &output_dir).unwrap();
+
+        assert_eq!(created, unpacked);
+        assert!(output_dir.join("bin/node").exists());
+
+        unpacked.verify(&package, "node", &output_dir).unwrap();
+    }
+
+    #[test]
+    fn errors_for_checksum_mismatch() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file("node/bin/node", "binary");
+
+        let dir = sandbox.path().join("node");
+        let receipt =
+            ToolPackageReceipt::create("node", &VersionSpec::parse("20.0.0").unwrap(), &dir)
+                .unwrap();
+
+        sandbox.create_file("node/bin/node", "tampered");
+
+        let error = receipt
+            .verify(&sandbox.path().join("node.tar.gz"), "node", &dir)
+            .unwrap_err();
+
+        assert!(error.to_string().contains("Checksum mismatch"));
+    }
+
+    #[test]
+    fn errors_for_other_tool() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file("node/bin/node", "binary");
+
+        let dir = sandbox.path().join("node");
+        let receipt =
+            ToolPackageReceipt::create("node", &VersionSpec::parse("20.0.0").unwrap(), &dir)
+                .unwrap();
+
+        assert!(receipt
+            .verify(&sandbox.path().join("node.tar.gz"), "deno", &dir)
+            .is_err());
+    }
+
+    #[test]
+    fn errors_for_other_platform() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file("node/bin/node", "binary");
+
+        let dir = sandbox.path().join("node");
+        let mut receipt =
+            ToolPackageReceipt::create("node", &VersionSpec::parse("20.0.0").unwrap(), &dir)
+                .unwrap();
+
+        receipt.os = "unknown".into();
+
+        let error = receipt
+            .verify(&sandbox.path().join("node.tar.gz"), "node", &dir)
+            .unwrap_err();
+
+        assert!(error.to_string().contains("unknown-"));
+    }
+}
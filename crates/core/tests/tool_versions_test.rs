@@ -0,0 +1,53 @@
+use proto_core::{ProtoToolVersionsSettingsConfig, ToolVersionsFile};
+use std::path::PathBuf;
+use warpgate::Id;
+
+mod tool_versions {
+    use super::*;
+
+    #[test]
+    fn parses_lines() {
+        let file = ToolVersionsFile::parse(
+            PathBuf::from(".tool-versions"),
+            "# comment\nnodejs 20.10.0 18.0.0\n\npython 3.12.0 # pinned\nruby\n",
+        );
+
+        assert_eq!(
+            file.tools,
+            vec![
+                (
+                    "nodejs".to_owned(),
+                    vec!["20.10.0".to_owned(), "18.0.0".to_owned()]
+                ),
+                ("python".to_owned(), vec!["3.12.0".to_owned()]),
+            ]
+        );
+    }
+
+    #[test]
+    fn maps_names_to_ids() {
+        let mut settings = ProtoToolVersionsSettingsConfig::default();
+
+        assert_eq!(settings.get_tool_id("nodejs"), Some(Id::raw("node")));
+        assert_eq!(settings.get_tool_id("golang"), Some(Id::raw("go")));
+        assert_eq!(settings.get_tool_id("deno"), Some(Id::raw("deno")));
+
+        settings
+            .aliases
+            .insert("nodejs".into(), Id::raw("custom-node"));
+
+        assert_eq!(settings.get_tool_id("nodejs"), Some(Id::raw("custom-node")));
+    }
+
+    #[test]
+    fn finds_versions_for_tool() {
+        let file = ToolVersionsFile::parse(PathBuf::from(".tool-versions"), "nodejs 20.10.0");
+        let settings = ProtoToolVersionsSettingsConfig::default();
+
+        assert_eq!(
+            file.get_versions(&Id::raw("node"), &settings),
+            Some(["20.10.0".to_owned()].as_slice())
+        );
+        assert_eq!(file.get_versions(&Id::raw("nodejs"), &settings), None);
+    }
+}
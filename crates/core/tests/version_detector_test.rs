@@ -1,6 +1,7 @@
 use proto_core::{
-    detect_version_first_available, detect_version_prefer_prototools, load_tool_from_locator,
-    ProtoConfig, ProtoConfigManager, ProtoEnvironment, Tool, UnresolvedVersionSpec,
+    detect_version_first_available, detect_version_merged, detect_version_prefer_prototools,
+    load_tool_from_locator, ProtoConfig, ProtoConfigManager, ProtoEnvironment, Tool,
+    UnresolvedVersionSpec,
 };
 use starbase_sandbox::create_empty_sandbox;
 use std::path::Path;
@@ -111,4 +112,81 @@ mod version_detector {
             Some(UnresolvedVersionSpec::parse("~18").unwrap())
         );
     }
+
+    #[tokio::test]
+    async fn merges_files_in_closest_dir() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(".prototools", "node = \"16\"");
+        sandbox.create_file("a/.nvmrc", "20.10");
+        sandbox.create_file("a/package.json", r#"{ "engines": { "node": ">=18" } }"#);
+
+        let tool = create_node(sandbox.path()).await;
+        let manager = ProtoConfigManager::load(sandbox.path().join("a"), None, None).unwrap();
+
+        assert_eq!(
+            detect_version_merged(&tool, &manager).await.unwrap(),
+            Some(UnresolvedVersionSpec::parse("~20.10, >=18").unwrap())
+        );
+    }
+
+    #[tokio::test]
+    async fn merges_aliases_as_first_match() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(".prototools", "node = \"lts\"");
+        sandbox.create_file("package.json", r#"{ "engines": { "node": ">=18" } }"#);
+
+        let tool = create_node(sandbox.path()).await;
+        let manager = ProtoConfigManager::load(sandbox.path(), None, None).unwrap();
+
+        assert_eq!(
+            detect_version_merged(&tool, &manager).await.unwrap(),
+            Some(UnresolvedVersionSpec::Alias("lts".into()))
+        );
+    }
+
+    #[tokio::test]
+    async fn errors_when_merged_versions_conflict() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(".prototools", "node = \"16.0.0\"");
+        sandbox.create_file("package.json", r#"{ "engines": { "node": ">=18" } }"#);
+
+        let tool = create_node(sandbox.path()).await;
+        let manager = ProtoConfigManager::load(sandbox.path(), None, None).unwrap();
+
+        assert!(detect_version_merged(&tool, &manager).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn detects_from_tool_versions_when_enabled() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file("a/.prototools", "[settings.tool-versions]\nenabled = true");
+        sandbox.create_file("a/b/.tool-versions", "nodejs system 18.0.0 16.0.0");
+
+        let tool = create_node(sandbox.path()).await;
+        let manager = ProtoConfigManager::load(sandbox.path().join("a/b"), None, None).unwrap();
+
+        assert_eq!(
+            detect_version_prefer_prototools(&tool, &manager)
+                .await
+                .unwrap(),
+            Some(UnresolvedVersionSpec::parse("18.0.0").unwrap())
+        );
+    }
+
+    #[tokio::test]
+    async fn ignores_tool_versions_when_disabled() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file("a/.prototools", "node = \"20\"");
+        sandbox.create_file("a/b/.tool-versions", "nodejs 18.0.0");
+
+        let tool = create_node(sandbox.path()).await;
+        let manager = ProtoConfigManager::load(sandbox.path().join("a/b"), None, None).unwrap();
+
+        assert_eq!(
+            detect_version_prefer_prototools(&tool, &manager)
+                .await
+                .unwrap(),
+            Some(UnresolvedVersionSpec::parse("~20").unwrap())
+        );
+    }
 }
@@ -0,0 +1,138 @@
+use proto_core::{extract_version_from_content, get_detect_version_files};
+use proto_pdk_api::{DetectVersionFile, DetectVersionOutput, VersionFileFormat};
+
+mod version_file {
+    use super::*;
+
+    #[test]
+    fn sorts_files_by_priority() {
+        let output = DetectVersionOutput {
+            files: vec![".nvmrc".into(), ".node-version".into()],
+            detailed_files: vec![
+                DetectVersionFile {
+                    file: "package.json".into(),
+                    priority: 1,
+                    ..Default::default()
+                },
+                DetectVersionFile {
+                    file: ".tool-versions".into(),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+
+        assert_eq!(
+            get_detect_version_files(&output)
+                .into_iter()
+                .map(|file| file.file)
+                .collect::<Vec<_>>(),
+            vec!["package.json", ".nvmrc", ".node-version", ".tool-versions"]
+        );
+    }
+
+    #[test]
+    fn extracts_from_json() {
+        assert_eq!(
+            extract_version_from_content(
+                r#"{ "engines": { "node": ">=18" } }"#,
+                &VersionFileFormat::Json {
+                    path: "engines.node".into()
+                }
+            )
+            .unwrap(),
+            Some(">=18".into())
+        );
+
+        assert_eq!(
+            extract_version_from_content(
+                r#"{ "engines": {} }"#,
+                &VersionFileFormat::Json {
+                    path: "engines.node".into()
+                }
+            )
+            .unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn extracts_from_toml() {
+        assert_eq!(
+            extract_version_from_content(
+                "[project]\nrequires-python = \">=3.10\"",
+                &VersionFileFormat::Toml {
+                    path: "project.requires-python".into()
+                }
+            )
+            .unwrap(),
+            Some(">=3.10".into())
+        );
+    }
+
+    #[test]
+    fn extracts_from_tool_versions() {
+        assert_eq!(
+            extract_version_from_content(
+                "# comment\nnodejs 20.10.0 18.0.0\npython 3.12.0 # pinned",
+                &VersionFileFormat::ToolVersions {
+                    name: "python".into()
+                }
+            )
+            .unwrap(),
+            Some("3.12.0".into())
+        );
+    }
+
+    #[test]
+    fn extracts_from_regex() {
+        assert_eq!(
+            extract_version_from_content(
+                "module example.com/app\n\ngo 1.21\n",
+                &VersionFileFormat::Regex {
+                    pattern: r"^go\s+(\S+)".into()
+                }
+            )
+            .unwrap(),
+            Some("1.21".into())
+        );
+    }
+
+    #[test]
+    fn extracts_from_package_manager() {
+        let format = VersionFileFormat::PackageManager {
+            name: "pnpm".into(),
+        };
+
+        assert_eq!(
+            extract_version_from_content(r#"{ "packageManager": "pnpm@8.15.1" }"#, &format)
+                .unwrap(),
+            Some("8.15.1".into())
+        );
+
+        assert_eq!(
+            extract_version_from_content(
+                r#"{ "packageManager": "pnpm@8.15.1+sha256.1a2b3c" }"#,
+                &format
+            )
+            .unwrap(),
+            Some("8.15.1".into())
+        );
+
+        assert_eq!(
+            extract_version_from_content(r#"{ "packageManager": "yarn@4.1.0" }"#, &format).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn errors_for_invalid_content() {
+        assert!(extract_version_from_content(
+            "{",
+            &VersionFileFormat::Json {
+                path: "engines.node".into()
+            }
+        )
+        .is_err());
+    }
+}
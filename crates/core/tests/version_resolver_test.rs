@@ -1,5 +1,7 @@
 use proto_core::{
-    resolve_version, ProtoToolConfig, ToolManifest, UnresolvedVersionSpec, VersionSpec,
+    expand_composite_spec, explain_version, format_alias_chain, resolve_version, satisfies_version,
+    trace_alias_chain, ProtoToolConfig, ToolManifest, UnresolvedVersionSpec, VersionResolver,
+    VersionSpec,
 };
 use semver::Version;
 use std::collections::BTreeMap;
@@ -496,4 +498,350 @@ mod version_resolver {
             .unwrap();
         }
     }
+
+    #[test]
+    fn resolves_installed_versions_from_shared_manifest() {
+        let manifest = ToolManifest::default();
+        let shared_manifest = create_manifest();
+
+        let mut resolver = VersionResolver::default();
+        resolver.versions = create_versions();
+
+        assert_eq!(
+            resolver.resolve(&UnresolvedVersionSpec::parse("^3").unwrap()),
+            None
+        );
+
+        resolver.with_manifest(&manifest);
+        resolver.with_shared_manifest(&shared_manifest);
+
+        assert_eq!(
+            resolver
+                .resolve(&UnresolvedVersionSpec::parse("^3").unwrap())
+                .unwrap(),
+            Version::new(3, 3, 3)
+        );
+    }
+
+    #[test]
+    fn traces_alias_chains() {
+        let aliases = create_aliases();
+        let config = create_tool_config();
+
+        assert_eq!(
+            format_alias_chain(
+                &trace_alias_chain("stable-manifest", &aliases, Some(&config)).unwrap()
+            ),
+            "stable-manifest -> stable -> latest -> 10.0.0"
+        );
+
+        assert_eq!(
+            format_alias_chain(&trace_alias_chain("no-alias", &aliases, None).unwrap()),
+            "no-alias -> missing"
+        );
+    }
+
+    #[test]
+    fn errors_alias_cycle() {
+        let mut config = create_tool_config();
+        config
+            .aliases
+            .insert("a".into(), UnresolvedVersionSpec::Alias("b".into()));
+        config
+            .aliases
+            .insert("b".into(), UnresolvedVersionSpec::Alias("a".into()));
+
+        let error = trace_alias_chain("a", &create_aliases(), Some(&config)).unwrap_err();
+
+        assert!(error.to_string().contains("a -> b -> a"));
+
+        assert_eq!(
+            resolve_version(
+                &UnresolvedVersionSpec::Alias("a".into()),
+                &create_versions(),
+                &create_aliases(),
+                None,
+                Some(&config),
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn resolves_range_aliases() {
+        let mut config = create_tool_config();
+        config.aliases.insert(
+            "supported".into(),
+            UnresolvedVersionSpec::parse(">=4 <9").unwrap(),
+        );
+
+        assert_eq!(
+            resolve_version(
+                &UnresolvedVersionSpec::Alias("supported".into()),
+                &create_versions(),
+                &create_aliases(),
+                None,
+                Some(&config),
+            )
+            .unwrap(),
+            Version::new(8, 0, 0)
+        );
+    }
+
+    #[test]
+    fn resolves_composite_aliases_to_highest() {
+        let mut config = create_tool_config();
+        config.aliases.insert(
+            "supported".into(),
+            UnresolvedVersionSpec::parse("latest-manifest || ^1 || ^7").unwrap(),
+        );
+
+        assert_eq!(
+            resolve_version(
+                &UnresolvedVersionSpec::Alias("supported".into()),
+                &create_versions(),
+                &create_aliases(),
+                None,
+                Some(&config),
+            )
+            .unwrap(),
+            Version::new(8, 0, 0)
+        );
+
+        assert_eq!(
+            resolve_version(
+                &UnresolvedVersionSpec::parse("^1 || no-alias").unwrap(),
+                &create_versions(),
+                &create_aliases(),
+                None,
+                Some(&config),
+            )
+            .unwrap(),
+            Version::new(1, 10, 5)
+        );
+    }
+
+    #[test]
+    fn expands_nested_composites() {
+        let mut config = create_tool_config();
+        config.aliases.insert(
+            "old".into(),
+            UnresolvedVersionSpec::parse("no-version || ^1").unwrap(),
+        );
+        config.aliases.insert(
+            "supported".into(),
+            UnresolvedVersionSpec::parse("old || stable").unwrap(),
+        );
+
+        assert_eq!(
+            expand_composite_spec(
+                &[UnresolvedVersionSpec::Alias("supported".into())],
+                &create_aliases(),
+                Some(&config)
+            )
+            .unwrap(),
+            vec![
+                UnresolvedVersionSpec::Alias("no-version".into()),
+                UnresolvedVersionSpec::parse("^1").unwrap(),
+                UnresolvedVersionSpec::Alias("stable".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn errors_composite_cycle() {
+        let mut config = create_tool_config();
+        config
+            .aliases
+            .insert("a".into(), UnresolvedVersionSpec::parse("b || ^1").unwrap());
+        config
+            .aliases
+            .insert("b".into(), UnresolvedVersionSpec::parse("a || ^4").unwrap());
+
+        assert!(expand_composite_spec(
+            &[UnresolvedVersionSpec::Alias("a".into())],
+            &create_aliases(),
+            Some(&config)
+        )
+        .is_err());
+
+        assert_eq!(
+            resolve_version(
+                &UnresolvedVersionSpec::Alias("a".into()),
+                &create_versions(),
+                &create_aliases(),
+                None,
+                Some(&config),
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn explains_resolution_steps() {
+        let versions = create_versions();
+        let aliases = create_aliases();
+        let manifest = create_manifest();
+
+        let (version, steps) = explain_version(
+            &UnresolvedVersionSpec::parse("^3").unwrap(),
+            &versions,
+            &aliases,
+            Some(&manifest),
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(version, Version::new(3, 3, 3));
+        assert_eq!(steps, vec!["highest installed version matching ^3"]);
+
+        let (version, steps) = explain_version(
+            &UnresolvedVersionSpec::Alias("stable".into()),
+            &versions,
+            &aliases,
+            None,
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(version, Version::new(10, 0, 0));
+        assert_eq!(
+            steps,
+            vec![
+                "expanded alias chain stable -> latest -> 10.0.0",
+                "exact version 10.0.0 is available"
+            ]
+        );
+    }
+
+    #[test]
+    fn excludes_prereleases_unless_enabled() {
+        let mut resolver = VersionResolver::default();
+        resolver.versions = create_versions();
+        resolver
+            .versions
+            .push(Version::parse("11.0.0-rc.1").unwrap());
+        resolver.aliases.insert(
+            "latest".into(),
+            UnresolvedVersionSpec::parse("11.0.0-rc.1").unwrap(),
+        );
+
+        assert_eq!(
+            resolver.resolve(&UnresolvedVersionSpec::default()).unwrap(),
+            Version::new(10, 0, 0)
+        );
+        assert_eq!(
+            resolver
+                .resolve(&UnresolvedVersionSpec::parse(">=10").unwrap())
+                .unwrap(),
+            Version::new(10, 0, 0)
+        );
+
+        resolver.with_prereleases(true);
+
+        assert_eq!(
+            resolver.resolve(&UnresolvedVersionSpec::default()).unwrap(),
+            Version::parse("11.0.0-rc.1").unwrap()
+        );
+        assert_eq!(
+            resolver
+                .resolve(&UnresolvedVersionSpec::parse(">=10").unwrap())
+                .unwrap(),
+            Version::parse("11.0.0-rc.1").unwrap()
+        );
+    }
+
+    #[test]
+    fn includes_prereleases_from_tool_config() {
+        let mut versions = create_versions();
+        versions.push(Version::parse("11.0.0-rc.1").unwrap());
+
+        let config = ProtoToolConfig {
+            include_prereleases: Some(true),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            resolve_version(
+                &UnresolvedVersionSpec::parse("^11").unwrap(),
+                &versions,
+                &create_aliases(),
+                None,
+                Some(&config),
+            )
+            .unwrap(),
+            Version::parse("11.0.0-rc.1").unwrap()
+        );
+    }
+
+    #[test]
+    fn tool_config_doesnt_exclude_explicitly_enabled_prereleases() {
+        let config = ProtoToolConfig {
+            include_prereleases: Some(false),
+            ..Default::default()
+        };
+
+        let mut resolver = VersionResolver::default();
+        resolver.versions = create_versions();
+        resolver
+            .versions
+            .push(Version::parse("11.0.0-rc.1").unwrap());
+        resolver.with_config(&config);
+        resolver.with_prereleases(true);
+
+        assert_eq!(
+            resolver
+                .resolve(&UnresolvedVersionSpec::parse("^11").unwrap())
+                .unwrap(),
+            Version::parse("11.0.0-rc.1").unwrap()
+        );
+    }
+
+    #[test]
+    fn checks_if_version_satisfies_spec() {
+        let version = Version::new(1, 2, 3);
+        let pre = Version::parse("2.0.0-rc.1").unwrap();
+
+        assert!(satisfies_version(
+            &UnresolvedVersionSpec::parse("1.2.3").unwrap(),
+            &version,
+            false
+        ));
+        assert!(satisfies_version(
+            &UnresolvedVersionSpec::parse("^1").unwrap(),
+            &version,
+            false
+        ));
+        assert!(satisfies_version(
+            &UnresolvedVersionSpec::parse("^2 || ~1.2").unwrap(),
+            &version,
+            false
+        ));
+        assert!(!satisfies_version(
+            &UnresolvedVersionSpec::parse("1.2.4").unwrap(),
+            &version,
+            false
+        ));
+        assert!(!satisfies_version(
+            &UnresolvedVersionSpec::parse(">=2").unwrap(),
+            &version,
+            false
+        ));
+        assert!(!satisfies_version(
+            &UnresolvedVersionSpec::Canary,
+            &version,
+            false
+        ));
+
+        assert!(!satisfies_version(
+            &UnresolvedVersionSpec::parse("^2").unwrap(),
+            &pre,
+            false
+        ));
+        assert!(satisfies_version(
+            &UnresolvedVersionSpec::parse("^2").unwrap(),
+            &pre,
+            true
+        ));
+    }
 }
@@ -0,0 +1,41 @@
+use proto_core::{
+    find_host_warning, take_triggered_host_warnings, trigger_host_warning, HostWarningsState,
+};
+use starbase_sandbox::create_empty_sandbox;
+
+mod host_warnings {
+    use super::*;
+
+    #[test]
+    fn triggers_known_warnings_once() {
+        trigger_host_warning("tool-command");
+        trigger_host_warning("tool-command");
+        trigger_host_warning("unknown-warning");
+
+        assert_eq!(
+            take_triggered_host_warnings(),
+            vec![find_host_warning("tool-command").unwrap()]
+        );
+        assert!(take_triggered_host_warnings().is_empty());
+    }
+
+    #[test]
+    fn shows_once_per_day() {
+        let sandbox = create_empty_sandbox();
+        let mut state = HostWarningsState::load_from(sandbox.path()).unwrap();
+
+        assert!(state.should_show("tool-command"));
+        assert!(!state.should_show("tool-command"));
+        assert!(state.should_show("proto-root-env-var"));
+
+        state.save().unwrap();
+
+        let mut state = HostWarningsState::load_from(sandbox.path()).unwrap();
+
+        assert!(!state.should_show("tool-command"));
+
+        state.last_shown_at.insert("tool-command".into(), 0);
+
+        assert!(state.should_show("tool-command"));
+    }
+}
@@ -1,5 +1,6 @@
 use miette::Diagnostic;
 use starbase_styles::{Style, Stylize};
+use std::path::PathBuf;
 use thiserror::Error;
 
 #[derive(Error, Debug, Diagnostic)]
@@ -15,4 +16,43 @@ pub enum ProtoInstallerError {
         #[source]
         error: reqwest::Error,
     },
+
+    #[diagnostic(
+        code(proto::installer::missing_checksum),
+        help = "Pass --skip-verify to upgrade without verifying, at your own risk."
+    )]
+    #[error("Unable to verify the release, failed to download checksum {}.", .url.style(Style::Url))]
+    MissingChecksum { url: String },
+
+    #[diagnostic(
+        code(proto::installer::checksum_mismatch),
+        help = "The download may be corrupted or tampered with. Pass --skip-verify to upgrade without verifying, at your own risk."
+    )]
+    #[error(
+        "Checksum mismatch for {}, expected {} but received {}.",
+        .file.style(Style::File),
+        .expected.style(Style::Hash),
+        .actual.style(Style::Hash),
+    )]
+    ChecksumMismatch {
+        actual: String,
+        expected: String,
+        file: String,
+    },
+
+    #[diagnostic(
+        code(proto::installer::invalid_signature),
+        help = "The download may be corrupted or tampered with. Pass --skip-verify to upgrade without verifying, at your own risk."
+    )]
+    #[error("Failed to verify the signature of {}: {error}", .file.style(Style::File))]
+    InvalidSignature { error: String, file: String },
+
+    #[diagnostic(code(proto::installer::verify_failed))]
+    #[error("Failed to verify the installed {} binary, expected version {version}.", .bin.style(Style::Path))]
+    VerifyFailed {
+        bin: PathBuf,
+        version: String,
+        #[help]
+        output: Option<String>,
+    },
 }
@@ -1,6 +1,8 @@
 mod error;
 
 use futures::StreamExt;
+use minisign_verify::{PublicKey, Signature};
+use sha2::{Digest, Sha256};
 use starbase_archive::Archiver;
 use starbase_utils::fs::{self, FsError};
 use std::cmp;
@@ -8,6 +10,7 @@ use std::env;
 use std::env::consts;
 use std::io::Write;
 use std::path::{Path, PathBuf};
+use std::process::Command;
 use system_env::SystemLibc;
 
 pub use error::ProtoInstallerError;
@@ -37,27 +40,162 @@ pub struct DownloadResult {
     pub file: String,
     pub file_stem: String,
     pub url: String,
+    pub version: String,
 }
 
+/// Options for verifying the integrity of a downloaded release.
+#[derive(Clone, Debug, Default)]
+pub struct VerifyOptions {
+    /// Base64 encoded minisign public key. When provided, the `.minisig`
+    /// signature published alongside the archive is also verified.
+    pub public_key: Option<String>,
+
+    /// Skip verifying the checksum and signature entirely.
+    pub skip: bool,
+}
+
+/// Extract the checksum for the provided file name from the contents of a
+/// checksum file, in the `<checksum>  <file>` or `<checksum>` formats.
+pub fn extract_checksum(content: &str, file_name: &str) -> Option<String> {
+    let lines = content
+        .lines()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>();
+
+    let line = if lines.len() == 1 {
+        lines.first()
+    } else {
+        lines.iter().find(|line| {
+            line.split_whitespace()
+                .last()
+                .is_some_and(|file| file.trim_start_matches('*') == file_name)
+        })
+    }?;
+
+    line.split_whitespace()
+        .next()
+        .map(|hash| hash.to_lowercase())
+}
+
+/// Verify that the checksum of the downloaded archive matches
+/// the checksum published in the provided checksum file contents.
+pub fn verify_checksum(content: &str, file_name: &str, actual: &str) -> miette::Result<()> {
+    let expected = extract_checksum(content, file_name).unwrap_or_default();
+
+    if expected != actual {
+        return Err(ProtoInstallerError::ChecksumMismatch {
+            actual: actual.to_owned(),
+            expected,
+            file: file_name.to_owned(),
+        }
+        .into());
+    }
+
+    Ok(())
+}
+
+/// Verify the downloaded archive against a minisign signature,
+/// using the provided base64 encoded public key.
+pub fn verify_signature(
+    bytes: &[u8],
+    signature: &str,
+    public_key: &str,
+    file_name: &str,
+) -> miette::Result<()> {
+    let handle_error = |error: minisign_verify::Error| ProtoInstallerError::InvalidSignature {
+        error: error.to_string(),
+        file: file_name.to_owned(),
+    };
+
+    PublicKey::from_base64(public_key)
+        .map_err(handle_error)?
+        .verify(
+            bytes,
+            &Signature::decode(signature).map_err(handle_error)?,
+            false,
+        )
+        .map_err(handle_error)?;
+
+    Ok(())
+}
+
+async fn fetch_text(client: &reqwest::Client, url: &str) -> miette::Result<Option<String>> {
+    let handle_error = |error: reqwest::Error| ProtoInstallerError::DownloadFailed {
+        url: url.to_owned(),
+        error,
+    };
+    let response = client.get(url).send().await.map_err(handle_error)?;
+
+    if !response.status().is_success() {
+        return Ok(None);
+    }
+
+    Ok(Some(response.text().await.map_err(handle_error)?))
+}
+
+async fn verify_download(
+    client: &reqwest::Client,
+    download: &DownloadResult,
+    checksum: &str,
+    options: &VerifyOptions,
+) -> miette::Result<()> {
+    let checksum_url = format!("{}.sha256", download.url);
+
+    let Some(content) = fetch_text(client, &checksum_url).await? else {
+        return Err(ProtoInstallerError::MissingChecksum { url: checksum_url }.into());
+    };
+
+    verify_checksum(&content, &download.file, checksum)?;
+
+    if let Some(public_key) = &options.public_key {
+        let signature_url = format!("{}.minisig", download.url);
+
+        let Some(signature) = fetch_text(client, &signature_url).await? else {
+            return Err(ProtoInstallerError::InvalidSignature {
+                error: format!("no signature was published at {signature_url}"),
+                file: download.file.clone(),
+            }
+            .into());
+        };
+
+        verify_signature(
+            &fs::read_file_bytes(&download.archive_file)?,
+            &signature,
+            public_key,
+            &download.file,
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Download the release archive for the provided target and version into
+/// the temporary directory, and verify its integrity before returning.
+/// Requests are made to the URL returned by `rewrite_url`, allowing mirrors.
 pub async fn download_release(
+    client: &reqwest::Client,
     triple: &str,
     version: &str,
     temp_dir: impl AsRef<Path>,
+    options: &VerifyOptions,
+    rewrite_url: impl Fn(&str) -> miette::Result<String>,
     on_chunk: impl Fn(u64, u64),
 ) -> miette::Result<DownloadResult> {
     let target_ext = if cfg!(windows) { "zip" } else { "tar.xz" };
     let target_file = format!("proto_cli-{triple}");
 
     let download_file = format!("{target_file}.{target_ext}");
-    let download_url =
-        format!("https://github.com/moonrepo/proto/releases/download/v{version}/{download_file}");
+    let download_url = rewrite_url(&format!(
+        "https://github.com/moonrepo/proto/releases/download/v{version}/{download_file}"
+    ))?;
 
     // Request file from url
     let handle_error = |error: reqwest::Error| ProtoInstallerError::DownloadFailed {
         url: download_url.clone(),
         error,
     };
-    let response = reqwest::Client::new()
+    let response = client
         .get(&download_url)
         .send()
         .await
@@ -71,6 +209,7 @@ pub async fn download_release(
     let mut file = fs::create_file(&archive_file)?;
     let mut stream = response.bytes_stream();
     let mut downloaded: u64 = 0;
+    let mut sha = Sha256::new();
 
     while let Some(item) = stream.next().await {
         let chunk = item.map_err(handle_error)?;
@@ -80,47 +219,66 @@ pub async fn download_release(
             error,
         })?;
 
+        sha.update(&chunk);
+
         downloaded = cmp::min(downloaded + (chunk.len() as u64), total_size);
 
         on_chunk(downloaded, total_size);
     }
 
-    Ok(DownloadResult {
+    let result = DownloadResult {
         archive_file,
         file: download_file,
         file_stem: target_file,
         url: download_url,
-    })
+        version: version.to_owned(),
+    };
+
+    if options.skip {
+        return Ok(result);
+    }
+
+    // Verify before unpacking, and remove the archive if it can't be trusted
+    if let Err(error) =
+        verify_download(client, &result, &format!("{:x}", sha.finalize()), options).await
+    {
+        fs::remove_file(&result.archive_file)?;
+
+        return Err(error);
+    }
+
+    Ok(result)
 }
 
-pub fn unpack_release(
-    download: DownloadResult,
-    install_dir: impl AsRef<Path>,
-    relocate_dir: impl AsRef<Path>,
-) -> miette::Result<bool> {
-    let temp_dir = download
-        .archive_file
-        .parent()
-        .unwrap()
-        .join(&download.file_stem);
+/// Phases of unpacking a release, in the order they occur.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum UnpackPhase {
+    Unpacking,
+    Relocating,
+    Replacing,
+    CleaningUp,
+}
 
-    // Unpack the downloaded file
-    Archiver::new(&temp_dir, &download.archive_file).unpack_from_ext()?;
+fn determine_bin_dir(install_dir: impl AsRef<Path>) -> PathBuf {
+    match env::var("PROTO_INSTALL_DIR") {
+        Ok(dir) => PathBuf::from(dir),
+        Err(_) => install_dir.as_ref().to_owned(),
+    }
+}
 
-    // Move the old binaries
-    let bin_names = if cfg!(windows) {
+fn get_bin_names() -> Vec<&'static str> {
+    if cfg!(windows) {
         vec!["proto.exe", "proto-shim.exe"]
     } else {
         vec!["proto", "proto-shim"]
-    };
-    let bin_dir = match env::var("PROTO_INSTALL_DIR") {
-        Ok(dir) => PathBuf::from(dir),
-        Err(_) => install_dir.as_ref().to_owned(),
-    };
+    }
+}
 
-    for bin_name in &bin_names {
+// Move the current binaries out of the bin directory
+fn relocate_binaries(bin_dir: &Path, relocate_dir: &Path) -> miette::Result<()> {
+    for bin_name in get_bin_names() {
         let output_path = bin_dir.join(bin_name);
-        let relocate_path = relocate_dir.as_ref().join(bin_name);
+        let relocate_path = relocate_dir.join(bin_name);
 
         if output_path.exists() && output_path != relocate_path {
             fs::rename(&output_path, &relocate_path)?;
@@ -129,38 +287,279 @@ pub fn unpack_release(
         // If not installed at our standard location
         if let Ok(current_exe) = env::current_exe() {
             if current_exe != output_path
-                && current_exe
-                    .file_name()
-                    .is_some_and(|name| name == *bin_name)
+                && current_exe.file_name().is_some_and(|name| name == bin_name)
             {
                 fs::rename(&current_exe, &relocate_path)?;
             }
         }
     }
 
-    // Move the new binary to the bins directory
-    let mut unpacked = false;
+    Ok(())
+}
+
+// Copy the new binaries into the bin directory, from the first input
+// directory that contains them. Existing binaries are removed first,
+// as they may be running (when repairing an upgrade).
+fn replace_binaries(input_dirs: &[PathBuf], bin_dir: &Path) -> miette::Result<bool> {
+    let mut replaced = false;
 
-    for bin_name in &bin_names {
+    for bin_name in get_bin_names() {
         let output_path = bin_dir.join(bin_name);
-        let input_paths = vec![
-            temp_dir.join(&download.file_stem).join(bin_name),
-            temp_dir.join(bin_name),
-        ];
 
-        for input_path in input_paths {
+        for input_dir in input_dirs {
+            let input_path = input_dir.join(bin_name);
+
             if input_path.exists() {
+                if output_path.exists() {
+                    fs::remove_file(&output_path)?;
+                }
+
                 fs::copy_file(input_path, &output_path)?;
                 fs::update_perms(&output_path, None)?;
 
-                unpacked = true;
+                replaced = true;
                 break;
             }
         }
     }
 
-    fs::remove(temp_dir)?;
-    fs::remove(download.archive_file)?;
+    Ok(replaced)
+}
+
+pub const STAGED_UPGRADE_FILE: &str = ".staged-upgrade";
+
+/// An upgrade whose new binaries have been staged, but were not yet fully
+/// moved into the bin directory. A marker file is written to the proto tools
+/// directory before relocating, and only removed once the binaries are replaced,
+/// so an interrupted upgrade can be detected on the next invocation.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct StagedUpgrade {
+    /// Directory the binaries are being replaced in.
+    pub bin_dir: PathBuf,
+
+    /// Files and directories to remove once the upgrade has been completed.
+    pub cleanup_paths: Vec<PathBuf>,
+
+    /// Directory the previous binaries are being relocated to.
+    pub relocate_dir: PathBuf,
+
+    /// Directories containing the new binaries, in order of precedence.
+    pub staged_dirs: Vec<PathBuf>,
+
+    /// Version being upgraded to.
+    pub version: String,
+}
+
+impl StagedUpgrade {
+    /// Load the staged upgrade from the marker file in the provided
+    /// proto tools directory, if one exists.
+    pub fn load(proto_dir: impl AsRef<Path>) -> miette::Result<Option<Self>> {
+        let path = proto_dir.as_ref().join(STAGED_UPGRADE_FILE);
+
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let mut upgrade = StagedUpgrade::default();
+
+        for line in fs::read_file(&path)?.lines() {
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+
+            match key {
+                "bin_dir" => upgrade.bin_dir = PathBuf::from(value),
+                "cleanup_path" => upgrade.cleanup_paths.push(PathBuf::from(value)),
+                "relocate_dir" => upgrade.relocate_dir = PathBuf::from(value),
+                "staged_dir" => upgrade.staged_dirs.push(PathBuf::from(value)),
+                "version" => upgrade.version = value.to_owned(),
+                _ => {}
+            };
+        }
+
+        Ok(Some(upgrade))
+    }
+
+    /// Return true if a marker file exists in the provided proto tools directory.
+    pub fn exists(proto_dir: impl AsRef<Path>) -> bool {
+        proto_dir.as_ref().join(STAGED_UPGRADE_FILE).exists()
+    }
+
+    fn get_marker_path(&self) -> PathBuf {
+        self.relocate_dir
+            .parent()
+            .unwrap_or(&self.relocate_dir)
+            .join(STAGED_UPGRADE_FILE)
+    }
+
+    fn save(&self) -> miette::Result<()> {
+        let mut lines = vec![
+            format!("version={}", self.version),
+            format!("bin_dir={}", self.bin_dir.display()),
+            format!("relocate_dir={}", self.relocate_dir.display()),
+        ];
+
+        for dir in &self.staged_dirs {
+            lines.push(format!("staged_dir={}", dir.display()));
+        }
+
+        for path in &self.cleanup_paths {
+            lines.push(format!("cleanup_path={}", path.display()));
+        }
+
+        fs::write_file(self.get_marker_path(), lines.join("\n"))?;
+
+        Ok(())
+    }
+
+    /// Complete the upgrade by moving the staged binaries into the bin directory.
+    /// Returns false if the staged binaries no longer exist.
+    pub fn complete(&self) -> miette::Result<bool> {
+        replace_binaries(&self.staged_dirs, &self.bin_dir)
+    }
+
+    /// Roll back the upgrade by moving the relocated binaries back into the bin
+    /// directory. Returns false if no binaries were relocated.
+    pub fn roll_back(&self) -> miette::Result<bool> {
+        replace_binaries(&[self.relocate_dir.clone()], &self.bin_dir)
+    }
+
+    /// Remove the marker file and any staged files that are no longer required.
+    pub fn clear(&self) -> miette::Result<()> {
+        fs::remove_file(self.get_marker_path())?;
+
+        for path in &self.cleanup_paths {
+            fs::remove(path)?;
+        }
+
+        Ok(())
+    }
+}
+
+pub fn unpack_release(
+    download: DownloadResult,
+    install_dir: impl AsRef<Path>,
+    relocate_dir: impl AsRef<Path>,
+) -> miette::Result<bool> {
+    unpack_release_with_progress(download, install_dir, relocate_dir, |_| {})
+}
+
+/// Like [`unpack_release`], but calls `on_phase` as each phase begins.
+pub fn unpack_release_with_progress(
+    download: DownloadResult,
+    install_dir: impl AsRef<Path>,
+    relocate_dir: impl AsRef<Path>,
+    on_phase: impl Fn(UnpackPhase),
+) -> miette::Result<bool> {
+    let temp_dir = download
+        .archive_file
+        .parent()
+        .unwrap()
+        .join(&download.file_stem);
+
+    // Unpack the downloaded file
+    on_phase(UnpackPhase::Unpacking);
+
+    Archiver::new(&temp_dir, &download.archive_file).unpack_from_ext()?;
+
+    let staged = StagedUpgrade {
+        bin_dir: determine_bin_dir(install_dir),
+        cleanup_paths: vec![temp_dir.clone(), download.archive_file.clone()],
+        relocate_dir: relocate_dir.as_ref().to_path_buf(),
+        staged_dirs: vec![temp_dir.join(&download.file_stem), temp_dir.clone()],
+        version: download.version.clone(),
+    };
+
+    staged.save()?;
+
+    // Move the old binaries
+    on_phase(UnpackPhase::Relocating);
+
+    relocate_binaries(&staged.bin_dir, &staged.relocate_dir)?;
+
+    // Move the new binary to the bins directory
+    on_phase(UnpackPhase::Replacing);
+
+    let unpacked = staged.complete()?;
+
+    on_phase(UnpackPhase::CleaningUp);
+
+    staged.clear()?;
 
     Ok(unpacked)
 }
+
+/// Return true if the directory contains the binaries of a previous
+/// release, which were relocated when upgrading.
+pub fn has_stored_release(stored_dir: impl AsRef<Path>) -> bool {
+    let stored_dir = stored_dir.as_ref();
+
+    get_bin_names()
+        .iter()
+        .any(|bin_name| stored_dir.join(bin_name).exists())
+}
+
+/// Switch to a previous release using the binaries stored in the provided
+/// directory, instead of downloading it. The current binaries are relocated,
+/// so that they can be switched back to.
+pub fn restore_release(
+    stored_dir: impl AsRef<Path>,
+    install_dir: impl AsRef<Path>,
+    relocate_dir: impl AsRef<Path>,
+    on_phase: impl Fn(UnpackPhase),
+) -> miette::Result<bool> {
+    let stored_dir = stored_dir.as_ref();
+
+    let staged = StagedUpgrade {
+        bin_dir: determine_bin_dir(install_dir),
+        cleanup_paths: vec![],
+        relocate_dir: relocate_dir.as_ref().to_path_buf(),
+        staged_dirs: vec![stored_dir.to_path_buf()],
+        version: fs::file_name(stored_dir),
+    };
+
+    staged.save()?;
+
+    on_phase(UnpackPhase::Relocating);
+
+    relocate_binaries(&staged.bin_dir, &staged.relocate_dir)?;
+
+    on_phase(UnpackPhase::Replacing);
+
+    let restored = staged.complete()?;
+
+    staged.clear()?;
+
+    Ok(restored)
+}
+
+/// Verify the newly installed binary by running `proto --version`,
+/// and checking that it outputs the expected version.
+pub fn verify_release(install_dir: impl AsRef<Path>, version: &str) -> miette::Result<()> {
+    let bin =
+        determine_bin_dir(install_dir).join(if cfg!(windows) { "proto.exe" } else { "proto" });
+
+    let handle_error = |output: Option<String>| ProtoInstallerError::VerifyFailed {
+        bin: bin.clone(),
+        version: version.to_owned(),
+        output,
+    };
+
+    let output = Command::new(&bin)
+        .arg("--version")
+        .output()
+        .map_err(|error| handle_error(Some(error.to_string())))?;
+
+    let stdout = String::from_utf8_lossy(&output.stdout).trim().to_owned();
+
+    if !output.status.success() || !stdout.ends_with(version) {
+        return Err(handle_error(if stdout.is_empty() {
+            None
+        } else {
+            Some(stdout)
+        })
+        .into());
+    }
+
+    Ok(())
+}
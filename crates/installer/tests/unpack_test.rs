@@ -0,0 +1,133 @@
+use proto_installer::*;
+use starbase_archive::Archiver;
+use starbase_sandbox::{create_empty_sandbox, Sandbox};
+use std::cell::RefCell;
+use std::fs;
+
+const FILE_STEM: &str = "proto_cli-test";
+
+fn bin_name(name: &str) -> String {
+    if cfg!(windows) {
+        format!("{name}.exe")
+    } else {
+        name.to_owned()
+    }
+}
+
+fn create_download(sandbox: &Sandbox) -> DownloadResult {
+    sandbox.create_file(format!("source/{}", bin_name("proto")), "new");
+    sandbox.create_file(format!("source/{}", bin_name("proto-shim")), "new");
+    sandbox.create_file(format!("bin/{}", bin_name("proto")), "old");
+
+    let file = format!("{FILE_STEM}.tar.xz");
+    let archive_file = sandbox.path().join("temp").join(&file);
+
+    fs::create_dir_all(archive_file.parent().unwrap()).unwrap();
+    fs::create_dir_all(sandbox.path().join("relocate")).unwrap();
+
+    let source_dir = sandbox.path().join("source");
+    let mut archiver = Archiver::new(&source_dir, &archive_file);
+    archiver.add_source_file(bin_name("proto"), None);
+    archiver.add_source_file(bin_name("proto-shim"), None);
+    archiver.pack_from_ext().unwrap();
+
+    DownloadResult {
+        archive_file,
+        file,
+        file_stem: FILE_STEM.into(),
+        url: format!("https://example.com/{FILE_STEM}.tar.xz"),
+        version: "1.2.3".into(),
+    }
+}
+
+mod unpack_release {
+    use super::*;
+
+    #[test]
+    fn replaces_and_relocates_binaries() {
+        let sandbox = create_empty_sandbox();
+        let download = create_download(&sandbox);
+        let archive_file = download.archive_file.clone();
+
+        let unpacked = unpack_release(
+            download,
+            sandbox.path().join("bin"),
+            sandbox.path().join("relocate"),
+        )
+        .unwrap();
+
+        assert!(unpacked);
+        assert_eq!(
+            fs::read_to_string(sandbox.path().join("bin").join(bin_name("proto"))).unwrap(),
+            "new"
+        );
+        assert_eq!(
+            fs::read_to_string(sandbox.path().join("relocate").join(bin_name("proto"))).unwrap(),
+            "old"
+        );
+        assert!(!archive_file.exists());
+        assert!(!StagedUpgrade::exists(sandbox.path()));
+    }
+
+    #[test]
+    fn reports_phases_in_order() {
+        let sandbox = create_empty_sandbox();
+        let phases = RefCell::new(vec![]);
+
+        unpack_release_with_progress(
+            create_download(&sandbox),
+            sandbox.path().join("bin"),
+            sandbox.path().join("relocate"),
+            |phase| phases.borrow_mut().push(phase),
+        )
+        .unwrap();
+
+        assert_eq!(
+            phases.into_inner(),
+            vec![
+                UnpackPhase::Unpacking,
+                UnpackPhase::Relocating,
+                UnpackPhase::Replacing,
+                UnpackPhase::CleaningUp,
+            ]
+        );
+    }
+}
+
+#[cfg(unix)]
+mod verify_release {
+    use super::*;
+    use std::os::unix::fs::PermissionsExt;
+
+    fn create_bin(sandbox: &Sandbox, output: &str) {
+        sandbox.create_file("bin/proto", format!("#!/bin/sh\necho \"{output}\"\n"));
+
+        let bin = sandbox.path().join("bin/proto");
+        fs::set_permissions(&bin, fs::Permissions::from_mode(0o755)).unwrap();
+    }
+
+    #[test]
+    fn passes_when_version_matches() {
+        let sandbox = create_empty_sandbox();
+        create_bin(&sandbox, "proto 1.2.3");
+
+        assert!(verify_release(sandbox.path().join("bin"), "1.2.3").is_ok());
+    }
+
+    #[test]
+    fn errors_when_version_doesnt_match() {
+        let sandbox = create_empty_sandbox();
+        create_bin(&sandbox, "proto 1.2.2");
+
+        let error = verify_release(sandbox.path().join("bin"), "1.2.3").unwrap_err();
+
+        assert!(error.to_string().contains("expected version 1.2.3"));
+    }
+
+    #[test]
+    fn errors_when_binary_is_missing() {
+        let sandbox = create_empty_sandbox();
+
+        assert!(verify_release(sandbox.path().join("bin"), "1.2.3").is_err());
+    }
+}
@@ -0,0 +1,73 @@
+use proto_installer::*;
+
+const FILE: &str = "proto_cli-x86_64-unknown-linux-gnu.tar.xz";
+
+mod extract_checksum {
+    use super::*;
+
+    #[test]
+    fn supports_only_a_checksum() {
+        assert_eq!(
+            extract_checksum("ABC123\n", FILE),
+            Some("abc123".to_owned())
+        );
+    }
+
+    #[test]
+    fn supports_checksum_and_file() {
+        assert_eq!(
+            extract_checksum(&format!("abc123  {FILE}"), FILE),
+            Some("abc123".to_owned())
+        );
+        assert_eq!(
+            extract_checksum(&format!("abc123 *{FILE}"), FILE),
+            Some("abc123".to_owned())
+        );
+    }
+
+    #[test]
+    fn finds_matching_file_in_multiple_lines() {
+        let content = format!("def456  other.tar.xz\nabc123  {FILE}\n");
+
+        assert_eq!(extract_checksum(&content, FILE), Some("abc123".to_owned()));
+    }
+
+    #[test]
+    fn returns_none_when_no_match() {
+        assert_eq!(
+            extract_checksum("def456  other.tar.xz\nghi789  another.tar.xz", FILE),
+            None
+        );
+        assert_eq!(extract_checksum("", FILE), None);
+    }
+}
+
+mod verify_checksum {
+    use super::*;
+
+    #[test]
+    fn passes_when_matching() {
+        assert!(verify_checksum(&format!("abc123  {FILE}"), FILE, "abc123").is_ok());
+    }
+
+    #[test]
+    fn errors_when_mismatched() {
+        let error = verify_checksum(&format!("abc123  {FILE}"), FILE, "def456").unwrap_err();
+
+        assert!(error.to_string().contains("Checksum mismatch"));
+    }
+
+    #[test]
+    fn errors_when_missing() {
+        assert!(verify_checksum("", FILE, "abc123").is_err());
+    }
+}
+
+mod verify_signature {
+    use super::*;
+
+    #[test]
+    fn errors_for_invalid_public_key() {
+        assert!(verify_signature(b"data", "invalid", "invalid", FILE).is_err());
+    }
+}
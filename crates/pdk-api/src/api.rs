@@ -1,7 +1,7 @@
 use crate::shapes::StringOrVec;
 use rustc_hash::FxHashMap;
 use std::path::PathBuf;
-use system_env::SystemDependency;
+use system_env::{SystemArch, SystemDependency, SystemOS};
 use version_spec::{UnresolvedVersionSpec, VersionSpec};
 use warpgate_api::*;
 
@@ -36,6 +36,19 @@ api_enum!(
     }
 );
 
+api_enum!(
+    /// Supported kinds of tools.
+    #[derive(Default)]
+    pub enum ToolKind {
+        /// Provides executables that will be shimmed and symlinked.
+        #[default]
+        Executable,
+        /// Provides an SDK root directory (JDKs, Android SDK, etc) that's
+        /// consumed through environment variables, instead of executables.
+        SdkRoot,
+    }
+);
+
 api_struct!(
     /// Input passed to the `register_tool` function.
     pub struct ToolMetadataInput {
@@ -65,6 +78,20 @@ api_struct!(
 api_struct!(
     /// Output returned by the `register_tool` function.
     pub struct ToolMetadataOutput {
+        /// Version of the WASM API that the plugin targets, which is the version
+        /// of proto that introduced it. Checked against the host by `proto doctor`.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub api_version: Option<Version>,
+
+        /// JSON schema of the settings supported in `[tools.<id>]`.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub config_schema: Option<serde_json::Value>,
+
+        /// Public key used to verify minisign signatures of downloads, when
+        /// `download_prebuilt` does not provide a `checksum_public_key`.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub checksum_public_key: Option<String>,
+
         /// Default alias or version to use as a fallback.
         #[serde(skip_serializing_if = "Option::is_none")]
         pub default_version: Option<UnresolvedVersionSpec>,
@@ -72,18 +99,42 @@ api_struct!(
         /// Controls aspects of the tool inventory.
         pub inventory: ToolInventoryMetadata,
 
+        /// Kind of the tool, which controls how it's made available.
+        pub kind: ToolKind,
+
         /// Human readable name of the tool.
         pub name: String,
 
+        /// Hosts and host capabilities that the plugin requires. When not declared,
+        /// built-in plugins are unrestricted, while third-party plugins are denied
+        /// everything, unless granted by the user.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub permissions: Option<PluginPermissions>,
+
         /// Version of the plugin.
         #[serde(skip_serializing_if = "Option::is_none")]
         pub plugin_version: Option<String>,
 
+        /// IDs of other tools that this tool requires at runtime (npm requires node).
+        /// Uninstalling the last version of a required tool will be refused.
+        #[serde(skip_serializing_if = "Vec::is_empty")]
+        pub requires: Vec<String>,
+
         /// Names of commands that will self-upgrade the tool,
         /// and should be blocked from happening.
         #[serde(skip_serializing_if = "Vec::is_empty")]
         pub self_upgrade_commands: Vec<String>,
 
+        /// Names of environment variables that will be set to the
+        /// tool's install directory (`JAVA_HOME`, etc), when an SDK root.
+        #[serde(skip_serializing_if = "Vec::is_empty")]
+        pub root_env_vars: Vec<String>,
+
+        /// Operating systems, and their architectures, that the tool can be
+        /// installed on. When empty, all platforms are assumed to be supported.
+        #[serde(skip_serializing_if = "FxHashMap::is_empty")]
+        pub supported_platforms: FxHashMap<SystemOS, Vec<SystemArch>>,
+
         /// Type of the tool.
         #[serde(rename = "type")]
         pub type_of: PluginType,
@@ -92,6 +143,60 @@ api_struct!(
 
 // VERSION DETECTION
 
+api_enum!(
+    /// Structured formats that proto can extract a version from,
+    /// without calling the `parse_version_file` function.
+    #[serde(tag = "type", rename_all = "kebab-case")]
+    pub enum VersionFileFormat {
+        /// The entire file content is the version.
+        Plain,
+
+        /// A value in a JSON file, at a dot-separated path (`engines.node`).
+        Json { path: String },
+
+        /// A value in a TOML file, at a dot-separated path (`project.requires-python`).
+        Toml { path: String },
+
+        /// A line in an asdf `.tool-versions` file, for the provided tool name.
+        ToolVersions { name: String },
+
+        /// The first capture group of a multi-line regex pattern (`^go (\S+)`).
+        Regex { pattern: String },
+
+        /// The `packageManager` field of a `package.json` file (`pnpm@8.15.1`),
+        /// when it declares the provided package manager name.
+        PackageManager { name: String },
+    }
+);
+
+api_struct!(
+    /// A file that should be checked for version information,
+    /// with options for how the version is extracted.
+    pub struct DetectVersionFile {
+        /// Name of the file.
+        pub file: String,
+
+        /// Format to extract the version with. When not defined, the
+        /// `parse_version_file` function is called, if implemented.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub format: Option<VersionFileFormat>,
+
+        /// Ignore the file in project directories, and only
+        /// detect from the file in the user's home directory.
+        #[serde(skip_serializing_if = "is_false")]
+        pub ignore_local: bool,
+
+        /// Allow the detected version to resolve to a prerelease.
+        #[serde(skip_serializing_if = "is_false")]
+        pub prerelease_allowed: bool,
+
+        /// When multiple files in a directory contain a version, files
+        /// with a higher priority take precedence. Files with the
+        /// same priority are checked in the order they're defined.
+        pub priority: i8,
+    }
+);
+
 api_struct!(
     /// Output returned by the `detect_version_files` function.
     pub struct DetectVersionOutput {
@@ -99,6 +204,11 @@ api_struct!(
         #[serde(skip_serializing_if = "Vec::is_empty")]
         pub files: Vec<String>,
 
+        /// List of files with a structured format or options,
+        /// that are checked after `files` of the same priority.
+        #[serde(skip_serializing_if = "Vec::is_empty")]
+        pub detailed_files: Vec<DetectVersionFile>,
+
         /// List of path patterns to ignore when traversing directories.
         #[serde(skip_serializing_if = "Vec::is_empty")]
         pub ignore: Vec<String>,
@@ -126,6 +236,30 @@ api_struct!(
     }
 );
 
+api_struct!(
+    /// Input passed to the `write_version_file` function.
+    pub struct WriteVersionFileInput {
+        /// Current file contents to update.
+        pub content: String,
+
+        /// Name of file that's being written.
+        pub file: String,
+
+        /// The version that was pinned.
+        pub version: UnresolvedVersionSpec,
+    }
+);
+
+api_struct!(
+    /// Output returned by the `write_version_file` function.
+    pub struct WriteVersionFileOutput {
+        /// The updated file contents to write.
+        /// If not provided, the file will not be modified.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub content: Option<String>,
+    }
+);
+
 // DOWNLOAD, BUILD, INSTALL, VERIFY
 
 api_struct!(
@@ -182,18 +316,25 @@ api_struct!(
     pub struct BuildInstructionsInput {
         /// Current tool context.
         pub context: ToolContext,
+
+        /// Virtual directory to install to.
+        pub install_dir: VirtualPath,
     }
 );
 
 api_enum!(
+    /// Location in which to acquire the source files.
     #[derive(Default)]
     #[serde(tag = "type", rename_all = "lowercase")]
     pub enum SourceLocation {
+        /// No sources are required, instructions are executed in an empty directory.
         #[default]
         None,
-        Archive {
-            url: String,
-        },
+
+        /// An archive that will be downloaded and unpacked.
+        Archive { url: String },
+
+        /// A Git repository that will be cloned, and checked out at the reference.
         Git {
             url: String,
             reference: String,
@@ -203,8 +344,13 @@ api_enum!(
 );
 
 api_enum!(
+    /// An instruction to execute when building from source. Instructions are
+    /// executed within the build directory, and are passed the absolute build
+    /// and install directories with the `PROTO_BUILD_DIR` and `PROTO_INSTALL_DIR`
+    /// environment variables.
     #[serde(tag = "type", rename_all = "lowercase")]
     pub enum BuildInstruction {
+        /// Execute a command with arguments and additional environment variables.
         Command {
             bin: String,
             args: Vec<String>,
@@ -217,6 +363,7 @@ api_struct!(
     /// Output returned by the `build_instructions` function.
     pub struct BuildInstructionsOutput {
         /// Link to the documentation/help.
+        #[serde(skip_serializing_if = "Option::is_none")]
         pub help_url: Option<String>,
 
         /// Location in which to acquire the source files. Can be an archive URL,
@@ -224,7 +371,8 @@ api_struct!(
         pub source: SourceLocation,
 
         /// List of instructions to execute to build the tool, after system
-        /// dependencies have been installed.
+        /// dependencies have been installed. If the instructions do not install
+        /// anything into the install directory, the build directory is used as-is.
         #[serde(skip_serializing_if = "Vec::is_empty")]
         pub instructions: Vec<BuildInstruction>,
 
@@ -246,6 +394,38 @@ api_struct!(
     }
 );
 
+api_enum!(
+    /// Hashing algorithms supported for checksum verification.
+    #[derive(Copy, Default)]
+    #[serde(rename_all = "lowercase")]
+    pub enum ChecksumAlgorithm {
+        Blake3,
+        #[default]
+        Sha256,
+        Sha512,
+    }
+);
+
+api_enum!(
+    /// Formats of checksum files.
+    #[derive(Copy, Default)]
+    #[serde(rename_all = "lowercase")]
+    pub enum ChecksumFormat {
+        /// Detect the format from each line of the file.
+        #[default]
+        Auto,
+
+        /// `SHA256 (<file>) = <hash>`, as generated by BSD tools.
+        Bsd,
+
+        /// `<hash>  <file>` or `<hash> *<file>`, as generated by GNU coreutils.
+        Gnu,
+
+        /// The entire file content is the hash.
+        Single,
+    }
+);
+
 api_struct!(
     /// Output returned by the `download_prebuilt` function.
     pub struct DownloadPrebuiltOutput {
@@ -254,6 +434,14 @@ api_struct!(
         #[serde(skip_serializing_if = "Option::is_none")]
         pub archive_prefix: Option<String>,
 
+        /// Algorithm used to hash the download. Defaults to SHA256.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub checksum_algorithm: Option<ChecksumAlgorithm>,
+
+        /// Format of the checksum file. Defaults to detecting the format.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub checksum_format: Option<ChecksumFormat>,
+
         /// File name of the checksum to download. If not provided,
         /// will attempt to extract it from the URL.
         #[serde(skip_serializing_if = "Option::is_none")]
@@ -273,8 +461,18 @@ api_struct!(
         #[serde(skip_serializing_if = "Option::is_none")]
         pub download_name: Option<String>,
 
+        /// Size of the download in bytes, used to schedule large downloads
+        /// when installing many tools. If not provided, a HEAD request is made.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub download_size: Option<u64>,
+
         /// A secure URL to download the tool/archive.
         pub download_url: String,
+
+        /// A URL to download a detached minisign signature of the tool/archive,
+        /// which is verified in addition to the checksum. Requires a public key.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub signature_url: Option<String>,
     }
 );
 
@@ -313,6 +511,41 @@ api_struct!(
     }
 );
 
+api_struct!(
+    /// Input passed to the `verify_install` function.
+    pub struct VerifyInstallInput {
+        /// Current tool context.
+        pub context: ToolContext,
+    }
+);
+
+api_struct!(
+    /// A command that is ran to verify that an install is functional.
+    pub struct VerifyInstallCommand {
+        /// Arguments to pass to the executable.
+        pub args: Vec<String>,
+
+        /// Relative path from the tool directory to the executable to run.
+        /// If not provided, will use the primary executable.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub exe_path: Option<PathBuf>,
+
+        /// A string that the command's output (stdout or stderr) must contain,
+        /// for example the version. If not provided, only the exit code is checked.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub expected_output: Option<String>,
+    }
+);
+
+api_struct!(
+    /// Output returned by the `verify_install` function.
+    pub struct VerifyInstallOutput {
+        /// Commands to run against the installed version. Each command must
+        /// exit successfully for the install to be considered functional.
+        pub commands: Vec<VerifyInstallCommand>,
+    }
+);
+
 // EXECUTABLES, BINARYS, GLOBALS
 
 api_struct!(
@@ -356,14 +589,17 @@ api_struct!(
         pub parent_exe_name: Option<String>,
 
         /// Custom args to prepend to user-provided args within the generated shim.
+        /// Prefer the `define_shims` function for customizing shims.
         #[serde(skip_serializing_if = "Option::is_none")]
         pub shim_before_args: Option<StringOrVec>,
 
         /// Custom args to append to user-provided args within the generated shim.
+        /// Prefer the `define_shims` function for customizing shims.
         #[serde(skip_serializing_if = "Option::is_none")]
         pub shim_after_args: Option<StringOrVec>,
 
         /// Custom environment variables to set when executing the shim.
+        /// Prefer the `define_shims` function for customizing shims.
         #[serde(skip_serializing_if = "Option::is_none")]
         pub shim_env_vars: Option<FxHashMap<String, String>>,
     }
@@ -411,6 +647,57 @@ api_struct!(
     }
 );
 
+// SHIMS
+
+api_struct!(
+    /// Input passed to the `define_shims` function.
+    pub struct DefineShimsInput {
+        /// Current tool context.
+        pub context: ToolContext,
+
+        /// Names of the shims that will be generated.
+        pub shims: Vec<String>,
+    }
+);
+
+api_struct!(
+    /// Behavior of an individual shim when it is executed.
+    pub struct ShimConfig {
+        /// Custom args to append to user-provided args.
+        #[serde(skip_serializing_if = "Vec::is_empty")]
+        pub after_args: Vec<String>,
+
+        /// Custom args to prepend to user-provided args.
+        #[serde(skip_serializing_if = "Vec::is_empty")]
+        pub before_args: Vec<String>,
+
+        /// Custom environment variables to set when executing the shim.
+        #[serde(skip_serializing_if = "FxHashMap::is_empty")]
+        pub env_vars: FxHashMap<String, String>,
+
+        /// Only apply the custom args and environment variables when the shim
+        /// is invoked by a parent process with this name (e.g. `npm` for `npx`).
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub parent_process: Option<String>,
+
+        /// Resolve symlinks of the executable to its real path before executing,
+        /// so that relative file lookups from the executable work correctly.
+        #[serde(skip_serializing_if = "is_false")]
+        pub resolve_symlinks: bool,
+    }
+);
+
+api_struct!(
+    /// Output returned by the `define_shims` function.
+    pub struct DefineShimsOutput {
+        /// Configures the behavior of shims. The map key is the name
+        /// of the shim file, and the primary shim is the tool ID.
+        /// Takes precedence over the `shim_*` fields in `locate_executables`.
+        #[serde(skip_serializing_if = "FxHashMap::is_empty")]
+        pub shims: FxHashMap<String, ShimConfig>,
+    }
+);
+
 // VERSION RESOLVING
 
 api_struct!(
@@ -436,6 +723,11 @@ api_struct!(
         #[serde(skip_serializing_if = "FxHashMap::is_empty")]
         pub aliases: FxHashMap<String, Version>,
 
+        /// Mapping of versions to when they were released,
+        /// as a UNIX timestamp in milliseconds.
+        #[serde(skip_serializing_if = "FxHashMap::is_empty")]
+        pub release_dates: FxHashMap<Version, u128>,
+
         /// List of available production versions to install.
         #[serde(skip_serializing_if = "Vec::is_empty")]
         pub versions: Vec<Version>,
@@ -507,9 +799,30 @@ api_struct!(
     }
 );
 
+api_enum!(
+    /// A change to the user's configuration suggested by a plugin. Changes are
+    /// never applied directly, as they are reviewed by proto, recorded in the
+    /// config journal, and require confirmation in interactive mode.
+    #[serde(tag = "type", rename_all = "kebab-case")]
+    pub enum ConfigChange {
+        /// Add an alias for the tool to the global config.
+        AddAlias {
+            alias: String,
+            version: UnresolvedVersionSpec,
+        },
+
+        /// Pin a version of the tool in the global config.
+        PinVersion { version: UnresolvedVersionSpec },
+    }
+);
+
 api_struct!(
     /// Output returned by the `sync_manifest` function.
     pub struct SyncManifestOutput {
+        /// Changes to the user's configuration to suggest after syncing.
+        #[serde(skip_serializing_if = "Vec::is_empty")]
+        pub config_changes: Vec<ConfigChange>,
+
         /// List of versions that are currently installed. Will replace
         /// what is currently in the manifest.
         #[serde(skip_serializing_if = "Option::is_none")]
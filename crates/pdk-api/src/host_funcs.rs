@@ -0,0 +1,77 @@
+use warpgate_api::api_struct;
+
+api_struct!(
+    /// Input passed to the `parse_version_spec` host function.
+    pub struct ParseVersionSpecInput {
+        /// The version, requirement, or alias to parse.
+        pub spec: String,
+    }
+);
+
+api_struct!(
+    /// Output returned from the `parse_version_spec` host function.
+    pub struct ParseVersionSpecOutput {
+        /// Error message if the spec failed to parse.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub error: Option<String>,
+
+        /// The type of spec that was parsed: `alias`, `canary`,
+        /// `req`, `req-any`, or `version`.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub kind: Option<String>,
+
+        /// The spec normalized by proto, for example, `1.2` becomes `~1.2`.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub spec: Option<String>,
+    }
+);
+
+api_struct!(
+    /// Input passed to the `compare_versions` host function.
+    pub struct CompareVersionsInput {
+        /// The version on the left side of the comparison.
+        pub left: String,
+
+        /// The version on the right side of the comparison.
+        pub right: String,
+    }
+);
+
+api_struct!(
+    /// Output returned from the `compare_versions` host function.
+    pub struct CompareVersionsOutput {
+        /// Error message if either version failed to parse.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub error: Option<String>,
+
+        /// -1 if left is lower, 0 if equal, and 1 if left is higher.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub ordering: Option<i8>,
+    }
+);
+
+api_struct!(
+    /// Input passed to the `satisfies_version` host function.
+    pub struct SatisfiesVersionInput {
+        /// Allow prerelease versions to satisfy requirements.
+        pub include_prereleases: bool,
+
+        /// The version or requirement to match against.
+        pub spec: String,
+
+        /// The fully-qualified version to check.
+        pub version: String,
+    }
+);
+
+api_struct!(
+    /// Output returned from the `satisfies_version` host function.
+    pub struct SatisfiesVersionOutput {
+        /// Error message if the spec or version failed to parse.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub error: Option<String>,
+
+        /// Whether the version satisfies the spec.
+        pub satisfied: bool,
+    }
+);
@@ -1,11 +1,13 @@
 mod api;
 mod error;
 mod hooks;
+mod host_funcs;
 mod shapes;
 
 pub use api::*;
 pub use error::*;
 pub use hooks::*;
+pub use host_funcs::*;
 pub use shapes::*;
 pub use system_env::{DependencyConfig, DependencyName, SystemDependency, SystemPackageManager};
 pub use version_spec::*;
@@ -0,0 +1,185 @@
+use proto_core::get_home_dir;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const HOME_TOKEN: &str = "{home}";
+const SANDBOX_TOKEN: &str = "{sandbox}";
+
+/// Return true if fixtures should be recorded (`PROTO_TEST_RECORD`),
+/// overwriting existing fixtures, instead of being replayed.
+pub fn is_recording_fixtures() -> bool {
+    env::var("PROTO_TEST_RECORD").is_ok_and(|value| !value.is_empty() && value != "false")
+}
+
+/// Return the directory where fixtures are stored, defaulting
+/// to `tests/__fixtures__` in the crate being tested.
+pub fn get_fixtures_dir() -> PathBuf {
+    match env::var("PROTO_TEST_FIXTURES_DIR") {
+        Ok(dir) => PathBuf::from(dir),
+        Err(_) => PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap_or_default())
+            .join("tests")
+            .join("__fixtures__"),
+    }
+}
+
+/// Records the output of plugin functions that access the network (`load_versions`,
+/// `download_prebuilt`, etc) and downloaded files (checksums) into fixtures,
+/// and replays them from disk on subsequent runs, so that plugins can be tested
+/// offline against real data. Run tests with `PROTO_TEST_RECORD=1` to record.
+///
+/// Fixtures are keyed by the function, its input, and the plugin configuration,
+/// with sandbox and home paths normalized, so that they are stable across runs.
+#[derive(Clone, Debug)]
+pub struct FixtureRecorder {
+    /// Directory where fixtures for the plugin are stored.
+    pub dir: PathBuf,
+
+    /// Record fixtures instead of replaying them.
+    pub record: bool,
+
+    home: String,
+    sandbox: String,
+    seed: String,
+}
+
+impl FixtureRecorder {
+    pub fn new(id: &str, sandbox: &Path, config: &BTreeMap<String, String>) -> Self {
+        let mut recorder = Self {
+            dir: get_fixtures_dir().join(id),
+            record: is_recording_fixtures(),
+            home: get_home_dir()
+                .map(|dir| dir.to_string_lossy().into_owned())
+                .unwrap_or_default(),
+            sandbox: sandbox.to_string_lossy().into_owned(),
+            seed: String::new(),
+        };
+
+        // The test environment differs between local and CI runs
+        recorder.seed = recorder.normalize(
+            &config
+                .iter()
+                .filter(|(key, _)| *key != "test_environment")
+                .map(|(key, value)| format!("{key}={value}"))
+                .collect::<Vec<_>>()
+                .join("\n"),
+        );
+
+        recorder
+    }
+
+    /// Call the plugin function, or replay its output from a fixture if one exists.
+    /// When recording, the output of the call is written to the fixture.
+    pub fn call<I: Serialize, O: Serialize + DeserializeOwned>(
+        &self,
+        func: &str,
+        input: I,
+        op: impl FnOnce(I) -> O,
+    ) -> O {
+        let input_json = self.normalize(&serde_json::to_string(&input).unwrap());
+        let path = self
+            .dir
+            .join(format!("{func}-{}.json", self.hash(&[func, &input_json])));
+
+        if !self.record && path.exists() {
+            let fixture: Value =
+                serde_json::from_str(&self.denormalize(&fs::read_to_string(&path).unwrap()))
+                    .unwrap();
+
+            return serde_json::from_value(fixture["output"].clone()).unwrap();
+        }
+
+        let output = op(input);
+
+        if self.record {
+            let output_json = self.normalize(&serde_json::to_string(&output).unwrap());
+            let fixture = json!({
+                "function": func,
+                "input": serde_json::from_str::<Value>(&input_json).unwrap(),
+                "output": serde_json::from_str::<Value>(&output_json).unwrap(),
+            });
+
+            fs::create_dir_all(&self.dir).unwrap();
+            fs::write(&path, serde_json::to_string_pretty(&fixture).unwrap()).unwrap();
+        }
+
+        output
+    }
+
+    /// Download the file from the URL to the destination, or copy it from
+    /// a fixture if one exists. When recording, the file is copied to the fixture.
+    pub async fn download_file(&self, client: &reqwest::Client, url: &str, dest_file: &Path) {
+        let file_name = url.rsplit('/').next().unwrap_or("file");
+        let path = self
+            .dir
+            .join("files")
+            .join(format!("{}-{file_name}", self.hash(&[url])));
+
+        if let Some(parent) = dest_file.parent() {
+            fs::create_dir_all(parent).unwrap();
+        }
+
+        if !self.record && path.exists() {
+            fs::copy(&path, dest_file).unwrap();
+
+            return;
+        }
+
+        warpgate::download_from_url_to_file(url, dest_file, client)
+            .await
+            .unwrap();
+
+        if self.record {
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::copy(dest_file, &path).unwrap();
+        }
+    }
+
+    fn hash(&self, values: &[&str]) -> String {
+        let mut sha = Sha256::new();
+        sha.update(&self.seed);
+
+        for value in values {
+            sha.update(value);
+        }
+
+        format!("{:x}", sha.finalize())[0..16].to_owned()
+    }
+
+    // Paths are escaped within JSON strings on Windows
+    fn replace_path(value: &str, path: &str, token: &str) -> String {
+        if path.is_empty() {
+            return value.to_owned();
+        }
+
+        value
+            .replace(&path.replace('\\', "\\\\"), token)
+            .replace(path, token)
+    }
+
+    fn normalize(&self, value: &str) -> String {
+        // The sandbox may be within the home directory, so replace it first
+        let value = Self::replace_path(value, &self.sandbox, SANDBOX_TOKEN);
+
+        Self::replace_path(&value, &self.home, HOME_TOKEN)
+    }
+
+    fn denormalize(&self, value: &str) -> String {
+        let escape = |path: &str| {
+            if cfg!(windows) {
+                path.replace('\\', "\\\\")
+            } else {
+                path.to_owned()
+            }
+        };
+
+        value
+            .replace(SANDBOX_TOKEN, &escape(&self.sandbox))
+            .replace(HOME_TOKEN, &escape(&self.home))
+    }
+}
@@ -0,0 +1,103 @@
+use crate::wrapper::WasmTestWrapper;
+use proto_core::{get_proto_version, Version, VersionReq};
+use std::panic::{self, AssertUnwindSafe};
+
+/// Versions of proto that introduced changes to the WASM plugin API,
+/// from the oldest supported version through the latest release.
+pub const HOST_API_VERSIONS: &[&str] =
+    &["0.24.0", "0.26.0", "0.28.0", "0.29.0", "0.30.0", "0.31.0"];
+
+/// Return the host versions to simulate, from the oldest supported
+/// version through the current version of proto.
+pub fn get_host_versions() -> Vec<Version> {
+    let current = get_proto_version();
+    let mut versions = HOST_API_VERSIONS
+        .iter()
+        .map(|version| Version::parse(version).unwrap())
+        .filter(|version| version < &current)
+        .collect::<Vec<_>>();
+
+    versions.push(current);
+    versions
+}
+
+/// The result of running a test against a simulated host version.
+#[derive(Debug)]
+pub struct HostCompatibility {
+    pub version: Version,
+    pub error: Option<String>,
+}
+
+impl HostCompatibility {
+    pub fn is_compatible(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+/// Run the test against the plugin once for each provided host version,
+/// and capture whether it passed. Plugin functions called through the
+/// wrapper receive the simulated version as `context.proto_version`.
+pub fn check_host_compatibility(
+    plugin: &mut WasmTestWrapper,
+    versions: &[Version],
+    test: impl Fn(&WasmTestWrapper),
+) -> Vec<HostCompatibility> {
+    let previous = plugin.host_version.take();
+    let mut results = vec![];
+
+    for version in versions {
+        plugin.host_version = Some(version.to_owned());
+
+        let error = panic::catch_unwind(AssertUnwindSafe(|| test(plugin)))
+            .err()
+            .map(|error| {
+                error
+                    .downcast_ref::<String>()
+                    .cloned()
+                    .or_else(|| error.downcast_ref::<&str>().map(|msg| msg.to_string()))
+                    .unwrap_or_else(|| "Unknown failure".into())
+            });
+
+        results.push(HostCompatibility {
+            version: version.to_owned(),
+            error,
+        });
+    }
+
+    plugin.host_version = previous;
+    results
+}
+
+/// Assert that the test passes for every simulated host version that
+/// satisfies the requirement. Versions outside of the range are not run.
+pub fn assert_host_compatibility(
+    plugin: &mut WasmTestWrapper,
+    req: &str,
+    test: impl Fn(&WasmTestWrapper),
+) {
+    let req = VersionReq::parse(req).unwrap();
+    let versions = get_host_versions()
+        .into_iter()
+        .filter(|version| req.matches(version))
+        .collect::<Vec<_>>();
+
+    assert!(
+        !versions.is_empty(),
+        "No simulated host versions match the requirement {req}"
+    );
+
+    let failures = check_host_compatibility(plugin, &versions, test)
+        .into_iter()
+        .filter_map(|result| {
+            result
+                .error
+                .map(|error| format!("  proto {}: {error}", result.version))
+        })
+        .collect::<Vec<_>>();
+
+    assert!(
+        failures.is_empty(),
+        "Plugin is not compatible with all host versions matching {req}:\n{}",
+        failures.join("\n")
+    );
+}
@@ -1,6 +1,10 @@
+mod fixtures;
+mod host;
 mod macros;
 mod wrapper;
 
+pub use fixtures::*;
+pub use host::*;
 pub use proto_core as core;
 pub use proto_core::{
     Id, ProtoEnvironment, Tool, ToolManifest, UnresolvedVersionSpec, Version, VersionReq,
@@ -72,6 +76,8 @@ pub fn create_plugin_with_config(
     }
 
     WasmTestWrapper {
+        fixtures: FixtureRecorder::new(id.as_str(), sandbox, &manifest.config),
+        host_version: None,
         tool: Tool::load_from_manifest(Id::new(id).unwrap(), proto, manifest).unwrap(),
     }
 }
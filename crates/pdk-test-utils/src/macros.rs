@@ -49,10 +49,9 @@ macro_rules! generate_download_install_tests {
             tool.version = Some(proto_pdk_test_utils::VersionSpec::parse($version).unwrap());
 
             let temp_dir = tool.get_temp_dir();
+            let install_dir = tool.get_tool_dir();
 
-            tool.install_from_prebuilt(&tool.get_tool_dir())
-                .await
-                .unwrap();
+            tool.install_from_prebuilt(&install_dir).await.unwrap();
 
             assert!(temp_dir.exists());
         }
@@ -1,29 +1,62 @@
-use proto_core::Tool;
+use crate::fixtures::FixtureRecorder;
+use proto_core::{Tool, Version};
 use proto_pdk_api::*;
+use std::path::Path;
 
 pub struct WasmTestWrapper {
+    /// Records and replays the output of functions that access the network.
+    pub fixtures: FixtureRecorder,
+
+    /// Version of proto to simulate when passing context to plugin functions.
+    /// When not defined, the context's version is used as-is.
+    pub host_version: Option<Version>,
+
     pub tool: Tool,
 }
 
 impl WasmTestWrapper {
-    pub fn detect_version_files(&self) -> DetectVersionOutput {
-        self.tool.plugin.call_func("detect_version_files").unwrap()
+    pub fn build_instructions(&self, mut input: BuildInstructionsInput) -> BuildInstructionsOutput {
+        input.context = self.prepare_context(input.context);
+
+        self.fixtures.call("build_instructions", input, |input| {
+            self.tool
+                .plugin
+                .call_func_with("build_instructions", input)
+                .unwrap()
+        })
     }
 
-    pub fn download_prebuilt(&self, mut input: DownloadPrebuiltInput) -> DownloadPrebuiltOutput {
+    pub fn define_shims(&self, mut input: DefineShimsInput) -> DefineShimsOutput {
         input.context = self.prepare_context(input.context);
 
         self.tool
             .plugin
-            .call_func_with("download_prebuilt", input)
+            .call_func_with("define_shims", input)
             .unwrap()
     }
 
+    pub fn detect_version_files(&self) -> DetectVersionOutput {
+        self.tool.plugin.call_func("detect_version_files").unwrap()
+    }
+
+    pub fn download_prebuilt(&self, mut input: DownloadPrebuiltInput) -> DownloadPrebuiltOutput {
+        input.context = self.prepare_context(input.context);
+
+        self.fixtures.call("download_prebuilt", input, |input| {
+            self.tool
+                .plugin
+                .call_func_with("download_prebuilt", input)
+                .unwrap()
+        })
+    }
+
     pub fn load_versions(&self, input: LoadVersionsInput) -> LoadVersionsOutput {
-        self.tool
-            .plugin
-            .call_func_with("load_versions", input)
-            .unwrap()
+        self.fixtures.call("load_versions", input, |input| {
+            self.tool
+                .plugin
+                .call_func_with("load_versions", input)
+                .unwrap()
+        })
     }
 
     pub fn locate_executables(&self, mut input: LocateExecutablesInput) -> LocateExecutablesOutput {
@@ -92,10 +125,12 @@ impl WasmTestWrapper {
     }
 
     pub fn resolve_version(&self, input: ResolveVersionInput) -> ResolveVersionOutput {
-        self.tool
-            .plugin
-            .call_func_with("resolve_version", input)
-            .unwrap()
+        self.fixtures.call("resolve_version", input, |input| {
+            self.tool
+                .plugin
+                .call_func_with("resolve_version", input)
+                .unwrap()
+        })
     }
 
     pub fn sync_manifest(&self, mut input: SyncManifestInput) -> SyncManifestOutput {
@@ -137,6 +172,30 @@ impl WasmTestWrapper {
             .unwrap()
     }
 
+    pub fn verify_install(&self, mut input: VerifyInstallInput) -> VerifyInstallOutput {
+        input.context = self.prepare_context(input.context);
+
+        self.tool
+            .plugin
+            .call_func_with("verify_install", input)
+            .unwrap()
+    }
+
+    pub fn write_version_file(&self, input: WriteVersionFileInput) -> WriteVersionFileOutput {
+        self.tool
+            .plugin
+            .call_func_with("write_version_file", input)
+            .unwrap()
+    }
+
+    /// Download a file (like a checksum) with the tool's HTTP client,
+    /// or copy it from a fixture when replaying.
+    pub async fn download_file(&self, url: &str, dest_file: &Path) {
+        self.fixtures
+            .download_file(self.tool.proto.get_http_client().unwrap(), url, dest_file)
+            .await;
+    }
+
     fn prepare_context(&self, context: ToolContext) -> ToolContext {
         let dir = if context.tool_dir.virtual_path().components().count() == 0 {
             self.tool.get_tool_dir()
@@ -145,6 +204,10 @@ impl WasmTestWrapper {
         };
 
         ToolContext {
+            proto_version: match &self.host_version {
+                Some(version) => Some(version.to_owned()),
+                None => context.proto_version,
+            },
             tool_dir: self.tool.to_virtual_path(&dir),
             ..context
         }
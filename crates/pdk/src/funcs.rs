@@ -0,0 +1,73 @@
+use extism_pdk::*;
+use proto_pdk_api::{
+    AnyResult, CompareVersionsInput, CompareVersionsOutput, ParseVersionSpecInput,
+    ParseVersionSpecOutput, SatisfiesVersionInput, SatisfiesVersionOutput,
+};
+use std::cmp::Ordering;
+
+mod host {
+    use super::*;
+
+    #[host_fn]
+    extern "ExtismHost" {
+        pub fn compare_versions(input: Json<CompareVersionsInput>) -> Json<CompareVersionsOutput>;
+        pub fn parse_version_spec(
+            input: Json<ParseVersionSpecInput>,
+        ) -> Json<ParseVersionSpecOutput>;
+        pub fn satisfies_version(
+            input: Json<SatisfiesVersionInput>,
+        ) -> Json<SatisfiesVersionOutput>;
+    }
+}
+
+/// Compare 2 fully-qualified versions using proto's semver rules.
+pub fn compare_versions(left: &str, right: &str) -> AnyResult<Ordering> {
+    let output = unsafe {
+        host::compare_versions(Json(CompareVersionsInput {
+            left: left.to_owned(),
+            right: right.to_owned(),
+        }))?
+    }
+    .0;
+
+    match (output.error, output.ordering) {
+        (Some(error), _) => Err(Error::msg(error)),
+        (_, Some(ordering)) => Ok(ordering.cmp(&0)),
+        _ => Err(Error::msg(format!("Unable to compare {left} and {right}"))),
+    }
+}
+
+/// Parse a version, requirement, or alias using proto's parser,
+/// and return the normalized spec.
+pub fn parse_version_spec(spec: &str) -> AnyResult<String> {
+    let output = unsafe {
+        host::parse_version_spec(Json(ParseVersionSpecInput {
+            spec: spec.to_owned(),
+        }))?
+    }
+    .0;
+
+    match (output.error, output.spec) {
+        (Some(error), _) => Err(Error::msg(error)),
+        (_, Some(spec)) => Ok(spec),
+        _ => Err(Error::msg(format!("Unable to parse {spec}"))),
+    }
+}
+
+/// Return true if the version satisfies the spec, using the same
+/// matching rules that proto uses when resolving versions.
+pub fn satisfies_version(spec: &str, version: &str, include_prereleases: bool) -> AnyResult<bool> {
+    let output = unsafe {
+        host::satisfies_version(Json(SatisfiesVersionInput {
+            include_prereleases,
+            spec: spec.to_owned(),
+            version: version.to_owned(),
+        }))?
+    }
+    .0;
+
+    match output.error {
+        Some(error) => Err(Error::msg(error)),
+        None => Ok(output.satisfied),
+    }
+}
@@ -1,6 +1,8 @@
+mod funcs;
 mod helpers;
 mod macros;
 
+pub use funcs::*;
 pub use helpers::*;
 pub use proto_pdk_api::*;
 pub use warpgate_pdk::*;
@@ -63,6 +63,69 @@ pub fn find_command_on_path<T: AsRef<OsStr>>(name: T) -> Option<PathBuf> {
     None
 }
 
+/// Return absolute paths to every occurrence of the provided program (without extension)
+/// on `PATH`, in precedence order, cycling through `PATHEXT` extensions.
+#[cfg(windows)]
+pub fn find_all_commands_on_path<T: AsRef<OsStr>>(name: T) -> Vec<PathBuf> {
+    let Ok(system_path) = env::var("PATH") else {
+        return vec![];
+    };
+
+    // Only extensions we care about
+    let exts = vec![".exe", ".ps1", ".cmd", ".bat"];
+    let name = name.as_ref();
+    let has_ext = name
+        .as_encoded_bytes()
+        .iter()
+        .any(|b| b.eq_ignore_ascii_case(&b'.'));
+    let mut paths = vec![];
+
+    for path_dir in env::split_paths(&system_path) {
+        if has_ext {
+            let path = path_dir.join(name);
+
+            if path.exists() && !paths.contains(&path) {
+                paths.push(path);
+            }
+        } else {
+            for ext in &exts {
+                let mut file_name = name.to_os_string();
+                file_name.push(ext);
+
+                let path = path_dir.join(file_name);
+
+                if path.exists() && !paths.contains(&path) {
+                    paths.push(path);
+                }
+            }
+        }
+    }
+
+    paths
+}
+
+/// Return absolute paths to every occurrence of the provided command
+/// on `PATH`, in precedence order.
+#[cfg(not(windows))]
+pub fn find_all_commands_on_path<T: AsRef<OsStr>>(name: T) -> Vec<PathBuf> {
+    let Ok(system_path) = env::var("PATH") else {
+        return vec![];
+    };
+
+    let name = name.as_ref();
+    let mut paths = vec![];
+
+    for path_dir in env::split_paths(&system_path) {
+        let path = path_dir.join(name);
+
+        if path.exists() && !paths.contains(&path) {
+            paths.push(path);
+        }
+    }
+
+    paths
+}
+
 /// Return true if the provided command/program (without extension)
 /// is available on `PATH`.
 pub fn is_command_on_path<T: AsRef<OsStr>>(name: T) -> bool {
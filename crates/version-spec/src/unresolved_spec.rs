@@ -20,6 +20,9 @@ pub enum UnresolvedVersionSpec {
     Req(VersionReq),
     /// A list of requirements to match any against (joined by `||`).
     ReqAny(Vec<VersionReq>),
+    /// A list of specifications, including aliases, to match any against
+    /// (joined by `||`). The highest resolved version is used.
+    Composite(Vec<UnresolvedVersionSpec>),
     /// A fully-qualified semantic version.
     Version(Version),
 }
@@ -30,6 +33,8 @@ impl UnresolvedVersionSpec {
     ///
     /// - If the value "canary", map as `Canary` variant.
     /// - If an alpha-numeric value that starts with a character, map as `Alias`.
+    /// - If contains `||` and any item is an alias, split and parse each
+    ///   item recursively, and map as `Composite`.
     /// - If contains `||`, split and parse each item with [`VersionReq`],
     ///   and map as `ReqAny`.
     /// - If contains `,` or ` ` (space), parse with [`VersionReq`], and map as `Req`.
@@ -65,11 +70,49 @@ impl UnresolvedVersionSpec {
         }
     }
 
+    /// Intersect the current specification with another, returning a specification
+    /// that only matches versions that both match. Returns `None` if either
+    /// specification is canary, an alias, or composite, as they can't be compared
+    /// without resolving, or if they can never match the same version.
+    pub fn intersect(&self, other: &Self) -> Option<Self> {
+        let combine = |a: &VersionReq, b: &VersionReq| VersionReq {
+            comparators: a
+                .comparators
+                .iter()
+                .chain(b.comparators.iter())
+                .cloned()
+                .collect(),
+        };
+
+        match (self, other) {
+            (Self::Version(a), Self::Version(b)) => (a == b).then(|| self.clone()),
+            (Self::Version(version), Self::Req(req)) | (Self::Req(req), Self::Version(version)) => {
+                req.matches(version)
+                    .then(|| Self::Version(version.to_owned()))
+            }
+            (Self::Version(version), Self::ReqAny(reqs))
+            | (Self::ReqAny(reqs), Self::Version(version)) => reqs
+                .iter()
+                .any(|req| req.matches(version))
+                .then(|| Self::Version(version.to_owned())),
+            (Self::Req(a), Self::Req(b)) => Some(Self::Req(combine(a, b))),
+            (Self::Req(a), Self::ReqAny(reqs)) | (Self::ReqAny(reqs), Self::Req(a)) => Some(
+                Self::ReqAny(reqs.iter().map(|req| combine(a, req)).collect()),
+            ),
+            (Self::ReqAny(a), Self::ReqAny(b)) => Some(Self::ReqAny(
+                a.iter()
+                    .flat_map(|a| b.iter().map(|b| combine(a, b)))
+                    .collect(),
+            )),
+            _ => None,
+        }
+    }
+
     /// Convert the current unresolved specification to a resolved specification.
     /// Note that this *does not* actually resolve or validate against a manifest,
     /// and instead simply constructs the [`VersionSpec`].
     ///
-    /// Furthermore, the `Req` and `ReqAny` variants will panic, as they are not
+    /// Furthermore, the `Req`, `ReqAny`, and `Composite` variants will panic, as they are not
     /// resolved or valid versions.
     pub fn to_resolved_spec(&self) -> VersionSpec {
         match self {
@@ -114,6 +157,18 @@ impl FromStr for UnresolvedVersionSpec {
             let mut any = vec![];
             let mut parts = value.split("||").map(|p| p.trim()).collect::<Vec<_>>();
 
+            // Aliases can't be sorted, so keep the order as-is
+            if parts
+                .iter()
+                .any(|part| *part == "canary" || is_alias_name(part))
+            {
+                for part in parts {
+                    any.push(Self::from_str(part)?);
+                }
+
+                return Ok(UnresolvedVersionSpec::Composite(any));
+            }
+
             // Try and sort from highest to lowest range
             parts.sort_by(|a, d| compare(d, a));
 
@@ -175,6 +230,15 @@ impl Display for UnresolvedVersionSpec {
                     .collect::<Vec<_>>()
                     .join(" || ")
             ),
+            Self::Composite(specs) => write!(
+                f,
+                "{}",
+                specs
+                    .iter()
+                    .map(|spec| spec.to_string())
+                    .collect::<Vec<_>>()
+                    .join(" || ")
+            ),
             Self::Version(version) => write!(f, "{}", version),
         }
     }
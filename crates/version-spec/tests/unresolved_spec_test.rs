@@ -97,4 +97,43 @@ mod unresolved_spec {
             ])
         );
     }
+
+    #[test]
+    fn composite_requirements() {
+        assert_eq!(
+            UnresolvedVersionSpec::parse("lts || ^22 || >=18 <21").unwrap(),
+            UnresolvedVersionSpec::Composite(vec![
+                UnresolvedVersionSpec::Alias("lts".into()),
+                UnresolvedVersionSpec::Req(VersionReq::parse("^22").unwrap()),
+                UnresolvedVersionSpec::Req(VersionReq::parse(">=18, <21").unwrap()),
+            ])
+        );
+        assert_eq!(
+            UnresolvedVersionSpec::parse("lts || ^22")
+                .unwrap()
+                .to_string(),
+            "lts || ^22"
+        );
+    }
+
+    #[test]
+    fn intersects_versions_and_reqs() {
+        let parse = |value: &str| UnresolvedVersionSpec::parse(value).unwrap();
+
+        assert_eq!(
+            parse("20.10").intersect(&parse(">=18")),
+            Some(parse("~20.10, >=18"))
+        );
+        assert_eq!(
+            parse("20.10.0").intersect(&parse(">=18")),
+            Some(parse("20.10.0"))
+        );
+        assert_eq!(
+            parse("^18 || ^20").intersect(&parse("20.1.0")),
+            Some(parse("20.1.0"))
+        );
+        assert_eq!(parse("16.0.0").intersect(&parse(">=18")), None);
+        assert_eq!(parse("16.0.0").intersect(&parse("18.0.0")), None);
+        assert_eq!(parse("lts").intersect(&parse(">=18")), None);
+    }
 }
@@ -21,3 +21,49 @@ api_struct!(
         pub sandbox: PathBuf,
     }
 );
+
+api_struct!(
+    /// Capabilities that a plugin requires from the host. Hosts that are
+    /// not listed cannot be contacted, and capabilities that are not
+    /// enabled are denied by the host.
+    pub struct PluginPermissions {
+        /// Hosts that the plugin may send HTTP requests to. Supports
+        /// wildcards, like `*.github.com`, or `*` for all hosts.
+        #[serde(skip_serializing_if = "Vec::is_empty")]
+        pub allowed_hosts: Vec<String>,
+
+        /// Allow executing commands on the host, with `exec_command`.
+        pub exec: bool,
+
+        /// Allow accessing real paths outside of the virtual paths
+        /// (`/cwd`, `/proto`, `/userhome`) through host functions.
+        pub filesystem: bool,
+    }
+);
+
+impl PluginPermissions {
+    /// Permissions for third-party plugins that have not declared any,
+    /// which cannot contact any hosts or use any capabilities.
+    pub fn denied() -> Self {
+        Self {
+            allowed_hosts: vec![],
+            exec: false,
+            filesystem: false,
+        }
+    }
+
+    /// Permissions for trusted (built-in) plugins that have not declared
+    /// any, which can contact all hosts and use all capabilities.
+    pub fn unrestricted() -> Self {
+        Self {
+            allowed_hosts: vec!["*".into()],
+            exec: true,
+            filesystem: true,
+        }
+    }
+
+    /// Return true if the plugin can send HTTP requests to any host.
+    pub fn allows_all_hosts(&self) -> bool {
+        self.allowed_hosts.iter().any(|host| host == "*")
+    }
+}
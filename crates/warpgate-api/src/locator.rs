@@ -14,11 +14,25 @@ pub struct GitHubLocator {
     pub repo_slug: String,
 
     /// Release tag to use. Defaults to `latest`.
+    /// Can also be a version constraint, like `^0.5`.
     pub tag: Option<String>,
 }
 
+impl GitHubLocator {
+    /// Return true if the tag is a version constraint (`^0.5`, `~1.2`, etc),
+    /// that matches against release tags, instead of an explicit tag.
+    pub fn is_tag_constraint(&self) -> bool {
+        self.tag
+            .as_deref()
+            .is_some_and(|tag| tag.starts_with(['^', '~', '>', '<', '=', '*']))
+    }
+}
+
 #[derive(thiserror::Error, Debug)]
 pub enum PluginLocatorError {
+    #[error("asdf plugin locator requires a repository with organization scope (org/repo) or an https URL.")]
+    AsdfMissingRepo,
+
     #[error("GitHub release locator requires a repository with organization scope (org/repo).")]
     GitHubMissingOrg,
 
@@ -47,7 +61,14 @@ pub enum PluginLocator {
 
     /// github:owner/repo
     /// github:owner/repo@tag
+    /// github:owner/repo@^0.5
     GitHub(GitHubLocator),
+
+    /// asdf:owner/repo
+    /// asdf:owner/repo@rev
+    /// asdf:https://url/to/repo.git
+    /// asdf:https://url/to/repo.git@rev
+    Asdf { repo: String, rev: Option<String> },
 }
 
 impl PluginLocator {
@@ -61,6 +82,16 @@ impl PluginLocator {
             .expect("Expected a package or repository name!")
     }
 
+    /// Return the Git URL of an asdf plugin repository. Slugs are
+    /// assumed to be hosted on GitHub.
+    pub fn create_asdf_repo_url(repo: &str) -> String {
+        if repo.starts_with("https:") {
+            repo.to_owned()
+        } else {
+            format!("https://github.com/{repo}.git")
+        }
+    }
+
     pub fn create_wasm_file_prefix(name: &str) -> String {
         let mut name = name.to_lowercase().replace('-', "_");
 
@@ -94,6 +125,12 @@ impl Display for PluginLocator {
                     .map(|t| format!("@{t}"))
                     .unwrap_or_default()
             ),
+            PluginLocator::Asdf { repo, rev } => write!(
+                f,
+                "asdf:{}{}",
+                repo,
+                rev.as_ref().map(|r| format!("@{r}")).unwrap_or_default()
+            ),
         }
     }
 }
@@ -156,6 +193,26 @@ impl TryFrom<String> for PluginLocator {
                     tag,
                 }))
             }
+            "asdf" => {
+                if location.starts_with("http:") {
+                    Err(PluginLocatorError::SecureUrlsOnly)
+                } else if location.starts_with("https:") || location.contains('/') {
+                    // A revision can't contain slashes, otherwise it's part of the URL
+                    let (repo, rev) = match location.rsplit_once('@') {
+                        Some((repo, rev)) if !rev.is_empty() && !rev.contains('/') => {
+                            (repo, Some(rev.to_owned()))
+                        }
+                        _ => (location, None),
+                    };
+
+                    Ok(PluginLocator::Asdf {
+                        repo: repo.to_owned(),
+                        rev,
+                    })
+                } else {
+                    Err(PluginLocatorError::AsdfMissingRepo)
+                }
+            }
             unknown => Err(PluginLocatorError::UnknownScope(unknown.to_owned())),
         }
     }
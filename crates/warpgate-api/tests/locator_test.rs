@@ -167,5 +167,121 @@ mod locator {
                 })
             );
         }
+
+        #[test]
+        fn parses_constraint() {
+            let locator =
+                PluginLocator::try_from("github:moonrepo/bun_plugin@^0.5".to_string()).unwrap();
+
+            let PluginLocator::GitHub(github) = &locator else {
+                panic!();
+            };
+
+            assert_eq!(github.tag.as_deref(), Some("^0.5"));
+            assert!(github.is_tag_constraint());
+            assert_eq!(locator.to_string(), "github:moonrepo/bun_plugin@^0.5");
+        }
+
+        #[test]
+        fn tags_are_not_constraints() {
+            for locator in ["github:moonrepo/bun", "github:moonrepo/bun@v1.2.3"] {
+                let PluginLocator::GitHub(github) =
+                    PluginLocator::try_from(locator.to_string()).unwrap()
+                else {
+                    panic!();
+                };
+
+                assert!(!github.is_tag_constraint());
+            }
+        }
+    }
+
+    mod asdf {
+        use super::*;
+
+        #[test]
+        #[should_panic(expected = "AsdfMissingRepo")]
+        fn errors_no_slug() {
+            PluginLocator::try_from("asdf:nodejs".to_string()).unwrap();
+        }
+
+        #[test]
+        #[should_panic(expected = "SecureUrlsOnly")]
+        fn errors_http_url() {
+            PluginLocator::try_from("asdf:http://host.com/asdf-nodejs.git".to_string()).unwrap();
+        }
+
+        #[test]
+        fn parses_slug() {
+            let locator = PluginLocator::try_from("asdf:asdf-vm/asdf-nodejs".to_string()).unwrap();
+
+            assert_eq!(
+                locator,
+                PluginLocator::Asdf {
+                    repo: "asdf-vm/asdf-nodejs".into(),
+                    rev: None,
+                }
+            );
+            assert_eq!(locator.to_string(), "asdf:asdf-vm/asdf-nodejs");
+            assert_eq!(
+                PluginLocator::create_asdf_repo_url("asdf-vm/asdf-nodejs"),
+                "https://github.com/asdf-vm/asdf-nodejs.git"
+            );
+        }
+
+        #[test]
+        fn parses_url() {
+            assert_eq!(
+                PluginLocator::try_from("asdf:https://host.com/asdf-nodejs.git".to_string())
+                    .unwrap(),
+                PluginLocator::Asdf {
+                    repo: "https://host.com/asdf-nodejs.git".into(),
+                    rev: None,
+                }
+            );
+            assert_eq!(
+                PluginLocator::create_asdf_repo_url("https://host.com/asdf-nodejs.git"),
+                "https://host.com/asdf-nodejs.git"
+            );
+        }
+
+        #[test]
+        fn parses_slug_with_rev() {
+            let locator =
+                PluginLocator::try_from("asdf:asdf-vm/asdf-nodejs@a1b2c3d".to_string()).unwrap();
+
+            assert_eq!(
+                locator,
+                PluginLocator::Asdf {
+                    repo: "asdf-vm/asdf-nodejs".into(),
+                    rev: Some("a1b2c3d".into()),
+                }
+            );
+            assert_eq!(locator.to_string(), "asdf:asdf-vm/asdf-nodejs@a1b2c3d");
+        }
+
+        #[test]
+        fn parses_url_with_rev() {
+            assert_eq!(
+                PluginLocator::try_from("asdf:https://host.com/asdf-nodejs.git@v1.0.0".to_string())
+                    .unwrap(),
+                PluginLocator::Asdf {
+                    repo: "https://host.com/asdf-nodejs.git".into(),
+                    rev: Some("v1.0.0".into()),
+                }
+            );
+        }
+
+        #[test]
+        fn doesnt_parse_url_user_as_rev() {
+            assert_eq!(
+                PluginLocator::try_from("asdf:https://user@host.com/asdf-nodejs.git".to_string())
+                    .unwrap(),
+                PluginLocator::Asdf {
+                    repo: "https://user@host.com/asdf-nodejs.git".into(),
+                    rev: None,
+                }
+            );
+        }
     }
 }
@@ -13,6 +13,9 @@ extern "ExtismHost" {
 }
 
 /// Fetch the provided request and return a response object.
+///
+/// Requests are made by the WASM runtime, not the host's HTTP client,
+/// so the host's proxy, certificate, and mirror settings do not apply.
 pub fn fetch(req: HttpRequest, body: Option<String>) -> AnyResult<HttpResponse> {
     debug!("Fetching <url>{}</url>", req.url);
 
@@ -0,0 +1,312 @@
+use miette::IntoDiagnostic;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use starbase_utils::{fs, json};
+use std::collections::{BTreeMap, BTreeSet};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use tracing::trace;
+
+pub const BLOB_STORE_INDEX: &str = "index.json";
+
+/// An entry in a [`BlobStore`] index, that references a content-addressed blob.
+pub trait BlobRef: Serialize + DeserializeOwned {
+    /// Create an entry for the provided blob name, which has been stored at the path.
+    fn create(blob: String, blob_path: &Path) -> miette::Result<Self>;
+
+    /// Return the file name of the referenced blob.
+    fn get_blob(&self) -> &str;
+}
+
+/// An index of keys (locators, URLs, etc) to blobs, stored as `index.json` in a
+/// directory. Blobs are stored by the SHA256 of their contents, so that multiple
+/// keys that resolve to the same file share a single copy.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct BlobStore<T> {
+    pub refs: BTreeMap<String, T>,
+
+    #[serde(skip)]
+    pub dir: PathBuf,
+}
+
+impl<T> Default for BlobStore<T> {
+    fn default() -> Self {
+        Self {
+            refs: BTreeMap::new(),
+            dir: PathBuf::new(),
+        }
+    }
+}
+
+impl<T: BlobRef> BlobStore<T> {
+    /// Load the index from the provided directory. When mutating,
+    /// the directory should be locked with [`BlobStore::lock`] first.
+    pub fn load<P: AsRef<Path>>(dir: P) -> miette::Result<Self> {
+        let dir = dir.as_ref();
+        let path = dir.join(BLOB_STORE_INDEX);
+
+        let mut store: BlobStore<T> = if path.exists() {
+            let content = fs::read_file_with_lock(&path)?;
+
+            // The file may be empty if another process is writing to it
+            if content.is_empty() {
+                BlobStore::default()
+            } else {
+                json::from_str(&content).into_diagnostic()?
+            }
+        } else {
+            BlobStore::default()
+        };
+
+        store.dir = dir.to_path_buf();
+
+        Ok(store)
+    }
+
+    /// Lock the directory, so that the index and blobs are not
+    /// mutated by multiple processes at the same time.
+    pub fn lock<P: AsRef<Path>>(dir: P) -> miette::Result<fs::DirLock> {
+        Ok(fs::lock_directory(dir.as_ref())?)
+    }
+
+    pub fn save(&self) -> miette::Result<()> {
+        fs::write_file_with_lock(
+            self.dir.join(BLOB_STORE_INDEX),
+            json::to_string_pretty(self).into_diagnostic()?,
+        )?;
+
+        Ok(())
+    }
+
+    /// Return an absolute path to the blob directory.
+    pub fn get_blobs_dir(&self) -> PathBuf {
+        self.dir.join("blobs")
+    }
+
+    /// Return an absolute path to the directory of incomplete blobs.
+    pub fn get_partials_dir(&self) -> PathBuf {
+        self.dir.join("partials")
+    }
+
+    /// Return an absolute path to the blob for the provided key,
+    /// if the key exists and the blob has not been deleted.
+    pub fn get_blob_path(&self, key: &str) -> Option<PathBuf> {
+        let entry = self.refs.get(key)?;
+        let path = self.get_blobs_dir().join(entry.get_blob());
+
+        if path.exists() {
+            Some(path)
+        } else {
+            None
+        }
+    }
+
+    /// Return an absolute path to the incomplete blob for the provided key,
+    /// which is stable across processes.
+    pub fn get_partial_path(&self, key: &str) -> PathBuf {
+        let mut sha = Sha256::new();
+        sha.update(key.as_bytes());
+
+        self.get_partials_dir()
+            .join(format!("{:x}.part", sha.finalize()))
+    }
+
+    /// Hash the contents of the provided file and move it into the blob directory
+    /// with the provided extension, then reference it with the provided key. If a
+    /// blob with the same contents already exists, it will be re-used.
+    pub fn insert_blob(&mut self, key: &str, file: &Path, ext: &str) -> miette::Result<PathBuf> {
+        let blob = format!("{}{ext}", hash_file(file)?);
+        let blobs_dir = self.get_blobs_dir();
+        let blob_path = blobs_dir.join(&blob);
+
+        fs::create_dir_all(&blobs_dir)?;
+
+        if blob_path.exists() {
+            trace!(key, blob = ?blob_path, "Blob already exists, re-using");
+
+            fs::remove_file(file)?;
+        } else {
+            trace!(key, blob = ?blob_path, "Storing blob");
+
+            fs::rename(file, &blob_path)?;
+        }
+
+        self.refs
+            .insert(key.to_owned(), T::create(blob, &blob_path)?);
+
+        Ok(blob_path)
+    }
+
+    /// Remove all references, blobs, and incomplete blobs (excluding their locks).
+    /// Returns the number of bytes that were freed.
+    pub fn clear(&mut self) -> miette::Result<u64> {
+        let mut freed = 0;
+
+        for dir in [self.get_blobs_dir(), self.get_partials_dir()] {
+            if !dir.exists() {
+                continue;
+            }
+
+            for entry in fs::read_dir(&dir)? {
+                let path = entry.path();
+
+                // Locks may be held by other processes
+                if path.is_file() && !path.extension().is_some_and(|ext| ext == "lock") {
+                    freed += fs::metadata(&path)?.len();
+                    fs::remove_file(&path)?;
+                }
+            }
+        }
+
+        self.refs.clear();
+
+        Ok(freed)
+    }
+
+    /// Delete blobs that are no longer referenced by any key.
+    /// Returns the paths of the deleted blobs.
+    pub fn remove_unreferenced_blobs(&self) -> miette::Result<Vec<PathBuf>> {
+        let blobs_dir = self.get_blobs_dir();
+        let mut deleted = vec![];
+
+        if !blobs_dir.exists() {
+            return Ok(deleted);
+        }
+
+        let referenced = self
+            .refs
+            .values()
+            .map(|entry| entry.get_blob())
+            .collect::<BTreeSet<_>>();
+
+        for entry in fs::read_dir(&blobs_dir)? {
+            let path = entry.path();
+
+            if path.is_file() && !referenced.contains(fs::file_name(&path).as_str()) {
+                trace!(blob = ?path, "Deleting unreferenced blob");
+
+                fs::remove_file(&path)?;
+                deleted.push(path);
+            }
+        }
+
+        deleted.sort();
+
+        Ok(deleted)
+    }
+}
+
+/// A reference from a locator (its cache key) to a content-addressed blob.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct PluginCacheEntry {
+    /// File name of the blob, composed of the SHA256 of its contents and an extension.
+    pub blob: String,
+
+    /// Timestamp (in milliseconds) of when the blob was downloaded for this reference.
+    pub cached_at: u128,
+
+    /// The `ETag` header of the download response, if provided.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub etag: Option<String>,
+
+    /// The GitHub release tag the blob was downloaded from, if applicable.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>,
+}
+
+impl PluginCacheEntry {
+    /// Return true if the reference is older than the provided duration.
+    pub fn is_older_than(&self, duration: Duration) -> bool {
+        now_millis().saturating_sub(self.cached_at) > duration.as_millis()
+    }
+}
+
+impl BlobRef for PluginCacheEntry {
+    fn create(blob: String, _blob_path: &Path) -> miette::Result<Self> {
+        Ok(Self {
+            blob,
+            cached_at: now_millis(),
+            etag: None,
+            tag: None,
+        })
+    }
+
+    fn get_blob(&self) -> &str {
+        &self.blob
+    }
+}
+
+/// An index of locators to plugin blobs, stored in the plugins directory.
+pub type PluginCache = BlobStore<PluginCacheEntry>;
+
+impl BlobStore<PluginCacheEntry> {
+    /// Hash the contents of the provided file and move it into the blob directory,
+    /// then reference it with the provided key. If a blob with the same contents
+    /// already exists, it will be re-used.
+    pub fn insert(&mut self, key: &str, file: &Path) -> miette::Result<PathBuf> {
+        self.insert_with_source(key, file, None, None)
+    }
+
+    /// Like [`PluginCache::insert`], but also record the `ETag` and release tag
+    /// of the download, so that the blob can be compared against its source later.
+    pub fn insert_with_source(
+        &mut self,
+        key: &str,
+        file: &Path,
+        etag: Option<String>,
+        tag: Option<String>,
+    ) -> miette::Result<PathBuf> {
+        let ext = file
+            .extension()
+            .map(|ext| format!(".{}", ext.to_string_lossy()))
+            .unwrap_or_default();
+        let blob_path = self.insert_blob(key, file, &ext)?;
+
+        if let Some(entry) = self.refs.get_mut(key) {
+            entry.etag = etag;
+            entry.tag = tag;
+        }
+
+        Ok(blob_path)
+    }
+
+    /// Remove references that are older than the provided duration.
+    /// Returns the keys of the removed references.
+    pub fn remove_stale_refs(&mut self, duration: Duration) -> Vec<String> {
+        let keys = self
+            .refs
+            .iter()
+            .filter(|(_, entry)| entry.is_older_than(duration))
+            .map(|(key, _)| key.to_owned())
+            .collect::<Vec<_>>();
+
+        for key in &keys {
+            self.refs.remove(key);
+        }
+
+        keys
+    }
+}
+
+fn hash_file(path: &Path) -> miette::Result<String> {
+    let mut file = fs::open_file(path)?;
+    let mut sha = Sha256::new();
+
+    io::copy(&mut file, &mut sha).map_err(|error| fs::FsError::Read {
+        path: path.to_path_buf(),
+        error,
+    })?;
+
+    Ok(format!("{:x}", sha.finalize()))
+}
+
+fn now_millis() -> u128 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|duration| duration.as_millis())
+        .unwrap_or_default()
+}
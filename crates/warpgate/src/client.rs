@@ -1,7 +1,11 @@
+#[cfg(feature = "loader")]
 use miette::IntoDiagnostic;
 use serde::{Deserialize, Serialize};
+#[cfg(feature = "loader")]
 use starbase_utils::fs;
+use std::collections::BTreeMap;
 use std::path::PathBuf;
+#[cfg(feature = "loader")]
 use tracing::{debug, trace, warn};
 
 #[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
@@ -9,17 +13,68 @@ use tracing::{debug, trace, warn};
 #[cfg_attr(feature = "schematic", derive(schematic::Schematic))]
 pub struct HttpOptions {
     pub allow_invalid_certs: bool,
+
+    /// Mapping of hosts (`nodejs.org`) or URL prefixes (`https://nodejs.org/dist`)
+    /// to a URL that downloads will be redirected to instead.
+    pub mirrors: BTreeMap<String, String>,
+
+    /// Hosts that should not be requested through the configured proxies.
+    pub no_proxy: Vec<String>,
+
     pub proxies: Vec<String>,
     pub root_cert: Option<PathBuf>,
 }
 
+impl HttpOptions {
+    /// Rewrite the provided URL to a configured mirror. URL prefixes take precedence
+    /// over hosts, and the longest matching prefix wins. The remainder of the URL
+    /// (path and query) is appended to the mirror URL.
+    pub fn rewrite_url(&self, url: &str) -> String {
+        let Some((_, rest)) = url.split_once("://") else {
+            return url.to_owned();
+        };
+
+        let host_end = rest.find(['/', '?', '#']).unwrap_or(rest.len());
+        let host = &rest[..host_end];
+
+        let prefix_match = self
+            .mirrors
+            .iter()
+            .filter(|(from, _)| from.contains("://") && url.starts_with(from.as_str()))
+            .max_by_key(|(from, _)| from.len())
+            .map(|(from, to)| (from.len(), to));
+
+        let host_match = || {
+            self.mirrors
+                .iter()
+                .find(|(from, _)| !from.contains("://") && from.eq_ignore_ascii_case(host))
+                .map(|(_, to)| (url.len() - rest.len() + host_end, to))
+        };
+
+        match prefix_match.or_else(host_match) {
+            Some((index, to)) => {
+                let remainder = &url[index..];
+
+                if to.ends_with('/') && remainder.starts_with('/') {
+                    format!("{}{}", to, &remainder[1..])
+                } else {
+                    format!("{to}{remainder}")
+                }
+            }
+            None => url.to_owned(),
+        }
+    }
+}
+
 /// Create an HTTP/HTTPS client that'll be used for downloading files.
+#[cfg(feature = "loader")]
 pub fn create_http_client() -> miette::Result<reqwest::Client> {
     create_http_client_with_options(&HttpOptions::default())
 }
 
 /// Create an HTTP/HTTPS client with the provided options, that'll be
 /// used for downloading files.
+#[cfg(feature = "loader")]
 pub fn create_http_client_with_options(options: &HttpOptions) -> miette::Result<reqwest::Client> {
     debug!("Creating HTTP client");
 
@@ -58,13 +113,27 @@ pub fn create_http_client_with_options(options: &HttpOptions) -> miette::Result<
         };
     }
 
+    let no_proxy = if options.no_proxy.is_empty() {
+        None
+    } else {
+        reqwest::NoProxy::from_string(&options.no_proxy.join(","))
+    };
+
     for proxy in &options.proxies {
         trace!(proxy = &proxy, "Adding proxy to http client");
 
         if proxy.starts_with("http:") {
-            client = client.proxy(reqwest::Proxy::http(proxy).into_diagnostic()?);
+            client = client.proxy(
+                reqwest::Proxy::http(proxy)
+                    .into_diagnostic()?
+                    .no_proxy(no_proxy.clone()),
+            );
         } else if proxy.starts_with("https:") {
-            client = client.proxy(reqwest::Proxy::https(proxy).into_diagnostic()?);
+            client = client.proxy(
+                reqwest::Proxy::https(proxy)
+                    .into_diagnostic()?
+                    .no_proxy(no_proxy.clone()),
+            );
         } else {
             warn!(proxy, "Invalid proxy, only http or https URLs allowed");
         };
@@ -16,4 +16,7 @@ pub struct GitHubApiAsset {
 #[serde(default)]
 pub struct GitHubApiRelease {
     pub assets: Vec<GitHubApiAsset>,
+    pub draft: bool,
+    pub prerelease: bool,
+    pub tag_name: String,
 }
@@ -10,6 +10,7 @@ pub enum WarpgateError {
     #[error("{0}")]
     Serde(String),
 
+    #[cfg(feature = "loader")]
     #[diagnostic(code(plugin::http))]
     #[error("Failed to make HTTP request for {}.", .url.style(Style::Url))]
     Http {
@@ -27,6 +28,14 @@ pub enum WarpgateError {
     #[error("Invalid plugin identifier {}, must be a valid kebab-case string.", .0.style(Style::Id))]
     InvalidID(String),
 
+    #[diagnostic(code(plugin::not_wasm))]
+    #[error(
+        "Cannot load {} plugin, locator {} does not point to a WASM plugin.",
+        .id.style(Style::Id),
+        .locator.style(Style::File),
+    )]
+    NotWasmPlugin { id: Id, locator: String },
+
     #[diagnostic(code(plugin::source::file_missing))]
     #[error(
         "Cannot load {} plugin, source file {} does not exist.",
@@ -48,6 +57,19 @@ pub enum WarpgateError {
         tag: String,
     },
 
+    #[diagnostic(code(plugin::github::release_missing))]
+    #[error(
+        "Cannot download {} plugin from GitHub ({}), no release found matching {}.",
+        .id.style(Style::Id),
+        .repo_slug.style(Style::Id),
+        .constraint.style(Style::Hash),
+    )]
+    GitHubReleaseMissing {
+        id: Id,
+        repo_slug: String,
+        constraint: String,
+    },
+
     #[diagnostic(code(plugin::wapm::module_missing))]
     #[error(
         "Cannot download {} plugin from wamp.io ({}), no applicable module found for release {}.",
@@ -87,6 +109,13 @@ pub enum WarpgateError {
     )]
     PluginCommandMissing { command: String },
 
+    #[diagnostic(
+        code(plugin::permission_denied),
+        help = "If the plugin is trusted, grant the permission in the [tools.<id>.permissions] section of .prototools."
+    )]
+    #[error("Plugin {} is not permitted to {action}.", .id.style(Style::Id))]
+    PermissionDenied { id: Id, action: String },
+
     #[diagnostic(code(plugin::call_func::format_input))]
     #[error(
         "Failed to format input for {} plugin function {} call.",
@@ -22,6 +22,18 @@ pub async fn download_from_url_to_file(
     temp_file: &Path,
     client: &reqwest::Client,
 ) -> miette::Result<()> {
+    download_from_url_to_file_with_etag(source_url, temp_file, client).await?;
+
+    Ok(())
+}
+
+/// Download the file like [`download_from_url_to_file`],
+/// and return the `ETag` header of the response, if provided.
+pub async fn download_from_url_to_file_with_etag(
+    source_url: &str,
+    temp_file: &Path,
+    client: &reqwest::Client,
+) -> miette::Result<Option<String>> {
     let url = Url::parse(source_url).into_diagnostic()?;
 
     // Fetch the file from the HTTP source
@@ -50,6 +62,8 @@ pub async fn download_from_url_to_file(
         .into());
     }
 
+    let etag = extract_etag(response.headers());
+
     // Write the bytes to our temporary file
     fs::write_file(
         temp_file,
@@ -62,7 +76,31 @@ pub async fn download_from_url_to_file(
             })?,
     )?;
 
-    Ok(())
+    Ok(etag)
+}
+
+/// Fetch the `ETag` header of the URL with a HEAD request, if provided.
+pub async fn fetch_url_etag(
+    source_url: &str,
+    client: &reqwest::Client,
+) -> miette::Result<Option<String>> {
+    let response = client
+        .head(source_url)
+        .send()
+        .await
+        .map_err(|error| WarpgateError::Http {
+            error,
+            url: source_url.to_owned(),
+        })?;
+
+    Ok(extract_etag(response.headers()))
+}
+
+fn extract_etag(headers: &reqwest::header::HeaderMap) -> Option<String> {
+    headers
+        .get(reqwest::header::ETAG)
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.to_owned())
 }
 
 pub fn move_or_unpack_download(temp_file: &Path, dest_file: &Path) -> miette::Result<()> {
@@ -1,21 +1,77 @@
 use crate::error::WarpgateError;
 use crate::helpers;
+use crate::id::Id;
 use extism::{CurrentPlugin, Error, Function, UserData, Val, ValType};
 use starbase_styles::color::{self, apply_style_tags};
 use starbase_utils::fs;
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 use std::env;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, RwLock};
 use system_env::{create_process_command, find_command_on_path};
 use tracing::trace;
-use warpgate_api::{ExecCommandInput, ExecCommandOutput, HostLogInput, HostLogTarget};
+use warpgate_api::{
+    ExecCommandInput, ExecCommandOutput, HostLogInput, HostLogTarget, PluginPermissions,
+    VirtualPath,
+};
+
+/// Real paths that a plugin has accessed through host functions,
+/// shared with the host so that the plugin's behavior can be audited.
+pub type PathAudit = Arc<Mutex<BTreeSet<PathBuf>>>;
+
+/// Permissions granted to a plugin, shared with the host so that they
+/// can be updated once the plugin has declared its own.
+pub type SharedPermissions = Arc<RwLock<PluginPermissions>>;
 
 #[derive(Clone)]
 pub struct HostData {
+    pub id: Id,
+    pub path_audit: Option<PathAudit>,
+    pub permissions: SharedPermissions,
     pub virtual_paths: BTreeMap<PathBuf, PathBuf>,
     pub working_dir: PathBuf,
 }
 
+impl HostData {
+    fn audit_path(&self, path: &Path) {
+        if let Some(audit) = &self.path_audit {
+            audit.lock().unwrap().insert(path.to_path_buf());
+        }
+    }
+
+    fn check_exec(&self, command: &str) -> Result<(), WarpgateError> {
+        if self.permissions.read().unwrap().exec {
+            return Ok(());
+        }
+
+        Err(WarpgateError::PermissionDenied {
+            id: self.id.clone(),
+            action: format!("execute command {command} (requires exec)"),
+        })
+    }
+
+    fn check_path(&self, path: &Path) -> Result<(), WarpgateError> {
+        let abs_path = self.working_dir.join(path);
+
+        if self.permissions.read().unwrap().filesystem
+            || self
+                .virtual_paths
+                .keys()
+                .any(|host_path| abs_path.starts_with(host_path))
+        {
+            return Ok(());
+        }
+
+        Err(WarpgateError::PermissionDenied {
+            id: self.id.clone(),
+            action: format!(
+                "access {} outside of virtual paths (requires filesystem)",
+                path.display()
+            ),
+        })
+    }
+}
+
 pub fn create_host_functions(data: HostData) -> Vec<Function> {
     vec![
         Function::new(
@@ -123,10 +179,15 @@ fn exec_command(
     let data = user_data.get()?;
     let data = data.lock().unwrap();
 
+    data.check_exec(&input.command)?;
+
     // Relative or absolute file path
     let maybe_bin = if input.command.contains('/') || input.command.contains('\\') {
         let path = helpers::from_virtual_path(&data.virtual_paths, PathBuf::from(&input.command));
 
+        data.audit_path(&path);
+        data.check_path(&path)?;
+
         if path.exists() {
             // This is temporary since WASI does not support updating file permissions yet!
             if input.set_executable {
@@ -156,6 +217,9 @@ fn exec_command(
         data.working_dir.clone()
     };
 
+    data.audit_path(&cwd);
+    data.check_path(&cwd)?;
+
     trace!(
         command = &input.command,
         args = ?input.args,
@@ -290,6 +354,9 @@ fn from_virtual_path(
     let data = data.lock().unwrap();
     let real_path = helpers::from_virtual_path(&data.virtual_paths, &original_path);
 
+    data.audit_path(&real_path);
+    data.check_path(&real_path)?;
+
     trace!(
         original_path = ?original_path,
         real_path = ?real_path,
@@ -313,6 +380,12 @@ fn to_virtual_path(
     let data = data.lock().unwrap();
     let virtual_path = helpers::to_virtual_path(&data.virtual_paths, &original_path);
 
+    data.audit_path(&original_path);
+
+    if matches!(virtual_path, VirtualPath::Only(_)) {
+        data.check_path(&original_path)?;
+    }
+
     trace!(
         original_path = ?original_path,
         virtual_path = ?virtual_path.virtual_path(),
@@ -1,20 +1,34 @@
+#[cfg(feature = "loader")]
+mod cache;
 mod client;
+#[cfg(feature = "loader")]
 mod endpoints;
 mod error;
+#[cfg(feature = "loader")]
 mod helpers;
+#[cfg(feature = "loader")]
 pub mod host_funcs;
 mod id;
+#[cfg(feature = "loader")]
 mod loader;
+#[cfg(feature = "loader")]
 mod plugin;
+#[cfg(feature = "loader")]
 pub mod test_utils;
 
+#[cfg(feature = "loader")]
+pub use cache::*;
 pub use client::*;
 pub use error::*;
+#[cfg(feature = "loader")]
 pub use helpers::*;
 pub use id::*;
+#[cfg(feature = "loader")]
 pub use loader::*;
+#[cfg(feature = "loader")]
 pub use plugin::*;
 
+#[cfg(feature = "loader")]
 pub use extism::{Manifest as PluginManifest, Wasm};
 pub use warpgate_api as api;
 pub use warpgate_api::{GitHubLocator, PluginLocator, PluginLocatorError, VirtualPath};
@@ -1,11 +1,15 @@
+use crate::cache::PluginCache;
 use crate::client::{create_http_client_with_options, HttpOptions};
 use crate::endpoints::*;
 use crate::error::WarpgateError;
 use crate::helpers::{
-    determine_cache_extension, download_from_url_to_file, move_or_unpack_download,
+    determine_cache_extension, download_from_url_to_file_with_etag, fetch_url_etag,
+    move_or_unpack_download,
 };
 use crate::id::Id;
 use once_cell::sync::OnceCell;
+use semver::{Version, VersionReq};
+use serde::Serialize;
 use sha2::{Digest, Sha256};
 use starbase_styles::color;
 use starbase_utils::fs;
@@ -18,6 +22,51 @@ use warpgate_api::{GitHubLocator, PluginLocator};
 
 pub type OfflineChecker = Arc<fn() -> bool>;
 
+/// The cached plugin compared to the target its locator currently points at.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct PluginUpdate {
+    /// Release tag or `ETag` of the cached plugin, if known.
+    pub cached: Option<String>,
+
+    /// Release tag or `ETag` that the locator currently points at, if known.
+    pub latest: Option<String>,
+
+    /// Whether the cached plugin is behind the locator's target.
+    pub outdated: bool,
+}
+
+/// Return the GitHub API URL for the locator's release, which is also used as the
+/// seed for the plugin's cache path. Constraints are resolved from the list of
+/// releases, so are seeded with the constraint itself.
+fn create_github_api_url(github: &GitHubLocator) -> String {
+    match &github.tag {
+        Some(tag) if github.is_tag_constraint() => format!(
+            "https://api.github.com/repos/{}/releases?constraint={tag}",
+            github.repo_slug,
+        ),
+        Some(tag) => format!(
+            "https://api.github.com/repos/{}/releases/tags/{tag}",
+            github.repo_slug,
+        ),
+        None => format!(
+            "https://api.github.com/repos/{}/releases/latest",
+            github.repo_slug,
+        ),
+    }
+}
+
+// Without an explicit tag, the locator may point at a different release over time
+fn is_github_latest(github: &GitHubLocator) -> bool {
+    github.tag.is_none() || github.is_tag_constraint()
+}
+
+// Tags are commonly prefixed, like `v1.2.3` or `plugin-v1.2.3`
+fn parse_tag_version(tag: &str) -> Option<Version> {
+    let start = tag.find(|c: char| c.is_ascii_digit())?;
+
+    Version::parse(&tag[start..]).ok()
+}
+
 /// A system for loading plugins from a locator strategy,
 /// and caching the `.wasm` file to the host's file system.
 #[derive(Clone)]
@@ -31,9 +80,13 @@ pub struct PluginLoader {
     /// Checks whether there's an internet connection or not.
     offline_checker: Option<OfflineChecker>,
 
-    /// Location where downloaded `.wasm` plugins are stored.
+    /// Location where downloaded `.wasm` plugins are stored,
+    /// as content-addressed blobs with an index of references.
     plugins_dir: PathBuf,
 
+    /// Location of a read-only directory with pre-downloaded `.wasm` plugins.
+    shared_plugins_dir: Option<PathBuf>,
+
     /// Location where temporary files (like archives) are stored.
     temp_dir: PathBuf,
 
@@ -53,6 +106,7 @@ impl PluginLoader {
             http_options: HttpOptions::default(),
             offline_checker: None,
             plugins_dir: plugins_dir.to_owned(),
+            shared_plugins_dir: None,
             temp_dir: temp_dir.as_ref().to_owned(),
             seed: None,
         }
@@ -110,15 +164,60 @@ impl PluginLoader {
                     id,
                     url,
                     self.create_cache_path(id, url, url.contains("latest")),
+                    None,
+                    false,
+                )
+                .await
+            }
+            PluginLocator::GitHub(github) => {
+                self.download_plugin_from_github(id, github, false).await
+            }
+            PluginLocator::Asdf { .. } => Err(WarpgateError::NotWasmPlugin {
+                id: id.to_owned(),
+                locator: locator.to_string(),
+            }
+            .into()),
+        }
+    }
+
+    /// Download the plugin that the locator currently points at, bypassing the cache,
+    /// and swap the cached reference to it once the download has completed.
+    /// File system plugins are loaded directly, as they are never cached.
+    pub async fn update_plugin<I: AsRef<Id>, L: AsRef<PluginLocator>>(
+        &self,
+        id: I,
+        locator: L,
+    ) -> miette::Result<PathBuf> {
+        let id = id.as_ref();
+        let locator = locator.as_ref();
+
+        trace!(
+            id = id.as_str(),
+            "Updating plugin {}",
+            color::id(id.as_str())
+        );
+
+        match locator {
+            PluginLocator::SourceUrl { url } => {
+                self.download_plugin(
+                    id,
+                    url,
+                    self.create_cache_path(id, url, url.contains("latest")),
+                    None,
+                    true,
                 )
                 .await
             }
-            PluginLocator::GitHub(github) => self.download_plugin_from_github(id, github).await,
+            PluginLocator::GitHub(github) => {
+                self.download_plugin_from_github(id, github, true).await
+            }
+            _ => self.load_plugin(id, locator).await,
         }
     }
 
     /// Create an absolute path to the plugin's destination file, located in the plugins directory.
     /// Hash the source URL to ensure uniqueness of each plugin + version combination.
+    /// The file name of this path is used as the reference key in the plugin cache index.
     pub fn create_cache_path(&self, id: &Id, url: &str, is_latest: bool) -> PathBuf {
         let mut sha = Sha256::new();
         sha.update(url);
@@ -180,6 +279,214 @@ impl PluginLoader {
         Ok(cached)
     }
 
+    /// Check if the plugin has been downloaded and referenced in the plugin cache index,
+    /// and return the path to its blob. Plugins downloaded before the index existed
+    /// are moved into the blob store. Uses the same staleness rules as [`is_cached`].
+    pub fn find_cached_plugin(&self, id: &Id, path: &Path) -> miette::Result<Option<PathBuf>> {
+        let key = fs::file_name(path);
+        let cache = PluginCache::load(&self.plugins_dir)?;
+
+        if let Some(entry) = cache.refs.get(&key) {
+            let days = if key.contains("-latest-") { 7 } else { 30 };
+
+            if !entry.is_older_than(Duration::from_secs(86400 * days)) || self.is_offline() {
+                if let Some(blob_path) = cache.get_blob_path(&key) {
+                    trace!(id = id.as_str(), path = ?blob_path, "Plugin already downloaded and cached");
+
+                    return Ok(Some(blob_path));
+                }
+            }
+
+            trace!(id = id.as_str(), "Plugin cached but stale, re-downloading");
+
+            return Ok(None);
+        }
+
+        if self.is_cached(id, path)? {
+            return Ok(Some(self.store_plugin(path, path, None, None)?));
+        }
+
+        Ok(None)
+    }
+
+    /// Check if the plugin has been pre-downloaded into the shared plugins directory,
+    /// and return its path. Shared plugins are read-only, so they never become stale.
+    pub fn find_shared_plugin(&self, id: &Id, path: &Path) -> Option<PathBuf> {
+        let shared_dir = self.shared_plugins_dir.as_ref()?;
+        let shared_path = PluginCache::load(shared_dir)
+            .ok()
+            .and_then(|cache| cache.get_blob_path(&fs::file_name(path)))
+            .unwrap_or_else(|| shared_dir.join(fs::file_name(path)));
+
+        if shared_path.exists() {
+            trace!(id = id.as_str(), path = ?shared_path, "Plugin found in shared store");
+
+            return Some(shared_path);
+        }
+
+        None
+    }
+
+    /// Move the downloaded file into the plugin cache as a blob, and reference it
+    /// with the file name of the destination path, along with the `ETag` and
+    /// release tag it was downloaded from. Returns the path to the blob.
+    pub fn store_plugin(
+        &self,
+        dest_file: &Path,
+        file: &Path,
+        etag: Option<String>,
+        tag: Option<String>,
+    ) -> miette::Result<PathBuf> {
+        let lock = PluginCache::lock(&self.plugins_dir)?;
+        let mut cache = PluginCache::load(&self.plugins_dir)?;
+        let blob_path = cache.insert_with_source(&fs::file_name(dest_file), file, etag, tag)?;
+
+        cache.save()?;
+        lock.unlock()?;
+
+        Ok(blob_path)
+    }
+
+    /// Fetch the tag of the latest release for the provided GitHub repository slug.
+    pub async fn fetch_latest_github_tag(&self, repo_slug: &str) -> miette::Result<String> {
+        let api_url = self.http_options.rewrite_url(&format!(
+            "https://api.github.com/repos/{repo_slug}/releases/latest"
+        ));
+
+        if self.is_offline() {
+            return Err(WarpgateError::InternetConnectionRequired {
+                message: format!("Unable to check the latest release of {repo_slug}."),
+                url: api_url,
+            }
+            .into());
+        }
+
+        trace!(api_url = &api_url, "Fetching latest release tag from GitHub");
+
+        let handle_error = |error: reqwest::Error| WarpgateError::Http {
+            error,
+            url: api_url.clone(),
+        };
+
+        let mut request = self.get_client()?.get(&api_url);
+
+        if let Ok(auth_token) = env::var("GITHUB_TOKEN") {
+            request = request.bearer_auth(auth_token);
+        }
+
+        let response = request
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(handle_error)?;
+        let release: GitHubApiRelease = response.json().await.map_err(handle_error)?;
+
+        Ok(release.tag_name)
+    }
+
+    /// Pin a GitHub locator without a tag to its latest release, as a compatible
+    /// version constraint (`^1.2.3`), or the exact tag if it's not a version.
+    /// Other locators are returned as-is.
+    pub async fn pin_locator<L: AsRef<PluginLocator>>(
+        &self,
+        locator: L,
+    ) -> miette::Result<PluginLocator> {
+        let locator = locator.as_ref();
+
+        let PluginLocator::GitHub(github) = locator else {
+            return Ok(locator.to_owned());
+        };
+
+        if github.tag.is_some() {
+            return Ok(locator.to_owned());
+        }
+
+        let tag = self.fetch_latest_github_tag(&github.repo_slug).await?;
+
+        Ok(PluginLocator::GitHub(GitHubLocator {
+            tag: Some(match parse_tag_version(&tag) {
+                Some(version) => format!("^{version}"),
+                None => tag,
+            }),
+            ..github.to_owned()
+        }))
+    }
+
+    /// Compare the cached plugin for the locator against what the locator currently
+    /// points at: the latest release tag for GitHub, or the `ETag` for URLs.
+    /// Returns nothing if the plugin is not cached, or not a remote plugin.
+    pub async fn check_plugin_update<I: AsRef<Id>, L: AsRef<PluginLocator>>(
+        &self,
+        id: I,
+        locator: L,
+    ) -> miette::Result<Option<PluginUpdate>> {
+        let id = id.as_ref();
+        let cache = PluginCache::load(&self.plugins_dir)?;
+
+        match locator.as_ref() {
+            PluginLocator::SourceUrl { url } => {
+                let key = fs::file_name(self.create_cache_path(id, url, url.contains("latest")));
+
+                let Some(entry) = cache.refs.get(&key) else {
+                    return Ok(None);
+                };
+
+                if self.is_offline() {
+                    return Err(WarpgateError::InternetConnectionRequired {
+                        message: "Unable to check plugin for updates.".into(),
+                        url: url.to_owned(),
+                    }
+                    .into());
+                }
+
+                trace!(id = id.as_str(), url, "Comparing cached plugin ETag");
+
+                let latest =
+                    fetch_url_etag(&self.http_options.rewrite_url(url), self.get_client()?).await?;
+
+                Ok(Some(PluginUpdate {
+                    outdated: entry
+                        .etag
+                        .as_ref()
+                        .is_some_and(|etag| latest.as_ref().is_some_and(|l| l != etag)),
+                    cached: entry.etag.clone(),
+                    latest,
+                }))
+            }
+            PluginLocator::GitHub(github) => {
+                let key = fs::file_name(self.create_cache_path(
+                    id,
+                    &create_github_api_url(github),
+                    is_github_latest(github),
+                ));
+
+                let Some(entry) = cache.refs.get(&key) else {
+                    return Ok(None);
+                };
+
+                // An explicit tag always points at the same release
+                let cached = entry
+                    .tag
+                    .clone()
+                    .or_else(|| github.tag.clone().filter(|_| !github.is_tag_constraint()));
+                let latest = match &github.tag {
+                    Some(_) if github.is_tag_constraint() => {
+                        self.fetch_github_release(id, github).await?.tag_name
+                    }
+                    Some(tag) => tag.to_owned(),
+                    None => self.fetch_latest_github_tag(&github.repo_slug).await?,
+                };
+
+                Ok(Some(PluginUpdate {
+                    outdated: cached.as_ref().is_some_and(|tag| tag != &latest),
+                    cached,
+                    latest: Some(latest),
+                }))
+            }
+            PluginLocator::SourceFile { .. } | PluginLocator::Asdf { .. } => Ok(None),
+        }
+    }
+
     /// Check for an internet connection.
     pub fn is_offline(&self) -> bool {
         self.offline_checker
@@ -198,6 +505,11 @@ impl PluginLoader {
         self.offline_checker = Some(Arc::new(op));
     }
 
+    /// Set a read-only directory to search for plugins before downloading.
+    pub fn set_shared_plugins_dir<P: AsRef<Path>>(&mut self, dir: P) {
+        self.shared_plugins_dir = Some(dir.as_ref().to_owned());
+    }
+
     /// Set the provided value as a seed for generating hashes.
     pub fn set_seed(&mut self, value: &str) {
         self.seed = Some(value.to_owned());
@@ -208,9 +520,17 @@ impl PluginLoader {
         id: &Id,
         source_url: &str,
         dest_file: PathBuf,
+        tag: Option<String>,
+        force: bool,
     ) -> miette::Result<PathBuf> {
-        if self.is_cached(id, &dest_file)? {
-            return Ok(dest_file);
+        if !force {
+            if let Some(shared_file) = self.find_shared_plugin(id, &dest_file) {
+                return Ok(shared_file);
+            }
+
+            if let Some(cached_file) = self.find_cached_plugin(id, &dest_file)? {
+                return Ok(cached_file);
+            }
         }
 
         if self.is_offline() {
@@ -229,55 +549,38 @@ impl PluginLoader {
         );
 
         let temp_file = self.temp_dir.join(fs::file_name(&dest_file));
-
-        download_from_url_to_file(source_url, &temp_file, self.get_client()?).await?;
-        move_or_unpack_download(&temp_file, &dest_file)?;
-
-        Ok(dest_file)
+        let unpacked_file = self
+            .temp_dir
+            .join(format!("unpacked-{}", fs::file_name(&dest_file)));
+
+        let etag = download_from_url_to_file_with_etag(
+            &self.http_options.rewrite_url(source_url),
+            &temp_file,
+            self.get_client()?,
+        )
+        .await?;
+        move_or_unpack_download(&temp_file, &unpacked_file)?;
+
+        self.store_plugin(&dest_file, &unpacked_file, etag, tag)
     }
 
-    async fn download_plugin_from_github(
+    /// Fetch the release that the GitHub locator points at. Version constraints are
+    /// matched against the tags of published (non-draft and non-prerelease) releases,
+    /// and the highest matching release is returned.
+    async fn fetch_github_release(
         &self,
         id: &Id,
         github: &GitHubLocator,
-    ) -> miette::Result<PathBuf> {
-        let (api_url, release_tag) = if let Some(tag) = &github.tag {
-            (
-                format!(
-                    "https://api.github.com/repos/{}/releases/tags/{tag}",
-                    github.repo_slug,
-                ),
-                tag.to_owned(),
-            )
-        } else {
-            (
-                format!(
-                    "https://api.github.com/repos/{}/releases/latest",
-                    github.repo_slug,
-                ),
-                "latest".to_owned(),
-            )
-        };
-
-        // Check the cache first using the API URL as the seed,
-        // so that we can avoid making unnecessary HTTP requests.
-        let plugin_path = self.create_cache_path(id, &api_url, release_tag == "latest");
-
-        if self.is_cached(id, &plugin_path)? {
-            return Ok(plugin_path);
-        }
-
-        trace!(
-            id = id.as_str(),
-            api_url = &api_url,
-            release_tag = &release_tag,
-            "Attempting to download plugin from GitHub release",
-        );
-
-        let handle_error = |error: reqwest::Error| WarpgateError::Http {
-            error,
-            url: api_url.clone(),
+    ) -> miette::Result<GitHubApiRelease> {
+        let constraint = github.tag.as_deref().filter(|_| github.is_tag_constraint());
+        let api_url = match constraint {
+            Some(_) => format!(
+                "https://api.github.com/repos/{}/releases?per_page=100",
+                github.repo_slug
+            ),
+            None => create_github_api_url(github),
         };
+        let api_url = self.http_options.rewrite_url(&api_url);
 
         if self.is_offline() {
             return Err(WarpgateError::InternetConnectionRequired {
@@ -290,8 +593,11 @@ impl PluginLoader {
             .into());
         }
 
-        // Otherwise make an HTTP request to the GitHub releases API,
-        // and loop through the assets to find a matching one.
+        let handle_error = |error: reqwest::Error| WarpgateError::Http {
+            error,
+            url: api_url.clone(),
+        };
+
         let mut request = self.get_client()?.get(&api_url);
 
         if let Ok(auth_token) = env::var("GITHUB_TOKEN") {
@@ -299,7 +605,71 @@ impl PluginLoader {
         }
 
         let response = request.send().await.map_err(handle_error)?;
-        let release: GitHubApiRelease = response.json().await.map_err(handle_error)?;
+
+        let Some(constraint) = constraint else {
+            return Ok(response.json().await.map_err(handle_error)?);
+        };
+
+        let handle_missing = || WarpgateError::GitHubReleaseMissing {
+            id: id.to_owned(),
+            repo_slug: github.repo_slug.to_owned(),
+            constraint: constraint.to_owned(),
+        };
+
+        let req = VersionReq::parse(constraint).map_err(|_| handle_missing())?;
+        let releases: Vec<GitHubApiRelease> = response.json().await.map_err(handle_error)?;
+
+        trace!(
+            id = id.as_str(),
+            constraint,
+            "Matching {} GitHub releases against constraint",
+            releases.len()
+        );
+
+        releases
+            .into_iter()
+            .filter(|release| !release.draft && !release.prerelease)
+            .filter_map(|release| {
+                parse_tag_version(&release.tag_name)
+                    .filter(|version| req.matches(version))
+                    .map(|version| (version, release))
+            })
+            .max_by(|a, b| a.0.cmp(&b.0))
+            .map(|(_, release)| release)
+            .ok_or_else(|| handle_missing().into())
+    }
+
+    async fn download_plugin_from_github(
+        &self,
+        id: &Id,
+        github: &GitHubLocator,
+        force: bool,
+    ) -> miette::Result<PathBuf> {
+        // Check the cache first using the API URL as the seed,
+        // so that we can avoid making unnecessary HTTP requests.
+        let api_url = create_github_api_url(github);
+        let plugin_path = self.create_cache_path(id, &api_url, is_github_latest(github));
+
+        if !force {
+            if let Some(shared_path) = self.find_shared_plugin(id, &plugin_path) {
+                return Ok(shared_path);
+            }
+
+            if let Some(cached_path) = self.find_cached_plugin(id, &plugin_path)? {
+                return Ok(cached_path);
+            }
+        }
+
+        trace!(
+            id = id.as_str(),
+            api_url = &api_url,
+            release_tag = github.tag.as_deref().unwrap_or("latest"),
+            "Attempting to download plugin from GitHub release",
+        );
+
+        // Otherwise make an HTTP request to the GitHub releases API,
+        // and loop through the assets to find a matching one.
+        let release = self.fetch_github_release(id, github).await?;
 
         // Find a direct WASM asset first
         for asset in &release.assets {
@@ -311,7 +681,13 @@ impl PluginLoader {
                 );
 
                 return self
-                    .download_plugin(id, &asset.browser_download_url, plugin_path)
+                    .download_plugin(
+                        id,
+                        &asset.browser_download_url,
+                        plugin_path,
+                        Some(release.tag_name.clone()),
+                        force,
+                    )
                     .await;
             }
         }
@@ -336,7 +712,13 @@ impl PluginLoader {
                 );
 
                 return self
-                    .download_plugin(id, &asset.browser_download_url, plugin_path)
+                    .download_plugin(
+                        id,
+                        &asset.browser_download_url,
+                        plugin_path,
+                        Some(release.tag_name.clone()),
+                        force,
+                    )
                     .await;
             }
         }
@@ -344,7 +726,7 @@ impl PluginLoader {
         Err(WarpgateError::GitHubAssetMissing {
             id: id.to_owned(),
             repo_slug: github.repo_slug.to_owned(),
-            tag: release_tag,
+            tag: release.tag_name,
         }
         .into())
     }
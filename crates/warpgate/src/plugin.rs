@@ -2,15 +2,16 @@ use crate::endpoints::Empty;
 use crate::error::WarpgateError;
 use crate::helpers::{from_virtual_path, to_virtual_path};
 use crate::id::Id;
-use extism::{Error, Function, Manifest, Plugin};
+use extism::{CancelHandle, Error, Function, Manifest, Plugin};
 use miette::IntoDiagnostic;
 use once_map::OnceMap;
 use serde::de::DeserializeOwned;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use starbase_styles::color::{self, apply_style_tags};
 use std::fmt::Debug;
 use std::path::{Path, PathBuf};
-use std::sync::{Arc, RwLock};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Instant, SystemTime};
 use system_env::{SystemArch, SystemLibc, SystemOS};
 use tracing::trace;
 use warpgate_api::{HostEnvironment, VirtualPath};
@@ -30,6 +31,50 @@ fn is_incompatible_runtime(error: &Error) -> bool {
     check(error.to_string())
 }
 
+// Inputs and outputs can be massive (version lists, etc), so only keep the start
+const MAX_RECORDED_DATA_LENGTH: usize = 500;
+
+fn truncate_recorded_data(data: &[u8]) -> String {
+    let data = String::from_utf8_lossy(data);
+
+    if data.len() <= MAX_RECORDED_DATA_LENGTH {
+        return data.into_owned();
+    }
+
+    let mut end = MAX_RECORDED_DATA_LENGTH;
+
+    while !data.is_char_boundary(end) {
+        end -= 1;
+    }
+
+    format!("{}... ({} bytes)", &data[0..end], data.len())
+}
+
+/// A record of a single function call made to a plugin.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(default)]
+pub struct PluginCall {
+    /// Timestamp in milliseconds when the function was called.
+    pub called_at: u128,
+
+    /// How long the call took in milliseconds.
+    pub duration_ms: u128,
+
+    /// Error message if the call failed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+
+    /// Name of the function that was called.
+    pub func: String,
+
+    /// Input passed to the function (truncated).
+    pub input: String,
+
+    /// Output returned from the function (truncated).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output: Option<String>,
+}
+
 /// Inject our default configuration into the provided plugin manifest.
 /// This will set `plugin_id` and `host_environment` for use within PDKs.
 pub fn inject_default_manifest_config(
@@ -66,6 +111,7 @@ pub struct PluginContainer {
     pub id: Id,
     pub manifest: Manifest,
 
+    calls: Mutex<Vec<PluginCall>>,
     func_cache: OnceMap<String, Vec<u8>>,
     plugin: Arc<RwLock<Plugin>>,
 }
@@ -101,6 +147,7 @@ impl PluginContainer {
             manifest,
             plugin: Arc::new(RwLock::new(plugin)),
             id,
+            calls: Mutex::new(vec![]),
             func_cache: OnceMap::new(),
         })
     }
@@ -171,6 +218,26 @@ impl PluginContainer {
         Ok(())
     }
 
+    /// Return and clear all function calls that have been made to the plugin
+    /// since the last time this was called. Cached calls are not included.
+    pub fn take_calls(&self) -> Vec<PluginCall> {
+        std::mem::take(&mut *self.calls.lock().unwrap())
+    }
+
+    /// Return a handle that can cancel the function currently being called on
+    /// the plugin, from another thread. Must be acquired before calling.
+    pub fn cancel_handle(&self) -> CancelHandle {
+        self.plugin
+            .read()
+            .unwrap_or_else(|_| {
+                panic!(
+                    "Unable to acquire read access to `{}` WASM plugin.",
+                    self.id
+                )
+            })
+            .cancel_handle()
+    }
+
     /// Return true if the plugin has a function with the given id.
     pub fn has_func(&self, func: &str) -> bool {
         self.plugin
@@ -223,7 +290,28 @@ impl PluginContainer {
             color::property(func),
         );
 
-        let output = instance.call(func, input).map_err(|error| {
+        let mut call = PluginCall {
+            called_at: SystemTime::now()
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .map(|d| d.as_millis())
+                .unwrap_or(0),
+            func: func.to_owned(),
+            input: truncate_recorded_data(input),
+            ..PluginCall::default()
+        };
+        let started = Instant::now();
+        let result: Result<&[u8], Error> = instance.call(func, input);
+
+        call.duration_ms = started.elapsed().as_millis();
+
+        match &result {
+            Ok(output) => call.output = Some(truncate_recorded_data(output)),
+            Err(error) => call.error = Some(error.to_string()),
+        };
+
+        self.calls.lock().unwrap().push(call);
+
+        let output = result.map_err(|error| {
             if is_incompatible_runtime(&error) {
                 return WarpgateError::IncompatibleRuntime {
                     id: self.id.clone(),
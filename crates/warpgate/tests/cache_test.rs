@@ -0,0 +1,142 @@
+use starbase_sandbox::create_empty_sandbox;
+use std::time::Duration;
+use warpgate::PluginCache;
+
+mod plugin_cache {
+    use super::*;
+
+    #[test]
+    fn stores_blobs_by_content() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file("a.wasm", "plugin");
+        sandbox.create_file("b.wasm", "plugin");
+
+        let mut cache = PluginCache::load(sandbox.path().join("plugins")).unwrap();
+        let a = cache
+            .insert("a-123.wasm", &sandbox.path().join("a.wasm"))
+            .unwrap();
+        let b = cache
+            .insert("b-456.wasm", &sandbox.path().join("b.wasm"))
+            .unwrap();
+
+        assert_eq!(a, b);
+        assert!(a.exists());
+        assert!(a.starts_with(sandbox.path().join("plugins/blobs")));
+        assert!(!sandbox.path().join("a.wasm").exists());
+        assert!(!sandbox.path().join("b.wasm").exists());
+        assert_eq!(cache.refs.len(), 2);
+    }
+
+    #[test]
+    fn saves_and_loads_index() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file("a.toml", "plugin");
+
+        let mut cache = PluginCache::load(sandbox.path().join("plugins")).unwrap();
+        let a = cache
+            .insert("a-123.toml", &sandbox.path().join("a.toml"))
+            .unwrap();
+
+        cache.save().unwrap();
+
+        let cache = PluginCache::load(sandbox.path().join("plugins")).unwrap();
+
+        assert!(a.to_string_lossy().ends_with(".toml"));
+        assert_eq!(cache.get_blob_path("a-123.toml").unwrap(), a);
+        assert_eq!(cache.get_blob_path("unknown.toml"), None);
+    }
+
+    #[test]
+    fn saves_and_loads_source() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file("a.wasm", "a");
+        sandbox.create_file("b.wasm", "b");
+
+        let mut cache = PluginCache::load(sandbox.path().join("plugins")).unwrap();
+        cache
+            .insert_with_source(
+                "a-123.wasm",
+                &sandbox.path().join("a.wasm"),
+                Some("\"abc\"".into()),
+                Some("v1.0.0".into()),
+            )
+            .unwrap();
+        cache
+            .insert("b-456.wasm", &sandbox.path().join("b.wasm"))
+            .unwrap();
+        cache.save().unwrap();
+
+        let cache = PluginCache::load(sandbox.path().join("plugins")).unwrap();
+        let a = cache.refs.get("a-123.wasm").unwrap();
+        let b = cache.refs.get("b-456.wasm").unwrap();
+
+        assert_eq!(a.etag.as_deref(), Some("\"abc\""));
+        assert_eq!(a.tag.as_deref(), Some("v1.0.0"));
+        assert_eq!(b.etag, None);
+        assert_eq!(b.tag, None);
+    }
+
+    #[test]
+    fn removes_unreferenced_blobs() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file("a.wasm", "a");
+        sandbox.create_file("b.wasm", "b");
+
+        let mut cache = PluginCache::load(sandbox.path().join("plugins")).unwrap();
+        let a = cache
+            .insert("a-123.wasm", &sandbox.path().join("a.wasm"))
+            .unwrap();
+        let b = cache
+            .insert("b-456.wasm", &sandbox.path().join("b.wasm"))
+            .unwrap();
+
+        cache.refs.remove("b-456.wasm");
+
+        assert_eq!(cache.remove_unreferenced_blobs().unwrap(), vec![b.clone()]);
+        assert!(a.exists());
+        assert!(!b.exists());
+    }
+
+    #[test]
+    fn keeps_blobs_shared_by_other_refs() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file("a.wasm", "plugin");
+        sandbox.create_file("b.wasm", "plugin");
+
+        let mut cache = PluginCache::load(sandbox.path().join("plugins")).unwrap();
+        let a = cache
+            .insert("a-123.wasm", &sandbox.path().join("a.wasm"))
+            .unwrap();
+        cache
+            .insert("b-456.wasm", &sandbox.path().join("b.wasm"))
+            .unwrap();
+
+        cache.refs.remove("a-123.wasm");
+
+        assert!(cache.remove_unreferenced_blobs().unwrap().is_empty());
+        assert!(a.exists());
+    }
+
+    #[test]
+    fn removes_stale_refs() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file("a.wasm", "a");
+        sandbox.create_file("b.wasm", "b");
+
+        let mut cache = PluginCache::load(sandbox.path().join("plugins")).unwrap();
+        cache
+            .insert("a-123.wasm", &sandbox.path().join("a.wasm"))
+            .unwrap();
+        cache
+            .insert("b-456.wasm", &sandbox.path().join("b.wasm"))
+            .unwrap();
+
+        cache.refs.get_mut("a-123.wasm").unwrap().cached_at = 0;
+
+        assert_eq!(
+            cache.remove_stale_refs(Duration::from_secs(86400)),
+            vec!["a-123.wasm".to_owned()]
+        );
+        assert_eq!(cache.refs.len(), 1);
+    }
+}
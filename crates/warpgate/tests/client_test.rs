@@ -0,0 +1,73 @@
+use std::collections::BTreeMap;
+use warpgate::HttpOptions;
+
+fn create_options(mirrors: &[(&str, &str)]) -> HttpOptions {
+    HttpOptions {
+        mirrors: BTreeMap::from_iter(
+            mirrors
+                .iter()
+                .map(|(from, to)| (from.to_string(), to.to_string())),
+        ),
+        ..Default::default()
+    }
+}
+
+mod rewrite_url {
+    use super::*;
+
+    #[test]
+    fn returns_url_when_no_mirrors() {
+        let options = HttpOptions::default();
+
+        assert_eq!(
+            options.rewrite_url("https://nodejs.org/dist/v20.0.0/node.tar.gz"),
+            "https://nodejs.org/dist/v20.0.0/node.tar.gz"
+        );
+    }
+
+    #[test]
+    fn rewrites_by_host() {
+        let options = create_options(&[("nodejs.org", "https://artifactory.corp/nodejs")]);
+
+        assert_eq!(
+            options.rewrite_url("https://nodejs.org/dist/v20.0.0/node.tar.gz"),
+            "https://artifactory.corp/nodejs/dist/v20.0.0/node.tar.gz"
+        );
+        assert_eq!(
+            options.rewrite_url("https://NodeJS.org/dist"),
+            "https://artifactory.corp/nodejs/dist"
+        );
+    }
+
+    #[test]
+    fn doesnt_rewrite_subdomains_of_host() {
+        let options = create_options(&[("nodejs.org", "https://artifactory.corp/nodejs")]);
+
+        assert_eq!(
+            options.rewrite_url("https://cdn.nodejs.org/dist"),
+            "https://cdn.nodejs.org/dist"
+        );
+    }
+
+    #[test]
+    fn rewrites_by_longest_prefix() {
+        let options = create_options(&[
+            ("https://nodejs.org", "https://a.corp"),
+            ("https://nodejs.org/dist", "https://b.corp/node-dist/"),
+            ("nodejs.org", "https://c.corp"),
+        ]);
+
+        assert_eq!(
+            options.rewrite_url("https://nodejs.org/dist/index.json"),
+            "https://b.corp/node-dist/index.json"
+        );
+        assert_eq!(
+            options.rewrite_url("https://nodejs.org/download/index.json"),
+            "https://a.corp/download/index.json"
+        );
+        assert_eq!(
+            options.rewrite_url("http://nodejs.org/download/index.json"),
+            "https://c.corp/download/index.json"
+        );
+    }
+}
@@ -1,6 +1,6 @@
 use starbase_sandbox::{create_empty_sandbox, locate_fixture, Sandbox};
 use std::path::PathBuf;
-use warpgate::{GitHubLocator, Id, PluginLoader, PluginLocator};
+use warpgate::{GitHubLocator, Id, PluginCache, PluginLoader, PluginLocator};
 
 fn create_loader() -> (Sandbox, PluginLoader) {
     let sandbox = create_empty_sandbox();
@@ -86,7 +86,34 @@ mod loader {
                 .await
                 .unwrap();
 
-            assert_eq!(path, sandbox.path().join("plugins/test-1cab19a12ec96a1036dc5d51011634dddfa2911941f31e4957d7780bb70f88f0.wasm"));
+            let cache = PluginCache::load(sandbox.path().join("plugins")).unwrap();
+
+            assert_eq!(
+                cache.get_blob_path("test-1cab19a12ec96a1036dc5d51011634dddfa2911941f31e4957d7780bb70f88f0.wasm").unwrap(),
+                path
+            );
+        }
+
+        #[tokio::test]
+        async fn moves_previously_downloaded_file_into_blobs() {
+            let (sandbox, loader) = create_loader();
+            let url =
+                "https://github.com/moonrepo/deno-plugin/releases/download/v0.0.2/deno_plugin.wasm";
+            let legacy_path = loader.create_cache_path(&Id::raw("test"), url, false);
+
+            std::fs::create_dir_all(legacy_path.parent().unwrap()).unwrap();
+            std::fs::write(&legacy_path, "plugin").unwrap();
+
+            let path = loader
+                .load_plugin(
+                    Id::raw("test"),
+                    PluginLocator::SourceUrl { url: url.into() },
+                )
+                .await
+                .unwrap();
+
+            assert!(path.starts_with(sandbox.path().join("plugins/blobs")));
+            assert!(!legacy_path.exists());
         }
 
         #[tokio::test]
@@ -101,7 +128,12 @@ mod loader {
                 .await
                 .unwrap();
 
-            assert_eq!(path, sandbox.path().join("plugins/test-latest-db3f668c2fe22a7f9a6ce86b6fa8feeffbfd8e7874bdb854e82b154319675269.wasm"));
+            let cache = PluginCache::load(sandbox.path().join("plugins")).unwrap();
+
+            assert_eq!(
+                cache.get_blob_path("test-latest-db3f668c2fe22a7f9a6ce86b6fa8feeffbfd8e7874bdb854e82b154319675269.wasm").unwrap(),
+                path
+            );
         }
     }
 
@@ -144,7 +176,12 @@ mod loader {
                 .await
                 .unwrap();
 
-            assert_eq!(path, sandbox.path().join("plugins/test-6858d7b8b0bcd96afd3da08c25cda7cfa2d25b8776fba1cbacea2391e81bdc1e.wasm"));
+            let cache = PluginCache::load(sandbox.path().join("plugins")).unwrap();
+
+            assert_eq!(
+                cache.get_blob_path("test-6858d7b8b0bcd96afd3da08c25cda7cfa2d25b8776fba1cbacea2391e81bdc1e.wasm").unwrap(),
+                path
+            );
         }
 
         #[tokio::test]
@@ -163,7 +200,12 @@ mod loader {
                 .await
                 .unwrap();
 
-            assert_eq!(path, sandbox.path().join("plugins/test-latest-fbd480065d33e0cb2cc9501b7f20fb7edd1a552f1c629dd8b35071f5bac4a0cb.wasm"));
+            let cache = PluginCache::load(sandbox.path().join("plugins")).unwrap();
+
+            assert_eq!(
+                cache.get_blob_path("test-latest-fbd480065d33e0cb2cc9501b7f20fb7edd1a552f1c629dd8b35071f5bac4a0cb.wasm").unwrap(),
+                path
+            );
         }
     }
 }
@@ -21,6 +21,7 @@ struct WasmTestConfig {
     string: String,
     boolean: bool,
     unknown: Option<usize>,
+    download_size: Option<u64>,
     list: Vec<String>,
     map: HashMap<String, usize>,
 }
@@ -107,6 +108,7 @@ pub fn detect_version_files(_: ()) -> FnResult<Json<DetectVersionOutput>> {
     Ok(Json(DetectVersionOutput {
         files: vec![".proto-wasm-version".into(), ".protowasmrc".into()],
         ignore: vec!["node_modules".into()],
+        ..DetectVersionOutput::default()
     }))
 }
 
@@ -127,6 +129,21 @@ pub fn parse_version_file(
     Ok(Json(ParseVersionFileOutput { version }))
 }
 
+#[plugin_fn]
+pub fn write_version_file(
+    Json(input): Json<WriteVersionFileInput>,
+) -> FnResult<Json<WriteVersionFileOutput>> {
+    let content = if input.file == ".proto-wasm-version" {
+        format!("version={}", input.version)
+    } else {
+        input.version.to_string()
+    };
+
+    Ok(Json(WriteVersionFileOutput {
+        content: Some(content),
+    }))
+}
+
 // Downloader
 
 fn map_arch(arch: HostArch) -> String {
@@ -143,6 +160,7 @@ pub fn download_prebuilt(
     Json(input): Json<DownloadPrebuiltInput>,
 ) -> FnResult<Json<DownloadPrebuiltOutput>> {
     let env = get_host_environment()?;
+    let config = get_tool_config::<WasmTestConfig>()?;
     let version = input.context.version;
     let arch = map_arch(env.arch);
 
@@ -166,6 +184,8 @@ pub fn download_prebuilt(
         checksum_url: Some(format!("https://nodejs.org/dist/v{version}/SHASUMS256.txt")),
         checksum_name: None,
         checksum_public_key: None,
+        download_size: config.download_size,
+        ..DownloadPrebuiltOutput::default()
     }))
 }
 
@@ -229,6 +249,12 @@ pub fn resolve_version(
         }
     }
 
+    if let UnresolvedVersionSpec::Req(req) = &input.initial {
+        if req.to_string() == "~17" {
+            output.version = Some(VersionSpec::parse("17.0.0")?);
+        }
+    }
+
     Ok(Json(output))
 }
 
@@ -0,0 +1,37 @@
+use proto_pdk_test_utils::*;
+use starbase_sandbox::create_empty_sandbox;
+use std::collections::HashMap;
+
+#[tokio::test]
+async fn prefers_plugin_download_size_over_head_request() {
+    let sandbox = create_empty_sandbox();
+    let mut plugin = create_plugin_with_config(
+        "wasm-test",
+        sandbox.path(),
+        HashMap::from_iter([map_config_tool_config(HashMap::from_iter([(
+            "download-size",
+            1234,
+        )]))]),
+    );
+
+    plugin
+        .tool
+        .set_version(VersionSpec::parse("20.0.0").unwrap());
+
+    // The actual archive is much larger, so this can only come from the hint
+    assert_eq!(plugin.tool.get_download_size().await.unwrap(), Some(1234));
+}
+
+#[tokio::test]
+async fn makes_head_request_without_plugin_download_size() {
+    let sandbox = create_empty_sandbox();
+    let mut plugin = create_plugin("wasm-test", sandbox.path());
+
+    plugin
+        .tool
+        .set_version(VersionSpec::parse("20.0.0").unwrap());
+
+    let size = plugin.tool.get_download_size().await.unwrap();
+
+    assert!(size.is_some_and(|size| size > 1234));
+}
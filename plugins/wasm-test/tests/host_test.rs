@@ -0,0 +1,73 @@
+use proto_pdk_test_utils::*;
+use starbase_sandbox::create_empty_sandbox;
+
+#[test]
+fn includes_oldest_through_current_host_versions() {
+    let versions = get_host_versions();
+
+    assert_eq!(versions.first().unwrap().to_string(), HOST_API_VERSIONS[0]);
+    assert_eq!(
+        versions.last().unwrap(),
+        &proto_pdk_test_utils::core::get_proto_version()
+    );
+}
+
+#[test]
+fn runs_against_each_host_version() {
+    let sandbox = create_empty_sandbox();
+    let mut plugin = create_plugin("wasm-test", sandbox.path());
+    let versions = get_host_versions();
+
+    let results = check_host_compatibility(&mut plugin, &versions, |plugin| {
+        plugin.locate_executables(LocateExecutablesInput::default());
+    });
+
+    assert_eq!(results.len(), versions.len());
+    assert!(results.iter().all(|result| result.is_compatible()));
+    assert!(plugin.host_version.is_none());
+}
+
+#[test]
+fn captures_incompatible_host_versions() {
+    let sandbox = create_empty_sandbox();
+    let mut plugin = create_plugin("wasm-test", sandbox.path());
+
+    let results = check_host_compatibility(&mut plugin, &get_host_versions(), |plugin| {
+        if plugin.host_version.as_ref().unwrap() < &Version::new(0, 28, 0) {
+            panic!("requires a newer proto");
+        }
+    });
+
+    for result in results {
+        if result.version < Version::new(0, 28, 0) {
+            assert_eq!(result.error.unwrap(), "requires a newer proto");
+        } else {
+            assert!(result.is_compatible());
+        }
+    }
+}
+
+#[test]
+fn asserts_compatible_range() {
+    let sandbox = create_empty_sandbox();
+    let mut plugin = create_plugin("wasm-test", sandbox.path());
+
+    assert_host_compatibility(&mut plugin, ">=0.28.0", |plugin| {
+        if plugin.host_version.as_ref().unwrap() < &Version::new(0, 28, 0) {
+            panic!("requires a newer proto");
+        }
+    });
+}
+
+#[test]
+#[should_panic(expected = "Plugin is not compatible with all host versions")]
+fn panics_when_range_is_incompatible() {
+    let sandbox = create_empty_sandbox();
+    let mut plugin = create_plugin("wasm-test", sandbox.path());
+
+    assert_host_compatibility(&mut plugin, "*", |plugin| {
+        if plugin.host_version.as_ref().unwrap() < &Version::new(0, 28, 0) {
+            panic!("requires a newer proto");
+        }
+    });
+}
@@ -0,0 +1,66 @@
+use proto_pdk_test_utils::*;
+use starbase_sandbox::create_empty_sandbox;
+
+#[tokio::test]
+async fn resolves_range_to_installed_version() {
+    let sandbox = create_empty_sandbox();
+    let mut plugin = create_plugin("wasm-test", sandbox.path());
+
+    // Doesn't exist upstream, so can only be resolved from installed versions
+    plugin
+        .tool
+        .manifest
+        .installed_versions
+        .insert(VersionSpec::parse("18.99.0").unwrap());
+
+    plugin
+        .tool
+        .resolve_version(&UnresolvedVersionSpec::parse("^18").unwrap(), true)
+        .await
+        .unwrap();
+
+    assert_eq!(plugin.tool.get_resolved_version(), "18.99.0");
+}
+
+#[tokio::test]
+async fn prefers_plugin_remap_over_installed_version() {
+    let sandbox = create_empty_sandbox();
+    let mut plugin = create_plugin("wasm-test", sandbox.path());
+
+    plugin
+        .tool
+        .manifest
+        .installed_versions
+        .insert(VersionSpec::parse("17.99.0").unwrap());
+
+    plugin
+        .tool
+        .resolve_version(&UnresolvedVersionSpec::parse("~17").unwrap(), true)
+        .await
+        .unwrap();
+
+    assert_eq!(plugin.tool.get_resolved_version(), "17.0.0");
+}
+
+#[tokio::test]
+async fn resolves_from_plugin_when_no_installed_version_matches() {
+    let sandbox = create_empty_sandbox();
+    let mut plugin = create_plugin("wasm-test", sandbox.path());
+
+    plugin
+        .tool
+        .manifest
+        .installed_versions
+        .insert(VersionSpec::parse("18.99.0").unwrap());
+
+    plugin
+        .tool
+        .resolve_version(&UnresolvedVersionSpec::parse("^16").unwrap(), true)
+        .await
+        .unwrap();
+
+    let version = plugin.tool.get_resolved_version();
+
+    assert_ne!(version, "18.99.0");
+    assert!(version.to_string().starts_with("16."));
+}